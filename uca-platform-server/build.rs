@@ -0,0 +1,34 @@
+//! 构建脚本：记录 Git 提交与 rustc 版本，供 `/admin/system/info` 与 `status` 命令展示。
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    for head in ["../.git/HEAD", ".git/HEAD"] {
+        if Path::new(head).exists() {
+            println!("cargo:rerun-if-changed={head}");
+        }
+    }
+
+    let commit = env::var("GIT_COMMIT")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .or_else(|| command_output("git", &["rev-parse", "--short=12", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string());
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = command_output(&rustc, &["--version"]).unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=UCA_GIT_COMMIT={commit}");
+    println!("cargo:rustc-env=UCA_RUSTC_VERSION={rustc_version}");
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
@@ -2,12 +2,18 @@
 
 use axum_extra::extract::cookie::CookieJar;
 use chrono::Utc;
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use sea_orm::{ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
 
 use crate::{
     auth::hash_session_token,
-    entities::{sessions, users, Session, User},
+    config::StudentExportPolicy,
+    delegations::{active_delegation, DELEGATE_ROLES},
+    entities::{advisor_classes, sessions, students, users, AdvisorClass, Session, User},
     error::AppError,
+    services::{
+        review::{role_can_review, REVIEW_STAGE_FINAL, REVIEW_STAGE_FIRST, STATUS_FINAL_REVIEWED},
+        DbStudentService, StudentService,
+    },
     state::AppState,
 };
 
@@ -23,18 +29,28 @@ pub async fn require_session_user(
         .to_string();
     let token_hash = hash_session_token(&token);
 
-    let session = Session::find()
-        .filter(sessions::Column::TokenHash.eq(token_hash))
-        .one(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-        .ok_or_else(|| AppError::auth("invalid session"))?;
+    let (user_id, expires_at) = match state.session_cache.get(&token_hash).await {
+        Some(cached) => cached,
+        None => {
+            let session = Session::find()
+                .filter(sessions::Column::TokenHash.eq(token_hash.clone()))
+                .one(&state.db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?
+                .ok_or_else(|| AppError::auth("invalid session"))?;
+            state
+                .session_cache
+                .insert(token_hash, session.user_id, session.expires_at)
+                .await;
+            (session.user_id, session.expires_at)
+        }
+    };
 
-    if session.expires_at < Utc::now() {
+    if expires_at < Utc::now() {
         return Err(AppError::auth("session expired"));
     }
 
-    User::find_by_id(session.user_id)
+    User::find_by_id(user_id)
         .one(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?
@@ -49,3 +65,394 @@ pub fn require_role(user: &users::Model, role: &str) -> Result<(), AppError> {
         Err(AppError::auth("forbidden"))
     }
 }
+
+/// 读取当前学生账号关联的学生档案。
+pub async fn require_student_profile(
+    state: &AppState,
+    user: &users::Model,
+) -> Result<students::Model, AppError> {
+    DbStudentService::new(&state.db).profile_for_user(user).await
+}
+
+/// 规范化院系名称；空白视为未设置。
+pub fn normalize_department(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string())
+}
+
+/// 教师/审核人员的院系数据范围；管理员及未设置院系的账号不受限制。
+pub fn department_scope(user: &users::Model) -> Option<&str> {
+    if user.role == "admin" {
+        return None;
+    }
+    user.department
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// 确认学生属于当前用户的数据范围。
+///
+/// 班主任的范围取决于负责班级，需异步读取，此处一律拒绝；允许班主任访问的接口改用
+/// [`ensure_student_visible`]。
+pub fn ensure_student_in_scope(user: &users::Model, student: &students::Model) -> Result<(), AppError> {
+    if user.role == ROLE_CLASS_ADVISOR {
+        return Err(AppError::auth("forbidden"));
+    }
+    match department_scope(user) {
+        Some(department) if student.department.trim() != department => Err(AppError::auth("forbidden")),
+        _ => Ok(()),
+    }
+}
+
+/// 班主任角色：只读查看负责班级的记录、学时与汇总导出，不能审核。
+pub const ROLE_CLASS_ADVISOR: &str = "class_advisor";
+
+/// 读取班主任负责的班级（按名称排序）；非班主任返回 `None`。
+pub async fn advisor_class_scope<C>(db: &C, user: &users::Model) -> Result<Option<Vec<String>>, AppError>
+where
+    C: ConnectionTrait,
+{
+    if user.role != ROLE_CLASS_ADVISOR {
+        return Ok(None);
+    }
+    let rows = AdvisorClass::find()
+        .filter(advisor_classes::Column::UserId.eq(user.id))
+        .order_by_asc(advisor_classes::Column::ClassName)
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Some(rows.into_iter().map(|row| row.class_name).collect()))
+}
+
+/// 学生查询的数据范围条件：院系范围，以及班主任的负责班级（未分配班级时不匹配任何学生）。
+pub fn student_scope_condition(user: &users::Model, classes: Option<&[String]>) -> Condition {
+    let mut condition = Condition::all();
+    if let Some(department) = department_scope(user) {
+        condition = condition.add(students::Column::Department.eq(department));
+    }
+    if let Some(classes) = classes {
+        condition = condition.add(students::Column::ClassName.is_in(classes.iter().cloned()));
+    }
+    condition
+}
+
+/// 确认学生属于当前用户的数据范围；班主任仅可访问负责班级（及所属院系）的学生。
+pub async fn ensure_student_visible(
+    state: &AppState,
+    user: &users::Model,
+    student: &students::Model,
+) -> Result<(), AppError> {
+    let Some(classes) = advisor_class_scope(&state.db, user).await? else {
+        return ensure_student_in_scope(user, student);
+    };
+    if !class_scope_allows(user, &classes, student) {
+        return Err(AppError::auth("forbidden"));
+    }
+    Ok(())
+}
+
+fn class_scope_allows(user: &users::Model, classes: &[String], student: &students::Model) -> bool {
+    let in_department = department_scope(user).is_none_or(|department| student.department.trim() == department);
+    in_department && classes.iter().any(|class_name| class_name == student.class_name.trim())
+}
+
+/// 权限：系统管理（用户、学生、配置、备份等管理接口）。
+pub const PERMISSION_ADMIN: &str = "admin";
+/// 权限：提交本人的竞赛与志愿记录。
+pub const PERMISSION_RECORDS_SUBMIT: &str = "records:submit";
+/// 权限：查看数据范围内的学生记录。
+pub const PERMISSION_RECORDS_READ: &str = "records:read";
+/// 权限：初审。
+pub const PERMISSION_REVIEW_FIRST: &str = "review:first";
+/// 权限：终审。
+pub const PERMISSION_REVIEW_FINAL: &str = "review:final";
+/// 权限：导出本人的个人文件与记录 PDF。
+pub const PERMISSION_EXPORT_SELF: &str = "export:self";
+/// 权限：导出学生个人文件、记录 PDF 与批量学时认定表。
+pub const PERMISSION_EXPORT_STUDENTS: &str = "export:students";
+/// 权限：导出汇总表。
+pub const PERMISSION_EXPORT_SUMMARY: &str = "export:summary";
+/// 权限：导出审核签名审计表。
+pub const PERMISSION_EXPORT_SIGNATURE_AUDIT: &str = "export:signature_audit";
+
+/// 角色本身具备的权限，与各接口的角色检查及 [`authorize_export`] 一致。
+pub fn role_permissions(role: &str, policy: StudentExportPolicy) -> Vec<&'static str> {
+    let mut permissions = Vec::new();
+    match role {
+        "student" => {
+            permissions.push(PERMISSION_RECORDS_SUBMIT);
+            if policy != StudentExportPolicy::Disabled {
+                permissions.push(PERMISSION_EXPORT_SELF);
+            }
+            return permissions;
+        }
+        "admin" => permissions.push(PERMISSION_ADMIN),
+        "teacher" | "reviewer" | ROLE_CLASS_ADVISOR => {}
+        _ => return permissions,
+    }
+    permissions.push(PERMISSION_RECORDS_READ);
+    for (stage, permission) in [
+        (REVIEW_STAGE_FIRST, PERMISSION_REVIEW_FIRST),
+        (REVIEW_STAGE_FINAL, PERMISSION_REVIEW_FINAL),
+    ] {
+        if role_can_review(role, stage) {
+            permissions.push(permission);
+        }
+    }
+    if role != ROLE_CLASS_ADVISOR {
+        permissions.push(PERMISSION_EXPORT_STUDENTS);
+    }
+    permissions.push(PERMISSION_EXPORT_SUMMARY);
+    if matches!(role, "admin" | "teacher") {
+        permissions.push(PERMISSION_EXPORT_SIGNATURE_AUDIT);
+    }
+    permissions
+}
+
+/// 用户当前生效的权限：角色权限加上生效中的审核委托授予的审核阶段。
+pub async fn effective_permissions<C>(
+    db: &C,
+    user: &users::Model,
+    policy: StudentExportPolicy,
+) -> Result<Vec<&'static str>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut permissions = role_permissions(&user.role, policy);
+    if DELEGATE_ROLES.contains(&user.role.as_str()) {
+        for (stage, permission) in [
+            (REVIEW_STAGE_FIRST, PERMISSION_REVIEW_FIRST),
+            (REVIEW_STAGE_FINAL, PERMISSION_REVIEW_FINAL),
+        ] {
+            if !permissions.contains(&permission) && active_delegation(db, user.id, stage).await?.is_some() {
+                permissions.push(permission);
+            }
+        }
+    }
+    Ok(permissions)
+}
+
+/// 导出目标。
+#[derive(Debug, Clone, Copy)]
+pub enum ExportTarget<'a> {
+    /// 多名学生的汇总表（学院/专业/班级汇总、学时汇总）。
+    Summary,
+    /// 批量导出学时认定表。
+    LaborHoursBatch,
+    /// 审核签名审计表。
+    SignatureAudit,
+    /// 单个学生的个人文件（个人学时表、学时认定表）。
+    Student { student_no: &'a str },
+    /// 单条记录的 PDF。
+    Record { student_no: &'a str, status: &'a str },
+}
+
+/// 导出文件可包含的记录范围。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportRecordScope {
+    /// 全部未撤回记录。
+    All,
+    /// 仅终审通过的记录。
+    FinalReviewedOnly,
+}
+
+/// 判断用户能否导出目标，返回导出文件可包含的记录范围。
+///
+/// 只检查角色、本人与学生自行导出策略；院系与班级数据范围仍由调用方在读取学生后检查。
+pub fn authorize_export(
+    user: &users::Model,
+    target: ExportTarget<'_>,
+    policy: StudentExportPolicy,
+) -> Result<ExportRecordScope, AppError> {
+    let forbidden = || AppError::auth("forbidden");
+    if user.role == "student" {
+        let (student_no, status) = match target {
+            ExportTarget::Student { student_no } => (student_no, None),
+            ExportTarget::Record { student_no, status } => (student_no, Some(status)),
+            _ => return Err(forbidden()),
+        };
+        if user.username != student_no {
+            return Err(forbidden());
+        }
+        return match (policy, status) {
+            (StudentExportPolicy::Disabled, _) => Err(forbidden()),
+            (StudentExportPolicy::FinalReviewed, Some(status)) if status != STATUS_FINAL_REVIEWED => {
+                Err(AppError::auth("only final reviewed records can be exported"))
+            }
+            (StudentExportPolicy::FinalReviewed, None) => Ok(ExportRecordScope::FinalReviewedOnly),
+            _ => Ok(ExportRecordScope::All),
+        };
+    }
+    let allowed: &[&str] = match target {
+        ExportTarget::Summary => &["admin", "teacher", "reviewer", ROLE_CLASS_ADVISOR],
+        ExportTarget::SignatureAudit => &["admin", "teacher"],
+        ExportTarget::LaborHoursBatch | ExportTarget::Student { .. } | ExportTarget::Record { .. } => {
+            &["admin", "teacher", "reviewer"]
+        }
+    };
+    if !allowed.contains(&user.role.as_str()) {
+        return Err(forbidden());
+    }
+    Ok(ExportRecordScope::All)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn account(role: &str, department: Option<&str>) -> users::Model {
+        users::Model {
+            id: Uuid::new_v4(),
+            username: "t01".to_string(),
+            display_name: "教师".to_string(),
+            role: role.to_string(),
+            email: None,
+            password_hash: None,
+            allow_password_login: false,
+            password_updated_at: None,
+            must_change_password: false,
+            is_active: true,
+            department: department.map(|value| value.to_string()),
+            notify_review_email: true,
+            locale: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn student(department: &str) -> students::Model {
+        students::Model {
+            id: Uuid::new_v4(),
+            student_no: "2023001".to_string(),
+            name: "张三".to_string(),
+            gender: "男".to_string(),
+            department: department.to_string(),
+            major: "软件工程".to_string(),
+            class_name: "软工1班".to_string(),
+            phone: String::new(),
+            import_batch_id: None,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn department_scope_applies_to_reviewers_only() {
+        assert_eq!(department_scope(&account("teacher", Some(" 信息学院 "))), Some("信息学院"));
+        assert_eq!(department_scope(&account("reviewer", Some(""))), None);
+        assert_eq!(department_scope(&account("admin", Some("信息学院"))), None);
+
+        let teacher = account("teacher", Some("信息学院"));
+        assert!(ensure_student_in_scope(&teacher, &student("信息学院")).is_ok());
+        assert!(ensure_student_in_scope(&teacher, &student("机械学院")).is_err());
+        assert!(ensure_student_in_scope(&account("admin", None), &student("机械学院")).is_ok());
+        assert_eq!(normalize_department(Some("  ")), None);
+    }
+
+    #[test]
+    fn export_authorization_covers_each_role() {
+        let own = ExportTarget::Student { student_no: "2023001" };
+        let other = ExportTarget::Student { student_no: "2023002" };
+        let final_record = ExportTarget::Record {
+            student_no: "2023001",
+            status: "final_reviewed",
+        };
+        let pending_record = ExportTarget::Record {
+            student_no: "2023001",
+            status: "submitted",
+        };
+        let all_targets = [
+            ExportTarget::Summary,
+            ExportTarget::LaborHoursBatch,
+            ExportTarget::SignatureAudit,
+            own,
+            final_record,
+        ];
+        let policy = StudentExportPolicy::FinalReviewed;
+
+        let mut student = account("student", None);
+        student.username = "2023001".to_string();
+        assert_eq!(
+            authorize_export(&student, own, policy).unwrap(),
+            ExportRecordScope::FinalReviewedOnly
+        );
+        assert_eq!(authorize_export(&student, final_record, policy).unwrap(), ExportRecordScope::All);
+        assert!(authorize_export(&student, pending_record, policy).is_err());
+        assert!(authorize_export(&student, other, policy).is_err());
+        assert!(authorize_export(&student, ExportTarget::Summary, policy).is_err());
+        assert!(authorize_export(&student, ExportTarget::LaborHoursBatch, policy).is_err());
+        assert_eq!(
+            authorize_export(&student, pending_record, StudentExportPolicy::All).unwrap(),
+            ExportRecordScope::All
+        );
+        assert_eq!(
+            authorize_export(&student, own, StudentExportPolicy::All).unwrap(),
+            ExportRecordScope::All
+        );
+        assert!(authorize_export(&student, own, StudentExportPolicy::Disabled).is_err());
+        assert!(authorize_export(&student, final_record, StudentExportPolicy::Disabled).is_err());
+
+        for target in all_targets {
+            assert_eq!(
+                authorize_export(&account("admin", None), target, policy).unwrap(),
+                ExportRecordScope::All
+            );
+            assert!(authorize_export(&account("teacher", None), target, policy).is_ok());
+        }
+        let reviewer = account("reviewer", None);
+        assert!(authorize_export(&reviewer, pending_record, policy).is_ok());
+        assert!(authorize_export(&reviewer, ExportTarget::LaborHoursBatch, policy).is_ok());
+        assert!(authorize_export(&reviewer, ExportTarget::SignatureAudit, policy).is_err());
+
+        let advisor = account(ROLE_CLASS_ADVISOR, None);
+        assert!(authorize_export(&advisor, ExportTarget::Summary, policy).is_ok());
+        assert!(authorize_export(&advisor, own, policy).is_err());
+        assert!(authorize_export(&advisor, ExportTarget::LaborHoursBatch, policy).is_err());
+        for target in all_targets {
+            assert!(authorize_export(&account("guest", None), target, policy).is_err());
+        }
+    }
+
+    #[test]
+    fn role_permissions_match_role_checks() {
+        let policy = StudentExportPolicy::FinalReviewed;
+        assert_eq!(
+            role_permissions("student", policy),
+            vec![PERMISSION_RECORDS_SUBMIT, PERMISSION_EXPORT_SELF]
+        );
+        assert_eq!(
+            role_permissions("student", StudentExportPolicy::Disabled),
+            vec![PERMISSION_RECORDS_SUBMIT]
+        );
+        let reviewer = role_permissions("reviewer", policy);
+        assert!(reviewer.contains(&PERMISSION_REVIEW_FIRST));
+        assert!(!reviewer.contains(&PERMISSION_REVIEW_FINAL));
+        assert!(!reviewer.contains(&PERMISSION_EXPORT_SIGNATURE_AUDIT));
+        let teacher = role_permissions("teacher", policy);
+        assert!(teacher.contains(&PERMISSION_REVIEW_FINAL));
+        assert!(teacher.contains(&PERMISSION_EXPORT_SIGNATURE_AUDIT));
+        let advisor = role_permissions(ROLE_CLASS_ADVISOR, policy);
+        assert_eq!(advisor, vec![PERMISSION_RECORDS_READ, PERMISSION_EXPORT_SUMMARY]);
+        let admin = role_permissions("admin", policy);
+        assert!(admin.contains(&PERMISSION_ADMIN));
+        assert!(admin.contains(&PERMISSION_REVIEW_FIRST) && admin.contains(&PERMISSION_REVIEW_FINAL));
+        assert!(role_permissions("guest", policy).is_empty());
+    }
+
+    #[test]
+    fn class_advisor_scope_requires_assigned_class() {
+        let advisor = account(ROLE_CLASS_ADVISOR, Some("信息学院"));
+        assert!(ensure_student_in_scope(&advisor, &student("信息学院")).is_err());
+
+        let classes = vec!["软工1班".to_string()];
+        assert!(class_scope_allows(&advisor, &classes, &student("信息学院")));
+        assert!(!class_scope_allows(&advisor, &classes, &student("机械学院")));
+        assert!(!class_scope_allows(&advisor, &[], &student("信息学院")));
+        assert!(class_scope_allows(&account(ROLE_CLASS_ADVISOR, None), &classes, &student("机械学院")));
+    }
+}
@@ -0,0 +1,158 @@
+//! 公告：按生效时间、目标角色与院系筛选前端展示的通知。
+
+use chrono::{DateTime, Utc};
+
+use crate::{access::normalize_department, entities::announcements, error::AppError};
+
+/// 公告严重程度，按展示优先级从低到高排列。
+pub const ANNOUNCEMENT_SEVERITIES: [&str; 3] = ["info", "warning", "critical"];
+
+/// 可作为公告目标的角色。
+pub const ANNOUNCEMENT_ROLES: [&str; 5] =
+    ["student", "teacher", "reviewer", "class_advisor", "admin"];
+
+/// 校验严重程度。
+pub fn validate_severity(severity: &str) -> Result<(), AppError> {
+    if ANNOUNCEMENT_SEVERITIES.contains(&severity) {
+        Ok(())
+    } else {
+        Err(AppError::validation(
+            "severity must be info, warning or critical",
+        ))
+    }
+}
+
+/// 校验并去重目标角色，返回按固定顺序排列的逗号分隔字符串；为空表示面向所有人。
+pub fn normalize_roles(roles: &[String]) -> Result<Option<String>, AppError> {
+    for role in roles {
+        if !ANNOUNCEMENT_ROLES.contains(&role.trim()) {
+            return Err(AppError::validation(&format!("unknown role: {role}")));
+        }
+    }
+    let selected: Vec<&str> = ANNOUNCEMENT_ROLES
+        .into_iter()
+        .filter(|name| roles.iter().any(|role| role.trim() == *name))
+        .collect();
+    Ok((!selected.is_empty()).then(|| selected.join(",")))
+}
+
+/// 解析数据库中逗号分隔的目标角色。
+pub fn target_roles(stored: Option<&str>) -> Vec<String> {
+    stored
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 校验生效时间范围。
+pub fn validate_range(
+    starts_at: DateTime<Utc>,
+    ends_at: Option<DateTime<Utc>>,
+) -> Result<(), AppError> {
+    match ends_at {
+        Some(ends_at) if ends_at <= starts_at => {
+            Err(AppError::validation("starts_at must be before ends_at"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 公告在给定时间是否生效（起止时间均包含在内）。
+pub fn is_effective(announcement: &announcements::Model, now: DateTime<Utc>) -> bool {
+    announcement.starts_at <= now && announcement.ends_at.map_or(true, |ends_at| now <= ends_at)
+}
+
+/// 公告是否面向指定读者；未登录时 `role` 为空，只能看到不限角色与院系的公告。
+pub fn targets(
+    announcement: &announcements::Model,
+    role: Option<&str>,
+    department: Option<&str>,
+) -> bool {
+    let roles = target_roles(announcement.target_roles.as_deref());
+    let role_matches =
+        roles.is_empty() || role.is_some_and(|role| roles.iter().any(|item| item == role));
+    let department_matches = match normalize_department(announcement.target_department.as_deref()) {
+        None => true,
+        Some(target) => {
+            normalize_department(department).is_some_and(|department| department == target)
+        }
+    };
+    role_matches && department_matches
+}
+
+/// 严重程度的排序权重（越严重越大）。
+pub fn severity_rank(severity: &str) -> usize {
+    ANNOUNCEMENT_SEVERITIES
+        .iter()
+        .position(|item| *item == severity)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    fn announcement(roles: Option<&str>, department: Option<&str>) -> announcements::Model {
+        let now = Utc::now();
+        announcements::Model {
+            id: Uuid::new_v4(),
+            title: "复审截止".to_string(),
+            content: "复审截止5月30日".to_string(),
+            severity: "warning".to_string(),
+            target_roles: roles.map(str::to_string),
+            target_department: department.map(str::to_string),
+            starts_at: Utc.with_ymd_and_hms(2026, 5, 1, 0, 0, 0).unwrap(),
+            ends_at: Some(Utc.with_ymd_and_hms(2026, 5, 30, 23, 59, 59).unwrap()),
+            created_by: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn targeting_filters_by_role_and_department() {
+        let public = announcement(None, None);
+        assert!(targets(&public, None, None));
+
+        let reviewers = announcement(Some("teacher,reviewer"), Some("信息学院"));
+        assert!(targets(&reviewers, Some("reviewer"), Some(" 信息学院 ")));
+        assert!(!targets(&reviewers, Some("reviewer"), Some("机械学院")));
+        assert!(!targets(&reviewers, Some("student"), Some("信息学院")));
+        assert!(!targets(&reviewers, None, None));
+    }
+
+    #[test]
+    fn effective_range_is_inclusive() {
+        let item = announcement(None, None);
+        assert!(is_effective(&item, item.starts_at));
+        assert!(is_effective(&item, item.ends_at.unwrap()));
+        assert!(!is_effective(
+            &item,
+            item.starts_at - chrono::Duration::seconds(1)
+        ));
+        assert!(!is_effective(
+            &item,
+            item.ends_at.unwrap() + chrono::Duration::seconds(1)
+        ));
+    }
+
+    #[test]
+    fn roles_are_validated_and_ordered() {
+        let roles = vec![
+            "reviewer".to_string(),
+            "student".to_string(),
+            "reviewer".to_string(),
+        ];
+        assert_eq!(
+            normalize_roles(&roles).unwrap().as_deref(),
+            Some("student,reviewer")
+        );
+        assert!(normalize_roles(&[]).unwrap().is_none());
+        assert!(normalize_roles(&["guest".to_string()]).is_err());
+    }
+}
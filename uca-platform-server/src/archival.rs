@@ -0,0 +1,794 @@
+//! 数据保留与归档：将超过保留期限的毕业年级整届移入归档表，并删除其附件文件。
+//!
+//! 入学年份取学号前四位，满足 `入学年份 + 学制年限 + 保留年限 <= 当前年份` 的年级到期。
+//! 学生、竞赛与志愿记录及其自定义字段、审核签名和评论以 JSON 快照写入 `archive_items`，
+//! 学生登录账号被停用。归档可整批恢复，但附件文件已删除，无法恢复；
+//! 已恢复的学生不会再被自动归档。
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{Datelike, Utc};
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseTransaction, EntityTrait, QueryFilter,
+    Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    audit::record_audit,
+    backup::restore_table,
+    entities::{
+        archive_items, archives, attachments, contest_records, form_field_values, record_comments,
+        review_signatures, runtime_settings, sessions, students, users, volunteer_records, Archive,
+        ArchiveItem, Attachment, ContestRecord, FormFieldValue, RecordComment, ReviewSignature,
+        RuntimeSetting, Session, Student, User, VolunteerRecord,
+    },
+    error::AppError,
+    routes::attachments::remove_thumbnails,
+    state::AppState,
+};
+
+/// 归档策略在运行时设置表中的键。
+const ARCHIVAL_SETTINGS_KEY: &str = "archival";
+/// 保留年限上限。
+const MAX_RETENTION_YEARS: u32 = 50;
+/// 学制年限上限。
+const MAX_PROGRAM_YEARS: u32 = 10;
+/// 学号前缀可识别的入学年份范围。
+const MIN_ENROLLMENT_YEAR: i32 = 1950;
+const MAX_ENROLLMENT_YEAR: i32 = 2100;
+/// 每个事务处理的学生数，避免单条语句参数过多。
+const ARCHIVE_BATCH_STUDENTS: usize = 200;
+/// 每批写入的归档行数。
+const ITEM_BATCH_SIZE: usize = 100;
+
+const TABLE_STUDENTS: &str = "students";
+const TABLE_VOLUNTEER_RECORDS: &str = "volunteer_records";
+const TABLE_CONTEST_RECORDS: &str = "contest_records";
+const TABLE_FORM_FIELD_VALUES: &str = "form_field_values";
+const TABLE_REVIEW_SIGNATURES: &str = "review_signatures";
+const TABLE_RECORD_COMMENTS: &str = "record_comments";
+/// 归档时被停用的学生账号，恢复时重新启用。
+const TABLE_USERS: &str = "users";
+
+/// 数据保留策略。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchivalPolicy {
+    /// 是否由后台任务自动归档。
+    pub enabled: bool,
+    /// 毕业后保留的年数。
+    pub retention_years: u32,
+    /// 学制年限，用于由入学年份推算毕业年份。
+    pub program_years: u32,
+}
+
+impl Default for ArchivalPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_years: 5,
+            program_years: 4,
+        }
+    }
+}
+
+impl ArchivalPolicy {
+    /// 校验策略内容。
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.retention_years > MAX_RETENTION_YEARS {
+            return Err(AppError::validation(&format!(
+                "retention_years must be at most {MAX_RETENTION_YEARS}"
+            )));
+        }
+        if !(1..=MAX_PROGRAM_YEARS).contains(&self.program_years) {
+            return Err(AppError::validation(&format!(
+                "program_years must be 1-{MAX_PROGRAM_YEARS}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// 给定年份下已到期的最晚入学年份。
+    pub fn cutoff_year(&self, current_year: i32) -> i32 {
+        current_year - self.program_years as i32 - self.retention_years as i32
+    }
+}
+
+/// 由学号前四位解析入学年份。
+pub fn enrollment_year(student_no: &str) -> Option<i32> {
+    let prefix = student_no.trim().get(..4)?;
+    if !prefix.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let year = prefix.parse::<i32>().ok()?;
+    (MIN_ENROLLMENT_YEAR..=MAX_ENROLLMENT_YEAR)
+        .contains(&year)
+        .then_some(year)
+}
+
+/// 读取归档策略；未配置时返回默认值（不自动归档）。
+pub async fn load_archival_policy<C>(db: &C) -> Result<ArchivalPolicy, AppError>
+where
+    C: ConnectionTrait,
+{
+    let record = RuntimeSetting::find_by_id(ARCHIVAL_SETTINGS_KEY.to_string())
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(record
+        .and_then(|record| {
+            serde_json::from_str(&record.value)
+                .map_err(
+                    |err| tracing::warn!(error = %err, "invalid archival policy, using defaults"),
+                )
+                .ok()
+        })
+        .unwrap_or_default())
+}
+
+/// 保存归档策略。
+pub async fn save_archival_policy(
+    state: &AppState,
+    actor_id: Uuid,
+    policy: ArchivalPolicy,
+) -> Result<ArchivalPolicy, AppError> {
+    policy.validate()?;
+    let value = serde_json::to_value(&policy)
+        .map_err(|err| AppError::internal(&format!("serialize archival policy failed: {err}")))?;
+    let model = runtime_settings::ActiveModel {
+        key: Set(ARCHIVAL_SETTINGS_KEY.to_string()),
+        value: Set(value.to_string()),
+        updated_by: Set(Some(actor_id)),
+        updated_at: Set(Utc::now()),
+    };
+    RuntimeSetting::insert(model)
+        .on_conflict(
+            OnConflict::column(runtime_settings::Column::Key)
+                .update_columns([
+                    runtime_settings::Column::Value,
+                    runtime_settings::Column::UpdatedBy,
+                    runtime_settings::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(actor_id),
+        "archival_policy_update",
+        "runtime_settings",
+        Some(ARCHIVAL_SETTINGS_KEY),
+        Some(value),
+    )
+    .await?;
+    Ok(policy)
+}
+
+/// 一次归档执行的结果。
+#[derive(Debug, Serialize)]
+pub struct ArchivalReport {
+    /// 本次到期的最晚入学年份。
+    pub cutoff_year: i32,
+    /// 新建的归档批次（每个年级一个）。
+    pub archives: Vec<archives::Model>,
+}
+
+/// 后台任务入口：策略启用时归档到期年级，返回归档的学生数。
+pub async fn run_scheduled_archival(state: &AppState) -> Result<u64, AppError> {
+    let policy = load_archival_policy(&state.db).await?;
+    if !policy.enabled {
+        return Ok(0);
+    }
+    let report = run_archival(state, &policy, None).await?;
+    Ok(report
+        .archives
+        .iter()
+        .map(|archive| archive.student_count as u64)
+        .sum())
+}
+
+/// 按策略归档全部到期年级（不检查 `enabled`，供手动执行）。
+pub async fn run_archival(
+    state: &AppState,
+    policy: &ArchivalPolicy,
+    actor_id: Option<Uuid>,
+) -> Result<ArchivalReport, AppError> {
+    let cutoff_year = policy.cutoff_year(Utc::now().year());
+    let exempt = restored_student_ids(&state.db).await?;
+    let candidates = Student::find()
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let cohorts = due_cohorts(candidates, cutoff_year, &exempt);
+
+    let mut created = Vec::with_capacity(cohorts.len());
+    for (cohort_year, cohort) in cohorts {
+        created.push(archive_cohort(state, cohort_year, cohort, actor_id).await?);
+    }
+    Ok(ArchivalReport {
+        cutoff_year,
+        archives: created,
+    })
+}
+
+/// 按入学年份分组已到期的学生（跳过已恢复过的学生）。
+fn due_cohorts(
+    candidates: Vec<students::Model>,
+    cutoff_year: i32,
+    exempt: &HashSet<Uuid>,
+) -> BTreeMap<i32, Vec<students::Model>> {
+    let mut cohorts: BTreeMap<i32, Vec<students::Model>> = BTreeMap::new();
+    for student in candidates {
+        if exempt.contains(&student.id) {
+            continue;
+        }
+        match enrollment_year(&student.student_no) {
+            Some(year) if year <= cutoff_year => cohorts.entry(year).or_default().push(student),
+            _ => {}
+        }
+    }
+    cohorts
+}
+
+/// 已恢复归档中的学生 ID。
+async fn restored_student_ids<C>(db: &C) -> Result<HashSet<Uuid>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let restored: Vec<Uuid> = Archive::find()
+        .filter(archives::Column::RestoredAt.is_not_null())
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|archive| archive.id)
+        .collect();
+    if restored.is_empty() {
+        return Ok(HashSet::new());
+    }
+    Ok(ArchiveItem::find()
+        .filter(archive_items::Column::ArchiveId.is_in(restored))
+        .filter(archive_items::Column::TableName.eq(TABLE_STUDENTS))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|item| item.row_id)
+        .collect())
+}
+
+/// 归档一个年级：分批在事务中写入快照并删除原数据，提交后删除附件文件。
+async fn archive_cohort(
+    state: &AppState,
+    cohort_year: i32,
+    cohort: Vec<students::Model>,
+    actor_id: Option<Uuid>,
+) -> Result<archives::Model, AppError> {
+    let mut archive = archives::Model {
+        id: Uuid::new_v4(),
+        cohort_year,
+        student_count: 0,
+        record_count: 0,
+        purged_attachments: 0,
+        created_by: actor_id,
+        created_at: Utc::now(),
+        restored_at: None,
+        restored_by: None,
+    };
+    for (index, batch) in cohort.chunks(ARCHIVE_BATCH_STUDENTS).enumerate() {
+        let transaction = state
+            .db
+            .begin()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        let moved = archive_batch(&transaction, archive.id, batch).await?;
+        archive.student_count += batch.len() as i32;
+        archive.record_count += moved.records as i32;
+        archive.purged_attachments += moved.attachments.len() as i32;
+        let active: archives::ActiveModel = archive.clone().into();
+        if index == 0 {
+            Archive::insert(active)
+                .exec_without_returning(&transaction)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+        } else {
+            active
+                .reset_all()
+                .update(&transaction)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+        }
+        record_audit(
+            &transaction,
+            actor_id,
+            "archive_students",
+            "archive",
+            Some(&archive.id.to_string()),
+            Some(serde_json::json!({
+                "cohort_year": cohort_year,
+                "students": batch.len(),
+                "records": moved.records,
+                "attachments": moved.attachments.len(),
+            })),
+        )
+        .await?;
+        transaction
+            .commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        state.session_cache.invalidate_users(&moved.accounts).await;
+
+        let attachment_ids: Vec<Uuid> = moved.attachments.iter().map(|(id, _)| *id).collect();
+        for (_, stored_name) in &moved.attachments {
+            let _ = tokio::fs::remove_file(stored_name).await;
+        }
+        remove_thumbnails(&state.config.upload_dir, &attachment_ids).await;
+    }
+    Ok(archive)
+}
+
+/// 单批归档移动的数据。
+struct MovedBatch {
+    records: usize,
+    /// 已删除的附件（ID 与文件路径）。
+    attachments: Vec<(Uuid, String)>,
+    /// 已停用并清除会话的学生账号。
+    accounts: Vec<Uuid>,
+}
+
+async fn archive_batch(
+    txn: &DatabaseTransaction,
+    archive_id: Uuid,
+    batch: &[students::Model],
+) -> Result<MovedBatch, AppError> {
+    let student_ids: Vec<Uuid> = batch.iter().map(|student| student.id).collect();
+    let student_nos: Vec<String> = batch
+        .iter()
+        .map(|student| student.student_no.clone())
+        .collect();
+
+    let contest = ContestRecord::find()
+        .filter(contest_records::Column::StudentId.is_in(student_ids.clone()))
+        .all(txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let volunteer = VolunteerRecord::find()
+        .filter(volunteer_records::Column::StudentId.is_in(student_ids.clone()))
+        .all(txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    // 记录 ID 在竞赛与志愿记录之间不会重复，关联表只按 ID 过滤即可。
+    let record_ids: Vec<Uuid> = contest
+        .iter()
+        .map(|record| record.id)
+        .chain(volunteer.iter().map(|record| record.id))
+        .collect();
+    let (field_values, signatures, comments) = if record_ids.is_empty() {
+        (Vec::new(), Vec::new(), Vec::new())
+    } else {
+        (
+            FormFieldValue::find()
+                .filter(form_field_values::Column::RecordId.is_in(record_ids.clone()))
+                .all(txn)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?,
+            ReviewSignature::find()
+                .filter(review_signatures::Column::RecordId.is_in(record_ids.clone()))
+                .all(txn)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?,
+            RecordComment::find()
+                .filter(record_comments::Column::RecordId.is_in(record_ids.clone()))
+                .all(txn)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?,
+        )
+    };
+    let accounts = User::find()
+        .filter(users::Column::Role.eq("student"))
+        .filter(users::Column::Username.is_in(student_nos))
+        .filter(users::Column::IsActive.eq(true))
+        .all(txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let attachments: Vec<(Uuid, String)> = Attachment::find()
+        .filter(attachments::Column::StudentId.is_in(student_ids.clone()))
+        .all(txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|attachment| (attachment.id, attachment.stored_name))
+        .collect();
+
+    let mut items = Vec::new();
+    snapshot_rows(&mut items, archive_id, TABLE_STUDENTS, batch, |row| row.id)?;
+    snapshot_rows(
+        &mut items,
+        archive_id,
+        TABLE_VOLUNTEER_RECORDS,
+        &volunteer,
+        |row| row.id,
+    )?;
+    snapshot_rows(
+        &mut items,
+        archive_id,
+        TABLE_CONTEST_RECORDS,
+        &contest,
+        |row| row.id,
+    )?;
+    snapshot_rows(
+        &mut items,
+        archive_id,
+        TABLE_FORM_FIELD_VALUES,
+        &field_values,
+        |row| row.id,
+    )?;
+    snapshot_rows(
+        &mut items,
+        archive_id,
+        TABLE_REVIEW_SIGNATURES,
+        &signatures,
+        |row| row.id,
+    )?;
+    snapshot_rows(
+        &mut items,
+        archive_id,
+        TABLE_RECORD_COMMENTS,
+        &comments,
+        |row| row.id,
+    )?;
+    snapshot_rows(&mut items, archive_id, TABLE_USERS, &accounts, |row| row.id)?;
+    while !items.is_empty() {
+        let rest = items.split_off(items.len().min(ITEM_BATCH_SIZE));
+        ArchiveItem::insert_many(std::mem::replace(&mut items, rest))
+            .exec_without_returning(txn)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+    }
+
+    if !record_ids.is_empty() {
+        FormFieldValue::delete_many()
+            .filter(form_field_values::Column::RecordId.is_in(record_ids.clone()))
+            .exec(txn)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        ReviewSignature::delete_many()
+            .filter(review_signatures::Column::RecordId.is_in(record_ids.clone()))
+            .exec(txn)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        RecordComment::delete_many()
+            .filter(record_comments::Column::RecordId.is_in(record_ids.clone()))
+            .exec(txn)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+    }
+    Attachment::delete_many()
+        .filter(attachments::Column::StudentId.is_in(student_ids.clone()))
+        .exec(txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    ContestRecord::delete_many()
+        .filter(contest_records::Column::StudentId.is_in(student_ids.clone()))
+        .exec(txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    VolunteerRecord::delete_many()
+        .filter(volunteer_records::Column::StudentId.is_in(student_ids.clone()))
+        .exec(txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Student::delete_many()
+        .filter(students::Column::Id.is_in(student_ids))
+        .exec(txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let account_ids: Vec<Uuid> = accounts.iter().map(|account| account.id).collect();
+    if !account_ids.is_empty() {
+        User::update_many()
+            .col_expr(users::Column::IsActive, Expr::value(false))
+            .col_expr(users::Column::UpdatedAt, Expr::value(Utc::now()))
+            .filter(users::Column::Id.is_in(account_ids.clone()))
+            .exec(txn)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        Session::delete_many()
+            .filter(sessions::Column::UserId.is_in(account_ids.clone()))
+            .exec(txn)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+    }
+
+    Ok(MovedBatch {
+        records: record_ids.len(),
+        attachments,
+        accounts: account_ids,
+    })
+}
+
+fn snapshot_rows<M: Serialize>(
+    items: &mut Vec<archive_items::ActiveModel>,
+    archive_id: Uuid,
+    table: &str,
+    rows: &[M],
+    row_id: impl Fn(&M) -> Uuid,
+) -> Result<(), AppError> {
+    for row in rows {
+        let data = serde_json::to_string(row)
+            .map_err(|err| AppError::internal(&format!("serialize archive row failed: {err}")))?;
+        items.push(archive_items::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            archive_id: Set(archive_id),
+            table_name: Set(table.to_string()),
+            row_id: Set(row_id(row)),
+            data: Set(data),
+        });
+    }
+    Ok(())
+}
+
+/// 归档中的学生快照。
+pub async fn archived_students<C>(
+    db: &C,
+    archive_id: Uuid,
+) -> Result<Vec<students::Model>, AppError>
+where
+    C: ConnectionTrait,
+{
+    ArchiveItem::find()
+        .filter(archive_items::Column::ArchiveId.eq(archive_id))
+        .filter(archive_items::Column::TableName.eq(TABLE_STUDENTS))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|item| {
+            serde_json::from_str(&item.data)
+                .map_err(|err| AppError::internal(&format!("invalid archive row: {err}")))
+        })
+        .collect()
+}
+
+/// 归档快照中审核签名引用的图片路径；签名文件随归档保留，恢复时重新生效。
+pub async fn archived_signature_paths<C>(db: &C) -> Result<Vec<String>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let items = ArchiveItem::find()
+        .filter(archive_items::Column::TableName.eq(TABLE_REVIEW_SIGNATURES))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    items
+        .into_iter()
+        .map(|item| {
+            let row: serde_json::Value = serde_json::from_str(&item.data)
+                .map_err(|err| AppError::internal(&format!("invalid archive row: {err}")))?;
+            Ok(row["signature_path"].as_str().map(str::to_string))
+        })
+        .filter_map(Result::transpose)
+        .collect()
+}
+
+/// 恢复归档：按原 ID 写回学生与记录并重新启用学生账号（附件不可恢复）。
+pub async fn restore_archive(
+    state: &AppState,
+    archive_id: Uuid,
+    actor_id: Uuid,
+) -> Result<archives::Model, AppError> {
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let archive = Archive::find_by_id(archive_id)
+        .one(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("archive not found"))?;
+    if archive.restored_at.is_some() {
+        return Err(AppError::bad_request("archive already restored"));
+    }
+
+    let items = ArchiveItem::find()
+        .filter(archive_items::Column::ArchiveId.eq(archive_id))
+        .all(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let mut tables: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
+    let mut account_ids = Vec::new();
+    for item in items {
+        if item.table_name == TABLE_USERS {
+            account_ids.push(item.row_id);
+            continue;
+        }
+        let value = serde_json::from_str(&item.data)
+            .map_err(|err| AppError::internal(&format!("invalid archive row: {err}")))?;
+        tables.entry(item.table_name).or_default().push(value);
+    }
+
+    let student_nos: Vec<String> = tables
+        .get(TABLE_STUDENTS)
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| row["student_no"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    let existing = Student::find()
+        .filter(students::Column::StudentNo.is_in(student_nos))
+        .all(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if !existing.is_empty() {
+        let taken: Vec<String> = existing
+            .into_iter()
+            .map(|student| student.student_no)
+            .collect();
+        return Err(AppError::bad_request(&format!(
+            "students already exist: {}",
+            taken.join(", ")
+        )));
+    }
+
+    let mut take = |name: &str| tables.remove(name).unwrap_or_default();
+    restore_table::<students::ActiveModel, _>(&transaction, TABLE_STUDENTS, take(TABLE_STUDENTS))
+        .await?;
+    restore_table::<volunteer_records::ActiveModel, _>(
+        &transaction,
+        TABLE_VOLUNTEER_RECORDS,
+        take(TABLE_VOLUNTEER_RECORDS),
+    )
+    .await?;
+    restore_table::<contest_records::ActiveModel, _>(
+        &transaction,
+        TABLE_CONTEST_RECORDS,
+        take(TABLE_CONTEST_RECORDS),
+    )
+    .await?;
+    restore_table::<form_field_values::ActiveModel, _>(
+        &transaction,
+        TABLE_FORM_FIELD_VALUES,
+        take(TABLE_FORM_FIELD_VALUES),
+    )
+    .await?;
+    restore_table::<review_signatures::ActiveModel, _>(
+        &transaction,
+        TABLE_REVIEW_SIGNATURES,
+        take(TABLE_REVIEW_SIGNATURES),
+    )
+    .await?;
+    restore_table::<record_comments::ActiveModel, _>(
+        &transaction,
+        TABLE_RECORD_COMMENTS,
+        take(TABLE_RECORD_COMMENTS),
+    )
+    .await?;
+    if !account_ids.is_empty() {
+        User::update_many()
+            .col_expr(users::Column::IsActive, Expr::value(true))
+            .col_expr(users::Column::UpdatedAt, Expr::value(Utc::now()))
+            .filter(users::Column::Id.is_in(account_ids))
+            .exec(&transaction)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+    }
+
+    let mut active: archives::ActiveModel = archive.into();
+    active.restored_at = Set(Some(Utc::now()));
+    active.restored_by = Set(Some(actor_id));
+    let archive = active
+        .update(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &transaction,
+        Some(actor_id),
+        "archive_restore",
+        "archive",
+        Some(&archive.id.to_string()),
+        Some(serde_json::json!({
+            "cohort_year": archive.cohort_year,
+            "students": archive.student_count,
+        })),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(archive)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn student(no: &str) -> students::Model {
+        let now = Utc::now();
+        students::Model {
+            id: Uuid::new_v4(),
+            student_no: no.to_string(),
+            name: "张三".to_string(),
+            gender: "男".to_string(),
+            department: "信息学院".to_string(),
+            major: "软件工程".to_string(),
+            class_name: "软件1班".to_string(),
+            phone: "13800000000".to_string(),
+            import_batch_id: None,
+            is_deleted: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn enrollment_year_reads_numeric_prefix() {
+        assert_eq!(enrollment_year("2019010101"), Some(2019));
+        assert_eq!(enrollment_year(" 2023001 "), Some(2023));
+        assert_eq!(enrollment_year("19010101"), None);
+        assert_eq!(enrollment_year("A2019001"), None);
+        assert_eq!(enrollment_year("201"), None);
+        assert_eq!(enrollment_year("学号2019"), None);
+    }
+
+    #[test]
+    fn cutoff_year_adds_program_and_retention() {
+        let policy = ArchivalPolicy {
+            enabled: true,
+            retention_years: 5,
+            program_years: 4,
+        };
+        assert_eq!(policy.cutoff_year(2026), 2017);
+        assert!(policy.validate().is_ok());
+        assert!(ArchivalPolicy {
+            program_years: 0,
+            ..policy.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(ArchivalPolicy {
+            retention_years: MAX_RETENTION_YEARS + 1,
+            ..policy
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn due_cohorts_groups_expired_students_and_skips_exempt() {
+        let restored = student("2015000002");
+        let exempt = HashSet::from([restored.id]);
+        let cohorts = due_cohorts(
+            vec![
+                student("2015000001"),
+                restored,
+                student("2016000001"),
+                student("2018000001"),
+                student("T0001"),
+            ],
+            2017,
+            &exempt,
+        );
+        assert_eq!(
+            cohorts.keys().copied().collect::<Vec<_>>(),
+            vec![2015, 2016]
+        );
+        assert_eq!(cohorts[&2015].len(), 1);
+        assert_eq!(cohorts[&2015][0].student_no, "2015000001");
+    }
+
+    #[tokio::test]
+    async fn load_archival_policy_defaults_when_missing() {
+        let db = crate::services::test_support::memory_db().await;
+        let policy = load_archival_policy(&db).await.unwrap();
+        assert_eq!(policy, ArchivalPolicy::default());
+        assert!(!policy.enabled);
+    }
+}
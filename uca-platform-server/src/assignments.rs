@@ -0,0 +1,417 @@
+//! 审核任务指派：手动指派或按记录、班级、院系轮询分配，分配时优先交给待审数量最少的审核人。
+
+use std::collections::{BTreeMap, HashMap};
+
+use sea_orm::{sea_query::Expr, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    access::department_scope,
+    entities::{
+        contest_records, students, users, volunteer_records, ContestRecord, Student, User,
+        VolunteerRecord,
+    },
+    error::AppError,
+    services::review::{STATUS_FIRST_REVIEWED, STATUS_SUBMITTED},
+};
+
+/// 仍需审核的记录状态。
+pub const PENDING_STATUSES: [&str; 2] = [STATUS_SUBMITTED, STATUS_FIRST_REVIEWED];
+
+/// 轮询分配的分组方式；同一分组的记录交给同一审核人。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignGroup {
+    /// 逐条记录轮询。
+    Record,
+    /// 按班级分组。
+    ClassName,
+    /// 按院系分组。
+    Department,
+}
+
+impl AssignGroup {
+    /// 解析分组方式（record/class/department）。
+    pub fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "record" => Ok(Self::Record),
+            "class" => Ok(Self::ClassName),
+            "department" => Ok(Self::Department),
+            _ => Err(AppError::validation("invalid group_by")),
+        }
+    }
+}
+
+/// 记录类型（contest/volunteer）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignRecordType {
+    /// 竞赛记录。
+    Contest,
+    /// 志愿记录。
+    Volunteer,
+}
+
+impl AssignRecordType {
+    /// 解析记录类型。
+    pub fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "contest" => Ok(Self::Contest),
+            "volunteer" => Ok(Self::Volunteer),
+            _ => Err(AppError::validation("invalid record_type")),
+        }
+    }
+}
+
+/// 待分配的记录。
+#[derive(Debug, Clone)]
+pub struct AssignCandidate {
+    /// 记录 ID。
+    pub record_id: Uuid,
+    /// 学生院系。
+    pub department: String,
+    /// 学生班级。
+    pub class_name: String,
+}
+
+impl AssignCandidate {
+    fn group_key(&self, group: AssignGroup) -> String {
+        match group {
+            AssignGroup::Record => self.record_id.to_string(),
+            AssignGroup::ClassName => format!("{}/{}", self.department, self.class_name),
+            AssignGroup::Department => self.department.clone(),
+        }
+    }
+}
+
+/// 参与分配的审核人及其当前待审数量。
+#[derive(Debug, Clone)]
+pub struct ReviewerSlot {
+    /// 用户 ID。
+    pub id: Uuid,
+    /// 院系范围（为空时可审核全部院系）。
+    pub department: Option<String>,
+    /// 当前待审数量。
+    pub load: u64,
+}
+
+/// 轮询分配结果。
+#[derive(Debug, Default)]
+pub struct AssignmentPlan {
+    /// 记录 ID 与指派的审核人。
+    pub assignments: Vec<(Uuid, Uuid)>,
+    /// 没有可用审核人（院系范围不覆盖）的记录。
+    pub skipped: Vec<Uuid>,
+}
+
+/// 按分组依次把记录交给当前待审数量最少的审核人（并列时按传入顺序）。
+pub fn plan_round_robin(
+    candidates: &[AssignCandidate],
+    reviewers: &mut [ReviewerSlot],
+    group: AssignGroup,
+) -> AssignmentPlan {
+    let mut groups: BTreeMap<String, Vec<&AssignCandidate>> = BTreeMap::new();
+    for candidate in candidates {
+        groups.entry(candidate.group_key(group)).or_default().push(candidate);
+    }
+
+    let mut plan = AssignmentPlan::default();
+    for members in groups.values() {
+        let department = members[0].department.trim();
+        let chosen = reviewers
+            .iter_mut()
+            .filter(|slot| slot.department.as_deref().map_or(true, |scope| scope == department))
+            .min_by_key(|slot| slot.load);
+        match chosen {
+            Some(slot) => {
+                slot.load += members.len() as u64;
+                plan.assignments
+                    .extend(members.iter().map(|member| (member.record_id, slot.id)));
+            }
+            None => plan.skipped.extend(members.iter().map(|member| member.record_id)),
+        }
+    }
+    plan
+}
+
+/// 审核人工作量。
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewerWorkload {
+    /// 用户 ID。
+    pub reviewer_id: Uuid,
+    /// 用户名。
+    pub username: String,
+    /// 显示名称。
+    pub display_name: String,
+    /// 角色（teacher/reviewer）。
+    pub role: String,
+    /// 院系范围。
+    pub department: Option<String>,
+    /// 已指派且仍待审核的记录数（竞赛与志愿合计）。
+    pub pending: u64,
+}
+
+/// 读取可被指派的审核人（启用中的教师与审核人员），按用户名排序。
+pub async fn load_assignable_reviewers<C>(db: &C) -> Result<Vec<users::Model>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut reviewers = User::find()
+        .filter(users::Column::Role.is_in(["teacher", "reviewer"]))
+        .filter(users::Column::IsActive.eq(true))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    reviewers.sort_by(|a, b| a.username.cmp(&b.username));
+    Ok(reviewers)
+}
+
+/// 统计审核人已指派且仍待审核的记录数。
+pub async fn pending_workload<C>(db: &C) -> Result<HashMap<Uuid, u64>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut workload: HashMap<Uuid, u64> = HashMap::new();
+    let contest = ContestRecord::find()
+        .filter(contest_records::Column::IsDeleted.eq(false))
+        .filter(contest_records::Column::Status.is_in(PENDING_STATUSES))
+        .filter(contest_records::Column::AssignedReviewerId.is_not_null())
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    for reviewer_id in contest.into_iter().filter_map(|record| record.assigned_reviewer_id) {
+        *workload.entry(reviewer_id).or_default() += 1;
+    }
+    let volunteer = VolunteerRecord::find()
+        .filter(volunteer_records::Column::IsDeleted.eq(false))
+        .filter(volunteer_records::Column::Status.is_in(PENDING_STATUSES))
+        .filter(volunteer_records::Column::AssignedReviewerId.is_not_null())
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    for reviewer_id in volunteer.into_iter().filter_map(|record| record.assigned_reviewer_id) {
+        *workload.entry(reviewer_id).or_default() += 1;
+    }
+    Ok(workload)
+}
+
+/// 汇总全部可指派审核人的工作量。
+pub async fn reviewer_workloads<C>(db: &C) -> Result<Vec<ReviewerWorkload>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let workload = pending_workload(db).await?;
+    Ok(load_assignable_reviewers(db)
+        .await?
+        .into_iter()
+        .map(|reviewer| ReviewerWorkload {
+            pending: workload.get(&reviewer.id).copied().unwrap_or(0),
+            department: department_scope(&reviewer).map(str::to_string),
+            reviewer_id: reviewer.id,
+            username: reviewer.username,
+            display_name: reviewer.display_name,
+            role: reviewer.role,
+        })
+        .collect())
+}
+
+/// 读取待分配记录及其学生院系、班级。
+///
+/// `record_ids` 为空时取全部待审记录；`include_assigned` 为 false 时跳过已指派的记录。
+pub async fn load_candidates<C>(
+    db: &C,
+    record_type: AssignRecordType,
+    record_ids: Option<&[Uuid]>,
+    pending_only: bool,
+    include_assigned: bool,
+) -> Result<Vec<AssignCandidate>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let rows: Vec<(Uuid, Uuid)> = match record_type {
+        AssignRecordType::Contest => {
+            let mut finder = ContestRecord::find().filter(contest_records::Column::IsDeleted.eq(false));
+            if let Some(ids) = record_ids {
+                finder = finder.filter(contest_records::Column::Id.is_in(ids.iter().cloned()));
+            }
+            if pending_only {
+                finder = finder.filter(contest_records::Column::Status.is_in(PENDING_STATUSES));
+            }
+            if !include_assigned {
+                finder = finder.filter(contest_records::Column::AssignedReviewerId.is_null());
+            }
+            finder
+                .all(db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?
+                .into_iter()
+                .map(|record| (record.id, record.student_id))
+                .collect()
+        }
+        AssignRecordType::Volunteer => {
+            let mut finder = VolunteerRecord::find().filter(volunteer_records::Column::IsDeleted.eq(false));
+            if let Some(ids) = record_ids {
+                finder = finder.filter(volunteer_records::Column::Id.is_in(ids.iter().cloned()));
+            }
+            if pending_only {
+                finder = finder.filter(volunteer_records::Column::Status.is_in(PENDING_STATUSES));
+            }
+            if !include_assigned {
+                finder = finder.filter(volunteer_records::Column::AssignedReviewerId.is_null());
+            }
+            finder
+                .all(db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?
+                .into_iter()
+                .map(|record| (record.id, record.student_id))
+                .collect()
+        }
+    };
+
+    let student_ids: Vec<Uuid> = rows.iter().map(|(_, student_id)| *student_id).collect();
+    let students_map: HashMap<Uuid, students::Model> = if student_ids.is_empty() {
+        HashMap::new()
+    } else {
+        Student::find()
+            .filter(students::Column::Id.is_in(student_ids))
+            .filter(students::Column::IsDeleted.eq(false))
+            .all(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .into_iter()
+            .map(|student| (student.id, student))
+            .collect()
+    };
+    Ok(rows
+        .into_iter()
+        .filter_map(|(record_id, student_id)| {
+            students_map.get(&student_id).map(|student| AssignCandidate {
+                record_id,
+                department: student.department.trim().to_string(),
+                class_name: student.class_name.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// 把单条新提交的记录指派给院系范围覆盖且待审数量最少的审核人，返回指派结果。
+pub async fn auto_assign_record<C>(
+    db: &C,
+    record_type: AssignRecordType,
+    record_id: Uuid,
+) -> Result<Option<Uuid>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let workload = pending_workload(db).await?;
+    let mut slots: Vec<ReviewerSlot> = load_assignable_reviewers(db)
+        .await?
+        .iter()
+        .map(|reviewer| ReviewerSlot {
+            id: reviewer.id,
+            department: department_scope(reviewer).map(str::to_string),
+            load: workload.get(&reviewer.id).copied().unwrap_or(0),
+        })
+        .collect();
+    let candidates = load_candidates(db, record_type, Some(&[record_id]), true, false).await?;
+    let plan = plan_round_robin(&candidates, &mut slots, AssignGroup::Record);
+    let Some((_, reviewer_id)) = plan.assignments.first().copied() else {
+        return Ok(None);
+    };
+    apply_assignment(db, record_type, &[record_id], Some(reviewer_id)).await?;
+    Ok(Some(reviewer_id))
+}
+
+/// 写入指派结果；`reviewer_id` 为空时取消指派。
+pub async fn apply_assignment<C>(
+    db: &C,
+    record_type: AssignRecordType,
+    record_ids: &[Uuid],
+    reviewer_id: Option<Uuid>,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    if record_ids.is_empty() {
+        return Ok(());
+    }
+    match record_type {
+        AssignRecordType::Contest => {
+            ContestRecord::update_many()
+                .col_expr(contest_records::Column::AssignedReviewerId, Expr::value(reviewer_id))
+                .filter(contest_records::Column::Id.is_in(record_ids.iter().cloned()))
+                .exec(db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+        }
+        AssignRecordType::Volunteer => {
+            VolunteerRecord::update_many()
+                .col_expr(volunteer_records::Column::AssignedReviewerId, Expr::value(reviewer_id))
+                .filter(volunteer_records::Column::Id.is_in(record_ids.iter().cloned()))
+                .exec(db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(department: &str, class_name: &str) -> AssignCandidate {
+        AssignCandidate {
+            record_id: Uuid::new_v4(),
+            department: department.to_string(),
+            class_name: class_name.to_string(),
+        }
+    }
+
+    fn slot(department: Option<&str>, load: u64) -> ReviewerSlot {
+        ReviewerSlot {
+            id: Uuid::new_v4(),
+            department: department.map(str::to_string),
+            load,
+        }
+    }
+
+    #[test]
+    fn round_robin_balances_by_current_load() {
+        let candidates: Vec<_> = (0..5).map(|_| candidate("信息学院", "软工1班")).collect();
+        let mut reviewers = vec![slot(None, 3), slot(None, 0)];
+        let plan = plan_round_robin(&candidates, &mut reviewers, AssignGroup::Record);
+        assert_eq!(plan.assignments.len(), 5);
+        assert!(plan.skipped.is_empty());
+        assert_eq!(reviewers[0].load, 4);
+        assert_eq!(reviewers[1].load, 4);
+    }
+
+    #[test]
+    fn class_groups_stay_with_one_reviewer() {
+        let candidates = vec![
+            candidate("信息学院", "软工1班"),
+            candidate("信息学院", "软工2班"),
+            candidate("信息学院", "软工1班"),
+        ];
+        let mut reviewers = vec![slot(None, 0), slot(None, 0)];
+        let plan = plan_round_robin(&candidates, &mut reviewers, AssignGroup::ClassName);
+        let owner = |record_id: Uuid| {
+            plan.assignments
+                .iter()
+                .find(|(id, _)| *id == record_id)
+                .map(|(_, reviewer)| *reviewer)
+        };
+        assert_eq!(owner(candidates[0].record_id), owner(candidates[2].record_id));
+        assert_ne!(owner(candidates[0].record_id), owner(candidates[1].record_id));
+    }
+
+    #[test]
+    fn department_scope_limits_eligible_reviewers() {
+        let candidates = vec![candidate("信息学院", "软工1班"), candidate("机械学院", "机械1班")];
+        let mut reviewers = vec![slot(Some("信息学院"), 0)];
+        let plan = plan_round_robin(&candidates, &mut reviewers, AssignGroup::Record);
+        assert_eq!(plan.assignments, vec![(candidates[0].record_id, reviewers[0].id)]);
+        assert_eq!(plan.skipped, vec![candidates[1].record_id]);
+    }
+}
@@ -0,0 +1,228 @@
+//! 附件要求规则：按记录类型、竞赛级别或自定义字段条件要求佐证材料，审核通过前校验。
+
+use std::collections::HashMap;
+
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        attachment_requirements, attachments, contest_records, form_field_values, Attachment,
+        AttachmentRequirement, ContestRecord, FormFieldValue,
+    },
+    error::AppError,
+    routes::attachments::ATTACHMENT_KIND_EVIDENCE,
+};
+
+/// 单条规则允许要求的附件数量上限。
+pub const MAX_REQUIRED_ATTACHMENTS: i32 = 10;
+
+/// 规则是否适用于记录（所有已设置的条件均需满足，比较时忽略首尾空白）。
+pub fn rule_applies(
+    rule: &attachment_requirements::Model,
+    contest_level: Option<&str>,
+    custom_fields: &HashMap<String, String>,
+) -> bool {
+    let level_matches = match rule.contest_level.as_deref() {
+        Some(level) => contest_level.map(str::trim) == Some(level.trim()),
+        None => true,
+    };
+    let field_matches = match rule.field_key.as_deref() {
+        Some(key) => {
+            let value = custom_fields.get(key).map(|value| value.trim()).unwrap_or_default();
+            match rule.field_value.as_deref() {
+                Some(expected) => value == expected.trim(),
+                None => !value.is_empty(),
+            }
+        }
+        None => true,
+    };
+    level_matches && field_matches
+}
+
+/// 记录适用的附件要求：需要的附件数量（取各规则最大值）与规则说明。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequiredEvidence {
+    /// 至少需要的附件数量。
+    pub min_attachments: i32,
+    /// 适用规则的说明。
+    pub descriptions: Vec<String>,
+}
+
+/// 汇总记录适用的附件要求；没有适用规则时返回 `None`。
+pub fn required_evidence(
+    rules: &[attachment_requirements::Model],
+    contest_level: Option<&str>,
+    custom_fields: &HashMap<String, String>,
+) -> Option<RequiredEvidence> {
+    let matched: Vec<&attachment_requirements::Model> = rules
+        .iter()
+        .filter(|rule| rule_applies(rule, contest_level, custom_fields))
+        .collect();
+    let min_attachments = matched.iter().map(|rule| rule.min_attachments).max()?;
+    Some(RequiredEvidence {
+        min_attachments,
+        descriptions: matched.iter().map(|rule| rule.description.clone()).collect(),
+    })
+}
+
+/// 读取记录类型的附件要求规则。
+pub async fn load_requirements<C>(
+    db: &C,
+    record_type: &str,
+) -> Result<Vec<attachment_requirements::Model>, AppError>
+where
+    C: ConnectionTrait,
+{
+    AttachmentRequirement::find()
+        .filter(attachment_requirements::Column::RecordType.eq(record_type))
+        .order_by_asc(attachment_requirements::Column::CreatedAt)
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))
+}
+
+/// 找出附件数量不满足要求的记录，返回记录 ID 与缺少佐证的说明。
+pub async fn records_missing_evidence<C>(
+    db: &C,
+    record_type: &str,
+    record_ids: &[Uuid],
+) -> Result<HashMap<Uuid, String>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let rules = load_requirements(db, record_type).await?;
+    if rules.is_empty() || record_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let levels: HashMap<Uuid, Option<String>> = if record_type == "contest" {
+        ContestRecord::find()
+            .filter(contest_records::Column::Id.is_in(record_ids.to_vec()))
+            .all(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .into_iter()
+            .map(|record| (record.id, record.contest_level))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    let mut custom_fields: HashMap<Uuid, HashMap<String, String>> = HashMap::new();
+    if rules.iter().any(|rule| rule.field_key.is_some()) {
+        let values = FormFieldValue::find()
+            .filter(form_field_values::Column::RecordType.eq(record_type))
+            .filter(form_field_values::Column::RecordId.is_in(record_ids.to_vec()))
+            .all(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        for value in values {
+            custom_fields
+                .entry(value.record_id)
+                .or_default()
+                .insert(value.field_key, value.value);
+        }
+    }
+    let mut counts: HashMap<Uuid, i32> = HashMap::new();
+    let stored = Attachment::find()
+        .filter(attachments::Column::RecordType.eq(record_type))
+        .filter(attachments::Column::RecordId.is_in(record_ids.to_vec()))
+        .filter(attachments::Column::Kind.eq(ATTACHMENT_KIND_EVIDENCE))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    for attachment in stored {
+        *counts.entry(attachment.record_id).or_default() += 1;
+    }
+
+    let empty = HashMap::new();
+    let mut missing = HashMap::new();
+    for record_id in record_ids {
+        let level = levels.get(record_id).and_then(|level| level.as_deref());
+        let fields = custom_fields.get(record_id).unwrap_or(&empty);
+        let Some(required) = required_evidence(&rules, level, fields) else {
+            continue;
+        };
+        let count = counts.get(record_id).copied().unwrap_or(0);
+        if count < required.min_attachments {
+            missing.insert(
+                *record_id,
+                format!(
+                    "attachment required: {} ({count}/{})",
+                    required.descriptions.join("; "),
+                    required.min_attachments
+                ),
+            );
+        }
+    }
+    Ok(missing)
+}
+
+/// 确认记录已上传要求的佐证附件，否则拒绝审核通过。
+pub async fn ensure_required_evidence<C>(
+    db: &C,
+    record_type: &str,
+    record_id: Uuid,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    match records_missing_evidence(db, record_type, &[record_id])
+        .await?
+        .remove(&record_id)
+    {
+        Some(message) => Err(AppError::bad_request(&message)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn rule(
+        level: Option<&str>,
+        field: Option<(&str, Option<&str>)>,
+        min_attachments: i32,
+    ) -> attachment_requirements::Model {
+        let now = Utc::now();
+        attachment_requirements::Model {
+            id: Uuid::new_v4(),
+            record_type: "contest".to_string(),
+            contest_level: level.map(str::to_string),
+            field_key: field.map(|(key, _)| key.to_string()),
+            field_value: field.and_then(|(_, value)| value.map(str::to_string)),
+            min_attachments,
+            description: format!("{}证书扫描件", level.unwrap_or("获奖")),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn level_rules_apply_only_to_matching_levels() {
+        let rules = vec![rule(Some("国家级"), None, 1), rule(Some("省级"), None, 1)];
+        let fields = HashMap::new();
+        assert!(required_evidence(&rules, Some(" 国家级 "), &fields).is_some());
+        assert!(required_evidence(&rules, Some("校级"), &fields).is_none());
+        assert!(required_evidence(&rules, None, &fields).is_none());
+    }
+
+    #[test]
+    fn custom_field_conditions_and_max_count() {
+        let rules = vec![
+            rule(None, Some(("team", Some("是"))), 2),
+            rule(None, Some(("certificate_no", None)), 1),
+        ];
+        let mut fields = HashMap::new();
+        assert!(required_evidence(&rules, None, &fields).is_none());
+        fields.insert("team".to_string(), "是".to_string());
+        fields.insert("certificate_no".to_string(), "A-001".to_string());
+        let required = required_evidence(&rules, None, &fields).unwrap();
+        assert_eq!(required.min_attachments, 2);
+        assert_eq!(required.descriptions.len(), 2);
+        fields.insert("team".to_string(), "否".to_string());
+        assert_eq!(required_evidence(&rules, None, &fields).unwrap().min_attachments, 1);
+    }
+}
@@ -0,0 +1,35 @@
+//! 操作审计日志写入。
+
+use chrono::Utc;
+use sea_orm::{ConnectionTrait, EntityTrait, Set};
+use uuid::Uuid;
+
+use crate::{entities::audit_logs, error::AppError};
+
+/// 写入一条审计日志（可在事务内调用）。
+pub async fn record_audit<C>(
+    db: &C,
+    actor_id: Option<Uuid>,
+    action: &str,
+    target_type: &str,
+    target_id: Option<&str>,
+    detail: Option<serde_json::Value>,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    let model = audit_logs::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        actor_id: Set(actor_id),
+        action: Set(action.to_string()),
+        target_type: Set(target_type.to_string()),
+        target_id: Set(target_id.map(|value| value.to_string())),
+        detail: Set(detail.map(|value| value.to_string())),
+        created_at: Set(Utc::now()),
+    };
+    audit_logs::Entity::insert(model)
+        .exec_without_returning(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(())
+}
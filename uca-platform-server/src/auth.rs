@@ -1,5 +1,8 @@
 //! Passkey、TOTP 与会话的认证工具。
 
+pub mod ldap;
+pub mod sso;
+
 use aead::Aead;
 use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
 use argon2::{password_hash::SaltString, Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
@@ -8,11 +11,20 @@ use rand::{distributions::Alphanumeric, rngs::OsRng, Rng, RngCore};
 use sha2::{Digest, Sha256};
 use totp_rs::{Algorithm, Secret, TOTP};
 use uuid::Uuid;
+use webauthn_rs::prelude::{Webauthn, WebauthnBuilder};
 
-use crate::error::AppError;
+use crate::{
+    config::{Config, SecretKey, TotpConfig},
+    error::AppError,
+};
 
 const SECRET_NONCE_LEN: usize = 12;
-const SECRET_PREFIX: &str = "SECv1:";
+/// 旧版密文前缀（不含密钥 ID，按当前密钥与历史密钥依次尝试解密）。
+const SECRET_PREFIX_V1: &str = "SECv1:";
+/// 带密钥 ID 的密文前缀：`SECv2:<key_id>:<base64>`。
+const SECRET_PREFIX_V2: &str = "SECv2:";
+/// TOTP 时间步长（秒）。
+pub const TOTP_STEP_SECONDS: u64 = 30;
 
 /// 生成的恢复码与其哈希。
 #[derive(Debug, Clone)]
@@ -23,6 +35,20 @@ pub struct RecoveryCode {
     pub hash: String,
 }
 
+/// 按配置构建 WebAuthn 依赖方：`RP_ORIGIN` 与 `RP_EXTRA_ORIGINS` 均可发起认证，可选接受子域名。
+pub fn build_webauthn(config: &Config) -> Result<Webauthn, AppError> {
+    let mut builder = WebauthnBuilder::new(&config.rp_id, &config.rp_origin)
+        .map_err(|err| AppError::internal(&format!("webauthn config error: {err}")))?
+        .rp_name("Labor Hours Platform")
+        .allow_subdomains(config.rp_allow_subdomains);
+    for origin in &config.rp_extra_origins {
+        builder = builder.append_allowed_origin(origin);
+    }
+    builder
+        .build()
+        .map_err(|err| AppError::internal(&format!("webauthn build error: {err}")))
+}
+
 /// 生成随机会话令牌（base64url）。
 pub fn generate_session_token() -> String {
     let mut bytes = [0u8; 32];
@@ -114,7 +140,7 @@ pub fn generate_totp(secret_label: &str, account: &str) -> Result<(Vec<u8>, Stri
         Algorithm::SHA1,
         6,
         1,
-        30,
+        TOTP_STEP_SECONDS,
         bytes.clone(),
         Some(secret_label.to_string()),
         account.to_string(),
@@ -123,24 +149,82 @@ pub fn generate_totp(secret_label: &str, account: &str) -> Result<(Vec<u8>, Stri
     Ok((bytes, totp.get_url()))
 }
 
-/// 使用存储密钥校验 TOTP 验证码。
-pub fn verify_totp(secret: &[u8], code: &str) -> Result<bool, AppError> {
+/// 使用存储密钥校验 TOTP 验证码，允许前后 `skew_steps` 个时间步的偏差；通过时返回匹配的时间步。
+pub fn verify_totp(secret: &[u8], code: &str, skew_steps: u8, unix_time: u64) -> Result<Option<u64>, AppError> {
     let totp = TOTP::new(
         Algorithm::SHA1,
         6,
-        1,
-        30,
+        0,
+        TOTP_STEP_SECONDS,
         secret.to_vec(),
         None,
         "".to_string(),
     )
     .map_err(|_| AppError::internal("failed to build TOTP"))?;
-    Ok(totp.check_current(code).unwrap_or(false))
+    let current = unix_time / TOTP_STEP_SECONDS;
+    let skew = u64::from(skew_steps);
+    for step in current.saturating_sub(skew)..=current + skew {
+        if totp.check(code, step * TOTP_STEP_SECONDS) {
+            return Ok(Some(step));
+        }
+    }
+    Ok(None)
 }
 
-/// 使用 AES-256-GCM 加密密钥。
-pub fn encrypt_secret(secret: &[u8], key: &[u8]) -> Result<String, AppError> {
-    let cipher = Aes256Gcm::new_from_slice(key)
+/// 连续失败 `failures` 次后的锁定时长（秒），未达到阈值时返回 `None`；超过阈值后每次失败翻倍。
+pub fn totp_backoff_seconds(config: &TotpConfig, failures: u32) -> Option<u64> {
+    if failures < config.max_failures {
+        return None;
+    }
+    let exponent = (failures - config.max_failures).min(32);
+    Some(
+        config
+            .backoff_base_seconds
+            .saturating_mul(1u64 << exponent)
+            .min(config.backoff_max_seconds),
+    )
+}
+
+/// 应用密钥环：当前密钥用于加密，历史密钥仅用于解密轮换前的密文。
+#[derive(Debug, Clone, Copy)]
+pub struct SecretKeyring<'a> {
+    /// 当前密钥 ID。
+    pub primary_id: &'a str,
+    /// 当前密钥。
+    pub primary_key: &'a [u8],
+    /// 历史密钥。
+    pub previous: &'a [SecretKey],
+}
+
+impl SecretKeyring<'_> {
+    fn key(&self, id: &str) -> Option<&[u8]> {
+        if id == self.primary_id {
+            return Some(self.primary_key);
+        }
+        self.previous
+            .iter()
+            .find(|key| key.id == id)
+            .map(|key| key.key.as_slice())
+    }
+
+    /// 当前密钥在前的全部密钥。
+    fn all_keys(&self) -> impl Iterator<Item = &[u8]> {
+        std::iter::once(self.primary_key).chain(self.previous.iter().map(|key| key.key.as_slice()))
+    }
+}
+
+/// 密文使用的密钥 ID；旧版密文（无密钥 ID）返回 `None`。
+pub fn secret_key_id(encoded: &str) -> Option<&str> {
+    encoded
+        .trim()
+        .strip_prefix(SECRET_PREFIX_V2)
+        .and_then(|rest| rest.split_once(':'))
+        .map(|(id, _)| id)
+}
+
+/// 使用当前密钥以 AES-256-GCM 加密，密文记录密钥 ID。
+pub fn encrypt_secret(secret: &[u8], keyring: &SecretKeyring) -> Result<String, AppError> {
+    let cipher = Aes256Gcm::new_from_slice(keyring.primary_key)
         .map_err(|_| AppError::internal("invalid secret encryption key"))?;
     let mut nonce = [0u8; SECRET_NONCE_LEN];
     OsRng.fill_bytes(&mut nonce);
@@ -152,15 +236,35 @@ pub fn encrypt_secret(secret: &[u8], key: &[u8]) -> Result<String, AppError> {
     payload.extend_from_slice(&nonce);
     payload.extend_from_slice(&ciphertext);
     let encoded = base64::engine::general_purpose::STANDARD.encode(payload);
-    Ok(format!("{SECRET_PREFIX}{encoded}"))
+    Ok(format!("{SECRET_PREFIX_V2}{}:{encoded}", keyring.primary_id))
 }
 
-/// 解密已加密的密钥。
-pub fn decrypt_secret(encoded: &str, key: &[u8]) -> Result<Vec<u8>, AppError> {
+/// 解密已加密的密钥：带密钥 ID 的密文使用对应密钥，旧版密文依次尝试当前密钥与历史密钥。
+pub fn decrypt_secret(encoded: &str, keyring: &SecretKeyring) -> Result<Vec<u8>, AppError> {
+    let encoded = encoded.trim();
+    if let Some(rest) = encoded.strip_prefix(SECRET_PREFIX_V2) {
+        let (id, payload) = rest
+            .split_once(':')
+            .ok_or_else(|| AppError::internal("invalid encrypted secret format"))?;
+        let key = keyring
+            .key(id)
+            .ok_or_else(|| AppError::internal(&format!("unknown secret key id: {id}")))?;
+        return decrypt_payload(payload, key);
+    }
     let payload = encoded
-        .trim()
-        .strip_prefix(SECRET_PREFIX)
+        .strip_prefix(SECRET_PREFIX_V1)
         .ok_or_else(|| AppError::internal("invalid encrypted secret format"))?;
+    let mut last_err = AppError::internal("failed to decrypt secret");
+    for key in keyring.all_keys() {
+        match decrypt_payload(payload, key) {
+            Ok(secret) => return Ok(secret),
+            Err(err) => last_err = err,
+        }
+    }
+    Err(last_err)
+}
+
+fn decrypt_payload(payload: &str, key: &[u8]) -> Result<Vec<u8>, AppError> {
     let payload = base64::engine::general_purpose::STANDARD
         .decode(payload)
         .map_err(|_| AppError::internal("invalid encrypted secret base64"))?;
@@ -210,19 +314,95 @@ mod tests {
         )
         .expect("build");
         let code = totp.generate_current().expect("code");
-        assert!(verify_totp(&secret, &code).expect("verify"));
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("time")
+            .as_secs();
+        assert!(verify_totp(&secret, &code, 1, now).expect("verify").is_some());
     }
 
     #[test]
-    fn secret_encrypt_round_trip() {
-        let mut key = [0u8; 32];
+    fn totp_skew_controls_accepted_steps() {
+        let (secret, _) = generate_totp("Labor Hours Platform", "user@example.com").expect("totp");
+        let totp = TOTP::new(Algorithm::SHA1, 6, 0, 30, secret.clone(), None, "".to_string()).expect("build");
+        let now = 1_700_000_000;
+        let previous = totp.generate(now - 30);
+        assert_eq!(verify_totp(&secret, &previous, 1, now).expect("verify"), Some(now / 30 - 1));
+        assert_eq!(verify_totp(&secret, &previous, 0, now).expect("verify"), None);
+    }
+
+    #[test]
+    fn totp_backoff_doubles_up_to_cap() {
+        let config = TotpConfig::default();
+        assert_eq!(totp_backoff_seconds(&config, 4), None);
+        assert_eq!(totp_backoff_seconds(&config, 5), Some(30));
+        assert_eq!(totp_backoff_seconds(&config, 6), Some(60));
+        assert_eq!(totp_backoff_seconds(&config, 40), Some(3600));
+    }
+
+    fn random_key() -> Vec<u8> {
+        let mut key = vec![0u8; 32];
         OsRng.fill_bytes(&mut key);
+        key
+    }
+
+    #[test]
+    fn secret_encrypt_round_trip() {
+        let key = random_key();
+        let keyring = SecretKeyring {
+            primary_id: "k1",
+            primary_key: &key,
+            previous: &[],
+        };
         let data = b"secret";
-        let enc = encrypt_secret(data, &key).expect("encrypt");
-        let dec = decrypt_secret(&enc, &key).expect("decrypt");
+        let enc = encrypt_secret(data, &keyring).expect("encrypt");
+        assert_eq!(secret_key_id(&enc), Some("k1"));
+        let dec = decrypt_secret(&enc, &keyring).expect("decrypt");
         assert_eq!(dec, data);
     }
 
+    #[test]
+    fn secrets_decrypt_after_key_rotation() {
+        let old_key = random_key();
+        let new_key = random_key();
+        let old_keyring = SecretKeyring {
+            primary_id: "k1",
+            primary_key: &old_key,
+            previous: &[],
+        };
+        let enc = encrypt_secret(b"secret", &old_keyring).expect("encrypt");
+
+        // 旧版密文：无密钥 ID。
+        let cipher = Aes256Gcm::new_from_slice(&old_key).unwrap();
+        let nonce = [7u8; SECRET_NONCE_LEN];
+        let mut payload = nonce.to_vec();
+        payload.extend(cipher.encrypt(Nonce::from_slice(&nonce), b"legacy".as_slice()).unwrap());
+        let legacy = format!(
+            "{SECRET_PREFIX_V1}{}",
+            base64::engine::general_purpose::STANDARD.encode(payload)
+        );
+        assert_eq!(secret_key_id(&legacy), None);
+
+        let previous = [SecretKey {
+            id: "k1".to_string(),
+            key: old_key.clone(),
+        }];
+        let rotated = SecretKeyring {
+            primary_id: "k2",
+            primary_key: &new_key,
+            previous: &previous,
+        };
+        assert_eq!(decrypt_secret(&enc, &rotated).expect("decrypt"), b"secret");
+        assert_eq!(decrypt_secret(&legacy, &rotated).expect("decrypt"), b"legacy");
+        let without_old = SecretKeyring {
+            primary_id: "k2",
+            primary_key: &new_key,
+            previous: &[],
+        };
+        assert!(decrypt_secret(&enc, &without_old).is_err());
+        assert!(decrypt_secret(&legacy, &without_old).is_err());
+    }
+
     #[test]
     fn session_token_hash_changes() {
         let token = generate_session_token();
@@ -0,0 +1,247 @@
+//! LDAP/Active Directory 登录：绑定校验密码、按组映射角色并同步本地账号。
+
+use std::time::Duration;
+
+use chrono::Utc;
+use ldap3::{ldap_escape, LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use uuid::Uuid;
+
+use crate::{
+    audit::record_audit,
+    config::LdapConfig,
+    entities::{users, User},
+    error::AppError,
+};
+
+/// 连接 LDAP 服务器的超时时间。
+const LDAP_TIMEOUT_SECONDS: u64 = 10;
+
+/// 目录中的用户信息。
+#[derive(Debug, Clone)]
+pub struct LdapIdentity {
+    pub dn: String,
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    pub groups: Vec<String>,
+}
+
+/// 搜索用户并以其 DN 绑定校验密码。
+pub async fn authenticate(config: &LdapConfig, username: &str, password: &str) -> Result<LdapIdentity, AppError> {
+    if password.is_empty() {
+        // 空密码会被多数服务器视为匿名绑定而“成功”。
+        return Err(AppError::auth("invalid credentials"));
+    }
+    let settings = LdapConnSettings::new().set_conn_timeout(Duration::from_secs(LDAP_TIMEOUT_SECONDS));
+    let (conn, mut ldap) = LdapConnAsync::with_settings(settings, &config.url)
+        .await
+        .map_err(|err| AppError::service_unavailable(&format!("ldap connect failed: {err}")))?;
+    ldap3::drive!(conn);
+
+    if let Some(bind_dn) = config.bind_dn.as_deref() {
+        ldap.simple_bind(bind_dn, config.bind_password.as_deref().unwrap_or_default())
+            .await
+            .and_then(|result| result.success())
+            .map_err(|err| AppError::service_unavailable(&format!("ldap service bind failed: {err}")))?;
+    }
+    let attributes = vec![
+        config.display_name_attribute.as_str(),
+        config.email_attribute.as_str(),
+        config.group_attribute.as_str(),
+    ];
+    let (entries, _) = ldap
+        .search(&config.base_dn, Scope::Subtree, &user_filter(config, username), attributes)
+        .await
+        .and_then(|result| result.success())
+        .map_err(|err| AppError::service_unavailable(&format!("ldap search failed: {err}")))?;
+    let mut entries = entries.into_iter().map(SearchEntry::construct);
+    let entry = match (entries.next(), entries.next()) {
+        (Some(entry), None) => entry,
+        _ => return Err(AppError::auth("invalid credentials")),
+    };
+
+    ldap.simple_bind(&entry.dn, password)
+        .await
+        .and_then(|result| result.success())
+        .map_err(|_| AppError::auth("invalid credentials"))?;
+    let _ = ldap.unbind().await;
+
+    let first = |attribute: &str| {
+        entry
+            .attrs
+            .get(attribute)
+            .and_then(|values| values.first())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    Ok(LdapIdentity {
+        display_name: first(&config.display_name_attribute),
+        email: first(&config.email_attribute),
+        groups: entry
+            .attrs
+            .get(&config.group_attribute)
+            .cloned()
+            .unwrap_or_default(),
+        dn: entry.dn.clone(),
+    })
+}
+
+/// 按配置顺序返回第一个匹配组对应的角色（DN 比较忽略大小写与空白）。
+pub fn role_for_groups<'a>(config: &'a LdapConfig, groups: &[String]) -> Option<&'a str> {
+    let groups: Vec<String> = groups.iter().map(|group| normalize_dn(group)).collect();
+    config
+        .role_groups
+        .iter()
+        .find(|mapping| groups.contains(&normalize_dn(&mapping.group)))
+        .map(|mapping| mapping.role.as_str())
+}
+
+/// 创建或更新本地教师/审核人员账号；不接管学生与管理员账号。
+pub async fn sync_local_user<C>(
+    db: &C,
+    username: &str,
+    identity: &LdapIdentity,
+    role: &str,
+) -> Result<users::Model, AppError>
+where
+    C: ConnectionTrait,
+{
+    let now = Utc::now();
+    let existing = User::find()
+        .filter(users::Column::Username.eq(username))
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if let Some(user) = existing {
+        if !user.is_active {
+            return Err(AppError::auth("user disabled"));
+        }
+        if !matches!(user.role.as_str(), "teacher" | "reviewer") {
+            return Err(AppError::auth("ldap login not allowed"));
+        }
+        let previous_role = user.role.clone();
+        let mut active: users::ActiveModel = user.into();
+        if let Some(display_name) = identity.display_name.clone() {
+            active.display_name = Set(display_name);
+        }
+        if let Some(email) = identity.email.clone() {
+            active.email = Set(Some(email));
+        }
+        active.role = Set(role.to_string());
+        active.updated_at = Set(now);
+        let updated = active
+            .update(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        if previous_role != role {
+            record_audit(
+                db,
+                Some(updated.id),
+                "ldap_role_sync",
+                "user",
+                Some(&updated.id.to_string()),
+                Some(serde_json::json!({ "from": previous_role, "to": role })),
+            )
+            .await?;
+        }
+        return Ok(updated);
+    }
+
+    let user_id = Uuid::new_v4();
+    let model = users::ActiveModel {
+        id: Set(user_id),
+        username: Set(username.to_string()),
+        display_name: Set(identity.display_name.clone().unwrap_or_else(|| username.to_string())),
+        role: Set(role.to_string()),
+        email: Set(identity.email.clone()),
+        password_hash: Set(None),
+        allow_password_login: Set(false),
+        password_updated_at: Set(None),
+        must_change_password: Set(false),
+        is_active: Set(true),
+        department: Set(None),
+        notify_review_email: Set(true),
+        locale: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+    users::Entity::insert(model)
+        .exec_without_returning(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        db,
+        Some(user_id),
+        "ldap_user_provision",
+        "user",
+        Some(&user_id.to_string()),
+        Some(serde_json::json!({ "username": username, "role": role, "dn": identity.dn })),
+    )
+    .await?;
+    User::find_by_id(user_id)
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::internal("provisioned user missing"))
+}
+
+fn user_filter(config: &LdapConfig, username: &str) -> String {
+    config.user_filter.replace("{username}", &ldap_escape(username))
+}
+
+fn normalize_dn(value: &str) -> String {
+    value
+        .split(',')
+        .map(|part| part.trim().to_lowercase())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LdapRoleGroup;
+
+    fn config() -> LdapConfig {
+        LdapConfig {
+            url: "ldap://ldap.example.edu".to_string(),
+            base_dn: "dc=example,dc=edu".to_string(),
+            bind_dn: None,
+            bind_password: None,
+            user_filter: "(&(objectClass=person)(uid={username}))".to_string(),
+            display_name_attribute: "displayName".to_string(),
+            email_attribute: "mail".to_string(),
+            group_attribute: "memberOf".to_string(),
+            role_groups: vec![
+                LdapRoleGroup {
+                    group: "cn=reviewers,ou=groups,dc=example,dc=edu".to_string(),
+                    role: "reviewer".to_string(),
+                },
+                LdapRoleGroup {
+                    group: "cn=teachers,ou=groups,dc=example,dc=edu".to_string(),
+                    role: "teacher".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn user_filter_escapes_username() {
+        assert_eq!(
+            user_filter(&config(), "t001*)(uid=*"),
+            "(&(objectClass=person)(uid=t001\\2a\\29\\28uid=\\2a))"
+        );
+    }
+
+    #[test]
+    fn role_for_groups_uses_first_configured_match() {
+        let config = config();
+        let groups = vec![
+            "CN=Teachers, OU=Groups, DC=example, DC=edu".to_string(),
+            "cn=reviewers,ou=groups,dc=example,dc=edu".to_string(),
+        ];
+        assert_eq!(role_for_groups(&config, &groups), Some("reviewer"));
+        assert_eq!(role_for_groups(&config, &groups[..1]), Some("teacher"));
+        assert_eq!(role_for_groups(&config, &["cn=staff,dc=example,dc=edu".to_string()]), None);
+    }
+}
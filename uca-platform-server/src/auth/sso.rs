@@ -0,0 +1,346 @@
+//! 统一身份认证（CAS/OAuth2）：构造登录地址、校验回调并映射本地账号。
+
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use serde::Deserialize;
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    audit::record_audit,
+    config::{SsoConfig, SsoProvider},
+    entities::{students, users, Student, User},
+    error::AppError,
+};
+
+/// 访问身份提供方的超时时间。
+const SSO_HTTP_TIMEOUT_SECONDS: u64 = 10;
+
+/// 回调参数（CAS 返回 `ticket`，OAuth2 返回 `code`）。
+#[derive(Debug, Deserialize)]
+pub struct SsoCallbackQuery {
+    pub state: Option<String>,
+    pub ticket: Option<String>,
+    pub code: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 构造跳转到身份提供方的登录地址。
+pub fn login_url(config: &SsoConfig, state: &str) -> Result<Url, AppError> {
+    match config.provider {
+        SsoProvider::Cas => {
+            let mut url = endpoint(required_url(&config.cas_url, "cas_url")?, "login")?;
+            url.query_pairs_mut()
+                .append_pair("service", cas_service_url(config, state).as_str());
+            Ok(url)
+        }
+        SsoProvider::Oauth2 => {
+            let mut url = required_url(&config.authorize_url, "authorize_url")?.clone();
+            url.query_pairs_mut()
+                .append_pair("response_type", "code")
+                .append_pair("client_id", required_text(&config.client_id, "client_id")?)
+                .append_pair("redirect_uri", config.callback_url.as_str())
+                .append_pair("scope", &config.scope)
+                .append_pair("state", state);
+            Ok(url)
+        }
+    }
+}
+
+/// 校验回调并返回身份提供方中的用户名。
+pub async fn resolve_username(
+    config: &SsoConfig,
+    state: &str,
+    query: &SsoCallbackQuery,
+) -> Result<String, AppError> {
+    if let Some(error) = query.error.as_deref() {
+        return Err(AppError::auth(&format!("sso login failed: {error}")));
+    }
+    match config.provider {
+        SsoProvider::Cas => {
+            let ticket = query
+                .ticket
+                .as_deref()
+                .ok_or_else(|| AppError::bad_request("missing ticket"))?;
+            validate_cas_ticket(config, state, ticket).await
+        }
+        SsoProvider::Oauth2 => {
+            let code = query
+                .code
+                .as_deref()
+                .ok_or_else(|| AppError::bad_request("missing code"))?;
+            exchange_oauth2_code(config, code).await
+        }
+    }
+}
+
+/// 按用户名查找本地账号；不存在时可为学生名单中的学号自动创建学生账号。
+///
+/// 教职工与管理员账号登录需 Passkey/TOTP，未开启 `allow_staff` 时不允许经 SSO 登录。
+pub async fn resolve_local_user<C>(
+    db: &C,
+    username: &str,
+    auto_provision_students: bool,
+    allow_staff: bool,
+) -> Result<users::Model, AppError>
+where
+    C: ConnectionTrait,
+{
+    let existing = User::find()
+        .filter(users::Column::Username.eq(username))
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if let Some(user) = existing {
+        if !user.is_active {
+            return Err(AppError::auth("user disabled"));
+        }
+        if user.role != "student" && !allow_staff {
+            return Err(AppError::auth("sso login is limited to student accounts"));
+        }
+        return Ok(user);
+    }
+    if !auto_provision_students {
+        return Err(AppError::auth("user not provisioned"));
+    }
+    let student = Student::find()
+        .filter(students::Column::StudentNo.eq(username))
+        .filter(students::Column::IsDeleted.eq(false))
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::auth("user not provisioned"))?;
+
+    let now = Utc::now();
+    let user_id = Uuid::new_v4();
+    let model = users::ActiveModel {
+        id: Set(user_id),
+        username: Set(student.student_no.clone()),
+        display_name: Set(student.name.clone()),
+        role: Set("student".to_string()),
+        email: Set(None),
+        password_hash: Set(None),
+        allow_password_login: Set(false),
+        password_updated_at: Set(None),
+        must_change_password: Set(false),
+        is_active: Set(true),
+        department: Set(None),
+        notify_review_email: Set(true),
+        locale: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+    users::Entity::insert(model)
+        .exec_without_returning(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        db,
+        Some(user_id),
+        "sso_user_provision",
+        "user",
+        Some(&user_id.to_string()),
+        Some(serde_json::json!({ "username": student.student_no })),
+    )
+    .await?;
+    User::find_by_id(user_id)
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::internal("provisioned user missing"))
+}
+
+async fn validate_cas_ticket(config: &SsoConfig, state: &str, ticket: &str) -> Result<String, AppError> {
+    let mut url = endpoint(required_url(&config.cas_url, "cas_url")?, "serviceValidate")?;
+    url.query_pairs_mut()
+        .append_pair("service", cas_service_url(config, state).as_str())
+        .append_pair("ticket", ticket);
+    let body = http_client()?
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| AppError::service_unavailable(&format!("cas validation failed: {err}")))?
+        .text()
+        .await
+        .map_err(|err| AppError::service_unavailable(&format!("cas validation failed: {err}")))?;
+    parse_cas_response(&body)
+}
+
+async fn exchange_oauth2_code(config: &SsoConfig, code: &str) -> Result<String, AppError> {
+    let client = http_client()?;
+    let unavailable = |err: reqwest::Error| AppError::service_unavailable(&format!("oauth2 login failed: {err}"));
+    let token: serde_json::Value = client
+        .post(required_url(&config.token_url, "token_url")?.clone())
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", config.callback_url.as_str()),
+            ("client_id", required_text(&config.client_id, "client_id")?),
+            ("client_secret", required_text(&config.client_secret, "client_secret")?),
+        ])
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(unavailable)?
+        .json()
+        .await
+        .map_err(unavailable)?;
+    let access_token = token
+        .get("access_token")
+        .and_then(|value| value.as_str())
+        .ok_or_else(|| AppError::auth("oauth2 token response missing access_token"))?;
+    let userinfo: serde_json::Value = client
+        .get(required_url(&config.userinfo_url, "userinfo_url")?.clone())
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(unavailable)?
+        .json()
+        .await
+        .map_err(unavailable)?;
+    extract_claim(&userinfo, &config.username_claim)
+        .ok_or_else(|| AppError::auth("oauth2 userinfo missing username claim"))
+}
+
+/// CAS 的 service 参数：回调地址附带 state，校验票据时须与登录时完全一致。
+fn cas_service_url(config: &SsoConfig, state: &str) -> Url {
+    let mut url = config.callback_url.clone();
+    url.query_pairs_mut().append_pair("state", state);
+    url
+}
+
+/// 解析 CAS `serviceValidate` 响应中的用户名。
+fn parse_cas_response(body: &str) -> Result<String, AppError> {
+    if body.contains("authenticationFailure") {
+        return Err(AppError::auth("cas ticket rejected"));
+    }
+    let start = body
+        .find("<cas:user>")
+        .map(|index| index + "<cas:user>".len())
+        .ok_or_else(|| AppError::auth("cas response missing user"))?;
+    let end = body[start..]
+        .find("</cas:user>")
+        .ok_or_else(|| AppError::auth("cas response missing user"))?;
+    let username = body[start..start + end].trim();
+    if username.is_empty() {
+        return Err(AppError::auth("cas response missing user"));
+    }
+    Ok(username.to_string())
+}
+
+/// 读取用户信息中的用户名字段（支持字符串与数字）。
+fn extract_claim(userinfo: &serde_json::Value, claim: &str) -> Option<String> {
+    let value = userinfo.get(claim)?;
+    let text = match value {
+        serde_json::Value::String(text) => text.trim().to_string(),
+        serde_json::Value::Number(number) => number.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+fn endpoint(base: &Url, segment: &str) -> Result<Url, AppError> {
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .map_err(|_| AppError::config("invalid sso url"))?
+        .pop_if_empty()
+        .push(segment);
+    Ok(url)
+}
+
+fn required_url<'a>(value: &'a Option<Url>, key: &str) -> Result<&'a Url, AppError> {
+    value
+        .as_ref()
+        .ok_or_else(|| AppError::config(&format!("sso {key} is not configured")))
+}
+
+fn required_text<'a>(value: &'a Option<String>, key: &str) -> Result<&'a str, AppError> {
+    value
+        .as_deref()
+        .ok_or_else(|| AppError::config(&format!("sso {key} is not configured")))
+}
+
+fn http_client() -> Result<reqwest::Client, AppError> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(SSO_HTTP_TIMEOUT_SECONDS))
+        .build()
+        .map_err(|err| AppError::internal(&format!("build http client failed: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cas_config() -> SsoConfig {
+        SsoConfig {
+            provider: SsoProvider::Cas,
+            callback_url: "https://labor.example.edu/api/auth/sso/callback".parse().unwrap(),
+            cas_url: Some("https://cas.example.edu/cas".parse().unwrap()),
+            authorize_url: None,
+            token_url: None,
+            userinfo_url: None,
+            client_id: None,
+            client_secret: None,
+            scope: "openid profile".to_string(),
+            username_claim: "preferred_username".to_string(),
+            auto_provision_students: true,
+            allow_staff: false,
+        }
+    }
+
+    #[test]
+    fn cas_login_url_embeds_service_with_state() {
+        let url = login_url(&cas_config(), "abc").unwrap();
+        assert_eq!(url.path(), "/cas/login");
+        let service = url
+            .query_pairs()
+            .find(|(key, _)| key == "service")
+            .map(|(_, value)| value.into_owned())
+            .unwrap();
+        assert_eq!(service, "https://labor.example.edu/api/auth/sso/callback?state=abc");
+    }
+
+    #[test]
+    fn oauth2_login_url_includes_client_and_state() {
+        let config = SsoConfig {
+            provider: SsoProvider::Oauth2,
+            cas_url: None,
+            authorize_url: Some("https://idp.example.edu/oauth2/authorize".parse().unwrap()),
+            token_url: Some("https://idp.example.edu/oauth2/token".parse().unwrap()),
+            userinfo_url: Some("https://idp.example.edu/oauth2/userinfo".parse().unwrap()),
+            client_id: Some("labor".to_string()),
+            client_secret: Some("secret".to_string()),
+            ..cas_config()
+        };
+        let url = login_url(&config, "xyz").unwrap();
+        let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert!(pairs.contains(&("response_type".to_string(), "code".to_string())));
+        assert!(pairs.contains(&("client_id".to_string(), "labor".to_string())));
+        assert!(pairs.contains(&("state".to_string(), "xyz".to_string())));
+        assert!(!url.as_str().contains("secret"));
+    }
+
+    #[test]
+    fn parse_cas_response_reads_user() {
+        let body = "<cas:serviceResponse xmlns:cas='http://www.yale.edu/tp/cas'>\
+            <cas:authenticationSuccess><cas:user> 2023001 </cas:user></cas:authenticationSuccess>\
+            </cas:serviceResponse>";
+        assert_eq!(parse_cas_response(body).unwrap(), "2023001");
+        let failure = "<cas:serviceResponse><cas:authenticationFailure code='INVALID_TICKET'>\
+            </cas:authenticationFailure></cas:serviceResponse>";
+        assert!(parse_cas_response(failure).is_err());
+    }
+
+    #[test]
+    fn extract_claim_accepts_strings_and_numbers() {
+        let userinfo = serde_json::json!({"preferred_username": "t001", "uid": 2023001, "name": ""});
+        assert_eq!(extract_claim(&userinfo, "preferred_username").as_deref(), Some("t001"));
+        assert_eq!(extract_claim(&userinfo, "uid").as_deref(), Some("2023001"));
+        assert_eq!(extract_claim(&userinfo, "name"), None);
+        assert_eq!(extract_claim(&userinfo, "missing"), None);
+    }
+}
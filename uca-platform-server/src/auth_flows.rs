@@ -0,0 +1,305 @@
+//! 认证流程状态存储：Passkey 注册/认证挑战、Passkey 重新验证、二次验证令牌与统一身份认证的 `state`。
+//!
+//! 状态以 JSON 保存并带有过期时间，写入与取出时顺带清理过期项，后台任务定期清扫，
+//! 避免客户端放弃流程后无限增长。默认存于进程内存；`AUTH_FLOW_STORE=database` 时写入
+//! `auth_flow_states` 表，多实例部署可共享进行中的流程，服务重启也不会中断。
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::{
+    config::AuthFlowStoreKind,
+    entities::{auth_flow_states, AuthFlowState},
+    error::AppError,
+    state::{
+        PasskeyAuthSession, PasskeyDiscoverableSession, PasskeyRegisterSession, ReauthSession, SsoLoginSession,
+    },
+};
+
+/// Passkey 挑战的有效期。
+pub const CHALLENGE_TTL_SECONDS: i64 = 300;
+/// 二次验证令牌有效期。
+pub const REAUTH_TTL_SECONDS: i64 = 300;
+
+const KIND_PASSKEY_REGISTER: &str = "passkey_register";
+const KIND_PASSKEY_AUTH: &str = "passkey_auth";
+const KIND_PASSKEY_DISCOVERABLE: &str = "passkey_discoverable";
+const KIND_REAUTH_PASSKEY: &str = "reauth_passkey";
+const KIND_REAUTH_TOKEN: &str = "reauth_token";
+const KIND_SSO_LOGIN: &str = "sso_login";
+
+#[derive(Debug)]
+struct MemoryEntry {
+    payload: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// 认证流程状态存储，取出即删除，每个会话只能使用一次。
+pub struct AuthFlowStore {
+    kind: AuthFlowStoreKind,
+    db: DatabaseConnection,
+    memory: Mutex<HashMap<String, MemoryEntry>>,
+}
+
+impl AuthFlowStore {
+    /// 按配置的存储位置创建。
+    pub fn new(kind: AuthFlowStoreKind, db: DatabaseConnection) -> Self {
+        Self {
+            kind,
+            db,
+            memory: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 写入 Passkey 注册会话。
+    pub async fn insert_register(
+        &self,
+        session_id: Uuid,
+        session: &PasskeyRegisterSession,
+    ) -> Result<(), AppError> {
+        self.put(KIND_PASSKEY_REGISTER, &session_id.to_string(), CHALLENGE_TTL_SECONDS, session)
+            .await
+    }
+
+    /// 取出并移除有效的 Passkey 注册会话。
+    pub async fn take_register(
+        &self,
+        session_id: &Uuid,
+    ) -> Result<Option<PasskeyRegisterSession>, AppError> {
+        self.take(KIND_PASSKEY_REGISTER, &session_id.to_string()).await
+    }
+
+    /// 写入 Passkey 认证会话。
+    pub async fn insert_auth(&self, session_id: Uuid, session: &PasskeyAuthSession) -> Result<(), AppError> {
+        self.put(KIND_PASSKEY_AUTH, &session_id.to_string(), CHALLENGE_TTL_SECONDS, session)
+            .await
+    }
+
+    /// 取出并移除有效的 Passkey 认证会话。
+    pub async fn take_auth(&self, session_id: &Uuid) -> Result<Option<PasskeyAuthSession>, AppError> {
+        self.take(KIND_PASSKEY_AUTH, &session_id.to_string()).await
+    }
+
+    /// 写入可发现凭据认证会话。
+    pub async fn insert_discoverable(
+        &self,
+        session_id: Uuid,
+        session: &PasskeyDiscoverableSession,
+    ) -> Result<(), AppError> {
+        self.put(KIND_PASSKEY_DISCOVERABLE, &session_id.to_string(), CHALLENGE_TTL_SECONDS, session)
+            .await
+    }
+
+    /// 取出并移除有效的可发现凭据认证会话。
+    pub async fn take_discoverable(
+        &self,
+        session_id: &Uuid,
+    ) -> Result<Option<PasskeyDiscoverableSession>, AppError> {
+        self.take(KIND_PASSKEY_DISCOVERABLE, &session_id.to_string()).await
+    }
+
+    /// 写入 Passkey 重新验证会话。
+    pub async fn insert_reauth_passkey(
+        &self,
+        session_id: Uuid,
+        session: &PasskeyAuthSession,
+    ) -> Result<(), AppError> {
+        self.put(KIND_REAUTH_PASSKEY, &session_id.to_string(), CHALLENGE_TTL_SECONDS, session)
+            .await
+    }
+
+    /// 取出并移除有效的 Passkey 重新验证会话。
+    pub async fn take_reauth_passkey(
+        &self,
+        session_id: &Uuid,
+    ) -> Result<Option<PasskeyAuthSession>, AppError> {
+        self.take(KIND_REAUTH_PASSKEY, &session_id.to_string()).await
+    }
+
+    /// 写入二次验证令牌。
+    pub async fn insert_reauth_token(&self, token: &str, session: &ReauthSession) -> Result<(), AppError> {
+        self.put(KIND_REAUTH_TOKEN, token, REAUTH_TTL_SECONDS, session).await
+    }
+
+    /// 取出并移除有效的二次验证令牌。
+    pub async fn take_reauth_token(&self, token: &str) -> Result<Option<ReauthSession>, AppError> {
+        self.take(KIND_REAUTH_TOKEN, token).await
+    }
+
+    /// 写入统一身份认证登录状态。
+    pub async fn insert_sso_login(&self, state: &str, session: &SsoLoginSession) -> Result<(), AppError> {
+        self.put(KIND_SSO_LOGIN, state, CHALLENGE_TTL_SECONDS, session).await
+    }
+
+    /// 取出并移除有效的统一身份认证登录状态。
+    pub async fn take_sso_login(&self, state: &str) -> Result<Option<SsoLoginSession>, AppError> {
+        self.take(KIND_SSO_LOGIN, state).await
+    }
+
+    /// 删除已过期的流程状态，返回删除数量。
+    pub async fn purge_expired(&self) -> Result<u64, AppError> {
+        let now = Utc::now();
+        match self.kind {
+            AuthFlowStoreKind::Memory => Ok(evict_expired(&mut *self.memory.lock().await, now)),
+            AuthFlowStoreKind::Database => {
+                let result = AuthFlowState::delete_many()
+                    .filter(auth_flow_states::Column::ExpiresAt.lte(now))
+                    .exec(&self.db)
+                    .await
+                    .map_err(|err| AppError::Database(err.to_string()))?;
+                Ok(result.rows_affected)
+            }
+        }
+    }
+
+    async fn put<T: Serialize>(
+        &self,
+        kind: &str,
+        key: &str,
+        ttl_seconds: i64,
+        value: &T,
+    ) -> Result<(), AppError> {
+        let payload = serde_json::to_string(value)
+            .map_err(|_| AppError::internal("failed to serialize auth flow state"))?;
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(ttl_seconds);
+        let id = flow_id(kind, key);
+        match self.kind {
+            AuthFlowStoreKind::Memory => {
+                let mut memory = self.memory.lock().await;
+                evict_expired(&mut memory, now);
+                memory.insert(id, MemoryEntry { payload, expires_at });
+            }
+            AuthFlowStoreKind::Database => {
+                AuthFlowState::insert(auth_flow_states::ActiveModel {
+                    id: Set(id),
+                    kind: Set(kind.to_string()),
+                    payload: Set(payload),
+                    expires_at: Set(expires_at),
+                    created_at: Set(now),
+                })
+                .exec_without_returning(&self.db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn take<T: DeserializeOwned>(&self, kind: &str, key: &str) -> Result<Option<T>, AppError> {
+        let now = Utc::now();
+        let id = flow_id(kind, key);
+        let entry = match self.kind {
+            AuthFlowStoreKind::Memory => {
+                let mut memory = self.memory.lock().await;
+                evict_expired(&mut memory, now);
+                memory.remove(&id).map(|entry| entry.payload)
+            }
+            AuthFlowStoreKind::Database => {
+                let Some(row) = AuthFlowState::find_by_id(id.clone())
+                    .one(&self.db)
+                    .await
+                    .map_err(|err| AppError::Database(err.to_string()))?
+                else {
+                    return Ok(None);
+                };
+                // 以删除成功为准，避免多个实例同时取出同一会话。
+                let deleted = AuthFlowState::delete_by_id(id)
+                    .exec(&self.db)
+                    .await
+                    .map_err(|err| AppError::Database(err.to_string()))?;
+                (deleted.rows_affected == 1 && row.expires_at > now).then_some(row.payload)
+            }
+        };
+        entry
+            .map(|payload| {
+                serde_json::from_str(&payload)
+                    .map_err(|_| AppError::internal("failed to parse auth flow state"))
+            })
+            .transpose()
+    }
+}
+
+fn flow_id(kind: &str, key: &str) -> String {
+    format!("{kind}:{key}")
+}
+
+fn evict_expired(memory: &mut HashMap<String, MemoryEntry>, now: DateTime<Utc>) -> u64 {
+    let before = memory.len();
+    memory.retain(|_, entry| entry.expires_at > now);
+    (before - memory.len()) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_support::memory_db;
+    use time::OffsetDateTime;
+
+    fn reauth_session(user_id: Uuid) -> ReauthSession {
+        ReauthSession {
+            user_id,
+            created_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    #[test]
+    fn evict_removes_only_expired_entries() {
+        let now = Utc::now();
+        let mut memory = HashMap::from([
+            (
+                "reauth_token:a".to_string(),
+                MemoryEntry {
+                    payload: "{}".to_string(),
+                    expires_at: now - Duration::seconds(1),
+                },
+            ),
+            (
+                "reauth_token:b".to_string(),
+                MemoryEntry {
+                    payload: "{}".to_string(),
+                    expires_at: now + Duration::seconds(60),
+                },
+            ),
+        ]);
+        assert_eq!(evict_expired(&mut memory, now), 1);
+        assert!(memory.contains_key("reauth_token:b"));
+    }
+
+    #[tokio::test]
+    async fn tokens_are_single_use_in_both_backends() {
+        for kind in [AuthFlowStoreKind::Memory, AuthFlowStoreKind::Database] {
+            let store = AuthFlowStore::new(kind, memory_db().await);
+            let user_id = Uuid::new_v4();
+            store
+                .insert_reauth_token("token", &reauth_session(user_id))
+                .await
+                .unwrap();
+            let session = store.take_reauth_token("token").await.unwrap();
+            assert_eq!(session.map(|item| item.user_id), Some(user_id));
+            assert!(store.take_reauth_token("token").await.unwrap().is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn purge_drops_expired_database_rows() {
+        let db = memory_db().await;
+        let store = AuthFlowStore::new(AuthFlowStoreKind::Database, db.clone());
+        store
+            .put(KIND_REAUTH_TOKEN, "stale", -1, &reauth_session(Uuid::new_v4()))
+            .await
+            .unwrap();
+        store
+            .insert_reauth_token("fresh", &reauth_session(Uuid::new_v4()))
+            .await
+            .unwrap();
+        assert_eq!(store.purge_expired().await.unwrap(), 1);
+        assert!(store.take_reauth_token("stale").await.unwrap().is_none());
+        assert!(store.take_reauth_token("fresh").await.unwrap().is_some());
+    }
+}
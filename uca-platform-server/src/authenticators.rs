@@ -0,0 +1,227 @@
+//! Passkey 认证器识别：从注册时的 attestation 中解析 AAGUID，并映射为可读的认证器名称。
+//!
+//! 名称表取自 FIDO MDS 与社区维护的 passkey AAGUID 列表中的常见条目。
+
+use uuid::Uuid;
+
+/// 未识别认证器时的默认设备标签。
+pub const DEFAULT_PASSKEY_LABEL: &str = "Passkey";
+
+/// 设备标签最大长度（字符）。
+pub const MAX_DEVICE_LABEL_CHARS: usize = 64;
+
+/// CBOR 嵌套解析深度上限。
+const MAX_CBOR_DEPTH: usize = 16;
+
+/// authenticatorData 中 attestedCredentialData 存在标志（AT）。
+const FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+
+/// 常见认证器 AAGUID 与名称。
+const KNOWN_AUTHENTICATORS: &[(&str, &str)] = &[
+    ("ea9b8d66-4d01-1d21-3ce4-b6b48cb575d4", "Google Password Manager"),
+    ("adce0002-35bc-c60a-648b-0b25f1f05503", "Chrome on Mac"),
+    ("b5397666-4885-aa6b-cebf-e52262a439a2", "Chromium Browser"),
+    ("771b48fd-d3d4-4f74-9232-fc157ab0507a", "Edge on Mac"),
+    ("08987058-cadc-4b81-b6e1-30de50dcbe96", "Windows Hello"),
+    ("9ddd1817-af5a-4672-a2b9-3e3dd95000a9", "Windows Hello"),
+    ("6028b017-b1d4-4c02-b4b3-afcdafc96bb2", "Windows Hello"),
+    ("fbfc3007-154e-4ecc-8c0b-6e020557d7bd", "iCloud Keychain"),
+    ("dd4ec289-e01d-41c9-bb89-70fa845d4bf2", "iCloud Keychain (Managed)"),
+    ("53414d53-554e-4700-0000-000000000000", "Samsung Pass"),
+    ("bada5566-a7aa-401f-bd96-45619a55120d", "1Password"),
+    ("d548826e-79b4-db40-a3d8-11116f7e8349", "Bitwarden"),
+    ("531126d6-e717-415c-9320-3d9aa6981239", "Dashlane"),
+    ("cb69481e-8ff7-4039-93ec-0a2729a154a8", "YubiKey 5 Series"),
+    ("ee882879-721c-4913-9775-3dfcce97072a", "YubiKey 5 Series"),
+    ("fa2b99dc-9e39-4257-8f92-4a30d23c4118", "YubiKey 5 Series with NFC"),
+    ("2fc0579f-8113-47ea-b116-bb5a8db9202a", "YubiKey 5 Series with NFC"),
+    ("c5ef55ff-ad9a-4b9f-b580-adebafe026d0", "YubiKey 5Ci"),
+    ("73bb0cd4-e502-49b8-9c6f-b59445bf720b", "YubiKey 5 FIPS Series"),
+    ("149a2021-8ef6-4133-96b8-81f8d5b7f1f5", "Security Key by Yubico with NFC"),
+    ("a4e9fc6d-4cbe-4758-b8ba-37598bb5bbaa", "Security Key NFC by Yubico"),
+    ("f8a011f3-8c0a-4d15-8006-17111f9edc7d", "Security Key by Yubico"),
+];
+
+/// 按 AAGUID 查找认证器名称。
+pub fn authenticator_name(aaguid: &Uuid) -> Option<&'static str> {
+    let key = aaguid.hyphenated().to_string();
+    KNOWN_AUTHENTICATORS
+        .iter()
+        .find(|(id, _)| *id == key)
+        .map(|(_, name)| *name)
+}
+
+/// 未指定标签时的设备标签：已识别的认证器名称，否则为 `Passkey`。
+pub fn default_passkey_label(aaguid: Option<&Uuid>) -> String {
+    aaguid
+        .and_then(authenticator_name)
+        .unwrap_or(DEFAULT_PASSKEY_LABEL)
+        .to_string()
+}
+
+/// 规范化用户输入的设备标签：去除首尾空白，不允许为空或超长。
+pub fn normalize_device_label(label: &str) -> Option<String> {
+    let trimmed = label.trim();
+    if trimmed.is_empty() || trimmed.chars().count() > MAX_DEVICE_LABEL_CHARS {
+        return None;
+    }
+    Some(trimmed.to_string())
+}
+
+/// 从 attestationObject（CBOR）中取出 authenticatorData 并读取 AAGUID；全零视为未提供。
+pub fn aaguid_from_attestation(attestation_object: &[u8]) -> Option<Uuid> {
+    let auth_data = find_auth_data(attestation_object)?;
+    aaguid_from_auth_data(auth_data)
+}
+
+fn aaguid_from_auth_data(auth_data: &[u8]) -> Option<Uuid> {
+    // rpIdHash(32) + flags(1) + signCount(4) + aaguid(16)
+    let flags = *auth_data.get(32)?;
+    if flags & FLAG_ATTESTED_CREDENTIAL_DATA == 0 {
+        return None;
+    }
+    let bytes: [u8; 16] = auth_data.get(37..53)?.try_into().ok()?;
+    let aaguid = Uuid::from_bytes(bytes);
+    (!aaguid.is_nil()).then_some(aaguid)
+}
+
+fn find_auth_data(attestation_object: &[u8]) -> Option<&[u8]> {
+    let mut reader = CborReader {
+        data: attestation_object,
+        pos: 0,
+    };
+    let (major, entries) = reader.header()?;
+    if major != 5 {
+        return None;
+    }
+    for _ in 0..entries {
+        let (key_major, key_len) = reader.header()?;
+        if key_major != 3 {
+            reader.skip_payload(key_major, key_len, 0)?;
+            reader.skip(0)?;
+            continue;
+        }
+        let key = reader.take(key_len)?;
+        if key == b"authData" {
+            let (value_major, value_len) = reader.header()?;
+            return (value_major == 2).then(|| reader.take(value_len)).flatten();
+        }
+        reader.skip(0)?;
+    }
+    None
+}
+
+/// 仅支持定长编码的最小 CBOR 读取器。
+struct CborReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> CborReader<'a> {
+    fn header(&mut self) -> Option<(u8, u64)> {
+        let initial = *self.data.get(self.pos)?;
+        self.pos += 1;
+        let major = initial >> 5;
+        let value = match initial & 0x1f {
+            small @ 0..=23 => u64::from(small),
+            24 => u64::from(self.take(1)?[0]),
+            25 => u64::from(u16::from_be_bytes(self.take(2)?.try_into().ok()?)),
+            26 => u64::from(u32::from_be_bytes(self.take(4)?.try_into().ok()?)),
+            27 => u64::from_be_bytes(self.take(8)?.try_into().ok()?),
+            _ => return None,
+        };
+        Some((major, value))
+    }
+
+    fn take(&mut self, len: u64) -> Option<&'a [u8]> {
+        let len = usize::try_from(len).ok()?;
+        let end = self.pos.checked_add(len)?;
+        let bytes = self.data.get(self.pos..end)?;
+        self.pos = end;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, depth: usize) -> Option<()> {
+        let (major, value) = self.header()?;
+        self.skip_payload(major, value, depth)
+    }
+
+    fn skip_payload(&mut self, major: u8, value: u64, depth: usize) -> Option<()> {
+        if depth > MAX_CBOR_DEPTH {
+            return None;
+        }
+        match major {
+            0 | 1 | 7 => Some(()),
+            2 | 3 => self.take(value).map(|_| ()),
+            4 => (0..value).try_for_each(|_| self.skip(depth + 1)),
+            5 => (0..value.checked_mul(2)?).try_for_each(|_| self.skip(depth + 1)),
+            6 => self.skip(depth + 1),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YUBIKEY_5: &str = "cb69481e-8ff7-4039-93ec-0a2729a154a8";
+
+    fn auth_data(aaguid: &Uuid, flags: u8) -> Vec<u8> {
+        let mut data = vec![0xAA; 32];
+        data.push(flags);
+        data.extend_from_slice(&[0, 0, 0, 1]);
+        data.extend_from_slice(aaguid.as_bytes());
+        data.extend_from_slice(&[0x00, 0x02, 0x01, 0x02]);
+        data
+    }
+
+    /// 构造 `{"fmt": "packed", "attStmt": {"alg": -7, "x5c": [h'00']}, "authData": h'..'}`。
+    fn attestation(auth_data: &[u8]) -> Vec<u8> {
+        let mut out = vec![0xA3];
+        out.push(0x63);
+        out.extend_from_slice(b"fmt");
+        out.push(0x66);
+        out.extend_from_slice(b"packed");
+        out.push(0x67);
+        out.extend_from_slice(b"attStmt");
+        out.extend_from_slice(&[0xA2, 0x63]);
+        out.extend_from_slice(b"alg");
+        out.push(0x26);
+        out.push(0x63);
+        out.extend_from_slice(b"x5c");
+        out.extend_from_slice(&[0x81, 0x41, 0x00]);
+        out.push(0x68);
+        out.extend_from_slice(b"authData");
+        out.push(0x58);
+        out.push(auth_data.len() as u8);
+        out.extend_from_slice(auth_data);
+        out
+    }
+
+    #[test]
+    fn aaguid_is_read_from_attestation_object() {
+        let aaguid = Uuid::parse_str(YUBIKEY_5).unwrap();
+        let object = attestation(&auth_data(&aaguid, 0x45));
+        assert_eq!(aaguid_from_attestation(&object), Some(aaguid));
+        assert_eq!(default_passkey_label(Some(&aaguid)), "YubiKey 5 Series");
+    }
+
+    #[test]
+    fn missing_or_zero_aaguid_falls_back_to_default_label() {
+        let zero = attestation(&auth_data(&Uuid::nil(), 0x45));
+        assert_eq!(aaguid_from_attestation(&zero), None);
+        let no_credential = attestation(&auth_data(&Uuid::new_v4(), 0x05));
+        assert_eq!(aaguid_from_attestation(&no_credential), None);
+        assert_eq!(aaguid_from_attestation(&[0xA1, 0x63, b'f']), None);
+        assert_eq!(default_passkey_label(Some(&Uuid::new_v4())), DEFAULT_PASSKEY_LABEL);
+        assert_eq!(default_passkey_label(None), DEFAULT_PASSKEY_LABEL);
+    }
+
+    #[test]
+    fn device_labels_are_trimmed_and_bounded() {
+        assert_eq!(normalize_device_label("  办公室电脑 "), Some("办公室电脑".to_string()));
+        assert_eq!(normalize_device_label("   "), None);
+        assert_eq!(normalize_device_label(&"键".repeat(MAX_DEVICE_LABEL_CHARS + 1)), None);
+    }
+}
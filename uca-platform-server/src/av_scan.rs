@@ -0,0 +1,144 @@
+//! 附件病毒扫描：通过 clamd 的 INSTREAM 协议（TCP）扫描上传内容。
+
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::{config::ClamavConfig, error::AppError};
+
+/// 每个 INSTREAM 数据块的大小。
+const CHUNK_BYTES: usize = 64 * 1024;
+
+/// 扫描结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    /// 未发现威胁。
+    Clean,
+    /// 发现威胁，附带病毒特征名称。
+    Infected(String),
+}
+
+/// 将内容发送给 clamd 扫描；连接失败、超时或 clamd 报错时返回 503。
+pub async fn scan_bytes(config: &ClamavConfig, bytes: &[u8]) -> Result<ScanVerdict, AppError> {
+    let timeout = Duration::from_secs(config.timeout_seconds);
+    match tokio::time::timeout(timeout, instream(&config.address, bytes)).await {
+        Ok(Ok(reply)) => parse_reply(&reply),
+        Ok(Err(err)) => {
+            tracing::warn!(address = %config.address, error = %err, "clamd scan failed");
+            Err(AppError::service_unavailable("virus scanner unavailable"))
+        }
+        Err(_) => {
+            tracing::warn!(address = %config.address, "clamd scan timed out");
+            Err(AppError::service_unavailable("virus scan timed out"))
+        }
+    }
+}
+
+async fn instream(address: &str, bytes: &[u8]) -> std::io::Result<String> {
+    let mut stream = TcpStream::connect(address).await?;
+    stream.write_all(b"zINSTREAM\0").await?;
+    for chunk in bytes.chunks(CHUNK_BYTES) {
+        stream.write_all(&(chunk.len() as u32).to_be_bytes()).await?;
+        stream.write_all(chunk).await?;
+    }
+    stream.write_all(&0u32.to_be_bytes()).await?;
+    stream.flush().await?;
+    let mut reply = Vec::new();
+    stream.read_to_end(&mut reply).await?;
+    Ok(String::from_utf8_lossy(&reply).into_owned())
+}
+
+/// 解析 clamd 应答，如 `stream: OK`、`stream: Eicar-Signature FOUND`。
+fn parse_reply(reply: &str) -> Result<ScanVerdict, AppError> {
+    let reply = reply.trim_end_matches(['\0', '\n', '\r']).trim();
+    let result = reply.split_once(": ").map(|(_, value)| value).unwrap_or(reply);
+    if result == "OK" {
+        return Ok(ScanVerdict::Clean);
+    }
+    if let Some(signature) = result.strip_suffix(" FOUND") {
+        return Ok(ScanVerdict::Infected(signature.trim().to_string()));
+    }
+    tracing::warn!(reply, "unexpected clamd reply");
+    Err(AppError::service_unavailable("virus scanner error"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn parse_reply_handles_clean_infected_and_errors() {
+        assert_eq!(parse_reply("stream: OK\0").unwrap(), ScanVerdict::Clean);
+        assert_eq!(
+            parse_reply("stream: Win.Test.EICAR_HDB-1 FOUND\0").unwrap(),
+            ScanVerdict::Infected("Win.Test.EICAR_HDB-1".to_string())
+        );
+        assert!(parse_reply("INSTREAM size limit exceeded. ERROR\0").is_err());
+    }
+
+    /// 模拟 clamd：读取完整的 INSTREAM 数据后，内容含 `EICAR` 时报告感染。
+    async fn fake_clamd() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut command = [0u8; 10];
+                socket.read_exact(&mut command).await.unwrap();
+                assert_eq!(&command, b"zINSTREAM\0");
+                let mut content = Vec::new();
+                loop {
+                    let mut size = [0u8; 4];
+                    socket.read_exact(&mut size).await.unwrap();
+                    let size = u32::from_be_bytes(size) as usize;
+                    if size == 0 {
+                        break;
+                    }
+                    let mut chunk = vec![0u8; size];
+                    socket.read_exact(&mut chunk).await.unwrap();
+                    content.extend_from_slice(&chunk);
+                }
+                let reply: &[u8] = if content.windows(5).any(|window| window == b"EICAR") {
+                    b"stream: Eicar-Signature FOUND\0"
+                } else {
+                    b"stream: OK\0"
+                };
+                socket.write_all(reply).await.unwrap();
+            }
+        });
+        address
+    }
+
+    #[tokio::test]
+    async fn scan_bytes_streams_content_to_clamd() {
+        let config = ClamavConfig {
+            address: fake_clamd().await,
+            timeout_seconds: 5,
+            fail_open: false,
+        };
+        let mut large = vec![b'a'; CHUNK_BYTES * 2 + 10];
+        assert_eq!(scan_bytes(&config, &large).await.unwrap(), ScanVerdict::Clean);
+        large.extend_from_slice(b"EICAR");
+        assert_eq!(
+            scan_bytes(&config, &large).await.unwrap(),
+            ScanVerdict::Infected("Eicar-Signature".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn scan_bytes_reports_unavailable_scanner() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        drop(listener);
+        let config = ClamavConfig {
+            address,
+            timeout_seconds: 5,
+            fail_open: false,
+        };
+        assert!(matches!(
+            scan_bytes(&config, b"data").await,
+            Err(AppError::ServiceUnavailable(_))
+        ));
+    }
+}
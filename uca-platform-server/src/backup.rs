@@ -0,0 +1,497 @@
+//! 全量备份：导出所有业务数据与上传文件为单个 ZIP，并可恢复到空数据库。
+//!
+//! 归档结构：`manifest.json`、`data/<表名>.json`（JSON 数组）与 `files/`（上传目录原样复制）。
+//! 会话、一次性重置凭据与 Webhook 投递日志不参与备份，恢复后所有用户需要重新登录。
+//! 暂存的导出文件（`exports/`）同样不纳入备份。
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ConnectionTrait, DatabaseConnection, EntityTrait, IntoActiveModel,
+    PaginatorTrait, TransactionTrait,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    entities::{
+        advisor_classes, announcements, archive_items, attachment_requirements, archives, attachments, audit_logs, competition_library, contest_records,
+        devices, document_verifications, export_profiles, export_templates, form_field_values, form_fields, import_batches, import_template_fields,
+        import_templates, invites, labor_hour_rules, mail_templates, notifications, passkeys, password_policies,
+        record_comments, recovery_codes, review_delegations, review_signatures, runtime_settings, security_events,
+        semesters, students, submission_windows, totp_secrets, user_signatures, users, volunteer_records, webhooks, User,
+    },
+    error::AppError,
+    settings::refresh_runtime_settings,
+    signed_urls::export_artifact_dir,
+    state::AppState,
+};
+
+/// 备份格式版本；结构不兼容时递增。
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// 备份文件上传大小上限（1 GiB）。
+pub const MAX_BACKUP_BYTES: usize = 1024 * 1024 * 1024;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const DATA_PREFIX: &str = "data/";
+const FILES_PREFIX: &str = "files/";
+
+/// 每批插入的行数，避免超出数据库参数个数限制。
+const RESTORE_BATCH_SIZE: usize = 100;
+
+/// 保存了上传文件路径的字段；恢复时按新的上传目录改写。
+const FILE_PATH_FIELDS: [(&str, &str); 3] = [
+    ("attachments", "stored_name"),
+    ("review_signatures", "signature_path"),
+    ("user_signatures", "signature_path"),
+];
+
+/// 备份清单。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    /// 备份格式版本。
+    pub format_version: u32,
+    /// 生成备份的服务端版本。
+    pub app_version: String,
+    /// 生成时间。
+    pub created_at: DateTime<Utc>,
+    /// 源部署的上传目录，用于改写文件路径。
+    pub upload_dir: String,
+    /// 各表行数。
+    pub tables: BTreeMap<String, usize>,
+    /// 上传文件数。
+    pub files: usize,
+}
+
+type TableDump = Vec<(&'static str, Vec<serde_json::Value>)>;
+type TableData = HashMap<String, Vec<serde_json::Value>>;
+
+/// 在同一事务中读取全部表并写出备份归档（临时文件，已回到开头）。
+pub async fn export_backup(state: &AppState) -> Result<(std::fs::File, BackupManifest), AppError> {
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let tables = dump_tables(&transaction).await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let upload_dir = state.config.upload_dir.clone();
+    tokio::task::spawn_blocking(move || write_backup_archive(tables, &upload_dir))
+        .await
+        .map_err(|err| AppError::internal(&format!("write backup failed: {err}")))?
+}
+
+/// 将备份恢复到空数据库：先解压上传文件，再在单个事务中写入全部表。
+pub async fn import_backup(state: &AppState, file: std::fs::File) -> Result<BackupManifest, AppError> {
+    ensure_database_empty(&state.db).await?;
+
+    let upload_dir = state.config.upload_dir.clone();
+    let (manifest, mut tables) =
+        tokio::task::spawn_blocking(move || read_backup_archive(file, &upload_dir))
+            .await
+            .map_err(|err| AppError::internal(&format!("read backup failed: {err}")))??;
+    rebase_file_paths(&mut tables, Path::new(&manifest.upload_dir), &state.config.upload_dir);
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    restore_tables(&transaction, &mut tables).await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    refresh_runtime_settings(state).await?;
+    state.competition_match.lock().await.invalidate();
+    Ok(manifest)
+}
+
+/// 仅允许恢复到尚未创建任何用户的数据库。
+async fn ensure_database_empty(db: &DatabaseConnection) -> Result<(), AppError> {
+    let users = User::find()
+        .count(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if users > 0 {
+        return Err(AppError::bad_request("backup can only be restored into an empty database"));
+    }
+    Ok(())
+}
+
+/// 按外键依赖顺序读取各表。
+async fn dump_tables<C: ConnectionTrait>(db: &C) -> Result<TableDump, AppError> {
+    Ok(vec![
+        ("runtime_settings", dump_table::<runtime_settings::Entity, _>(db).await?),
+        ("password_policies", dump_table::<password_policies::Entity, _>(db).await?),
+        ("labor_hour_rules", dump_table::<labor_hour_rules::Entity, _>(db).await?),
+        ("competition_library", dump_table::<competition_library::Entity, _>(db).await?),
+        ("semesters", dump_table::<semesters::Entity, _>(db).await?),
+        ("form_fields", dump_table::<form_fields::Entity, _>(db).await?),
+        ("import_templates", dump_table::<import_templates::Entity, _>(db).await?),
+        ("import_template_fields", dump_table::<import_template_fields::Entity, _>(db).await?),
+        ("export_templates", dump_table::<export_templates::Entity, _>(db).await?),
+        ("mail_templates", dump_table::<mail_templates::Entity, _>(db).await?),
+        ("export_profiles", dump_table::<export_profiles::Entity, _>(db).await?),
+        ("users", dump_table::<users::Entity, _>(db).await?),
+        ("passkeys", dump_table::<passkeys::Entity, _>(db).await?),
+        ("totp_secrets", dump_table::<totp_secrets::Entity, _>(db).await?),
+        ("recovery_codes", dump_table::<recovery_codes::Entity, _>(db).await?),
+        ("devices", dump_table::<devices::Entity, _>(db).await?),
+        ("user_signatures", dump_table::<user_signatures::Entity, _>(db).await?),
+        ("invites", dump_table::<invites::Entity, _>(db).await?),
+        ("advisor_classes", dump_table::<advisor_classes::Entity, _>(db).await?),
+        ("submission_windows", dump_table::<submission_windows::Entity, _>(db).await?),
+        ("announcements", dump_table::<announcements::Entity, _>(db).await?),
+        ("attachment_requirements", dump_table::<attachment_requirements::Entity, _>(db).await?),
+        ("review_delegations", dump_table::<review_delegations::Entity, _>(db).await?),
+        ("import_batches", dump_table::<import_batches::Entity, _>(db).await?),
+        ("students", dump_table::<students::Entity, _>(db).await?),
+        ("volunteer_records", dump_table::<volunteer_records::Entity, _>(db).await?),
+        ("contest_records", dump_table::<contest_records::Entity, _>(db).await?),
+        ("attachments", dump_table::<attachments::Entity, _>(db).await?),
+        ("form_field_values", dump_table::<form_field_values::Entity, _>(db).await?),
+        ("review_signatures", dump_table::<review_signatures::Entity, _>(db).await?),
+        ("record_comments", dump_table::<record_comments::Entity, _>(db).await?),
+        ("notifications", dump_table::<notifications::Entity, _>(db).await?),
+        ("security_events", dump_table::<security_events::Entity, _>(db).await?),
+        ("audit_logs", dump_table::<audit_logs::Entity, _>(db).await?),
+        ("webhooks", dump_table::<webhooks::Entity, _>(db).await?),
+        ("archives", dump_table::<archives::Entity, _>(db).await?),
+        ("archive_items", dump_table::<archive_items::Entity, _>(db).await?),
+        ("document_verifications", dump_table::<document_verifications::Entity, _>(db).await?),
+    ])
+}
+
+/// 按与导出相同的顺序写入各表；缺失的表视为空表。
+async fn restore_tables<C: ConnectionTrait>(
+    db: &C,
+    tables: &mut TableData,
+) -> Result<(), AppError> {
+    let mut take = |name: &str| tables.remove(name).unwrap_or_default();
+    restore_table::<runtime_settings::ActiveModel, _>(db, "runtime_settings", take("runtime_settings")).await?;
+    restore_table::<password_policies::ActiveModel, _>(db, "password_policies", take("password_policies")).await?;
+    restore_table::<labor_hour_rules::ActiveModel, _>(db, "labor_hour_rules", take("labor_hour_rules")).await?;
+    restore_table::<competition_library::ActiveModel, _>(db, "competition_library", take("competition_library"))
+        .await?;
+    restore_table::<semesters::ActiveModel, _>(db, "semesters", take("semesters")).await?;
+    restore_table::<form_fields::ActiveModel, _>(db, "form_fields", take("form_fields")).await?;
+    restore_table::<import_templates::ActiveModel, _>(db, "import_templates", take("import_templates")).await?;
+    restore_table::<import_template_fields::ActiveModel, _>(
+        db,
+        "import_template_fields",
+        take("import_template_fields"),
+    )
+    .await?;
+    restore_table::<export_templates::ActiveModel, _>(db, "export_templates", take("export_templates")).await?;
+    restore_table::<mail_templates::ActiveModel, _>(db, "mail_templates", take("mail_templates")).await?;
+    restore_table::<export_profiles::ActiveModel, _>(db, "export_profiles", take("export_profiles")).await?;
+    restore_table::<users::ActiveModel, _>(db, "users", take("users")).await?;
+    restore_table::<passkeys::ActiveModel, _>(db, "passkeys", take("passkeys")).await?;
+    restore_table::<totp_secrets::ActiveModel, _>(db, "totp_secrets", take("totp_secrets")).await?;
+    restore_table::<recovery_codes::ActiveModel, _>(db, "recovery_codes", take("recovery_codes")).await?;
+    restore_table::<devices::ActiveModel, _>(db, "devices", take("devices")).await?;
+    restore_table::<user_signatures::ActiveModel, _>(db, "user_signatures", take("user_signatures")).await?;
+    restore_table::<invites::ActiveModel, _>(db, "invites", take("invites")).await?;
+    restore_table::<advisor_classes::ActiveModel, _>(db, "advisor_classes", take("advisor_classes")).await?;
+    restore_table::<submission_windows::ActiveModel, _>(db, "submission_windows", take("submission_windows"))
+        .await?;
+    restore_table::<announcements::ActiveModel, _>(db, "announcements", take("announcements")).await?;
+    restore_table::<attachment_requirements::ActiveModel, _>(
+        db,
+        "attachment_requirements",
+        take("attachment_requirements"),
+    )
+    .await?;
+    restore_table::<review_delegations::ActiveModel, _>(db, "review_delegations", take("review_delegations"))
+        .await?;
+    restore_table::<import_batches::ActiveModel, _>(db, "import_batches", take("import_batches")).await?;
+    restore_table::<students::ActiveModel, _>(db, "students", take("students")).await?;
+    restore_table::<volunteer_records::ActiveModel, _>(db, "volunteer_records", take("volunteer_records")).await?;
+    restore_table::<contest_records::ActiveModel, _>(db, "contest_records", take("contest_records")).await?;
+    restore_table::<attachments::ActiveModel, _>(db, "attachments", take("attachments")).await?;
+    restore_table::<form_field_values::ActiveModel, _>(db, "form_field_values", take("form_field_values")).await?;
+    restore_table::<review_signatures::ActiveModel, _>(db, "review_signatures", take("review_signatures")).await?;
+    restore_table::<record_comments::ActiveModel, _>(db, "record_comments", take("record_comments")).await?;
+    restore_table::<notifications::ActiveModel, _>(db, "notifications", take("notifications")).await?;
+    restore_table::<security_events::ActiveModel, _>(db, "security_events", take("security_events")).await?;
+    restore_table::<audit_logs::ActiveModel, _>(db, "audit_logs", take("audit_logs")).await?;
+    restore_table::<webhooks::ActiveModel, _>(db, "webhooks", take("webhooks")).await?;
+    restore_table::<archives::ActiveModel, _>(db, "archives", take("archives")).await?;
+    restore_table::<archive_items::ActiveModel, _>(db, "archive_items", take("archive_items")).await?;
+    restore_table::<document_verifications::ActiveModel, _>(
+        db,
+        "document_verifications",
+        take("document_verifications"),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn dump_table<E, C>(db: &C) -> Result<Vec<serde_json::Value>, AppError>
+where
+    E: EntityTrait,
+    E::Model: Serialize,
+    C: ConnectionTrait,
+{
+    let rows = E::find()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    rows.iter()
+        .map(|row| {
+            serde_json::to_value(row)
+                .map_err(|err| AppError::internal(&format!("serialize backup row failed: {err}")))
+        })
+        .collect()
+}
+
+/// 批量写入一张表的 JSON 行（归档恢复复用）。
+pub(crate) async fn restore_table<A, C>(db: &C, table: &str, rows: Vec<serde_json::Value>) -> Result<(), AppError>
+where
+    A: ActiveModelTrait + Send,
+    <A::Entity as EntityTrait>::Model: DeserializeOwned + IntoActiveModel<A>,
+    C: ConnectionTrait,
+{
+    let mut models = Vec::with_capacity(rows.len());
+    for row in rows {
+        let model: <A::Entity as EntityTrait>::Model = serde_json::from_value(row)
+            .map_err(|err| AppError::validation(&format!("invalid backup data in {table}: {err}")))?;
+        models.push(model.into_active_model());
+    }
+    while !models.is_empty() {
+        let rest = models.split_off(models.len().min(RESTORE_BATCH_SIZE));
+        <A::Entity as EntityTrait>::insert_many(std::mem::replace(&mut models, rest))
+            .exec_without_returning(db)
+            .await
+            .map_err(|err| AppError::Database(format!("restore {table} failed: {err}")))?;
+    }
+    Ok(())
+}
+
+fn write_backup_archive(
+    tables: TableDump,
+    upload_dir: &Path,
+) -> Result<(std::fs::File, BackupManifest), AppError> {
+    let files = collect_upload_files(upload_dir)?;
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now(),
+        upload_dir: upload_dir.to_string_lossy().to_string(),
+        tables: tables
+            .iter()
+            .map(|(name, rows)| (name.to_string(), rows.len()))
+            .collect(),
+        files: files.len(),
+    };
+
+    let file = tempfile::tempfile()
+        .map_err(|err| AppError::internal(&format!("create temp file failed: {err}")))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+
+    zip.start_file(MANIFEST_ENTRY, options).map_err(write_error)?;
+    serde_json::to_writer_pretty(&mut zip, &manifest).map_err(write_error)?;
+    for (name, rows) in &tables {
+        zip.start_file(format!("{DATA_PREFIX}{name}.json"), options)
+            .map_err(write_error)?;
+        serde_json::to_writer(&mut zip, rows).map_err(write_error)?;
+    }
+    for relative in &files {
+        zip.start_file(format!("{FILES_PREFIX}{relative}"), options)
+            .map_err(write_error)?;
+        let mut source = std::fs::File::open(upload_dir.join(relative)).map_err(write_error)?;
+        std::io::copy(&mut source, &mut zip).map_err(write_error)?;
+    }
+    let mut file = zip.finish().map_err(write_error)?;
+    file.flush().map_err(write_error)?;
+    file.seek(SeekFrom::Start(0))
+        .map_err(|err| AppError::internal(&format!("rewind temp file failed: {err}")))?;
+    Ok((file, manifest))
+}
+
+fn write_error(err: impl std::fmt::Display) -> AppError {
+    AppError::internal(&format!("write backup failed: {err}"))
+}
+
+/// 列出上传目录下的全部文件（相对路径，以 `/` 分隔）。
+fn collect_upload_files(root: &Path) -> Result<Vec<String>, AppError> {
+    let mut files = Vec::new();
+    if !root.exists() {
+        return Ok(files);
+    }
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|err| AppError::internal(&format!("read upload dir failed: {err}")))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if path != export_artifact_dir(root) {
+                    pending.push(path);
+                }
+            } else if file_type.is_file() {
+                if let Ok(relative) = path.strip_prefix(root) {
+                    let parts: Vec<String> = relative
+                        .components()
+                        .map(|part| part.as_os_str().to_string_lossy().to_string())
+                        .collect();
+                    files.push(parts.join("/"));
+                }
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// 读取并校验备份归档，解压上传文件到 `upload_dir`，返回清单与各表数据。
+fn read_backup_archive(
+    file: std::fs::File,
+    upload_dir: &Path,
+) -> Result<(BackupManifest, TableData), AppError> {
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|_| AppError::validation("backup is not a valid zip archive"))?;
+
+    let manifest: BackupManifest = {
+        let entry = archive
+            .by_name(MANIFEST_ENTRY)
+            .map_err(|_| AppError::validation("backup manifest missing"))?;
+        serde_json::from_reader(entry).map_err(|_| AppError::validation("invalid backup manifest"))?
+    };
+    if manifest.format_version != BACKUP_FORMAT_VERSION {
+        return Err(AppError::validation("unsupported backup format version"));
+    }
+
+    let mut tables = HashMap::new();
+    let mut file_entries = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|err| AppError::validation(&format!("read backup entry failed: {err}")))?;
+        let name = entry.name().to_string();
+        if let Some(table) = name
+            .strip_prefix(DATA_PREFIX)
+            .and_then(|rest| rest.strip_suffix(".json"))
+        {
+            let mut bytes = Vec::new();
+            entry
+                .read_to_end(&mut bytes)
+                .map_err(|err| AppError::validation(&format!("read backup entry failed: {err}")))?;
+            let rows: Vec<serde_json::Value> = serde_json::from_slice(&bytes)
+                .map_err(|_| AppError::validation(&format!("invalid backup data in {table}")))?;
+            tables.insert(table.to_string(), rows);
+        } else if name.starts_with(FILES_PREFIX) && !entry.is_dir() {
+            file_entries.push(index);
+        }
+    }
+
+    for index in file_entries {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|err| AppError::validation(&format!("read backup entry failed: {err}")))?;
+        let target = entry
+            .enclosed_name()
+            .and_then(|path| path.strip_prefix(FILES_PREFIX).ok().map(|rest| upload_dir.join(rest)))
+            .ok_or_else(|| AppError::validation("backup contains an unsafe file path"))?;
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| AppError::internal(&format!("create upload dir failed: {err}")))?;
+        }
+        let mut output = std::fs::File::create(&target)
+            .map_err(|err| AppError::internal(&format!("restore file failed: {err}")))?;
+        std::io::copy(&mut entry, &mut output)
+            .map_err(|err| AppError::internal(&format!("restore file failed: {err}")))?;
+    }
+    Ok((manifest, tables))
+}
+
+/// 将源部署上传目录下的文件路径改写到当前上传目录。
+fn rebase_file_paths(
+    tables: &mut TableData,
+    from: &Path,
+    to: &Path,
+) {
+    for (table, field) in FILE_PATH_FIELDS {
+        let Some(rows) = tables.get_mut(table) else {
+            continue;
+        };
+        for row in rows {
+            let Some(value) = row.get_mut(field) else {
+                continue;
+            };
+            if let Some(path) = value.as_str().and_then(|path| rebase_path(path, from, to)) {
+                *value = serde_json::Value::String(path.to_string_lossy().to_string());
+            }
+        }
+    }
+}
+
+fn rebase_path(path: &str, from: &Path, to: &Path) -> Option<PathBuf> {
+    Path::new(path).strip_prefix(from).ok().map(|rest| to.join(rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebase_file_paths_moves_known_fields_only() {
+        let mut tables = HashMap::new();
+        tables.insert(
+            "attachments".to_string(),
+            vec![serde_json::json!({ "stored_name": "/old/uploads/attachments/contest/a.pdf" })],
+        );
+        tables.insert(
+            "students".to_string(),
+            vec![serde_json::json!({ "stored_name": "/old/uploads/keep" })],
+        );
+        tables.insert(
+            "user_signatures".to_string(),
+            vec![serde_json::json!({ "signature_path": "/elsewhere/sig.png" })],
+        );
+        rebase_file_paths(&mut tables, Path::new("/old/uploads"), Path::new("data/uploads"));
+        let expected = Path::new("data/uploads").join("attachments/contest/a.pdf");
+        assert_eq!(
+            tables["attachments"][0]["stored_name"],
+            expected.to_string_lossy().to_string()
+        );
+        assert_eq!(tables["students"][0]["stored_name"], "/old/uploads/keep");
+        assert_eq!(tables["user_signatures"][0]["signature_path"], "/elsewhere/sig.png");
+    }
+
+    #[test]
+    fn backup_archive_round_trips_tables_and_files() {
+        let source = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(source.path().join("attachments/contest")).unwrap();
+        std::fs::write(source.path().join("attachments/contest/a.pdf"), b"pdf").unwrap();
+        let tables = vec![("users", vec![serde_json::json!({ "username": "admin" })])];
+
+        let (file, manifest) = write_backup_archive(tables, source.path()).unwrap();
+        assert_eq!(manifest.files, 1);
+        assert_eq!(manifest.tables["users"], 1);
+
+        let target = tempfile::tempdir().unwrap();
+        let (restored, tables) = read_backup_archive(file, target.path()).unwrap();
+        assert_eq!(restored.upload_dir, manifest.upload_dir);
+        assert_eq!(tables["users"][0]["username"], "admin");
+        assert_eq!(
+            std::fs::read(target.path().join("attachments/contest/a.pdf")).unwrap(),
+            b"pdf"
+        );
+    }
+}
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use base64::Engine;
 use url::Url;
 
-use crate::error::AppError;
+use crate::{auth::SecretKeyring, cors::OriginPattern, error::AppError, i18n::Locale};
 
 /// 服务端运行时配置。
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,8 +24,18 @@ pub struct Config {
     pub rp_id: String,
     /// WebAuthn 依赖方源 URL。
     pub rp_origin: Url,
+    /// 额外允许发起 WebAuthn 的源（须属于 `rp_id` 域名），同时允许跨域访问。
+    pub rp_extra_origins: Vec<Url>,
+    /// 是否接受 `rp_id` 子域名上的 WebAuthn 请求。
+    pub rp_allow_subdomains: bool,
+    /// 额外允许的跨域来源（`RP_ORIGIN` 始终允许），支持 `https://*.example.edu` 子域名通配。
+    pub cors_allowed_origins: Vec<String>,
     /// 前端基础 URL（用于邀请与重置链接）。
     pub base_url: Option<Url>,
+    /// 导出文档二维码中核验链接的 API 基础 URL；未配置时为前端基础 URL 加 `/api`。
+    pub verify_base_url: Option<Url>,
+    /// 服务端生成文本（邮件、导出、默认模板）的部署默认语言。
+    pub locale: Locale,
     /// TLS 证书路径。
     pub tls_cert_path: PathBuf,
     /// TLS 私钥加密文件路径。
@@ -38,14 +48,32 @@ pub struct Config {
     pub tls_key_enc_key: Vec<u8>,
     /// 附件与签名的基础目录。
     pub upload_dir: PathBuf,
+    /// 附件上传限制。
+    pub attachments: AttachmentConfig,
     /// LibreOffice 可执行文件路径。
     pub libreoffice_path: String,
+    /// 同时进行的 PDF 渲染（LibreOffice 转换）数量上限。
+    pub pdf_export_concurrency: usize,
+    /// 单次 LibreOffice 转换的超时时间（秒），超时后终止进程。
+    pub libreoffice_timeout_seconds: u64,
     /// 会话 Cookie 名称。
     pub session_cookie_name: String,
     /// 会话有效期（秒）。
     pub session_ttl_seconds: i64,
+    /// 会话令牌缓存有效期（秒），为 0 时不缓存。
+    pub session_cache_ttl_seconds: u64,
+    /// 签名下载链接有效期（秒），同时决定导出文件的保留时间。
+    pub signed_url_ttl_seconds: u64,
+    /// 导入表格、模板等上传文件的大小上限（字节），也是未单独设置上限的接口的请求体上限。
+    pub max_upload_bytes: usize,
     /// 应用密钥（TOTP、恢复码等）的 Base64 AES-256 密钥。
     pub auth_secret_key: Vec<u8>,
+    /// 当前应用密钥的 ID，写入新密文，轮换时用于区分密钥。
+    pub auth_secret_key_id: String,
+    /// 轮换前的历史应用密钥，仅用于解密尚未重新加密的密文。
+    pub auth_previous_secret_keys: Vec<SecretKey>,
+    /// TOTP 校验的时间偏差与失败退避。
+    pub totp: TotpConfig,
     /// 可选：用于创建初始管理员的引导令牌。
     pub bootstrap_token: Option<String>,
     /// 邮件发送配置。
@@ -54,10 +82,52 @@ pub struct Config {
     pub password_policy: PasswordPolicy,
     /// 重置凭证交付方式（email/code）。
     pub reset_delivery: ResetDelivery,
+    /// 后台定时任务配置。
+    pub jobs: JobsConfig,
+    /// 认证流程状态（Passkey 挑战、二次验证令牌）的存储位置。
+    pub auth_flow_store: AuthFlowStoreKind,
+    /// 是否向学生提供匿名同伴统计。
+    pub peer_stats_enabled: bool,
+    /// 学生本人导出个人文件的范围。
+    pub student_self_export: StudentExportPolicy,
+    /// 是否开放只读 GraphQL 查询接口（`/graphql`）。
+    pub graphql_enabled: bool,
+    /// 竞赛名称模糊匹配的编辑距离阈值（为空时仅按相似度匹配）。
+    pub competition_match_max_edit_distance: Option<usize>,
+    /// 高危安全事件告警邮箱。
+    pub security_alert_email: Option<String>,
+    /// 高危安全事件告警 Webhook 地址。
+    pub security_alert_webhook: Option<Url>,
+    /// 统一身份认证（CAS/OAuth2）配置。
+    pub sso: Option<SsoConfig>,
+    /// 教师/审核人员 LDAP 登录配置。
+    pub ldap: Option<LdapConfig>,
+    /// 附件病毒扫描（clamd）配置。
+    pub clamav: Option<ClamavConfig>,
 }
 
-/// 重置凭证交付方式。
+/// clamd 病毒扫描配置。
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClamavConfig {
+    /// clamd TCP 地址（`host:port`）。
+    pub address: String,
+    /// 单次扫描超时（秒）。
+    pub timeout_seconds: u64,
+    /// 扫描服务不可用时是否放行上传（默认拒绝）。
+    pub fail_open: bool,
+}
+
+/// 带 ID 的应用密钥。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretKey {
+    /// 密钥 ID。
+    pub id: String,
+    /// AES-256 密钥。
+    pub key: Vec<u8>,
+}
+
+/// 重置凭证交付方式。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ResetDelivery {
     Email,
@@ -70,8 +140,43 @@ impl Default for ResetDelivery {
     }
 }
 
+/// 学生本人导出个人文件（单条记录 PDF、个人学时表、学时认定表）的范围。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StudentExportPolicy {
+    /// 不允许学生自行导出。
+    Disabled,
+    /// 仅终审通过的记录：单条记录须已终审，个人文件只包含已终审记录。
+    FinalReviewed,
+    /// 全部记录（含审核中与不通过）。
+    All,
+}
+
+impl Default for StudentExportPolicy {
+    fn default() -> Self {
+        Self::FinalReviewed
+    }
+}
+
+/// 认证流程状态存储位置。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthFlowStoreKind {
+    /// 进程内存（单实例部署，重启后进行中的流程失效）。
+    Memory,
+    /// 数据库表 `auth_flow_states`（多实例共享，重启后保留）。
+    Database,
+}
+
+impl Default for AuthFlowStoreKind {
+    fn default() -> Self {
+        Self::Memory
+    }
+}
+
 /// 邮件发送配置。
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct MailConfig {
     /// SMTP 服务器地址。
     pub smtp_host: String,
@@ -116,7 +221,166 @@ impl Default for PasswordPolicy {
     }
 }
 
+/// 后台定时任务配置。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobsConfig {
+    /// 任务执行间隔（秒）。
+    pub interval_seconds: u64,
+    /// 是否清理过期会话。
+    pub session_purge: bool,
+    /// 是否清理过期邀请与认证重置记录。
+    pub token_cleanup: bool,
+    /// 是否删除未被引用的附件文件。
+    pub orphan_attachment_cleanup: bool,
+    /// 是否自动为缺少登录账号的学生补建账号。
+    pub student_user_repair: bool,
+    /// 是否提醒审核人处理积压的待审记录，并向管理员发送积压汇总。
+    pub review_reminder: bool,
+    /// 记录等待审核超过该天数后提醒。
+    pub review_reminder_days: u32,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            interval_seconds: 3600,
+            session_purge: true,
+            token_cleanup: true,
+            orphan_attachment_cleanup: false,
+            student_user_repair: false,
+            review_reminder: false,
+            review_reminder_days: 7,
+        }
+    }
+}
+
+/// TOTP 校验配置。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TotpConfig {
+    /// 允许的时间步偏差（前后各若干个 30 秒步长）。
+    pub skew_steps: u8,
+    /// 连续失败达到该次数后开始退避。
+    pub max_failures: u32,
+    /// 首次退避时长（秒），此后每次失败翻倍。
+    pub backoff_base_seconds: u64,
+    /// 退避时长上限（秒）。
+    pub backoff_max_seconds: u64,
+}
+
+impl Default for TotpConfig {
+    fn default() -> Self {
+        Self {
+            skew_steps: 1,
+            max_failures: 5,
+            backoff_base_seconds: 30,
+            backoff_max_seconds: 3600,
+        }
+    }
+}
+
+/// 统一身份认证协议。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SsoProvider {
+    Cas,
+    Oauth2,
+}
+
+/// 统一身份认证配置。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SsoConfig {
+    /// 协议类型。
+    pub provider: SsoProvider,
+    /// 回调地址（指向 `/auth/sso/callback`，需在身份提供方登记）。
+    pub callback_url: Url,
+    /// CAS 服务端地址（如 `https://cas.example.edu/cas`）。
+    pub cas_url: Option<Url>,
+    /// OAuth2 授权端点。
+    pub authorize_url: Option<Url>,
+    /// OAuth2 令牌端点。
+    pub token_url: Option<Url>,
+    /// OAuth2 用户信息端点。
+    pub userinfo_url: Option<Url>,
+    /// OAuth2 客户端 ID。
+    pub client_id: Option<String>,
+    /// OAuth2 客户端密钥。
+    pub client_secret: Option<String>,
+    /// OAuth2 授权范围。
+    pub scope: String,
+    /// 用户信息中作为用户名的字段。
+    pub username_claim: String,
+    /// 本地无账号时，是否为学生名单中的学号自动创建学生账号。
+    pub auto_provision_students: bool,
+    /// 是否允许映射到教职工与管理员账号（默认否，SSO 仅用于学生登录）。
+    pub allow_staff: bool,
+}
+
+/// LDAP/Active Directory 登录配置（仅教师与审核人员）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LdapConfig {
+    /// 服务器地址（`ldap://` 或 `ldaps://`）。
+    pub url: String,
+    /// 用户搜索的基础 DN。
+    pub base_dn: String,
+    /// 可选：搜索用户时使用的服务账号 DN（为空时匿名搜索）。
+    pub bind_dn: Option<String>,
+    /// 服务账号密码。
+    pub bind_password: Option<String>,
+    /// 用户搜索过滤器，`{username}` 会被替换为转义后的用户名。
+    pub user_filter: String,
+    /// 显示名称属性。
+    pub display_name_attribute: String,
+    /// 邮箱属性。
+    pub email_attribute: String,
+    /// 组成员属性。
+    pub group_attribute: String,
+    /// 组到角色的映射，按顺序取第一个匹配项。
+    pub role_groups: Vec<LdapRoleGroup>,
+}
+
+/// LDAP 组到本地角色的映射。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LdapRoleGroup {
+    /// 组 DN。
+    pub group: String,
+    /// 本地角色（teacher/reviewer）。
+    pub role: String,
+}
+
+/// 附件上传限制。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentConfig {
+    /// 单个文件大小上限（字节）。
+    pub max_file_bytes: usize,
+    /// 允许的 MIME 类型。
+    pub allowed_types: Vec<String>,
+    /// 每条记录的附件数量上限。
+    pub max_per_record: usize,
+    /// 每名学生附件总大小上限（字节），未设置时不限制。
+    pub max_student_bytes: Option<u64>,
+    /// 全部附件总大小上限（字节），未设置时不限制。
+    pub max_total_bytes: Option<u64>,
+}
+
+impl Default for AttachmentConfig {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: 10 * 1024 * 1024,
+            allowed_types: vec![
+                "image/jpeg".to_string(),
+                "image/png".to_string(),
+                "application/pdf".to_string(),
+            ],
+            max_per_record: 10,
+            max_student_bytes: None,
+            max_total_bytes: None,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
 struct ConfigFile {
     developer_mode: Option<bool>,
     allow_http: Option<bool>,
@@ -124,21 +388,84 @@ struct ConfigFile {
     database_url: Option<String>,
     rp_id: Option<String>,
     rp_origin: Option<String>,
+    rp_extra_origins: Option<Vec<String>>,
+    rp_allow_subdomains: Option<bool>,
+    cors_allowed_origins: Option<Vec<String>>,
     base_url: Option<String>,
+    verify_base_url: Option<String>,
+    locale: Option<String>,
     tls_cert_path: Option<PathBuf>,
     tls_key_path: Option<PathBuf>,
     tls_import_cert_path: Option<PathBuf>,
     tls_import_key_path: Option<PathBuf>,
     upload_dir: Option<PathBuf>,
+    attachments: Option<AttachmentConfigFile>,
     libreoffice_path: Option<String>,
+    pdf_export_concurrency: Option<usize>,
+    libreoffice_timeout_seconds: Option<u64>,
     session_cookie_name: Option<String>,
     session_ttl_seconds: Option<i64>,
+    session_cache_ttl_seconds: Option<u64>,
+    signed_url_ttl_seconds: Option<u64>,
+    max_upload_bytes: Option<usize>,
     mail: Option<MailConfig>,
     password_policy: Option<PasswordPolicyFile>,
     reset_delivery: Option<ResetDelivery>,
+    jobs: Option<JobsConfigFile>,
+    auth_flow_store: Option<AuthFlowStoreKind>,
+    peer_stats_enabled: Option<bool>,
+    student_self_export: Option<StudentExportPolicy>,
+    graphql_enabled: Option<bool>,
+    competition_match_max_edit_distance: Option<usize>,
+    security_alert_email: Option<String>,
+    security_alert_webhook: Option<String>,
+    sso: Option<SsoConfigFile>,
+    ldap: Option<LdapConfigFile>,
+    clamav: Option<ClamavConfigFile>,
+    totp: Option<TotpConfigFile>,
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClamavConfigFile {
+    address: Option<String>,
+    timeout_seconds: Option<u64>,
+    fail_open: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LdapConfigFile {
+    url: Option<String>,
+    base_dn: Option<String>,
+    bind_dn: Option<String>,
+    bind_password: Option<String>,
+    user_filter: Option<String>,
+    display_name_attribute: Option<String>,
+    email_attribute: Option<String>,
+    group_attribute: Option<String>,
+    role_groups: Option<Vec<LdapRoleGroup>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SsoConfigFile {
+    provider: Option<SsoProvider>,
+    callback_url: Option<String>,
+    cas_url: Option<String>,
+    authorize_url: Option<String>,
+    token_url: Option<String>,
+    userinfo_url: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    scope: Option<String>,
+    username_claim: Option<String>,
+    auto_provision_students: Option<bool>,
+    allow_staff: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
 struct PasswordPolicyFile {
     min_length: Option<usize>,
     require_uppercase: Option<bool>,
@@ -147,6 +474,37 @@ struct PasswordPolicyFile {
     require_symbol: Option<bool>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JobsConfigFile {
+    interval_seconds: Option<u64>,
+    session_purge: Option<bool>,
+    token_cleanup: Option<bool>,
+    orphan_attachment_cleanup: Option<bool>,
+    student_user_repair: Option<bool>,
+    review_reminder: Option<bool>,
+    review_reminder_days: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TotpConfigFile {
+    skew_steps: Option<u8>,
+    max_failures: Option<u32>,
+    backoff_base_seconds: Option<u64>,
+    backoff_max_seconds: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AttachmentConfigFile {
+    max_file_bytes: Option<usize>,
+    allowed_types: Option<Vec<String>>,
+    max_per_record: Option<usize>,
+    max_student_bytes: Option<u64>,
+    max_total_bytes: Option<u64>,
+}
+
 impl Config {
     /// 从环境变量加载配置。
     pub fn from_env() -> Result<Self, AppError> {
@@ -205,6 +563,11 @@ impl Config {
                 .parse::<Url>()
                 .map_err(|_| AppError::config("RP_ORIGIN must be a valid URL"))?
         };
+        let rp_extra_origins = load_rp_extra_origins(file_ref, &rp_id)?;
+        let rp_allow_subdomains = env_bool("RP_ALLOW_SUBDOMAINS")
+            .or_else(|| file_ref.and_then(|cfg| cfg.rp_allow_subdomains))
+            .unwrap_or(false);
+        let cors_allowed_origins = load_cors_allowed_origins(file_ref)?;
         let base_url = env::var("BASE_URL")
             .ok()
             .or_else(|| file_ref.and_then(|cfg| cfg.base_url.clone()))
@@ -214,6 +577,25 @@ impl Config {
                     .map_err(|_| AppError::config("BASE_URL must be a valid URL"))
             })
             .transpose()?;
+        let verify_base_url = env::var("VERIFY_BASE_URL")
+            .ok()
+            .or_else(|| file_ref.and_then(|cfg| cfg.verify_base_url.clone()))
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| {
+                value
+                    .parse::<Url>()
+                    .map_err(|_| AppError::config("VERIFY_BASE_URL must be a valid URL"))
+            })
+            .transpose()?;
+        let locale = env::var("LOCALE")
+            .ok()
+            .or_else(|| file_ref.and_then(|cfg| cfg.locale.clone()))
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| {
+                Locale::parse(&value).ok_or_else(|| AppError::config("LOCALE must be zh-CN or en-US"))
+            })
+            .transpose()?
+            .unwrap_or_default();
         let tls_cert_path = env::var("TLS_CERT_PATH")
             .ok()
             .or_else(|| file_ref.and_then(|cfg| cfg.tls_cert_path.clone()).map(|path| path.to_string_lossy().to_string()))
@@ -238,23 +620,59 @@ impl Config {
             env::var("TLS_KEY_ENC_KEY")
                 .map_err(|_| AppError::config("TLS_KEY_ENC_KEY is required"))?
         };
-        let tls_key_enc_key = base64::engine::general_purpose::STANDARD
-            .decode(tls_key_enc_key)
-            .map_err(|_| AppError::config("TLS_KEY_ENC_KEY must be base64"))?;
-        if tls_key_enc_key.len() != 32 {
-            return Err(AppError::config(
-                "TLS_KEY_ENC_KEY must be 32 bytes after base64 decode",
-            ));
-        }
+        let tls_key_enc_key = decode_key_material("TLS_KEY_ENC_KEY", &tls_key_enc_key)?;
         let upload_dir = env::var("UPLOAD_DIR")
             .ok()
             .or_else(|| file_ref.and_then(|cfg| cfg.upload_dir.clone()).map(|path| path.to_string_lossy().to_string()))
             .unwrap_or_else(|| "data/uploads".to_string())
             .into();
+        let attachments = load_attachment_config(file_ref)?;
         let libreoffice_path = env::var("LIBREOFFICE_PATH")
             .ok()
             .or_else(|| file_ref.and_then(|cfg| cfg.libreoffice_path.clone()))
             .unwrap_or_else(|| "soffice".to_string());
+        let pdf_export_concurrency = match env::var("PDF_EXPORT_CONCURRENCY") {
+            Ok(value) => value
+                .parse::<usize>()
+                .map_err(|_| AppError::config("PDF_EXPORT_CONCURRENCY must be integer"))?,
+            Err(_) => file_ref.and_then(|cfg| cfg.pdf_export_concurrency).unwrap_or(2),
+        };
+        if pdf_export_concurrency == 0 {
+            return Err(AppError::config("PDF_EXPORT_CONCURRENCY must be positive"));
+        }
+        let libreoffice_timeout_seconds = match env::var("LIBREOFFICE_TIMEOUT_SECONDS") {
+            Ok(value) => value
+                .parse::<u64>()
+                .map_err(|_| AppError::config("LIBREOFFICE_TIMEOUT_SECONDS must be integer"))?,
+            Err(_) => file_ref
+                .and_then(|cfg| cfg.libreoffice_timeout_seconds)
+                .unwrap_or(120),
+        };
+        if libreoffice_timeout_seconds == 0 {
+            return Err(AppError::config("LIBREOFFICE_TIMEOUT_SECONDS must be positive"));
+        }
+        let signed_url_ttl_seconds = match env::var("SIGNED_URL_TTL_SECONDS") {
+            Ok(value) => value
+                .parse::<u64>()
+                .map_err(|_| AppError::config("SIGNED_URL_TTL_SECONDS must be integer"))?,
+            Err(_) => file_ref
+                .and_then(|cfg| cfg.signed_url_ttl_seconds)
+                .unwrap_or(300),
+        };
+        if signed_url_ttl_seconds == 0 || signed_url_ttl_seconds > 86_400 {
+            return Err(AppError::config("SIGNED_URL_TTL_SECONDS must be between 1 and 86400"));
+        }
+        let max_upload_bytes = match env::var("MAX_UPLOAD_BYTES") {
+            Ok(value) => value
+                .parse::<usize>()
+                .map_err(|_| AppError::config("MAX_UPLOAD_BYTES must be integer"))?,
+            Err(_) => file_ref
+                .and_then(|cfg| cfg.max_upload_bytes)
+                .unwrap_or(20 * 1024 * 1024),
+        };
+        if max_upload_bytes == 0 {
+            return Err(AppError::config("MAX_UPLOAD_BYTES must be positive"));
+        }
         let session_cookie_name = env::var("SESSION_COOKIE_NAME")
             .ok()
             .or_else(|| file_ref.and_then(|cfg| cfg.session_cookie_name.clone()))
@@ -265,20 +683,35 @@ impl Config {
             .unwrap_or_else(|| "3600".to_string())
             .parse::<i64>()
             .map_err(|_| AppError::config("SESSION_TTL_SECONDS must be integer"))?;
+        let session_cache_ttl_seconds = env::var("SESSION_CACHE_TTL_SECONDS")
+            .ok()
+            .or_else(|| {
+                file_ref.and_then(|cfg| cfg.session_cache_ttl_seconds.map(|value| value.to_string()))
+            })
+            .unwrap_or_else(|| "0".to_string())
+            .parse::<u64>()
+            .map_err(|_| AppError::config("SESSION_CACHE_TTL_SECONDS must be integer"))?;
         let auth_secret_key = if developer_mode {
             "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=".to_string()
         } else {
             env::var("AUTH_SECRET_KEY")
                 .map_err(|_| AppError::config("AUTH_SECRET_KEY is required"))?
         };
-        let auth_secret_key = base64::engine::general_purpose::STANDARD
-            .decode(auth_secret_key)
-            .map_err(|_| AppError::config("AUTH_SECRET_KEY must be base64"))?;
-        if auth_secret_key.len() != 32 {
+        let auth_secret_key = decode_key_material("AUTH_SECRET_KEY", &auth_secret_key)?;
+        let auth_secret_key_id = env::var("AUTH_SECRET_KEY_ID")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| DEFAULT_SECRET_KEY_ID.to_string());
+        if !is_valid_key_id(&auth_secret_key_id) {
             return Err(AppError::config(
-                "AUTH_SECRET_KEY must be 32 bytes after base64 decode",
+                "AUTH_SECRET_KEY_ID must be 1-32 letters, digits, '-' or '_'",
             ));
         }
+        let auth_previous_secret_keys = match env::var("AUTH_PREVIOUS_SECRET_KEYS") {
+            Ok(value) => parse_previous_secret_keys(&value, &auth_secret_key_id)?,
+            Err(_) => Vec::new(),
+        };
         let bootstrap_token = if developer_mode {
             None
         } else {
@@ -291,6 +724,57 @@ impl Config {
             .and_then(|value| parse_reset_delivery(&value))
             .or_else(|| file_ref.and_then(|cfg| cfg.reset_delivery.clone()))
             .unwrap_or_default();
+        let jobs = load_jobs_config(file_ref)?;
+        let auth_flow_store = match env::var("AUTH_FLOW_STORE") {
+            Ok(value) if !value.trim().is_empty() => parse_auth_flow_store(&value)
+                .ok_or_else(|| AppError::config("AUTH_FLOW_STORE must be memory or database"))?,
+            _ => file_ref
+                .and_then(|cfg| cfg.auth_flow_store)
+                .unwrap_or_default(),
+        };
+        let peer_stats_enabled = env_bool("PEER_STATS_ENABLED")
+            .or_else(|| file_ref.and_then(|cfg| cfg.peer_stats_enabled))
+            .unwrap_or(true);
+        let student_self_export = match env::var("STUDENT_SELF_EXPORT") {
+            Ok(value) if !value.trim().is_empty() => parse_student_export_policy(&value).ok_or_else(|| {
+                AppError::config("STUDENT_SELF_EXPORT must be disabled, final_reviewed or all")
+            })?,
+            _ => file_ref
+                .and_then(|cfg| cfg.student_self_export)
+                .unwrap_or_default(),
+        };
+        let graphql_enabled = env_bool("GRAPHQL_ENABLED")
+            .or_else(|| file_ref.and_then(|cfg| cfg.graphql_enabled))
+            .unwrap_or(false);
+        let competition_match_max_edit_distance = env::var("COMPETITION_MATCH_MAX_EDIT_DISTANCE")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| {
+                value
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| AppError::config("COMPETITION_MATCH_MAX_EDIT_DISTANCE must be integer"))
+            })
+            .transpose()?
+            .or_else(|| file_ref.and_then(|cfg| cfg.competition_match_max_edit_distance));
+        let security_alert_email = env::var("SECURITY_ALERT_EMAIL")
+            .ok()
+            .or_else(|| file_ref.and_then(|cfg| cfg.security_alert_email.clone()))
+            .filter(|value| !value.trim().is_empty());
+        let security_alert_webhook = env::var("SECURITY_ALERT_WEBHOOK")
+            .ok()
+            .or_else(|| file_ref.and_then(|cfg| cfg.security_alert_webhook.clone()))
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| {
+                value
+                    .parse::<Url>()
+                    .map_err(|_| AppError::config("SECURITY_ALERT_WEBHOOK must be a valid URL"))
+            })
+            .transpose()?;
+        let sso = load_sso_config(file_ref)?;
+        let ldap = load_ldap_config(file_ref)?;
+        let clamav = load_clamav_config(file_ref)?;
+        let totp = load_totp_config(file_ref)?;
 
         Ok(Self {
             bind_addr,
@@ -299,23 +783,161 @@ impl Config {
             database_url,
             rp_id,
             rp_origin,
+            rp_extra_origins,
+            rp_allow_subdomains,
+            cors_allowed_origins,
             base_url,
+            verify_base_url,
+            locale,
             tls_cert_path,
             tls_key_path,
             tls_import_cert_path,
             tls_import_key_path,
             tls_key_enc_key,
             upload_dir,
+            attachments,
             libreoffice_path,
+            pdf_export_concurrency,
+            libreoffice_timeout_seconds,
             session_cookie_name,
             session_ttl_seconds,
+            session_cache_ttl_seconds,
+            signed_url_ttl_seconds,
+            max_upload_bytes,
             auth_secret_key,
+            auth_secret_key_id,
+            auth_previous_secret_keys,
+            totp,
             bootstrap_token,
             mail,
             password_policy,
             reset_delivery,
+            jobs,
+            auth_flow_store,
+            peer_stats_enabled,
+            student_self_export,
+            graphql_enabled,
+            competition_match_max_edit_distance,
+            security_alert_email,
+            security_alert_webhook,
+            sso,
+            ldap,
+            clamav,
         })
     }
+
+    /// 生成去除密钥与口令后的生效配置，供管理员核对部署参数。
+    pub fn redacted(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        for path in SECRET_CONFIG_KEYS {
+            let mut target = Some(&mut value);
+            for segment in path.split('.') {
+                target = target.and_then(|node| node.get_mut(segment));
+            }
+            if let Some(node) = target.filter(|node| !node.is_null()) {
+                *node = serde_json::Value::String(REDACTED.to_string());
+            }
+        }
+        if let Some(node) = value.get_mut("database_url") {
+            *node = serde_json::Value::String(redact_url_password(&self.database_url));
+        }
+        value
+    }
+
+    /// 应用密钥环：当前密钥加密，当前与历史密钥解密。
+    pub fn secret_keyring(&self) -> SecretKeyring<'_> {
+        SecretKeyring {
+            primary_id: &self.auth_secret_key_id,
+            primary_key: &self.auth_secret_key,
+            previous: &self.auth_previous_secret_keys,
+        }
+    }
+}
+
+/// 不在生效配置中展示的敏感配置键（点号分隔嵌套路径）。
+const SECRET_CONFIG_KEYS: [&str; 7] = [
+    "tls_key_enc_key",
+    "auth_secret_key",
+    "auth_previous_secret_keys",
+    "bootstrap_token",
+    "mail.smtp_password",
+    "sso.client_secret",
+    "ldap.bind_password",
+];
+
+const REDACTED: &str = "***";
+
+fn redact_url_password(raw: &str) -> String {
+    match Url::parse(raw) {
+        Ok(mut url) if url.password().is_some() => {
+            let _ = url.set_password(Some(REDACTED));
+            url.to_string()
+        }
+        Ok(_) => raw.to_string(),
+        Err(_) => REDACTED.to_string(),
+    }
+}
+
+/// 未设置 `AUTH_SECRET_KEY_ID` 时的密钥 ID。
+const DEFAULT_SECRET_KEY_ID: &str = "k1";
+/// 密钥中至少应出现的不同字节数；随机生成的 32 字节密钥远高于此值，
+/// 低于此值通常是手写口令、重复字符或全零等占位内容。
+const MIN_KEY_DISTINCT_BYTES: usize = 16;
+
+/// 解码 Base64 AES-256 密钥，并拒绝长度不符或明显缺乏随机性的密钥。
+fn decode_key_material(name: &str, value: &str) -> Result<Vec<u8>, AppError> {
+    let key = base64::engine::general_purpose::STANDARD
+        .decode(value.trim())
+        .map_err(|_| AppError::config(&format!("{name} must be base64")))?;
+    if key.len() != 32 {
+        return Err(AppError::config(&format!(
+            "{name} must be 32 bytes after base64 decode"
+        )));
+    }
+    let mut seen = [false; 256];
+    for byte in &key {
+        seen[*byte as usize] = true;
+    }
+    if seen.iter().filter(|hit| **hit).count() < MIN_KEY_DISTINCT_BYTES {
+        return Err(AppError::config(&format!(
+            "{name} has too little entropy; generate it with `openssl rand -base64 32`"
+        )));
+    }
+    Ok(key)
+}
+
+fn is_valid_key_id(value: &str) -> bool {
+    (1..=32).contains(&value.len())
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+}
+
+/// 解析 `id:base64` 逗号分隔的历史密钥列表。
+fn parse_previous_secret_keys(value: &str, primary_id: &str) -> Result<Vec<SecretKey>, AppError> {
+    let mut keys: Vec<SecretKey> = Vec::new();
+    for item in value.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+        let (id, key) = item
+            .split_once(':')
+            .ok_or_else(|| AppError::config("AUTH_PREVIOUS_SECRET_KEYS entries must be id:base64"))?;
+        let id = id.trim();
+        if !is_valid_key_id(id) {
+            return Err(AppError::config(
+                "AUTH_PREVIOUS_SECRET_KEYS ids must be 1-32 letters, digits, '-' or '_'",
+            ));
+        }
+        if id == primary_id || keys.iter().any(|existing| existing.id == id) {
+            return Err(AppError::config(&format!(
+                "AUTH_PREVIOUS_SECRET_KEYS has duplicate key id: {id}"
+            )));
+        }
+        let key = decode_key_material(&format!("AUTH_PREVIOUS_SECRET_KEYS[{id}]"), key)?;
+        keys.push(SecretKey {
+            id: id.to_string(),
+            key,
+        });
+    }
+    Ok(keys)
 }
 
 fn parse_reset_delivery(value: &str) -> Option<ResetDelivery> {
@@ -326,22 +948,330 @@ fn parse_reset_delivery(value: &str) -> Option<ResetDelivery> {
     }
 }
 
+fn parse_auth_flow_store(value: &str) -> Option<AuthFlowStoreKind> {
+    match value.trim().to_lowercase().as_str() {
+        "memory" => Some(AuthFlowStoreKind::Memory),
+        "database" => Some(AuthFlowStoreKind::Database),
+        _ => None,
+    }
+}
+
+fn parse_student_export_policy(value: &str) -> Option<StudentExportPolicy> {
+    match value.trim().to_lowercase().as_str() {
+        "disabled" => Some(StudentExportPolicy::Disabled),
+        "final_reviewed" => Some(StudentExportPolicy::FinalReviewed),
+        "all" => Some(StudentExportPolicy::All),
+        _ => None,
+    }
+}
+
 fn load_config_file() -> Result<Option<ConfigFile>, AppError> {
-    let explicit_path = env::var("CONFIG_PATH").ok().map(PathBuf::from);
+    let explicit_path = env::var("CONFIG_FILE")
+        .or_else(|_| env::var("CONFIG_PATH"))
+        .ok()
+        .map(PathBuf::from);
     let path = explicit_path.clone().unwrap_or_else(|| PathBuf::from("config.toml"));
     if !path.exists() {
         if explicit_path.is_some() {
-            return Err(AppError::config("CONFIG_PATH not found"));
+            return Err(AppError::config("CONFIG_FILE not found"));
         }
         return Ok(None);
     }
     let content = fs::read_to_string(&path)
         .map_err(|_| AppError::config("failed to read config file"))?;
-    let config = toml::from_str::<ConfigFile>(&content)
-        .map_err(|_| AppError::config("invalid config file"))?;
+    parse_config_file(&content).map(Some)
+}
+
+/// 解析 TOML 配置文件，错误信息包含出错的配置键。
+fn parse_config_file(content: &str) -> Result<ConfigFile, AppError> {
+    let deserializer = toml::Deserializer::new(content);
+    serde_path_to_error::deserialize(deserializer).map_err(|err| {
+        let key = err.path().to_string();
+        let message = err.inner().message().to_string();
+        if key.is_empty() || key == "." {
+            AppError::config(&format!("invalid config file: {message}"))
+        } else {
+            AppError::config(&format!("invalid config file: {key}: {message}"))
+        }
+    })
+}
+
+fn load_rp_extra_origins(file: Option<&ConfigFile>, rp_id: &str) -> Result<Vec<Url>, AppError> {
+    let values = match env::var("RP_EXTRA_ORIGINS") {
+        Ok(value) => value.split(',').map(str::to_string).collect(),
+        Err(_) => file.and_then(|cfg| cfg.rp_extra_origins.clone()).unwrap_or_default(),
+    };
+    values
+        .into_iter()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .map(|value| parse_rp_origin(&value, rp_id))
+        .collect()
+}
+
+/// 解析额外的 WebAuthn 源；浏览器只在源的域名等于 `rp_id` 或为其子域名时允许使用通行密钥，IP 地址不可用。
+fn parse_rp_origin(value: &str, rp_id: &str) -> Result<Url, AppError> {
+    let url = value
+        .parse::<Url>()
+        .map_err(|_| AppError::config(&format!("RP_EXTRA_ORIGINS: {value} is not a valid URL")))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::config(&format!("RP_EXTRA_ORIGINS: {value} must use http or https")));
+    }
+    let within_rp_id = url
+        .domain()
+        .is_some_and(|domain| domain == rp_id || domain.ends_with(&format!(".{rp_id}")));
+    if !within_rp_id {
+        return Err(AppError::config(&format!(
+            "RP_EXTRA_ORIGINS: {value} must be a domain equal to or under RP_ID {rp_id}"
+        )));
+    }
+    Ok(url)
+}
+
+fn load_cors_allowed_origins(file: Option<&ConfigFile>) -> Result<Vec<String>, AppError> {
+    let values = match env::var("CORS_ALLOWED_ORIGINS") {
+        Ok(value) => value.split(',').map(str::to_string).collect(),
+        Err(_) => file.and_then(|cfg| cfg.cors_allowed_origins.clone()).unwrap_or_default(),
+    };
+    let values: Vec<String> = values
+        .into_iter()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect();
+    for value in &values {
+        OriginPattern::parse(value)?;
+    }
+    Ok(values)
+}
+
+fn load_sso_config(file: Option<&ConfigFile>) -> Result<Option<SsoConfig>, AppError> {
+    let file_sso = file.and_then(|cfg| cfg.sso.as_ref());
+    let text = |key: &str, value: Option<&String>| {
+        env::var(key)
+            .ok()
+            .or_else(|| value.cloned())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let url = |key: &str, value: Option<&String>| {
+        text(key, value)
+            .map(|value| {
+                value
+                    .parse::<Url>()
+                    .map_err(|_| AppError::config(&format!("{key} must be a valid URL")))
+            })
+            .transpose()
+    };
+    let provider = match env::var("SSO_PROVIDER") {
+        Ok(value) if !value.trim().is_empty() => Some(match value.trim().to_lowercase().as_str() {
+            "cas" => SsoProvider::Cas,
+            "oauth2" => SsoProvider::Oauth2,
+            _ => return Err(AppError::config("SSO_PROVIDER must be cas or oauth2")),
+        }),
+        _ => file_sso.and_then(|sso| sso.provider),
+    };
+    let Some(provider) = provider else {
+        return Ok(None);
+    };
+    let callback_url = url("SSO_CALLBACK_URL", file_sso.and_then(|sso| sso.callback_url.as_ref()))?
+        .ok_or_else(|| AppError::config("SSO_CALLBACK_URL is required"))?;
+    let config = SsoConfig {
+        provider,
+        callback_url,
+        cas_url: url("SSO_CAS_URL", file_sso.and_then(|sso| sso.cas_url.as_ref()))?,
+        authorize_url: url("SSO_AUTHORIZE_URL", file_sso.and_then(|sso| sso.authorize_url.as_ref()))?,
+        token_url: url("SSO_TOKEN_URL", file_sso.and_then(|sso| sso.token_url.as_ref()))?,
+        userinfo_url: url("SSO_USERINFO_URL", file_sso.and_then(|sso| sso.userinfo_url.as_ref()))?,
+        client_id: text("SSO_CLIENT_ID", file_sso.and_then(|sso| sso.client_id.as_ref())),
+        client_secret: text("SSO_CLIENT_SECRET", file_sso.and_then(|sso| sso.client_secret.as_ref())),
+        scope: text("SSO_SCOPE", file_sso.and_then(|sso| sso.scope.as_ref()))
+            .unwrap_or_else(|| "openid profile".to_string()),
+        username_claim: text("SSO_USERNAME_CLAIM", file_sso.and_then(|sso| sso.username_claim.as_ref()))
+            .unwrap_or_else(|| "preferred_username".to_string()),
+        auto_provision_students: env_bool("SSO_AUTO_PROVISION")
+            .or_else(|| file_sso.and_then(|sso| sso.auto_provision_students))
+            .unwrap_or(true),
+        allow_staff: env_bool("SSO_ALLOW_STAFF")
+            .or_else(|| file_sso.and_then(|sso| sso.allow_staff))
+            .unwrap_or(false),
+    };
+    match provider {
+        SsoProvider::Cas if config.cas_url.is_none() => {
+            return Err(AppError::config("SSO_CAS_URL is required for cas"));
+        }
+        SsoProvider::Oauth2 => {
+            let required = [
+                ("SSO_AUTHORIZE_URL", config.authorize_url.is_some()),
+                ("SSO_TOKEN_URL", config.token_url.is_some()),
+                ("SSO_USERINFO_URL", config.userinfo_url.is_some()),
+                ("SSO_CLIENT_ID", config.client_id.is_some()),
+                ("SSO_CLIENT_SECRET", config.client_secret.is_some()),
+            ];
+            if let Some((key, _)) = required.iter().find(|(_, present)| !present) {
+                return Err(AppError::config(&format!("{key} is required for oauth2")));
+            }
+        }
+        SsoProvider::Cas => {}
+    }
     Ok(Some(config))
 }
 
+fn load_ldap_config(file: Option<&ConfigFile>) -> Result<Option<LdapConfig>, AppError> {
+    let file_ldap = file.and_then(|cfg| cfg.ldap.as_ref());
+    let text = |key: &str, value: Option<&String>| {
+        env::var(key)
+            .ok()
+            .or_else(|| value.cloned())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let Some(url) = text("LDAP_URL", file_ldap.and_then(|ldap| ldap.url.as_ref())) else {
+        return Ok(None);
+    };
+    if !url.starts_with("ldap://") && !url.starts_with("ldaps://") {
+        return Err(AppError::config("LDAP_URL must start with ldap:// or ldaps://"));
+    }
+    let base_dn = text("LDAP_BASE_DN", file_ldap.and_then(|ldap| ldap.base_dn.as_ref()))
+        .ok_or_else(|| AppError::config("LDAP_BASE_DN is required"))?;
+    let user_filter = text("LDAP_USER_FILTER", file_ldap.and_then(|ldap| ldap.user_filter.as_ref()))
+        .unwrap_or_else(|| "(uid={username})".to_string());
+    if !user_filter.contains("{username}") {
+        return Err(AppError::config("LDAP_USER_FILTER must contain {username}"));
+    }
+    let role_groups = match env::var("LDAP_ROLE_GROUPS") {
+        Ok(value) => parse_ldap_role_groups(&value)?,
+        Err(_) => file_ldap
+            .and_then(|ldap| ldap.role_groups.clone())
+            .unwrap_or_default(),
+    };
+    if role_groups.is_empty() {
+        return Err(AppError::config("LDAP_ROLE_GROUPS is required"));
+    }
+    if let Some(invalid) = role_groups
+        .iter()
+        .find(|mapping| !matches!(mapping.role.as_str(), "teacher" | "reviewer"))
+    {
+        return Err(AppError::config(&format!(
+            "LDAP role must be teacher or reviewer: {}",
+            invalid.role
+        )));
+    }
+    Ok(Some(LdapConfig {
+        url,
+        base_dn,
+        bind_dn: text("LDAP_BIND_DN", file_ldap.and_then(|ldap| ldap.bind_dn.as_ref())),
+        bind_password: text("LDAP_BIND_PASSWORD", file_ldap.and_then(|ldap| ldap.bind_password.as_ref())),
+        user_filter,
+        display_name_attribute: text(
+            "LDAP_DISPLAY_NAME_ATTRIBUTE",
+            file_ldap.and_then(|ldap| ldap.display_name_attribute.as_ref()),
+        )
+        .unwrap_or_else(|| "displayName".to_string()),
+        email_attribute: text("LDAP_EMAIL_ATTRIBUTE", file_ldap.and_then(|ldap| ldap.email_attribute.as_ref()))
+            .unwrap_or_else(|| "mail".to_string()),
+        group_attribute: text("LDAP_GROUP_ATTRIBUTE", file_ldap.and_then(|ldap| ldap.group_attribute.as_ref()))
+            .unwrap_or_else(|| "memberOf".to_string()),
+        role_groups,
+    }))
+}
+
+fn load_clamav_config(file: Option<&ConfigFile>) -> Result<Option<ClamavConfig>, AppError> {
+    let file_clamav = file.and_then(|cfg| cfg.clamav.as_ref());
+    let Some(address) = env::var("CLAMAV_ADDRESS")
+        .ok()
+        .or_else(|| file_clamav.and_then(|clamav| clamav.address.clone()))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    else {
+        return Ok(None);
+    };
+    if address.rsplit_once(':').and_then(|(_, port)| port.parse::<u16>().ok()).is_none() {
+        return Err(AppError::config("CLAMAV_ADDRESS must look like host:port"));
+    }
+    let timeout_seconds = env::var("CLAMAV_TIMEOUT_SECONDS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .or_else(|| file_clamav.and_then(|clamav| clamav.timeout_seconds))
+        .unwrap_or(30)
+        .max(1);
+    let fail_open = env_bool("CLAMAV_FAIL_OPEN")
+        .or_else(|| file_clamav.and_then(|clamav| clamav.fail_open))
+        .unwrap_or(false);
+    Ok(Some(ClamavConfig {
+        address,
+        timeout_seconds,
+        fail_open,
+    }))
+}
+
+fn load_totp_config(file: Option<&ConfigFile>) -> Result<TotpConfig, AppError> {
+    let mut totp = TotpConfig::default();
+    if let Some(file_totp) = file.and_then(|cfg| cfg.totp.as_ref()) {
+        if let Some(value) = file_totp.skew_steps {
+            totp.skew_steps = value;
+        }
+        if let Some(value) = file_totp.max_failures {
+            totp.max_failures = value;
+        }
+        if let Some(value) = file_totp.backoff_base_seconds {
+            totp.backoff_base_seconds = value;
+        }
+        if let Some(value) = file_totp.backoff_max_seconds {
+            totp.backoff_max_seconds = value;
+        }
+    }
+    if let Ok(value) = env::var("TOTP_SKEW_STEPS") {
+        totp.skew_steps = value
+            .parse::<u8>()
+            .map_err(|_| AppError::config("TOTP_SKEW_STEPS must be integer"))?;
+    }
+    if let Ok(value) = env::var("TOTP_MAX_FAILURES") {
+        totp.max_failures = value
+            .parse::<u32>()
+            .map_err(|_| AppError::config("TOTP_MAX_FAILURES must be integer"))?;
+    }
+    if let Ok(value) = env::var("TOTP_BACKOFF_BASE_SECONDS") {
+        totp.backoff_base_seconds = value
+            .parse::<u64>()
+            .map_err(|_| AppError::config("TOTP_BACKOFF_BASE_SECONDS must be integer"))?;
+    }
+    if let Ok(value) = env::var("TOTP_BACKOFF_MAX_SECONDS") {
+        totp.backoff_max_seconds = value
+            .parse::<u64>()
+            .map_err(|_| AppError::config("TOTP_BACKOFF_MAX_SECONDS must be integer"))?;
+    }
+    if totp.skew_steps > 10 {
+        return Err(AppError::config("TOTP_SKEW_STEPS must be between 0 and 10"));
+    }
+    if totp.max_failures == 0 {
+        return Err(AppError::config("TOTP_MAX_FAILURES must be positive"));
+    }
+    if totp.backoff_base_seconds == 0 || totp.backoff_max_seconds < totp.backoff_base_seconds {
+        return Err(AppError::config(
+            "TOTP_BACKOFF_BASE_SECONDS must be positive and not exceed TOTP_BACKOFF_MAX_SECONDS",
+        ));
+    }
+    Ok(totp)
+}
+
+/// 解析 `role:group_dn` 形式、以分号分隔的组映射。
+fn parse_ldap_role_groups(value: &str) -> Result<Vec<LdapRoleGroup>, AppError> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let (role, group) = item
+                .split_once(':')
+                .ok_or_else(|| AppError::config("LDAP_ROLE_GROUPS entries must look like role:group_dn"))?;
+            Ok(LdapRoleGroup {
+                group: group.trim().to_string(),
+                role: role.trim().to_lowercase(),
+            })
+        })
+        .collect()
+}
+
 fn load_mail_config(file: Option<&ConfigFile>) -> Result<Option<MailConfig>, AppError> {
     let host = env::var("SMTP_HOST").ok().or_else(|| {
         file.and_then(|cfg| cfg.mail.as_ref().map(|mail| mail.smtp_host.clone()))
@@ -414,6 +1344,139 @@ fn load_password_policy(file: Option<&ConfigFile>) -> PasswordPolicy {
     policy
 }
 
+fn load_attachment_config(file: Option<&ConfigFile>) -> Result<AttachmentConfig, AppError> {
+    let mut attachments = AttachmentConfig::default();
+    if let Some(file_attachments) = file.and_then(|cfg| cfg.attachments.as_ref()) {
+        if let Some(value) = file_attachments.max_file_bytes {
+            attachments.max_file_bytes = value;
+        }
+        if let Some(value) = file_attachments.allowed_types.clone() {
+            attachments.allowed_types = value;
+        }
+        if let Some(value) = file_attachments.max_per_record {
+            attachments.max_per_record = value;
+        }
+        if file_attachments.max_student_bytes.is_some() {
+            attachments.max_student_bytes = file_attachments.max_student_bytes;
+        }
+        if file_attachments.max_total_bytes.is_some() {
+            attachments.max_total_bytes = file_attachments.max_total_bytes;
+        }
+    }
+    if let Ok(value) = env::var("ATTACHMENT_MAX_BYTES") {
+        attachments.max_file_bytes = value
+            .parse::<usize>()
+            .map_err(|_| AppError::config("ATTACHMENT_MAX_BYTES must be integer"))?;
+    }
+    if let Ok(value) = env::var("ATTACHMENT_ALLOWED_TYPES") {
+        attachments.allowed_types = value.split(',').map(str::to_string).collect();
+    }
+    if let Ok(value) = env::var("ATTACHMENT_MAX_PER_RECORD") {
+        attachments.max_per_record = value
+            .parse::<usize>()
+            .map_err(|_| AppError::config("ATTACHMENT_MAX_PER_RECORD must be integer"))?;
+    }
+    // 环境变量为空时取消配置文件中的配额。
+    if let Ok(value) = env::var("ATTACHMENT_MAX_STUDENT_BYTES") {
+        attachments.max_student_bytes = parse_optional_bytes(&value, "ATTACHMENT_MAX_STUDENT_BYTES")?;
+    }
+    if let Ok(value) = env::var("ATTACHMENT_MAX_TOTAL_BYTES") {
+        attachments.max_total_bytes = parse_optional_bytes(&value, "ATTACHMENT_MAX_TOTAL_BYTES")?;
+    }
+    attachments.allowed_types = attachments
+        .allowed_types
+        .iter()
+        .map(|value| value.trim().to_ascii_lowercase())
+        .filter(|value| !value.is_empty())
+        .collect();
+    if attachments.max_file_bytes == 0 {
+        return Err(AppError::config("ATTACHMENT_MAX_BYTES must be positive"));
+    }
+    if attachments.max_per_record == 0 {
+        return Err(AppError::config("ATTACHMENT_MAX_PER_RECORD must be positive"));
+    }
+    if attachments.allowed_types.is_empty() {
+        return Err(AppError::config("ATTACHMENT_ALLOWED_TYPES must not be empty"));
+    }
+    if attachments.max_student_bytes == Some(0) {
+        return Err(AppError::config("ATTACHMENT_MAX_STUDENT_BYTES must be positive"));
+    }
+    if attachments.max_total_bytes == Some(0) {
+        return Err(AppError::config("ATTACHMENT_MAX_TOTAL_BYTES must be positive"));
+    }
+    Ok(attachments)
+}
+
+fn parse_optional_bytes(value: &str, key: &str) -> Result<Option<u64>, AppError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse::<u64>()
+        .map(Some)
+        .map_err(|_| AppError::config(&format!("{key} must be integer")))
+}
+
+fn load_jobs_config(file: Option<&ConfigFile>) -> Result<JobsConfig, AppError> {
+    let mut jobs = JobsConfig::default();
+    if let Some(file_jobs) = file.and_then(|cfg| cfg.jobs.as_ref()) {
+        if let Some(value) = file_jobs.interval_seconds {
+            jobs.interval_seconds = value;
+        }
+        if let Some(value) = file_jobs.session_purge {
+            jobs.session_purge = value;
+        }
+        if let Some(value) = file_jobs.token_cleanup {
+            jobs.token_cleanup = value;
+        }
+        if let Some(value) = file_jobs.orphan_attachment_cleanup {
+            jobs.orphan_attachment_cleanup = value;
+        }
+        if let Some(value) = file_jobs.student_user_repair {
+            jobs.student_user_repair = value;
+        }
+        if let Some(value) = file_jobs.review_reminder {
+            jobs.review_reminder = value;
+        }
+        if let Some(value) = file_jobs.review_reminder_days {
+            jobs.review_reminder_days = value;
+        }
+    }
+    if let Ok(value) = env::var("JOB_INTERVAL_SECONDS") {
+        jobs.interval_seconds = value
+            .parse::<u64>()
+            .map_err(|_| AppError::config("JOB_INTERVAL_SECONDS must be integer"))?;
+    }
+    if let Some(value) = env_bool("JOB_SESSION_PURGE") {
+        jobs.session_purge = value;
+    }
+    if let Some(value) = env_bool("JOB_TOKEN_CLEANUP") {
+        jobs.token_cleanup = value;
+    }
+    if let Some(value) = env_bool("JOB_ORPHAN_ATTACHMENT_CLEANUP") {
+        jobs.orphan_attachment_cleanup = value;
+    }
+    if let Some(value) = env_bool("JOB_STUDENT_USER_REPAIR") {
+        jobs.student_user_repair = value;
+    }
+    if let Some(value) = env_bool("JOB_REVIEW_REMINDER") {
+        jobs.review_reminder = value;
+    }
+    if let Ok(value) = env::var("JOB_REVIEW_REMINDER_DAYS") {
+        jobs.review_reminder_days = value
+            .parse::<u32>()
+            .map_err(|_| AppError::config("JOB_REVIEW_REMINDER_DAYS must be integer"))?;
+    }
+    if jobs.interval_seconds == 0 {
+        return Err(AppError::config("JOB_INTERVAL_SECONDS must be positive"));
+    }
+    if jobs.review_reminder_days == 0 {
+        return Err(AppError::config("JOB_REVIEW_REMINDER_DAYS must be positive"));
+    }
+    Ok(jobs)
+}
+
 fn env_bool(key: &str) -> Option<bool> {
     env::var(key).ok().map(|value| {
         matches!(
@@ -422,3 +1485,70 @@ fn env_bool(key: &str) -> Option<bool> {
         )
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_file_errors_name_the_offending_key() {
+        let err = parse_config_file("[jobs]\ninterval_seconds = \"hourly\"\n").unwrap_err();
+        assert!(err.to_string().contains("jobs.interval_seconds"), "{err}");
+        let err = parse_config_file("[jobs]\nsession_purg = true\n").unwrap_err();
+        assert!(err.to_string().contains("session_purg"), "{err}");
+        assert!(parse_config_file("peer_stats_enabled = false\n").is_ok());
+    }
+
+    #[test]
+    fn rp_extra_origins_must_be_within_rp_id() {
+        assert!(parse_rp_origin("https://labor.tust.edu.cn", "tust.edu.cn").is_ok());
+        assert!(parse_rp_origin("http://tust.edu.cn:8080", "tust.edu.cn").is_ok());
+        assert!(parse_rp_origin("https://eviltust.edu.cn", "tust.edu.cn").is_err());
+        assert!(parse_rp_origin("http://10.0.0.5:8080", "tust.edu.cn").is_err());
+        assert!(parse_rp_origin("ftp://labor.tust.edu.cn", "tust.edu.cn").is_err());
+    }
+
+    #[test]
+    fn key_material_is_checked_for_length_and_entropy() {
+        let engine = base64::engine::general_purpose::STANDARD;
+        let random: Vec<u8> = (0u8..32).map(|byte| byte.wrapping_mul(37)).collect();
+        assert!(decode_key_material("KEY", &engine.encode(&random)).is_ok());
+        assert!(decode_key_material("KEY", "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=").is_ok());
+        assert!(decode_key_material("KEY", &engine.encode([0u8; 32])).is_err());
+        assert!(decode_key_material("KEY", &engine.encode(b"passwordpasswordpasswordpassword")).is_err());
+        assert!(decode_key_material("KEY", &engine.encode(&random[..16])).is_err());
+        assert!(decode_key_material("KEY", "not base64!").is_err());
+    }
+
+    #[test]
+    fn previous_secret_keys_require_unique_ids() {
+        let key = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=";
+        let keys = parse_previous_secret_keys(&format!("k1:{key}, k0:{key}"), "k2").unwrap();
+        assert_eq!(
+            keys.iter().map(|key| key.id.as_str()).collect::<Vec<_>>(),
+            ["k1", "k0"]
+        );
+        assert!(parse_previous_secret_keys(&format!("k2:{key}"), "k2").is_err());
+        assert!(parse_previous_secret_keys(&format!("k1:{key},k1:{key}"), "k2").is_err());
+        assert!(parse_previous_secret_keys(key, "k2").is_err());
+    }
+
+    #[test]
+    fn optional_byte_limits_allow_clearing() {
+        assert_eq!(parse_optional_bytes(" 1048576 ", "KEY").unwrap(), Some(1_048_576));
+        assert_eq!(parse_optional_bytes("", "KEY").unwrap(), None);
+        assert!(parse_optional_bytes("1GB", "KEY").is_err());
+    }
+
+    #[test]
+    fn database_url_password_is_masked() {
+        assert_eq!(
+            redact_url_password("postgres://labor:secret@db:5432/labor"),
+            "postgres://labor:***@db:5432/labor"
+        );
+        assert_eq!(
+            redact_url_password("sqlite://data/dev.db?mode=rwc"),
+            "sqlite://data/dev.db?mode=rwc"
+        );
+    }
+}
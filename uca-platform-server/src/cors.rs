@@ -0,0 +1,190 @@
+//! 跨域（CORS）配置：允许 `RP_ORIGIN` 与 `CORS_ALLOWED_ORIGINS` 中的来源。
+
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
+use url::Url;
+
+use crate::{
+    config::Config, error::AppError, idempotency::IDEMPOTENCY_KEY_HEADER,
+    request_id::REQUEST_ID_HEADER,
+};
+
+/// 开发者模式下额外允许的前端开发服务器来源。
+const DEV_ORIGINS: [&str; 4] = [
+    "http://localhost:5173",
+    "http://127.0.0.1:5173",
+    "https://localhost:5173",
+    "https://127.0.0.1:5173",
+];
+
+/// 允许的跨域来源：精确来源，或 `https://*.example.edu[:port]` 形式的子域名通配。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OriginPattern {
+    /// 精确来源（规范化为 `scheme://host[:port]`）。
+    Exact(String),
+    /// 子域名通配：协议与 `.domain[:port]` 后缀，至少匹配一级子域名。
+    Subdomain { scheme: String, suffix: String },
+}
+
+impl OriginPattern {
+    /// 解析单个来源配置项。
+    pub fn parse(value: &str) -> Result<Self, AppError> {
+        let value = value.trim().trim_end_matches('/');
+        let invalid = || AppError::config(&format!("invalid CORS origin: {value}"));
+        let (scheme, rest) = value.split_once("://").ok_or_else(invalid)?;
+        let scheme = scheme.to_ascii_lowercase();
+        if scheme != "http" && scheme != "https" {
+            return Err(invalid());
+        }
+        if rest.is_empty() || rest.contains(['/', '?', '#', '@']) {
+            return Err(invalid());
+        }
+        if let Some(domain) = rest.strip_prefix("*.") {
+            if domain.contains('*') {
+                return Err(invalid());
+            }
+            let prefix = format!("{scheme}://wildcard");
+            let sample = Url::parse(&format!("{prefix}.{domain}")).map_err(|_| invalid())?;
+            let suffix = sample
+                .origin()
+                .ascii_serialization()
+                .strip_prefix(&prefix)
+                .map(str::to_string)
+                .ok_or_else(invalid)?;
+            return Ok(Self::Subdomain { scheme, suffix });
+        }
+        if rest.contains('*') {
+            return Err(invalid());
+        }
+        let url = Url::parse(value).map_err(|_| invalid())?;
+        if url.host().is_none() {
+            return Err(invalid());
+        }
+        Ok(Self::Exact(url.origin().ascii_serialization()))
+    }
+
+    /// 判断请求的 `Origin` 是否匹配。
+    pub fn matches(&self, origin: &str) -> bool {
+        let origin = origin.to_ascii_lowercase();
+        match self {
+            Self::Exact(value) => *value == origin,
+            Self::Subdomain { scheme, suffix } => origin
+                .strip_prefix(scheme.as_str())
+                .and_then(|rest| rest.strip_prefix("://"))
+                .and_then(|host| host.strip_suffix(suffix.as_str()))
+                .is_some_and(|label| !label.is_empty() && !label.contains([':', '/', '*'])),
+        }
+    }
+}
+
+/// 允许的跨域来源集合。
+#[derive(Debug, Clone)]
+pub struct AllowedOrigins {
+    patterns: Vec<OriginPattern>,
+}
+
+impl AllowedOrigins {
+    /// 由配置构建：`RP_ORIGIN` 与 `RP_EXTRA_ORIGINS` 始终允许；开发者模式额外允许本地前端开发服务器。
+    pub fn from_config(config: &Config) -> Result<Self, AppError> {
+        let mut patterns = vec![OriginPattern::parse(config.rp_origin.as_str())?];
+        for origin in &config.rp_extra_origins {
+            patterns.push(OriginPattern::parse(&origin.origin().ascii_serialization())?);
+        }
+        for value in &config.cors_allowed_origins {
+            patterns.push(OriginPattern::parse(value)?);
+        }
+        if config.developer_mode {
+            for value in DEV_ORIGINS {
+                patterns.push(OriginPattern::parse(value)?);
+            }
+        }
+        patterns.dedup();
+        Ok(Self { patterns })
+    }
+
+    /// 判断请求来源是否允许。
+    pub fn is_allowed(&self, origin: &HeaderValue) -> bool {
+        origin
+            .to_str()
+            .is_ok_and(|origin| self.patterns.iter().any(|pattern| pattern.matches(origin)))
+    }
+}
+
+/// 构建 CORS 中间件（允许携带 Cookie）。
+pub fn build_cors_layer(config: &Config) -> Result<CorsLayer, AppError> {
+    let origins = AllowedOrigins::from_config(config)?;
+    let allow_origin = AllowOrigin::predicate(move |origin, _| origins.is_allowed(origin));
+    let request_id_header = HeaderName::from_static(REQUEST_ID_HEADER);
+    let cors = if config.developer_mode {
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_credentials(true)
+            .allow_headers(AllowHeaders::mirror_request())
+            .allow_methods(AllowMethods::mirror_request())
+            .expose_headers([request_id_header])
+    } else {
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_credentials(true)
+            .allow_headers([
+                header::CONTENT_TYPE,
+                header::AUTHORIZATION,
+                request_id_header.clone(),
+                HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+            ])
+            .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
+            .expose_headers([request_id_header])
+    };
+    Ok(cors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_origins_are_normalized() {
+        assert_eq!(
+            OriginPattern::parse("https://Labor.Example.edu/").unwrap(),
+            OriginPattern::Exact("https://labor.example.edu".to_string())
+        );
+        assert_eq!(
+            OriginPattern::parse("http://10.0.0.5:8080").unwrap(),
+            OriginPattern::Exact("http://10.0.0.5:8080".to_string())
+        );
+        assert_eq!(
+            OriginPattern::parse("https://labor.example.edu:443").unwrap(),
+            OriginPattern::Exact("https://labor.example.edu".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_origins_are_rejected() {
+        let values = ["labor.example.edu", "ftp://example.edu", "https://example.edu/app", "https://*", "https://a.*.edu"];
+        for value in values {
+            assert!(OriginPattern::parse(value).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn wildcard_matches_subdomains_only() {
+        let pattern = OriginPattern::parse("https://*.example.edu").unwrap();
+        assert!(pattern.matches("https://labor.example.edu"));
+        assert!(pattern.matches("https://a.b.example.edu"));
+        assert!(!pattern.matches("https://example.edu"));
+        assert!(!pattern.matches("https://evil-example.edu"));
+        assert!(!pattern.matches("http://labor.example.edu"));
+        assert!(!pattern.matches("https://labor.example.edu:8443"));
+
+        let pattern = OriginPattern::parse("https://*.example.edu:8443").unwrap();
+        assert!(pattern.matches("https://labor.example.edu:8443"));
+        assert!(!pattern.matches("https://labor.example.edu"));
+    }
+
+    #[test]
+    fn exact_match_requires_same_port() {
+        let pattern = OriginPattern::parse("http://10.0.0.5:8080").unwrap();
+        assert!(pattern.matches("http://10.0.0.5:8080"));
+        assert!(!pattern.matches("http://10.0.0.5"));
+    }
+}
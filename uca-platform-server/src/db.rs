@@ -1,6 +1,9 @@
 //! 数据库连接辅助。
 
-use sea_orm::{Database, DatabaseConnection};
+use sea_orm::{
+    sea_query::{Alias, Expr, Func, SimpleExpr},
+    ActiveModelTrait, ConnectionTrait, Database, DatabaseConnection, DbBackend, EntityTrait,
+};
 
 use crate::error::AppError;
 
@@ -10,3 +13,41 @@ pub async fn connect(database_url: &str) -> Result<DatabaseConnection, AppError>
         .await
         .map_err(|err| AppError::Database(err.to_string()))
 }
+
+/// 批量插入时每条语句的行数，避免宽表超出数据库单条语句的参数数量上限。
+pub const INSERT_CHUNK_SIZE: usize = 100;
+
+/// 分块批量插入（每块一条 INSERT 语句），用于导入等一次写入大量行的场景。
+pub async fn insert_chunked<A, C>(db: &C, mut models: Vec<A>) -> Result<(), AppError>
+where
+    A: ActiveModelTrait + Send,
+    C: ConnectionTrait,
+{
+    while !models.is_empty() {
+        let rest = models.split_off(models.len().min(INSERT_CHUNK_SIZE));
+        <A::Entity as EntityTrait>::insert_many(std::mem::replace(&mut models, rest))
+            .exec_without_returning(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+    }
+    Ok(())
+}
+
+/// 对表达式求和并转换为整数，保证各数据库都能解码为 `i64`（MySQL 的 SUM 返回 DECIMAL）。
+pub fn sum_as_i64(backend: DbBackend, expr: impl Into<SimpleExpr>) -> SimpleExpr {
+    let int_type = match backend {
+        DbBackend::MySql => "SIGNED",
+        _ => "BIGINT",
+    };
+    Func::cast_as(Func::sum(expr), Alias::new(int_type)).into()
+}
+
+/// 两个时间戳相差的秒数（`end - start`，取整）；任一为空时结果为空。
+pub fn seconds_between(backend: DbBackend, start: SimpleExpr, end: SimpleExpr) -> SimpleExpr {
+    let template = match backend {
+        DbBackend::MySql => "TIMESTAMPDIFF(SECOND, $1, $2)",
+        DbBackend::Postgres => "CAST(EXTRACT(EPOCH FROM ($2 - $1)) AS BIGINT)",
+        DbBackend::Sqlite => "CAST((julianday($2) - julianday($1)) * 86400 AS INTEGER)",
+    };
+    Expr::cust_with_exprs(template, [start, end])
+}
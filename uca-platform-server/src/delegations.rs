@@ -0,0 +1,110 @@
+//! 审核委托：教职工请假期间将指定阶段的审核交由他人代为处理。
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::{
+    entities::{review_delegations, users, ReviewDelegation, User},
+    error::AppError,
+    services::review::role_can_review,
+};
+
+/// 可作为受托人的角色（班主任只读，不能代审）。
+pub const DELEGATE_ROLES: [&str; 3] = ["reviewer", "teacher", "admin"];
+
+/// 委托在给定时间是否生效（未撤销且在起止时间内，均包含边界）。
+pub fn is_active(delegation: &review_delegations::Model, now: DateTime<Utc>) -> bool {
+    delegation.revoked_at.is_none() && delegation.starts_at <= now && now <= delegation.ends_at
+}
+
+/// 校验委托时间范围：截止时间需晚于开始时间且尚未过去。
+pub fn validate_range(
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    if ends_at <= starts_at {
+        return Err(AppError::validation("starts_at must be before ends_at"));
+    }
+    if ends_at <= now {
+        return Err(AppError::validation("ends_at must be in the future"));
+    }
+    Ok(())
+}
+
+/// 查找受托人在指定阶段当前生效的委托，返回委托与委托人。
+///
+/// 委托人被停用或已不再具备该阶段的审核角色时委托随之失效。
+pub async fn active_delegation<C>(
+    db: &C,
+    delegate_id: Uuid,
+    stage: &str,
+) -> Result<Option<(review_delegations::Model, users::Model)>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let now = Utc::now();
+    let candidates = ReviewDelegation::find()
+        .filter(review_delegations::Column::DelegateId.eq(delegate_id))
+        .filter(review_delegations::Column::Stage.eq(stage))
+        .filter(review_delegations::Column::RevokedAt.is_null())
+        .filter(review_delegations::Column::StartsAt.lte(now))
+        .filter(review_delegations::Column::EndsAt.gte(now))
+        .order_by_asc(review_delegations::Column::CreatedAt)
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    for delegation in candidates {
+        let delegator = User::find_by_id(delegation.delegator_id)
+            .one(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        if let Some(delegator) = delegator {
+            if delegator.is_active && role_can_review(&delegator.role, stage) {
+                return Ok(Some((delegation, delegator)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn delegation(now: DateTime<Utc>) -> review_delegations::Model {
+        review_delegations::Model {
+            id: Uuid::new_v4(),
+            delegator_id: Uuid::new_v4(),
+            delegate_id: Uuid::new_v4(),
+            stage: "final".to_string(),
+            starts_at: now - Duration::days(1),
+            ends_at: now + Duration::days(1),
+            revoked_at: None,
+            created_by: None,
+            created_at: now,
+        }
+    }
+
+    #[test]
+    fn active_requires_range_and_not_revoked() {
+        let now = Utc::now();
+        let mut item = delegation(now);
+        assert!(is_active(&item, now));
+        assert!(is_active(&item, item.ends_at));
+        assert!(!is_active(&item, item.ends_at + Duration::seconds(1)));
+        assert!(!is_active(&item, item.starts_at - Duration::seconds(1)));
+        item.revoked_at = Some(now);
+        assert!(!is_active(&item, now));
+    }
+
+    #[test]
+    fn range_must_end_in_the_future() {
+        let now = Utc::now();
+        assert!(validate_range(now, now + Duration::days(3), now).is_ok());
+        assert!(validate_range(now, now, now).is_err());
+        assert!(validate_range(now - Duration::days(3), now - Duration::days(1), now).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+//! 导出文档的公开核验：导出时签发核验令牌并在文档中嵌入指向 `GET /verify/{token}` 的二维码。
+//!
+//! 令牌只保存摘要；核验记录保存导出时的学生姓名与认定学时快照及文档的 SHA-256 摘要，
+//! 持有文档的人无需登录即可扫码核对。核验接口按客户端限流，防止枚举令牌。
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+use image::{DynamicImage, Rgb, RgbImage};
+use qrcode::QrCode;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::{
+    auth::{generate_token, hash_token},
+    config::Config,
+    entities::{document_verifications, students, DocumentVerification},
+    error::AppError,
+};
+
+/// 核验接口的路径前缀。
+pub const VERIFY_PATH_PREFIX: &str = "/verify/";
+/// 竞赛记录 PDF。
+pub const DOCUMENT_RECORD_PDF: &str = "record_pdf";
+/// 劳动教育学时认定表 PDF。
+pub const DOCUMENT_LABOR_HOURS_PDF: &str = "labor_hours_pdf";
+/// 劳动教育学时认定表 XLSX。
+pub const DOCUMENT_LABOR_HOURS_EXCEL: &str = "labor_hours_excel";
+
+/// 核验限流窗口。
+const VERIFY_RATE_WINDOW_SECONDS: i64 = 60;
+/// 每个客户端在限流窗口内允许的核验次数。
+const VERIFY_RATE_LIMIT: usize = 30;
+/// 二维码每个模块的边长（像素）。
+const QR_MODULE_PIXELS: u32 = 8;
+/// 二维码四周留白的模块数。
+const QR_QUIET_ZONE: u32 = 4;
+
+/// 导出时签发、尚未登记的核验令牌。
+#[derive(Debug, Clone)]
+pub struct PendingVerification {
+    /// 核验令牌（只出现在二维码中）。
+    pub token: String,
+    /// 二维码指向的核验链接。
+    pub url: String,
+}
+
+impl PendingVerification {
+    /// 签发新的核验令牌。
+    pub fn issue(config: &Config) -> Self {
+        let token = generate_token();
+        let url = verification_url(config, &token);
+        Self { token, url }
+    }
+
+    /// 文档生成后登记核验记录，保存文档摘要与学生信息快照。
+    pub async fn record<C>(
+        &self,
+        db: &C,
+        document_type: &str,
+        student: &students::Model,
+        approved_hours: i32,
+        document: &[u8],
+        issued_by: Uuid,
+    ) -> Result<(), AppError>
+    where
+        C: ConnectionTrait,
+    {
+        document_verifications::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            token_hash: Set(hash_token(&self.token)),
+            document_type: Set(document_type.to_string()),
+            student_id: Set(student.id),
+            student_name: Set(student.name.clone()),
+            approved_hours: Set(approved_hours),
+            document_sha256: Set(document_sha256(document)),
+            issued_by: Set(Some(issued_by)),
+            created_at: Set(Utc::now()),
+        }
+        .insert(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+        Ok(())
+    }
+}
+
+/// 公开核验结果。
+#[derive(Debug, Serialize)]
+pub struct DocumentVerificationResult {
+    /// 文档类型。
+    pub document_type: String,
+    /// 脱敏后的学生姓名。
+    pub student_name: String,
+    /// 文档中的认定学时。
+    pub approved_hours: i32,
+    /// 文档的 SHA-256 摘要（十六进制）。
+    pub document_sha256: String,
+    /// 导出时间。
+    pub issued_at: DateTime<Utc>,
+}
+
+/// 核验接口的客户端限流状态。
+#[derive(Debug, Default)]
+pub struct VerifyRateLimiter {
+    requests: HashMap<String, Vec<OffsetDateTime>>,
+}
+
+impl VerifyRateLimiter {
+    /// 记录一次核验；超出限流返回 false。
+    pub fn allow(&mut self, client: &str) -> bool {
+        let now = OffsetDateTime::now_utc();
+        let window_start = now - Duration::seconds(VERIFY_RATE_WINDOW_SECONDS);
+        self.requests.retain(|_, hits| {
+            hits.retain(|hit| *hit > window_start);
+            !hits.is_empty()
+        });
+        let hits = self.requests.entry(client.to_string()).or_default();
+        if hits.len() >= VERIFY_RATE_LIMIT {
+            return false;
+        }
+        hits.push(now);
+        true
+    }
+}
+
+/// 生成核验链接：`VERIFY_BASE_URL`，未配置时为 `BASE_URL`（或 `RP_ORIGIN`）加 `/api`。
+pub fn verification_url(config: &Config, token: &str) -> String {
+    let base = match config.verify_base_url.as_ref() {
+        Some(url) => url.as_str().trim_end_matches('/').to_string(),
+        None => {
+            let frontend = config.base_url.as_ref().unwrap_or(&config.rp_origin);
+            format!("{}/api", frontend.as_str().trim_end_matches('/'))
+        }
+    };
+    format!("{base}{VERIFY_PATH_PREFIX}{token}")
+}
+
+/// 将核验链接编码为二维码图片（黑白，四周留白）。
+pub fn render_qr_image(url: &str) -> Result<DynamicImage, AppError> {
+    let code = QrCode::new(url.as_bytes())
+        .map_err(|err| AppError::internal(&format!("encode qr code failed: {err}")))?;
+    let width = code.width() as u32;
+    let colors = code.to_colors();
+    let size = (width + QR_QUIET_ZONE * 2) * QR_MODULE_PIXELS;
+    let image = RgbImage::from_fn(size, size, |x, y| {
+        let module_x = (x / QR_MODULE_PIXELS).checked_sub(QR_QUIET_ZONE);
+        let module_y = (y / QR_MODULE_PIXELS).checked_sub(QR_QUIET_ZONE);
+        let dark = match (module_x, module_y) {
+            (Some(mx), Some(my)) if mx < width && my < width => {
+                colors[(my * width + mx) as usize] == qrcode::Color::Dark
+            }
+            _ => false,
+        };
+        if dark {
+            Rgb([0, 0, 0])
+        } else {
+            Rgb([255, 255, 255])
+        }
+    });
+    Ok(DynamicImage::ImageRgb8(image))
+}
+
+/// 按令牌查询核验记录；令牌无效返回 404。
+pub async fn verify_document_token<C>(db: &C, token: &str) -> Result<DocumentVerificationResult, AppError>
+where
+    C: ConnectionTrait,
+{
+    let verification = DocumentVerification::find()
+        .filter(document_verifications::Column::TokenHash.eq(hash_token(token.trim())))
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("verification token not found"))?;
+    Ok(DocumentVerificationResult {
+        document_type: verification.document_type,
+        student_name: mask_name(&verification.student_name),
+        approved_hours: verification.approved_hours,
+        document_sha256: verification.document_sha256,
+        issued_at: verification.created_at,
+    })
+}
+
+/// 删除学生名下的核验记录（彻底删除学生时调用）。
+pub async fn delete_student_verifications<C>(db: &C, student_id: Uuid) -> Result<u64, AppError>
+where
+    C: ConnectionTrait,
+{
+    let result = DocumentVerification::delete_many()
+        .filter(document_verifications::Column::StudentId.eq(student_id))
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(result.rows_affected)
+}
+
+/// 姓名脱敏：保留首字，其余字符替换为 `*`。
+pub fn mask_name(name: &str) -> String {
+    let mut chars = name.trim().chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+    std::iter::once(first)
+        .chain(chars.map(|ch| if ch.is_whitespace() { ch } else { '*' }))
+        .collect()
+}
+
+/// 限流使用的客户端标识：对端为回环或内网地址（反向代理）时取 `X-Forwarded-For` 的最后一跳，否则取对端地址。
+pub fn client_key(headers: &HeaderMap, peer: Option<IpAddr>) -> String {
+    let forwarded = || {
+        headers
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.rsplit(',').next())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    match peer {
+        Some(ip) if is_proxy_address(ip) => forwarded().unwrap_or_else(|| ip.to_string()),
+        Some(ip) => ip.to_string(),
+        None => forwarded().unwrap_or_else(|| "unknown".to_string()),
+    }
+}
+
+fn is_proxy_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private(),
+        IpAddr::V6(ip) => ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00,
+    }
+}
+
+fn document_sha256(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_keep_only_the_first_character() {
+        assert_eq!(mask_name("张三"), "张*");
+        assert_eq!(mask_name(" 欧阳娜娜 "), "欧***");
+        assert_eq!(mask_name("Li Lei"), "L* ***");
+        assert_eq!(mask_name(""), "");
+    }
+
+    #[test]
+    fn forwarded_header_is_trusted_only_from_proxies() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "1.1.1.1, 203.0.113.7".parse().unwrap());
+        let proxy: IpAddr = "172.18.0.3".parse().unwrap();
+        let direct: IpAddr = "198.51.100.9".parse().unwrap();
+        assert_eq!(client_key(&headers, Some(proxy)), "203.0.113.7");
+        assert_eq!(client_key(&headers, Some(direct)), "198.51.100.9");
+        assert_eq!(client_key(&HeaderMap::new(), Some(proxy)), "172.18.0.3");
+    }
+
+    #[test]
+    fn limiter_blocks_after_limit_per_client() {
+        let mut limiter = VerifyRateLimiter::default();
+        for _ in 0..VERIFY_RATE_LIMIT {
+            assert!(limiter.allow("203.0.113.7"));
+        }
+        assert!(!limiter.allow("203.0.113.7"));
+        assert!(limiter.allow("198.51.100.9"));
+    }
+
+    #[test]
+    fn qr_image_includes_quiet_zone() {
+        let image = render_qr_image("https://labor.example.edu/api/verify/token").unwrap();
+        let rgb = image.to_rgb8();
+        assert_eq!(rgb.width() % QR_MODULE_PIXELS, 0);
+        assert_eq!(rgb.get_pixel(0, 0), &Rgb([255, 255, 255]));
+        let corner = QR_QUIET_ZONE * QR_MODULE_PIXELS;
+        assert_eq!(rgb.get_pixel(corner, corner), &Rgb([0, 0, 0]));
+    }
+}
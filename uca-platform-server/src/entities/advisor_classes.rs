@@ -0,0 +1,21 @@
+//! 班主任负责的班级。
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "advisor_classes")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// 班主任账号 ID。
+    pub user_id: Uuid,
+    /// 班级名称（对应学生档案的 `class_name`）。
+    pub class_name: String,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
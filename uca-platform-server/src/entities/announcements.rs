@@ -0,0 +1,30 @@
+//! 公告。
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "announcements")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub title: String,
+    pub content: String,
+    /// 严重程度（info/warning/critical）。
+    pub severity: String,
+    /// 目标角色，逗号分隔；为空时面向所有人。
+    pub target_roles: Option<String>,
+    /// 目标院系；为空时不限院系。
+    pub target_department: Option<String>,
+    pub starts_at: DateTimeUtc,
+    /// 截止时间；为空时长期有效。
+    pub ends_at: Option<DateTimeUtc>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,23 @@
+//! 归档批次中的行快照。
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "archive_items")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub archive_id: Uuid,
+    /// 原始表名。
+    pub table_name: String,
+    /// 原始行 ID。
+    pub row_id: Uuid,
+    /// 原始行的 JSON 快照。
+    pub data: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
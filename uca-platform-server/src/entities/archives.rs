@@ -0,0 +1,28 @@
+//! 数据保留归档批次。
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "archives")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// 归档的入学年级。
+    pub cohort_year: i32,
+    pub student_count: i32,
+    /// 归档的竞赛与志愿记录数。
+    pub record_count: i32,
+    /// 归档时删除的附件数（附件不可恢复）。
+    pub purged_attachments: i32,
+    /// 执行归档的管理员，后台任务执行时为空。
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTimeUtc,
+    pub restored_at: Option<DateTimeUtc>,
+    pub restored_by: Option<Uuid>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
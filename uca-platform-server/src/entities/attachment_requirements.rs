@@ -0,0 +1,30 @@
+//! 附件要求规则。
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "attachment_requirements")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// 记录类型（contest/volunteer）。
+    pub record_type: String,
+    /// 适用的竞赛级别；为空时不限级别。
+    pub contest_level: Option<String>,
+    /// 自定义字段条件的字段键；为空时不限。
+    pub field_key: Option<String>,
+    /// 自定义字段需等于的值；为空时字段非空即满足。
+    pub field_value: Option<String>,
+    /// 至少需要的附件数量。
+    pub min_attachments: i32,
+    /// 展示给学生与审核人员的说明。
+    pub description: String,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,9 +1,9 @@
 //! 附件记录。
 
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "attachments")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -14,9 +14,19 @@ pub struct Model {
     pub original_name: String,
     pub stored_name: String,
     pub mime_type: String,
+    /// 附件类型（evidence/signature/template）；早于该字段的备份均为佐证材料。
+    #[serde(default = "default_kind")]
+    pub kind: String,
+    /// 文件大小（字节），用于存储配额与空间统计；早于该字段的备份为 0。
+    #[serde(default)]
+    pub size_bytes: i64,
     pub created_at: DateTimeUtc,
 }
 
+fn default_kind() -> String {
+    "evidence".to_string()
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(
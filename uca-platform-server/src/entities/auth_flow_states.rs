@@ -0,0 +1,22 @@
+//! 认证流程状态（数据库存储后端）。
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "auth_flow_states")]
+pub struct Model {
+    /// 流程类型与会话键拼接而成的主键（`kind:key`）。
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    /// 流程类型（passkey_register/passkey_auth/passkey_discoverable/reauth_passkey/reauth_token）。
+    pub kind: String,
+    /// 序列化后的流程状态 JSON。
+    pub payload: String,
+    pub expires_at: DateTimeUtc,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
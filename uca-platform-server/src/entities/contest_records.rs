@@ -1,9 +1,9 @@
 //! 竞赛获奖记录。
 
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "contest_records")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -21,8 +21,27 @@ pub struct Model {
     pub final_review_hours: Option<i32>,
     pub first_reviewer_id: Option<Uuid>,
     pub final_reviewer_id: Option<Uuid>,
+    pub competition_id: Option<Uuid>,
+    pub semester_id: Option<Uuid>,
+    /// 指派的审核人（为空时所有审核人可见）。
+    pub assigned_reviewer_id: Option<Uuid>,
     pub status: String,
     pub rejection_reason: Option<String>,
+    /// 乐观锁版本号，每次审核后递增。
+    #[serde(default)]
+    pub version: i32,
+    /// 提交时按竞赛库匹配与学时规则计算的推荐学时；未匹配或信息不全时为空。
+    #[serde(default)]
+    pub recommended_hours: Option<i32>,
+    /// 初审时间（含不通过）；早于该字段上线的记录为空。
+    #[serde(default)]
+    pub first_reviewed_at: Option<DateTimeUtc>,
+    /// 终审时间（含不通过）；早于该字段上线的记录为空。
+    #[serde(default)]
+    pub final_reviewed_at: Option<DateTimeUtc>,
+    /// 创建该记录的导入批次，学生提交时为空。
+    #[serde(default)]
+    pub import_batch_id: Option<Uuid>,
     pub is_deleted: bool,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
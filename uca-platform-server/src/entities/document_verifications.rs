@@ -0,0 +1,31 @@
+//! 导出文档的核验令牌。
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "document_verifications")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    /// 核验令牌的 SHA-256 摘要。
+    #[sea_orm(unique)]
+    pub token_hash: String,
+    /// 文档类型：`record_pdf`、`labor_hours_pdf` 或 `labor_hours_excel`。
+    pub document_type: String,
+    pub student_id: Uuid,
+    /// 导出时的学生姓名（核验时脱敏显示）。
+    pub student_name: String,
+    /// 导出时文档中的认定学时。
+    pub approved_hours: i32,
+    /// 导出文档的 SHA-256 摘要（十六进制）。
+    pub document_sha256: String,
+    /// 发起导出的用户。
+    pub issued_by: Option<Uuid>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,24 @@
+//! 导出列配置。
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "export_profiles")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// 导出 key（summary/student_export/labor_hours_excel）。
+    pub profile_key: String,
+    /// 导出列 JSON 数组，数组顺序即列序。
+    pub columns_json: String,
+    /// 最后修改人。
+    pub updated_by: Option<Uuid>,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,16 +1,23 @@
 //! 导出模板定义。
 
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "export_templates")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: Uuid,
     pub template_key: String,
+    /// 已上传模板的文件名。
     pub name: String,
     pub layout_json: String,
+    /// 管理端显示名称。
+    pub display_name: String,
+    /// 模板说明。
+    pub description: Option<String>,
+    /// 适用的导出类型（如 `labor_hours`）。
+    pub export_type: String,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
@@ -1,9 +1,9 @@
 //! 自定义表单字段定义。
 
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "form_fields")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -14,6 +14,14 @@ pub struct Model {
     pub field_type: String,
     pub required: bool,
     pub order_index: i32,
+    /// select/radio 字段的可选值（JSON 字符串数组）。
+    pub options: Option<String>,
+    /// number 字段的最小值。
+    pub min_value: Option<i32>,
+    /// number 字段的最大值。
+    pub max_value: Option<i32>,
+    /// 取值的最大字符数（为空时使用默认上限）。
+    pub max_length: Option<i32>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
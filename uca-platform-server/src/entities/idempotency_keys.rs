@@ -0,0 +1,28 @@
+//! 提交请求的幂等键。
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "idempotency_keys")]
+pub struct Model {
+    /// 用户 ID 与幂等键拼接后的 SHA-256 摘要。
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub user_id: Uuid,
+    /// 首次请求的方法与路径，重试必须一致。
+    pub method: String,
+    pub path: String,
+    /// 首次响应的状态码；为空表示首次请求仍在处理中。
+    pub status_code: Option<i32>,
+    pub content_type: Option<String>,
+    pub response_body: Option<String>,
+    /// 首次响应正文的 SHA-256 摘要，回放前用于校验正文完整性。
+    pub response_hash: Option<String>,
+    pub expires_at: DateTimeUtc,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,23 @@
+//! 导入批次。
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "import_batches")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// 导入类型（student/contest）。
+    pub kind: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTimeUtc,
+    /// 撤销时间，未撤销时为空。
+    pub rolled_back_at: Option<DateTimeUtc>,
+    pub rolled_back_by: Option<Uuid>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
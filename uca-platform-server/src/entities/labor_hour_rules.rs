@@ -1,9 +1,9 @@
 //! 劳动教育学时规则。
 
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "labor_hour_rules")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -16,6 +16,7 @@ pub struct Model {
     pub provincial_member_hours: i32,
     pub school_leader_hours: i32,
     pub school_member_hours: i32,
+    pub annual_cap_hours: Option<i32>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
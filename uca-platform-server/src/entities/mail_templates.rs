@@ -0,0 +1,28 @@
+//! 邮件模板覆盖。
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "mail_templates")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// 模板 key（invite/password_reset/totp_reset/passkey_reset/review）。
+    pub template_key: String,
+    /// 语言标签（zh-CN/en-US）。
+    pub locale: String,
+    /// 邮件主题，支持 `{{name}}` 占位符。
+    pub subject: String,
+    /// 邮件正文，支持 `{{name}}` 占位符。
+    pub body: String,
+    /// 最后修改人。
+    pub updated_by: Option<Uuid>,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -22,6 +22,28 @@ pub mod import_template_fields;
 pub mod export_templates;
 pub mod invites;
 pub mod labor_hour_rules;
+pub mod security_events;
+pub mod audit_logs;
+pub mod runtime_settings;
+pub mod notifications;
+pub mod semesters;
+pub mod submission_windows;
+pub mod record_comments;
+pub mod webhooks;
+pub mod webhook_deliveries;
+pub mod archives;
+pub mod archive_items;
+pub mod advisor_classes;
+pub mod announcements;
+pub mod attachment_requirements;
+pub mod review_delegations;
+pub mod auth_flow_states;
+pub mod mail_templates;
+pub mod mail_outbox;
+pub mod import_batches;
+pub mod export_profiles;
+pub mod idempotency_keys;
+pub mod document_verifications;
 
 pub use devices::Entity as Device;
 pub use passkeys::Entity as Passkey;
@@ -45,3 +67,25 @@ pub use import_template_fields::Entity as ImportTemplateField;
 pub use export_templates::Entity as ExportTemplate;
 pub use invites::Entity as Invite;
 pub use labor_hour_rules::Entity as LaborHourRule;
+pub use security_events::Entity as SecurityEvent;
+pub use audit_logs::Entity as AuditLog;
+pub use runtime_settings::Entity as RuntimeSetting;
+pub use notifications::Entity as Notification;
+pub use semesters::Entity as Semester;
+pub use record_comments::Entity as RecordComment;
+pub use webhooks::Entity as Webhook;
+pub use webhook_deliveries::Entity as WebhookDelivery;
+pub use archives::Entity as Archive;
+pub use archive_items::Entity as ArchiveItem;
+pub use advisor_classes::Entity as AdvisorClass;
+pub use submission_windows::Entity as SubmissionWindow;
+pub use announcements::Entity as Announcement;
+pub use attachment_requirements::Entity as AttachmentRequirement;
+pub use review_delegations::Entity as ReviewDelegation;
+pub use auth_flow_states::Entity as AuthFlowState;
+pub use mail_templates::Entity as MailTemplate;
+pub use mail_outbox::Entity as MailOutbox;
+pub use import_batches::Entity as ImportBatch;
+pub use export_profiles::Entity as ExportProfile;
+pub use idempotency_keys::Entity as IdempotencyKey;
+pub use document_verifications::Entity as DocumentVerification;
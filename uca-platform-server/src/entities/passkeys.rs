@@ -1,8 +1,9 @@
 //! Passkey 凭据记录。
 
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "passkeys")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -10,6 +11,8 @@ pub struct Model {
     pub user_id: Uuid,
     pub credential_id: String,
     pub passkey_json: String,
+    /// 认证器 AAGUID（未提供或全零时为空）。
+    pub aaguid: Option<String>,
     pub created_at: DateTimeUtc,
     pub last_used_at: Option<DateTimeUtc>,
 }
@@ -1,8 +1,9 @@
 //! 密码策略配置。
 
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "password_policies")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -12,6 +13,15 @@ pub struct Model {
     pub require_lowercase: bool,
     pub require_digit: bool,
     pub require_symbol: bool,
+    /// 学生默认密码前缀（旧备份缺少该列时按 `st` 处理）。
+    #[serde(default = "default_student_password_prefix")]
+    pub student_password_prefix: Option<String>,
+    #[serde(default)]
+    pub student_password_suffix: Option<String>,
+    #[serde(default = "default_true")]
+    pub student_password_include_student_no: bool,
+    #[serde(default)]
+    pub student_password_include_phone: bool,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
@@ -20,3 +30,11 @@ pub struct Model {
 pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
+
+fn default_student_password_prefix() -> Option<String> {
+    Some("st".to_string())
+}
+
+fn default_true() -> bool {
+    true
+}
@@ -0,0 +1,28 @@
+//! 审核委托。
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "review_delegations")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// 委托人（原审核人）。
+    pub delegator_id: Uuid,
+    /// 受托人（代为审核的用户）。
+    pub delegate_id: Uuid,
+    /// 委托的审核阶段（first/final）。
+    pub stage: String,
+    pub starts_at: DateTimeUtc,
+    pub ends_at: DateTimeUtc,
+    /// 撤销时间；为空时按起止时间生效。
+    pub revoked_at: Option<DateTimeUtc>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
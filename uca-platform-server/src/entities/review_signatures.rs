@@ -1,9 +1,9 @@
 //! 审核签名记录。
 
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "review_signatures")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -11,6 +11,8 @@ pub struct Model {
     pub record_type: String,
     pub record_id: Uuid,
     pub reviewer_user_id: Uuid,
+    /// 通过审核委托代为签名时的委托人。
+    pub delegator_user_id: Option<Uuid>,
     pub stage: String,
     pub signature_path: String,
     pub created_at: DateTimeUtc,
@@ -1,9 +1,9 @@
 //! 学生名单记录。
 
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "students")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -15,6 +15,9 @@ pub struct Model {
     pub major: String,
     pub class_name: String,
     pub phone: String,
+    /// 创建该学生的导入批次，手工创建时为空。
+    #[serde(default)]
+    pub import_batch_id: Option<Uuid>,
     pub is_deleted: bool,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
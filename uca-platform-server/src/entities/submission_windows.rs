@@ -0,0 +1,26 @@
+//! 记录提交时间窗口。
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "submission_windows")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// 记录类型：contest/volunteer。
+    pub record_type: String,
+    /// 院系覆盖；为空表示该记录类型的默认窗口。
+    pub department: Option<String>,
+    /// 开放时间（含）。
+    pub opens_at: DateTimeUtc,
+    /// 截止时间（含）。
+    pub closes_at: DateTimeUtc,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
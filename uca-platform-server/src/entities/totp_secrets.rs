@@ -1,8 +1,9 @@
 //! 用户 TOTP 密钥。
 
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "totp_secrets")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -12,6 +13,15 @@ pub struct Model {
     pub enabled: bool,
     pub created_at: DateTimeUtc,
     pub verified_at: Option<DateTimeUtc>,
+    /// 最近一次通过校验的时间步，同一步及更早的验证码不再接受。
+    #[serde(default)]
+    pub last_used_step: Option<i64>,
+    /// 连续校验失败次数。
+    #[serde(default)]
+    pub failed_attempts: i32,
+    /// 退避锁定截止时间。
+    #[serde(default)]
+    pub locked_until: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,8 +1,9 @@
 //! 用户电子签名（图片）。
 
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "user_signatures")]
 pub struct Model {
     #[sea_orm(primary_key)]
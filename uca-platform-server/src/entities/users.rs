@@ -1,8 +1,9 @@
 //! 用户账户。
 
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "users")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -16,6 +17,10 @@ pub struct Model {
     pub password_updated_at: Option<DateTimeUtc>,
     pub must_change_password: bool,
     pub is_active: bool,
+    pub department: Option<String>,
+    pub notify_review_email: bool,
+    /// 语言偏好（如 `zh-CN`、`en-US`），为空时使用部署默认语言。
+    pub locale: Option<String>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
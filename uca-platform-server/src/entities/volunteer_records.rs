@@ -1,9 +1,9 @@
 //! 志愿服务记录。
 
 use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "volunteer_records")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -16,6 +16,12 @@ pub struct Model {
     pub final_review_hours: Option<i32>,
     pub status: String,
     pub rejection_reason: Option<String>,
+    pub semester_id: Option<Uuid>,
+    /// 指派的审核人（为空时所有审核人可见）。
+    pub assigned_reviewer_id: Option<Uuid>,
+    /// 乐观锁版本号，每次审核后递增。
+    #[serde(default)]
+    pub version: i32,
     pub is_deleted: bool,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
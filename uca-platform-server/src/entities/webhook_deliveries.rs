@@ -0,0 +1,29 @@
+//! Webhook 投递日志。
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook_deliveries")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: String,
+    /// 发送的 JSON 请求体。
+    pub payload: String,
+    /// 投递状态（pending/succeeded/failed）。
+    pub status: String,
+    pub attempts: i32,
+    /// 最近一次响应的 HTTP 状态码。
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTimeUtc,
+    pub created_at: DateTimeUtc,
+    pub delivered_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,5 +1,7 @@
 //! 错误类型与响应映射。
 
+use std::collections::BTreeMap;
+
 use axum::{http::StatusCode, response::{IntoResponse, Response}, Json};
 use serde::Serialize;
 use thiserror::Error;
@@ -11,6 +13,12 @@ pub struct ErrorBody {
     pub code: String,
     /// 可读的错误信息。
     pub message: String,
+    /// 按字段 key 给出的校验错误。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<BTreeMap<String, String>>,
+    /// 并发冲突时服务端的当前数据，便于客户端刷新。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<serde_json::Value>,
 }
 
 /// 应用错误类型。
@@ -28,12 +36,27 @@ pub enum AppError {
     /// 校验错误。
     #[error("validation error: {0}")]
     Validation(String),
+    /// 按字段 key 区分的校验错误。
+    #[error("validation error: {}", describe_fields(.0))]
+    FieldValidation(BTreeMap<String, String>),
     /// 资源不存在。
     #[error("not found: {0}")]
     NotFound(String),
     /// 请求参数错误。
     #[error("bad request: {0}")]
     BadRequest(String),
+    /// 并发修改或数据冲突，附带当前数据。
+    #[error("conflict: {0}")]
+    Conflict(String, Option<serde_json::Value>),
+    /// 请求体或上传文件过大。
+    #[error("payload too large: {0}")]
+    PayloadTooLarge(String),
+    /// 请求过于频繁。
+    #[error("too many requests: {0}")]
+    TooManyRequests(String),
+    /// 服务暂不可用（维护模式）。
+    #[error("service unavailable: {0}")]
+    ServiceUnavailable(String),
     /// 内部错误。
     #[error("internal error: {0}")]
     Internal(String),
@@ -50,6 +73,11 @@ impl AppError {
         Self::Validation(message.to_string())
     }
 
+    /// 创建按字段区分的校验错误。
+    pub fn fields(errors: BTreeMap<String, String>) -> Self {
+        Self::FieldValidation(errors)
+    }
+
     /// 创建请求参数错误。
     pub fn bad_request(message: &str) -> Self {
         Self::BadRequest(message.to_string())
@@ -65,28 +93,84 @@ impl AppError {
         Self::NotFound(message.to_string())
     }
 
+    /// 创建并发修改或数据冲突错误。
+    pub fn conflict(message: &str, current: Option<serde_json::Value>) -> Self {
+        Self::Conflict(message.to_string(), current)
+    }
+
+    /// 创建请求体过大错误。
+    pub fn payload_too_large(message: &str) -> Self {
+        Self::PayloadTooLarge(message.to_string())
+    }
+
+    /// 创建请求过于频繁错误。
+    pub fn too_many_requests(message: &str) -> Self {
+        Self::TooManyRequests(message.to_string())
+    }
+
+    /// 创建服务暂不可用错误。
+    pub fn service_unavailable(message: &str) -> Self {
+        Self::ServiceUnavailable(message.to_string())
+    }
+
     /// 创建内部错误。
     pub fn internal(message: &str) -> Self {
         Self::Internal(message.to_string())
     }
-}
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let (status, code) = match self {
+    /// HTTP 状态码与稳定错误码。
+    pub fn status_and_code(&self) -> (StatusCode, &'static str) {
+        match self {
             AppError::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, "config_error"),
             AppError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "database_error"),
             AppError::Auth(_) => (StatusCode::UNAUTHORIZED, "auth_error"),
-            AppError::Validation(_) => (StatusCode::UNPROCESSABLE_ENTITY, "validation_error"),
+            AppError::Validation(_) | AppError::FieldValidation(_) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "validation_error")
+            }
             AppError::NotFound(_) => (StatusCode::NOT_FOUND, "not_found"),
             AppError::BadRequest(_) => (StatusCode::BAD_REQUEST, "bad_request"),
+            AppError::Conflict(..) => (StatusCode::CONFLICT, "conflict"),
+            AppError::PayloadTooLarge(_) => (StatusCode::PAYLOAD_TOO_LARGE, "payload_too_large"),
+            AppError::TooManyRequests(_) => (StatusCode::TOO_MANY_REQUESTS, "rate_limited"),
+            AppError::ServiceUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, "maintenance"),
             AppError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error"),
-        };
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status, code) = self.status_and_code();
+
+        // 在请求 span 内记录，日志会带上请求 ID，便于与客户端反馈对照。
+        if status.is_server_error() {
+            tracing::error!(code, error = %self, "request error");
+        } else {
+            tracing::debug!(code, error = %self, "request rejected");
+        }
 
+        let fields = match &self {
+            AppError::FieldValidation(errors) => Some(errors.clone()),
+            _ => None,
+        };
+        let current = match &self {
+            AppError::Conflict(_, current) => current.clone(),
+            _ => None,
+        };
         let body = ErrorBody {
             code: code.to_string(),
             message: self.to_string(),
+            fields,
+            current,
         };
         (status, Json(body)).into_response()
     }
 }
+
+fn describe_fields(errors: &BTreeMap<String, String>) -> String {
+    errors
+        .iter()
+        .map(|(key, message)| format!("{key}: {message}"))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
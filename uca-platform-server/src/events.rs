@@ -0,0 +1,188 @@
+//! 审核队列实时事件：记录提交、审核与导入后广播给在线的审核看板。
+//!
+//! 事件只在本进程内广播，多实例部署时每个实例仅推送本实例产生的事件。
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::{
+    entities::{students, Student},
+    error::AppError,
+    state::AppState,
+};
+
+/// 广播通道容量；订阅方落后超过该数量时会收到 `lagged` 提示。
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+pub const EVENT_RECORD_SUBMITTED: &str = "record_submitted";
+pub const EVENT_RECORD_REVIEWED: &str = "record_reviewed";
+pub const EVENT_RECORDS_IMPORTED: &str = "records_imported";
+
+/// 审核队列事件。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueueEvent {
+    /// 事件类型（record_submitted/record_reviewed/records_imported）。
+    pub kind: &'static str,
+    /// 记录类型（contest/volunteer/student）。
+    pub record_type: &'static str,
+    /// 记录 ID（导入事件为空）。
+    pub record_id: Option<Uuid>,
+    /// 记录当前状态。
+    pub status: Option<String>,
+    /// 学生所属院系；为空表示不限院系。
+    pub department: Option<String>,
+    /// 涉及的记录数。
+    pub count: usize,
+    /// 发生时间。
+    pub at: DateTime<Utc>,
+}
+
+impl QueueEvent {
+    /// 单条记录事件。
+    pub fn record(
+        kind: &'static str,
+        record_type: &'static str,
+        record_id: Uuid,
+        status: &str,
+        department: Option<String>,
+    ) -> Self {
+        Self {
+            kind,
+            record_type,
+            record_id: Some(record_id),
+            status: Some(status.to_string()),
+            department,
+            count: 1,
+            at: Utc::now(),
+        }
+    }
+
+    /// 批量导入事件。
+    pub fn imported(record_type: &'static str, count: usize) -> Self {
+        Self {
+            kind: EVENT_RECORDS_IMPORTED,
+            record_type,
+            record_id: None,
+            status: None,
+            department: None,
+            count,
+            at: Utc::now(),
+        }
+    }
+
+    /// 是否在给定院系范围内可见；不限院系的事件对所有人可见。
+    pub fn visible_to(&self, scope: Option<&str>) -> bool {
+        match (scope, self.department.as_deref()) {
+            (Some(scope), Some(department)) => scope == department,
+            _ => true,
+        }
+    }
+}
+
+/// 进程内事件总线。
+#[derive(Debug)]
+pub struct EventBus {
+    sender: broadcast::Sender<QueueEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl EventBus {
+    /// 广播事件；无订阅者时直接丢弃。
+    pub fn publish(&self, event: QueueEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    /// 订阅事件。
+    pub fn subscribe(&self) -> broadcast::Receiver<QueueEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// 为一批已审核记录广播事件；院系按学生档案查询，失败时只记日志。
+pub async fn publish_review_events(
+    state: &AppState,
+    record_type: &'static str,
+    records: Vec<(Uuid, Uuid, String)>,
+) {
+    if records.is_empty() {
+        return;
+    }
+    let student_ids: Vec<Uuid> = records.iter().map(|(_, student_id, _)| *student_id).collect();
+    let departments = match load_student_departments(&state.db, student_ids).await {
+        Ok(departments) => departments,
+        Err(err) => {
+            tracing::warn!(error = %err, "load departments for queue events failed");
+            return;
+        }
+    };
+    for (record_id, student_id, status) in records {
+        state.events.publish(QueueEvent::record(
+            EVENT_RECORD_REVIEWED,
+            record_type,
+            record_id,
+            &status,
+            departments.get(&student_id).cloned(),
+        ));
+    }
+}
+
+async fn load_student_departments<C>(
+    db: &C,
+    mut student_ids: Vec<Uuid>,
+) -> Result<HashMap<Uuid, String>, AppError>
+where
+    C: ConnectionTrait,
+{
+    student_ids.sort();
+    student_ids.dedup();
+    let students = Student::find()
+        .filter(students::Column::Id.is_in(student_ids))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(students
+        .into_iter()
+        .map(|student| (student.id, student.department))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visible_to_filters_by_department_scope() {
+        let event = QueueEvent::record(
+            EVENT_RECORD_SUBMITTED,
+            "contest",
+            Uuid::new_v4(),
+            "submitted",
+            Some("信息学院".to_string()),
+        );
+        assert!(event.visible_to(None));
+        assert!(event.visible_to(Some("信息学院")));
+        assert!(!event.visible_to(Some("机械学院")));
+        assert!(QueueEvent::imported("contest", 10).visible_to(Some("机械学院")));
+    }
+
+    #[tokio::test]
+    async fn event_bus_delivers_to_subscribers() {
+        let bus = EventBus::default();
+        bus.publish(QueueEvent::imported("student", 1));
+        let mut receiver = bus.subscribe();
+        let event = QueueEvent::imported("contest", 3);
+        bus.publish(event.clone());
+        assert_eq!(receiver.recv().await.unwrap(), event);
+    }
+}
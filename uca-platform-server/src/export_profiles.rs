@@ -0,0 +1,309 @@
+//! 导出列配置：汇总表、个人专项表与学时认定汇总表的列、顺序、标题与数字格式。
+//!
+//! 未配置的导出使用内置默认列；未设置标题的列按导出人的语言使用内置标题。
+
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entities::{export_profiles, ExportProfile},
+    error::AppError,
+    i18n::Locale,
+};
+
+/// 单个导出的列数上限。
+pub const MAX_EXPORT_COLUMNS: usize = 32;
+/// 列标题长度上限（字符）。
+pub const EXPORT_LABEL_MAX_CHARS: usize = 64;
+/// 数字格式长度上限（字符）。
+pub const NUMBER_FORMAT_MAX_CHARS: usize = 64;
+
+/// 可导出的列。
+#[derive(Debug)]
+pub struct ExportColumnDef {
+    /// 列 key。
+    pub key: &'static str,
+    /// 内置标题（消息目录键）。
+    pub label_key: &'static str,
+    /// 是否为数值列；仅数值列可设置数字格式。
+    pub numeric: bool,
+    /// 是否在汇总行中求和。
+    pub summable: bool,
+}
+
+const fn text_column(key: &'static str, label_key: &'static str) -> ExportColumnDef {
+    ExportColumnDef {
+        key,
+        label_key,
+        numeric: false,
+        summable: false,
+    }
+}
+
+const fn number_column(key: &'static str, label_key: &'static str) -> ExportColumnDef {
+    ExportColumnDef {
+        key,
+        label_key,
+        numeric: true,
+        summable: false,
+    }
+}
+
+const fn hours_column(key: &'static str, label_key: &'static str) -> ExportColumnDef {
+    ExportColumnDef {
+        key,
+        label_key,
+        numeric: true,
+        summable: true,
+    }
+}
+
+/// 汇总表与个人专项表的可选列。
+const STUDENT_HOUR_COLUMNS: &[ExportColumnDef] = &[
+    text_column("student_no", "field.student_no"),
+    text_column("name", "field.name"),
+    text_column("gender", "field.gender"),
+    text_column("department", "field.department"),
+    text_column("major", "field.major"),
+    text_column("class_name", "field.class_name"),
+    text_column("phone", "field.phone"),
+    hours_column("self_hours", "field.self_hours"),
+    hours_column("approved_hours", "field.approved_hours"),
+    hours_column("capped_hours", "field.capped_hours"),
+    text_column("reason", "field.reason"),
+];
+
+/// 学时认定汇总表的可选列。
+const LABOR_HOURS_COLUMNS: &[ExportColumnDef] = &[
+    number_column("index", "field.index"),
+    text_column("student_no", "field.student_no"),
+    text_column("name", "field.name"),
+    text_column("gender", "field.gender"),
+    text_column("department", "field.department"),
+    text_column("major", "field.major"),
+    text_column("class_name", "field.class_name"),
+    text_column("phone", "field.phone"),
+    hours_column("planned_hours", "field.planned_hours"),
+    hours_column("module_hours", "field.module_hours"),
+    text_column("reason", "field.reason"),
+];
+
+/// 可在后台配置列的 Excel 导出。
+#[derive(Debug)]
+pub struct ExportProfileDef {
+    /// 导出 key。
+    pub key: &'static str,
+    /// 可选列。
+    pub columns: &'static [ExportColumnDef],
+    /// 未配置时的默认列，顺序即列序。
+    pub defaults: &'static [&'static str],
+}
+
+/// 学院/专业/班级汇总表（`/export/summary/excel`）。
+pub const SUMMARY_EXPORT: ExportProfileDef = ExportProfileDef {
+    key: "summary",
+    columns: STUDENT_HOUR_COLUMNS,
+    defaults: &["student_no", "name", "class_name", "self_hours", "approved_hours", "reason"],
+};
+
+/// 个人学时专项表（`/export/student/{student_no}/excel`）。
+pub const STUDENT_EXPORT: ExportProfileDef = ExportProfileDef {
+    key: "student_export",
+    columns: STUDENT_HOUR_COLUMNS,
+    defaults: &["student_no", "name", "self_hours", "approved_hours", "reason"],
+};
+
+/// 劳动教育学时汇总表（`/export/labor-hours/summary/excel`）。
+pub const LABOR_HOURS_EXCEL_EXPORT: ExportProfileDef = ExportProfileDef {
+    key: "labor_hours_excel",
+    columns: LABOR_HOURS_COLUMNS,
+    defaults: &[
+        "index",
+        "major",
+        "class_name",
+        "student_no",
+        "name",
+        "planned_hours",
+        "module_hours",
+        "reason",
+    ],
+};
+
+/// 全部可配置的导出。
+pub const EXPORT_PROFILES: [&ExportProfileDef; 3] = [&SUMMARY_EXPORT, &STUDENT_EXPORT, &LABOR_HOURS_EXCEL_EXPORT];
+
+/// 按 key 查找导出定义。
+pub fn export_profile_def(key: &str) -> Option<&'static ExportProfileDef> {
+    EXPORT_PROFILES.iter().copied().find(|def| def.key == key)
+}
+
+/// 导出列配置中的一列。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportColumn {
+    /// 列 key。
+    pub field_key: String,
+    /// 列标题，缺省时使用内置标题。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Excel 数字格式（如 `0.0`），仅数值列可设置。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number_format: Option<String>,
+}
+
+impl ExportProfileDef {
+    /// 查找可选列。
+    pub fn column(&self, key: &str) -> Option<&'static ExportColumnDef> {
+        self.columns.iter().find(|column| column.key == key)
+    }
+
+    /// 内置默认列（不含自定义标题与数字格式）。
+    pub fn default_columns(&self) -> Vec<ExportColumn> {
+        self.defaults
+            .iter()
+            .map(|key| ExportColumn {
+                field_key: key.to_string(),
+                label: None,
+                number_format: None,
+            })
+            .collect()
+    }
+
+    /// 列的生效标题：自定义标题优先，其次内置标题，未知列使用 key。
+    pub fn column_label(&self, locale: Locale, column: &ExportColumn) -> String {
+        column
+            .label
+            .clone()
+            .or_else(|| {
+                self.column(&column.field_key)
+                    .map(|def| locale.text(def.label_key).to_string())
+            })
+            .unwrap_or_else(|| column.field_key.clone())
+    }
+
+    /// 校验并规范化管理员提交的列配置（去除首尾空白，空标题与空格式视为未设置）。
+    pub fn normalize_columns(&self, columns: Vec<ExportColumn>) -> Result<Vec<ExportColumn>, AppError> {
+        if columns.is_empty() {
+            return Err(AppError::validation("columns must not be empty"));
+        }
+        if columns.len() > MAX_EXPORT_COLUMNS {
+            return Err(AppError::validation("too many columns"));
+        }
+        let mut normalized: Vec<ExportColumn> = Vec::with_capacity(columns.len());
+        for column in columns {
+            let key = column.field_key.trim().to_string();
+            let Some(def) = self.column(&key) else {
+                return Err(AppError::validation(&format!("column not allowed: {key}")));
+            };
+            if normalized.iter().any(|item| item.field_key == key) {
+                return Err(AppError::validation(&format!("duplicate column: {key}")));
+            }
+            let label = column
+                .label
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty());
+            if label
+                .as_deref()
+                .is_some_and(|value| value.chars().count() > EXPORT_LABEL_MAX_CHARS)
+            {
+                return Err(AppError::validation("label must be at most 64 characters"));
+            }
+            let number_format = column
+                .number_format
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty());
+            if let Some(format) = &number_format {
+                if !def.numeric {
+                    return Err(AppError::validation(&format!("column is not numeric: {key}")));
+                }
+                if format.chars().count() > NUMBER_FORMAT_MAX_CHARS || format.chars().any(char::is_control) {
+                    return Err(AppError::validation("invalid number format"));
+                }
+            }
+            normalized.push(ExportColumn {
+                field_key: key,
+                label,
+                number_format,
+            });
+        }
+        Ok(normalized)
+    }
+}
+
+/// 解析保存的列配置。
+pub fn parse_export_columns(profile: &export_profiles::Model) -> Result<Vec<ExportColumn>, AppError> {
+    serde_json::from_str(&profile.columns_json)
+        .map_err(|_| AppError::internal("invalid export profile columns"))
+}
+
+/// 读取导出的列配置记录。
+pub async fn find_export_profile<C>(
+    db: &C,
+    def: &ExportProfileDef,
+) -> Result<Option<export_profiles::Model>, AppError>
+where
+    C: ConnectionTrait,
+{
+    ExportProfile::find()
+        .filter(export_profiles::Column::ProfileKey.eq(def.key))
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))
+}
+
+/// 读取导出的生效列；未配置时返回内置默认列。
+pub async fn load_export_columns<C>(db: &C, def: &ExportProfileDef) -> Result<Vec<ExportColumn>, AppError>
+where
+    C: ConnectionTrait,
+{
+    match find_export_profile(db, def).await? {
+        Some(profile) => parse_export_columns(&profile),
+        None => Ok(def.default_columns()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(key: &str, label: Option<&str>, number_format: Option<&str>) -> ExportColumn {
+        ExportColumn {
+            field_key: key.to_string(),
+            label: label.map(str::to_string),
+            number_format: number_format.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn defaults_use_known_columns() {
+        for def in EXPORT_PROFILES {
+            assert!(def.defaults.iter().all(|key| def.column(key).is_some()), "{}", def.key);
+        }
+        assert_eq!(export_profile_def("labor_hours_excel").unwrap().defaults[0], "index");
+        assert!(export_profile_def("contest").is_none());
+    }
+
+    #[test]
+    fn normalize_columns_validates_keys_and_formats() {
+        let columns = SUMMARY_EXPORT
+            .normalize_columns(vec![
+                column(" approved_hours ", Some(" 通过学时 "), Some(" 0.0 ")),
+                column("name", Some(" "), Some("")),
+            ])
+            .unwrap();
+        assert_eq!(columns[0], column("approved_hours", Some("通过学时"), Some("0.0")));
+        assert_eq!(columns[1], column("name", None, None));
+        assert_eq!(SUMMARY_EXPORT.column_label(Locale::ZhCn, &columns[1]), "姓名");
+
+        assert!(SUMMARY_EXPORT.normalize_columns(Vec::new()).is_err());
+        assert!(SUMMARY_EXPORT.normalize_columns(vec![column("index", None, None)]).is_err());
+        assert!(SUMMARY_EXPORT
+            .normalize_columns(vec![column("name", None, None), column("name", None, None)])
+            .is_err());
+        assert!(SUMMARY_EXPORT
+            .normalize_columns(vec![column("name", None, Some("0.0"))])
+            .is_err());
+        assert!(LABOR_HOURS_EXCEL_EXPORT
+            .normalize_columns(vec![column("index", None, Some("0"))])
+            .is_ok());
+    }
+}
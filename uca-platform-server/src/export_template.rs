@@ -10,6 +10,9 @@ use umya_spreadsheet::Spreadsheet;
 
 use crate::error::AppError;
 
+/// 以图片文件路径替换、在单元格位置插入图片的占位符。
+const IMAGE_PLACEHOLDERS: [&str; 3] = ["first_signature_image", "final_signature_image", "verification_qr_image"];
+
 #[derive(Debug, Clone)]
 struct ListAnchor {
     sheet: String,
@@ -45,6 +48,103 @@ pub fn render_template_to_xlsx(
     Ok(())
 }
 
+/// 列出模板中无法由给定数据替换的占位符（按出现顺序去重，列表字段带 `list:` 前缀）。
+pub fn unresolved_placeholders(
+    template_path: &Path,
+    single_values: &HashMap<String, String>,
+    list_values: &[HashMap<String, String>],
+) -> Result<Vec<String>, AppError> {
+    let workbook = umya_spreadsheet::reader::xlsx::read(template_path)
+        .map_err(|_| AppError::bad_request("invalid export template"))?;
+    let mut unresolved = Vec::new();
+    let list_sample = list_values.first();
+    for anchor in collect_list_anchors(&workbook) {
+        let resolved = anchor.field_key == "seq"
+            || list_sample.is_some_and(|values| values.contains_key(&anchor.field_key));
+        let token = format!("list:{}", anchor.field_key);
+        if !resolved && !unresolved.contains(&token) {
+            unresolved.push(token);
+        }
+    }
+    for sheet in workbook.get_sheet_collection() {
+        for cell in iter_cells(sheet) {
+            let trimmed = cell.value.trim();
+            if trimmed.starts_with("{{list:") || trimmed == "{{/list}}" {
+                continue;
+            }
+            for placeholder in extract_placeholders(&cell.value) {
+                if placeholder.starts_with("list:") || placeholder == "/list" {
+                    continue;
+                }
+                if !single_values.contains_key(&placeholder) && !unresolved.contains(&placeholder) {
+                    unresolved.push(placeholder);
+                }
+            }
+        }
+    }
+    Ok(unresolved)
+}
+
+/// 预览用的示例学生信息（签名图片与核验二维码留空）。
+pub fn sample_single_values() -> HashMap<String, String> {
+    [
+        ("student_no", "2023000001"),
+        ("name", "张三"),
+        ("gender", "男"),
+        ("department", "计算机学院"),
+        ("major", "软件工程"),
+        ("class_name", "软件2301"),
+        ("phone", "13800000000"),
+        ("total_self_hours", "6"),
+        ("total_approved_hours", "4"),
+        ("total_reason", "示例不通过原因"),
+        ("first_signature_path", ""),
+        ("final_signature_path", ""),
+        ("first_signature_image", ""),
+        ("final_signature_image", ""),
+        ("verification_url", "https://labor.example.edu/api/verify/sample"),
+        ("verification_qr_image", ""),
+    ]
+    .iter()
+    .map(|(key, value)| (key.to_string(), value.to_string()))
+    .collect()
+}
+
+/// 预览用的示例竞赛记录；自定义字段以 `custom.<field_key>` 填入字段标签。
+pub fn sample_list_values(custom_fields: &[(String, String)]) -> Vec<HashMap<String, String>> {
+    let rows = [
+        ("全国大学生数学建模竞赛", "国家级", "负责人", "一等奖", "2025-09-01", "4", "final_reviewed"),
+        ("蓝桥杯全国软件和信息技术专业人才大赛", "省级", "成员", "二等奖", "2025-05-20", "2", "rejected"),
+    ];
+    rows.iter()
+        .map(|(name, level, role, award, date, hours, status)| {
+            let mut map: HashMap<String, String> = [
+                ("contest_year", "2025"),
+                ("contest_category", "A"),
+                ("contest_name", *name),
+                ("contest_level", *level),
+                ("contest_role", *role),
+                ("award_level", *award),
+                ("award_date", *date),
+                ("self_hours", *hours),
+                ("first_review_hours", *hours),
+                ("final_review_hours", *hours),
+                ("approved_hours", *hours),
+                ("recommended_hours", *hours),
+                ("status", *status),
+                ("rejection_reason", ""),
+            ]
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+            for (field_key, label) in custom_fields {
+                map.insert(format!("custom.{field_key}"), format!("示例{label}"));
+            }
+            map
+        })
+        .collect()
+}
+
 fn load_workbook_from_bytes(bytes: &[u8]) -> Result<Spreadsheet, AppError> {
     let mut temp = tempfile::NamedTempFile::new()
         .map_err(|_| AppError::internal("create temp file failed"))?;
@@ -194,9 +294,9 @@ fn apply_single_placeholders(
                 if placeholder.starts_with("list:") || placeholder == "/list" {
                     continue;
                 }
-                if placeholder == "first_signature_image" || placeholder == "final_signature_image" {
+                if IMAGE_PLACEHOLDERS.contains(&placeholder.as_str()) {
                     let path = single_values.get(&placeholder).cloned().unwrap_or_default();
-                    insert_signature_image(sheet, cell.column, cell.row, &path)?;
+                    insert_image(sheet, cell.column, cell.row, &path)?;
                     let token = format!("{{{{{placeholder}}}}}");
                     updated = updated.replace(&token, "");
                 } else {
@@ -290,7 +390,7 @@ fn set_cell_value(sheet: &mut umya_spreadsheet::Worksheet, column: u32, row: u32
     adjust_cell_size(sheet, column, row, value);
 }
 
-fn insert_signature_image(
+fn insert_image(
     sheet: &mut umya_spreadsheet::Worksheet,
     column: u32,
     row: u32,
@@ -428,6 +528,8 @@ fn allowed_single_placeholders() -> HashSet<String> {
         "final_signature_path",
         "first_signature_image",
         "final_signature_image",
+        "verification_url",
+        "verification_qr_image",
     ]
     .iter()
     .map(|value| value.to_string())
@@ -466,7 +568,10 @@ fn is_allowed_list_field(allowed: &HashSet<String>, field_key: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{allowed_list_placeholders, allowed_single_placeholders};
+    use super::{
+        allowed_list_placeholders, allowed_single_placeholders, sample_list_values, sample_single_values,
+        unresolved_placeholders,
+    };
 
     #[test]
     fn list_placeholders_include_seq() {
@@ -479,5 +584,35 @@ mod tests {
         let allowed = allowed_single_placeholders();
         assert!(allowed.contains("first_signature_image"));
         assert!(allowed.contains("final_signature_image"));
+        assert!(allowed.contains("verification_qr_image"));
+    }
+
+    #[test]
+    fn samples_cover_allowed_placeholders() {
+        let single = sample_single_values();
+        for key in allowed_single_placeholders() {
+            assert!(single.contains_key(&key), "missing sample for {key}");
+        }
+        let list = sample_list_values(&[("sponsor".to_string(), "主办方".to_string())]);
+        for key in allowed_list_placeholders() {
+            assert!(key == "seq" || list[0].contains_key(&key), "missing sample for {key}");
+        }
+        assert_eq!(list[0]["custom.sponsor"], "示例主办方");
+    }
+
+    #[test]
+    fn unresolved_placeholders_lists_unknown_fields() {
+        let mut workbook = umya_spreadsheet::new_file();
+        let sheet = workbook.get_sheet_mut(&0).unwrap();
+        sheet.get_cell_mut("A1").set_value("姓名：{{name}} {{nickname}}");
+        sheet.get_cell_mut("A2").set_value("{{list:contest_name}}");
+        sheet.get_cell_mut("B2").set_value("{{list:custom.venue}}");
+        sheet.get_cell_mut("C2").set_value("{{list:seq}}");
+        let temp = tempfile::Builder::new().suffix(".xlsx").tempfile().unwrap();
+        umya_spreadsheet::writer::xlsx::write(&workbook, temp.path()).unwrap();
+
+        let unresolved =
+            unresolved_placeholders(temp.path(), &sample_single_values(), &sample_list_values(&[])).unwrap();
+        assert_eq!(unresolved, vec!["list:custom.venue".to_string(), "nickname".to_string()]);
     }
 }
@@ -0,0 +1,238 @@
+//! 名称模糊匹配（基于字符二元组的 Dice 系数，可选编辑距离）。
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+/// 视为高度相似的 Dice 系数。
+const FUZZY_MEDIUM_SCORE: f64 = 0.85;
+/// 视为可能相同的最低 Dice 系数。
+const FUZZY_LOW_SCORE: f64 = 0.6;
+
+/// 名称匹配置信度。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchConfidence {
+    /// 相似度较低，需人工确认。
+    Low,
+    /// 编辑距离在阈值内或相似度较高。
+    Medium,
+    /// 归一化后完全一致（仅标点、空白、全半角或大小写不同）。
+    High,
+    /// 原文完全一致。
+    Exact,
+    /// 审核人员已确认对应关系。
+    Confirmed,
+}
+
+/// 最佳匹配结果。
+#[derive(Debug, Clone)]
+pub struct NameMatch<T> {
+    /// 匹配到的候选项。
+    pub item: T,
+    /// 置信度。
+    pub confidence: MatchConfidence,
+    /// 相似度（0.0~1.0）。
+    pub score: f64,
+}
+
+/// 归一化名称：全角字符转半角，去除空白与标点，英文转小写。
+pub fn normalize_name(value: &str) -> String {
+    value
+        .chars()
+        .map(to_half_width)
+        .filter(|ch| ch.is_alphanumeric())
+        .flat_map(|ch| ch.to_lowercase())
+        .collect()
+}
+
+fn to_half_width(ch: char) -> char {
+    match ch {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(ch as u32 - 0xFEE0).unwrap_or(ch),
+        _ => ch,
+    }
+}
+
+/// 计算两个名称的相似度，范围 0.0~1.0。
+pub fn similarity(left: &str, right: &str) -> f64 {
+    dice(&normalize_name(left), &normalize_name(right))
+}
+
+/// 计算两个名称归一化后的编辑距离（按字符）。
+pub fn edit_distance(left: &str, right: &str) -> usize {
+    let left: Vec<char> = normalize_name(left).chars().collect();
+    let right: Vec<char> = normalize_name(right).chars().collect();
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    for (i, left_ch) in left.iter().enumerate() {
+        let mut current = vec![i + 1; right.len() + 1];
+        for (j, right_ch) in right.iter().enumerate() {
+            let cost = usize::from(left_ch != right_ch);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[right.len()]
+}
+
+/// 判断名称与候选的匹配程度；不满足最低相似度时返回 `None`。
+/// `max_edit_distance` 为空时不按编辑距离判断。
+pub fn classify_match(
+    query: &str,
+    candidate: &str,
+    max_edit_distance: Option<usize>,
+) -> Option<(MatchConfidence, f64)> {
+    if query.trim() == candidate.trim() && !query.trim().is_empty() {
+        return Some((MatchConfidence::Exact, 1.0));
+    }
+    let left = normalize_name(query);
+    let right = normalize_name(candidate);
+    if left.is_empty() || right.is_empty() {
+        return None;
+    }
+    if left == right {
+        return Some((MatchConfidence::High, 1.0));
+    }
+    let score = dice(&left, &right);
+    let within_distance = max_edit_distance
+        .map(|max| edit_distance(&left, &right) <= max)
+        .unwrap_or(false);
+    if within_distance || score >= FUZZY_MEDIUM_SCORE {
+        Some((MatchConfidence::Medium, score))
+    } else if score >= FUZZY_LOW_SCORE {
+        Some((MatchConfidence::Low, score))
+    } else {
+        None
+    }
+}
+
+/// 选出置信度最高（同级取相似度最高）的候选。
+pub fn best_match<'a, T>(
+    query: &str,
+    candidates: impl IntoIterator<Item = (&'a str, T)>,
+    max_edit_distance: Option<usize>,
+) -> Option<NameMatch<T>> {
+    let mut best: Option<NameMatch<T>> = None;
+    for (name, item) in candidates {
+        let Some((confidence, score)) = classify_match(query, name, max_edit_distance) else {
+            continue;
+        };
+        let better = best.as_ref().is_none_or(|current| {
+            (confidence, score) > (current.confidence, current.score)
+        });
+        if better {
+            best = Some(NameMatch { item, confidence, score });
+        }
+    }
+    best
+}
+
+fn dice(left: &str, right: &str) -> f64 {
+    if left.is_empty() || right.is_empty() {
+        return 0.0;
+    }
+    if left == right {
+        return 1.0;
+    }
+    let left_grams = bigrams(&left);
+    let right_grams = bigrams(&right);
+    let total: usize = left_grams.values().sum::<usize>() + right_grams.values().sum::<usize>();
+    if total == 0 {
+        return 0.0;
+    }
+    let shared: usize = left_grams
+        .iter()
+        .map(|(gram, count)| (*count).min(right_grams.get(gram).copied().unwrap_or(0)))
+        .sum();
+    (2 * shared) as f64 / total as f64
+}
+
+/// 从候选中选出相似度不低于阈值的前 `limit` 项，按分数降序。
+pub fn top_matches<'a, T>(
+    query: &str,
+    candidates: impl IntoIterator<Item = (&'a str, T)>,
+    limit: usize,
+    threshold: f64,
+) -> Vec<(T, f64)> {
+    let mut scored: Vec<(T, f64)> = candidates
+        .into_iter()
+        .map(|(name, item)| (item, similarity(query, name)))
+        .filter(|(_, score)| *score >= threshold)
+        .collect();
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored.truncate(limit);
+    scored
+}
+
+fn bigrams(value: &str) -> HashMap<(char, char), usize> {
+    let chars: Vec<char> = value.chars().collect();
+    let mut grams = HashMap::new();
+    if chars.len() == 1 {
+        grams.insert((chars[0], chars[0]), 1);
+        return grams;
+    }
+    for pair in chars.windows(2) {
+        *grams.entry((pair[0], pair[1])).or_insert(0) += 1;
+    }
+    grams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn similarity_ignores_spacing_and_punctuation() {
+        assert_eq!(similarity("全国大学生 数学建模竞赛", "全国大学生数学建模竞赛"), 1.0);
+        assert_eq!(similarity("ACM-ICPC", "acm icpc"), 1.0);
+        assert_eq!(similarity("", "数学建模"), 0.0);
+    }
+
+    #[test]
+    fn normalize_name_folds_full_width_and_quotes() {
+        assert_eq!(normalize_name("“互联网+”大赛"), normalize_name("互联网+大赛"));
+        assert_eq!(normalize_name("ＡＣＭ－ＩＣＰＣ　２０２５"), "acmicpc2025");
+    }
+
+    #[test]
+    fn classify_match_grades_confidence() {
+        assert_eq!(
+            classify_match("互联网+大赛", "互联网+大赛", None),
+            Some((MatchConfidence::Exact, 1.0))
+        );
+        assert_eq!(
+            classify_match("“互联网+”大赛", "互联网+大赛", None),
+            Some((MatchConfidence::High, 1.0))
+        );
+        assert_eq!(edit_distance("全国大学生数学建模竞赛", "全国大学生数学建模大赛"), 1);
+        assert_eq!(
+            classify_match("全国大学生数学建模竞赛", "全国大学生数学建模大赛", Some(1)).map(|m| m.0),
+            Some(MatchConfidence::Medium)
+        );
+        assert_eq!(
+            classify_match("全国大学生数学建模竞赛", "全国大学生数学建模大赛", None).map(|m| m.0),
+            Some(MatchConfidence::Low)
+        );
+        assert_eq!(classify_match("蓝桥杯", "数学建模", Some(1)), None);
+    }
+
+    #[test]
+    fn best_match_prefers_higher_confidence() {
+        let names = ["全国大学生数学建模大赛", "“互联网+”大学生创新创业大赛", "互联网+大学生创新创业大赛"];
+        let result = best_match("互联网+大学生创新创业大赛", names.iter().map(|name| (*name, *name)), None)
+            .expect("match");
+        assert_eq!(result.item, "互联网+大学生创新创业大赛");
+        assert_eq!(result.confidence, MatchConfidence::Exact);
+    }
+
+    #[test]
+    fn top_matches_orders_by_score_and_applies_threshold() {
+        let names = ["全国大学生数学建模竞赛", "美国大学生数学建模竞赛", "蓝桥杯全国软件大赛"];
+        let result = top_matches("大学生数学建模", names.iter().map(|name| (*name, *name)), 2, 0.3);
+        assert_eq!(result.len(), 2);
+        assert!(result[0].1 >= result[1].1);
+        assert!(result.iter().all(|(name, _)| name.contains("数学建模")));
+    }
+}
@@ -0,0 +1,201 @@
+//! 就绪检查：数据库、上传目录、LibreOffice 与 SMTP 的可用性。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::{config::Config, mailer};
+
+/// 单项检查的超时时间。
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub const CHECK_OK: &str = "ok";
+pub const CHECK_FAILED: &str = "failed";
+pub const CHECK_SKIPPED: &str = "skipped";
+
+/// 单项依赖的检查结果。
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyStatus {
+    /// 检查结果（ok/failed/skipped）。
+    pub status: &'static str,
+    /// 是否为必需依赖；必需依赖失败时服务不可接收流量。
+    pub required: bool,
+    /// 说明（失败原因或跳过原因）。
+    pub detail: Option<String>,
+    /// 检查耗时（毫秒）。
+    pub latency_ms: u64,
+}
+
+/// 就绪检查报告。
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessReport {
+    /// 总体状态：ready（全部通过）、degraded（仅可选依赖失败）、not_ready（必需依赖失败）。
+    pub status: &'static str,
+    /// 各依赖的检查结果。
+    pub checks: BTreeMap<&'static str, DependencyStatus>,
+}
+
+impl ReadinessReport {
+    fn from_checks(checks: BTreeMap<&'static str, DependencyStatus>) -> Self {
+        let failed = |required: bool| {
+            checks
+                .values()
+                .any(|check| check.required == required && check.status == CHECK_FAILED)
+        };
+        let status = if failed(true) {
+            "not_ready"
+        } else if failed(false) {
+            "degraded"
+        } else {
+            "ready"
+        };
+        Self { status, checks }
+    }
+
+    /// 必需依赖是否全部可用。
+    pub fn is_ready(&self) -> bool {
+        self.status != "not_ready"
+    }
+}
+
+/// 执行就绪检查；SMTP 连接较慢，仅在 `include_smtp` 时检查。
+pub async fn check_readiness(config: &Config, db: &DatabaseConnection, include_smtp: bool) -> ReadinessReport {
+    let (database, storage, libreoffice, smtp) = tokio::join!(
+        timed(true, check_database(db)),
+        timed(true, check_storage(&config.upload_dir)),
+        timed(false, check_libreoffice(&config.libreoffice_path)),
+        timed(false, check_smtp(config, include_smtp)),
+    );
+    let mut checks = BTreeMap::new();
+    checks.insert("database", database);
+    checks.insert("storage", storage);
+    checks.insert("libreoffice", libreoffice);
+    checks.insert("smtp", smtp);
+    ReadinessReport::from_checks(checks)
+}
+
+/// 检查结果：`Ok(None)` 通过，`Ok(Some(reason))` 跳过，`Err(reason)` 失败。
+type CheckResult = Result<Option<String>, String>;
+
+async fn timed(required: bool, check: impl std::future::Future<Output = CheckResult>) -> DependencyStatus {
+    let started = Instant::now();
+    let result = tokio::time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| Err("timed out".to_string()));
+    let (status, detail) = match result {
+        Ok(None) => (CHECK_OK, None),
+        Ok(Some(reason)) => (CHECK_SKIPPED, Some(reason)),
+        Err(reason) => (CHECK_FAILED, Some(reason)),
+    };
+    DependencyStatus {
+        status,
+        required,
+        detail,
+        latency_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+async fn check_database(db: &DatabaseConnection) -> CheckResult {
+    db.ping().await.map(|_| None).map_err(|err| {
+        tracing::warn!(error = %err, "readiness database ping failed");
+        "database unreachable".to_string()
+    })
+}
+
+async fn check_storage(upload_dir: &Path) -> CheckResult {
+    let probe = upload_dir.join(format!(".ready-{}", Uuid::new_v4()));
+    let result = async {
+        tokio::fs::create_dir_all(upload_dir).await?;
+        tokio::fs::write(&probe, b"ok").await?;
+        tokio::fs::remove_file(&probe).await
+    }
+    .await;
+    result.map(|_| None).map_err(|err| {
+        tracing::warn!(error = %err, "readiness storage probe failed");
+        "upload directory not writable".to_string()
+    })
+}
+
+async fn check_libreoffice(libreoffice_path: &str) -> CheckResult {
+    if libreoffice_path == "internal" {
+        return Ok(Some("internal renderer".to_string()));
+    }
+    let status = Command::new(libreoffice_path)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .status()
+        .await
+        .map_err(|_| "libreoffice not found".to_string())?;
+    if status.success() {
+        Ok(None)
+    } else {
+        Err("libreoffice exited with error".to_string())
+    }
+}
+
+async fn check_smtp(config: &Config, include_smtp: bool) -> CheckResult {
+    let Some(mail) = config.mail.as_ref() else {
+        return Ok(Some("not configured".to_string()));
+    };
+    if !include_smtp {
+        return Ok(Some("not requested".to_string()));
+    }
+    match mailer::test_connection(mail).await {
+        Ok(true) => Ok(None),
+        _ => Err("smtp unreachable".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(status: &'static str, required: bool) -> DependencyStatus {
+        DependencyStatus {
+            status,
+            required,
+            detail: None,
+            latency_ms: 0,
+        }
+    }
+
+    #[test]
+    fn report_status_depends_on_required_checks() {
+        let mut checks = BTreeMap::new();
+        checks.insert("database", status(CHECK_OK, true));
+        checks.insert("smtp", status(CHECK_SKIPPED, false));
+        assert_eq!(ReadinessReport::from_checks(checks.clone()).status, "ready");
+
+        checks.insert("libreoffice", status(CHECK_FAILED, false));
+        let report = ReadinessReport::from_checks(checks.clone());
+        assert_eq!(report.status, "degraded");
+        assert!(report.is_ready());
+
+        checks.insert("storage", status(CHECK_FAILED, true));
+        let report = ReadinessReport::from_checks(checks);
+        assert_eq!(report.status, "not_ready");
+        assert!(!report.is_ready());
+    }
+
+    #[tokio::test]
+    async fn storage_check_creates_and_cleans_probe() {
+        let dir = tempfile::tempdir().unwrap();
+        let upload_dir = dir.path().join("uploads");
+        assert_eq!(check_storage(&upload_dir).await, Ok(None));
+        assert_eq!(std::fs::read_dir(&upload_dir).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn libreoffice_check_skips_internal_and_fails_missing_binary() {
+        assert!(matches!(check_libreoffice("internal").await, Ok(Some(_))));
+        assert!(check_libreoffice("/nonexistent/soffice").await.is_err());
+    }
+}
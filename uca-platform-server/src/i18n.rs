@@ -0,0 +1,433 @@
+//! 服务端生成文本的多语言消息目录（邮件、导出表头、PDF 标签与默认模板字段名）。
+//!
+//! 文本按键查找，`{name}` 形式的占位符在格式化时替换；英文目录缺失的键回退到中文。
+//! 部署默认语言由 `LOCALE` 配置，用户可在个人中心设置自己的语言偏好。
+
+use serde::{Deserialize, Serialize};
+
+/// 支持的语言。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Locale {
+    /// 简体中文（默认）。
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    /// 英语。
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+impl Locale {
+    /// 全部支持的语言。
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::EnUs];
+
+    /// 解析语言标签，忽略大小写并接受 `zh`、`en_US` 等写法。
+    pub fn parse(value: &str) -> Option<Self> {
+        let normalized = value.trim().replace('_', "-").to_ascii_lowercase();
+        match normalized.as_str() {
+            "zh" | "zh-cn" | "zh-hans" | "zh-hans-cn" => Some(Self::ZhCn),
+            "en" | "en-us" | "en-gb" => Some(Self::EnUs),
+            _ => None,
+        }
+    }
+
+    /// 标准语言标签。
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ZhCn => "zh-CN",
+            Self::EnUs => "en-US",
+        }
+    }
+
+    /// 按键查找文本；缺失时依次回退到中文与键本身。
+    pub fn text(self, key: &'static str) -> &'static str {
+        lookup(self.catalog(), key)
+            .or_else(|| lookup(ZH_CN, key))
+            .unwrap_or(key)
+    }
+
+    /// 查找文本并替换 `{name}` 占位符；只扫描一遍，参数值中的花括号不会被再次替换。
+    pub fn format(self, key: &'static str, args: &[(&str, &str)]) -> String {
+        let template = self.text(key);
+        let mut text = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            text.push_str(&rest[..start]);
+            let tail = &rest[start + 1..];
+            let value = tail.find('}').and_then(|end| {
+                let name = &tail[..end];
+                args.iter()
+                    .find(|(arg, _)| *arg == name)
+                    .map(|(_, value)| (*value, end))
+            });
+            match value {
+                Some((value, end)) => {
+                    text.push_str(value);
+                    rest = &tail[end + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = tail;
+                }
+            }
+        }
+        text.push_str(rest);
+        text
+    }
+
+    fn catalog(self) -> Catalog {
+        match self {
+            Self::ZhCn => ZH_CN,
+            Self::EnUs => EN_US,
+        }
+    }
+}
+
+/// 按用户偏好确定语言；偏好为空或无法识别时使用部署默认语言。
+pub fn resolve_locale(preference: Option<&str>, fallback: Locale) -> Locale {
+    preference.and_then(Locale::parse).unwrap_or(fallback)
+}
+
+type Catalog = &'static [(&'static str, &'static str)];
+
+fn lookup(catalog: Catalog, key: &str) -> Option<&'static str> {
+    catalog
+        .iter()
+        .find(|(item, _)| *item == key)
+        .map(|(_, text)| *text)
+}
+
+const ZH_CN: Catalog = &[
+    ("mail.password_reset.subject", "密码重置"),
+    (
+        "mail.password_reset.body",
+        "您好，\n\n请使用以下链接重置您的密码：\n{link}\n\n该链接 {hours} 小时后失效。",
+    ),
+    ("mail.invite.subject", "账号邀请"),
+    (
+        "mail.invite.body",
+        "您好，\n\n您被邀请加入 Labor Hours Platform，请点击以下链接完成注册并绑定 TOTP 或 Passkey：\n{link}\n\n该链接 {hours} 小时后失效。",
+    ),
+    ("mail.totp_reset.subject", "TOTP 重置"),
+    (
+        "mail.totp_reset.body",
+        "您好，\n\n请点击以下链接重置您的 TOTP：\n{link}\n\n该链接 {hours} 小时后失效。",
+    ),
+    ("mail.passkey_reset.subject", "Passkey 重置"),
+    (
+        "mail.passkey_reset.body",
+        "您好，\n\n请点击以下链接重置您的 Passkey：\n{link}\n\n该链接 {hours} 小时后失效。",
+    ),
+    ("mail.review.subject", "{record_type}审核结果：{status}"),
+    (
+        "mail.review.body",
+        "{name} 同学：\n\n你提交的{record_type}「{title}」审核结果为：{status}。\n",
+    ),
+    (
+        "mail.review.footer",
+        "\n请登录平台查看详情。如不希望接收此类邮件，可在个人中心关闭审核结果邮件通知。\n",
+    ),
+    ("mail.review_reminder.subject", "待审核提醒：{count} 条记录超过 {days} 天未处理"),
+    (
+        "mail.review_reminder.body",
+        "{name}：\n\n以下由你负责的记录已等待审核超过 {days} 天，请尽快处理：\n\n",
+    ),
+    ("mail.review_reminder.footer", "\n请登录平台处理。\n"),
+    ("mail.review_digest.subject", "审核积压汇总：{count} 条记录超过 {days} 天未审核"),
+    (
+        "mail.review_digest.body",
+        "{name}：\n\n当前共有 {count} 条记录等待审核超过 {days} 天：\n\n",
+    ),
+    ("mail.review_digest.footer", "\n可在审核任务指派中查看审核人工作量并重新指派。\n"),
+    ("mail.security_alert.subject", "安全告警：{event}"),
+    (
+        "mail.security_alert.body",
+        "事件：{event}\n级别：{severity}\n用户：{user}\n说明：{detail}\n时间：{time}",
+    ),
+    ("review.title", "{record_type}「{title}」{status}"),
+    ("review.hours", "认定学时：{hours}"),
+    ("review.reason", "原因：{reason}"),
+    ("review.reason_missing", "未填写"),
+    ("review.status.first_reviewed", "初审通过"),
+    ("review.status.final_reviewed", "复审通过"),
+    ("review.status.rejected", "未通过"),
+    ("review.status.updated", "已更新"),
+    ("record_type.contest", "竞赛获奖记录"),
+    ("record_type.volunteer", "志愿服务记录"),
+    ("reminder.title", "{count} 条记录等待审核超过 {days} 天"),
+    ("reminder.digest_title", "审核积压：{count} 条记录超过 {days} 天未审核"),
+    ("reminder.stage_line", "{stage}：{count} 条，最久已等待 {waiting} 天"),
+    ("reminder.stage.first", "待初审"),
+    ("reminder.stage.final", "待复审"),
+    ("reminder.department_line", "{department}：{count} 条"),
+    ("reminder.unassigned_line", "没有可提醒的审核人：{count} 条"),
+    ("field.index", "序号"),
+    ("field.student_no", "学号"),
+    ("field.name", "姓名"),
+    ("field.gender", "性别"),
+    ("field.department", "院系"),
+    ("field.major", "专业"),
+    ("field.class_name", "班级"),
+    ("field.phone", "手机号"),
+    ("field.self_hours", "个人自评学时"),
+    ("field.approved_hours", "审核通过学时"),
+    ("field.capped_hours", "计入学时（按学年上限截断）"),
+    ("field.reason", "备注"),
+    ("field.planned_hours", "拟加学时"),
+    ("field.module_hours", "生产劳动教育模块学时（不少于4学时）"),
+    ("record.record_type", "记录类型"),
+    ("record.contest", "竞赛获奖"),
+    ("record.contest_year", "竞赛年份"),
+    ("record.contest_category", "竞赛类型"),
+    ("record.contest_name", "竞赛名称"),
+    ("record.contest_level", "竞赛级别"),
+    ("record.contest_role", "竞赛角色"),
+    ("record.award_level", "获奖等级"),
+    ("record.award_date", "获奖时间"),
+    ("record.self_hours", "自评学时"),
+    ("record.recommended_hours", "推荐学时"),
+    ("record.first_review_hours", "初审学时"),
+    ("record.final_review_hours", "复审学时"),
+    ("record.status", "状态"),
+    ("record.reason", "原因"),
+    ("record.rejection_reason", "不通过原因"),
+    ("audit.final_hours", "终审学时"),
+    ("audit.first_reviewer", "初审人"),
+    ("audit.first_signed_at", "初审签名时间"),
+    ("audit.first_signature_sha256", "初审签名 SHA-256"),
+    ("audit.final_reviewer", "复审人"),
+    ("audit.final_signed_at", "复审签名时间"),
+    ("audit.final_signature_sha256", "复审签名 SHA-256"),
+    ("audit.on_behalf_of", "{actor}（代 {delegator}）"),
+    ("export.sheet.rejected", "不通过记录"),
+    ("export.sheet.withdrawn", "已撤回记录"),
+    ("export.file_missing", "文件缺失"),
+    ("export.total", "合计"),
+    ("export.subtotal", "{class} 小计"),
+    ("pdf.record.title", "审核记录详情"),
+    ("pdf.record.continued", "审核记录详情（续页 {page}）"),
+    ("pdf.record.student", "学生: {name} ({student_no})"),
+    ("pdf.record.field", "字段"),
+    ("pdf.record.value", "内容"),
+    ("pdf.record.signatures", "审核签名"),
+    ("pdf.record.first_signature", "初审签名"),
+    ("pdf.record.final_signature", "复审签名"),
+    ("pdf.record.signature_missing", "未找到签名文件"),
+    ("pdf.record.verification", "扫描右侧二维码或访问以下链接核验本文档"),
+    ("pdf.recovery.title", "账号恢复码"),
+    ("pdf.recovery.username", "用户名: {username}"),
+    ("pdf.recovery.generated_at", "生成时间: {time}"),
+    ("pdf.recovery.notice", "每个恢复码只能使用一次；重新生成后本页全部作废。请妥善保管，勿与他人共享。"),
+    ("template.competition_library", "认可竞赛列表"),
+    ("template.students", "学生名单"),
+    ("template.contest_records", "学生获奖情况清单"),
+    ("template.labor_hours", "劳动教育学时认定表"),
+    ("template.field.year", "年份"),
+    ("template.field.year.description", "竞赛年份（可选）"),
+    ("template.field.category.description", "A/B 类"),
+    ("template.field.contest_name.description", "标准竞赛名称"),
+    ("template.field.contest_level.description", "国家级/省级/校级"),
+    ("template.field.contest_role", "负责人/成员"),
+    ("template.field.contest_role.description", "负责人/成员"),
+    ("template.field.award_date", "时间"),
+];
+
+const EN_US: Catalog = &[
+    ("mail.password_reset.subject", "Password reset"),
+    (
+        "mail.password_reset.body",
+        "Hello,\n\nUse the link below to reset your password:\n{link}\n\nThe link expires in {hours} hour(s).",
+    ),
+    ("mail.invite.subject", "Account invitation"),
+    (
+        "mail.invite.body",
+        "Hello,\n\nYou have been invited to join Labor Hours Platform. Open the link below to finish registration and bind TOTP or a passkey:\n{link}\n\nThe link expires in {hours} hour(s).",
+    ),
+    ("mail.totp_reset.subject", "TOTP reset"),
+    (
+        "mail.totp_reset.body",
+        "Hello,\n\nOpen the link below to reset your TOTP:\n{link}\n\nThe link expires in {hours} hour(s).",
+    ),
+    ("mail.passkey_reset.subject", "Passkey reset"),
+    (
+        "mail.passkey_reset.body",
+        "Hello,\n\nOpen the link below to reset your passkey:\n{link}\n\nThe link expires in {hours} hour(s).",
+    ),
+    ("mail.review.subject", "Review result for {record_type}: {status}"),
+    (
+        "mail.review.body",
+        "Dear {name},\n\nThe review result of your {record_type} \"{title}\" is: {status}.\n",
+    ),
+    (
+        "mail.review.footer",
+        "\nSign in to the platform for details. To stop receiving these emails, turn off review result emails in your profile.\n",
+    ),
+    ("mail.review_reminder.subject", "Review reminder: {count} records pending for more than {days} days"),
+    (
+        "mail.review_reminder.body",
+        "Dear {name},\n\nThe following records assigned to you have been waiting for review for more than {days} days:\n\n",
+    ),
+    ("mail.review_reminder.footer", "\nPlease sign in to the platform to review them.\n"),
+    ("mail.review_digest.subject", "Review backlog digest: {count} records pending for more than {days} days"),
+    (
+        "mail.review_digest.body",
+        "Dear {name},\n\n{count} records have been waiting for review for more than {days} days:\n\n",
+    ),
+    ("mail.review_digest.footer", "\nSee reviewer workloads under review assignments to reassign them.\n"),
+    ("mail.security_alert.subject", "Security alert: {event}"),
+    (
+        "mail.security_alert.body",
+        "Event: {event}\nSeverity: {severity}\nUser: {user}\nDetail: {detail}\nTime: {time}",
+    ),
+    ("review.title", "{record_type} \"{title}\": {status}"),
+    ("review.hours", "Approved hours: {hours}"),
+    ("review.reason", "Reason: {reason}"),
+    ("review.reason_missing", "not provided"),
+    ("review.status.first_reviewed", "passed first review"),
+    ("review.status.final_reviewed", "passed final review"),
+    ("review.status.rejected", "rejected"),
+    ("review.status.updated", "updated"),
+    ("record_type.contest", "contest award record"),
+    ("record_type.volunteer", "volunteer service record"),
+    ("reminder.title", "{count} records pending review for more than {days} days"),
+    ("reminder.digest_title", "Review backlog: {count} records pending for more than {days} days"),
+    ("reminder.stage_line", "{stage}: {count} records, oldest waiting {waiting} days"),
+    ("reminder.stage.first", "Awaiting first review"),
+    ("reminder.stage.final", "Awaiting final review"),
+    ("reminder.department_line", "{department}: {count}"),
+    ("reminder.unassigned_line", "No reviewer to remind: {count}"),
+    ("field.index", "No."),
+    ("field.student_no", "Student No."),
+    ("field.name", "Name"),
+    ("field.gender", "Gender"),
+    ("field.department", "Department"),
+    ("field.major", "Major"),
+    ("field.class_name", "Class"),
+    ("field.phone", "Phone"),
+    ("field.self_hours", "Self-reported hours"),
+    ("field.approved_hours", "Approved hours"),
+    ("field.capped_hours", "Counted hours (annual cap applied)"),
+    ("field.reason", "Remarks"),
+    ("field.planned_hours", "Planned hours"),
+    ("field.module_hours", "Productive labor module hours (at least 4)"),
+    ("record.record_type", "Record type"),
+    ("record.contest", "Contest award"),
+    ("record.contest_year", "Contest year"),
+    ("record.contest_category", "Contest category"),
+    ("record.contest_name", "Contest"),
+    ("record.contest_level", "Contest level"),
+    ("record.contest_role", "Role"),
+    ("record.award_level", "Award level"),
+    ("record.award_date", "Award date"),
+    ("record.self_hours", "Self-reported hours"),
+    ("record.recommended_hours", "Recommended hours"),
+    ("record.first_review_hours", "First review hours"),
+    ("record.final_review_hours", "Final review hours"),
+    ("record.status", "Status"),
+    ("record.reason", "Reason"),
+    ("record.rejection_reason", "Rejection reason"),
+    ("audit.final_hours", "Final approved hours"),
+    ("audit.first_reviewer", "First reviewer"),
+    ("audit.first_signed_at", "First review signed at"),
+    ("audit.first_signature_sha256", "First review signature SHA-256"),
+    ("audit.final_reviewer", "Final reviewer"),
+    ("audit.final_signed_at", "Final review signed at"),
+    ("audit.final_signature_sha256", "Final review signature SHA-256"),
+    ("audit.on_behalf_of", "{actor} (on behalf of {delegator})"),
+    ("export.sheet.rejected", "Rejected records"),
+    ("export.sheet.withdrawn", "Withdrawn records"),
+    ("export.file_missing", "file missing"),
+    ("export.total", "Total"),
+    ("export.subtotal", "{class} subtotal"),
+    ("pdf.record.title", "Review record details"),
+    ("pdf.record.continued", "Review record details (page {page})"),
+    ("pdf.record.student", "Student: {name} ({student_no})"),
+    ("pdf.record.field", "Field"),
+    ("pdf.record.value", "Value"),
+    ("pdf.record.signatures", "Review signatures"),
+    ("pdf.record.first_signature", "First review signature"),
+    ("pdf.record.final_signature", "Final review signature"),
+    ("pdf.record.signature_missing", "Signature file not found"),
+    ("pdf.record.verification", "Scan the QR code or open the link below to verify this document"),
+    ("pdf.recovery.title", "Account recovery codes"),
+    ("pdf.recovery.username", "Username: {username}"),
+    ("pdf.recovery.generated_at", "Generated at: {time}"),
+    ("pdf.recovery.notice", "Each code can be used once. Regenerating codes invalidates this page. Keep it safe and do not share it."),
+    ("template.competition_library", "Recognized competitions"),
+    ("template.students", "Student roster"),
+    ("template.contest_records", "Student award list"),
+    ("template.labor_hours", "Labor education hours certificate"),
+    ("template.field.year", "Year"),
+    ("template.field.year.description", "Contest year (optional)"),
+    ("template.field.category.description", "Category A/B"),
+    ("template.field.contest_name.description", "Standard contest name"),
+    ("template.field.contest_level.description", "National/provincial/school"),
+    ("template.field.contest_role", "Leader/member"),
+    ("template.field.contest_role.description", "Leader/member"),
+    ("template.field.award_date", "Date"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn catalogs_cover_the_same_keys_and_placeholders() {
+        for (key, zh) in ZH_CN {
+            let en = lookup(EN_US, key).unwrap_or_else(|| panic!("missing en-US text for {key}"));
+            assert_eq!(
+                placeholders(zh),
+                placeholders(en),
+                "placeholders differ for {key}"
+            );
+        }
+        for (key, _) in EN_US {
+            assert!(lookup(ZH_CN, key).is_some(), "missing zh-CN text for {key}");
+        }
+    }
+
+    #[test]
+    fn parse_accepts_common_tags() {
+        assert_eq!(Locale::parse("zh-CN"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse(" en_us "), Some(Locale::EnUs));
+        assert_eq!(Locale::parse("EN"), Some(Locale::EnUs));
+        assert_eq!(Locale::parse("fr-FR"), None);
+        for locale in Locale::ALL {
+            assert_eq!(Locale::parse(locale.as_str()), Some(locale));
+        }
+    }
+
+    #[test]
+    fn format_replaces_placeholders_and_falls_back() {
+        let text = Locale::EnUs.format("pdf.record.continued", &[("page", "2")]);
+        assert_eq!(text, "Review record details (page 2)");
+        let text = Locale::ZhCn.format(
+            "review.title",
+            &[
+                ("record_type", "竞赛获奖记录"),
+                ("title", "{status}"),
+                ("status", "初审通过"),
+            ],
+        );
+        assert_eq!(text, "竞赛获奖记录「{status}」初审通过");
+        assert_eq!(Locale::ZhCn.text("field.student_no"), "学号");
+        assert_eq!(Locale::EnUs.text("missing.key"), "missing.key");
+    }
+
+    #[test]
+    fn resolve_locale_prefers_valid_user_preference() {
+        assert_eq!(resolve_locale(Some("en-US"), Locale::ZhCn), Locale::EnUs);
+        assert_eq!(resolve_locale(Some("xx"), Locale::EnUs), Locale::EnUs);
+        assert_eq!(resolve_locale(None, Locale::ZhCn), Locale::ZhCn);
+    }
+}
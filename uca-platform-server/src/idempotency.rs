@@ -0,0 +1,387 @@
+//! 提交接口的幂等键：带 `Idempotency-Key` 头的重复提交直接回放首次响应，避免重复创建记录。
+//!
+//! 幂等键按用户隔离，仅保存摘要；首次请求处理期间的并发重试返回 409，服务端错误（5xx）不保存，允许客户端重试。
+//! 处理中的登记超过租约仍未完成（客户端断开、处理逻辑崩溃或进程重启）时视为已放弃，重试可重新登记。
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::{Duration as ChronoDuration, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter, Set,
+};
+use uuid::Uuid;
+
+use crate::{
+    access::require_session_user,
+    auth::hash_token,
+    entities::{idempotency_keys, IdempotencyKey},
+    error::AppError,
+    state::AppState,
+};
+
+/// 幂等键请求头。
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// 回放响应时附带的响应头。
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+/// 幂等键保留时长（小时）。
+pub const IDEMPOTENCY_TTL_HOURS: i64 = 24;
+/// 处理中登记的租约（分钟），超过后视为首次请求已放弃。
+pub const IDEMPOTENCY_LEASE_MINUTES: i64 = 10;
+/// 幂等键的最大长度。
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// 登记幂等键后的处理方式。
+#[derive(Debug)]
+pub enum IdempotencyClaim {
+    /// 首次出现，继续处理请求。
+    Fresh,
+    /// 已有完成的响应，直接回放。
+    Replay(idempotency_keys::Model),
+}
+
+/// 为带幂等键的提交请求回放首次响应；未带该请求头时不做处理。
+pub async fn replay_idempotent(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let key = match idempotency_key(request.headers()) {
+        Ok(Some(key)) => key,
+        Ok(None) => return next.run(request).await,
+        Err(err) => return err.into_response(),
+    };
+    let jar = CookieJar::from_headers(request.headers());
+    let user = match require_session_user(&state, &jar).await {
+        Ok(user) => user,
+        Err(err) => return err.into_response(),
+    };
+    let id = key_digest(user.id, &key);
+    let method = request.method().to_string();
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|value| value.as_str().to_string())
+        .unwrap_or_default();
+    match claim_idempotency_key(&state.db, &id, user.id, &method, &path).await {
+        Ok(IdempotencyClaim::Fresh) => {}
+        Ok(IdempotencyClaim::Replay(stored)) => {
+            return replay_response(stored).unwrap_or_else(|err| err.into_response());
+        }
+        Err(err) => return err.into_response(),
+    }
+
+    let response = next.run(request).await;
+    if response.status().is_server_error() {
+        release_idempotency_key(&state.db, &id).await;
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            release_idempotency_key(&state.db, &id).await;
+            return AppError::internal(&format!("read response body failed: {err}")).into_response();
+        }
+    };
+    let content_type = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    match std::str::from_utf8(&bytes) {
+        Ok(text) => {
+            if let Err(err) =
+                complete_idempotency_key(&state.db, &id, parts.status, content_type, text.to_string()).await
+            {
+                tracing::warn!(error = %err, "store idempotent response failed");
+                release_idempotency_key(&state.db, &id).await;
+            }
+        }
+        // 非文本响应无法回放，释放幂等键。
+        Err(_) => release_idempotency_key(&state.db, &id).await,
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// 读取并校验 `Idempotency-Key` 请求头：1-255 个可见 ASCII 字符。
+pub fn idempotency_key(headers: &HeaderMap) -> Result<Option<String>, AppError> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+    let key = value
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|key| {
+            !key.is_empty()
+                && key.len() <= MAX_IDEMPOTENCY_KEY_LEN
+                && key.chars().all(|ch| ch.is_ascii_graphic())
+        })
+        .ok_or_else(|| AppError::validation("invalid Idempotency-Key header"))?;
+    Ok(Some(key.to_string()))
+}
+
+/// 计算按用户隔离的幂等键摘要。
+pub fn key_digest(user_id: Uuid, key: &str) -> String {
+    hash_token(&format!("{user_id}:{key}"))
+}
+
+/// 登记幂等键：首次出现时写入处理中状态；已完成则返回首次响应。
+///
+/// 同一幂等键用于不同接口返回 422，首次请求仍在处理中返回 409；处理中的登记超过租约时重新登记。
+pub async fn claim_idempotency_key<C>(
+    db: &C,
+    id: &str,
+    user_id: Uuid,
+    method: &str,
+    path: &str,
+) -> Result<IdempotencyClaim, AppError>
+where
+    C: ConnectionTrait,
+{
+    let now = Utc::now();
+    if let Some(existing) = IdempotencyKey::find_by_id(id.to_string())
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+    {
+        if existing.expires_at > now {
+            if existing.method != method || existing.path != path {
+                return Err(AppError::validation("Idempotency-Key was already used for a different request"));
+            }
+            if existing.status_code.is_none() {
+                if existing.created_at > now - ChronoDuration::minutes(IDEMPOTENCY_LEASE_MINUTES) {
+                    return Err(AppError::conflict(
+                        "a request with this Idempotency-Key is still in progress",
+                        None,
+                    ));
+                }
+                return reclaim_stale_key(db, &existing, now).await;
+            }
+            return Ok(IdempotencyClaim::Replay(existing));
+        }
+        IdempotencyKey::delete_by_id(existing.id)
+            .exec(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+    }
+
+    let model = idempotency_keys::ActiveModel {
+        id: Set(id.to_string()),
+        user_id: Set(user_id),
+        method: Set(method.to_string()),
+        path: Set(path.to_string()),
+        status_code: Set(None),
+        content_type: Set(None),
+        response_body: Set(None),
+        response_hash: Set(None),
+        expires_at: Set(now + ChronoDuration::hours(IDEMPOTENCY_TTL_HOURS)),
+        created_at: Set(now),
+    };
+    // 主键冲突说明并发的重试已抢先登记。
+    if IdempotencyKey::insert(model).exec_without_returning(db).await.is_err() {
+        return Err(AppError::conflict(
+            "a request with this Idempotency-Key is still in progress",
+            None,
+        ));
+    }
+    Ok(IdempotencyClaim::Fresh)
+}
+
+/// 保存首次响应及其摘要。
+pub async fn complete_idempotency_key<C>(
+    db: &C,
+    id: &str,
+    status: StatusCode,
+    content_type: Option<String>,
+    body: String,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    let Some(existing) = IdempotencyKey::find_by_id(id.to_string())
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+    else {
+        return Ok(());
+    };
+    let mut active: idempotency_keys::ActiveModel = existing.into();
+    active.status_code = Set(Some(i32::from(status.as_u16())));
+    active.content_type = Set(content_type);
+    active.response_hash = Set(Some(hash_token(&body)));
+    active.response_body = Set(Some(body));
+    active
+        .update(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(())
+}
+
+/// 删除过期的幂等键及超过租约仍未完成的登记。
+pub async fn purge_expired_idempotency_keys<C>(db: &C) -> Result<u64, AppError>
+where
+    C: ConnectionTrait,
+{
+    let now = Utc::now();
+    let result = IdempotencyKey::delete_many()
+        .filter(
+            Condition::any()
+                .add(idempotency_keys::Column::ExpiresAt.lt(now))
+                .add(
+                    Condition::all()
+                        .add(idempotency_keys::Column::StatusCode.is_null())
+                        .add(
+                            idempotency_keys::Column::CreatedAt
+                                .lt(now - ChronoDuration::minutes(IDEMPOTENCY_LEASE_MINUTES)),
+                        ),
+                ),
+        )
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(result.rows_affected)
+}
+
+/// 重新登记已放弃的处理中幂等键；以原登记时间为条件更新，并发重试中只有一个成功。
+async fn reclaim_stale_key<C>(
+    db: &C,
+    existing: &idempotency_keys::Model,
+    now: chrono::DateTime<Utc>,
+) -> Result<IdempotencyClaim, AppError>
+where
+    C: ConnectionTrait,
+{
+    let result = IdempotencyKey::update_many()
+        .col_expr(idempotency_keys::Column::CreatedAt, Expr::value(now))
+        .col_expr(
+            idempotency_keys::Column::ExpiresAt,
+            Expr::value(now + ChronoDuration::hours(IDEMPOTENCY_TTL_HOURS)),
+        )
+        .filter(idempotency_keys::Column::Id.eq(existing.id.clone()))
+        .filter(idempotency_keys::Column::StatusCode.is_null())
+        .filter(idempotency_keys::Column::CreatedAt.eq(existing.created_at))
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if result.rows_affected != 1 {
+        return Err(AppError::conflict(
+            "a request with this Idempotency-Key is still in progress",
+            None,
+        ));
+    }
+    tracing::warn!(path = %existing.path, "reclaimed abandoned idempotency key");
+    Ok(IdempotencyClaim::Fresh)
+}
+
+async fn release_idempotency_key<C>(db: &C, id: &str)
+where
+    C: ConnectionTrait,
+{
+    if let Err(err) = IdempotencyKey::delete_by_id(id.to_string()).exec(db).await {
+        tracing::warn!(error = %err, "release idempotency key failed");
+    }
+}
+
+fn replay_response(stored: idempotency_keys::Model) -> Result<Response, AppError> {
+    let body = stored.response_body.unwrap_or_default();
+    if stored.response_hash.as_deref() != Some(hash_token(&body).as_str()) {
+        return Err(AppError::internal("stored idempotent response is corrupted"));
+    }
+    let status = stored
+        .status_code
+        .and_then(|code| u16::try_from(code).ok())
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .unwrap_or(StatusCode::OK);
+    let mut response = (status, body).into_response();
+    let headers = response.headers_mut();
+    match stored.content_type.and_then(|value| HeaderValue::from_str(&value).ok()) {
+        Some(value) => {
+            headers.insert(header::CONTENT_TYPE, value);
+        }
+        None => {
+            headers.remove(header::CONTENT_TYPE);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_support::memory_db;
+
+    #[test]
+    fn idempotency_key_header_is_validated() {
+        let mut headers = HeaderMap::new();
+        assert!(idempotency_key(&headers).unwrap().is_none());
+        headers.insert(IDEMPOTENCY_KEY_HEADER, HeaderValue::from_static(" submit-1 "));
+        assert_eq!(idempotency_key(&headers).unwrap().as_deref(), Some("submit-1"));
+        headers.insert(IDEMPOTENCY_KEY_HEADER, HeaderValue::from_static("two words"));
+        assert!(idempotency_key(&headers).is_err());
+        headers.insert(IDEMPOTENCY_KEY_HEADER, HeaderValue::from_str(&"k".repeat(256)).unwrap());
+        assert!(idempotency_key(&headers).is_err());
+    }
+
+    #[tokio::test]
+    async fn claimed_key_replays_completed_response() {
+        let db = memory_db().await;
+        let user_id = Uuid::new_v4();
+        let id = key_digest(user_id, "submit-1");
+        let claim = claim_idempotency_key(&db, &id, user_id, "POST", "/records/contest").await.unwrap();
+        assert!(matches!(claim, IdempotencyClaim::Fresh));
+        assert!(claim_idempotency_key(&db, &id, user_id, "POST", "/records/contest").await.is_err());
+
+        complete_idempotency_key(
+            &db,
+            &id,
+            StatusCode::OK,
+            Some("application/json".to_string()),
+            "{\"id\":1}".to_string(),
+        )
+        .await
+        .unwrap();
+        let claim = claim_idempotency_key(&db, &id, user_id, "POST", "/records/contest").await.unwrap();
+        let IdempotencyClaim::Replay(stored) = claim else {
+            panic!("expected replay");
+        };
+        let response = replay_response(stored).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert!(claim_idempotency_key(&db, &id, user_id, "POST", "/students/import").await.is_err());
+        assert_ne!(key_digest(Uuid::new_v4(), "submit-1"), id);
+    }
+
+    #[tokio::test]
+    async fn abandoned_claim_is_reclaimed_after_lease() {
+        let db = memory_db().await;
+        let user_id = Uuid::new_v4();
+        let id = key_digest(user_id, "submit-2");
+        let claim = claim_idempotency_key(&db, &id, user_id, "POST", "/records/contest").await.unwrap();
+        assert!(matches!(claim, IdempotencyClaim::Fresh));
+
+        // 首次请求未完成也未释放（如客户端断开后处理逻辑被丢弃）。
+        let abandoned_at = Utc::now() - ChronoDuration::minutes(IDEMPOTENCY_LEASE_MINUTES + 1);
+        let mut active: idempotency_keys::ActiveModel =
+            IdempotencyKey::find_by_id(id.clone()).one(&db).await.unwrap().unwrap().into();
+        active.created_at = Set(abandoned_at);
+        active.update(&db).await.unwrap();
+
+        let claim = claim_idempotency_key(&db, &id, user_id, "POST", "/records/contest").await.unwrap();
+        assert!(matches!(claim, IdempotencyClaim::Fresh));
+        let stored = IdempotencyKey::find_by_id(id.clone()).one(&db).await.unwrap().unwrap();
+        assert!(stored.created_at > abandoned_at);
+        assert!(stored.status_code.is_none());
+        // 重新登记后的请求同样受并发保护。
+        assert!(claim_idempotency_key(&db, &id, user_id, "POST", "/records/contest").await.is_err());
+
+        let mut active: idempotency_keys::ActiveModel = stored.into();
+        active.created_at = Set(abandoned_at);
+        active.update(&db).await.unwrap();
+        assert_eq!(purge_expired_idempotency_keys(&db).await.unwrap(), 1);
+        assert!(IdempotencyKey::find_by_id(id).one(&db).await.unwrap().is_none());
+    }
+}
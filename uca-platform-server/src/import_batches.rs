@@ -0,0 +1,204 @@
+//! 导入批次：学生名单与竞赛记录导入新建的行标记批次 ID，误导入时可按批次整体撤销。
+//!
+//! 撤销只软删除该批次新建的行；导入时更新的已有学生、自动创建的登录账号不受影响。
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, QuerySelect, Set,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    db::sum_as_i64,
+    entities::{contest_records, import_batches, students, ContestRecord, ImportBatch, Student},
+    error::AppError,
+};
+
+/// 学生名单导入。
+pub const IMPORT_KIND_STUDENT: &str = "student";
+/// 竞赛记录导入。
+pub const IMPORT_KIND_CONTEST: &str = "contest";
+
+/// 批次内的行数统计。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct BatchRowCounts {
+    /// 批次新建的行数。
+    pub created: i64,
+    /// 其中仍未删除的行数。
+    pub active: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct BatchCountRow {
+    batch_id: Uuid,
+    created: i64,
+    active: Option<i64>,
+}
+
+/// 登记导入批次，需与新建行在同一事务中调用。
+pub async fn record_import_batch<C>(db: &C, batch_id: Uuid, kind: &str, created_by: Uuid) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    ImportBatch::insert(import_batches::ActiveModel {
+        id: Set(batch_id),
+        kind: Set(kind.to_string()),
+        created_by: Set(Some(created_by)),
+        created_at: Set(Utc::now()),
+        rolled_back_at: Set(None),
+        rolled_back_by: Set(None),
+    })
+    .exec_without_returning(db)
+    .await
+    .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(())
+}
+
+/// 统计各批次新建与仍有效的行数。
+pub async fn batch_row_counts<C>(
+    db: &C,
+    kind: &str,
+    batch_ids: Vec<Uuid>,
+) -> Result<HashMap<Uuid, BatchRowCounts>, AppError>
+where
+    C: ConnectionTrait,
+{
+    if batch_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let backend = db.get_database_backend();
+    let rows = match kind {
+        IMPORT_KIND_STUDENT => {
+            Student::find()
+                .select_only()
+                .column_as(students::Column::ImportBatchId, "batch_id")
+                .column_as(Expr::col(students::Column::Id).count(), "created")
+                .column_as(
+                    sum_as_i64(backend, Expr::case(students::Column::IsDeleted.eq(false), 1).finally(0)),
+                    "active",
+                )
+                .filter(students::Column::ImportBatchId.is_in(batch_ids))
+                .group_by(students::Column::ImportBatchId)
+                .into_model::<BatchCountRow>()
+                .all(db)
+                .await
+        }
+        _ => {
+            ContestRecord::find()
+                .select_only()
+                .column_as(contest_records::Column::ImportBatchId, "batch_id")
+                .column_as(Expr::col(contest_records::Column::Id).count(), "created")
+                .column_as(
+                    sum_as_i64(backend, Expr::case(contest_records::Column::IsDeleted.eq(false), 1).finally(0)),
+                    "active",
+                )
+                .filter(contest_records::Column::ImportBatchId.is_in(batch_ids))
+                .group_by(contest_records::Column::ImportBatchId)
+                .into_model::<BatchCountRow>()
+                .all(db)
+                .await
+        }
+    }
+    .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.batch_id,
+                BatchRowCounts {
+                    created: row.created,
+                    active: row.active.unwrap_or(0),
+                },
+            )
+        })
+        .collect())
+}
+
+/// 软删除批次新建且仍有效的行，返回删除数量。
+pub async fn soft_delete_batch_rows<C>(db: &C, batch: &import_batches::Model) -> Result<u64, AppError>
+where
+    C: ConnectionTrait,
+{
+    let now = Utc::now();
+    let result = match batch.kind.as_str() {
+        IMPORT_KIND_STUDENT => {
+            Student::update_many()
+                .col_expr(students::Column::IsDeleted, Expr::value(true))
+                .col_expr(students::Column::UpdatedAt, Expr::value(now))
+                .filter(students::Column::ImportBatchId.eq(batch.id))
+                .filter(students::Column::IsDeleted.eq(false))
+                .exec(db)
+                .await
+        }
+        IMPORT_KIND_CONTEST => {
+            ContestRecord::update_many()
+                .col_expr(contest_records::Column::IsDeleted, Expr::value(true))
+                .col_expr(contest_records::Column::UpdatedAt, Expr::value(now))
+                .filter(contest_records::Column::ImportBatchId.eq(batch.id))
+                .filter(contest_records::Column::IsDeleted.eq(false))
+                .exec(db)
+                .await
+        }
+        _ => return Err(AppError::internal("unknown import batch kind")),
+    }
+    .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::ActiveModelTrait;
+
+    use crate::services::test_support::memory_db;
+
+    async fn insert_student(db: &sea_orm::DatabaseConnection, student_no: &str, batch_id: Option<Uuid>) {
+        let now = Utc::now();
+        students::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            student_no: Set(student_no.to_string()),
+            name: Set("张三".to_string()),
+            gender: Set("男".to_string()),
+            department: Set("信息学院".to_string()),
+            major: Set("软件工程".to_string()),
+            class_name: Set("软工1班".to_string()),
+            phone: Set("13800000000".to_string()),
+            import_batch_id: Set(batch_id),
+            is_deleted: Set(false),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn rollback_deletes_only_batch_rows() {
+        let db = memory_db().await;
+        let batch_id = Uuid::new_v4();
+        record_import_batch(&db, batch_id, IMPORT_KIND_STUDENT, Uuid::new_v4())
+            .await
+            .unwrap();
+        insert_student(&db, "2023001", Some(batch_id)).await;
+        insert_student(&db, "2023002", Some(batch_id)).await;
+        insert_student(&db, "2023003", None).await;
+
+        let batch = ImportBatch::find_by_id(batch_id).one(&db).await.unwrap().unwrap();
+        assert_eq!(soft_delete_batch_rows(&db, &batch).await.unwrap(), 2);
+        assert_eq!(soft_delete_batch_rows(&db, &batch).await.unwrap(), 0);
+
+        let counts = batch_row_counts(&db, IMPORT_KIND_STUDENT, vec![batch_id]).await.unwrap();
+        assert_eq!(counts[&batch_id], BatchRowCounts { created: 2, active: 0 });
+        let remaining = Student::find()
+            .filter(students::Column::IsDeleted.eq(false))
+            .all(&db)
+            .await
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].student_no, "2023003");
+    }
+}
@@ -0,0 +1,170 @@
+//! 后台导入任务的进度与取消控制。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// 每个事务批次处理的行数。
+pub const IMPORT_BATCH_SIZE: usize = 500;
+/// 导入时按学号等键批量预读已有数据，每次查询的键数量。
+pub const IMPORT_LOOKUP_CHUNK_SIZE: usize = 500;
+/// 已结束任务在内存中的保留时间。
+const FINISHED_JOB_TTL_HOURS: i64 = 24;
+
+pub const JOB_RUNNING: &str = "running";
+pub const JOB_COMPLETED: &str = "completed";
+pub const JOB_CANCELLED: &str = "cancelled";
+pub const JOB_FAILED: &str = "failed";
+
+/// 导入任务状态。
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportJobStatus {
+    /// 任务 ID。
+    pub id: Uuid,
+    /// 任务类型（如 student_import）。
+    pub kind: String,
+    /// 发起人 ID。
+    pub owner_id: Uuid,
+    /// 状态（running/completed/cancelled/failed）。
+    pub status: String,
+    /// 是否已请求取消。
+    pub cancel_requested: bool,
+    /// 总行数。
+    pub total_rows: usize,
+    /// 已提交的行数。
+    pub committed_rows: usize,
+    /// 已提交部分的统计结果。
+    pub report: serde_json::Value,
+    /// 失败原因。
+    pub error: Option<String>,
+    /// 创建时间。
+    pub created_at: DateTime<Utc>,
+    /// 结束时间。
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// 任务执行方持有的句柄，用于在批次之间检查取消标记。
+#[derive(Debug, Clone)]
+pub struct ImportJobHandle {
+    /// 任务 ID。
+    pub id: Uuid,
+    cancel: Arc<AtomicBool>,
+}
+
+impl ImportJobHandle {
+    /// 是否已请求取消。
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Debug)]
+struct ImportJobEntry {
+    status: ImportJobStatus,
+    cancel: Arc<AtomicBool>,
+}
+
+/// 导入任务的内存存储。
+#[derive(Debug, Default)]
+pub struct ImportJobStore {
+    jobs: HashMap<Uuid, ImportJobEntry>,
+}
+
+impl ImportJobStore {
+    /// 登记新任务并返回执行句柄。
+    pub fn create(&mut self, kind: &str, owner_id: Uuid, total_rows: usize) -> ImportJobHandle {
+        self.evict_finished();
+        let id = Uuid::new_v4();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let status = ImportJobStatus {
+            id,
+            kind: kind.to_string(),
+            owner_id,
+            status: JOB_RUNNING.to_string(),
+            cancel_requested: false,
+            total_rows,
+            committed_rows: 0,
+            report: serde_json::Value::Null,
+            error: None,
+            created_at: Utc::now(),
+            finished_at: None,
+        };
+        self.jobs.insert(
+            id,
+            ImportJobEntry {
+                status,
+                cancel: cancel.clone(),
+            },
+        );
+        ImportJobHandle { id, cancel }
+    }
+
+    /// 读取任务状态。
+    pub fn get(&self, id: &Uuid) -> Option<ImportJobStatus> {
+        self.jobs.get(id).map(|entry| entry.status.clone())
+    }
+
+    /// 请求取消运行中的任务；任务已结束时不做处理。
+    pub fn request_cancel(&mut self, id: &Uuid) -> Option<ImportJobStatus> {
+        let entry = self.jobs.get_mut(id)?;
+        if entry.status.status == JOB_RUNNING {
+            entry.cancel.store(true, Ordering::SeqCst);
+            entry.status.cancel_requested = true;
+        }
+        Some(entry.status.clone())
+    }
+
+    /// 记录批次提交后的进度。
+    pub fn update_progress(&mut self, id: &Uuid, committed_rows: usize, report: serde_json::Value) {
+        if let Some(entry) = self.jobs.get_mut(id) {
+            entry.status.committed_rows = committed_rows;
+            entry.status.report = report;
+        }
+    }
+
+    /// 标记任务结束。
+    pub fn finish(&mut self, id: &Uuid, status: &str, error: Option<String>) {
+        if let Some(entry) = self.jobs.get_mut(id) {
+            entry.status.status = status.to_string();
+            entry.status.error = error;
+            entry.status.finished_at = Some(Utc::now());
+        }
+    }
+
+    fn evict_finished(&mut self) {
+        let expiry = Utc::now() - Duration::hours(FINISHED_JOB_TTL_HOURS);
+        self.jobs.retain(|_, entry| {
+            entry
+                .status
+                .finished_at
+                .map(|finished_at| finished_at > expiry)
+                .unwrap_or(true)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_cancel_sets_flag_only_while_running() {
+        let mut store = ImportJobStore::default();
+        let handle = store.create("student_import", Uuid::new_v4(), 10);
+        assert!(!handle.is_cancelled());
+        let status = store.request_cancel(&handle.id).expect("job");
+        assert!(status.cancel_requested);
+        assert!(handle.is_cancelled());
+
+        let finished = store.create("student_import", Uuid::new_v4(), 10);
+        store.finish(&finished.id, JOB_COMPLETED, None);
+        let status = store.request_cancel(&finished.id).expect("job");
+        assert!(!status.cancel_requested);
+        assert!(!finished.is_cancelled());
+        assert!(store.request_cancel(&Uuid::new_v4()).is_none());
+    }
+}
@@ -0,0 +1,109 @@
+//! 后台定时维护任务。
+
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+use crate::{
+    archival::run_scheduled_archival,
+    entities::{auth_resets, invites, sessions, AuthReset, Invite, Session},
+    error::AppError,
+    idempotency::purge_expired_idempotency_keys,
+    mail_outbox::{purge_sent_mail, spawn_mail_dispatcher},
+    purge::cleanup_orphans,
+    reconcile::repair_student_users,
+    review_reminders::send_review_reminders,
+    settings::spawn_settings_refresh,
+    signed_urls::cleanup_export_artifacts,
+    state::AppState,
+    webhooks::spawn_webhook_dispatcher,
+};
+
+/// 根据配置启动后台任务。
+pub fn spawn_jobs(state: AppState) {
+    spawn_settings_refresh(state.clone());
+    spawn_webhook_dispatcher(state.clone());
+    spawn_mail_dispatcher(state.clone());
+    let jobs = state.config.jobs.clone();
+    let interval = Duration::from_secs(jobs.interval_seconds);
+    if jobs.session_purge {
+        spawn_job("session_purge", interval, state.clone(), |state| async move {
+            let sessions = purge_expired_sessions(&state).await?;
+            Ok(sessions + state.auth_flows.purge_expired().await?)
+        });
+    }
+    if jobs.token_cleanup {
+        spawn_job("token_cleanup", interval, state.clone(), |state| async move {
+            let tokens = cleanup_expired_tokens(&state).await?;
+            let artifacts = cleanup_export_artifacts(&state).await?;
+            let idempotency_keys = purge_expired_idempotency_keys(&state.db).await?;
+            Ok(tokens + artifacts + idempotency_keys + purge_sent_mail(&state).await?)
+        });
+    }
+    if jobs.orphan_attachment_cleanup {
+        spawn_job("orphan_attachment_cleanup", interval, state.clone(), |state| async move {
+            Ok(cleanup_orphans(&state, false).await?.total())
+        });
+    }
+    if jobs.student_user_repair {
+        spawn_job("student_user_repair", interval, state.clone(), |state| async move {
+            let report = repair_student_users(&state.db, false).await?;
+            Ok(report.created_users.len() as u64)
+        });
+    }
+    if jobs.review_reminder {
+        spawn_job("review_reminder", interval, state.clone(), |state| async move {
+            send_review_reminders(&state).await
+        });
+    }
+    // 是否自动归档由管理员在数据保留策略中开启。
+    spawn_job("archival", interval, state, |state| async move {
+        run_scheduled_archival(&state).await
+    });
+}
+
+fn spawn_job<F, Fut>(name: &'static str, interval: Duration, state: AppState, job: F)
+where
+    F: Fn(AppState) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<u64, AppError>> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match job(state.clone()).await {
+                Ok(affected) => tracing::info!(job = name, affected, "background job finished"),
+                Err(err) => tracing::warn!(job = name, error = %err, "background job failed"),
+            }
+        }
+    });
+}
+
+/// 删除已过期的会话。
+pub async fn purge_expired_sessions(state: &AppState) -> Result<u64, AppError> {
+    let result = Session::delete_many()
+        .filter(sessions::Column::ExpiresAt.lt(Utc::now()))
+        .exec(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(result.rows_affected)
+}
+
+/// 删除已过期的邀请与认证重置记录。
+pub async fn cleanup_expired_tokens(state: &AppState) -> Result<u64, AppError> {
+    let now = Utc::now();
+    let invites = Invite::delete_many()
+        .filter(invites::Column::ExpiresAt.lt(now))
+        .exec(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let resets = AuthReset::delete_many()
+        .filter(auth_resets::Column::ExpiresAt.lt(now))
+        .exec(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(invites.rows_affected + resets.rows_affected)
+}
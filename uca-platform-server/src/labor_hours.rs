@@ -1,14 +1,36 @@
 //! 劳动教育学时规则与计算。
 
-use chrono::Utc;
-use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::{Expr, Func, SimpleExpr},
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, JoinType, QueryFilter, QuerySelect,
+    RelationTrait, Set,
+};
+use serde::Serialize;
+use uuid::Uuid;
 
 use crate::{
-    entities::{labor_hour_rules, LaborHourRule},
+    entities::{contest_records, labor_hour_rules, ContestRecord, LaborHourRule},
     error::AppError,
+    semesters::{academic_year_of, load_semester_years},
     state::AppState,
 };
 
+/// 竞赛记录的通过学时表达式（仅终审通过的记录计入），用于 SQL 聚合。
+pub fn approved_hours_expr() -> SimpleExpr {
+    Expr::case(
+        Expr::col((contest_records::Entity, contest_records::Column::Status)).eq("final_reviewed"),
+        Func::coalesce([
+            Expr::col((contest_records::Entity, contest_records::Column::FinalReviewHours)).into(),
+            Expr::val(0).into(),
+        ]),
+    )
+    .finally(0)
+    .into()
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct LaborHourRuleConfig {
     pub base_hours_a: i32,
@@ -19,6 +41,8 @@ pub struct LaborHourRuleConfig {
     pub provincial_member_hours: i32,
     pub school_leader_hours: i32,
     pub school_member_hours: i32,
+    /// 每名学生每学年计入的通过学时上限；为空表示不限。
+    pub annual_cap_hours: Option<i32>,
 }
 
 impl Default for LaborHourRuleConfig {
@@ -32,6 +56,7 @@ impl Default for LaborHourRuleConfig {
             provincial_member_hours: 1,
             school_leader_hours: 1,
             school_member_hours: 1,
+            annual_cap_hours: None,
         }
     }
 }
@@ -51,6 +76,7 @@ pub async fn load_labor_hour_rules(state: &AppState) -> Result<LaborHourRuleConf
             provincial_member_hours: rule.provincial_member_hours,
             school_leader_hours: rule.school_leader_hours,
             school_member_hours: rule.school_member_hours,
+            annual_cap_hours: rule.annual_cap_hours,
         });
     }
     Ok(LaborHourRuleConfig::default())
@@ -75,6 +101,7 @@ pub async fn upsert_labor_hour_rules(
         active.provincial_member_hours = Set(config.provincial_member_hours);
         active.school_leader_hours = Set(config.school_leader_hours);
         active.school_member_hours = Set(config.school_member_hours);
+        active.annual_cap_hours = Set(config.annual_cap_hours);
         active.updated_at = Set(now);
         active
             .update(&state.db)
@@ -91,6 +118,7 @@ pub async fn upsert_labor_hour_rules(
             provincial_member_hours: Set(config.provincial_member_hours),
             school_leader_hours: Set(config.school_leader_hours),
             school_member_hours: Set(config.school_member_hours),
+            annual_cap_hours: Set(config.annual_cap_hours),
             created_at: Set(now),
             updated_at: Set(now),
         };
@@ -144,3 +172,491 @@ pub fn compute_recommended_hours(
 
     hours
 }
+
+/// 记录的推荐学时：优先取提交时保存的值，未保存时按当前规则计算。
+pub fn record_recommended_hours(config: LaborHourRuleConfig, record: &contest_records::Model) -> i32 {
+    record.recommended_hours.unwrap_or_else(|| {
+        compute_recommended_hours(
+            config,
+            record.contest_category.as_deref(),
+            record.contest_level.as_deref(),
+            record.contest_role.as_deref(),
+        )
+    })
+}
+
+/// 终审通过学时按学生、学年分组（学年有序）。
+pub type YearlyHours = HashMap<Uuid, BTreeMap<String, i32>>;
+
+/// 单条终审通过记录：学生 ID、所属学期、提交时间、终审学时。
+pub type ApprovedHoursRow = (Uuid, Option<Uuid>, DateTime<Utc>, Option<i32>);
+
+/// 超出每学年学时上限的提醒。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HourCapWarning {
+    /// 学生 ID。
+    pub student_id: Uuid,
+    /// 学年。
+    pub academic_year: String,
+    /// 该学年终审通过学时合计（截断前）。
+    pub approved_hours: i32,
+    /// 学时上限。
+    pub cap_hours: i32,
+}
+
+/// 按学生与学年汇总通过学时；学年取记录所属学期，未归属学期时按提交时间推算。
+pub fn group_hours_by_year<I>(rows: I, semester_years: &HashMap<Uuid, String>) -> YearlyHours
+where
+    I: IntoIterator<Item = ApprovedHoursRow>,
+{
+    let mut grouped: YearlyHours = HashMap::new();
+    for (student_id, semester_id, created_at, hours) in rows {
+        let year = semester_id
+            .and_then(|id| semester_years.get(&id).cloned())
+            .unwrap_or_else(|| academic_year_of(created_at.date_naive()));
+        *grouped.entry(student_id).or_default().entry(year).or_default() += hours.unwrap_or(0);
+    }
+    grouped
+}
+
+/// 各学年学时分别按上限截断后求和。
+pub fn capped_total(years: &BTreeMap<String, i32>, cap: Option<i32>) -> i32 {
+    years
+        .values()
+        .map(|hours| match cap {
+            Some(cap) => (*hours).min(cap),
+            None => *hours,
+        })
+        .sum()
+}
+
+/// 列出超出上限的学生学年。
+pub fn cap_warnings(yearly: &YearlyHours, cap: Option<i32>) -> Vec<HourCapWarning> {
+    let Some(cap) = cap else {
+        return Vec::new();
+    };
+    let mut warnings: Vec<HourCapWarning> = yearly
+        .iter()
+        .flat_map(|(student_id, years)| {
+            years
+                .iter()
+                .filter(|(_, hours)| **hours > cap)
+                .map(move |(year, hours)| HourCapWarning {
+                    student_id: *student_id,
+                    academic_year: year.clone(),
+                    approved_hours: *hours,
+                    cap_hours: cap,
+                })
+        })
+        .collect();
+    warnings.sort_by(|a, b| (a.student_id, &a.academic_year).cmp(&(b.student_id, &b.academic_year)));
+    warnings
+}
+
+/// 查询条件范围内（可含学生字段）的终审通过学时，按学生与学年分组。
+pub async fn approved_hours_by_year<C>(db: &C, condition: Condition) -> Result<YearlyHours, AppError>
+where
+    C: ConnectionTrait,
+{
+    let rows = ContestRecord::find()
+        .select_only()
+        .column(contest_records::Column::StudentId)
+        .column(contest_records::Column::SemesterId)
+        .column(contest_records::Column::CreatedAt)
+        .column(contest_records::Column::FinalReviewHours)
+        .join(JoinType::InnerJoin, contest_records::Relation::Student.def())
+        .filter(condition)
+        .filter(contest_records::Column::IsDeleted.eq(false))
+        .filter(contest_records::Column::Status.eq("final_reviewed"))
+        .into_tuple::<ApprovedHoursRow>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let semester_years = load_semester_years(db).await?;
+    Ok(group_hours_by_year(rows, &semester_years))
+}
+
+/// 学时台账中的单条记录。
+#[derive(Debug, Clone, Serialize)]
+pub struct HourLedgerEntry {
+    /// 记录 ID。
+    pub record_id: Uuid,
+    /// 竞赛名称。
+    pub contest_name: String,
+    /// 获奖等级。
+    pub award_level: String,
+    /// 状态。
+    pub status: String,
+    /// 所属学年。
+    pub academic_year: String,
+    /// 自评学时。
+    pub self_hours: i32,
+    /// 初审学时。
+    pub first_review_hours: Option<i32>,
+    /// 复审学时。
+    pub final_review_hours: Option<i32>,
+    /// 按当前学时规则计算的推荐学时。
+    pub recommended_hours: i32,
+    /// 计入的通过学时（仅终审通过的记录）。
+    pub approved_hours: i32,
+    /// 不通过原因。
+    pub rejection_reason: Option<String>,
+    /// 提交时间。
+    pub created_at: DateTime<Utc>,
+}
+
+/// 学时台账中的学年小计。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HourLedgerYear {
+    /// 学年。
+    pub academic_year: String,
+    /// 终审通过学时合计（截断前）。
+    pub approved_hours: i32,
+    /// 按每学年上限截断后的计入学时。
+    pub capped_hours: i32,
+}
+
+/// 单个学生的学时台账：逐条记录明细与合计；撤回记录不计入。
+#[derive(Debug, Clone, Serialize)]
+pub struct HourLedger {
+    /// 记录明细（按提交时间排序）。
+    pub records: Vec<HourLedgerEntry>,
+    /// 有通过学时的学年小计。
+    pub years: Vec<HourLedgerYear>,
+    /// 自评学时合计。
+    pub self_hours: i32,
+    /// 终审通过学时合计。
+    pub approved_hours: i32,
+    /// 按每学年上限截断后的计入学时；未配置上限时等于通过学时。
+    pub capped_hours: i32,
+    /// 每学年学时上限。
+    pub annual_cap_hours: Option<i32>,
+}
+
+impl HourLedger {
+    /// 各条不通过记录的原因。
+    pub fn rejection_reasons(&self) -> Vec<String> {
+        self.records
+            .iter()
+            .filter(|entry| entry.status == "rejected")
+            .filter_map(|entry| entry.rejection_reason.clone())
+            .collect()
+    }
+}
+
+/// 由学生的竞赛记录组装学时台账。
+pub fn build_hour_ledger(
+    records: &[contest_records::Model],
+    config: LaborHourRuleConfig,
+    semester_years: &HashMap<Uuid, String>,
+) -> HourLedger {
+    let mut records: Vec<&contest_records::Model> = records.iter().filter(|record| !record.is_deleted).collect();
+    records.sort_by_key(|record| record.created_at);
+
+    let mut approved_by_year: BTreeMap<String, i32> = BTreeMap::new();
+    let entries: Vec<HourLedgerEntry> = records
+        .into_iter()
+        .map(|record| {
+            let academic_year = record
+                .semester_id
+                .and_then(|id| semester_years.get(&id).cloned())
+                .unwrap_or_else(|| academic_year_of(record.created_at.date_naive()));
+            let approved_hours = if record.status == "final_reviewed" {
+                record.final_review_hours.unwrap_or(0)
+            } else {
+                0
+            };
+            if record.status == "final_reviewed" {
+                *approved_by_year.entry(academic_year.clone()).or_default() += approved_hours;
+            }
+            HourLedgerEntry {
+                record_id: record.id,
+                contest_name: record.contest_name.clone(),
+                award_level: record.award_level.clone(),
+                status: record.status.clone(),
+                academic_year,
+                self_hours: record.self_hours,
+                first_review_hours: record.first_review_hours,
+                final_review_hours: record.final_review_hours,
+                recommended_hours: record_recommended_hours(config, record),
+                approved_hours,
+                rejection_reason: record.rejection_reason.clone(),
+                created_at: record.created_at,
+            }
+        })
+        .collect();
+
+    let cap = config.annual_cap_hours;
+    HourLedger {
+        self_hours: entries.iter().map(|entry| entry.self_hours).sum(),
+        approved_hours: approved_by_year.values().sum(),
+        capped_hours: capped_total(&approved_by_year, cap),
+        years: approved_by_year
+            .into_iter()
+            .map(|(academic_year, approved_hours)| HourLedgerYear {
+                academic_year,
+                approved_hours,
+                capped_hours: cap.map_or(approved_hours, |cap| approved_hours.min(cap)),
+            })
+            .collect(),
+        records: entries,
+        annual_cap_hours: cap,
+    }
+}
+
+/// 读取单个学生的竞赛记录并组装学时台账。
+pub async fn load_hour_ledger(state: &AppState, student_id: Uuid) -> Result<HourLedger, AppError> {
+    let records = ContestRecord::find()
+        .filter(contest_records::Column::StudentId.eq(student_id))
+        .filter(contest_records::Column::IsDeleted.eq(false))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let config = load_labor_hour_rules(state).await?;
+    let semester_years = load_semester_years(&state.db).await?;
+    Ok(build_hour_ledger(&records, config, &semester_years))
+}
+
+/// 推荐学时重算中的单条记录。
+#[derive(Debug, Clone, Serialize)]
+pub struct RecomputedRecord {
+    /// 记录 ID。
+    pub record_id: Uuid,
+    /// 学生 ID。
+    pub student_id: Uuid,
+    /// 状态。
+    pub status: String,
+    /// 所属学年。
+    pub academic_year: String,
+    /// 重算前的初审学时。
+    pub first_review_hours: Option<i32>,
+    /// 按当前学时规则计算的推荐学时。
+    pub recommended_hours: i32,
+    /// 是否以推荐学时写入初审学时。
+    pub filled: bool,
+}
+
+/// 推荐学时重算汇总。
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HourRecomputeReport {
+    /// 参与重算的记录数。
+    pub scanned: usize,
+    /// 推荐学时与现有初审学时不一致的记录数。
+    pub changed: usize,
+    /// 写入初审学时的记录数。
+    pub filled: usize,
+    /// 写入时因记录已被并发修改而跳过的记录数。
+    pub conflicts: usize,
+    /// 按状态统计的记录数。
+    pub by_status: BTreeMap<String, usize>,
+    /// 逐条结果。
+    pub records: Vec<RecomputedRecord>,
+}
+
+/// 按当前规则重算记录的推荐学时；`fill_first_review` 时对未审核记录标记写入初审学时。
+/// `academic_years` 为空表示不限学年，学年取法与学时台账一致。
+pub fn plan_hour_recompute(
+    records: &[contest_records::Model],
+    config: LaborHourRuleConfig,
+    semester_years: &HashMap<Uuid, String>,
+    academic_years: &[String],
+    fill_first_review: bool,
+) -> HourRecomputeReport {
+    let mut report = HourRecomputeReport::default();
+    for record in records.iter().filter(|record| !record.is_deleted) {
+        let academic_year = record
+            .semester_id
+            .and_then(|id| semester_years.get(&id).cloned())
+            .unwrap_or_else(|| academic_year_of(record.created_at.date_naive()));
+        if !academic_years.is_empty() && !academic_years.contains(&academic_year) {
+            continue;
+        }
+        let recommended_hours = compute_recommended_hours(
+            config,
+            record.contest_category.as_deref(),
+            record.contest_level.as_deref(),
+            record.contest_role.as_deref(),
+        );
+        let changed = record.first_review_hours != Some(recommended_hours);
+        let filled = fill_first_review && changed && record.status == "submitted";
+        report.scanned += 1;
+        report.changed += usize::from(changed);
+        report.filled += usize::from(filled);
+        *report.by_status.entry(record.status.clone()).or_default() += 1;
+        report.records.push(RecomputedRecord {
+            record_id: record.id,
+            student_id: record.student_id,
+            status: record.status.clone(),
+            academic_year,
+            first_review_hours: record.first_review_hours,
+            recommended_hours,
+            filled,
+        });
+    }
+    report
+}
+
+/// 审核后检查相关学生是否超出每学年上限；未配置上限时不查询。
+pub async fn hour_cap_warnings(
+    state: &AppState,
+    student_ids: &[Uuid],
+) -> Result<Vec<HourCapWarning>, AppError> {
+    let config = load_labor_hour_rules(state).await?;
+    if config.annual_cap_hours.is_none() || student_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let condition = Condition::all().add(contest_records::Column::StudentId.is_in(student_ids.to_vec()));
+    let yearly = approved_hours_by_year(&state.db, condition).await?;
+    Ok(cap_warnings(&yearly, config.annual_cap_hours))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, 8, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn group_hours_prefers_semester_academic_year() {
+        let student_id = Uuid::new_v4();
+        let semester_id = Uuid::new_v4();
+        let semester_years = HashMap::from([(semester_id, "2024-2025".to_string())]);
+        let rows = vec![
+            (student_id, Some(semester_id), at(2025, 10, 1), Some(6)),
+            (student_id, None, at(2025, 10, 1), Some(4)),
+            (student_id, None, at(2026, 3, 1), None),
+        ];
+        let grouped = group_hours_by_year(rows, &semester_years);
+        let years = grouped.get(&student_id).expect("student years");
+        assert_eq!(years.get("2024-2025"), Some(&6));
+        assert_eq!(years.get("2025-2026"), Some(&4));
+    }
+
+    #[test]
+    fn capped_total_truncates_each_year() {
+        let years = BTreeMap::from([("2024-2025".to_string(), 25), ("2025-2026".to_string(), 12)]);
+        assert_eq!(capped_total(&years, None), 37);
+        assert_eq!(capped_total(&years, Some(20)), 32);
+    }
+
+    fn ledger_record(status: &str, final_hours: Option<i32>, created_at: DateTime<Utc>) -> contest_records::Model {
+        contest_records::Model {
+            id: Uuid::new_v4(),
+            student_id: Uuid::new_v4(),
+            contest_year: None,
+            contest_category: Some("A".to_string()),
+            contest_name: "数学建模".to_string(),
+            contest_level: Some("国家级".to_string()),
+            contest_role: Some("负责人".to_string()),
+            award_level: "一等奖".to_string(),
+            award_date: None,
+            self_hours: 4,
+            first_review_hours: final_hours,
+            final_review_hours: final_hours,
+            first_reviewer_id: None,
+            final_reviewer_id: None,
+            competition_id: None,
+            semester_id: None,
+            assigned_reviewer_id: None,
+            status: status.to_string(),
+            rejection_reason: (status == "rejected").then(|| "材料不全".to_string()),
+            version: 0,
+            recommended_hours: None,
+            first_reviewed_at: None,
+            final_reviewed_at: None,
+            import_batch_id: None,
+            is_deleted: false,
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    #[test]
+    fn plan_hour_recompute_fills_only_unreviewed_records() {
+        let mut stale = ledger_record("submitted", None, at(2025, 10, 1));
+        stale.first_review_hours = Some(2);
+        let mut current = ledger_record("submitted", None, at(2025, 11, 1));
+        current.first_review_hours = Some(6);
+        let records = vec![
+            stale,
+            current,
+            ledger_record("first_reviewed", Some(3), at(2025, 12, 1)),
+            ledger_record("submitted", None, at(2024, 10, 1)),
+        ];
+        let config = LaborHourRuleConfig::default();
+        let years = vec!["2025-2026".to_string()];
+
+        let report = plan_hour_recompute(&records, config, &HashMap::new(), &years, true);
+        assert_eq!((report.scanned, report.changed, report.filled), (3, 2, 1));
+        assert_eq!(report.by_status.get("submitted"), Some(&2));
+        assert!(report.records[0].filled);
+        assert_eq!(report.records[0].recommended_hours, 6);
+        assert!(!report.records[2].filled);
+
+        let report = plan_hour_recompute(&records, config, &HashMap::new(), &[], false);
+        assert_eq!((report.scanned, report.filled), (4, 0));
+    }
+
+    #[test]
+    fn build_hour_ledger_breaks_down_records_and_caps_years() {
+        let mut withdrawn = ledger_record("submitted", None, at(2025, 11, 1));
+        withdrawn.is_deleted = true;
+        let records = vec![
+            ledger_record("final_reviewed", Some(8), at(2025, 10, 1)),
+            ledger_record("rejected", None, at(2024, 10, 1)),
+            ledger_record("final_reviewed", Some(5), at(2024, 12, 1)),
+            ledger_record("first_reviewed", None, at(2026, 3, 1)),
+            withdrawn,
+        ];
+        let config = LaborHourRuleConfig {
+            annual_cap_hours: Some(6),
+            ..LaborHourRuleConfig::default()
+        };
+        let ledger = build_hour_ledger(&records, config, &HashMap::new());
+
+        assert_eq!(ledger.records.len(), 4);
+        assert_eq!(ledger.records[0].status, "rejected");
+        assert_eq!(ledger.records[0].recommended_hours, 6);
+        assert_eq!(ledger.records[3].approved_hours, 0);
+        assert_eq!((ledger.self_hours, ledger.approved_hours, ledger.capped_hours), (16, 13, 11));
+        assert_eq!(
+            ledger.years,
+            vec![
+                HourLedgerYear {
+                    academic_year: "2024-2025".to_string(),
+                    approved_hours: 5,
+                    capped_hours: 5,
+                },
+                HourLedgerYear {
+                    academic_year: "2025-2026".to_string(),
+                    approved_hours: 8,
+                    capped_hours: 6,
+                },
+            ]
+        );
+        assert_eq!(ledger.rejection_reasons(), vec!["材料不全".to_string()]);
+    }
+
+    #[test]
+    fn cap_warnings_list_only_exceeding_years() {
+        let student_id = Uuid::new_v4();
+        let yearly = HashMap::from([(
+            student_id,
+            BTreeMap::from([("2024-2025".to_string(), 25), ("2025-2026".to_string(), 20)]),
+        )]);
+        assert!(cap_warnings(&yearly, None).is_empty());
+        let warnings = cap_warnings(&yearly, Some(20));
+        assert_eq!(
+            warnings,
+            vec![HourCapWarning {
+                student_id,
+                academic_year: "2024-2025".to_string(),
+                approved_hours: 25,
+                cap_hours: 20,
+            }]
+        );
+    }
+}
@@ -2,16 +2,61 @@
 
 pub mod auth;
 pub mod access;
+pub mod announcements;
+pub mod archival;
+pub mod assignments;
+pub mod attachment_requirements;
+pub mod audit;
+pub mod auth_flows;
+pub mod authenticators;
+pub mod av_scan;
+pub mod backup;
 pub mod config;
+pub mod cors;
 pub mod db;
+pub mod delegations;
+pub mod document_verification;
 pub mod entities;
 pub mod error;
+pub mod events;
+pub mod export_profiles;
 pub mod export_template;
+pub mod fuzzy;
+pub mod health;
+pub mod i18n;
+pub mod idempotency;
+pub mod import_batches;
+pub mod import_jobs;
+pub mod jobs;
+pub mod mail_outbox;
 pub mod mailer;
 pub mod migration;
+pub mod notify;
+pub mod pdf_convert;
 pub mod policy;
+pub mod purge;
+pub mod reconcile;
+pub mod record_filter;
+pub mod request_id;
+pub mod review_reminders;
 pub mod templates;
 pub mod labor_hours;
 pub mod routes;
+pub mod secret_rotation;
+pub mod security;
+pub mod seed;
+pub mod semesters;
+pub mod services;
+pub mod session_cache;
+pub mod settings;
+pub mod signed_tokens;
+pub mod signed_urls;
+pub mod storage_usage;
+pub mod submission_windows;
+pub mod spreadsheet;
+pub mod student_bundle;
 pub mod state;
+pub mod system;
 pub mod tls;
+pub mod uploads;
+pub mod webhooks;
@@ -0,0 +1,215 @@
+//! 邮件发件箱：请求处理中只写入待发送邮件，由后台任务通过共享的 SMTP 连接池发送。
+//!
+//! 发送失败按指数退避重试，超过最大次数后标记为失败（死信），管理员可查看并手动重新排队。
+//! 取件时先延后下次尝试时间作为租约，多实例部署时同一封邮件不会被重复发送；
+//! 进程在发送途中退出的邮件会在租约到期后重新发送。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::{
+    entities::{mail_outbox, MailOutbox},
+    error::AppError,
+    mailer::{build_message, build_transport, parse_recipient},
+    state::AppState,
+};
+
+pub const OUTBOX_PENDING: &str = "pending";
+pub const OUTBOX_SENT: &str = "sent";
+pub const OUTBOX_FAILED: &str = "failed";
+
+/// 单封邮件最多尝试次数，超过后标记为失败。
+pub const MAX_MAIL_ATTEMPTS: i32 = 6;
+/// 首次重试等待时间，之后每次翻倍。
+const RETRY_BASE_SECONDS: i64 = 60;
+/// 重试等待时间上限。
+const RETRY_MAX_SECONDS: i64 = 3600;
+/// 定时扫描待发送邮件的间隔。
+const DISPATCH_INTERVAL_SECONDS: u64 = 15;
+/// 取件租约：发送中的邮件在此期间不会被其他实例取走。
+const SEND_LEASE_SECONDS: i64 = 300;
+/// 每轮最多处理的邮件数。
+const DUE_BATCH_SIZE: u64 = 50;
+/// 错误信息的最大字符数。
+const MAX_ERROR_CHARS: usize = 500;
+/// 已发送邮件的保留天数。
+pub const SENT_RETENTION_DAYS: i64 = 30;
+
+/// 将邮件写入发件箱并唤醒发送任务，返回邮件 ID。收件地址无效时返回 422。
+pub async fn enqueue_mail(state: &AppState, to: &str, subject: &str, body: &str) -> Result<Uuid, AppError> {
+    parse_recipient(to)?;
+    let now = Utc::now();
+    let id = Uuid::new_v4();
+    MailOutbox::insert(mail_outbox::ActiveModel {
+        id: Set(id),
+        recipient: Set(to.to_string()),
+        subject: Set(subject.to_string()),
+        body: Set(body.to_string()),
+        status: Set(OUTBOX_PENDING.to_string()),
+        attempts: Set(0),
+        last_error: Set(None),
+        next_attempt_at: Set(now),
+        created_at: Set(now),
+        sent_at: Set(None),
+    })
+    .exec_without_returning(&state.db)
+    .await
+    .map_err(|err| AppError::Database(err.to_string()))?;
+    state.mail_wake.notify_one();
+    Ok(id)
+}
+
+/// 第 `attempts` 次失败后的重试等待时间。
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    let seconds = RETRY_BASE_SECONDS
+        .saturating_mul(2i64.saturating_pow(exponent))
+        .min(RETRY_MAX_SECONDS);
+    chrono::Duration::seconds(seconds)
+}
+
+/// 启动邮件发送任务；未配置邮件时不启动。
+pub fn spawn_mail_dispatcher(state: AppState) {
+    let Some(config) = state.config.mail.clone() else {
+        return;
+    };
+    let transport = match build_transport(&config) {
+        Ok(transport) => Arc::new(transport),
+        Err(err) => {
+            tracing::warn!(error = %err, "build smtp transport failed");
+            return;
+        }
+    };
+    let wake = state.mail_wake.clone();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(DISPATCH_INTERVAL_SECONDS));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = wake.notified() => {}
+            }
+            if let Err(err) = send_due_mail(&state, &transport).await {
+                tracing::warn!(error = %err, "mail delivery round failed");
+            }
+        }
+    });
+}
+
+/// 发送所有到期的待发送邮件，返回本轮成功的封数。
+pub async fn send_due_mail(
+    state: &AppState,
+    transport: &AsyncSmtpTransport<Tokio1Executor>,
+) -> Result<u64, AppError> {
+    let due = MailOutbox::find()
+        .filter(mail_outbox::Column::Status.eq(OUTBOX_PENDING))
+        .filter(mail_outbox::Column::NextAttemptAt.lte(Utc::now()))
+        .order_by_asc(mail_outbox::Column::NextAttemptAt)
+        .limit(DUE_BATCH_SIZE)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let mut sent = 0;
+    for item in due {
+        if !claim(state, &item).await? {
+            continue;
+        }
+        let result = match state.mail_config() {
+            Some(config) => match build_message(&config, &item.recipient, &item.subject, &item.body) {
+                Ok(message) => transport.send(message).await.map(|_| ()).map_err(|err| err.to_string()),
+                Err(err) => Err(err.to_string()),
+            },
+            None => Err("mail not configured".to_string()),
+        };
+        if result.is_ok() {
+            sent += 1;
+        }
+        record_outcome(state, item, result).await?;
+    }
+    Ok(sent)
+}
+
+/// 以下次尝试时间为条件延后租约，更新成功才取得该邮件。
+async fn claim(state: &AppState, item: &mail_outbox::Model) -> Result<bool, AppError> {
+    let now = Utc::now();
+    let result = MailOutbox::update_many()
+        .col_expr(
+            mail_outbox::Column::NextAttemptAt,
+            Expr::value(now + chrono::Duration::seconds(SEND_LEASE_SECONDS)),
+        )
+        .filter(mail_outbox::Column::Id.eq(item.id))
+        .filter(mail_outbox::Column::Status.eq(OUTBOX_PENDING))
+        .filter(mail_outbox::Column::NextAttemptAt.eq(item.next_attempt_at))
+        .exec(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(result.rows_affected == 1)
+}
+
+async fn record_outcome(
+    state: &AppState,
+    item: mail_outbox::Model,
+    result: Result<(), String>,
+) -> Result<(), AppError> {
+    let now = Utc::now();
+    let attempts = item.attempts.saturating_add(1);
+    let mut active: mail_outbox::ActiveModel = item.into();
+    active.attempts = Set(attempts);
+    match result {
+        Ok(()) => {
+            active.status = Set(OUTBOX_SENT.to_string());
+            active.body = Set(String::new());
+            active.last_error = Set(None);
+            active.sent_at = Set(Some(now));
+        }
+        Err(error) => {
+            let status = if attempts >= MAX_MAIL_ATTEMPTS {
+                OUTBOX_FAILED
+            } else {
+                OUTBOX_PENDING
+            };
+            tracing::warn!(attempts, error = %error, "send mail failed");
+            active.status = Set(status.to_string());
+            active.last_error = Set(Some(error.chars().take(MAX_ERROR_CHARS).collect()));
+            active.next_attempt_at = Set(now + retry_delay(attempts));
+        }
+    }
+    active
+        .update(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(())
+}
+
+/// 删除超过保留期的已发送邮件。
+pub async fn purge_sent_mail(state: &AppState) -> Result<u64, AppError> {
+    let result = MailOutbox::delete_many()
+        .filter(mail_outbox::Column::Status.eq(OUTBOX_SENT))
+        .filter(mail_outbox::Column::SentAt.lt(Utc::now() - chrono::Duration::days(SENT_RETENTION_DAYS)))
+        .exec(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_grows_and_is_capped() {
+        assert_eq!(retry_delay(1), chrono::Duration::seconds(60));
+        assert_eq!(retry_delay(3), chrono::Duration::seconds(240));
+        assert_eq!(retry_delay(20), chrono::Duration::seconds(RETRY_MAX_SECONDS));
+    }
+}
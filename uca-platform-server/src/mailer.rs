@@ -1,44 +1,310 @@
-//! 邮件发送工具。
+//! 邮件构造、SMTP 连接与邮件模板；邮件统一经 [`crate::mail_outbox`] 排队发送。
+//!
+//! 邀请、重置与审核结果邮件的主题和正文可在后台按语言覆盖，模板中的 `{{name}}`
+//! 占位符在发送时替换；未覆盖时使用多语言目录中的内置文本。
+
+use std::collections::HashMap;
+use std::time::Duration;
 
 use lettre::message::{Mailbox, Message};
 use lettre::transport::smtp::authentication::Credentials;
-use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use lettre::transport::smtp::PoolConfig;
+use lettre::{AsyncSmtpTransport, Tokio1Executor};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
 
 use crate::config::MailConfig;
+use crate::entities::{mail_templates, MailTemplate};
 use crate::error::AppError;
+use crate::i18n::Locale;
+
+/// 单次 SMTP 操作超时。
+const SMTP_TIMEOUT_SECONDS: u64 = 30;
+/// SMTP 连接池大小。
+const SMTP_POOL_SIZE: u32 = 4;
+/// 邮件主题长度上限（字符）。
+pub const MAIL_SUBJECT_MAX_CHARS: usize = 200;
+/// 邮件正文长度上限（字符）。
+pub const MAIL_BODY_MAX_CHARS: usize = 20_000;
+
+/// 可在后台自定义的邮件模板。
+#[derive(Debug)]
+pub struct MailTemplateDef {
+    /// 模板 key。
+    pub key: &'static str,
+    /// 可用占位符。
+    pub variables: &'static [&'static str],
+    subject_key: &'static str,
+    body_key: &'static str,
+    /// 内置正文后追加 `{{details}}` 与该页脚。
+    footer_key: Option<&'static str>,
+}
+
+/// 账号邀请邮件。
+pub const INVITE_MAIL: MailTemplateDef = MailTemplateDef {
+    key: "invite",
+    variables: &["name", "link", "hours"],
+    subject_key: "mail.invite.subject",
+    body_key: "mail.invite.body",
+    footer_key: None,
+};
+/// 密码重置邮件。
+pub const PASSWORD_RESET_MAIL: MailTemplateDef = MailTemplateDef {
+    key: "password_reset",
+    variables: &["name", "link", "hours"],
+    subject_key: "mail.password_reset.subject",
+    body_key: "mail.password_reset.body",
+    footer_key: None,
+};
+/// TOTP 重置邮件。
+pub const TOTP_RESET_MAIL: MailTemplateDef = MailTemplateDef {
+    key: "totp_reset",
+    variables: &["name", "link", "hours"],
+    subject_key: "mail.totp_reset.subject",
+    body_key: "mail.totp_reset.body",
+    footer_key: None,
+};
+/// Passkey 重置邮件。
+pub const PASSKEY_RESET_MAIL: MailTemplateDef = MailTemplateDef {
+    key: "passkey_reset",
+    variables: &["name", "link", "hours"],
+    subject_key: "mail.passkey_reset.subject",
+    body_key: "mail.passkey_reset.body",
+    footer_key: None,
+};
+/// 审核结果邮件；`details` 为认定学时与不通过原因（每项一行）。
+pub const REVIEW_MAIL: MailTemplateDef = MailTemplateDef {
+    key: "review",
+    variables: &["name", "record_type", "title", "status", "details"],
+    subject_key: "mail.review.subject",
+    body_key: "mail.review.body",
+    footer_key: Some("mail.review.footer"),
+};
+
+/// 审核积压提醒邮件；`details` 为按审核阶段统计的待审数量（每项一行）。
+pub const REVIEW_REMINDER_MAIL: MailTemplateDef = MailTemplateDef {
+    key: "review_reminder",
+    variables: &["name", "count", "days", "details"],
+    subject_key: "mail.review_reminder.subject",
+    body_key: "mail.review_reminder.body",
+    footer_key: Some("mail.review_reminder.footer"),
+};
+/// 管理员审核积压汇总邮件；`details` 为按审核阶段与院系统计的待审数量（每项一行）。
+pub const REVIEW_DIGEST_MAIL: MailTemplateDef = MailTemplateDef {
+    key: "review_digest",
+    variables: &["name", "count", "days", "details"],
+    subject_key: "mail.review_digest.subject",
+    body_key: "mail.review_digest.body",
+    footer_key: Some("mail.review_digest.footer"),
+};
+
+/// 全部可自定义的邮件模板。
+pub const MAIL_TEMPLATES: [&MailTemplateDef; 7] = [
+    &INVITE_MAIL,
+    &PASSWORD_RESET_MAIL,
+    &TOTP_RESET_MAIL,
+    &PASSKEY_RESET_MAIL,
+    &REVIEW_MAIL,
+    &REVIEW_REMINDER_MAIL,
+    &REVIEW_DIGEST_MAIL,
+];
+
+/// 按 key 查找邮件模板。
+pub fn mail_template_def(key: &str) -> Option<&'static MailTemplateDef> {
+    MAIL_TEMPLATES.iter().copied().find(|def| def.key == key)
+}
+
+impl MailTemplateDef {
+    /// 内置主题，占位符为 `{{name}}` 形式。
+    pub fn default_subject(&self, locale: Locale) -> String {
+        self.to_placeholders(locale.text(self.subject_key))
+    }
+
+    /// 内置正文，占位符为 `{{name}}` 形式。
+    pub fn default_body(&self, locale: Locale) -> String {
+        let mut body = locale.text(self.body_key).to_string();
+        if let Some(footer_key) = self.footer_key {
+            body.push_str("{details}");
+            body.push_str(locale.text(footer_key));
+        }
+        self.to_placeholders(&body)
+    }
+
+    /// 校验模板中的占位符均为该模板支持的变量。
+    pub fn validate(&self, template: &str) -> Result<(), AppError> {
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            let tail = &rest[start + 2..];
+            let Some(end) = tail.find("}}") else {
+                break;
+            };
+            let name = tail[..end].trim();
+            if !self.variables.contains(&name) {
+                return Err(AppError::validation(&format!("unknown placeholder {{{{{name}}}}}")));
+            }
+            rest = &tail[end + 2..];
+        }
+        Ok(())
+    }
+
+    fn to_placeholders(&self, text: &str) -> String {
+        self.variables.iter().fold(text.to_string(), |text, name| {
+            text.replace(&format!("{{{name}}}"), &format!("{{{{{name}}}}}"))
+        })
+    }
+}
+
+/// 替换 `{{name}}` 占位符；只扫描一遍，参数值中的花括号不会被再次替换，未知占位符原样保留。
+pub fn render_placeholders(template: &str, args: &[(&str, &str)]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        text.push_str(&rest[..start]);
+        let tail = &rest[start + 2..];
+        let value = tail.find("}}").and_then(|end| {
+            let name = tail[..end].trim();
+            args.iter()
+                .find(|(arg, _)| *arg == name)
+                .map(|(_, value)| (*value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                text.push_str(value);
+                rest = &tail[end + 2..];
+            }
+            None => {
+                text.push_str("{{");
+                rest = tail;
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// 某个邮件模板已加载的后台覆盖，按语言索引。
+#[derive(Debug, Default)]
+pub struct MailTemplates {
+    overrides: HashMap<Locale, (String, String)>,
+}
 
-pub async fn send_mail(config: &MailConfig, to: &str, subject: &str, body: &str) -> Result<(), AppError> {
+impl MailTemplates {
+    /// 读取指定模板的全部语言覆盖。
+    pub async fn load<C>(db: &C, def: &MailTemplateDef) -> Result<Self, AppError>
+    where
+        C: ConnectionTrait,
+    {
+        let overrides = MailTemplate::find()
+            .filter(mail_templates::Column::TemplateKey.eq(def.key))
+            .all(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .into_iter()
+            .filter_map(|row| Some((Locale::parse(&row.locale)?, (row.subject, row.body))))
+            .collect();
+        Ok(Self { overrides })
+    }
+
+    /// 渲染邮件主题与正文；该语言没有覆盖时使用内置文本。
+    pub fn render(&self, def: &MailTemplateDef, locale: Locale, args: &[(&str, &str)]) -> (String, String) {
+        let (subject, body) = match self.overrides.get(&locale) {
+            Some((subject, body)) => (subject.clone(), body.clone()),
+            None => (def.default_subject(locale), def.default_body(locale)),
+        };
+        (render_placeholders(&subject, args), render_placeholders(&body, args))
+    }
+}
+
+/// 读取覆盖并渲染单封邮件的主题与正文。
+pub async fn render_mail<C>(
+    db: &C,
+    def: &MailTemplateDef,
+    locale: Locale,
+    args: &[(&str, &str)],
+) -> Result<(String, String), AppError>
+where
+    C: ConnectionTrait,
+{
+    Ok(MailTemplates::load(db, def).await?.render(def, locale, args))
+}
+
+/// 构造邮件；发件人取自邮件配置，收件地址无效时返回 422。
+pub fn build_message(config: &MailConfig, to: &str, subject: &str, body: &str) -> Result<Message, AppError> {
     let from = if let Some(name) = &config.from_name {
         Mailbox::new(Some(name.to_string()), config.from_address.parse().map_err(|_| AppError::config("invalid mail from address"))?)
     } else {
         Mailbox::new(None, config.from_address.parse().map_err(|_| AppError::config("invalid mail from address"))?)
     };
-    let to_mailbox = Mailbox::new(None, to.parse().map_err(|_| AppError::validation("invalid email"))?);
-    let message = Message::builder()
+    let to_mailbox = parse_recipient(to)?;
+    Message::builder()
         .from(from)
         .to(to_mailbox)
         .subject(subject)
         .body(body.to_string())
-        .map_err(|_| AppError::internal("failed to build email"))?;
+        .map_err(|_| AppError::internal("failed to build email"))
+}
+
+/// 解析收件地址。
+pub fn parse_recipient(to: &str) -> Result<Mailbox, AppError> {
+    Ok(Mailbox::new(None, to.parse().map_err(|_| AppError::validation("invalid email"))?))
+}
 
+/// 检查 SMTP 服务器是否可连接（用于就绪检查）。
+pub async fn test_connection(config: &MailConfig) -> Result<bool, AppError> {
+    build_transport(config)?
+        .test_connection()
+        .await
+        .map_err(|_| AppError::service_unavailable("smtp unreachable"))
+}
+
+/// 构造 SMTP 连接；同一实例内的连接由连接池复用，应在多次发送间共享。
+pub(crate) fn build_transport(config: &MailConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>, AppError> {
     let creds = Credentials::new(config.smtp_username.clone(), config.smtp_password.clone());
-    let mailer = if config.use_tls {
+    let builder = if config.use_tls {
         AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
-            .map_err(|_| AppError::config("invalid smtp host"))?
-            .port(config.smtp_port)
-            .credentials(creds)
-            .build()
     } else {
         AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)
-            .map_err(|_| AppError::config("invalid smtp host"))?
-            .port(config.smtp_port)
-            .credentials(creds)
-            .build()
-    };
+    }
+    .map_err(|_| AppError::config("invalid smtp host"))?;
+    Ok(builder
+        .port(config.smtp_port)
+        .credentials(creds)
+        .timeout(Some(Duration::from_secs(SMTP_TIMEOUT_SECONDS)))
+        .pool_config(PoolConfig::new().max_size(SMTP_POOL_SIZE))
+        .build())
+}
 
-    mailer
-        .send(message)
-        .await
-        .map_err(|_| AppError::internal("failed to send email"))?;
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_render_like_the_catalog() {
+        let args = [("name", "张三"), ("link", "https://example.edu/invite?token=x"), ("hours", "72")];
+        let (subject, body) = MailTemplates::default().render(&INVITE_MAIL, Locale::ZhCn, &args);
+        assert_eq!(subject, Locale::ZhCn.text("mail.invite.subject"));
+        assert_eq!(body, Locale::ZhCn.format("mail.invite.body", &args));
+    }
+
+    #[test]
+    fn overrides_apply_per_locale() {
+        let templates = MailTemplates {
+            overrides: HashMap::from([(
+                Locale::EnUs,
+                ("Hi {{ name }}".to_string(), "Open {{link}} within {{hours}}h {{unknown}}".to_string()),
+            )]),
+        };
+        let args = [("name", "Alice"), ("link", "{{hours}}"), ("hours", "2")];
+        let (subject, body) = templates.render(&PASSWORD_RESET_MAIL, Locale::EnUs, &args);
+        assert_eq!(subject, "Hi Alice");
+        assert_eq!(body, "Open {{hours}} within 2h {{unknown}}");
+        let (subject, _) = templates.render(&PASSWORD_RESET_MAIL, Locale::ZhCn, &args);
+        assert_eq!(subject, "密码重置");
+    }
+
+    #[test]
+    fn validate_rejects_unknown_placeholders() {
+        assert!(REVIEW_MAIL.validate("{{name}}：{{ status }}{{details}}").is_ok());
+        assert!(REVIEW_MAIL.validate("{{link}}").is_err());
+        assert!(REVIEW_MAIL.validate(&REVIEW_MAIL.default_body(Locale::EnUs)).is_ok());
+    }
 }
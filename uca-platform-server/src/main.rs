@@ -3,20 +3,22 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use axum::http::HeaderValue;
 use axum_server::tls_rustls::RustlsConfig;
 use sea_orm_migration::MigratorTrait;
-use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
 use tracing_subscriber::{fmt, EnvFilter};
-use webauthn_rs::prelude::WebauthnBuilder;
 
 use ucaplatform::{
+    auth,
     config::Config,
+    cors,
     db,
     error::AppError,
+    jobs,
     migration::Migrator,
     routes,
+    settings,
     state::AppState,
+    system,
     tls,
 };
 
@@ -28,6 +30,10 @@ async fn main() -> Result<(), AppError> {
 
     let config = Arc::new(Config::from_env()?);
 
+    if std::env::args().nth(1).as_deref() == Some("status") {
+        return print_status(&config).await;
+    }
+
     if config.developer_mode {
         tls::ensure_tls_material(&config)?;
     }
@@ -37,57 +43,13 @@ async fn main() -> Result<(), AppError> {
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
 
-    let mut builder = WebauthnBuilder::new(&config.rp_id, &config.rp_origin)
-        .map_err(|err| AppError::internal(&format!("webauthn config error: {err}")))?;
-    builder = builder.rp_name("Labor Hours Platform");
-    let webauthn = builder
-        .build()
-        .map_err(|err| AppError::internal(&format!("webauthn build error: {err}")))?;
+    let webauthn = auth::build_webauthn(&config)?;
 
     let state = AppState::new(config.clone(), db, webauthn)?;
+    settings::refresh_runtime_settings(&state).await?;
+    jobs::spawn_jobs(state.clone());
 
-    let origin = HeaderValue::from_str(config.rp_origin.as_str())
-        .map_err(|_| AppError::internal("invalid RP_ORIGIN header"))?;
-    let cors_allowed = if config.developer_mode {
-        let mut origins = vec![origin.clone()];
-        for dev_origin in [
-            "http://localhost:5173",
-            "http://127.0.0.1:5173",
-            "https://localhost:5173",
-            "https://127.0.0.1:5173",
-        ] {
-            let header = HeaderValue::from_str(dev_origin)
-                .map_err(|_| AppError::internal("invalid dev origin"))?;
-            origins.push(header);
-        }
-        AllowOrigin::list(origins)
-    } else {
-        AllowOrigin::exact(origin)
-    };
-    let cors = if config.developer_mode {
-        let cors_methods = AllowMethods::mirror_request();
-        CorsLayer::new()
-            .allow_origin(cors_allowed)
-            .allow_credentials(true)
-            .allow_headers(AllowHeaders::mirror_request())
-            .allow_methods(cors_methods)
-    } else {
-        let cors_methods = [
-            axum::http::Method::GET,
-            axum::http::Method::POST,
-            axum::http::Method::DELETE,
-            axum::http::Method::OPTIONS,
-        ];
-        CorsLayer::new()
-            .allow_origin(cors_allowed)
-            .allow_credentials(true)
-            .allow_headers([
-                axum::http::header::CONTENT_TYPE,
-                axum::http::header::AUTHORIZATION,
-            ])
-            .allow_methods(cors_methods)
-    };
-
+    let cors = cors::build_cors_layer(&config)?;
     let app = routes::router(state).layer(cors);
 
     let addr: SocketAddr = config
@@ -97,7 +59,7 @@ async fn main() -> Result<(), AppError> {
 
     if config.allow_http {
         axum_server::bind(addr)
-            .serve(app.into_make_service())
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
             .await
             .map_err(|err| AppError::internal(&format!("server error: {err}")))?;
     } else {
@@ -108,10 +70,33 @@ async fn main() -> Result<(), AppError> {
             .map_err(|err| AppError::internal(&format!("failed to configure TLS: {err}")))?;
 
         axum_server::bind_rustls(addr, tls_config)
-            .serve(app.into_make_service())
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
             .await
             .map_err(|err| AppError::internal(&format!("server error: {err}")))?;
     }
 
     Ok(())
 }
+
+/// `status` 子命令：输出构建版本、数据库迁移状态与功能开关（不执行迁移、不启动服务）。
+async fn print_status(config: &Config) -> Result<(), AppError> {
+    let db = db::connect(&config.database_url).await?;
+    // 未执行最新迁移时可能没有运行时设置表，此时按默认设置展示。
+    let runtime = settings::load_runtime_settings(&db)
+        .await
+        .map(|(runtime, _)| runtime)
+        .unwrap_or_default();
+    let reset_delivery = runtime
+        .reset_delivery
+        .unwrap_or_else(|| config.reset_delivery.clone());
+    let info = system::collect_system_info(
+        config,
+        &db,
+        &reset_delivery,
+        runtime.maintenance_mode,
+        None,
+    )
+    .await?;
+    print!("{}", system::format_system_info(&info));
+    Ok(())
+}
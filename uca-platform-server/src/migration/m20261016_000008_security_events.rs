@@ -0,0 +1,57 @@
+//! 安全事件日志表。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SecurityEvents::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(SecurityEvents::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(SecurityEvents::EventType).string().not_null())
+                    .col(ColumnDef::new(SecurityEvents::Severity).string().not_null())
+                    .col(ColumnDef::new(SecurityEvents::UserId).uuid().null())
+                    .col(ColumnDef::new(SecurityEvents::Username).string().null())
+                    .col(ColumnDef::new(SecurityEvents::ActorId).uuid().null())
+                    .col(ColumnDef::new(SecurityEvents::Detail).text().null())
+                    .col(ColumnDef::new(SecurityEvents::CreatedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_security_events_created_at")
+                    .table(SecurityEvents::Table)
+                    .col(SecurityEvents::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SecurityEvents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SecurityEvents {
+    Table,
+    Id,
+    EventType,
+    Severity,
+    UserId,
+    Username,
+    ActorId,
+    Detail,
+    CreatedAt,
+}
@@ -0,0 +1,39 @@
+//! 运行时设置表。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RuntimeSettings::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RuntimeSettings::Key).string().not_null().primary_key())
+                    .col(ColumnDef::new(RuntimeSettings::Value).text().not_null())
+                    .col(ColumnDef::new(RuntimeSettings::UpdatedBy).uuid().null())
+                    .col(ColumnDef::new(RuntimeSettings::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RuntimeSettings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RuntimeSettings {
+    Table,
+    Key,
+    Value,
+    UpdatedBy,
+    UpdatedAt,
+}
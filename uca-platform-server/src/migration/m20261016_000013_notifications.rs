@@ -0,0 +1,60 @@
+//! 站内通知表。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notifications::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Notifications::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Notifications::UserId).uuid().not_null())
+                    .col(ColumnDef::new(Notifications::Kind).string().not_null())
+                    .col(ColumnDef::new(Notifications::Title).string().not_null())
+                    .col(ColumnDef::new(Notifications::Body).text().not_null())
+                    .col(ColumnDef::new(Notifications::TargetType).string().null())
+                    .col(ColumnDef::new(Notifications::TargetId).string().null())
+                    .col(ColumnDef::new(Notifications::ReadAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(Notifications::CreatedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_notifications_user_created_at")
+                    .table(Notifications::Table)
+                    .col(Notifications::UserId)
+                    .col(Notifications::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Notifications::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Notifications {
+    Table,
+    Id,
+    UserId,
+    Kind,
+    Title,
+    Body,
+    TargetType,
+    TargetId,
+    ReadAt,
+    CreatedAt,
+}
@@ -0,0 +1,37 @@
+//! 为竞赛记录增加审核人员确认的竞赛库条目。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContestRecords::Table)
+                    .add_column(ColumnDef::new(ContestRecords::CompetitionId).uuid().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContestRecords::Table)
+                    .drop_column(ContestRecords::CompetitionId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ContestRecords {
+    Table,
+    CompetitionId,
+}
@@ -0,0 +1,124 @@
+//! 学期表，并为志愿与竞赛记录增加所属学期。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Semesters::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Semesters::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Semesters::Name).string().not_null().unique_key())
+                    .col(ColumnDef::new(Semesters::AcademicYear).string().not_null())
+                    .col(ColumnDef::new(Semesters::StartDate).date().not_null())
+                    .col(ColumnDef::new(Semesters::EndDate).date().not_null())
+                    .col(ColumnDef::new(Semesters::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(Semesters::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VolunteerRecords::Table)
+                    .add_column(ColumnDef::new(VolunteerRecords::SemesterId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContestRecords::Table)
+                    .add_column(ColumnDef::new(ContestRecords::SemesterId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_contest_records_semester_id")
+                    .table(ContestRecords::Table)
+                    .col(ContestRecords::SemesterId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_volunteer_records_semester_id")
+                    .table(VolunteerRecords::Table)
+                    .col(VolunteerRecords::SemesterId)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_volunteer_records_semester_id")
+                    .table(VolunteerRecords::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_contest_records_semester_id")
+                    .table(ContestRecords::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContestRecords::Table)
+                    .drop_column(ContestRecords::SemesterId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VolunteerRecords::Table)
+                    .drop_column(VolunteerRecords::SemesterId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Semesters::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Semesters {
+    Table,
+    Id,
+    Name,
+    AcademicYear,
+    StartDate,
+    EndDate,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum VolunteerRecords {
+    Table,
+    SemesterId,
+}
+
+#[derive(DeriveIden)]
+enum ContestRecords {
+    Table,
+    SemesterId,
+}
@@ -0,0 +1,37 @@
+//! 为劳动学时规则增加每学年学时上限。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(LaborHourRules::Table)
+                    .add_column(ColumnDef::new(LaborHourRules::AnnualCapHours).integer().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(LaborHourRules::Table)
+                    .drop_column(LaborHourRules::AnnualCapHours)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum LaborHourRules {
+    Table,
+    AnnualCapHours,
+}
@@ -0,0 +1,58 @@
+//! 为表单字段增加取值约束（数值范围与最大长度）。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(FormFields::Table)
+                    .add_column(ColumnDef::new(FormFields::MinValue).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(FormFields::Table)
+                    .add_column(ColumnDef::new(FormFields::MaxValue).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(FormFields::Table)
+                    .add_column(ColumnDef::new(FormFields::MaxLength).integer().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [FormFields::MinValue, FormFields::MaxValue, FormFields::MaxLength] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(FormFields::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum FormFields {
+    Table,
+    MinValue,
+    MaxValue,
+    MaxLength,
+}
@@ -0,0 +1,72 @@
+//! 导出模板登记信息：显示名称、说明与适用的导出类型。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExportTemplates::Table)
+                    .add_column(
+                        ColumnDef::new(ExportTemplates::DisplayName)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExportTemplates::Table)
+                    .add_column(ColumnDef::new(ExportTemplates::Description).text().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ExportTemplates::Table)
+                    .add_column(
+                        ColumnDef::new(ExportTemplates::ExportType)
+                            .string()
+                            .not_null()
+                            .default("labor_hours"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            ExportTemplates::DisplayName,
+            ExportTemplates::Description,
+            ExportTemplates::ExportType,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(ExportTemplates::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ExportTemplates {
+    Table,
+    DisplayName,
+    Description,
+    ExportType,
+}
@@ -0,0 +1,94 @@
+//! 为志愿与竞赛记录增加指派的审核人。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VolunteerRecords::Table)
+                    .add_column(ColumnDef::new(VolunteerRecords::AssignedReviewerId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContestRecords::Table)
+                    .add_column(ColumnDef::new(ContestRecords::AssignedReviewerId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_contest_records_assigned_reviewer_id")
+                    .table(ContestRecords::Table)
+                    .col(ContestRecords::AssignedReviewerId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_volunteer_records_assigned_reviewer_id")
+                    .table(VolunteerRecords::Table)
+                    .col(VolunteerRecords::AssignedReviewerId)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_volunteer_records_assigned_reviewer_id")
+                    .table(VolunteerRecords::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_contest_records_assigned_reviewer_id")
+                    .table(ContestRecords::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContestRecords::Table)
+                    .drop_column(ContestRecords::AssignedReviewerId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VolunteerRecords::Table)
+                    .drop_column(VolunteerRecords::AssignedReviewerId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum VolunteerRecords {
+    Table,
+    AssignedReviewerId,
+}
+
+#[derive(DeriveIden)]
+enum ContestRecords {
+    Table,
+    AssignedReviewerId,
+}
@@ -0,0 +1,55 @@
+//! 记录评论表：审核人员与学生围绕单条记录的沟通。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecordComments::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RecordComments::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(RecordComments::RecordType).string().not_null())
+                    .col(ColumnDef::new(RecordComments::RecordId).uuid().not_null())
+                    .col(ColumnDef::new(RecordComments::AuthorId).uuid().not_null())
+                    .col(ColumnDef::new(RecordComments::Body).text().not_null())
+                    .col(ColumnDef::new(RecordComments::CreatedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_record_comments_record")
+                    .table(RecordComments::Table)
+                    .col(RecordComments::RecordType)
+                    .col(RecordComments::RecordId)
+                    .col(RecordComments::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecordComments::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RecordComments {
+    Table,
+    Id,
+    RecordType,
+    RecordId,
+    AuthorId,
+    Body,
+    CreatedAt,
+}
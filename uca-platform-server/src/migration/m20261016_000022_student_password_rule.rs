@@ -0,0 +1,73 @@
+//! 在密码策略中保存学生默认密码规则（默认 `st` + 学号）。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 每条 ALTER TABLE 只能增加一列。
+        let columns = [
+            ColumnDef::new(PasswordPolicies::StudentPasswordPrefix)
+                .string()
+                .null()
+                .default("st")
+                .to_owned(),
+            ColumnDef::new(PasswordPolicies::StudentPasswordSuffix)
+                .string()
+                .null()
+                .to_owned(),
+            ColumnDef::new(PasswordPolicies::StudentPasswordIncludeStudentNo)
+                .boolean()
+                .not_null()
+                .default(true)
+                .to_owned(),
+            ColumnDef::new(PasswordPolicies::StudentPasswordIncludePhone)
+                .boolean()
+                .not_null()
+                .default(false)
+                .to_owned(),
+        ];
+        for column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(PasswordPolicies::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            PasswordPolicies::StudentPasswordIncludePhone,
+            PasswordPolicies::StudentPasswordIncludeStudentNo,
+            PasswordPolicies::StudentPasswordSuffix,
+            PasswordPolicies::StudentPasswordPrefix,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(PasswordPolicies::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum PasswordPolicies {
+    Table,
+    StudentPasswordPrefix,
+    StudentPasswordSuffix,
+    StudentPasswordIncludeStudentNo,
+    StudentPasswordIncludePhone,
+}
@@ -0,0 +1,59 @@
+//! 记录 Passkey 认证器的 AAGUID，用于显示认证器名称。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Passkeys::Table)
+                    .add_column(ColumnDef::new(Passkeys::Aaguid).string_len(36).null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Devices::Table)
+                    .add_column(ColumnDef::new(Devices::Aaguid).string_len(36).null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Devices::Table)
+                    .drop_column(Devices::Aaguid)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Passkeys::Table)
+                    .drop_column(Passkeys::Aaguid)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Passkeys {
+    Table,
+    Aaguid,
+}
+
+#[derive(DeriveIden)]
+enum Devices {
+    Table,
+    Aaguid,
+}
@@ -0,0 +1,114 @@
+//! 数据保留归档表：归档批次与归档行快照。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Archives::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Archives::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Archives::CohortYear).integer().not_null())
+                    .col(
+                        ColumnDef::new(Archives::StudentCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(Archives::RecordCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(Archives::PurgedAttachments)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(Archives::CreatedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(Archives::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Archives::RestoredAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(Archives::RestoredBy).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArchiveItems::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ArchiveItems::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ArchiveItems::ArchiveId).uuid().not_null())
+                    .col(ColumnDef::new(ArchiveItems::TableName).string().not_null())
+                    .col(ColumnDef::new(ArchiveItems::RowId).uuid().not_null())
+                    .col(ColumnDef::new(ArchiveItems::Data).text().not_null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_archive_items_archive")
+                    .table(ArchiveItems::Table)
+                    .col(ArchiveItems::ArchiveId)
+                    .col(ArchiveItems::TableName)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ArchiveItems::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Archives::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Archives {
+    Table,
+    Id,
+    CohortYear,
+    StudentCount,
+    RecordCount,
+    PurgedAttachments,
+    CreatedBy,
+    CreatedAt,
+    RestoredAt,
+    RestoredBy,
+}
+
+#[derive(DeriveIden)]
+enum ArchiveItems {
+    Table,
+    Id,
+    ArchiveId,
+    TableName,
+    RowId,
+    Data,
+}
@@ -0,0 +1,59 @@
+//! 为志愿与竞赛记录增加乐观锁版本号。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VolunteerRecords::Table)
+                    .add_column(ColumnDef::new(VolunteerRecords::Version).integer().not_null().default(0))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContestRecords::Table)
+                    .add_column(ColumnDef::new(ContestRecords::Version).integer().not_null().default(0))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContestRecords::Table)
+                    .drop_column(ContestRecords::Version)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VolunteerRecords::Table)
+                    .drop_column(VolunteerRecords::Version)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum VolunteerRecords {
+    Table,
+    Version,
+}
+
+#[derive(DeriveIden)]
+enum ContestRecords {
+    Table,
+    Version,
+}
@@ -0,0 +1,64 @@
+//! 班主任负责班级表：班主任账号与班级的对应关系。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AdvisorClasses::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AdvisorClasses::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AdvisorClasses::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(AdvisorClasses::ClassName)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AdvisorClasses::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_advisor_classes_user_class")
+                    .table(AdvisorClasses::Table)
+                    .col(AdvisorClasses::UserId)
+                    .col(AdvisorClasses::ClassName)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AdvisorClasses::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AdvisorClasses {
+    Table,
+    Id,
+    UserId,
+    ClassName,
+    CreatedAt,
+}
@@ -0,0 +1,84 @@
+//! 记录提交时间窗口表：按记录类型配置开放时间，可按院系覆盖。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SubmissionWindows::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SubmissionWindows::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SubmissionWindows::RecordType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SubmissionWindows::Department)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(SubmissionWindows::OpensAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SubmissionWindows::ClosesAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SubmissionWindows::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SubmissionWindows::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_submission_windows_record_type")
+                    .table(SubmissionWindows::Table)
+                    .col(SubmissionWindows::RecordType)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SubmissionWindows::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SubmissionWindows {
+    Table,
+    Id,
+    RecordType,
+    Department,
+    OpensAt,
+    ClosesAt,
+    CreatedAt,
+    UpdatedAt,
+}
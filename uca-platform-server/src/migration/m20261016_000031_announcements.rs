@@ -0,0 +1,88 @@
+//! 公告表：按生效时间、角色与院系向前端展示通知。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Announcements::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Announcements::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Announcements::Title).string().not_null())
+                    .col(ColumnDef::new(Announcements::Content).text().not_null())
+                    .col(ColumnDef::new(Announcements::Severity).string().not_null())
+                    .col(ColumnDef::new(Announcements::TargetRoles).string().null())
+                    .col(
+                        ColumnDef::new(Announcements::TargetDepartment)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(Announcements::StartsAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Announcements::EndsAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(Announcements::CreatedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(Announcements::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Announcements::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_announcements_starts_at")
+                    .table(Announcements::Table)
+                    .col(Announcements::StartsAt)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Announcements::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Announcements {
+    Table,
+    Id,
+    Title,
+    Content,
+    Severity,
+    TargetRoles,
+    TargetDepartment,
+    StartsAt,
+    EndsAt,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
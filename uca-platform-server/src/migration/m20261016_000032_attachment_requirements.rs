@@ -0,0 +1,97 @@
+//! 附件要求规则表：按记录类型、竞赛级别或自定义字段条件要求上传佐证材料。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AttachmentRequirements::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AttachmentRequirements::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AttachmentRequirements::RecordType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AttachmentRequirements::ContestLevel)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(AttachmentRequirements::FieldKey)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(AttachmentRequirements::FieldValue)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(AttachmentRequirements::MinAttachments)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(AttachmentRequirements::Description)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AttachmentRequirements::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AttachmentRequirements::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_attachment_requirements_record_type")
+                    .table(AttachmentRequirements::Table)
+                    .col(AttachmentRequirements::RecordType)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AttachmentRequirements::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AttachmentRequirements {
+    Table,
+    Id,
+    RecordType,
+    ContestLevel,
+    FieldKey,
+    FieldValue,
+    MinAttachments,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+}
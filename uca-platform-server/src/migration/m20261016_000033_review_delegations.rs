@@ -0,0 +1,101 @@
+//! 审核委托表：请假期间将指定阶段的审核权限委托给其他教职工，并在审核签名中记录委托人。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReviewDelegations::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReviewDelegations::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ReviewDelegations::DelegatorId).uuid().not_null())
+                    .col(ColumnDef::new(ReviewDelegations::DelegateId).uuid().not_null())
+                    .col(ColumnDef::new(ReviewDelegations::Stage).string().not_null())
+                    .col(
+                        ColumnDef::new(ReviewDelegations::StartsAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReviewDelegations::EndsAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReviewDelegations::RevokedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(ReviewDelegations::CreatedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(ReviewDelegations::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_review_delegations_delegate")
+                    .table(ReviewDelegations::Table)
+                    .col(ReviewDelegations::DelegateId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReviewSignatures::Table)
+                    .add_column(ColumnDef::new(ReviewSignatures::DelegatorUserId).uuid().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReviewSignatures::Table)
+                    .drop_column(ReviewSignatures::DelegatorUserId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ReviewDelegations::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReviewDelegations {
+    Table,
+    Id,
+    DelegatorId,
+    DelegateId,
+    Stage,
+    StartsAt,
+    EndsAt,
+    RevokedAt,
+    CreatedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ReviewSignatures {
+    Table,
+    DelegatorUserId,
+}
@@ -0,0 +1,63 @@
+//! 认证流程状态表：多实例部署时共享进行中的 Passkey 挑战与二次验证令牌。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuthFlowStates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuthFlowStates::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuthFlowStates::Kind).string().not_null())
+                    .col(ColumnDef::new(AuthFlowStates::Payload).text().not_null())
+                    .col(
+                        ColumnDef::new(AuthFlowStates::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AuthFlowStates::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_auth_flow_states_expires_at")
+                    .table(AuthFlowStates::Table)
+                    .col(AuthFlowStates::ExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuthFlowStates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuthFlowStates {
+    Table,
+    Id,
+    Kind,
+    Payload,
+    ExpiresAt,
+    CreatedAt,
+}
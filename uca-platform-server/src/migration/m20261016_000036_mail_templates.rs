@@ -0,0 +1,76 @@
+//! 邮件模板表：按模板与语言覆盖内置的邮件主题与正文。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MailTemplates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MailTemplates::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MailTemplates::TemplateKey)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MailTemplates::Locale).string().not_null())
+                    .col(ColumnDef::new(MailTemplates::Subject).string().not_null())
+                    .col(ColumnDef::new(MailTemplates::Body).text().not_null())
+                    .col(ColumnDef::new(MailTemplates::UpdatedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(MailTemplates::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MailTemplates::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_mail_templates_key_locale")
+                    .table(MailTemplates::Table)
+                    .col(MailTemplates::TemplateKey)
+                    .col(MailTemplates::Locale)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MailTemplates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MailTemplates {
+    Table,
+    Id,
+    TemplateKey,
+    Locale,
+    Subject,
+    Body,
+    UpdatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,79 @@
+//! 邮件发件箱：待发送与发送失败的邮件，由后台任务发送并按指数退避重试。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MailOutbox::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(MailOutbox::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(MailOutbox::Recipient).string().not_null())
+                    .col(ColumnDef::new(MailOutbox::Subject).string().not_null())
+                    .col(ColumnDef::new(MailOutbox::Body).text().not_null())
+                    .col(ColumnDef::new(MailOutbox::Status).string().not_null())
+                    .col(
+                        ColumnDef::new(MailOutbox::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(MailOutbox::LastError).string().null())
+                    .col(
+                        ColumnDef::new(MailOutbox::NextAttemptAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MailOutbox::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MailOutbox::SentAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_mail_outbox_status_next_attempt")
+                    .table(MailOutbox::Table)
+                    .col(MailOutbox::Status)
+                    .col(MailOutbox::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MailOutbox::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MailOutbox {
+    Table,
+    Id,
+    Recipient,
+    Subject,
+    Body,
+    Status,
+    Attempts,
+    LastError,
+    NextAttemptAt,
+    CreatedAt,
+    SentAt,
+}
@@ -0,0 +1,130 @@
+//! 导入批次：记录每次学生名单与竞赛记录导入，并在新建的行上标记批次，便于整批撤销。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportBatches::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ImportBatches::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(ImportBatches::Kind).string().not_null())
+                    .col(ColumnDef::new(ImportBatches::CreatedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(ImportBatches::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ImportBatches::RolledBackAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(ImportBatches::RolledBackBy).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Students::Table)
+                    .add_column(ColumnDef::new(Students::ImportBatchId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContestRecords::Table)
+                    .add_column(ColumnDef::new(ContestRecords::ImportBatchId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_students_import_batch_id")
+                    .table(Students::Table)
+                    .col(Students::ImportBatchId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_contest_records_import_batch_id")
+                    .table(ContestRecords::Table)
+                    .col(ContestRecords::ImportBatchId)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_contest_records_import_batch_id")
+                    .table(ContestRecords::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_students_import_batch_id")
+                    .table(Students::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ContestRecords::Table)
+                    .drop_column(ContestRecords::ImportBatchId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Students::Table)
+                    .drop_column(Students::ImportBatchId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ImportBatches::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ImportBatches {
+    Table,
+    Id,
+    Kind,
+    CreatedBy,
+    CreatedAt,
+    RolledBackAt,
+    RolledBackBy,
+}
+
+#[derive(DeriveIden)]
+enum Students {
+    Table,
+    ImportBatchId,
+}
+
+#[derive(DeriveIden)]
+enum ContestRecords {
+    Table,
+    ImportBatchId,
+}
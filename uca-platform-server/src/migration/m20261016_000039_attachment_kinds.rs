@@ -0,0 +1,133 @@
+//! 为附件增加类型（evidence/signature/template），并将已有审核签名补录为签名类附件。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 已有附件均为学生上传的佐证材料，由默认值回填。
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Attachments::Table)
+                    .add_column(
+                        ColumnDef::new(Attachments::Kind)
+                            .string()
+                            .not_null()
+                            .default("evidence"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_attachments_record_kind")
+                    .table(Attachments::Table)
+                    .col(Attachments::RecordType)
+                    .col(Attachments::RecordId)
+                    .col(Attachments::Kind)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 审核签名以相同 ID 补录为签名类附件，学生取自所属竞赛记录。
+        let backfill = Query::insert()
+            .into_table(Attachments::Table)
+            .columns([
+                Attachments::Id,
+                Attachments::StudentId,
+                Attachments::RecordType,
+                Attachments::RecordId,
+                Attachments::OriginalName,
+                Attachments::StoredName,
+                Attachments::MimeType,
+                Attachments::Kind,
+                Attachments::CreatedAt,
+            ])
+            .select_from(
+                Query::select()
+                    .column((ReviewSignatures::Table, ReviewSignatures::Id))
+                    .column((ContestRecords::Table, ContestRecords::StudentId))
+                    .column((ReviewSignatures::Table, ReviewSignatures::RecordType))
+                    .column((ReviewSignatures::Table, ReviewSignatures::RecordId))
+                    .expr(Expr::val("signature"))
+                    .column((ReviewSignatures::Table, ReviewSignatures::SignaturePath))
+                    .expr(Expr::val("application/octet-stream"))
+                    .expr(Expr::val("signature"))
+                    .column((ReviewSignatures::Table, ReviewSignatures::CreatedAt))
+                    .from(ReviewSignatures::Table)
+                    .inner_join(
+                        ContestRecords::Table,
+                        Expr::col((ContestRecords::Table, ContestRecords::Id))
+                            .equals((ReviewSignatures::Table, ReviewSignatures::RecordId)),
+                    )
+                    .and_where(Expr::col((ReviewSignatures::Table, ReviewSignatures::RecordType)).eq("contest"))
+                    .to_owned(),
+            )
+            .map_err(|err| DbErr::Migration(err.to_string()))?
+            .to_owned();
+        manager.exec_stmt(backfill).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(Attachments::Table)
+                    .and_where(Expr::col(Attachments::Kind).eq("signature"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_attachments_record_kind")
+                    .table(Attachments::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Attachments::Table)
+                    .drop_column(Attachments::Kind)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Attachments {
+    Table,
+    Id,
+    StudentId,
+    RecordType,
+    RecordId,
+    OriginalName,
+    StoredName,
+    MimeType,
+    Kind,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ReviewSignatures {
+    Table,
+    Id,
+    RecordType,
+    RecordId,
+    SignaturePath,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ContestRecords {
+    Table,
+    Id,
+    StudentId,
+}
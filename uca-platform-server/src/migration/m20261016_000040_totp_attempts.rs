@@ -0,0 +1,68 @@
+//! TOTP 校验状态：记录最近一次通过的时间步（防重放）与连续失败次数、锁定截止时间（退避）。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TotpSecrets::Table)
+                    .add_column(ColumnDef::new(TotpSecrets::LastUsedStep).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TotpSecrets::Table)
+                    .add_column(
+                        ColumnDef::new(TotpSecrets::FailedAttempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TotpSecrets::Table)
+                    .add_column(
+                        ColumnDef::new(TotpSecrets::LockedUntil)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [TotpSecrets::LockedUntil, TotpSecrets::FailedAttempts, TotpSecrets::LastUsedStep] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(TotpSecrets::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum TotpSecrets {
+    Table,
+    LastUsedStep,
+    FailedAttempts,
+    LockedUntil,
+}
@@ -0,0 +1,146 @@
+//! 导出列配置表：各 Excel 导出的列、顺序、标题与数字格式。
+//!
+//! 此前导出列借用表单字段（`form_type` 为 summary/student_export/labor_hours_excel）保存，
+//! 迁移时按原排序转存为导出列配置并删除这些表单字段。
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+use uuid::Uuid;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 原先借用表单字段保存的导出列类型。
+const LEGACY_EXPORT_FORM_TYPES: [&str; 3] = ["summary", "student_export", "labor_hours_excel"];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExportProfiles::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ExportProfiles::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ExportProfiles::ProfileKey)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ExportProfiles::ColumnsJson).text().not_null())
+                    .col(ColumnDef::new(ExportProfiles::UpdatedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(ExportProfiles::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExportProfiles::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_export_profiles_key")
+                    .table(ExportProfiles::Table)
+                    .col(ExportProfiles::ProfileKey)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+        let select = Query::select()
+            .columns([
+                FormFields::FormType,
+                FormFields::FieldKey,
+                FormFields::Label,
+            ])
+            .from(FormFields::Table)
+            .and_where(Expr::col(FormFields::FormType).is_in(LEGACY_EXPORT_FORM_TYPES))
+            .order_by(FormFields::FormType, Order::Asc)
+            .order_by(FormFields::OrderIndex, Order::Asc)
+            .to_owned();
+        let mut profiles: BTreeMap<String, Vec<serde_json::Value>> = BTreeMap::new();
+        for row in db.query_all(backend.build(&select)).await? {
+            let form_type: String = row.try_get("", "form_type")?;
+            let field_key: String = row.try_get("", "field_key")?;
+            let label: String = row.try_get("", "label")?;
+            let columns = profiles.entry(form_type).or_default();
+            // 表单字段未约束 key 唯一，重复的 key 只保留排序靠前的一个。
+            if columns.iter().any(|column| column["field_key"] == field_key.as_str()) {
+                continue;
+            }
+            columns.push(serde_json::json!({ "field_key": field_key, "label": label }));
+        }
+        let now = Utc::now();
+        for (profile_key, columns) in profiles {
+            let insert = Query::insert()
+                .into_table(ExportProfiles::Table)
+                .columns([
+                    ExportProfiles::Id,
+                    ExportProfiles::ProfileKey,
+                    ExportProfiles::ColumnsJson,
+                    ExportProfiles::CreatedAt,
+                    ExportProfiles::UpdatedAt,
+                ])
+                .values_panic([
+                    Uuid::new_v4().into(),
+                    profile_key.into(),
+                    serde_json::Value::Array(columns).to_string().into(),
+                    now.into(),
+                    now.into(),
+                ])
+                .to_owned();
+            manager.exec_stmt(insert).await?;
+        }
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(FormFields::Table)
+                    .and_where(Expr::col(FormFields::FormType).is_in(LEGACY_EXPORT_FORM_TYPES))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 回滚不恢复已转存的表单字段，导出将使用内置默认列。
+        manager
+            .drop_table(Table::drop().table(ExportProfiles::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ExportProfiles {
+    Table,
+    Id,
+    ProfileKey,
+    ColumnsJson,
+    UpdatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum FormFields {
+    Table,
+    FormType,
+    FieldKey,
+    Label,
+    OrderIndex,
+}
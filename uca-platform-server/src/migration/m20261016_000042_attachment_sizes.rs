@@ -0,0 +1,75 @@
+//! 为附件记录文件大小，用于存储配额与空间统计；已有附件按磁盘上的文件回填。
+
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+use uuid::Uuid;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Attachments::Table)
+                    .add_column(
+                        ColumnDef::new(Attachments::SizeBytes)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 文件已丢失的附件保持 0，不影响迁移。
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+        let select = Query::select()
+            .columns([Attachments::Id, Attachments::StoredName])
+            .from(Attachments::Table)
+            .to_owned();
+        for row in db.query_all(backend.build(&select)).await? {
+            let id: Uuid = row.try_get("", "id")?;
+            let stored_name: String = row.try_get("", "stored_name")?;
+            let Ok(metadata) = tokio::fs::metadata(&stored_name).await else {
+                continue;
+            };
+            let size = i64::try_from(metadata.len()).unwrap_or(i64::MAX);
+            if size == 0 {
+                continue;
+            }
+            manager
+                .exec_stmt(
+                    Query::update()
+                        .table(Attachments::Table)
+                        .value(Attachments::SizeBytes, size)
+                        .and_where(Expr::col(Attachments::Id).eq(id))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Attachments::Table)
+                    .drop_column(Attachments::SizeBytes)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Attachments {
+    Table,
+    Id,
+    StoredName,
+    SizeBytes,
+}
@@ -0,0 +1,73 @@
+//! 幂等键表：记录带 `Idempotency-Key` 的提交请求及其首次响应，重试时原样返回。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IdempotencyKeys::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IdempotencyKeys::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(IdempotencyKeys::UserId).uuid().not_null())
+                    .col(ColumnDef::new(IdempotencyKeys::Method).string().not_null())
+                    .col(ColumnDef::new(IdempotencyKeys::Path).string().not_null())
+                    .col(ColumnDef::new(IdempotencyKeys::StatusCode).integer().null())
+                    .col(ColumnDef::new(IdempotencyKeys::ContentType).string().null())
+                    .col(ColumnDef::new(IdempotencyKeys::ResponseBody).text().null())
+                    .col(ColumnDef::new(IdempotencyKeys::ResponseHash).string().null())
+                    .col(
+                        ColumnDef::new(IdempotencyKeys::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdempotencyKeys::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_idempotency_keys_expires_at")
+                    .table(IdempotencyKeys::Table)
+                    .col(IdempotencyKeys::ExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IdempotencyKeys::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum IdempotencyKeys {
+    Table,
+    Id,
+    UserId,
+    Method,
+    Path,
+    StatusCode,
+    ContentType,
+    ResponseBody,
+    ResponseHash,
+    ExpiresAt,
+    CreatedAt,
+}
@@ -0,0 +1,72 @@
+//! 导出文档核验表：记录导出时签发的核验令牌及文档摘要、学生姓名与认定学时快照。
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DocumentVerifications::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DocumentVerifications::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentVerifications::TokenHash)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(DocumentVerifications::DocumentType).string().not_null())
+                    .col(ColumnDef::new(DocumentVerifications::StudentId).uuid().not_null())
+                    .col(ColumnDef::new(DocumentVerifications::StudentName).string().not_null())
+                    .col(ColumnDef::new(DocumentVerifications::ApprovedHours).integer().not_null())
+                    .col(ColumnDef::new(DocumentVerifications::DocumentSha256).string().not_null())
+                    .col(ColumnDef::new(DocumentVerifications::IssuedBy).uuid().null())
+                    .col(
+                        ColumnDef::new(DocumentVerifications::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_document_verifications_student_id")
+                    .table(DocumentVerifications::Table)
+                    .col(DocumentVerifications::StudentId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DocumentVerifications::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DocumentVerifications {
+    Table,
+    Id,
+    TokenHash,
+    DocumentType,
+    StudentId,
+    StudentName,
+    ApprovedHours,
+    DocumentSha256,
+    IssuedBy,
+    CreatedAt,
+}
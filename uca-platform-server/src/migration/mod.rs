@@ -9,6 +9,43 @@ mod m20250212_000004_auth_invites_and_passwords;
 mod m20250215_000005_labor_hours_templates;
 mod m20250220_000006_user_signatures;
 mod m20260122_000007_force_password_change;
+mod m20261016_000008_security_events;
+mod m20261016_000009_audit_logs;
+mod m20261016_000010_runtime_settings;
+mod m20261016_000011_user_departments;
+mod m20261016_000012_review_email_opt_out;
+mod m20261016_000013_notifications;
+mod m20261016_000014_contest_competition_link;
+mod m20261016_000015_semesters;
+mod m20261016_000016_labor_hour_cap;
+mod m20261016_000017_form_field_options;
+mod m20261016_000018_form_field_constraints;
+mod m20261016_000019_export_template_registry;
+mod m20261016_000020_record_assignments;
+mod m20261016_000021_record_comments;
+mod m20261016_000022_student_password_rule;
+mod m20261016_000023_passkey_aaguid;
+mod m20261016_000024_webhooks;
+mod m20261016_000025_archives;
+mod m20261016_000026_user_locale;
+mod m20261016_000027_record_versions;
+mod m20261016_000028_advisor_classes;
+mod m20261016_000029_submission_windows;
+mod m20261016_000030_contest_recommended_hours;
+mod m20261016_000031_announcements;
+mod m20261016_000032_attachment_requirements;
+mod m20261016_000033_review_delegations;
+mod m20261016_000034_auth_flow_states;
+mod m20261016_000035_contest_review_times;
+mod m20261016_000036_mail_templates;
+mod m20261016_000037_mail_outbox;
+mod m20261016_000038_import_batches;
+mod m20261016_000039_attachment_kinds;
+mod m20261016_000040_totp_attempts;
+mod m20261016_000041_export_profiles;
+mod m20261016_000042_attachment_sizes;
+mod m20261016_000043_idempotency_keys;
+mod m20261016_000044_document_verifications;
 
 /// Labor Hours Platform 数据库迁移器。
 pub struct Migrator;
@@ -24,6 +61,43 @@ impl MigratorTrait for Migrator {
             Box::new(m20250215_000005_labor_hours_templates::Migration),
             Box::new(m20250220_000006_user_signatures::Migration),
             Box::new(m20260122_000007_force_password_change::Migration),
+            Box::new(m20261016_000008_security_events::Migration),
+            Box::new(m20261016_000009_audit_logs::Migration),
+            Box::new(m20261016_000010_runtime_settings::Migration),
+            Box::new(m20261016_000011_user_departments::Migration),
+            Box::new(m20261016_000012_review_email_opt_out::Migration),
+            Box::new(m20261016_000013_notifications::Migration),
+            Box::new(m20261016_000014_contest_competition_link::Migration),
+            Box::new(m20261016_000015_semesters::Migration),
+            Box::new(m20261016_000016_labor_hour_cap::Migration),
+            Box::new(m20261016_000017_form_field_options::Migration),
+            Box::new(m20261016_000018_form_field_constraints::Migration),
+            Box::new(m20261016_000019_export_template_registry::Migration),
+            Box::new(m20261016_000020_record_assignments::Migration),
+            Box::new(m20261016_000021_record_comments::Migration),
+            Box::new(m20261016_000022_student_password_rule::Migration),
+            Box::new(m20261016_000023_passkey_aaguid::Migration),
+            Box::new(m20261016_000024_webhooks::Migration),
+            Box::new(m20261016_000025_archives::Migration),
+            Box::new(m20261016_000026_user_locale::Migration),
+            Box::new(m20261016_000027_record_versions::Migration),
+            Box::new(m20261016_000028_advisor_classes::Migration),
+            Box::new(m20261016_000029_submission_windows::Migration),
+            Box::new(m20261016_000030_contest_recommended_hours::Migration),
+            Box::new(m20261016_000031_announcements::Migration),
+            Box::new(m20261016_000032_attachment_requirements::Migration),
+            Box::new(m20261016_000033_review_delegations::Migration),
+            Box::new(m20261016_000034_auth_flow_states::Migration),
+            Box::new(m20261016_000035_contest_review_times::Migration),
+            Box::new(m20261016_000036_mail_templates::Migration),
+            Box::new(m20261016_000037_mail_outbox::Migration),
+            Box::new(m20261016_000038_import_batches::Migration),
+            Box::new(m20261016_000039_attachment_kinds::Migration),
+            Box::new(m20261016_000040_totp_attempts::Migration),
+            Box::new(m20261016_000041_export_profiles::Migration),
+            Box::new(m20261016_000042_attachment_sizes::Migration),
+            Box::new(m20261016_000043_idempotency_keys::Migration),
+            Box::new(m20261016_000044_document_verifications::Migration),
         ]
     }
 }
@@ -0,0 +1,413 @@
+//! 通知：站内通知的写入，以及审核结果邮件。
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use uuid::Uuid;
+
+use crate::{
+    entities::{contest_records, notifications, students, users, volunteer_records, Notification, Student, User},
+    error::AppError,
+    i18n::Locale,
+    mail_outbox::enqueue_mail,
+    mailer::{MailTemplates, REVIEW_MAIL},
+    services::review::{STATUS_FINAL_REVIEWED, STATUS_FIRST_REVIEWED, STATUS_REJECTED},
+    state::AppState,
+};
+
+pub const KIND_RECORD_REVIEWED: &str = "record_reviewed";
+pub const KIND_RECORD_REJECTED: &str = "record_rejected";
+pub const KIND_PASSWORD_RESET: &str = "password_reset";
+pub const KIND_IMPORT_COMPLETED: &str = "import_completed";
+pub const KIND_RECORD_COMMENT: &str = "record_comment";
+pub const KIND_REVIEW_REMINDER: &str = "review_reminder";
+pub const KIND_REVIEW_DIGEST: &str = "review_digest";
+
+/// 待写入的站内通知。
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    /// 接收用户 ID。
+    pub user_id: Uuid,
+    /// 通知类型。
+    pub kind: &'static str,
+    /// 标题。
+    pub title: String,
+    /// 正文。
+    pub body: String,
+    /// 关联对象类型（contest/volunteer/import_job 等）。
+    pub target_type: Option<&'static str>,
+    /// 关联对象 ID。
+    pub target_id: Option<String>,
+}
+
+impl NewNotification {
+    /// 创建不关联对象的通知。
+    pub fn new(user_id: Uuid, kind: &'static str, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            user_id,
+            kind,
+            title: title.into(),
+            body: body.into(),
+            target_type: None,
+            target_id: None,
+        }
+    }
+
+    /// 设置关联对象。
+    pub fn target(mut self, target_type: &'static str, target_id: impl ToString) -> Self {
+        self.target_type = Some(target_type);
+        self.target_id = Some(target_id.to_string());
+        self
+    }
+}
+
+/// 写入站内通知（可在事务内调用）。
+pub async fn create_notifications<C>(db: &C, items: Vec<NewNotification>) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    if items.is_empty() {
+        return Ok(());
+    }
+    let now = Utc::now();
+    let models = items.into_iter().map(|item| notifications::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(item.user_id),
+        kind: Set(item.kind.to_string()),
+        title: Set(item.title),
+        body: Set(item.body),
+        target_type: Set(item.target_type.map(|value| value.to_string())),
+        target_id: Set(item.target_id),
+        read_at: Set(None),
+        created_at: Set(now),
+    });
+    Notification::insert_many(models)
+        .exec_without_returning(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(())
+}
+
+/// 写入站内通知；失败只记日志，不影响业务请求。
+pub async fn push_notifications(state: &AppState, items: Vec<NewNotification>) {
+    if let Err(err) = create_notifications(&state.db, items).await {
+        tracing::warn!(error = %err, "create notifications failed");
+    }
+}
+
+/// 一条记录的审核结果。
+#[derive(Debug, Clone)]
+pub struct ReviewNotice {
+    /// 记录 ID。
+    pub record_id: Uuid,
+    /// 学生 ID。
+    pub student_id: Uuid,
+    /// 记录类型（contest/volunteer）。
+    pub record_type: &'static str,
+    /// 记录标题（竞赛名称或志愿服务标题）。
+    pub title: String,
+    /// 审核后状态。
+    pub status: String,
+    /// 本次审核认定的学时。
+    pub hours: Option<i32>,
+    /// 不通过原因。
+    pub rejection_reason: Option<String>,
+}
+
+impl ReviewNotice {
+    /// 由竞赛记录生成通知。
+    pub fn from_contest(model: &contest_records::Model) -> Self {
+        Self {
+            record_id: model.id,
+            student_id: model.student_id,
+            record_type: "contest",
+            title: model.contest_name.clone(),
+            status: model.status.clone(),
+            hours: stage_hours(&model.status, model.first_review_hours, model.final_review_hours),
+            rejection_reason: model.rejection_reason.clone(),
+        }
+    }
+
+    /// 由志愿服务记录生成通知。
+    pub fn from_volunteer(model: &volunteer_records::Model) -> Self {
+        Self {
+            record_id: model.id,
+            student_id: model.student_id,
+            record_type: "volunteer",
+            title: model.title.clone(),
+            status: model.status.clone(),
+            hours: stage_hours(&model.status, model.first_review_hours, model.final_review_hours),
+            rejection_reason: model.rejection_reason.clone(),
+        }
+    }
+}
+
+fn stage_hours(status: &str, first: Option<i32>, final_hours: Option<i32>) -> Option<i32> {
+    match status {
+        STATUS_FIRST_REVIEWED => first,
+        STATUS_FINAL_REVIEWED => final_hours,
+        _ => None,
+    }
+}
+
+fn status_label(locale: Locale, status: &str) -> &'static str {
+    locale.text(match status {
+        STATUS_FIRST_REVIEWED => "review.status.first_reviewed",
+        STATUS_FINAL_REVIEWED => "review.status.final_reviewed",
+        STATUS_REJECTED => "review.status.rejected",
+        _ => "review.status.updated",
+    })
+}
+
+fn record_type_label(locale: Locale, record_type: &str) -> &'static str {
+    if record_type == "volunteer" {
+        locale.text("record_type.volunteer")
+    } else {
+        locale.text("record_type.contest")
+    }
+}
+
+fn rejection_reason(locale: Locale, notice: &ReviewNotice) -> String {
+    let reason = notice
+        .rejection_reason
+        .as_deref()
+        .unwrap_or_else(|| locale.text("review.reason_missing"));
+    locale.format("review.reason", &[("reason", reason)])
+}
+
+/// 生成审核结果邮件的主题与正文。
+pub fn format_review_email(
+    templates: &MailTemplates,
+    locale: Locale,
+    student_name: &str,
+    notice: &ReviewNotice,
+) -> (String, String) {
+    let mut details = String::new();
+    if let Some(hours) = notice.hours {
+        details.push_str(&locale.format("review.hours", &[("hours", &hours.to_string())]));
+        details.push('\n');
+    }
+    if notice.status == STATUS_REJECTED {
+        details.push_str(&rejection_reason(locale, notice));
+        details.push('\n');
+    }
+    templates.render(
+        &REVIEW_MAIL,
+        locale,
+        &[
+            ("name", student_name),
+            ("record_type", record_type_label(locale, notice.record_type)),
+            ("title", &notice.title),
+            ("status", status_label(locale, &notice.status)),
+            ("details", &details),
+        ],
+    )
+}
+
+/// 生成审核结果站内通知。
+pub fn review_notification(locale: Locale, user_id: Uuid, notice: &ReviewNotice) -> NewNotification {
+    let kind = if notice.status == STATUS_REJECTED {
+        KIND_RECORD_REJECTED
+    } else {
+        KIND_RECORD_REVIEWED
+    };
+    let title = locale.format(
+        "review.title",
+        &[
+            ("record_type", record_type_label(locale, notice.record_type)),
+            ("title", &notice.title),
+            ("status", status_label(locale, &notice.status)),
+        ],
+    );
+    let body = match (&notice.status[..], notice.hours) {
+        (STATUS_REJECTED, _) => rejection_reason(locale, notice),
+        (_, Some(hours)) => locale.format("review.hours", &[("hours", &hours.to_string())]),
+        _ => String::new(),
+    };
+    NewNotification::new(user_id, kind, title, body).target(notice.record_type, notice.record_id)
+}
+
+/// 审核结果通知的接收人。
+struct ReviewRecipient {
+    name: String,
+    user_id: Uuid,
+    /// 绑定且未关闭审核邮件时的邮箱。
+    email: Option<String>,
+    /// 通知与邮件使用的语言。
+    locale: Locale,
+}
+
+/// 向学生发送审核结果：写入站内通知，并向绑定邮箱发送邮件。
+/// 未配置邮件、未绑定邮箱或已关闭邮件通知的学生只收到站内通知。
+/// 邮件写入发件箱后由后台任务发送，写入失败只记日志，不影响审核请求。
+pub async fn notify_review_results(state: &AppState, notices: Vec<ReviewNotice>) {
+    if notices.is_empty() {
+        return;
+    }
+    let recipients = match load_recipients(state, &notices).await {
+        Ok(recipients) => recipients,
+        Err(err) => {
+            tracing::warn!(error = %err, "load review notification recipients failed");
+            return;
+        }
+    };
+    let in_app = notices
+        .iter()
+        .filter_map(|notice| {
+            let recipient = recipients.get(&notice.student_id)?;
+            Some(review_notification(recipient.locale, recipient.user_id, notice))
+        })
+        .collect();
+    push_notifications(state, in_app).await;
+
+    if state.mail_config().is_none() {
+        return;
+    }
+    let templates = MailTemplates::load(&state.db, &REVIEW_MAIL).await.unwrap_or_else(|err| {
+        tracing::warn!(error = %err, "load review mail template failed, using default");
+        MailTemplates::default()
+    });
+    let messages: Vec<(String, String, String)> = notices
+        .iter()
+        .filter_map(|notice| {
+            let recipient = recipients.get(&notice.student_id)?;
+            let email = recipient.email.clone()?;
+            let (subject, body) = format_review_email(&templates, recipient.locale, &recipient.name, notice);
+            Some((email, subject, body))
+        })
+        .collect();
+    for (to, subject, body) in messages {
+        if let Err(err) = enqueue_mail(state, &to, &subject, &body).await {
+            tracing::warn!(error = %err, "enqueue review notification email failed");
+        }
+    }
+}
+
+/// 按学生 ID 查找接收人。学生账号以学号为用户名。
+async fn load_recipients(
+    state: &AppState,
+    notices: &[ReviewNotice],
+) -> Result<HashMap<Uuid, ReviewRecipient>, AppError> {
+    let mut student_ids: Vec<Uuid> = notices.iter().map(|notice| notice.student_id).collect();
+    student_ids.sort();
+    student_ids.dedup();
+    let students = Student::find()
+        .filter(students::Column::Id.is_in(student_ids))
+        .filter(students::Column::IsDeleted.eq(false))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let student_nos: Vec<String> = students.iter().map(|student| student.student_no.clone()).collect();
+    let users = User::find()
+        .filter(users::Column::Username.is_in(student_nos))
+        .filter(users::Column::Role.eq("student"))
+        .filter(users::Column::IsActive.eq(true))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let mut accounts: HashMap<String, users::Model> = users
+        .into_iter()
+        .map(|user| (user.username.clone(), user))
+        .collect();
+    Ok(students
+        .into_iter()
+        .filter_map(|student| {
+            let user = accounts.remove(&student.student_no)?;
+            let locale = state.locale_for(&user);
+            let email = user.email.filter(|_| user.notify_review_email);
+            Some((
+                student.id,
+                ReviewRecipient {
+                    name: student.name,
+                    user_id: user.id,
+                    email,
+                    locale,
+                },
+            ))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notice(status: &str, hours: Option<i32>, reason: Option<&str>) -> ReviewNotice {
+        ReviewNotice {
+            record_id: Uuid::new_v4(),
+            student_id: Uuid::new_v4(),
+            record_type: "contest",
+            title: "数学建模竞赛".to_string(),
+            status: status.to_string(),
+            hours,
+            rejection_reason: reason.map(|value| value.to_string()),
+        }
+    }
+
+    #[test]
+    fn format_review_email_describes_approval() {
+        let (subject, body) = format_review_email(&MailTemplates::default(), Locale::ZhCn, "张三", &notice(STATUS_FINAL_REVIEWED, Some(8), None));
+        assert_eq!(subject, "竞赛获奖记录审核结果：复审通过");
+        assert!(body.starts_with("张三 同学"));
+        assert!(body.contains("「数学建模竞赛」"));
+        assert!(body.contains("认定学时：8"));
+        assert!(!body.contains("原因"));
+    }
+
+    #[test]
+    fn format_review_email_includes_rejection_reason() {
+        let (subject, body) = format_review_email(&MailTemplates::default(), Locale::ZhCn, "张三", &notice(STATUS_REJECTED, None, Some("材料不完整")));
+        assert_eq!(subject, "竞赛获奖记录审核结果：未通过");
+        assert!(body.contains("原因：材料不完整"));
+        assert!(!body.contains("认定学时"));
+    }
+
+    #[test]
+    fn review_notification_kind_follows_status() {
+        let user_id = Uuid::new_v4();
+        let approved = review_notification(Locale::ZhCn, user_id, &notice(STATUS_FIRST_REVIEWED, Some(4), None));
+        assert_eq!(approved.kind, KIND_RECORD_REVIEWED);
+        assert_eq!(approved.title, "竞赛获奖记录「数学建模竞赛」初审通过");
+        assert_eq!(approved.body, "认定学时：4");
+        assert_eq!(approved.target_type, Some("contest"));
+
+        let rejected = review_notification(Locale::ZhCn, user_id, &notice(STATUS_REJECTED, None, Some("材料不完整")));
+        assert_eq!(rejected.kind, KIND_RECORD_REJECTED);
+        assert_eq!(rejected.body, "原因：材料不完整");
+    }
+
+    #[test]
+    fn review_messages_follow_locale() {
+        let (subject, body) = format_review_email(&MailTemplates::default(), Locale::EnUs, "Alice", &notice(STATUS_REJECTED, None, None));
+        assert_eq!(subject, "Review result for contest award record: rejected");
+        assert!(body.starts_with("Dear Alice"));
+        assert!(body.contains("Reason: not provided"));
+
+        let approved = review_notification(Locale::EnUs, Uuid::new_v4(), &notice(STATUS_FINAL_REVIEWED, Some(8), None));
+        assert_eq!(approved.title, "contest award record \"数学建模竞赛\": passed final review");
+        assert_eq!(approved.body, "Approved hours: 8");
+    }
+
+    #[tokio::test]
+    async fn create_notifications_inserts_unread_rows() {
+        let db = crate::services::test_support::memory_db().await;
+        let user_id = Uuid::new_v4();
+        create_notifications(
+            &db,
+            vec![
+                NewNotification::new(user_id, KIND_PASSWORD_RESET, "密码已重置", "请尽快修改密码"),
+                NewNotification::new(user_id, KIND_IMPORT_COMPLETED, "导入完成", "").target("import_job", user_id),
+            ],
+        )
+        .await
+        .unwrap();
+        let rows = Notification::find()
+            .filter(notifications::Column::UserId.eq(user_id))
+            .all(&db)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.read_at.is_none()));
+    }
+}
@@ -0,0 +1,205 @@
+//! LibreOffice 转换：限制并发进程数，超时后终止整个进程组。
+
+use std::io::{BufWriter, Cursor};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use printpdf::{BuiltinFont, Mm, PdfDocument};
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+use crate::{config::Config, error::AppError};
+
+/// xlsx 转 PDF 的转换器；超出并发上限的请求排队等待许可。
+#[derive(Debug)]
+pub struct PdfConverter {
+    libreoffice_path: String,
+    timeout: Duration,
+    permits: Semaphore,
+}
+
+impl PdfConverter {
+    /// 创建转换器。
+    pub fn new(libreoffice_path: String, concurrency: usize, timeout: Duration) -> Self {
+        Self {
+            libreoffice_path,
+            timeout,
+            permits: Semaphore::new(concurrency),
+        }
+    }
+
+    /// 按启动配置创建转换器。
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.libreoffice_path.clone(),
+            config.pdf_export_concurrency,
+            Duration::from_secs(config.libreoffice_timeout_seconds),
+        )
+    }
+
+    /// 将 xlsx 转为 PDF，输出写入 `output_dir`。
+    pub async fn convert(&self, input_path: &Path, output_dir: &Path) -> Result<Vec<u8>, AppError> {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|_| AppError::service_unavailable("pdf renderer unavailable"))?;
+
+        // 测试环境使用内置简易 PDF，避免依赖外部转换程序。
+        if self.libreoffice_path == "internal" {
+            return tokio::task::spawn_blocking(render_placeholder_pdf)
+                .await
+                .map_err(|err| AppError::internal(&format!("render pdf failed: {err}")))?;
+        }
+
+        // 每次转换使用独立的用户配置目录，允许多个 LibreOffice 进程并行运行。
+        let profile = url::Url::from_directory_path(output_dir.join("lo-profile"))
+            .map_err(|_| AppError::internal("invalid libreoffice profile path"))?;
+        let mut command = Command::new(&self.libreoffice_path);
+        // soffice 会派生 soffice.bin 等子进程；放入独立进程组，超时时整组终止。
+        #[cfg(unix)]
+        command.process_group(0);
+        let mut child = command
+            .arg(format!("-env:UserInstallation={profile}"))
+            .arg("--headless")
+            .arg("--convert-to")
+            .arg("pdf")
+            .arg("--outdir")
+            .arg(output_dir)
+            .arg(input_path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|_| AppError::internal("run libreoffice failed"))?;
+        let status = match tokio::time::timeout(self.timeout, child.wait()).await {
+            Ok(status) => status.map_err(|_| AppError::internal("run libreoffice failed"))?,
+            Err(_) => {
+                tracing::warn!(
+                    timeout_seconds = self.timeout.as_secs(),
+                    "libreoffice conversion timed out, killing process group"
+                );
+                kill_process_group(&child);
+                let _ = child.kill().await;
+                return Err(AppError::service_unavailable("pdf conversion timed out"));
+            }
+        };
+        if !status.success() {
+            return Err(AppError::internal("libreoffice conversion failed"));
+        }
+        let file_stem = input_path
+            .file_stem()
+            .and_then(|value| value.to_str())
+            .ok_or_else(|| AppError::internal("invalid template filename"))?;
+        let pdf_path = output_dir.join(format!("{file_stem}.pdf"));
+        tokio::fs::read(&pdf_path)
+            .await
+            .map_err(|_| AppError::internal("read pdf failed"))
+    }
+}
+
+/// 终止子进程所在的进程组（进程组号即子进程 pid）。
+#[cfg(unix)]
+fn kill_process_group(child: &tokio::process::Child) {
+    if let Some(pid) = child.id() {
+        // SAFETY: killpg 只发送信号，不涉及内存访问。
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_child: &tokio::process::Child) {}
+
+fn render_placeholder_pdf() -> Result<Vec<u8>, AppError> {
+    let (doc, page, layer) = PdfDocument::new("劳动教育学时认定表", Mm(210.0), Mm(297.0), "Layer");
+    let font = doc
+        .add_builtin_font(BuiltinFont::Helvetica)
+        .map_err(|_| AppError::internal("load font failed"))?;
+    let layer = doc.get_page(page).get_layer(layer);
+    layer.use_text("模板转换未启用（internal）", 12.0, Mm(20.0), Mm(280.0), &font);
+    let mut writer = BufWriter::new(Cursor::new(Vec::new()));
+    doc.save(&mut writer)
+        .map_err(|_| AppError::internal("save pdf failed"))?;
+    let cursor = writer
+        .into_inner()
+        .map_err(|_| AppError::internal("save pdf failed"))?;
+    Ok(cursor.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn internal_converter_renders_placeholder_pdf() {
+        let dir = tempfile::tempdir().unwrap();
+        let converter = PdfConverter::new("internal".to_string(), 1, Duration::from_secs(5));
+        let bytes = converter
+            .convert(&dir.path().join("input.xlsx"), dir.path())
+            .await
+            .unwrap();
+        assert!(bytes.starts_with(b"%PDF"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn hung_conversion_is_killed_after_timeout() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("fake-soffice");
+        let pid_file = dir.path().join("child.pid");
+        // 模拟 soffice 派生子进程后挂起。
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\nsleep 30 &\necho $! > '{}'\nwait\n",
+                pid_file.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let converter = PdfConverter::new(
+            script.to_string_lossy().to_string(),
+            1,
+            Duration::from_millis(200),
+        );
+        let started = std::time::Instant::now();
+        let result = converter.convert(&dir.path().join("input.xlsx"), dir.path()).await;
+        assert!(matches!(result, Err(AppError::ServiceUnavailable(_))));
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        let pid: libc::pid_t = std::fs::read_to_string(&pid_file)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while process_alive(pid) {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "child process survived the timeout"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    /// 进程仍在运行（已退出但未被回收的僵尸进程视为已终止）。
+    #[cfg(unix)]
+    fn process_alive(pid: libc::pid_t) -> bool {
+        // SAFETY: 信号 0 只检查进程是否存在。
+        if unsafe { libc::kill(pid, 0) } != 0 {
+            return false;
+        }
+        match std::fs::read_to_string(format!("/proc/{pid}/stat")) {
+            Ok(stat) => !stat
+                .rsplit_once(')')
+                .is_some_and(|(_, rest)| rest.trim_start().starts_with('Z')),
+            Err(_) => true,
+        }
+    }
+}
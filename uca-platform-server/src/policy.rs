@@ -1,7 +1,8 @@
-//! 密码策略读取与更新。
+//! 密码策略与学生默认密码规则的读取与更新。
 
 use chrono::Utc;
-use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder, Set};
+use sea_orm::{ActiveModelTrait, ConnectionTrait, EntityTrait, QueryOrder, Set};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::config::PasswordPolicy;
@@ -9,6 +10,72 @@ use crate::entities::{password_policies, PasswordPolicy as PasswordPolicyEntity}
 use crate::error::AppError;
 use crate::state::AppState;
 
+/// 学生密码生成规则：按前缀、学号、手机号、后缀的顺序拼接。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StudentPasswordRule {
+    /// 固定前缀。
+    pub prefix: Option<String>,
+    /// 固定后缀。
+    pub suffix: Option<String>,
+    /// 是否包含学号。
+    pub include_student_no: bool,
+    /// 是否包含手机号。
+    pub include_phone: bool,
+}
+
+impl Default for StudentPasswordRule {
+    /// 默认规则：`st` + 学号。
+    fn default() -> Self {
+        Self {
+            prefix: Some("st".to_string()),
+            suffix: None,
+            include_student_no: true,
+            include_phone: false,
+        }
+    }
+}
+
+impl StudentPasswordRule {
+    /// 按规则生成密码；需要手机号但为空，或结果为空时报错。
+    pub fn build(&self, student_no: &str, phone: &str) -> Result<String, AppError> {
+        let mut password = String::new();
+        if let Some(prefix) = self.prefix.as_deref() {
+            password.push_str(prefix);
+        }
+        if self.include_student_no {
+            password.push_str(student_no);
+        }
+        if self.include_phone {
+            if phone.is_empty() {
+                return Err(AppError::bad_request("student phone missing"));
+            }
+            password.push_str(phone);
+        }
+        if let Some(suffix) = self.suffix.as_deref() {
+            password.push_str(suffix);
+        }
+        if password.is_empty() {
+            return Err(AppError::bad_request("password rule produces empty password"));
+        }
+        Ok(password)
+    }
+
+    /// 校验规则：必须包含学号或手机号，固定部分不超过 32 个字符。
+    pub fn validate(&self) -> Result<(), AppError> {
+        if !self.include_student_no && !self.include_phone {
+            return Err(AppError::validation("password rule must include student_no or phone"));
+        }
+        let fixed_too_long = [self.prefix.as_deref(), self.suffix.as_deref()]
+            .into_iter()
+            .flatten()
+            .any(|value| value.chars().count() > 32);
+        if fixed_too_long {
+            return Err(AppError::validation("password rule prefix/suffix too long"));
+        }
+        Ok(())
+    }
+}
+
 pub async fn load_password_policy(state: &AppState) -> Result<PasswordPolicy, AppError> {
     let record = PasswordPolicyEntity::find()
         .order_by_desc(password_policies::Column::UpdatedAt)
@@ -50,6 +117,7 @@ pub async fn upsert_password_policy(
             .await
             .map_err(|err| AppError::Database(err.to_string()))?;
     } else {
+        let default_rule = StudentPasswordRule::default();
         let model = password_policies::ActiveModel {
             id: Set(Uuid::new_v4()),
             min_length: Set(policy.min_length as i32),
@@ -57,6 +125,10 @@ pub async fn upsert_password_policy(
             require_lowercase: Set(policy.require_lowercase),
             require_digit: Set(policy.require_digit),
             require_symbol: Set(policy.require_symbol),
+            student_password_prefix: Set(default_rule.prefix),
+            student_password_suffix: Set(default_rule.suffix),
+            student_password_include_student_no: Set(default_rule.include_student_no),
+            student_password_include_phone: Set(default_rule.include_phone),
             created_at: Set(now),
             updated_at: Set(now),
         };
@@ -67,3 +139,105 @@ pub async fn upsert_password_policy(
     }
     Ok(policy)
 }
+
+/// 读取学生默认密码规则；未保存过时使用 `st` + 学号。
+pub async fn load_student_password_rule<C>(db: &C) -> Result<StudentPasswordRule, AppError>
+where
+    C: ConnectionTrait,
+{
+    let record = PasswordPolicyEntity::find()
+        .order_by_desc(password_policies::Column::UpdatedAt)
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(record
+        .map(|model| StudentPasswordRule {
+            prefix: model.student_password_prefix,
+            suffix: model.student_password_suffix,
+            include_student_no: model.student_password_include_student_no,
+            include_phone: model.student_password_include_phone,
+        })
+        .unwrap_or_default())
+}
+
+/// 保存学生默认密码规则，与密码策略存于同一条记录。
+pub async fn upsert_student_password_rule(
+    state: &AppState,
+    rule: StudentPasswordRule,
+) -> Result<StudentPasswordRule, AppError> {
+    rule.validate()?;
+    let existing = PasswordPolicyEntity::find()
+        .order_by_desc(password_policies::Column::UpdatedAt)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let rule = StudentPasswordRule {
+        prefix: rule.prefix.filter(|value| !value.is_empty()),
+        suffix: rule.suffix.filter(|value| !value.is_empty()),
+        ..rule
+    };
+    let now = Utc::now();
+    if let Some(record) = existing {
+        let mut active: password_policies::ActiveModel = record.into();
+        active.student_password_prefix = Set(rule.prefix.clone());
+        active.student_password_suffix = Set(rule.suffix.clone());
+        active.student_password_include_student_no = Set(rule.include_student_no);
+        active.student_password_include_phone = Set(rule.include_phone);
+        active.updated_at = Set(now);
+        active
+            .update(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+    } else {
+        let policy = &state.config.password_policy;
+        let model = password_policies::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            min_length: Set(policy.min_length as i32),
+            require_uppercase: Set(policy.require_uppercase),
+            require_lowercase: Set(policy.require_lowercase),
+            require_digit: Set(policy.require_digit),
+            require_symbol: Set(policy.require_symbol),
+            student_password_prefix: Set(rule.prefix.clone()),
+            student_password_suffix: Set(rule.suffix.clone()),
+            student_password_include_student_no: Set(rule.include_student_no),
+            student_password_include_phone: Set(rule.include_phone),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+        PasswordPolicyEntity::insert(model)
+            .exec_without_returning(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+    }
+    Ok(rule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_rule_matches_legacy_password() {
+        let rule = StudentPasswordRule::default();
+        assert_eq!(rule.build("2023001", "").unwrap(), "st2023001");
+    }
+
+    #[test]
+    fn rule_combines_parts_and_checks_phone() {
+        let rule = StudentPasswordRule {
+            prefix: Some("Uca@".to_string()),
+            suffix: Some("!".to_string()),
+            include_student_no: true,
+            include_phone: true,
+        };
+        assert_eq!(rule.build("2023001", "13800000000").unwrap(), "Uca@202300113800000000!");
+        assert!(rule.build("2023001", "").is_err());
+        assert!(rule.validate().is_ok());
+
+        let fixed_only = StudentPasswordRule {
+            include_student_no: false,
+            ..StudentPasswordRule::default()
+        };
+        assert!(fixed_only.validate().is_err());
+    }
+}
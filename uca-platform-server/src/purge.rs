@@ -0,0 +1,838 @@
+//! 彻底删除的级联策略与孤立数据清理。
+//!
+//! 彻底删除学生或记录时，在同一事务中删除竞赛与志愿记录及其自定义字段值、审核签名、评论与附件行；
+//! 事务提交后再删除附件原文件、缩略图与签名文件。孤立数据清理删除找不到所属学生或记录的数据行，
+//! 以及上传目录中未被任何数据引用的文件。
+//!
+//! 按条件批量彻底删除须先试运行：试运行返回匹配数与签名的确认令牌，令牌绑定匹配记录 ID 集合的摘要，
+//! 正式删除时匹配的记录必须与试运行时完全一致。
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use sea_orm::{
+    sea_query::Query, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    archival::archived_signature_paths,
+    document_verification::delete_student_verifications,
+    entities::{
+        attachments, contest_records, form_field_values, record_comments, review_signatures, students,
+        user_signatures, volunteer_records, Attachment, ContestRecord, FormFieldValue, RecordComment,
+        ReviewSignature, Student, UserSignature, VolunteerRecord,
+    },
+    error::AppError,
+    routes::attachments::{remove_stored_file, remove_thumbnails, thumbnail_path},
+    signed_tokens,
+    state::AppState,
+};
+
+/// 孤立文件的最短保留时间，避免误删正在写入数据库的上传文件。
+const ORPHAN_GRACE_SECONDS: u64 = 3600;
+
+/// 参与孤立文件清理的上传子目录。
+const SCANNED_UPLOAD_DIRS: [&str; 3] = ["attachments", "thumbnails", "signatures"];
+
+/// 批量彻底删除时每个事务处理的记录数。
+pub const BULK_PURGE_BATCH_SIZE: usize = 200;
+
+/// 批量彻底删除确认令牌的有效期。
+const PURGE_CONFIRM_TTL_SECONDS: i64 = 600;
+
+/// 确认令牌的上下文标签。
+const PURGE_CONFIRM_CONTEXT: &str = "bulk-purge";
+
+/// 彻底删除涉及的数据行数。
+#[derive(Debug, Default, Serialize)]
+pub struct PurgeSummary {
+    /// 删除的竞赛记录数。
+    pub contest_records: u64,
+    /// 删除的志愿服务记录数。
+    pub volunteer_records: u64,
+    /// 删除的自定义字段值数。
+    pub field_values: u64,
+    /// 删除的审核签名数。
+    pub signatures: u64,
+    /// 删除的评论数。
+    pub comments: u64,
+    /// 删除的附件数。
+    pub attachments: u64,
+    /// 事务提交后待删除的文件。
+    #[serde(skip)]
+    pub files: PurgedFiles,
+}
+
+/// 彻底删除后需要从磁盘移除的文件。
+#[derive(Debug, Default)]
+pub struct PurgedFiles {
+    /// 附件原文件与签名文件路径。
+    pub paths: Vec<String>,
+    /// 附件 ID（用于删除缩略图）。
+    pub attachment_ids: Vec<Uuid>,
+}
+
+impl PurgeSummary {
+    fn absorb(&mut self, other: PurgeSummary) {
+        self.contest_records += other.contest_records;
+        self.volunteer_records += other.volunteer_records;
+        self.field_values += other.field_values;
+        self.signatures += other.signatures;
+        self.comments += other.comments;
+        self.attachments += other.attachments;
+        self.files.paths.extend(other.files.paths);
+        self.files.attachment_ids.extend(other.files.attachment_ids);
+    }
+}
+
+/// 批量彻底删除的筛选条件，除记录类型外至少指定一项。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BulkPurgeFilter {
+    /// 记录类型：`contest` 或 `volunteer`。
+    pub record_type: String,
+    /// 审核状态，如 `rejected`。
+    #[serde(default)]
+    pub status: Option<String>,
+    /// 只匹配在此时间之前提交的记录。
+    #[serde(default)]
+    pub created_before: Option<DateTime<Utc>>,
+    /// 导入批次（仅竞赛记录）。
+    #[serde(default)]
+    pub import_batch_id: Option<Uuid>,
+}
+
+impl BulkPurgeFilter {
+    /// 校验筛选条件，并去除状态两端的空白。
+    pub fn normalized(mut self) -> Result<Self, AppError> {
+        if !matches!(self.record_type.as_str(), "contest" | "volunteer") {
+            return Err(AppError::validation("record_type must be contest or volunteer"));
+        }
+        self.status = self
+            .status
+            .map(|status| status.trim().to_string())
+            .filter(|status| !status.is_empty());
+        if self.status.is_none() && self.created_before.is_none() && self.import_batch_id.is_none() {
+            return Err(AppError::validation(
+                "at least one of status, created_before or import_batch_id is required",
+            ));
+        }
+        if self.import_batch_id.is_some() && self.record_type != "contest" {
+            return Err(AppError::validation("import_batch_id only applies to contest records"));
+        }
+        Ok(self)
+    }
+}
+
+/// 确认令牌载荷。
+#[derive(Debug, Serialize, Deserialize)]
+struct PurgeConfirmClaims {
+    /// 试运行的管理员。
+    user_id: Uuid,
+    filter: BulkPurgeFilter,
+    /// 试运行时的匹配数。
+    matched: u64,
+    /// 试运行匹配的记录 ID 集合摘要，见 [`record_ids_digest`]。
+    ids_digest: String,
+    /// 过期时间（Unix 秒）。
+    exp: i64,
+}
+
+/// 孤立数据清理报告。
+#[derive(Debug, Default, Serialize)]
+pub struct OrphanReport {
+    /// 为 true 时只统计不删除。
+    pub dry_run: bool,
+    /// 所属学生不存在的竞赛记录数。
+    pub contest_records: u64,
+    /// 所属学生不存在的志愿服务记录数。
+    pub volunteer_records: u64,
+    /// 所属记录不存在的自定义字段值数。
+    pub field_values: u64,
+    /// 所属记录不存在的审核签名数。
+    pub signatures: u64,
+    /// 所属记录不存在的评论数。
+    pub comments: u64,
+    /// 所属学生或记录不存在的附件数。
+    pub attachments: u64,
+    /// 上传目录中未被引用的文件数。
+    pub files: u64,
+}
+
+impl OrphanReport {
+    /// 孤立数据行与文件的总数。
+    pub fn total(&self) -> u64 {
+        self.contest_records
+            + self.volunteer_records
+            + self.field_values
+            + self.signatures
+            + self.comments
+            + self.attachments
+            + self.files
+    }
+}
+
+/// 彻底删除指定类型的记录及其关联数据，返回删除数量与待删除文件。
+pub async fn purge_records<C>(db: &C, record_type: &str, record_ids: &[Uuid]) -> Result<PurgeSummary, AppError>
+where
+    C: ConnectionTrait,
+{
+    if !matches!(record_type, "contest" | "volunteer") {
+        return Err(AppError::bad_request("invalid record type"));
+    }
+    let mut summary = PurgeSummary::default();
+    if record_ids.is_empty() {
+        return Ok(summary);
+    }
+    summary.field_values = FormFieldValue::delete_many()
+        .filter(form_field_values::Column::RecordType.eq(record_type))
+        .filter(form_field_values::Column::RecordId.is_in(record_ids.to_vec()))
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .rows_affected;
+    summary.comments = RecordComment::delete_many()
+        .filter(record_comments::Column::RecordType.eq(record_type))
+        .filter(record_comments::Column::RecordId.is_in(record_ids.to_vec()))
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .rows_affected;
+
+    let signatures = ReviewSignature::find()
+        .filter(review_signatures::Column::RecordType.eq(record_type))
+        .filter(review_signatures::Column::RecordId.is_in(record_ids.to_vec()))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    summary.signatures = ReviewSignature::delete_many()
+        .filter(review_signatures::Column::RecordType.eq(record_type))
+        .filter(review_signatures::Column::RecordId.is_in(record_ids.to_vec()))
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .rows_affected;
+    summary
+        .files
+        .paths
+        .extend(signatures.into_iter().map(|signature| signature.signature_path));
+
+    let attachments = Attachment::find()
+        .filter(attachments::Column::RecordType.eq(record_type))
+        .filter(attachments::Column::RecordId.is_in(record_ids.to_vec()))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    summary.attachments = delete_attachments(db, attachments, &mut summary.files).await?;
+
+    if record_type == "contest" {
+        summary.contest_records = ContestRecord::delete_many()
+            .filter(contest_records::Column::Id.is_in(record_ids.to_vec()))
+            .exec(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .rows_affected;
+    } else {
+        summary.volunteer_records = VolunteerRecord::delete_many()
+            .filter(volunteer_records::Column::Id.is_in(record_ids.to_vec()))
+            .exec(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .rows_affected;
+    }
+    Ok(summary)
+}
+
+/// 彻底删除学生及其全部竞赛、志愿记录与附件，返回删除数量与待删除文件。
+pub async fn purge_student<C>(db: &C, student_id: Uuid) -> Result<PurgeSummary, AppError>
+where
+    C: ConnectionTrait,
+{
+    let contest_ids: Vec<Uuid> = ContestRecord::find()
+        .select_only()
+        .column(contest_records::Column::Id)
+        .filter(contest_records::Column::StudentId.eq(student_id))
+        .into_tuple()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let volunteer_ids: Vec<Uuid> = VolunteerRecord::find()
+        .select_only()
+        .column(volunteer_records::Column::Id)
+        .filter(volunteer_records::Column::StudentId.eq(student_id))
+        .into_tuple()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let mut summary = purge_records(db, "contest", &contest_ids).await?;
+    summary.absorb(purge_records(db, "volunteer", &volunteer_ids).await?);
+    // 记录已不存在但仍登记在学生名下的附件。
+    let leftover = Attachment::find()
+        .filter(attachments::Column::StudentId.eq(student_id))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    summary.attachments += delete_attachments(db, leftover, &mut summary.files).await?;
+    delete_student_verifications(db, student_id).await?;
+    Student::delete_by_id(student_id)
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(summary)
+}
+
+/// 查询匹配筛选条件的记录 ID（包括已软删除的记录），按提交时间排序。
+pub async fn matching_record_ids<C>(db: &C, filter: &BulkPurgeFilter) -> Result<Vec<Uuid>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let ids = match filter.record_type.as_str() {
+        "contest" => {
+            let mut query = ContestRecord::find()
+                .select_only()
+                .column(contest_records::Column::Id);
+            if let Some(status) = filter.status.as_deref() {
+                query = query.filter(contest_records::Column::Status.eq(status));
+            }
+            if let Some(created_before) = filter.created_before {
+                query = query.filter(contest_records::Column::CreatedAt.lt(created_before));
+            }
+            if let Some(import_batch_id) = filter.import_batch_id {
+                query = query.filter(contest_records::Column::ImportBatchId.eq(import_batch_id));
+            }
+            query
+                .order_by_asc(contest_records::Column::CreatedAt)
+                .into_tuple()
+                .all(db)
+                .await
+        }
+        "volunteer" => {
+            let mut query = VolunteerRecord::find()
+                .select_only()
+                .column(volunteer_records::Column::Id);
+            if let Some(status) = filter.status.as_deref() {
+                query = query.filter(volunteer_records::Column::Status.eq(status));
+            }
+            if let Some(created_before) = filter.created_before {
+                query = query.filter(volunteer_records::Column::CreatedAt.lt(created_before));
+            }
+            query
+                .order_by_asc(volunteer_records::Column::CreatedAt)
+                .into_tuple()
+                .all(db)
+                .await
+        }
+        _ => return Err(AppError::validation("record_type must be contest or volunteer")),
+    };
+    ids.map_err(|err| AppError::Database(err.to_string()))
+}
+
+/// 记录 ID 集合的摘要：排序后逐个拼接字节计算 SHA-256，与查询顺序无关。
+fn record_ids_digest(record_ids: &[Uuid]) -> String {
+    let mut sorted = record_ids.to_vec();
+    sorted.sort_unstable();
+    let mut hasher = Sha256::new();
+    for id in &sorted {
+        hasher.update(id.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// 为试运行结果签发确认令牌，返回令牌及其过期时间。
+pub fn issue_purge_confirm_token(
+    secret: &[u8],
+    user_id: Uuid,
+    filter: &BulkPurgeFilter,
+    record_ids: &[Uuid],
+    now: DateTime<Utc>,
+) -> (String, DateTime<Utc>) {
+    let expires_at = now + ChronoDuration::seconds(PURGE_CONFIRM_TTL_SECONDS);
+    let claims = PurgeConfirmClaims {
+        user_id,
+        filter: filter.clone(),
+        matched: record_ids.len() as u64,
+        ids_digest: record_ids_digest(record_ids),
+        exp: expires_at.timestamp(),
+    };
+    (signed_tokens::sign(secret, PURGE_CONFIRM_CONTEXT, &claims), expires_at)
+}
+
+/// 校验确认令牌：须由同一管理员针对相同筛选条件签发且未过期，且当前匹配的记录与试运行时一致。
+pub fn verify_purge_confirm_token(
+    secret: &[u8],
+    token: &str,
+    user_id: Uuid,
+    filter: &BulkPurgeFilter,
+    record_ids: &[Uuid],
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    let claims: PurgeConfirmClaims = signed_tokens::verify(secret, PURGE_CONFIRM_CONTEXT, token)
+        .ok_or_else(|| AppError::validation("invalid confirm_token, run a dry run first"))?;
+    if claims.user_id != user_id || &claims.filter != filter {
+        return Err(AppError::validation("confirm_token was issued for a different filter"));
+    }
+    if claims.exp < now.timestamp() {
+        return Err(AppError::validation("confirm_token expired, run the dry run again"));
+    }
+    if claims.ids_digest != record_ids_digest(record_ids) {
+        return Err(AppError::conflict(
+            "matching records changed since the dry run",
+            Some(serde_json::json!({ "dry_run_matched": claims.matched, "matched": record_ids.len() })),
+        ));
+    }
+    Ok(())
+}
+
+/// 分批彻底删除记录：每批在独立事务中删除，提交后立即删除该批的文件；返回删除数量与批次数。
+///
+/// 某一批失败时之前已提交的批次不会回滚。
+pub async fn purge_records_in_batches(
+    db: &DatabaseConnection,
+    upload_dir: &Path,
+    record_type: &str,
+    record_ids: &[Uuid],
+) -> Result<(PurgeSummary, u64), AppError> {
+    let mut summary = PurgeSummary::default();
+    let mut batches = 0;
+    for chunk in record_ids.chunks(BULK_PURGE_BATCH_SIZE) {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        let batch = purge_records(&txn, record_type, chunk).await?;
+        txn.commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        remove_purged_files(upload_dir, &batch.files).await;
+        summary.absorb(PurgeSummary {
+            files: PurgedFiles::default(),
+            ..batch
+        });
+        batches += 1;
+    }
+    Ok((summary, batches))
+}
+
+/// 删除彻底删除后遗留的附件原文件、签名文件与缩略图（应在事务提交后调用）。
+pub async fn remove_purged_files(upload_dir: &Path, files: &PurgedFiles) {
+    let mut removed = HashSet::new();
+    for path in &files.paths {
+        if removed.insert(path.as_str()) {
+            remove_stored_file(path).await;
+        }
+    }
+    remove_thumbnails(upload_dir, &files.attachment_ids).await;
+}
+
+/// 统计或清理孤立数据：所属学生或记录已不存在的数据行，以及超过 1 小时仍未被引用的上传文件。
+pub async fn cleanup_orphans(state: &AppState, dry_run: bool) -> Result<OrphanReport, AppError> {
+    let db = &state.db;
+    let mut report = OrphanReport {
+        dry_run,
+        ..OrphanReport::default()
+    };
+
+    let student_ids = || {
+        Query::select()
+            .column(students::Column::Id)
+            .from(students::Entity)
+            .to_owned()
+    };
+    let contest_ids = || {
+        Query::select()
+            .column(contest_records::Column::Id)
+            .from(contest_records::Entity)
+            .to_owned()
+    };
+    let volunteer_ids = || {
+        Query::select()
+            .column(volunteer_records::Column::Id)
+            .from(volunteer_records::Entity)
+            .to_owned()
+    };
+
+    let orphan_contest: Vec<Uuid> = ContestRecord::find()
+        .select_only()
+        .column(contest_records::Column::Id)
+        .filter(contest_records::Column::StudentId.not_in_subquery(student_ids()))
+        .into_tuple()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let orphan_volunteer: Vec<Uuid> = VolunteerRecord::find()
+        .select_only()
+        .column(volunteer_records::Column::Id)
+        .filter(volunteer_records::Column::StudentId.not_in_subquery(student_ids()))
+        .into_tuple()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    report.contest_records = orphan_contest.len() as u64;
+    report.volunteer_records = orphan_volunteer.len() as u64;
+
+    let field_values = FormFieldValue::find()
+        .filter(
+            form_field_values::Column::RecordType
+                .eq("contest")
+                .and(form_field_values::Column::RecordId.not_in_subquery(contest_ids()))
+                .or(form_field_values::Column::RecordType
+                    .eq("volunteer")
+                    .and(form_field_values::Column::RecordId.not_in_subquery(volunteer_ids()))),
+        )
+        .select_only()
+        .column(form_field_values::Column::Id)
+        .into_tuple::<Uuid>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let signatures = ReviewSignature::find()
+        .filter(
+            review_signatures::Column::RecordType
+                .eq("contest")
+                .and(review_signatures::Column::RecordId.not_in_subquery(contest_ids()))
+                .or(review_signatures::Column::RecordType
+                    .eq("volunteer")
+                    .and(review_signatures::Column::RecordId.not_in_subquery(volunteer_ids()))),
+        )
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let comments = RecordComment::find()
+        .filter(
+            record_comments::Column::RecordType
+                .eq("contest")
+                .and(record_comments::Column::RecordId.not_in_subquery(contest_ids()))
+                .or(record_comments::Column::RecordType
+                    .eq("volunteer")
+                    .and(record_comments::Column::RecordId.not_in_subquery(volunteer_ids()))),
+        )
+        .select_only()
+        .column(record_comments::Column::Id)
+        .into_tuple::<Uuid>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let orphan_attachments = Attachment::find()
+        .filter(
+            attachments::Column::StudentId
+                .not_in_subquery(student_ids())
+                .or(attachments::Column::RecordType
+                    .eq("contest")
+                    .and(attachments::Column::RecordId.not_in_subquery(contest_ids())))
+                .or(attachments::Column::RecordType
+                    .eq("volunteer")
+                    .and(attachments::Column::RecordId.not_in_subquery(volunteer_ids()))),
+        )
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    report.field_values = field_values.len() as u64;
+    report.signatures = signatures.len() as u64;
+    report.comments = comments.len() as u64;
+    report.attachments = orphan_attachments.len() as u64;
+
+    if !dry_run {
+        let mut summary = purge_records(db, "contest", &orphan_contest).await?;
+        summary.absorb(purge_records(db, "volunteer", &orphan_volunteer).await?);
+        let mut files = summary.files;
+        if !field_values.is_empty() {
+            FormFieldValue::delete_many()
+                .filter(form_field_values::Column::Id.is_in(field_values))
+                .exec(db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+        }
+        if !comments.is_empty() {
+            RecordComment::delete_many()
+                .filter(record_comments::Column::Id.is_in(comments))
+                .exec(db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+        }
+        if !signatures.is_empty() {
+            ReviewSignature::delete_many()
+                .filter(review_signatures::Column::Id.is_in(signatures.iter().map(|signature| signature.id)))
+                .exec(db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+            files
+                .paths
+                .extend(signatures.into_iter().map(|signature| signature.signature_path));
+        }
+        delete_attachments(db, orphan_attachments, &mut files).await?;
+        remove_purged_files(&state.config.upload_dir, &files).await;
+    }
+
+    let referenced = referenced_files(state).await?;
+    let upload_dir = state.config.upload_dir.clone();
+    report.files = tokio::task::spawn_blocking(move || {
+        let mut total = 0;
+        for dir in SCANNED_UPLOAD_DIRS {
+            total += remove_orphan_files(&upload_dir.join(dir), &referenced, dry_run)?;
+        }
+        Ok::<u64, AppError>(total)
+    })
+    .await
+    .map_err(|err| AppError::internal(&format!("orphan cleanup failed: {err}")))??;
+    Ok(report)
+}
+
+async fn delete_attachments<C>(
+    db: &C,
+    rows: Vec<attachments::Model>,
+    files: &mut PurgedFiles,
+) -> Result<u64, AppError>
+where
+    C: ConnectionTrait,
+{
+    if rows.is_empty() {
+        return Ok(0);
+    }
+    let ids: Vec<Uuid> = rows.iter().map(|attachment| attachment.id).collect();
+    let result = Attachment::delete_many()
+        .filter(attachments::Column::Id.is_in(ids.clone()))
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    files.paths.extend(rows.into_iter().map(|attachment| attachment.stored_name));
+    files.attachment_ids.extend(ids);
+    Ok(result.rows_affected)
+}
+
+/// 数据库中引用的全部上传文件：附件、缩略图、审核签名（含归档快照中的）与个人签名。
+async fn referenced_files(state: &AppState) -> Result<HashSet<PathBuf>, AppError> {
+    let attachments = Attachment::find()
+        .select_only()
+        .column(attachments::Column::Id)
+        .column(attachments::Column::StoredName)
+        .into_tuple::<(Uuid, String)>()
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let review_signatures: Vec<String> = ReviewSignature::find()
+        .select_only()
+        .column(review_signatures::Column::SignaturePath)
+        .into_tuple()
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let user_signatures: Vec<String> = UserSignature::find()
+        .select_only()
+        .column(user_signatures::Column::SignaturePath)
+        .into_tuple()
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let archived_signatures = archived_signature_paths(&state.db).await?;
+    let upload_dir = &state.config.upload_dir;
+    let mut referenced = HashSet::with_capacity(attachments.len() * 2 + review_signatures.len());
+    for (id, stored_name) in attachments {
+        referenced.insert(PathBuf::from(stored_name));
+        referenced.insert(thumbnail_path(upload_dir, id));
+    }
+    referenced.extend(review_signatures.into_iter().map(PathBuf::from));
+    referenced.extend(user_signatures.into_iter().map(PathBuf::from));
+    referenced.extend(archived_signatures.into_iter().map(PathBuf::from));
+    Ok(referenced)
+}
+
+/// 删除（或在 `dry_run` 时只统计）目录中未被引用且超过保留时间的文件。
+fn remove_orphan_files(root: &Path, referenced: &HashSet<PathBuf>, dry_run: bool) -> Result<u64, AppError> {
+    if !root.exists() {
+        return Ok(0);
+    }
+    let mut removed = 0;
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|err| AppError::internal(&format!("read upload dir failed: {err}")))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if referenced.contains(&path) || !is_past_grace(&metadata) {
+                continue;
+            }
+            if dry_run || std::fs::remove_file(&path).is_ok() {
+                removed += 1;
+            }
+        }
+    }
+    Ok(removed)
+}
+
+fn is_past_grace(metadata: &std::fs::Metadata) -> bool {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age.as_secs() >= ORPHAN_GRACE_SECONDS)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use sea_orm::{ActiveModelTrait, Set};
+
+    use crate::services::test_support::memory_db;
+
+    #[test]
+    fn remove_orphan_files_keeps_recent_and_referenced_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("contest");
+        std::fs::create_dir_all(&nested).unwrap();
+        let kept = nested.join("kept.pdf");
+        let fresh = nested.join("fresh.pdf");
+        std::fs::write(&kept, b"a").unwrap();
+        std::fs::write(&fresh, b"b").unwrap();
+        let referenced = HashSet::from([kept.clone()]);
+
+        let removed = remove_orphan_files(dir.path(), &referenced, false).unwrap();
+        assert_eq!(removed, 0);
+        assert!(kept.exists());
+        assert!(fresh.exists());
+    }
+
+    #[test]
+    fn remove_orphan_files_ignores_missing_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+        assert_eq!(remove_orphan_files(&missing, &HashSet::new(), false).unwrap(), 0);
+    }
+
+    #[test]
+    fn bulk_purge_filter_requires_a_condition() {
+        let filter = BulkPurgeFilter {
+            record_type: "volunteer".to_string(),
+            status: Some("  ".to_string()),
+            created_before: None,
+            import_batch_id: None,
+        };
+        assert!(filter.clone().normalized().is_err());
+        let batch = BulkPurgeFilter {
+            import_batch_id: Some(Uuid::new_v4()),
+            ..filter.clone()
+        };
+        assert!(batch.normalized().is_err());
+        let rejected = BulkPurgeFilter {
+            status: Some(" rejected ".to_string()),
+            ..filter
+        };
+        assert_eq!(rejected.normalized().unwrap().status.as_deref(), Some("rejected"));
+    }
+
+    #[test]
+    fn confirm_token_is_bound_to_user_filter_records_and_expiry() {
+        let secret = b"secret";
+        let user_id = Uuid::new_v4();
+        let filter = BulkPurgeFilter {
+            record_type: "contest".to_string(),
+            status: Some("rejected".to_string()),
+            created_before: None,
+            import_batch_id: None,
+        };
+        let ids = vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let now = Utc::now();
+        let (token, expires_at) = issue_purge_confirm_token(secret, user_id, &filter, &ids, now);
+        assert!(verify_purge_confirm_token(secret, &token, user_id, &filter, &ids, now).is_ok());
+        let reordered = vec![ids[2], ids[0], ids[1]];
+        assert!(verify_purge_confirm_token(secret, &token, user_id, &filter, &reordered, now).is_ok());
+        assert!(verify_purge_confirm_token(secret, &token, Uuid::new_v4(), &filter, &ids, now).is_err());
+        let other = BulkPurgeFilter {
+            status: Some("submitted".to_string()),
+            ..filter.clone()
+        };
+        assert!(verify_purge_confirm_token(secret, &token, user_id, &other, &ids, now).is_err());
+        assert!(verify_purge_confirm_token(b"other", &token, user_id, &filter, &ids, now).is_err());
+        let later = expires_at + ChronoDuration::seconds(1);
+        assert!(verify_purge_confirm_token(secret, &token, user_id, &filter, &ids, later).is_err());
+
+        // 匹配数不变但记录集合已变化（一条被替换）时拒绝删除。
+        let swapped = vec![ids[0], ids[1], Uuid::new_v4()];
+        assert!(matches!(
+            verify_purge_confirm_token(secret, &token, user_id, &filter, &swapped, now),
+            Err(AppError::Conflict(..))
+        ));
+    }
+
+    #[tokio::test]
+    async fn purge_student_cascades_to_volunteer_records() {
+        let db = memory_db().await;
+        let now = Utc::now();
+        let student_id = Uuid::new_v4();
+        students::ActiveModel {
+            id: Set(student_id),
+            student_no: Set("2023001".to_string()),
+            name: Set("张三".to_string()),
+            gender: Set("男".to_string()),
+            department: Set("信息学院".to_string()),
+            major: Set("软件工程".to_string()),
+            class_name: Set("软工1班".to_string()),
+            phone: Set("13800000000".to_string()),
+            import_batch_id: Set(None),
+            is_deleted: Set(true),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        let record_id = Uuid::new_v4();
+        volunteer_records::ActiveModel {
+            id: Set(record_id),
+            student_id: Set(student_id),
+            title: Set("社区服务".to_string()),
+            description: Set("社区志愿服务".to_string()),
+            self_hours: Set(4),
+            first_review_hours: Set(None),
+            final_review_hours: Set(None),
+            status: Set("submitted".to_string()),
+            rejection_reason: Set(None),
+            semester_id: Set(None),
+            assigned_reviewer_id: Set(None),
+            version: Set(0),
+            is_deleted: Set(true),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        attachments::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            student_id: Set(student_id),
+            record_type: Set("volunteer".to_string()),
+            record_id: Set(record_id),
+            original_name: Set("proof.pdf".to_string()),
+            stored_name: Set("/nonexistent/proof.pdf".to_string()),
+            mime_type: Set("application/pdf".to_string()),
+            kind: Set("evidence".to_string()),
+            size_bytes: Set(10),
+            created_at: Set(now),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let summary = purge_student(&db, student_id).await.unwrap();
+        assert_eq!(summary.volunteer_records, 1);
+        assert_eq!(summary.attachments, 1);
+        assert_eq!(summary.files.paths, vec!["/nonexistent/proof.pdf".to_string()]);
+        assert!(VolunteerRecord::find_by_id(record_id).one(&db).await.unwrap().is_none());
+        assert!(Attachment::find().all(&db).await.unwrap().is_empty());
+        assert!(Student::find_by_id(student_id).one(&db).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,236 @@
+//! 学生档案与登录账号的一致性检查与修复。
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    auth::hash_password,
+    entities::{students, users, Student, User},
+    error::AppError,
+    policy::{load_student_password_rule, StudentPasswordRule},
+};
+
+/// 一致性检查报告。
+#[derive(Debug, Default, Serialize)]
+pub struct ReconcileReport {
+    /// 缺少登录账号的学生学号。
+    pub students_without_user: Vec<String>,
+    /// 缺少学生档案的学生账号。
+    pub users_without_student: Vec<String>,
+    /// 学号与非学生账号同名（需人工处理）。
+    pub role_conflicts: Vec<String>,
+    /// 已创建的登录账号。
+    pub created_users: Vec<String>,
+    /// 已创建的学生档案占位记录。
+    pub created_students: Vec<String>,
+}
+
+/// 检查学生档案与学生账号的对应关系。
+pub async fn check_student_users<C>(db: &C) -> Result<ReconcileReport, AppError>
+where
+    C: ConnectionTrait,
+{
+    let students_list = Student::find()
+        .filter(students::Column::IsDeleted.eq(false))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let users_list = User::find()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let all_student_nos: HashSet<String> = Student::find()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|student| student.student_no)
+        .collect();
+    Ok(build_report(&students_list, &users_list, &all_student_nos))
+}
+
+/// 修复缺失的学生账号；可选为孤立的学生账号创建学生档案占位记录。
+pub async fn repair_student_users<C>(
+    db: &C,
+    create_student_stubs: bool,
+) -> Result<ReconcileReport, AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut report = check_student_users(db).await?;
+    let now = Utc::now();
+
+    if !report.students_without_user.is_empty() {
+        let profiles: HashMap<String, students::Model> = Student::find()
+            .filter(students::Column::StudentNo.is_in(report.students_without_user.clone()))
+            .filter(students::Column::IsDeleted.eq(false))
+            .all(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .into_iter()
+            .map(|student| (student.student_no.clone(), student))
+            .collect();
+        let rule = load_student_password_rule(db).await?;
+        for student_no in &report.students_without_user {
+            let profile = profiles.get(student_no);
+            let display_name = profile
+                .map(|student| student.name.clone())
+                .unwrap_or_else(|| student_no.clone());
+            let phone = profile.map(|student| student.phone.as_str()).unwrap_or_default();
+            // 补建的账号不允许密码登录；规则缺少手机号等信息时回退到默认规则，避免整批修复失败。
+            let password = rule
+                .build(student_no, phone)
+                .or_else(|_| StudentPasswordRule::default().build(student_no, phone))?;
+            let model = users::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                username: Set(student_no.clone()),
+                display_name: Set(display_name),
+                role: Set("student".to_string()),
+                email: Set(None),
+                password_hash: Set(Some(hash_password(&password)?)),
+                allow_password_login: Set(false),
+                password_updated_at: Set(Some(now)),
+                must_change_password: Set(false),
+                is_active: Set(true),
+                department: Set(None),
+                notify_review_email: Set(true),
+                locale: Set(None),
+                created_at: Set(now),
+                updated_at: Set(now),
+            };
+            users::Entity::insert(model)
+                .exec_without_returning(db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+            report.created_users.push(student_no.clone());
+        }
+    }
+
+    if create_student_stubs && !report.users_without_student.is_empty() {
+        let names: HashMap<String, String> = User::find()
+            .filter(users::Column::Username.is_in(report.users_without_student.clone()))
+            .all(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .into_iter()
+            .map(|user| (user.username, user.display_name))
+            .collect();
+        for username in &report.users_without_student {
+            let model = students::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                student_no: Set(username.clone()),
+                name: Set(names.get(username).cloned().unwrap_or_else(|| username.clone())),
+                gender: Set(String::new()),
+                department: Set(String::new()),
+                major: Set(String::new()),
+                class_name: Set(String::new()),
+                phone: Set(String::new()),
+                import_batch_id: Set(None),
+                is_deleted: Set(false),
+                created_at: Set(now),
+                updated_at: Set(now),
+            };
+            students::Entity::insert(model)
+                .exec_without_returning(db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+            report.created_students.push(username.clone());
+        }
+    }
+
+    Ok(report)
+}
+
+fn build_report(
+    students_list: &[students::Model],
+    users_list: &[users::Model],
+    all_student_nos: &HashSet<String>,
+) -> ReconcileReport {
+    let users_by_name: HashMap<&str, &users::Model> = users_list
+        .iter()
+        .map(|user| (user.username.as_str(), user))
+        .collect();
+    let mut report = ReconcileReport::default();
+    for student in students_list {
+        match users_by_name.get(student.student_no.as_str()) {
+            None => report.students_without_user.push(student.student_no.clone()),
+            Some(user) if user.role != "student" => report.role_conflicts.push(student.student_no.clone()),
+            Some(_) => {}
+        }
+    }
+    for user in users_list {
+        // 已软删除的学生档案需通过恢复接口处理；已归档学生的账号处于停用状态，均不视为缺失。
+        if user.role == "student" && user.is_active && !all_student_nos.contains(&user.username) {
+            report.users_without_student.push(user.username.clone());
+        }
+    }
+    report.students_without_user.sort();
+    report.users_without_student.sort();
+    report.role_conflicts.sort();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn student(no: &str) -> students::Model {
+        students::Model {
+            id: Uuid::new_v4(),
+            student_no: no.to_string(),
+            name: "张三".to_string(),
+            gender: "男".to_string(),
+            department: "信息学院".to_string(),
+            major: "软件工程".to_string(),
+            class_name: "软工1班".to_string(),
+            phone: "13800000000".to_string(),
+            import_batch_id: None,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn user(username: &str, role: &str) -> users::Model {
+        users::Model {
+            id: Uuid::new_v4(),
+            username: username.to_string(),
+            display_name: username.to_string(),
+            role: role.to_string(),
+            email: None,
+            password_hash: None,
+            allow_password_login: false,
+            password_updated_at: None,
+            must_change_password: false,
+            is_active: true,
+            department: None,
+            notify_review_email: true,
+            locale: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn build_report_detects_mismatches() {
+        let students_list = vec![student("2023001"), student("2023002"), student("2023003")];
+        let users_list = vec![
+            user("2023001", "student"),
+            user("2023003", "teacher"),
+            user("2023009", "student"),
+            user("2023010", "student"),
+        ];
+        let all_nos: HashSet<String> = ["2023001", "2023002", "2023003", "2023010"]
+            .into_iter()
+            .map(|value| value.to_string())
+            .collect();
+        let report = build_report(&students_list, &users_list, &all_nos);
+        assert_eq!(report.students_without_user, vec!["2023002".to_string()]);
+        assert_eq!(report.role_conflicts, vec!["2023003".to_string()]);
+        assert_eq!(report.users_without_student, vec!["2023009".to_string()]);
+    }
+}
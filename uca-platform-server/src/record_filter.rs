@@ -0,0 +1,249 @@
+//! 记录筛选条件：竞赛记录查询与汇总导出共用同一套筛选字段。
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use sea_orm::{sea_query::Query, ColumnTrait, Condition};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    entities::{contest_records, semesters, students},
+    error::AppError,
+    semesters::validate_academic_year,
+};
+
+/// 竞赛名称匹配状态取值。
+pub const MATCH_STATUSES: [&str; 3] = ["matched", "fuzzy", "unmatched"];
+
+/// 结构化记录筛选条件；学生字段与记录字段分别转换为查询条件。
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RecordFilter {
+    /// 学号（精确匹配）。
+    pub student_no: Option<String>,
+    /// 院系。
+    pub department: Option<String>,
+    /// 专业。
+    pub major: Option<String>,
+    /// 班级。
+    pub class_name: Option<String>,
+    /// 获奖时间起（含，`YYYY-MM-DD`）。
+    pub date_from: Option<NaiveDate>,
+    /// 获奖时间止（含，`YYYY-MM-DD`）。
+    pub date_to: Option<NaiveDate>,
+    /// 自评学时下限（含）。
+    pub hours_min: Option<i32>,
+    /// 自评学时上限（含）。
+    pub hours_max: Option<i32>,
+    /// 竞赛类型（A/B）。
+    pub contest_category: Option<String>,
+    /// 竞赛名称匹配状态（matched/fuzzy/unmatched）。
+    pub match_status: Option<String>,
+    /// 所属学期 ID。
+    pub semester_id: Option<Uuid>,
+    /// 所属学年（如 `2025-2026`），包含该学年的全部学期。
+    pub academic_year: Option<String>,
+}
+
+impl RecordFilter {
+    /// 校验区间与取值。
+    pub fn validate(&self) -> Result<(), AppError> {
+        if let (Some(from), Some(to)) = (self.date_from, self.date_to) {
+            if from > to {
+                return Err(AppError::validation("date_from must not be after date_to"));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.hours_min, self.hours_max) {
+            if min > max {
+                return Err(AppError::validation("hours_min must not exceed hours_max"));
+            }
+        }
+        if let Some(status) = self.match_status.as_deref() {
+            if !MATCH_STATUSES.contains(&status) {
+                return Err(AppError::validation("invalid match_status"));
+            }
+        }
+        if let Some(year) = non_empty(&self.academic_year) {
+            validate_academic_year(year)?;
+        }
+        Ok(())
+    }
+
+    /// 学生字段条件（需要查询 students 表或关联学生）。
+    pub fn student_condition(&self) -> Condition {
+        let mut condition = Condition::all();
+        if let Some(value) = non_empty(&self.student_no) {
+            condition = condition.add(students::Column::StudentNo.eq(value));
+        }
+        if let Some(value) = non_empty(&self.department) {
+            condition = condition.add(students::Column::Department.eq(value));
+        }
+        if let Some(value) = non_empty(&self.major) {
+            condition = condition.add(students::Column::Major.eq(value));
+        }
+        if let Some(value) = non_empty(&self.class_name) {
+            condition = condition.add(students::Column::ClassName.eq(value));
+        }
+        condition
+    }
+
+    /// 记录字段条件（不含匹配状态，匹配状态需与竞赛库比对后筛选）。
+    pub fn record_condition(&self) -> Condition {
+        let mut condition = Condition::all();
+        if let Some(from) = self.date_from {
+            condition = condition.add(contest_records::Column::AwardDate.gte(day_start(from)));
+        }
+        if let Some(to) = self.date_to {
+            if let Some(next) = to.checked_add_days(Days::new(1)) {
+                condition = condition.add(contest_records::Column::AwardDate.lt(day_start(next)));
+            }
+        }
+        if let Some(min) = self.hours_min {
+            condition = condition.add(contest_records::Column::SelfHours.gte(min));
+        }
+        if let Some(max) = self.hours_max {
+            condition = condition.add(contest_records::Column::SelfHours.lte(max));
+        }
+        if let Some(value) = non_empty(&self.contest_category) {
+            condition = condition.add(contest_records::Column::ContestCategory.eq(value));
+        }
+        if let Some(semester_id) = self.semester_id {
+            condition = condition.add(contest_records::Column::SemesterId.eq(semester_id));
+        }
+        if let Some(year) = non_empty(&self.academic_year) {
+            condition = condition.add(
+                contest_records::Column::SemesterId.in_subquery(
+                    Query::select()
+                        .column(semesters::Column::Id)
+                        .from(semesters::Entity)
+                        .and_where(semesters::Column::AcademicYear.eq(year))
+                        .to_owned(),
+                ),
+            );
+        }
+        condition
+    }
+
+    /// 是否包含记录级筛选（学时统计只计入命中的记录）。
+    pub fn has_record_filters(&self) -> bool {
+        self.date_from.is_some()
+            || self.date_to.is_some()
+            || self.hours_min.is_some()
+            || self.hours_max.is_some()
+            || non_empty(&self.contest_category).is_some()
+            || self.match_status.is_some()
+            || self.semester_id.is_some()
+            || non_empty(&self.academic_year).is_some()
+    }
+
+    /// 匹配状态是否满足筛选；未设置时总是满足。
+    pub fn accepts_match_status(&self, status: &str) -> bool {
+        self.match_status.as_deref().is_none_or(|expected| expected == status)
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|value| !value.is_empty())
+}
+
+fn day_start(date: NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(0, 0, 0)
+        .expect("midnight is valid")
+        .and_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{EntityTrait, QueryFilter, QueryTrait};
+
+    fn sql(condition: Condition) -> String {
+        contest_records::Entity::find()
+            .filter(condition)
+            .build(sea_orm::DatabaseBackend::Sqlite)
+            .to_string()
+    }
+
+    #[test]
+    fn validate_rejects_inverted_ranges_and_unknown_status() {
+        let filter = RecordFilter {
+            hours_min: Some(10),
+            hours_max: Some(2),
+            ..Default::default()
+        };
+        assert!(filter.validate().is_err());
+        let filter = RecordFilter {
+            date_from: NaiveDate::from_ymd_opt(2026, 3, 1),
+            date_to: NaiveDate::from_ymd_opt(2026, 1, 1),
+            ..Default::default()
+        };
+        assert!(filter.validate().is_err());
+        let filter = RecordFilter {
+            match_status: Some("maybe".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.validate().is_err());
+        assert!(RecordFilter::default().validate().is_ok());
+    }
+
+    #[test]
+    fn record_condition_covers_date_hours_and_category() {
+        let filter = RecordFilter {
+            date_from: NaiveDate::from_ymd_opt(2026, 1, 1),
+            date_to: NaiveDate::from_ymd_opt(2026, 1, 31),
+            hours_min: Some(2),
+            contest_category: Some(" A ".to_string()),
+            ..Default::default()
+        };
+        let sql = sql(filter.record_condition());
+        assert!(sql.contains(r#""award_date" >= '2026-01-01"#));
+        assert!(sql.contains(r#""award_date" < '2026-02-01"#));
+        assert!(sql.contains(r#""self_hours" >= 2"#));
+        assert!(sql.contains(r#""contest_category" = 'A'"#));
+        assert!(filter.has_record_filters());
+    }
+
+    #[test]
+    fn blank_student_fields_are_ignored() {
+        let filter = RecordFilter {
+            class_name: Some("  ".to_string()),
+            student_no: Some("2023001".to_string()),
+            ..Default::default()
+        };
+        let sql = sql(filter.student_condition());
+        assert!(sql.contains(r#""student_no" = '2023001'"#));
+        assert!(!sql.contains("class_name"));
+        assert!(!filter.has_record_filters());
+    }
+
+    #[test]
+    fn term_filters_use_semester_column() {
+        let semester_id = Uuid::new_v4();
+        let filter = RecordFilter {
+            semester_id: Some(semester_id),
+            academic_year: Some("2025-2026".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.validate().is_ok());
+        assert!(filter.has_record_filters());
+        let sql = sql(filter.record_condition());
+        assert!(sql.contains(&semester_id.to_string()));
+        assert!(sql.contains(r#""semester_id" IN (SELECT "id" FROM "semesters""#));
+        assert!(sql.contains(r#""academic_year" = '2025-2026'"#));
+
+        let filter = RecordFilter {
+            academic_year: Some("2025-2027".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.validate().is_err());
+    }
+
+    #[test]
+    fn match_status_filter_defaults_to_accept() {
+        assert!(RecordFilter::default().accepts_match_status("fuzzy"));
+        let filter = RecordFilter {
+            match_status: Some("matched".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.accepts_match_status("matched"));
+        assert!(!filter.accepts_match_status("unmatched"));
+    }
+}
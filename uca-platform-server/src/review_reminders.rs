@@ -0,0 +1,432 @@
+//! 审核积压提醒：定期找出等待审核超过指定天数的记录，提醒负责的审核人，并向管理员发送积压汇总。
+//!
+//! 记录的等待时间从最后更新时间算起。已指派审核人且其可处理当前阶段时只提醒该审核人，
+//! 否则提醒院系范围内可处理该阶段的全部审核人员（待初审为 reviewer，待复审为 teacher）。
+//! 同一用户在 [`REMINDER_INTERVAL_HOURS`] 内最多收到一次提醒，任务间隔较短时不会重复打扰。
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect};
+use uuid::Uuid;
+
+use crate::{
+    access::department_scope,
+    assignments::PENDING_STATUSES,
+    entities::{
+        contest_records, notifications, users, volunteer_records, ContestRecord, Notification, Student, User,
+        VolunteerRecord,
+    },
+    error::AppError,
+    i18n::Locale,
+    mail_outbox::enqueue_mail,
+    mailer::{MailTemplateDef, MailTemplates, REVIEW_DIGEST_MAIL, REVIEW_REMINDER_MAIL},
+    notify::{create_notifications, NewNotification, KIND_REVIEW_DIGEST, KIND_REVIEW_REMINDER},
+    services::review::{role_can_review, REVIEW_STAGE_FINAL, REVIEW_STAGE_FIRST, STATUS_SUBMITTED},
+    state::AppState,
+};
+
+/// 同一用户两次提醒的最短间隔（小时）。
+pub const REMINDER_INTERVAL_HOURS: i64 = 24;
+
+/// 一条超期未审的记录。
+#[derive(Debug, Clone)]
+pub struct OverdueRecord {
+    /// 待处理的审核阶段（first/final）。
+    pub stage: &'static str,
+    /// 学生所属院系。
+    pub department: String,
+    /// 指派的审核人。
+    pub assigned_reviewer_id: Option<Uuid>,
+    /// 开始等待的时间（记录最后更新时间）。
+    pub waiting_since: DateTime<Utc>,
+}
+
+/// 某一审核阶段的积压。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StageBacklog {
+    /// 记录数。
+    pub count: u64,
+    /// 等待最久的记录开始等待的时间。
+    pub oldest: Option<DateTime<Utc>>,
+}
+
+impl StageBacklog {
+    fn add(&mut self, waiting_since: DateTime<Utc>) {
+        self.count += 1;
+        self.oldest = Some(self.oldest.map_or(waiting_since, |oldest| oldest.min(waiting_since)));
+    }
+}
+
+/// 按审核阶段统计的积压。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReviewBacklog {
+    /// 待初审。
+    pub first: StageBacklog,
+    /// 待复审。
+    pub final_stage: StageBacklog,
+}
+
+impl ReviewBacklog {
+    fn add(&mut self, record: &OverdueRecord) {
+        if record.stage == REVIEW_STAGE_FIRST {
+            self.first.add(record.waiting_since);
+        } else {
+            self.final_stage.add(record.waiting_since);
+        }
+    }
+
+    /// 积压记录总数。
+    pub fn total(&self) -> u64 {
+        self.first.count + self.final_stage.count
+    }
+}
+
+/// 一次提醒的分发计划。
+#[derive(Debug, Default)]
+pub struct ReminderPlan {
+    /// 各审核人负责的积压。
+    pub reviewers: HashMap<Uuid, ReviewBacklog>,
+    /// 全部积压。
+    pub overall: ReviewBacklog,
+    /// 按院系统计的积压记录数。
+    pub by_department: BTreeMap<String, u64>,
+    /// 找不到可提醒审核人的记录数。
+    pub unassigned: u64,
+}
+
+/// 读取等待审核超过截止时间的记录（不含已删除的记录与学生）。
+pub async fn load_overdue_records<C>(db: &C, cutoff: DateTime<Utc>) -> Result<Vec<OverdueRecord>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let contest = ContestRecord::find()
+        .find_also_related(Student)
+        .filter(contest_records::Column::IsDeleted.eq(false))
+        .filter(contest_records::Column::Status.is_in(PENDING_STATUSES))
+        .filter(contest_records::Column::UpdatedAt.lt(cutoff))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let volunteer = VolunteerRecord::find()
+        .find_also_related(Student)
+        .filter(volunteer_records::Column::IsDeleted.eq(false))
+        .filter(volunteer_records::Column::Status.is_in(PENDING_STATUSES))
+        .filter(volunteer_records::Column::UpdatedAt.lt(cutoff))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let contest = contest.into_iter().filter_map(|(record, student)| {
+        let student = student.filter(|student| !student.is_deleted)?;
+        Some(overdue_record(&record.status, student.department, record.assigned_reviewer_id, record.updated_at))
+    });
+    let volunteer = volunteer.into_iter().filter_map(|(record, student)| {
+        let student = student.filter(|student| !student.is_deleted)?;
+        Some(overdue_record(&record.status, student.department, record.assigned_reviewer_id, record.updated_at))
+    });
+    Ok(contest.chain(volunteer).collect())
+}
+
+fn overdue_record(
+    status: &str,
+    department: String,
+    assigned_reviewer_id: Option<Uuid>,
+    waiting_since: DateTime<Utc>,
+) -> OverdueRecord {
+    OverdueRecord {
+        stage: if status == STATUS_SUBMITTED {
+            REVIEW_STAGE_FIRST
+        } else {
+            REVIEW_STAGE_FINAL
+        },
+        department: department.trim().to_string(),
+        assigned_reviewer_id,
+        waiting_since,
+    }
+}
+
+/// 按记录确定负责的审核人并汇总积压；`reviewers` 为启用中的审核人员与教师。
+pub fn plan_review_reminders(records: &[OverdueRecord], reviewers: &[users::Model]) -> ReminderPlan {
+    let mut plan = ReminderPlan::default();
+    for record in records {
+        plan.overall.add(record);
+        *plan.by_department.entry(record.department.clone()).or_default() += 1;
+        let responsible = responsible_reviewers(record, reviewers);
+        if responsible.is_empty() {
+            plan.unassigned += 1;
+        }
+        for reviewer in responsible {
+            plan.reviewers.entry(reviewer.id).or_default().add(record);
+        }
+    }
+    plan
+}
+
+fn responsible_reviewers<'a>(record: &OverdueRecord, reviewers: &'a [users::Model]) -> Vec<&'a users::Model> {
+    let assigned = record.assigned_reviewer_id.and_then(|id| {
+        reviewers
+            .iter()
+            .find(|reviewer| reviewer.id == id && role_can_review(&reviewer.role, record.stage))
+    });
+    if let Some(reviewer) = assigned {
+        return vec![reviewer];
+    }
+    reviewers
+        .iter()
+        .filter(|reviewer| reviewer.role != "admin" && role_can_review(&reviewer.role, record.stage))
+        .filter(|reviewer| department_scope(reviewer).is_none_or(|department| department == record.department))
+        .collect()
+}
+
+/// 提醒正文：按审核阶段每项一行。
+pub fn backlog_details(locale: Locale, backlog: &ReviewBacklog, now: DateTime<Utc>) -> String {
+    let mut details = String::new();
+    for (label_key, stage) in [
+        ("reminder.stage.first", &backlog.first),
+        ("reminder.stage.final", &backlog.final_stage),
+    ] {
+        let Some(oldest) = stage.oldest else {
+            continue;
+        };
+        details.push_str(&locale.format(
+            "reminder.stage_line",
+            &[
+                ("stage", locale.text(label_key)),
+                ("count", &stage.count.to_string()),
+                ("waiting", &(now - oldest).num_days().to_string()),
+            ],
+        ));
+        details.push('\n');
+    }
+    details
+}
+
+/// 管理员汇总正文：按审核阶段、院系统计，并列出找不到审核人的记录数。
+pub fn digest_details(locale: Locale, plan: &ReminderPlan, now: DateTime<Utc>) -> String {
+    let mut details = backlog_details(locale, &plan.overall, now);
+    for (department, count) in &plan.by_department {
+        details.push_str(&locale.format(
+            "reminder.department_line",
+            &[("department", department), ("count", &count.to_string())],
+        ));
+        details.push('\n');
+    }
+    if plan.unassigned > 0 {
+        details.push_str(&locale.format(
+            "reminder.unassigned_line",
+            &[("count", &plan.unassigned.to_string())],
+        ));
+        details.push('\n');
+    }
+    details
+}
+
+/// 发送审核积压提醒与管理员汇总，返回收到提醒的用户数。
+pub async fn send_review_reminders(state: &AppState) -> Result<u64, AppError> {
+    let days = state.config.jobs.review_reminder_days;
+    let now = Utc::now();
+    let records = load_overdue_records(&state.db, now - Duration::days(i64::from(days))).await?;
+    if records.is_empty() {
+        return Ok(0);
+    }
+    let users = User::find()
+        .filter(users::Column::Role.is_in(["reviewer", "teacher", "admin"]))
+        .filter(users::Column::IsActive.eq(true))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let (admins, reviewers): (Vec<users::Model>, Vec<users::Model>) =
+        users.into_iter().partition(|user| user.role == "admin");
+    let plan = plan_review_reminders(&records, &reviewers);
+    let reminded = recently_reminded(&state.db, now - Duration::hours(REMINDER_INTERVAL_HOURS)).await?;
+    let days = days.to_string();
+
+    let mut notices = Vec::new();
+    for reviewer in &reviewers {
+        let Some(backlog) = plan.reviewers.get(&reviewer.id) else {
+            continue;
+        };
+        if reminded.contains(&reviewer.id) {
+            continue;
+        }
+        let locale = state.locale_for(reviewer);
+        let count = backlog.total().to_string();
+        let title = locale.format("reminder.title", &[("count", &count), ("days", &days)]);
+        notices.push(ReminderNotice {
+            user: reviewer,
+            kind: KIND_REVIEW_REMINDER,
+            mail: &REVIEW_REMINDER_MAIL,
+            locale,
+            title,
+            count,
+            details: backlog_details(locale, backlog, now),
+        });
+    }
+    for admin in &admins {
+        if reminded.contains(&admin.id) {
+            continue;
+        }
+        let locale = state.locale_for(admin);
+        let count = plan.overall.total().to_string();
+        let title = locale.format("reminder.digest_title", &[("count", &count), ("days", &days)]);
+        notices.push(ReminderNotice {
+            user: admin,
+            kind: KIND_REVIEW_DIGEST,
+            mail: &REVIEW_DIGEST_MAIL,
+            locale,
+            title,
+            count,
+            details: digest_details(locale, &plan, now),
+        });
+    }
+
+    let in_app = notices
+        .iter()
+        .map(|notice| {
+            NewNotification::new(
+                notice.user.id,
+                notice.kind,
+                notice.title.clone(),
+                notice.details.trim_end().to_string(),
+            )
+        })
+        .collect();
+    create_notifications(&state.db, in_app).await?;
+
+    if state.mail_config().is_some() {
+        let mut templates = HashMap::new();
+        for def in [&REVIEW_REMINDER_MAIL, &REVIEW_DIGEST_MAIL] {
+            let loaded = MailTemplates::load(&state.db, def).await.unwrap_or_else(|err| {
+                tracing::warn!(error = %err, template = def.key, "load reminder mail template failed, using default");
+                MailTemplates::default()
+            });
+            templates.insert(def.key, loaded);
+        }
+        for notice in &notices {
+            let Some(email) = notice.user.email.as_deref().filter(|_| notice.user.notify_review_email) else {
+                continue;
+            };
+            let (subject, body) = templates[notice.mail.key].render(
+                notice.mail,
+                notice.locale,
+                &[
+                    ("name", &notice.user.display_name),
+                    ("count", &notice.count),
+                    ("days", &days),
+                    ("details", &notice.details),
+                ],
+            );
+            if let Err(err) = enqueue_mail(state, email, &subject, &body).await {
+                tracing::warn!(error = %err, "enqueue review reminder email failed");
+            }
+        }
+    }
+    Ok(notices.len() as u64)
+}
+
+/// 待发送的一条提醒。
+struct ReminderNotice<'a> {
+    user: &'a users::Model,
+    kind: &'static str,
+    mail: &'static MailTemplateDef,
+    locale: Locale,
+    title: String,
+    count: String,
+    details: String,
+}
+
+/// 提醒间隔内已收到提醒或汇总的用户。
+async fn recently_reminded<C>(db: &C, since: DateTime<Utc>) -> Result<HashSet<Uuid>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let rows = Notification::find()
+        .select_only()
+        .column(notifications::Column::UserId)
+        .filter(notifications::Column::Kind.is_in([KIND_REVIEW_REMINDER, KIND_REVIEW_DIGEST]))
+        .filter(notifications::Column::CreatedAt.gte(since))
+        .into_tuple::<Uuid>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(rows.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(role: &str, department: Option<&str>) -> users::Model {
+        let now = Utc::now();
+        users::Model {
+            id: Uuid::new_v4(),
+            username: format!("{role}-{}", Uuid::new_v4().simple()),
+            display_name: role.to_string(),
+            role: role.to_string(),
+            email: None,
+            password_hash: None,
+            allow_password_login: false,
+            password_updated_at: None,
+            must_change_password: false,
+            is_active: true,
+            department: department.map(str::to_string),
+            notify_review_email: true,
+            locale: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn record(stage: &'static str, department: &str, assigned: Option<Uuid>, days_ago: i64) -> OverdueRecord {
+        OverdueRecord {
+            stage,
+            department: department.to_string(),
+            assigned_reviewer_id: assigned,
+            waiting_since: Utc::now() - Duration::days(days_ago),
+        }
+    }
+
+    #[test]
+    fn plan_routes_records_to_responsible_reviewers() {
+        let info_reviewer = user("reviewer", Some("信息学院"));
+        let any_reviewer = user("reviewer", None);
+        let chem_reviewer = user("reviewer", Some("化工学院"));
+        let teacher = user("teacher", Some("信息学院"));
+        let reviewers = vec![info_reviewer.clone(), any_reviewer.clone(), chem_reviewer.clone(), teacher.clone()];
+        let records = vec![
+            record(REVIEW_STAGE_FIRST, "信息学院", None, 10),
+            record(REVIEW_STAGE_FIRST, "化工学院", Some(chem_reviewer.id), 8),
+            record(REVIEW_STAGE_FINAL, "信息学院", Some(info_reviewer.id), 9),
+            record(REVIEW_STAGE_FINAL, "外语学院", None, 12),
+        ];
+
+        let plan = plan_review_reminders(&records, &reviewers);
+        assert_eq!(plan.reviewers[&info_reviewer.id].total(), 1);
+        assert_eq!(plan.reviewers[&any_reviewer.id].total(), 1);
+        assert_eq!(plan.reviewers[&chem_reviewer.id].first.count, 1);
+        // 指派的审核人不能处理复审时，改为提醒院系内的教师。
+        assert_eq!(plan.reviewers[&teacher.id].final_stage.count, 1);
+        assert_eq!(plan.unassigned, 1);
+        assert_eq!(plan.overall.total(), 4);
+        assert_eq!(plan.by_department["信息学院"], 2);
+    }
+
+    #[test]
+    fn details_list_stages_and_departments() {
+        let now = Utc::now();
+        let records = vec![
+            record(REVIEW_STAGE_FIRST, "信息学院", None, 10),
+            record(REVIEW_STAGE_FIRST, "信息学院", None, 8),
+        ];
+        let plan = plan_review_reminders(&records, &[]);
+        assert_eq!(
+            backlog_details(Locale::ZhCn, &plan.overall, now),
+            "待初审：2 条，最久已等待 10 天\n"
+        );
+        assert_eq!(
+            digest_details(Locale::ZhCn, &plan, now),
+            "待初审：2 条，最久已等待 10 天\n信息学院：2 条\n没有可提醒的审核人：2 条\n"
+        );
+    }
+}
@@ -1,34 +1,101 @@
 //! 管理员维护接口。
 
-use axum::{extract::{State, Multipart, Path}, Json};
+use axum::{
+    extract::{State, Multipart, Path, Query},
+    http::HeaderMap,
+    response::{IntoResponse, Response},
+    Json,
+};
 use axum_extra::extract::cookie::CookieJar;
-use calamine::{Data, Reader};
-use chrono::{Duration as ChronoDuration, TimeZone, Utc};
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set, TransactionTrait};
+use base64::Engine;
+use calamine::Data;
+use chrono::{Duration as ChronoDuration, NaiveDate, TimeZone, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::io::Cursor;
+use std::sync::Arc;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    access::{require_role, require_session_user},
+    access::{
+        advisor_class_scope, department_scope, normalize_department, require_role, require_session_user,
+        ROLE_CLASS_ADVISOR,
+    },
+    assignments::{
+        apply_assignment, load_assignable_reviewers, load_candidates, pending_workload, plan_round_robin,
+        reviewer_workloads, AssignGroup, AssignRecordType, ReviewerSlot, ReviewerWorkload,
+    },
+    attachment_requirements::MAX_REQUIRED_ATTACHMENTS,
+    audit::record_audit,
+    backup::{self, BackupManifest},
     auth::{generate_token, hash_password, hash_token},
+    db::insert_chunked,
     entities::{
-        attachments, auth_resets, competition_library, contest_records, form_field_values, form_fields,
-        invites, review_signatures, students, users,
-        Attachment, CompetitionLibrary, ContestRecord, FormField, FormFieldValue,
-        ReviewSignature, Student, User,
+        advisor_classes, attachment_requirements, auth_resets, competition_library, contest_records,
+        export_profiles, form_field_values, form_fields, invites, security_events, semesters, students,
+        submission_windows, users, volunteer_records, AttachmentRequirement, CompetitionLibrary, ContestRecord,
+        ExportProfile, FormField, FormFieldValue, SecurityEvent, Semester, Student, SubmissionWindow, User,
+        VolunteerRecord,
     },
     error::AppError,
-    labor_hours::{load_labor_hour_rules, upsert_labor_hour_rules, LaborHourRuleConfig},
-    mailer::send_mail,
-    policy::{load_password_policy, upsert_password_policy},
+    events::QueueEvent,
+    export_profiles::{
+        export_profile_def, find_export_profile, parse_export_columns, ExportColumn, ExportProfileDef,
+        EXPORT_PROFILES,
+    },
+    fuzzy,
+    import_batches::{record_import_batch, IMPORT_KIND_CONTEST},
+    import_jobs::IMPORT_LOOKUP_CHUNK_SIZE,
+    labor_hours::{
+        load_labor_hour_rules, plan_hour_recompute, upsert_labor_hour_rules, HourRecomputeReport,
+        LaborHourRuleConfig,
+    },
+    mail_outbox::enqueue_mail,
+    mailer::{render_mail, INVITE_MAIL, PASSKEY_RESET_MAIL, TOTP_RESET_MAIL},
+    notify::{push_notifications, NewNotification, KIND_PASSWORD_RESET},
+    purge::{self, BulkPurgeFilter, OrphanReport, PurgeSummary},
+    policy::{
+        load_password_policy, load_student_password_rule, upsert_password_policy,
+        upsert_student_password_rule, StudentPasswordRule,
+    },
+    reconcile::{check_student_users, repair_student_users, ReconcileReport},
+    routes::{
+        auth::require_reauth,
+        exports::{file_response, stream_file_response},
+    },
+    secret_rotation::{rotate_secrets, secret_usage, SecretRotationReport, SecretUsageReport},
+    seed::{seed_demo_data, SeedSummary},
+    security::{
+        record_security_event, role_grant_severity, SecurityEventInput, EVENT_RESET_ISSUED,
+        EVENT_ROLE_GRANTED, SEVERITY_HIGH,
+    },
     state::AppState,
+    storage_usage::{storage_usage, StorageUsageReport, DEFAULT_TOP_STUDENTS},
+    student_bundle::{build_student_bundle, write_bundle_zip},
+    services::{
+        record::{check_field_value, field_options, normalize_field_definition},
+        review::{STATUS_FINAL_REVIEWED, STATUS_FIRST_REVIEWED, STATUS_REJECTED, STATUS_SUBMITTED},
+    },
+    semesters::{
+        clear_semester_records, contest_semester_date, ensure_semester_range, load_semester_years,
+        reassign_semester_records, semester_containing, validate_academic_year,
+    },
+    spreadsheet::{
+        expected_headers, find_header_index, locate_header, parse_hours, read_cell_by_index,
+        read_cell_by_index_opt, read_upload_workbook, resolve_status, HeaderOptions,
+    },
+    submission_windows::{ensure_window_unique, validate_record_type, validate_window_range},
+    system::{collect_system_info, SystemInfo},
     templates::{
-        export_template_file_path, load_export_template, upsert_export_template_meta,
-        ExportTemplateConfig,
+        export_template_file_path, register_export_template, require_export_template,
+        update_export_template_info, upsert_export_template_meta, ExportTemplateConfig,
+        BUILTIN_EXPORT_TEMPLATE_KEY,
     },
+    uploads::{multipart_error, read_file_field, spool_file_field},
 };
 
 /// 竞赛库新增请求。
@@ -68,6 +135,118 @@ pub struct CompetitionResponse {
     pub name: String,
 }
 
+/// 学期新增/更新请求。
+#[derive(Debug, Deserialize, Validate)]
+pub struct SemesterRequest {
+    /// 学期名称（如 `2025-2026 学年第一学期`）。
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    /// 所属学年（如 `2025-2026`）。
+    pub academic_year: String,
+    /// 开始日期（含）。
+    pub start_date: NaiveDate,
+    /// 结束日期（含）。
+    pub end_date: NaiveDate,
+}
+
+/// 学期响应。
+#[derive(Debug, Serialize)]
+pub struct SemesterResponse {
+    /// 学期 ID。
+    pub id: Uuid,
+    /// 学期名称。
+    pub name: String,
+    /// 所属学年。
+    pub academic_year: String,
+    /// 开始日期。
+    pub start_date: NaiveDate,
+    /// 结束日期。
+    pub end_date: NaiveDate,
+}
+
+/// 学期保存响应。
+#[derive(Debug, Serialize)]
+pub struct SemesterSaveResponse {
+    /// 学期。
+    pub semester: SemesterResponse,
+    /// 按提交时间归入该学期的记录数。
+    pub assigned_records: u64,
+}
+
+/// 提交窗口新增/更新请求。
+#[derive(Debug, Deserialize, Validate)]
+pub struct SubmissionWindowRequest {
+    /// 记录类型：contest/volunteer。
+    pub record_type: String,
+    /// 院系覆盖；留空表示该记录类型的默认窗口。
+    #[validate(length(max = 64))]
+    pub department: Option<String>,
+    /// 开放时间（含）。
+    pub opens_at: chrono::DateTime<Utc>,
+    /// 截止时间（含）。
+    pub closes_at: chrono::DateTime<Utc>,
+}
+
+/// 提交窗口响应。
+#[derive(Debug, Serialize)]
+pub struct SubmissionWindowResponse {
+    /// 窗口 ID。
+    pub id: Uuid,
+    /// 记录类型。
+    pub record_type: String,
+    /// 院系覆盖。
+    pub department: Option<String>,
+    /// 开放时间。
+    pub opens_at: chrono::DateTime<Utc>,
+    /// 截止时间。
+    pub closes_at: chrono::DateTime<Utc>,
+}
+
+/// 附件要求规则新增/更新请求。
+#[derive(Debug, Deserialize, Validate)]
+pub struct AttachmentRequirementRequest {
+    /// 记录类型：contest/volunteer。
+    pub record_type: String,
+    /// 适用的竞赛级别；留空时不限级别（仅竞赛记录可设置）。
+    #[validate(length(max = 64))]
+    pub contest_level: Option<String>,
+    /// 自定义字段条件的字段键；留空时不限。
+    #[validate(length(max = 64))]
+    pub field_key: Option<String>,
+    /// 自定义字段需等于的值；留空时字段非空即满足。
+    #[validate(length(max = 255))]
+    pub field_value: Option<String>,
+    /// 至少需要的附件数量，默认 1。
+    pub min_attachments: Option<i32>,
+    /// 展示给学生与审核人员的说明。
+    #[validate(length(min = 1, max = 255))]
+    pub description: String,
+}
+
+/// 相似竞赛查询参数。
+#[derive(Debug, Deserialize)]
+pub struct SimilarCompetitionQuery {
+    /// 待检查的竞赛名称。
+    pub name: String,
+    /// 返回条数（默认 5，最大 20）。
+    pub limit: Option<usize>,
+}
+
+/// 相似竞赛响应。
+#[derive(Debug, Serialize)]
+pub struct SimilarCompetitionResponse {
+    /// 记录 ID。
+    pub id: Uuid,
+    /// 竞赛年份。
+    pub year: Option<i32>,
+    /// 竞赛类型。
+    pub category: Option<String>,
+    /// 竞赛名称。
+    pub name: String,
+    /// 相似度（0~1）。
+    pub score: f64,
+}
+
 /// 劳动学时规则请求。
 #[derive(Debug, Deserialize, Serialize)]
 pub struct LaborHourRuleRequest {
@@ -79,6 +258,23 @@ pub struct LaborHourRuleRequest {
     pub provincial_member_hours: i32,
     pub school_leader_hours: i32,
     pub school_member_hours: i32,
+    /// 每名学生每学年计入的通过学时上限；为空表示不限。
+    #[serde(default)]
+    pub annual_cap_hours: Option<i32>,
+}
+
+/// 推荐学时重算请求。
+#[derive(Debug, Deserialize)]
+pub struct RecomputeHoursRequest {
+    /// 参与重算的记录状态，为空表示全部状态。
+    #[serde(default)]
+    pub statuses: Vec<String>,
+    /// 参与重算的学年（如 `2025-2026`），为空表示全部学年。
+    #[serde(default)]
+    pub academic_years: Vec<String>,
+    /// 是否以推荐学时覆盖未审核（submitted）记录的初审学时。
+    #[serde(default)]
+    pub fill_first_review: bool,
 }
 
 /// 新建用户请求。
@@ -98,6 +294,9 @@ pub struct CreateUserRequest {
     pub email: Option<String>,
     /// 内网模式下的认证重置用途（totp/passkey）。
     pub reset_purpose: Option<String>,
+    /// 所属院系（教师/审核人员仅能查看该院系学生；留空不限制）。
+    #[validate(length(max = 64))]
+    pub department: Option<String>,
 }
 
 /// 新建用户响应。
@@ -113,6 +312,39 @@ pub struct CreateUserResponse {
     pub reset_purpose: Option<String>,
 }
 
+/// 更新用户院系请求。
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateUserDepartmentRequest {
+    /// 所属院系；留空表示不限制数据范围。
+    #[validate(length(max = 64))]
+    pub department: Option<String>,
+}
+
+/// 用户院系响应。
+#[derive(Debug, Serialize)]
+pub struct UserDepartmentResponse {
+    /// 用户 ID。
+    pub user_id: Uuid,
+    /// 所属院系。
+    pub department: Option<String>,
+}
+
+/// 设置班主任负责班级请求。
+#[derive(Debug, Deserialize)]
+pub struct UpdateAdvisorClassesRequest {
+    /// 负责的班级名称（整体替换）。
+    pub classes: Vec<String>,
+}
+
+/// 班主任负责班级响应。
+#[derive(Debug, Serialize)]
+pub struct AdvisorClassesResponse {
+    /// 用户 ID。
+    pub user_id: Uuid,
+    /// 负责的班级名称。
+    pub classes: Vec<String>,
+}
+
 /// 密码策略配置请求。
 #[derive(Debug, Deserialize)]
 pub struct PasswordPolicyRequest {
@@ -140,6 +372,35 @@ pub struct ExportTemplateResponse {
     pub name: String,
     pub issues: Vec<String>,
     pub orientation: String,
+    pub display_name: String,
+    pub description: Option<String>,
+    pub export_type: String,
+}
+
+/// 登记导出模板请求。
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateExportTemplateRequest {
+    /// 模板 key（小写字母、数字、`_`、`-`）。
+    pub template_key: String,
+    /// 显示名称。
+    #[validate(length(min = 1, max = 64))]
+    pub display_name: String,
+    /// 模板说明。
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    /// 适用的导出类型，默认 `labor_hours`。
+    pub export_type: Option<String>,
+}
+
+/// 更新导出模板信息请求。
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateExportTemplateRequest {
+    /// 显示名称。
+    #[validate(length(min = 1, max = 64))]
+    pub display_name: String,
+    /// 模板说明。
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
 }
 
 /// 重置认证方式请求。
@@ -172,6 +433,8 @@ struct CompetitionSheetPlan {
     name_column: Option<String>,
     category_column: Option<String>,
     category_suffix: Option<String>,
+    header_row_index: Option<usize>,
+    header_rows: Option<usize>,
 }
 
 const INVITE_TTL_HOURS: i64 = 72;
@@ -195,7 +458,9 @@ const CONTEST_IMPORT_HEADERS: [(&str, &[&str]); 13] = [
     ("status", &["审核状态", "status"]),
     ("rejection_reason", &["不通过原因", "rejection_reason"]),
 ];
-const EXPORT_TEMPLATE_KEYS: [&str; 1] = ["labor_hours"];
+const SIMILAR_DEFAULT_LIMIT: usize = 5;
+const SIMILAR_MAX_LIMIT: usize = 20;
+const SIMILAR_MIN_SCORE: f64 = 0.3;
 
 /// 查询竞赛库。
 pub async fn list_competitions(
@@ -245,6 +510,64 @@ pub async fn list_competitions_public(
     ))
 }
 
+/// 查询与输入名称相似的已有竞赛（用于录入时提示重复）。
+pub async fn list_similar_competitions(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<SimilarCompetitionQuery>,
+) -> Result<Json<Vec<SimilarCompetitionResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    if !state.competition_match.lock().await.allow_request(user.id) {
+        return Err(AppError::too_many_requests("similar competition lookup rate limited"));
+    }
+    if fuzzy::normalize_name(&query.name).is_empty() {
+        return Ok(Json(Vec::new()));
+    }
+    let items = load_competition_snapshot(&state).await?;
+
+    let limit = query
+        .limit
+        .unwrap_or(SIMILAR_DEFAULT_LIMIT)
+        .clamp(1, SIMILAR_MAX_LIMIT);
+    let matches = fuzzy::top_matches(
+        &query.name,
+        items.iter().map(|item| (item.name.as_str(), item)),
+        limit,
+        SIMILAR_MIN_SCORE,
+    );
+    Ok(Json(
+        matches
+            .into_iter()
+            .map(|(item, score)| SimilarCompetitionResponse {
+                id: item.id,
+                year: item.year,
+                category: item.category.clone(),
+                name: item.name.clone(),
+                score: (score * 1000.0).round() / 1000.0,
+            })
+            .collect(),
+    ))
+}
+
+/// 读取竞赛库快照（带短期缓存，竞赛库变更时失效）。
+pub(crate) async fn load_competition_snapshot(
+    state: &AppState,
+) -> Result<Arc<Vec<competition_library::Model>>, AppError> {
+    if let Some(items) = state.competition_match.lock().await.snapshot() {
+        return Ok(items);
+    }
+    let items = Arc::new(
+        CompetitionLibrary::find()
+            .all(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?,
+    );
+    state.competition_match.lock().await.set_snapshot(items.clone());
+    Ok(items)
+}
+
 /// 新增竞赛名称。
 pub async fn create_competition(
     State(state): State<AppState>,
@@ -294,6 +617,7 @@ pub async fn create_competition(
         .exec_without_returning(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    state.competition_match.lock().await.invalidate();
 
     Ok(Json(CompetitionResponse {
         id,
@@ -343,6 +667,7 @@ pub async fn update_competition(
         .update(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    state.competition_match.lock().await.invalidate();
 
     Ok(Json(CompetitionResponse {
         id: model.id,
@@ -368,122 +693,665 @@ pub async fn delete_competition(
     if result.rows_affected == 0 {
         return Err(AppError::not_found("competition not found"));
     }
+    state.competition_match.lock().await.invalidate();
 
     Ok(Json(serde_json::json!({ "status": "ok" })))
 }
 
-/// 管理员创建用户或发送邀请。
-pub async fn create_user(
+/// 学期列表（登录用户，用于按学期筛选），按开始日期倒序。
+pub async fn list_semesters(
     State(state): State<AppState>,
     jar: CookieJar,
-    Json(payload): Json<CreateUserRequest>,
-) -> Result<Json<CreateUserResponse>, AppError> {
-    let admin = require_session_user(&state, &jar).await?;
-    require_role(&admin, "admin")?;
+) -> Result<Json<Vec<SemesterResponse>>, AppError> {
+    require_session_user(&state, &jar).await?;
+    let items = Semester::find()
+        .order_by_desc(semesters::Column::StartDate)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(items.into_iter().map(semester_to_response).collect()))
+}
 
-    payload
-        .validate()
-        .map_err(|_| AppError::validation("invalid user payload"))?;
+/// 新增学期（管理员），并将提交时间在范围内且未归属学期的记录划入。
+pub async fn create_semester(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<SemesterRequest>,
+) -> Result<Json<SemesterSaveResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    validate_semester_payload(&payload)?;
 
-    let role = payload.role.as_str();
-    if !matches!(role, "student" | "teacher" | "reviewer" | "admin") {
-        return Err(AppError::validation("invalid role"));
-    }
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    ensure_semester_name_available(&transaction, payload.name.trim(), None).await?;
+    ensure_semester_range(&transaction, payload.start_date, payload.end_date, None).await?;
+    let now = Utc::now();
+    let model = semesters::Model {
+        id: Uuid::new_v4(),
+        name: payload.name.trim().to_string(),
+        academic_year: payload.academic_year.trim().to_string(),
+        start_date: payload.start_date,
+        end_date: payload.end_date,
+        created_at: now,
+        updated_at: now,
+    };
+    let active: semesters::ActiveModel = model.clone().into();
+    semesters::Entity::insert(active)
+        .exec_without_returning(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let assigned_records = reassign_semester_records(&transaction, &model).await?;
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "semester_create",
+        "semester",
+        Some(&model.id.to_string()),
+        Some(serde_json::json!({
+            "name": model.name,
+            "start_date": model.start_date,
+            "end_date": model.end_date,
+        })),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
 
-    if role == "student" {
-        let _student = Student::find()
-            .filter(students::Column::StudentNo.eq(&payload.username))
-            .one(&state.db)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?
-            .ok_or_else(|| AppError::bad_request("student not found"))?;
+    Ok(Json(SemesterSaveResponse {
+        semester: semester_to_response(model),
+        assigned_records,
+    }))
+}
 
-        let now = Utc::now();
-        let default_password = format!("st{}", payload.username);
-        let hash = hash_password(&default_password)?;
+/// 更新学期（管理员），按新的日期范围重新归属记录。
+pub async fn update_semester(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(semester_id): Path<Uuid>,
+    Json(payload): Json<SemesterRequest>,
+) -> Result<Json<SemesterSaveResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    validate_semester_payload(&payload)?;
 
-        if let Some(existing) = User::find()
-            .filter(users::Column::Username.eq(&payload.username))
-            .one(&state.db)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?
-        {
-            let missing_password = existing.password_hash.is_none();
-            let mut active: users::ActiveModel = existing.into();
-            active.display_name = Set(payload.display_name.clone());
-            active.role = Set("student".to_string());
-            if payload.email.is_some() {
-                active.email = Set(payload.email.clone());
-            }
-            if missing_password {
-                active.password_hash = Set(Some(hash));
-            }
-            active.allow_password_login = Set(true);
-            active.must_change_password = Set(true);
-            active.updated_at = Set(now);
-            let model = active
-                .update(&state.db)
-                .await
-                .map_err(|err| AppError::Database(err.to_string()))?;
-            return Ok(Json(CreateUserResponse {
-                user_id: Some(model.id),
-                invite_sent: false,
-                reset_code: None,
-                reset_purpose: None,
-            }));
-        }
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let existing = Semester::find_by_id(semester_id)
+        .one(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("semester not found"))?;
+    ensure_semester_name_available(&transaction, payload.name.trim(), Some(semester_id)).await?;
+    ensure_semester_range(&transaction, payload.start_date, payload.end_date, Some(semester_id)).await?;
+    let previous = serde_json::json!({
+        "name": existing.name,
+        "start_date": existing.start_date,
+        "end_date": existing.end_date,
+    });
+    let mut active: semesters::ActiveModel = existing.into();
+    active.name = Set(payload.name.trim().to_string());
+    active.academic_year = Set(payload.academic_year.trim().to_string());
+    active.start_date = Set(payload.start_date);
+    active.end_date = Set(payload.end_date);
+    active.updated_at = Set(Utc::now());
+    let model = active
+        .update(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let assigned_records = reassign_semester_records(&transaction, &model).await?;
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "semester_update",
+        "semester",
+        Some(&model.id.to_string()),
+        Some(serde_json::json!({
+            "from": previous,
+            "to": {
+                "name": model.name,
+                "start_date": model.start_date,
+                "end_date": model.end_date,
+            },
+        })),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
 
-        let user_id = Uuid::new_v4();
-        let model = users::ActiveModel {
-            id: Set(user_id),
-            username: Set(payload.username.clone()),
-            display_name: Set(payload.display_name.clone()),
-            role: Set("student".to_string()),
-            email: Set(payload.email.clone()),
-            password_hash: Set(Some(hash)),
-            allow_password_login: Set(true),
-            password_updated_at: Set(Some(now)),
-            must_change_password: Set(true),
-            is_active: Set(true),
-            created_at: Set(now),
-            updated_at: Set(now),
-        };
-        users::Entity::insert(model)
-            .exec_without_returning(&state.db)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(SemesterSaveResponse {
+        semester: semester_to_response(model),
+        assigned_records,
+    }))
+}
 
-        return Ok(Json(CreateUserResponse {
-            user_id: Some(user_id),
-            invite_sent: false,
-            reset_code: None,
-            reset_purpose: None,
-        }));
-    }
+/// 删除学期（管理员），记录的学期归属随之清除。
+pub async fn delete_semester(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(semester_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
 
-    let existing = User::find()
-        .filter(users::Column::Username.eq(&payload.username))
-        .one(&state.db)
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let existing = Semester::find_by_id(semester_id)
+        .one(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("semester not found"))?;
+    clear_semester_records(&transaction, semester_id).await?;
+    Semester::delete_by_id(semester_id)
+        .exec(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "semester_delete",
+        "semester",
+        Some(&semester_id.to_string()),
+        Some(serde_json::json!({ "name": existing.name })),
+    )
+    .await?;
+    transaction
+        .commit()
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
-    if existing.is_some() {
-        return Err(AppError::bad_request("user already exists"));
-    }
 
-    if matches!(state.config.reset_delivery, crate::config::ResetDelivery::Code) {
-        let now = Utc::now();
-        let user_id = Uuid::new_v4();
-        let model = users::ActiveModel {
-            id: Set(user_id),
-            username: Set(payload.username.clone()),
+    Ok(Json(serde_json::json!({ "status": "ok" })))
+}
+
+fn validate_semester_payload(payload: &SemesterRequest) -> Result<(), AppError> {
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid semester payload"))?;
+    if payload.name.trim().is_empty() {
+        return Err(AppError::validation("invalid semester payload"));
+    }
+    validate_academic_year(&payload.academic_year)
+}
+
+async fn ensure_semester_name_available<C>(
+    db: &C,
+    name: &str,
+    exclude_id: Option<Uuid>,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut finder = Semester::find().filter(semesters::Column::Name.eq(name));
+    if let Some(id) = exclude_id {
+        finder = finder.filter(semesters::Column::Id.ne(id));
+    }
+    let exists = finder
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if exists.is_some() {
+        return Err(AppError::bad_request("semester exists"));
+    }
+    Ok(())
+}
+
+fn semester_to_response(model: semesters::Model) -> SemesterResponse {
+    SemesterResponse {
+        id: model.id,
+        name: model.name,
+        academic_year: model.academic_year,
+        start_date: model.start_date,
+        end_date: model.end_date,
+    }
+}
+
+/// 提交窗口列表（管理员），按记录类型与院系排序。
+pub async fn list_submission_windows(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<SubmissionWindowResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let items = SubmissionWindow::find()
+        .order_by_asc(submission_windows::Column::RecordType)
+        .order_by_asc(submission_windows::Column::Department)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(items.into_iter().map(submission_window_to_response).collect()))
+}
+
+/// 新增提交窗口（管理员）。
+pub async fn create_submission_window(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<SubmissionWindowRequest>,
+) -> Result<Json<SubmissionWindowResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let department = validate_submission_window_payload(&payload)?;
+    ensure_window_unique(&state.db, &payload.record_type, department.as_deref(), None).await?;
+
+    let now = Utc::now();
+    let model = submission_windows::Model {
+        id: Uuid::new_v4(),
+        record_type: payload.record_type,
+        department,
+        opens_at: payload.opens_at,
+        closes_at: payload.closes_at,
+        created_at: now,
+        updated_at: now,
+    };
+    let active: submission_windows::ActiveModel = model.clone().into();
+    submission_windows::Entity::insert(active)
+        .exec_without_returning(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "submission_window_create",
+        "submission_window",
+        Some(&model.id.to_string()),
+        Some(submission_window_audit(&model)),
+    )
+    .await?;
+
+    Ok(Json(submission_window_to_response(model)))
+}
+
+/// 更新提交窗口（管理员）。
+pub async fn update_submission_window(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(window_id): Path<Uuid>,
+    Json(payload): Json<SubmissionWindowRequest>,
+) -> Result<Json<SubmissionWindowResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let department = validate_submission_window_payload(&payload)?;
+
+    let existing = SubmissionWindow::find_by_id(window_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("submission window not found"))?;
+    ensure_window_unique(&state.db, &payload.record_type, department.as_deref(), Some(window_id)).await?;
+    let previous = submission_window_audit(&existing);
+    let mut active: submission_windows::ActiveModel = existing.into();
+    active.record_type = Set(payload.record_type);
+    active.department = Set(department);
+    active.opens_at = Set(payload.opens_at);
+    active.closes_at = Set(payload.closes_at);
+    active.updated_at = Set(Utc::now());
+    let model = active
+        .update(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "submission_window_update",
+        "submission_window",
+        Some(&model.id.to_string()),
+        Some(serde_json::json!({ "from": previous, "to": submission_window_audit(&model) })),
+    )
+    .await?;
+
+    Ok(Json(submission_window_to_response(model)))
+}
+
+/// 删除提交窗口（管理员），删除后该范围不再限制提交时间。
+pub async fn delete_submission_window(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(window_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let existing = SubmissionWindow::find_by_id(window_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("submission window not found"))?;
+    SubmissionWindow::delete_by_id(window_id)
+        .exec(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "submission_window_delete",
+        "submission_window",
+        Some(&window_id.to_string()),
+        Some(submission_window_audit(&existing)),
+    )
+    .await?;
+
+    Ok(Json(serde_json::json!({ "status": "ok" })))
+}
+
+/// 列出附件要求规则（管理员）。
+pub async fn list_attachment_requirements(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<attachment_requirements::Model>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let items = AttachmentRequirement::find()
+        .order_by_asc(attachment_requirements::Column::RecordType)
+        .order_by_asc(attachment_requirements::Column::CreatedAt)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(items))
+}
+
+/// 新增附件要求规则（管理员）。
+pub async fn create_attachment_requirement(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<AttachmentRequirementRequest>,
+) -> Result<Json<attachment_requirements::Model>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let rule = validate_attachment_requirement_payload(payload)?;
+
+    let now = Utc::now();
+    let model = attachment_requirements::Model {
+        id: Uuid::new_v4(),
+        created_at: now,
+        updated_at: now,
+        ..rule
+    };
+    let active: attachment_requirements::ActiveModel = model.clone().into();
+    AttachmentRequirement::insert(active)
+        .exec_without_returning(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "attachment_requirement_create",
+        "attachment_requirement",
+        Some(&model.id.to_string()),
+        Some(attachment_requirement_audit(&model)),
+    )
+    .await?;
+    Ok(Json(model))
+}
+
+/// 更新附件要求规则（管理员）。
+pub async fn update_attachment_requirement(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(requirement_id): Path<Uuid>,
+    Json(payload): Json<AttachmentRequirementRequest>,
+) -> Result<Json<attachment_requirements::Model>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let rule = validate_attachment_requirement_payload(payload)?;
+
+    let existing = AttachmentRequirement::find_by_id(requirement_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("attachment requirement not found"))?;
+    let previous = attachment_requirement_audit(&existing);
+    let mut active: attachment_requirements::ActiveModel = existing.into();
+    active.record_type = Set(rule.record_type);
+    active.contest_level = Set(rule.contest_level);
+    active.field_key = Set(rule.field_key);
+    active.field_value = Set(rule.field_value);
+    active.min_attachments = Set(rule.min_attachments);
+    active.description = Set(rule.description);
+    active.updated_at = Set(Utc::now());
+    let model = active
+        .update(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "attachment_requirement_update",
+        "attachment_requirement",
+        Some(&model.id.to_string()),
+        Some(serde_json::json!({ "from": previous, "to": attachment_requirement_audit(&model) })),
+    )
+    .await?;
+    Ok(Json(model))
+}
+
+/// 删除附件要求规则（管理员）。
+pub async fn delete_attachment_requirement(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(requirement_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let existing = AttachmentRequirement::find_by_id(requirement_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("attachment requirement not found"))?;
+    AttachmentRequirement::delete_by_id(requirement_id)
+        .exec(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "attachment_requirement_delete",
+        "attachment_requirement",
+        Some(&requirement_id.to_string()),
+        Some(attachment_requirement_audit(&existing)),
+    )
+    .await?;
+
+    Ok(Json(serde_json::json!({ "status": "ok" })))
+}
+
+/// 校验附件要求规则请求，返回待写入的规则（ID 与时间由调用方填写）。
+fn validate_attachment_requirement_payload(
+    payload: AttachmentRequirementRequest,
+) -> Result<attachment_requirements::Model, AppError> {
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid attachment requirement payload"))?;
+    validate_record_type(&payload.record_type)?;
+    let trimmed = |value: Option<&str>| {
+        value
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let contest_level = trimmed(payload.contest_level.as_deref());
+    if contest_level.is_some() && payload.record_type != "contest" {
+        return Err(AppError::validation("contest_level only applies to contest records"));
+    }
+    let field_key = trimmed(payload.field_key.as_deref());
+    let field_value = trimmed(payload.field_value.as_deref());
+    if field_value.is_some() && field_key.is_none() {
+        return Err(AppError::validation("field_value requires field_key"));
+    }
+    let min_attachments = payload.min_attachments.unwrap_or(1);
+    if !(1..=MAX_REQUIRED_ATTACHMENTS).contains(&min_attachments) {
+        return Err(AppError::validation(&format!(
+            "min_attachments must be 1-{MAX_REQUIRED_ATTACHMENTS}"
+        )));
+    }
+    let description = payload.description.trim().to_string();
+    if description.is_empty() {
+        return Err(AppError::validation("description is required"));
+    }
+    let now = Utc::now();
+    Ok(attachment_requirements::Model {
+        id: Uuid::nil(),
+        record_type: payload.record_type,
+        contest_level,
+        field_key,
+        field_value,
+        min_attachments,
+        description,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+fn attachment_requirement_audit(model: &attachment_requirements::Model) -> serde_json::Value {
+    serde_json::json!({
+        "record_type": model.record_type,
+        "contest_level": model.contest_level,
+        "field_key": model.field_key,
+        "field_value": model.field_value,
+        "min_attachments": model.min_attachments,
+        "description": model.description,
+    })
+}
+
+fn validate_submission_window_payload(payload: &SubmissionWindowRequest) -> Result<Option<String>, AppError> {
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid submission window payload"))?;
+    validate_record_type(&payload.record_type)?;
+    validate_window_range(payload.opens_at, payload.closes_at)?;
+    Ok(normalize_department(payload.department.as_deref()))
+}
+
+fn submission_window_audit(model: &submission_windows::Model) -> serde_json::Value {
+    serde_json::json!({
+        "record_type": model.record_type,
+        "department": model.department,
+        "opens_at": model.opens_at,
+        "closes_at": model.closes_at,
+    })
+}
+
+fn submission_window_to_response(model: submission_windows::Model) -> SubmissionWindowResponse {
+    SubmissionWindowResponse {
+        id: model.id,
+        record_type: model.record_type,
+        department: model.department,
+        opens_at: model.opens_at,
+        closes_at: model.closes_at,
+    }
+}
+
+/// 在同一事务中写入新用户及其重置码，任一写入失败都不会留下无法激活的账号。
+async fn insert_user_with_reset(
+    db: &DatabaseConnection,
+    user: users::ActiveModel,
+    reset: auth_resets::ActiveModel,
+) -> Result<(), AppError> {
+    let txn = db.begin().await.map_err(|err| AppError::Database(err.to_string()))?;
+    users::Entity::insert(user)
+        .exec_without_returning(&txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    auth_resets::Entity::insert(reset)
+        .exec_without_returning(&txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    txn.commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(())
+}
+
+/// 管理员创建用户或发送邀请。
+pub async fn create_user(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<CreateUserRequest>,
+) -> Result<Json<CreateUserResponse>, AppError> {
+    let admin = require_session_user(&state, &jar).await?;
+    require_role(&admin, "admin")?;
+
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid user payload"))?;
+
+    let role = payload.role.as_str();
+    if !matches!(role, "student" | "teacher" | "reviewer" | "admin" | ROLE_CLASS_ADVISOR) {
+        return Err(AppError::validation("invalid role"));
+    }
+
+    if role == "student" {
+        let student = Student::find()
+            .filter(students::Column::StudentNo.eq(&payload.username))
+            .one(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .ok_or_else(|| AppError::bad_request("student not found"))?;
+
+        let now = Utc::now();
+        let default_password = load_student_password_rule(&state.db)
+            .await?
+            .build(&student.student_no, &student.phone)?;
+        let hash = hash_password(&default_password)?;
+
+        if let Some(existing) = User::find()
+            .filter(users::Column::Username.eq(&payload.username))
+            .one(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+        {
+            let missing_password = existing.password_hash.is_none();
+            let mut active: users::ActiveModel = existing.into();
+            active.display_name = Set(payload.display_name.clone());
+            active.role = Set("student".to_string());
+            if payload.email.is_some() {
+                active.email = Set(payload.email.clone());
+            }
+            if missing_password {
+                active.password_hash = Set(Some(hash));
+            }
+            active.allow_password_login = Set(true);
+            active.must_change_password = Set(true);
+            active.updated_at = Set(now);
+            let model = active
+                .update(&state.db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+            return Ok(Json(CreateUserResponse {
+                user_id: Some(model.id),
+                invite_sent: false,
+                reset_code: None,
+                reset_purpose: None,
+            }));
+        }
+
+        let user_id = Uuid::new_v4();
+        let model = users::ActiveModel {
+            id: Set(user_id),
+            username: Set(payload.username.clone()),
             display_name: Set(payload.display_name.clone()),
-            role: Set(payload.role.clone()),
+            role: Set("student".to_string()),
             email: Set(payload.email.clone()),
-            password_hash: Set(None),
-            allow_password_login: Set(false),
-            password_updated_at: Set(None),
-            must_change_password: Set(false),
+            password_hash: Set(Some(hash)),
+            allow_password_login: Set(true),
+            password_updated_at: Set(Some(now)),
+            must_change_password: Set(true),
             is_active: Set(true),
+            department: Set(None),
+            notify_review_email: Set(true),
+            locale: Set(None),
             created_at: Set(now),
             updated_at: Set(now),
         };
@@ -492,363 +1360,1196 @@ pub async fn create_user(
             .await
             .map_err(|err| AppError::Database(err.to_string()))?;
 
-        let purpose = payload
-            .reset_purpose
-            .clone()
-            .unwrap_or_else(|| "totp".to_string());
-        if !matches!(purpose.as_str(), "totp" | "passkey") {
-            return Err(AppError::validation("invalid reset purpose"));
-        }
+        return Ok(Json(CreateUserResponse {
+            user_id: Some(user_id),
+            invite_sent: false,
+            reset_code: None,
+            reset_purpose: None,
+        }));
+    }
+
+    let existing = User::find()
+        .filter(users::Column::Username.eq(&payload.username))
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if existing.is_some() {
+        return Err(AppError::bad_request("user already exists"));
+    }
+
+    if matches!(state.reset_delivery(), crate::config::ResetDelivery::Code) {
+        let purpose = payload
+            .reset_purpose
+            .clone()
+            .unwrap_or_else(|| "totp".to_string());
+        if !matches!(purpose.as_str(), "totp" | "passkey") {
+            return Err(AppError::validation("invalid reset purpose"));
+        }
+
+        let now = Utc::now();
+        let user_id = Uuid::new_v4();
+        let model = users::ActiveModel {
+            id: Set(user_id),
+            username: Set(payload.username.clone()),
+            display_name: Set(payload.display_name.clone()),
+            role: Set(payload.role.clone()),
+            email: Set(payload.email.clone()),
+            password_hash: Set(None),
+            allow_password_login: Set(false),
+            password_updated_at: Set(None),
+            must_change_password: Set(false),
+            is_active: Set(true),
+            department: Set(normalize_department(payload.department.as_deref())),
+            notify_review_email: Set(true),
+            locale: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        let token = generate_token();
+        let token_hash = hash_token(&token);
+        let expires_at = now + ChronoDuration::minutes(RESET_TTL_MINUTES);
+        let reset = auth_resets::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            token_hash: Set(token_hash),
+            user_id: Set(user_id),
+            purpose: Set(purpose.clone()),
+            expires_at: Set(expires_at),
+            created_at: Set(now),
+            used_at: Set(None),
+        };
+        insert_user_with_reset(&state.db, model, reset).await?;
+        record_security_event(
+            &state,
+            SecurityEventInput::new(EVENT_ROLE_GRANTED, role_grant_severity(&payload.role))
+                .user(Some(user_id), &payload.username)
+                .actor(admin.id)
+                .detail(format!("role={}", payload.role)),
+        )
+        .await;
+        record_security_event(
+            &state,
+            SecurityEventInput::new(EVENT_RESET_ISSUED, SEVERITY_HIGH)
+                .user(Some(user_id), &payload.username)
+                .actor(admin.id)
+                .detail(format!("purpose={purpose};delivery=code")),
+        )
+        .await;
+
+        return Ok(Json(CreateUserResponse {
+            user_id: Some(user_id),
+            invite_sent: false,
+            reset_code: Some(token),
+            reset_purpose: Some(purpose),
+        }));
+    }
+
+    let email = payload
+        .email
+        .clone()
+        .ok_or_else(|| AppError::validation("email required"))?;
+    let base_url = state
+        .config
+        .base_url
+        .as_ref()
+        .ok_or_else(|| AppError::config("BASE_URL is required"))?;
+    state
+        .mail_config()
+        .ok_or_else(|| AppError::config("mail config required"))?;
+
+    let token = generate_token();
+    let token_hash = hash_token(&token);
+    let now = Utc::now();
+    let expires_at = now + ChronoDuration::hours(INVITE_TTL_HOURS);
+    let invite_id = Uuid::new_v4();
+    let invite = invites::ActiveModel {
+        id: Set(invite_id),
+        token_hash: Set(token_hash),
+        email: Set(email.clone()),
+        username: Set(payload.username.clone()),
+        display_name: Set(payload.display_name.clone()),
+        role: Set(payload.role.clone()),
+        department: Set(normalize_department(payload.department.as_deref())),
+        expires_at: Set(expires_at),
+        created_at: Set(now),
+        used_at: Set(None),
+    };
+    invites::Entity::insert(invite)
+        .exec_without_returning(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let link = format!("{}/invite?token={}", base_url, token);
+    let locale = state.config.locale;
+    let hours = INVITE_TTL_HOURS.to_string();
+    let (subject, body) = render_mail(
+        &state.db,
+        &INVITE_MAIL,
+        locale,
+        &[("name", &payload.display_name), ("link", &link), ("hours", &hours)],
+    )
+    .await?;
+    enqueue_mail(&state, &email, &subject, &body).await?;
+    record_security_event(
+        &state,
+        SecurityEventInput::new(EVENT_ROLE_GRANTED, role_grant_severity(&payload.role))
+            .user(None, &payload.username)
+            .actor(admin.id)
+            .detail(format!("role={};delivery=invite", payload.role)),
+    )
+    .await;
+
+    Ok(Json(CreateUserResponse {
+        user_id: None,
+        invite_sent: true,
+        reset_code: None,
+        reset_purpose: None,
+    }))
+}
+
+/// 设置教师/审核人员的所属院系（管理员）。
+pub async fn update_user_department(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<UpdateUserDepartmentRequest>,
+) -> Result<Json<UserDepartmentResponse>, AppError> {
+    let admin = require_session_user(&state, &jar).await?;
+    require_role(&admin, "admin")?;
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid department payload"))?;
+
+    let user = User::find_by_id(user_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("user not found"))?;
+    if user.role == "student" {
+        return Err(AppError::validation("student department is managed by student profile"));
+    }
+    let department = normalize_department(payload.department.as_deref());
+    let previous = user.department.clone();
+    let mut active: users::ActiveModel = user.into();
+    active.department = Set(department.clone());
+    active.updated_at = Set(Utc::now());
+    active
+        .update(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(admin.id),
+        "user_department_update",
+        "user",
+        Some(&user_id.to_string()),
+        Some(serde_json::json!({ "from": previous, "to": department })),
+    )
+    .await?;
+
+    Ok(Json(UserDepartmentResponse { user_id, department }))
+}
+
+/// 查询班主任负责的班级（管理员）。
+pub async fn get_advisor_classes(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<AdvisorClassesResponse>, AppError> {
+    let admin = require_session_user(&state, &jar).await?;
+    require_role(&admin, "admin")?;
+    let advisor = find_advisor(&state, user_id).await?;
+    let classes = advisor_class_scope(&state.db, &advisor).await?.unwrap_or_default();
+    Ok(Json(AdvisorClassesResponse { user_id, classes }))
+}
+
+/// 整体替换班主任负责的班级（管理员）。
+pub async fn update_advisor_classes(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<UpdateAdvisorClassesRequest>,
+) -> Result<Json<AdvisorClassesResponse>, AppError> {
+    let admin = require_session_user(&state, &jar).await?;
+    require_role(&admin, "admin")?;
+    let advisor = find_advisor(&state, user_id).await?;
+    let mut classes: Vec<String> = payload
+        .classes
+        .iter()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect();
+    classes.sort();
+    classes.dedup();
+    if classes.iter().any(|value| value.chars().count() > 64) {
+        return Err(AppError::validation("class name too long"));
+    }
+
+    let previous = advisor_class_scope(&state.db, &advisor).await?.unwrap_or_default();
+    let now = Utc::now();
+    let txn = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    advisor_classes::Entity::delete_many()
+        .filter(advisor_classes::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    for class_name in &classes {
+        advisor_classes::Entity::insert(advisor_classes::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            class_name: Set(class_name.clone()),
+            created_at: Set(now),
+        })
+        .exec_without_returning(&txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    }
+    record_audit(
+        &txn,
+        Some(admin.id),
+        "advisor_classes_update",
+        "user",
+        Some(&user_id.to_string()),
+        Some(serde_json::json!({ "from": previous, "to": classes })),
+    )
+    .await?;
+    txn.commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(AdvisorClassesResponse { user_id, classes }))
+}
+
+async fn find_advisor(state: &AppState, user_id: Uuid) -> Result<users::Model, AppError> {
+    let user = User::find_by_id(user_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("user not found"))?;
+    if user.role != ROLE_CLASS_ADVISOR {
+        return Err(AppError::validation("user is not a class advisor"));
+    }
+    Ok(user)
+}
+
+/// 获取密码策略配置。
+pub async fn get_password_policy(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<PasswordPolicyResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let policy = load_password_policy(&state).await?;
+    Ok(Json(PasswordPolicyResponse {
+        min_length: policy.min_length,
+        require_uppercase: policy.require_uppercase,
+        require_lowercase: policy.require_lowercase,
+        require_digit: policy.require_digit,
+        require_symbol: policy.require_symbol,
+    }))
+}
+
+/// 更新密码策略配置。
+pub async fn update_password_policy(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<PasswordPolicyRequest>,
+) -> Result<Json<PasswordPolicyResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    if payload.min_length < 4 || payload.min_length > 64 {
+        return Err(AppError::validation("invalid min_length"));
+    }
+    let policy = crate::config::PasswordPolicy {
+        min_length: payload.min_length,
+        require_uppercase: payload.require_uppercase,
+        require_lowercase: payload.require_lowercase,
+        require_digit: payload.require_digit,
+        require_symbol: payload.require_symbol,
+    };
+    let updated = upsert_password_policy(&state, policy).await?;
+    Ok(Json(PasswordPolicyResponse {
+        min_length: updated.min_length,
+        require_uppercase: updated.require_uppercase,
+        require_lowercase: updated.require_lowercase,
+        require_digit: updated.require_digit,
+        require_symbol: updated.require_symbol,
+    }))
+}
+
+/// 获取学生默认密码规则（仅管理员）。
+pub async fn get_student_password_rule(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<StudentPasswordRule>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    Ok(Json(load_student_password_rule(&state.db).await?))
+}
+
+/// 更新学生默认密码规则（仅管理员），用于创建学生账号与重置密码。
+pub async fn update_student_password_rule(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<StudentPasswordRule>,
+) -> Result<Json<StudentPasswordRule>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let previous = load_student_password_rule(&state.db).await?;
+    let updated = upsert_student_password_rule(&state, payload).await?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "student_password_rule_update",
+        "password_policy",
+        None,
+        Some(serde_json::json!({ "from": previous, "to": updated })),
+    )
+    .await?;
+    Ok(Json(updated))
+}
+
+/// 获取劳动学时规则。
+pub async fn get_labor_hour_rules(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<LaborHourRuleRequest>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let rules = load_labor_hour_rules(&state).await?;
+    Ok(Json(LaborHourRuleRequest {
+        base_hours_a: rules.base_hours_a,
+        base_hours_b: rules.base_hours_b,
+        national_leader_hours: rules.national_leader_hours,
+        national_member_hours: rules.national_member_hours,
+        provincial_leader_hours: rules.provincial_leader_hours,
+        provincial_member_hours: rules.provincial_member_hours,
+        school_leader_hours: rules.school_leader_hours,
+        school_member_hours: rules.school_member_hours,
+        annual_cap_hours: rules.annual_cap_hours,
+    }))
+}
+
+/// 更新劳动学时规则。
+pub async fn update_labor_hour_rules(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<LaborHourRuleRequest>,
+) -> Result<Json<LaborHourRuleRequest>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    if payload.annual_cap_hours.is_some_and(|cap| cap <= 0) {
+        return Err(AppError::validation("annual_cap_hours must be positive"));
+    }
+    let updated = upsert_labor_hour_rules(
+        &state,
+        LaborHourRuleConfig {
+            base_hours_a: payload.base_hours_a,
+            base_hours_b: payload.base_hours_b,
+            national_leader_hours: payload.national_leader_hours,
+            national_member_hours: payload.national_member_hours,
+            provincial_leader_hours: payload.provincial_leader_hours,
+            provincial_member_hours: payload.provincial_member_hours,
+            school_leader_hours: payload.school_leader_hours,
+            school_member_hours: payload.school_member_hours,
+            annual_cap_hours: payload.annual_cap_hours,
+        },
+    )
+    .await?;
+    Ok(Json(LaborHourRuleRequest {
+        base_hours_a: updated.base_hours_a,
+        base_hours_b: updated.base_hours_b,
+        national_leader_hours: updated.national_leader_hours,
+        national_member_hours: updated.national_member_hours,
+        provincial_leader_hours: updated.provincial_leader_hours,
+        provincial_member_hours: updated.provincial_member_hours,
+        school_leader_hours: updated.school_leader_hours,
+        school_member_hours: updated.school_member_hours,
+        annual_cap_hours: updated.annual_cap_hours,
+    }))
+}
+
+/// 学时规则变更后按当前规则重算推荐学时，可选写入未审核记录的初审学时（事务内执行）。
+pub async fn recompute_labor_hours(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<RecomputeHoursRequest>,
+) -> Result<Json<HourRecomputeReport>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    const STATUSES: [&str; 4] = [STATUS_SUBMITTED, STATUS_FIRST_REVIEWED, STATUS_FINAL_REVIEWED, STATUS_REJECTED];
+    if let Some(status) = payload.statuses.iter().find(|status| !STATUSES.contains(&status.as_str())) {
+        return Err(AppError::validation(&format!("invalid status: {status}")));
+    }
+    for year in &payload.academic_years {
+        validate_academic_year(year)?;
+    }
+    let academic_years: Vec<String> = payload.academic_years.iter().map(|year| year.trim().to_string()).collect();
+    let rules = load_labor_hour_rules(&state).await?;
+
+    let txn = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let mut finder = ContestRecord::find().filter(contest_records::Column::IsDeleted.eq(false));
+    if !payload.statuses.is_empty() {
+        finder = finder.filter(contest_records::Column::Status.is_in(payload.statuses.clone()));
+    }
+    let records = finder
+        .order_by_asc(contest_records::Column::CreatedAt)
+        .all(&txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let semester_years = load_semester_years(&txn).await?;
+    let mut report = plan_hour_recompute(
+        &records,
+        rules,
+        &semester_years,
+        &academic_years,
+        payload.fill_first_review,
+    );
+
+    let snapshots: HashMap<Uuid, (i32, bool)> = records
+        .iter()
+        .map(|record| (record.id, (record.version, record.recommended_hours.is_some())))
+        .collect();
+    let now = Utc::now();
+    for entry in report.records.iter_mut().filter(|entry| entry.filled) {
+        let (version, stored_recommendation) = snapshots.get(&entry.record_id).copied().unwrap_or_default();
+        let mut update = ContestRecord::update_many()
+            .col_expr(contest_records::Column::FirstReviewHours, Expr::value(entry.recommended_hours));
+        if stored_recommendation {
+            update = update.col_expr(contest_records::Column::RecommendedHours, Expr::value(entry.recommended_hours));
+        }
+        let result = update
+            .col_expr(contest_records::Column::Version, Expr::value(version + 1))
+            .col_expr(contest_records::Column::UpdatedAt, Expr::value(now))
+            .filter(contest_records::Column::Id.eq(entry.record_id))
+            .filter(contest_records::Column::Version.eq(version))
+            .filter(contest_records::Column::Status.eq(STATUS_SUBMITTED))
+            .exec(&txn)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        if result.rows_affected == 0 {
+            entry.filled = false;
+            report.filled -= 1;
+            report.conflicts += 1;
+        }
+    }
+    if report.filled > 0 {
+        record_audit(
+            &txn,
+            Some(user.id),
+            "labor_hours_recompute",
+            "contest_record",
+            None,
+            Some(serde_json::json!({
+                "statuses": payload.statuses,
+                "academic_years": academic_years,
+                "filled": report.filled,
+            })),
+        )
+        .await?;
+    }
+    txn.commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(report))
+}
+
+/// 为用户发送 TOTP 重置链接。
+pub async fn reset_user_totp(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<ResetUserRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let admin = require_session_user(&state, &jar).await?;
+    require_role(&admin, "admin")?;
+    if matches!(state.reset_delivery(), crate::config::ResetDelivery::Code) {
+        return Err(AppError::bad_request("reset delivery set to code"));
+    }
+
+    let user = User::find()
+        .filter(users::Column::Username.eq(payload.username))
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("user not found"))?;
+    if user.role == "student" {
+        return Err(AppError::bad_request("student reset via email"));
+    }
+    let locale = state.locale_for(&user);
+    let email = user.email.ok_or_else(|| AppError::bad_request("email not set"))?;
+    let base_url = state
+        .config
+        .base_url
+        .as_ref()
+        .ok_or_else(|| AppError::config("BASE_URL is required"))?;
+    state
+        .mail_config()
+        .ok_or_else(|| AppError::config("mail config required"))?;
+
+    let token = generate_token();
+    let token_hash = hash_token(&token);
+    let now = Utc::now();
+    let expires_at = now + ChronoDuration::minutes(RESET_TTL_MINUTES);
+    let reset = auth_resets::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        token_hash: Set(token_hash),
+        user_id: Set(user.id),
+        purpose: Set("totp".to_string()),
+        expires_at: Set(expires_at),
+        created_at: Set(now),
+        used_at: Set(None),
+    };
+    auth_resets::Entity::insert(reset)
+        .exec_without_returning(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let link = format!("{}/reset?token={}", base_url, token);
+    let hours = (RESET_TTL_MINUTES / 60).to_string();
+    let (subject, body) = render_mail(
+        &state.db,
+        &TOTP_RESET_MAIL,
+        locale,
+        &[("name", &user.display_name), ("link", &link), ("hours", &hours)],
+    )
+    .await?;
+    enqueue_mail(&state, &email, &subject, &body).await?;
+    record_security_event(
+        &state,
+        SecurityEventInput::new(EVENT_RESET_ISSUED, SEVERITY_HIGH)
+            .user(Some(user.id), &user.username)
+            .actor(admin.id)
+            .detail("purpose=totp;delivery=email"),
+    )
+    .await;
+    Ok(Json(serde_json::json!({"status": "ok"})))
+}
+
+/// 为用户发送 Passkey 重置链接。
+pub async fn reset_user_passkey(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<ResetUserRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let admin = require_session_user(&state, &jar).await?;
+    require_role(&admin, "admin")?;
+    if matches!(state.reset_delivery(), crate::config::ResetDelivery::Code) {
+        return Err(AppError::bad_request("reset delivery set to code"));
+    }
+
+    let user = User::find()
+        .filter(users::Column::Username.eq(payload.username))
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("user not found"))?;
+    if user.role == "student" {
+        return Err(AppError::bad_request("student reset via email"));
+    }
+    let locale = state.locale_for(&user);
+    let email = user.email.ok_or_else(|| AppError::bad_request("email not set"))?;
+    let base_url = state
+        .config
+        .base_url
+        .as_ref()
+        .ok_or_else(|| AppError::config("BASE_URL is required"))?;
+    state
+        .mail_config()
+        .ok_or_else(|| AppError::config("mail config required"))?;
+
+    let token = generate_token();
+    let token_hash = hash_token(&token);
+    let now = Utc::now();
+    let expires_at = now + ChronoDuration::minutes(RESET_TTL_MINUTES);
+    let reset = auth_resets::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        token_hash: Set(token_hash),
+        user_id: Set(user.id),
+        purpose: Set("passkey".to_string()),
+        expires_at: Set(expires_at),
+        created_at: Set(now),
+        used_at: Set(None),
+    };
+    auth_resets::Entity::insert(reset)
+        .exec_without_returning(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let link = format!("{}/reset?token={}", base_url, token);
+    let hours = (RESET_TTL_MINUTES / 60).to_string();
+    let (subject, body) = render_mail(
+        &state.db,
+        &PASSKEY_RESET_MAIL,
+        locale,
+        &[("name", &user.display_name), ("link", &link), ("hours", &hours)],
+    )
+    .await?;
+    enqueue_mail(&state, &email, &subject, &body).await?;
+    record_security_event(
+        &state,
+        SecurityEventInput::new(EVENT_RESET_ISSUED, SEVERITY_HIGH)
+            .user(Some(user.id), &user.username)
+            .actor(admin.id)
+            .detail("purpose=passkey;delivery=email"),
+    )
+    .await;
+    Ok(Json(serde_json::json!({"status": "ok"})))
+}
+
+/// 生成一次性重置码（仅内网模式）。
+pub async fn generate_reset_code(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<ResetCodeRequest>,
+) -> Result<Json<ResetCodeResponse>, AppError> {
+    let admin = require_session_user(&state, &jar).await?;
+    require_role(&admin, "admin")?;
+    if matches!(state.reset_delivery(), crate::config::ResetDelivery::Email) {
+        return Err(AppError::bad_request("reset delivery set to email"));
+    }
+
+    let user = User::find()
+        .filter(users::Column::Username.eq(&payload.username))
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("user not found"))?;
+
+    let purpose = payload.purpose.as_str();
+    validate_reset_purpose(&user, purpose)?;
+
+    let token = generate_token();
+    let token_hash = hash_token(&token);
+    let now = Utc::now();
+    let expires_at = now + ChronoDuration::minutes(RESET_TTL_MINUTES);
+    let reset = auth_resets::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        token_hash: Set(token_hash),
+        user_id: Set(user.id),
+        purpose: Set(purpose.to_string()),
+        expires_at: Set(expires_at),
+        created_at: Set(now),
+        used_at: Set(None),
+    };
+    auth_resets::Entity::insert(reset)
+        .exec_without_returning(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_security_event(
+        &state,
+        SecurityEventInput::new(EVENT_RESET_ISSUED, SEVERITY_HIGH)
+            .user(Some(user.id), &user.username)
+            .actor(admin.id)
+            .detail(format!("purpose={purpose};delivery=code")),
+    )
+    .await;
+    if purpose == "password" {
+        push_notifications(
+            &state,
+            vec![NewNotification::new(
+                user.id,
+                KIND_PASSWORD_RESET,
+                "管理员已生成密码重置码",
+                "管理员为你的账号生成了一次性密码重置码，如非本人申请请联系管理员。",
+            )],
+        )
+        .await;
+    }
+
+    Ok(Json(ResetCodeResponse {
+        code: Some(token),
+        expires_in_minutes: RESET_TTL_MINUTES,
+    }))
+}
+
+/// 批量生成重置码请求。
+#[derive(Debug, Deserialize, Validate)]
+pub struct BatchResetCodeRequest {
+    /// 用户名列表。
+    #[validate(length(min = 1, max = 200))]
+    pub usernames: Vec<String>,
+    /// 重置目的（password/totp/passkey）。
+    pub purpose: String,
+    /// 返回格式（json/xlsx，默认 json）。
+    pub format: Option<String>,
+}
+
+/// 批量重置码结果项。
+#[derive(Debug, Serialize)]
+pub struct BatchResetCodeItem {
+    /// 用户名。
+    pub username: String,
+    /// 显示名称。
+    pub display_name: Option<String>,
+    /// 重置码（失败时为空）。
+    pub code: Option<String>,
+    /// 失败原因。
+    pub error: Option<String>,
+}
+
+/// 批量重置码响应。
+#[derive(Debug, Serialize)]
+pub struct BatchResetCodeResponse {
+    /// 过期分钟数。
+    pub expires_in_minutes: i64,
+    /// 逐个用户结果。
+    pub items: Vec<BatchResetCodeItem>,
+}
+
+/// 批量生成一次性重置码（仅内网模式），可选返回 xlsx 表格。
+pub async fn batch_generate_reset_codes(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<BatchResetCodeRequest>,
+) -> Result<Response, AppError> {
+    let admin = require_session_user(&state, &jar).await?;
+    require_role(&admin, "admin")?;
+    if matches!(state.reset_delivery(), crate::config::ResetDelivery::Email) {
+        return Err(AppError::bad_request("reset delivery set to email"));
+    }
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("usernames must contain 1-200 entries"))?;
+    let as_xlsx = match payload.format.as_deref() {
+        None | Some("json") => false,
+        Some("xlsx") => true,
+        Some(_) => return Err(AppError::validation("invalid format")),
+    };
+    let purpose = payload.purpose.as_str();
+    if !matches!(purpose, "password" | "totp" | "passkey") {
+        return Err(AppError::validation("invalid reset purpose"));
+    }
+
+    let users_by_name: HashMap<String, users::Model> = User::find()
+        .filter(users::Column::Username.is_in(payload.usernames.clone()))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|user| (user.username.clone(), user))
+        .collect();
 
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let now = Utc::now();
+    let expires_at = now + ChronoDuration::minutes(RESET_TTL_MINUTES);
+    let mut items = Vec::with_capacity(payload.usernames.len());
+    let mut issued = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for username in &payload.usernames {
+        if !seen.insert(username.as_str()) {
+            continue;
+        }
+        let Some(user) = users_by_name.get(username) else {
+            items.push(BatchResetCodeItem {
+                username: username.clone(),
+                display_name: None,
+                code: None,
+                error: Some("user not found".to_string()),
+            });
+            continue;
+        };
+        if let Err(err) = validate_reset_purpose(user, purpose) {
+            items.push(BatchResetCodeItem {
+                username: username.clone(),
+                display_name: Some(user.display_name.clone()),
+                code: None,
+                error: Some(err.to_string()),
+            });
+            continue;
+        }
         let token = generate_token();
-        let token_hash = hash_token(&token);
-        let expires_at = now + ChronoDuration::minutes(RESET_TTL_MINUTES);
+        let reset_id = Uuid::new_v4();
         let reset = auth_resets::ActiveModel {
-            id: Set(Uuid::new_v4()),
-            token_hash: Set(token_hash),
-            user_id: Set(user_id),
-            purpose: Set(purpose.clone()),
+            id: Set(reset_id),
+            token_hash: Set(hash_token(&token)),
+            user_id: Set(user.id),
+            purpose: Set(purpose.to_string()),
             expires_at: Set(expires_at),
             created_at: Set(now),
             used_at: Set(None),
         };
         auth_resets::Entity::insert(reset)
-            .exec_without_returning(&state.db)
+            .exec_without_returning(&transaction)
             .await
             .map_err(|err| AppError::Database(err.to_string()))?;
-
-        return Ok(Json(CreateUserResponse {
-            user_id: Some(user_id),
-            invite_sent: false,
-            reset_code: Some(token),
-            reset_purpose: Some(purpose),
-        }));
+        record_audit(
+            &transaction,
+            Some(admin.id),
+            "reset_code_issued",
+            "user",
+            Some(&user.username),
+            Some(serde_json::json!({ "purpose": purpose, "reset_id": reset_id, "batch": true })),
+        )
+        .await?;
+        issued.push(user);
+        items.push(BatchResetCodeItem {
+            username: username.clone(),
+            display_name: Some(user.display_name.clone()),
+            code: Some(token),
+            error: None,
+        });
     }
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
 
-    let email = payload
-        .email
-        .clone()
-        .ok_or_else(|| AppError::validation("email required"))?;
-    let base_url = state
-        .config
-        .base_url
-        .as_ref()
-        .ok_or_else(|| AppError::config("BASE_URL is required"))?;
-    let mail_config = state
-        .config
-        .mail
-        .as_ref()
-        .ok_or_else(|| AppError::config("mail config required"))?;
+    for user in issued {
+        record_security_event(
+            &state,
+            SecurityEventInput::new(EVENT_RESET_ISSUED, SEVERITY_HIGH)
+                .user(Some(user.id), &user.username)
+                .actor(admin.id)
+                .detail(format!("purpose={purpose};delivery=code;batch=true")),
+        )
+        .await;
+    }
 
-    let token = generate_token();
-    let token_hash = hash_token(&token);
-    let now = Utc::now();
-    let expires_at = now + ChronoDuration::hours(INVITE_TTL_HOURS);
-    let invite_id = Uuid::new_v4();
-    let invite = invites::ActiveModel {
-        id: Set(invite_id),
-        token_hash: Set(token_hash),
-        email: Set(email.clone()),
-        username: Set(payload.username.clone()),
-        display_name: Set(payload.display_name.clone()),
-        role: Set(payload.role.clone()),
-        expires_at: Set(expires_at),
-        created_at: Set(now),
-        used_at: Set(None),
+    let response = BatchResetCodeResponse {
+        expires_in_minutes: RESET_TTL_MINUTES,
+        items,
     };
-    invites::Entity::insert(invite)
-        .exec_without_returning(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
+    if as_xlsx {
+        let bytes = build_reset_code_workbook(&response, purpose)?;
+        return Ok(file_response(
+            "reset-codes.xlsx",
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            bytes,
+        ));
+    }
+    Ok(Json(response).into_response())
+}
 
-    let link = format!("{}/invite?token={}", base_url, token);
-    let body = format!(
-        "您好，\n\n您被邀请加入 Labor Hours Platform，请点击以下链接完成注册并绑定 TOTP 或 Passkey：\n{}\n\n该链接 {} 小时后失效。",
-        link, INVITE_TTL_HOURS
-    );
-    send_mail(mail_config, &email, "账号邀请", &body).await?;
+fn validate_reset_purpose(user: &users::Model, purpose: &str) -> Result<(), AppError> {
+    if purpose == "password" && user.role != "student" {
+        return Err(AppError::bad_request("password reset only for students"));
+    }
+    if (purpose == "totp" || purpose == "passkey") && user.role == "student" {
+        return Err(AppError::bad_request("student reset via password"));
+    }
+    if !matches!(purpose, "password" | "totp" | "passkey") {
+        return Err(AppError::validation("invalid reset purpose"));
+    }
+    Ok(())
+}
 
-    Ok(Json(CreateUserResponse {
-        user_id: None,
-        invite_sent: true,
-        reset_code: None,
-        reset_purpose: None,
-    }))
+fn build_reset_code_workbook(response: &BatchResetCodeResponse, purpose: &str) -> Result<Vec<u8>, AppError> {
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    for (col, header) in ["用户名", "姓名", "重置目的", "重置码", "有效期（分钟）", "错误"].iter().enumerate() {
+        worksheet
+            .write_string(0, col as u16, *header)
+            .map_err(|_| AppError::internal("write excel failed"))?;
+    }
+    for (idx, item) in response.items.iter().enumerate() {
+        let row = (idx + 1) as u32;
+        let values = [
+            item.username.as_str(),
+            item.display_name.as_deref().unwrap_or(""),
+            purpose,
+            item.code.as_deref().unwrap_or(""),
+            "",
+            item.error.as_deref().unwrap_or(""),
+        ];
+        for (col, value) in values.iter().enumerate() {
+            worksheet
+                .write_string(row, col as u16, *value)
+                .map_err(|_| AppError::internal("write excel failed"))?;
+        }
+        if item.code.is_some() {
+            worksheet
+                .write_number(row, 4, response.expires_in_minutes as f64)
+                .map_err(|_| AppError::internal("write excel failed"))?;
+        }
+    }
+    workbook
+        .save_to_buffer()
+        .map_err(|_| AppError::internal("save excel failed"))
 }
 
-/// 获取密码策略配置。
-pub async fn get_password_policy(
+/// 安全事件查询参数。
+#[derive(Debug, Deserialize)]
+pub struct SecurityEventQuery {
+    /// 事件类型。
+    pub event_type: Option<String>,
+    /// 严重级别。
+    pub severity: Option<String>,
+    /// 用户名。
+    pub username: Option<String>,
+    /// 起始时间（RFC3339）。
+    pub since: Option<chrono::DateTime<Utc>>,
+    /// 截止时间（RFC3339）。
+    pub until: Option<chrono::DateTime<Utc>>,
+    /// 返回条数上限（默认 100，最大 1000）。
+    pub limit: Option<u64>,
+}
+
+/// 获取系统运行信息（版本、运行时长、迁移状态、存储与功能开关，仅管理员）。
+pub async fn get_system_info(
     State(state): State<AppState>,
     jar: CookieJar,
-) -> Result<Json<PasswordPolicyResponse>, AppError> {
+) -> Result<Json<SystemInfo>, AppError> {
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
-    let policy = load_password_policy(&state).await?;
-    Ok(Json(PasswordPolicyResponse {
-        min_length: policy.min_length,
-        require_uppercase: policy.require_uppercase,
-        require_lowercase: policy.require_lowercase,
-        require_digit: policy.require_digit,
-        require_symbol: policy.require_symbol,
-    }))
+    let info = collect_system_info(
+        &state.config,
+        &state.db,
+        &state.reset_delivery(),
+        state.settings.current().maintenance_mode,
+        Some(state.started_at.elapsed()),
+    )
+    .await?;
+    Ok(Json(info))
 }
 
-/// 更新密码策略配置。
-pub async fn update_password_policy(
+/// 查看生效配置（配置文件与环境变量合并结果，不含密钥与口令）。
+pub async fn get_effective_config(
     State(state): State<AppState>,
     jar: CookieJar,
-    Json(payload): Json<PasswordPolicyRequest>,
-) -> Result<Json<PasswordPolicyResponse>, AppError> {
+) -> Result<Json<serde_json::Value>, AppError> {
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
-    if payload.min_length < 4 || payload.min_length > 64 {
-        return Err(AppError::validation("invalid min_length"));
-    }
-    let policy = crate::config::PasswordPolicy {
-        min_length: payload.min_length,
-        require_uppercase: payload.require_uppercase,
-        require_lowercase: payload.require_lowercase,
-        require_digit: payload.require_digit,
-        require_symbol: payload.require_symbol,
-    };
-    let updated = upsert_password_policy(&state, policy).await?;
-    Ok(Json(PasswordPolicyResponse {
-        min_length: updated.min_length,
-        require_uppercase: updated.require_uppercase,
-        require_lowercase: updated.require_lowercase,
-        require_digit: updated.require_digit,
-        require_symbol: updated.require_symbol,
-    }))
+    Ok(Json(state.config.redacted()))
 }
 
-/// 获取劳动学时规则。
-pub async fn get_labor_hour_rules(
+/// 查看应用密钥的使用情况（各表密文按密钥 ID 统计，管理员）。
+pub async fn get_secret_usage(
     State(state): State<AppState>,
     jar: CookieJar,
-) -> Result<Json<LaborHourRuleRequest>, AppError> {
+) -> Result<Json<SecretUsageReport>, AppError> {
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
-    let rules = load_labor_hour_rules(&state).await?;
-    Ok(Json(LaborHourRuleRequest {
-        base_hours_a: rules.base_hours_a,
-        base_hours_b: rules.base_hours_b,
-        national_leader_hours: rules.national_leader_hours,
-        national_member_hours: rules.national_member_hours,
-        provincial_leader_hours: rules.provincial_leader_hours,
-        provincial_member_hours: rules.provincial_member_hours,
-        school_leader_hours: rules.school_leader_hours,
-        school_member_hours: rules.school_member_hours,
-    }))
+    Ok(Json(secret_usage(&state.db, &state.config.secret_keyring()).await?))
 }
 
-/// 更新劳动学时规则。
-pub async fn update_labor_hour_rules(
+/// 使用当前应用密钥重新加密旧密文（需管理员二次验证）。
+pub async fn rotate_secret_keys(
     State(state): State<AppState>,
     jar: CookieJar,
-    Json(payload): Json<LaborHourRuleRequest>,
-) -> Result<Json<LaborHourRuleRequest>, AppError> {
+    headers: HeaderMap,
+) -> Result<Json<SecretRotationReport>, AppError> {
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
-    let updated = upsert_labor_hour_rules(
-        &state,
-        LaborHourRuleConfig {
-            base_hours_a: payload.base_hours_a,
-            base_hours_b: payload.base_hours_b,
-            national_leader_hours: payload.national_leader_hours,
-            national_member_hours: payload.national_member_hours,
-            provincial_leader_hours: payload.provincial_leader_hours,
-            provincial_member_hours: payload.provincial_member_hours,
-            school_leader_hours: payload.school_leader_hours,
-            school_member_hours: payload.school_member_hours,
-        },
+    require_reauth(&state, &headers, user.id).await?;
+
+    let report = rotate_secrets(&state.db, &state.config.secret_keyring()).await?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "secret_rotate",
+        "system",
+        None,
+        Some(serde_json::json!({
+            "key_id": report.primary_key_id,
+            "rotated": report.rotated,
+            "failures": report.failures.len(),
+        })),
     )
     .await?;
-    Ok(Json(LaborHourRuleRequest {
-        base_hours_a: updated.base_hours_a,
-        base_hours_b: updated.base_hours_b,
-        national_leader_hours: updated.national_leader_hours,
-        national_member_hours: updated.national_member_hours,
-        provincial_leader_hours: updated.provincial_leader_hours,
-        provincial_member_hours: updated.provincial_member_hours,
-        school_leader_hours: updated.school_leader_hours,
-        school_member_hours: updated.school_member_hours,
-    }))
+    Ok(Json(report))
 }
 
-/// 为用户发送 TOTP 重置链接。
-pub async fn reset_user_totp(
+/// 附件存储用量查询参数。
+#[derive(Debug, Deserialize)]
+pub struct StorageUsageQuery {
+    /// 用量排行返回的学生数（默认 20，最大 100）。
+    pub top: Option<u64>,
+}
+
+/// 查看附件存储用量与配额（管理员）。
+pub async fn get_storage_usage(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<StorageUsageQuery>,
+) -> Result<Json<StorageUsageReport>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let top = query.top.unwrap_or(DEFAULT_TOP_STUDENTS);
+    Ok(Json(storage_usage(&state.db, &state.config.attachments, top).await?))
+}
+
+/// 统计孤立数据（管理员，只统计不删除）。
+pub async fn get_orphan_report(State(state): State<AppState>, jar: CookieJar) -> Result<Json<OrphanReport>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    Ok(Json(purge::cleanup_orphans(&state, true).await?))
+}
+
+/// 清理孤立数据行与未被引用的上传文件（管理员）。
+pub async fn cleanup_orphan_data(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<OrphanReport>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let report = purge::cleanup_orphans(&state, false).await?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "orphan_cleanup",
+        "system",
+        None,
+        Some(serde_json::to_value(&report).unwrap_or_default()),
+    )
+    .await?;
+    Ok(Json(report))
+}
+
+/// 导出全量备份（数据与上传文件，需管理员二次验证）。
+pub async fn export_backup(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    require_reauth(&state, &headers, user.id).await?;
+
+    let (file, manifest) = backup::export_backup(&state).await?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "backup_export",
+        "system",
+        None,
+        Some(serde_json::json!({ "tables": manifest.tables, "files": manifest.files })),
+    )
+    .await?;
+    let name = format!("uca-platform-backup-{}.zip", manifest.created_at.format("%Y%m%d%H%M%S"));
+    stream_file_response(&name, "application/zip", file)
+}
+
+/// 写入演示数据（仅开发模式，管理员）。
+pub async fn seed_dev_data(
     State(state): State<AppState>,
     jar: CookieJar,
-    Json(payload): Json<ResetUserRequest>,
-) -> Result<Json<serde_json::Value>, AppError> {
-    let admin = require_session_user(&state, &jar).await?;
-    require_role(&admin, "admin")?;
-    if matches!(state.config.reset_delivery, crate::config::ResetDelivery::Code) {
-        return Err(AppError::bad_request("reset delivery set to code"));
+) -> Result<Json<SeedSummary>, AppError> {
+    if !state.config.developer_mode {
+        return Err(AppError::not_found("developer mode disabled"));
     }
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
 
-    let user = User::find()
-        .filter(users::Column::Username.eq(payload.username))
-        .one(&state.db)
+    let txn = state
+        .db
+        .begin()
         .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-        .ok_or_else(|| AppError::not_found("user not found"))?;
-    if user.role == "student" {
-        return Err(AppError::bad_request("student reset via email"));
-    }
-    let email = user.email.ok_or_else(|| AppError::bad_request("email not set"))?;
-    let base_url = state
-        .config
-        .base_url
-        .as_ref()
-        .ok_or_else(|| AppError::config("BASE_URL is required"))?;
-    let mail_config = state
-        .config
-        .mail
-        .as_ref()
-        .ok_or_else(|| AppError::config("mail config required"))?;
-
-    let token = generate_token();
-    let token_hash = hash_token(&token);
-    let now = Utc::now();
-    let expires_at = now + ChronoDuration::minutes(RESET_TTL_MINUTES);
-    let reset = auth_resets::ActiveModel {
-        id: Set(Uuid::new_v4()),
-        token_hash: Set(token_hash),
-        user_id: Set(user.id),
-        purpose: Set("totp".to_string()),
-        expires_at: Set(expires_at),
-        created_at: Set(now),
-        used_at: Set(None),
-    };
-    auth_resets::Entity::insert(reset)
-        .exec_without_returning(&state.db)
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let summary = seed_demo_data(&txn).await?;
+    txn.commit()
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
-
-    let link = format!("{}/reset?token={}", base_url, token);
-    let body = format!(
-        "您好，\n\n请点击以下链接重置您的 TOTP：\n{}\n\n该链接 {} 小时后失效。",
-        link,
-        RESET_TTL_MINUTES / 60
-    );
-    send_mail(mail_config, &email, "TOTP 重置", &body).await?;
-    Ok(Json(serde_json::json!({"status": "ok"})))
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "dev_seed",
+        "system",
+        None,
+        Some(serde_json::json!({
+            "students": summary.students,
+            "users": summary.users,
+            "form_fields": summary.form_fields,
+            "competitions": summary.competitions,
+            "records": summary.records,
+        })),
+    )
+    .await?;
+    Ok(Json(summary))
 }
 
-/// 为用户发送 Passkey 重置链接。
-pub async fn reset_user_passkey(
+/// 从备份恢复（仅限尚未创建用户的空数据库，配置了引导令牌时需提供 `token` 字段）。
+pub async fn import_backup(
     State(state): State<AppState>,
-    jar: CookieJar,
-    Json(payload): Json<ResetUserRequest>,
-) -> Result<Json<serde_json::Value>, AppError> {
-    let admin = require_session_user(&state, &jar).await?;
-    require_role(&admin, "admin")?;
-    if matches!(state.config.reset_delivery, crate::config::ResetDelivery::Code) {
-        return Err(AppError::bad_request("reset delivery set to code"));
-    }
-
-    let user = User::find()
-        .filter(users::Column::Username.eq(payload.username))
-        .one(&state.db)
+    mut multipart: Multipart,
+) -> Result<Json<BackupManifest>, AppError> {
+    let mut token = None;
+    let mut archive = None;
+    while let Some(field) = multipart
+        .next_field()
         .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-        .ok_or_else(|| AppError::not_found("user not found"))?;
-    if user.role == "student" {
-        return Err(AppError::bad_request("student reset via email"));
+        .map_err(multipart_error)?
+    {
+        let name = field.name().map(|value| value.to_string());
+        match name.as_deref() {
+            Some("token") => {
+                token = Some(field.text().await.map_err(|_| AppError::bad_request("invalid token"))?);
+            }
+            Some("file") => {
+                archive = Some(spool_file_field(field, backup::MAX_BACKUP_BYTES).await?);
+            }
+            _ => {}
+        }
     }
-    let email = user.email.ok_or_else(|| AppError::bad_request("email not set"))?;
-    let base_url = state
-        .config
-        .base_url
-        .as_ref()
-        .ok_or_else(|| AppError::config("BASE_URL is required"))?;
-    let mail_config = state
-        .config
-        .mail
-        .as_ref()
-        .ok_or_else(|| AppError::config("mail config required"))?;
-
-    let token = generate_token();
-    let token_hash = hash_token(&token);
-    let now = Utc::now();
-    let expires_at = now + ChronoDuration::minutes(RESET_TTL_MINUTES);
-    let reset = auth_resets::ActiveModel {
-        id: Set(Uuid::new_v4()),
-        token_hash: Set(token_hash),
-        user_id: Set(user.id),
-        purpose: Set("passkey".to_string()),
-        expires_at: Set(expires_at),
-        created_at: Set(now),
-        used_at: Set(None),
-    };
-    auth_resets::Entity::insert(reset)
-        .exec_without_returning(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
 
-    let link = format!("{}/reset?token={}", base_url, token);
-    let body = format!(
-        "您好，\n\n请点击以下链接重置您的 Passkey：\n{}\n\n该链接 {} 小时后失效。",
-        link,
-        RESET_TTL_MINUTES / 60
-    );
-    send_mail(mail_config, &email, "Passkey 重置", &body).await?;
-    Ok(Json(serde_json::json!({"status": "ok"})))
+    if let Some(expected) = state.config.bootstrap_token.as_ref() {
+        if token.as_deref() != Some(expected.as_str()) {
+            return Err(AppError::auth("invalid bootstrap token"));
+        }
+    }
+    let archive = archive.ok_or_else(|| AppError::bad_request("missing file"))?;
+    let manifest = backup::import_backup(&state, archive).await?;
+    record_audit(
+        &state.db,
+        None,
+        "backup_import",
+        "system",
+        None,
+        Some(serde_json::json!({
+            "created_at": manifest.created_at,
+            "app_version": manifest.app_version,
+            "tables": manifest.tables,
+            "files": manifest.files,
+        })),
+    )
+    .await?;
+    Ok(Json(manifest))
 }
 
-/// 生成一次性重置码（仅内网模式）。
-pub async fn generate_reset_code(
+/// 查询安全事件（仅管理员）。
+pub async fn list_security_events(
     State(state): State<AppState>,
     jar: CookieJar,
-    Json(payload): Json<ResetCodeRequest>,
-) -> Result<Json<ResetCodeResponse>, AppError> {
-    let admin = require_session_user(&state, &jar).await?;
-    require_role(&admin, "admin")?;
-    if matches!(state.config.reset_delivery, crate::config::ResetDelivery::Email) {
-        return Err(AppError::bad_request("reset delivery set to email"));
-    }
-
-    let user = User::find()
-        .filter(users::Column::Username.eq(&payload.username))
-        .one(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-        .ok_or_else(|| AppError::not_found("user not found"))?;
+    Query(query): Query<SecurityEventQuery>,
+) -> Result<Json<Vec<security_events::Model>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
 
-    let purpose = payload.purpose.as_str();
-    if purpose == "password" && user.role != "student" {
-        return Err(AppError::bad_request("password reset only for students"));
+    let mut finder = SecurityEvent::find();
+    if let Some(event_type) = query.event_type.filter(|value| !value.is_empty()) {
+        finder = finder.filter(security_events::Column::EventType.eq(event_type));
     }
-    if (purpose == "totp" || purpose == "passkey") && user.role == "student" {
-        return Err(AppError::bad_request("student reset via password"));
+    if let Some(severity) = query.severity.filter(|value| !value.is_empty()) {
+        finder = finder.filter(security_events::Column::Severity.eq(severity));
     }
-    if !matches!(purpose, "password" | "totp" | "passkey") {
-        return Err(AppError::validation("invalid reset purpose"));
+    if let Some(username) = query.username.filter(|value| !value.is_empty()) {
+        finder = finder.filter(security_events::Column::Username.eq(username));
     }
-
-    let token = generate_token();
-    let token_hash = hash_token(&token);
-    let now = Utc::now();
-    let expires_at = now + ChronoDuration::minutes(RESET_TTL_MINUTES);
-    let reset = auth_resets::ActiveModel {
-        id: Set(Uuid::new_v4()),
-        token_hash: Set(token_hash),
-        user_id: Set(user.id),
-        purpose: Set(purpose.to_string()),
-        expires_at: Set(expires_at),
-        created_at: Set(now),
-        used_at: Set(None),
-    };
-    auth_resets::Entity::insert(reset)
-        .exec_without_returning(&state.db)
+    if let Some(since) = query.since {
+        finder = finder.filter(security_events::Column::CreatedAt.gte(since));
+    }
+    if let Some(until) = query.until {
+        finder = finder.filter(security_events::Column::CreatedAt.lte(until));
+    }
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let events = finder
+        .order_by_desc(security_events::Column::CreatedAt)
+        .limit(limit)
+        .all(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
-
-    Ok(Json(ResetCodeResponse {
-        code: Some(token),
-        expires_in_minutes: RESET_TTL_MINUTES,
-    }))
+    Ok(Json(events))
 }
 
 /// 从 Excel 导入竞赛名称（仅管理员）。
@@ -860,7 +2561,7 @@ pub async fn import_competitions(
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
 
-    let (file_bytes, fields) = read_upload_payload(&mut multipart).await?;
+    let (file_bytes, fields) = read_upload_payload(&mut multipart, state.config.max_upload_bytes).await?;
     let default_year = fields
         .get("default_year")
         .and_then(|value| value.parse::<i32>().ok());
@@ -869,12 +2570,9 @@ pub async fn import_competitions(
         .map(|value| serde_json::from_str::<Vec<CompetitionSheetPlan>>(value))
         .transpose()
         .map_err(|_| AppError::bad_request("invalid sheet_plan"))?;
-    let mut workbook = calamine::Xlsx::new(Cursor::new(file_bytes))
-        .map_err(|_| AppError::bad_request("invalid xlsx file"))?;
-    let sheet_names = workbook.sheet_names().to_vec();
-    if sheet_names.is_empty() {
-        return Err(AppError::bad_request("xlsx has no sheets"));
-    }
+    let header_options = HeaderOptions::from_fields(&fields)?;
+    let workbook = read_upload_workbook(file_bytes)?;
+    let sheet_names = workbook.sheet_names();
 
     let mut inserted = 0usize;
     let mut skipped = 0usize;
@@ -891,6 +2589,8 @@ pub async fn import_competitions(
             name_column: None,
             category_column: None,
             category_suffix: None,
+            header_row_index: None,
+            header_rows: None,
         }]
     };
 
@@ -904,11 +2604,23 @@ pub async fn import_competitions(
                 return Err(AppError::validation("invalid category_suffix"));
             }
         }
-        let range = workbook
-            .worksheet_range(&plan.name)
-            .map_err(|_| AppError::bad_request("failed to read worksheet"))?;
-
-        let header_index = build_header_index(range.rows().next());
+        let range = workbook.sheet(&plan.name)?;
+
+        let sheet_header_options = HeaderOptions::new(
+            plan.header_row_index.or(header_options.row_index),
+            plan.header_rows.or(header_options.rows),
+        )?;
+        let mut expected: Vec<&str> = COMPETITION_HEADER
+            .iter()
+            .chain(&COMPETITION_CATEGORY_HEADERS)
+            .chain(&COMPETITION_YEAR_HEADERS)
+            .copied()
+            .collect();
+        expected.extend(plan.name_column.as_deref());
+        expected.extend(plan.category_column.as_deref());
+        let header = locate_header(range, sheet_header_options, &expected)?;
+        let data_start = header.data_start();
+        let header_index = header.index;
         let name_idx = resolve_column_index(
             &header_index,
             plan.name_column.as_deref(),
@@ -930,7 +2642,7 @@ pub async fn import_competitions(
             return Err(AppError::bad_request(&message));
         }
 
-        for row in range.rows().skip(1) {
+        for row in range.rows().skip(data_start) {
             let name = read_cell_by_index(name_idx, row);
             if name.is_empty() {
                 continue;
@@ -985,67 +2697,379 @@ pub async fn import_competitions(
         }
     }
 
-    Ok(Json(serde_json::json!({ "inserted": inserted, "skipped": skipped })))
+    state.competition_match.lock().await.invalidate();
+
+    Ok(Json(serde_json::json!({
+        "inserted": inserted,
+        "skipped": skipped,
+        "encoding": workbook.encoding,
+    })))
+}
+
+/// 表单字段新增请求。
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateFormFieldRequest {
+    /// 表单类型。
+    #[validate(length(min = 1, max = 32))]
+    pub form_type: String,
+    /// 字段 key。
+    #[validate(length(min = 1, max = 64))]
+    pub field_key: String,
+    /// 字段标签。
+    #[validate(length(min = 1, max = 64))]
+    pub label: String,
+    /// 字段类型。
+    #[validate(length(min = 1, max = 32))]
+    pub field_type: String,
+    /// 是否必填。
+    pub required: bool,
+    /// 排序序号。
+    pub order_index: i32,
+    /// select/radio 字段的可选值。
+    #[serde(default)]
+    pub options: Option<Vec<String>>,
+    /// number 字段的最小值。
+    #[serde(default)]
+    pub min_value: Option<i32>,
+    /// number 字段的最大值。
+    #[serde(default)]
+    pub max_value: Option<i32>,
+    /// 取值最大字符数（缺省为 500）。
+    #[serde(default)]
+    pub max_length: Option<i32>,
+}
+
+/// 表单字段更新请求（form_type 与 field_key 不可修改）。
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateFormFieldRequest {
+    /// 字段标签。
+    #[validate(length(min = 1, max = 64))]
+    pub label: String,
+    /// 字段类型。
+    #[validate(length(min = 1, max = 32))]
+    pub field_type: String,
+    /// 是否必填。
+    pub required: bool,
+    /// 排序序号。
+    pub order_index: i32,
+    /// select/radio 字段的可选值。
+    #[serde(default)]
+    pub options: Option<Vec<String>>,
+    /// number 字段的最小值。
+    #[serde(default)]
+    pub min_value: Option<i32>,
+    /// number 字段的最大值。
+    #[serde(default)]
+    pub max_value: Option<i32>,
+    /// 取值最大字符数（缺省为 500）。
+    #[serde(default)]
+    pub max_length: Option<i32>,
+}
+
+/// 表单字段响应。
+#[derive(Debug, Serialize)]
+pub struct FormFieldResponse {
+    /// 记录 ID。
+    pub id: Uuid,
+    /// 表单类型。
+    pub form_type: String,
+    /// 字段 key。
+    pub field_key: String,
+    /// 字段标签。
+    pub label: String,
+    /// 字段类型。
+    pub field_type: String,
+    /// 是否必填。
+    pub required: bool,
+    /// 排序序号。
+    pub order_index: i32,
+    /// select/radio 字段的可选值。
+    pub options: Option<Vec<String>>,
+    /// number 字段的最小值。
+    pub min_value: Option<i32>,
+    /// number 字段的最大值。
+    pub max_value: Option<i32>,
+    /// 取值最大字符数。
+    pub max_length: Option<i32>,
+}
+
+impl From<form_fields::Model> for FormFieldResponse {
+    fn from(field: form_fields::Model) -> Self {
+        let options = field_options(&field);
+        Self {
+            id: field.id,
+            form_type: field.form_type,
+            field_key: field.field_key,
+            label: field.label,
+            field_type: field.field_type,
+            required: field.required,
+            order_index: field.order_index,
+            options,
+            min_value: field.min_value,
+            max_value: field.max_value,
+            max_length: field.max_length,
+        }
+    }
+}
+
+/// 查询表单字段。
+pub async fn list_form_fields(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<FormFieldResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let fields = FormField::find()
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(fields.into_iter().map(FormFieldResponse::from).collect()))
+}
+
+/// 新增表单字段。
+pub async fn create_form_field(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<CreateFormFieldRequest>,
+) -> Result<Json<FormFieldResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid form field payload"))?;
+    reject_export_form_type(&payload.form_type)?;
+    let options = normalize_field_definition(
+        &payload.field_type,
+        payload.options.as_deref(),
+        payload.min_value,
+        payload.max_value,
+        payload.max_length,
+    )?;
+
+    let now = Utc::now();
+    let id = Uuid::new_v4();
+    let model = form_fields::ActiveModel {
+        id: Set(id),
+        form_type: Set(payload.form_type.clone()),
+        field_key: Set(payload.field_key.clone()),
+        label: Set(payload.label.clone()),
+        field_type: Set(payload.field_type.clone()),
+        required: Set(payload.required),
+        order_index: Set(payload.order_index),
+        options: Set(options),
+        min_value: Set(payload.min_value),
+        max_value: Set(payload.max_value),
+        max_length: Set(payload.max_length),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+    let model = model
+        .insert(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(FormFieldResponse::from(model)))
+}
+
+/// 更新表单字段（标签、类型、必填、排序与可选值）。
+pub async fn update_form_field(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(field_id): Path<Uuid>,
+    Json(payload): Json<UpdateFormFieldRequest>,
+) -> Result<Json<FormFieldResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid form field payload"))?;
+    let options = normalize_field_definition(
+        &payload.field_type,
+        payload.options.as_deref(),
+        payload.min_value,
+        payload.max_value,
+        payload.max_length,
+    )?;
+
+    let existing = FormField::find_by_id(field_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("form field not found"))?;
+    let mut active: form_fields::ActiveModel = existing.into();
+    active.label = Set(payload.label);
+    active.field_type = Set(payload.field_type);
+    active.required = Set(payload.required);
+    active.order_index = Set(payload.order_index);
+    active.options = Set(options);
+    active.min_value = Set(payload.min_value);
+    active.max_value = Set(payload.max_value);
+    active.max_length = Set(payload.max_length);
+    active.updated_at = Set(Utc::now());
+    let model = active
+        .update(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "form_field_update",
+        "form_field",
+        Some(&field_id.to_string()),
+        Some(serde_json::json!({ "form_type": model.form_type, "field_key": model.field_key })),
+    )
+    .await?;
+
+    Ok(Json(FormFieldResponse::from(model)))
+}
+
+/// 删除表单字段；已有记录填写过该字段时拒绝删除。
+pub async fn delete_form_field(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(field_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let existing = FormField::find_by_id(field_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("form field not found"))?;
+    let value_count = FormFieldValue::find()
+        .filter(form_field_values::Column::RecordType.eq(&existing.form_type))
+        .filter(form_field_values::Column::FieldKey.eq(&existing.field_key))
+        .count(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if value_count > 0 {
+        return Err(AppError::bad_request("form field has recorded values"));
+    }
+    FormField::delete_by_id(field_id)
+        .exec(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "form_field_delete",
+        "form_field",
+        Some(&field_id.to_string()),
+        Some(serde_json::json!({ "form_type": existing.form_type, "field_key": existing.field_key })),
+    )
+    .await?;
+
+    Ok(Json(serde_json::json!({ "status": "ok" })))
 }
 
-/// 表单字段新增请求。
-#[derive(Debug, Deserialize, Validate)]
-pub struct CreateFormFieldRequest {
-    /// 表单类型。
-    #[validate(length(min = 1, max = 32))]
+/// 表单配置导出/导入中的字段定义。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FormSchemaField {
+    /// 表单类型（contest/volunteer 等）；导出列见 [`FormSchemaDocument::export_profiles`]。
     pub form_type: String,
     /// 字段 key。
-    #[validate(length(min = 1, max = 64))]
     pub field_key: String,
     /// 字段标签。
-    #[validate(length(min = 1, max = 64))]
     pub label: String,
     /// 字段类型。
-    #[validate(length(min = 1, max = 32))]
     pub field_type: String,
     /// 是否必填。
     pub required: bool,
     /// 排序序号。
     pub order_index: i32,
+    /// select/radio 字段的可选值。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<String>>,
+    /// number 字段的最小值。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_value: Option<i32>,
+    /// number 字段的最大值。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_value: Option<i32>,
+    /// 取值最大字符数。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<i32>,
 }
 
-/// 表单字段响应。
-#[derive(Debug, Serialize)]
-pub struct FormFieldResponse {
-    /// 记录 ID。
-    pub id: Uuid,
-    /// 表单类型。
-    pub form_type: String,
-    /// 字段 key。
-    pub field_key: String,
-    /// 字段标签。
-    pub label: String,
-    /// 字段类型。
-    pub field_type: String,
-    /// 是否必填。
-    pub required: bool,
-    /// 排序序号。
-    pub order_index: i32,
+/// 表单配置导出/导入中的导出列配置。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FormSchemaExportProfile {
+    /// 导出 key（summary/student_export/labor_hours_excel）。
+    pub profile_key: String,
+    /// 导出列，数组顺序即列序。
+    pub columns: Vec<ExportColumn>,
 }
 
-/// 查询表单字段。
-pub async fn list_form_fields(
+/// 表单配置文档（用于跨环境迁移）。
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FormSchemaDocument {
+    /// 文档格式版本。
+    pub version: u32,
+    /// 字段定义列表。
+    pub fields: Vec<FormSchemaField>,
+    /// 已在后台配置的导出列（版本 2 起）。
+    #[serde(default)]
+    pub export_profiles: Vec<FormSchemaExportProfile>,
+}
+
+/// 表单配置导入请求。
+#[derive(Debug, Deserialize)]
+pub struct FormSchemaImportRequest {
+    /// 表单配置文档。
+    pub schema: FormSchemaDocument,
+    /// field_key 冲突处理方式（overwrite/skip），默认 overwrite。
+    pub on_conflict: Option<String>,
+}
+
+/// 当前表单配置文档版本；版本 2 起导出列单独保存在 `export_profiles` 中。
+const FORM_SCHEMA_VERSION: u32 = 2;
+/// 导出列仍以表单字段保存的旧版文档。
+const LEGACY_FORM_SCHEMA_VERSION: u32 = 1;
+
+/// 导出全部表单字段配置（仅管理员）。
+pub async fn export_form_schema(
     State(state): State<AppState>,
     jar: CookieJar,
-) -> Result<Json<Vec<FormFieldResponse>>, AppError> {
+) -> Result<Json<FormSchemaDocument>, AppError> {
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
 
-    let fields = FormField::find()
+    let mut fields = FormField::find()
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    fields.sort_by(|a, b| {
+        a.form_type
+            .cmp(&b.form_type)
+            .then(a.order_index.cmp(&b.order_index))
+            .then(a.field_key.cmp(&b.field_key))
+    });
+    let profiles = ExportProfile::find()
         .all(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    let mut export_profiles = Vec::new();
+    for def in EXPORT_PROFILES {
+        if let Some(profile) = profiles.iter().find(|profile| profile.profile_key == def.key) {
+            export_profiles.push(FormSchemaExportProfile {
+                profile_key: def.key.to_string(),
+                columns: parse_export_columns(profile)?,
+            });
+        }
+    }
 
-    Ok(Json(
-        fields
+    Ok(Json(FormSchemaDocument {
+        version: FORM_SCHEMA_VERSION,
+        fields: fields
             .into_iter()
-            .map(|field| FormFieldResponse {
-                id: field.id,
+            .map(|field| FormSchemaField {
+                options: field_options(&field),
+                min_value: field.min_value,
+                max_value: field.max_value,
+                max_length: field.max_length,
                 form_type: field.form_type,
                 field_key: field.field_key,
                 label: field.label,
@@ -1054,48 +3078,149 @@ pub async fn list_form_fields(
                 order_index: field.order_index,
             })
             .collect(),
-    ))
+        export_profiles,
+    }))
 }
 
-/// 新增表单字段。
-pub async fn create_form_field(
+/// 导入表单字段与导出列配置（字段按 form_type + field_key、导出列按 profile_key 处理冲突，仅管理员）。
+pub async fn import_form_schema(
     State(state): State<AppState>,
     jar: CookieJar,
-    Json(payload): Json<CreateFormFieldRequest>,
-) -> Result<Json<FormFieldResponse>, AppError> {
+    Json(payload): Json<FormSchemaImportRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
-    payload
-        .validate()
-        .map_err(|_| AppError::validation("invalid form field payload"))?;
+    if !(LEGACY_FORM_SCHEMA_VERSION..=FORM_SCHEMA_VERSION).contains(&payload.schema.version) {
+        return Err(AppError::bad_request("unsupported schema version"));
+    }
+    let overwrite = match payload.on_conflict.as_deref().unwrap_or("overwrite") {
+        "overwrite" => true,
+        "skip" => false,
+        _ => return Err(AppError::bad_request("invalid conflict strategy")),
+    };
+    let (fields, profiles) = split_form_schema(payload.schema)?;
+    let fields = normalize_form_schema_fields(fields)?;
 
+    let existing = FormField::find()
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let mut existing_map: HashMap<(String, String), form_fields::Model> = existing
+        .into_iter()
+        .map(|field| ((field.form_type.clone(), field.field_key.clone()), field))
+        .collect();
+
+    let txn = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
     let now = Utc::now();
-    let id = Uuid::new_v4();
-    let model = form_fields::ActiveModel {
-        id: Set(id),
-        form_type: Set(payload.form_type.clone()),
-        field_key: Set(payload.field_key.clone()),
-        label: Set(payload.label.clone()),
-        field_type: Set(payload.field_type.clone()),
-        required: Set(payload.required),
-        order_index: Set(payload.order_index),
-        created_at: Set(now),
-        updated_at: Set(now),
-    };
-    form_fields::Entity::insert(model)
-        .exec_without_returning(&state.db)
+    let mut inserted = 0;
+    let mut updated = 0;
+    let mut skipped = 0;
+    for (field, options) in fields {
+        let key = (field.form_type.clone(), field.field_key.clone());
+        if let Some(model) = existing_map.remove(&key) {
+            if !overwrite {
+                skipped += 1;
+                continue;
+            }
+            let mut active: form_fields::ActiveModel = model.into();
+            active.label = Set(field.label);
+            active.field_type = Set(field.field_type);
+            active.required = Set(field.required);
+            active.order_index = Set(field.order_index);
+            active.options = Set(options);
+            active.min_value = Set(field.min_value);
+            active.max_value = Set(field.max_value);
+            active.max_length = Set(field.max_length);
+            active.updated_at = Set(now);
+            active
+                .update(&txn)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+            updated += 1;
+        } else {
+            let model = form_fields::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                form_type: Set(field.form_type),
+                field_key: Set(field.field_key),
+                label: Set(field.label),
+                field_type: Set(field.field_type),
+                required: Set(field.required),
+                order_index: Set(field.order_index),
+                options: Set(options),
+                min_value: Set(field.min_value),
+                max_value: Set(field.max_value),
+                max_length: Set(field.max_length),
+                created_at: Set(now),
+                updated_at: Set(now),
+            };
+            form_fields::Entity::insert(model)
+                .exec_without_returning(&txn)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+            inserted += 1;
+        }
+    }
+    let mut profiles_saved = 0;
+    let mut profiles_skipped = 0;
+    for (def, columns) in profiles {
+        let existing = find_export_profile(&txn, def).await?;
+        if existing.is_some() && !overwrite {
+            profiles_skipped += 1;
+            continue;
+        }
+        let columns_json =
+            serde_json::to_string(&columns).map_err(|_| AppError::internal("serialize export profile failed"))?;
+        match existing {
+            Some(existing) => {
+                let mut active: export_profiles::ActiveModel = existing.into();
+                active.columns_json = Set(columns_json);
+                active.updated_by = Set(Some(user.id));
+                active.updated_at = Set(now);
+                active
+                    .update(&txn)
+                    .await
+                    .map_err(|err| AppError::Database(err.to_string()))?;
+            }
+            None => {
+                ExportProfile::insert(export_profiles::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    profile_key: Set(def.key.to_string()),
+                    columns_json: Set(columns_json),
+                    updated_by: Set(Some(user.id)),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                })
+                .exec_without_returning(&txn)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+            }
+        }
+        let keys: Vec<&str> = columns.iter().map(|column| column.field_key.as_str()).collect();
+        record_audit(
+            &txn,
+            Some(user.id),
+            "export_profile_update",
+            "export_profile",
+            Some(def.key),
+            Some(serde_json::json!({ "columns": keys, "source": "form_schema_import" })),
+        )
+        .await?;
+        profiles_saved += 1;
+    }
+    txn.commit()
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
 
-    Ok(Json(FormFieldResponse {
-        id,
-        form_type: payload.form_type,
-        field_key: payload.field_key,
-        label: payload.label,
-        field_type: payload.field_type,
-        required: payload.required,
-        order_index: payload.order_index,
-    }))
+    Ok(Json(serde_json::json!({
+        "inserted": inserted,
+        "updated": updated,
+        "skipped": skipped,
+        "export_profiles": { "saved": profiles_saved, "skipped": profiles_skipped }
+    })))
 }
 
 /// 获取导出模板（仅管理员）。
@@ -1106,17 +3231,125 @@ pub async fn get_export_template(
 ) -> Result<Json<ExportTemplateResponse>, AppError> {
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
-    if !EXPORT_TEMPLATE_KEYS.contains(&template_key.as_str()) {
-        return Err(AppError::bad_request("unknown template key"));
-    }
+    let config = require_export_template(&state, &template_key, None).await?;
+    Ok(Json(export_template_to_response(&state, config)))
+}
 
-    let mut config = load_export_template(&state, &template_key).await?;
-    let template_path = export_template_file_path(&state, &template_key);
-    if !template_path.exists() {
-        config.name.clear();
-        config.issues.clear();
-    }
-    Ok(Json(export_template_to_response(config)))
+/// 列出导出模板（仅管理员）。
+pub async fn list_export_templates(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<ExportTemplateResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let templates = crate::templates::list_export_templates(&state).await?;
+    Ok(Json(
+        templates
+            .into_iter()
+            .map(|template| export_template_to_response(&state, template))
+            .collect(),
+    ))
+}
+
+/// 登记新的导出模板（仅管理员），之后通过上传接口提供文件。
+pub async fn create_export_template(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<CreateExportTemplateRequest>,
+) -> Result<Json<ExportTemplateResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid export template payload"))?;
+
+    let template_key = payload.template_key.trim().to_string();
+    let export_type = payload
+        .export_type
+        .unwrap_or_else(|| BUILTIN_EXPORT_TEMPLATE_KEY.to_string());
+    let template = register_export_template(
+        &state,
+        &template_key,
+        payload.display_name.trim().to_string(),
+        payload
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string),
+        export_type,
+    )
+    .await?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "export_template_create",
+        "export_template",
+        Some(&template_key),
+        Some(serde_json::json!({ "export_type": template.export_type })),
+    )
+    .await?;
+    Ok(Json(export_template_to_response(&state, template)))
+}
+
+/// 更新导出模板的显示名称与说明（仅管理员）。
+pub async fn update_export_template(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(template_key): Path<String>,
+    Json(payload): Json<UpdateExportTemplateRequest>,
+) -> Result<Json<ExportTemplateResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid export template payload"))?;
+
+    let template = update_export_template_info(
+        &state,
+        &template_key,
+        payload.display_name.trim().to_string(),
+        payload
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string),
+    )
+    .await?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "export_template_update",
+        "export_template",
+        Some(&template_key),
+        None,
+    )
+    .await?;
+    Ok(Json(export_template_to_response(&state, template)))
+}
+
+/// 删除已登记的导出模板及文件（仅管理员，内置模板不可删除）。
+pub async fn delete_export_template(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(template_key): Path<String>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    crate::templates::delete_export_template(&state, &template_key).await?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "export_template_delete",
+        "export_template",
+        Some(&template_key),
+        None,
+    )
+    .await?;
+    Ok(Json(serde_json::json!({ "status": "ok" })))
 }
 
 /// 上传导出模板（仅管理员）。
@@ -1128,31 +3361,159 @@ pub async fn upload_export_template(
 ) -> Result<Json<ExportTemplateResponse>, AppError> {
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
-    if !EXPORT_TEMPLATE_KEYS.contains(&template_key.as_str()) {
-        return Err(AppError::bad_request("unknown template key"));
+    require_export_template(&state, &template_key, None).await?;
+
+    let (file_bytes, file_name, fields) = read_upload_file_with_fields(&mut multipart, state.config.max_upload_bytes).await?;
+    let orientation = fields
+        .get("orientation")
+        .map(|value| value.as_str())
+        .unwrap_or("portrait");
+    if orientation != "portrait" && orientation != "landscape" {
+        return Err(AppError::bad_request("invalid orientation"));
+    }
+    let issues = crate::export_template::validate_export_template_bytes(&file_bytes)?;
+
+    let template_path = export_template_file_path(&state, &template_key);
+    if let Some(parent) = template_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| AppError::internal(&format!("create template dir failed: {err}")))?;
+    }
+    std::fs::write(&template_path, &file_bytes)
+        .map_err(|err| AppError::internal(&format!("save template failed: {err}")))?;
+
+    let updated =
+        upsert_export_template_meta(&state, &template_key, file_name, issues, orientation.to_string()).await?;
+    Ok(Json(export_template_to_response(&state, updated)))
+}
+
+/// 导出模板预览参数。
+#[derive(Debug, Deserialize)]
+pub struct ExportTemplatePreviewQuery {
+    /// 输出格式（xlsx/pdf），默认 xlsx。
+    pub format: Option<String>,
+}
+
+/// 导出模板预览响应。
+#[derive(Debug, Serialize)]
+pub struct ExportTemplatePreviewResponse {
+    /// 文件名。
+    pub file_name: String,
+    /// MIME 类型。
+    pub content_type: String,
+    /// 文件内容（Base64）。
+    pub content_base64: String,
+    /// 示例数据无法替换的占位符。
+    pub unresolved_placeholders: Vec<String>,
+}
+
+/// 使用示例数据渲染已上传的导出模板（仅管理员）。
+pub async fn preview_export_template(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(template_key): Path<String>,
+    Query(query): Query<ExportTemplatePreviewQuery>,
+) -> Result<Json<ExportTemplatePreviewResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let template_meta = require_export_template(&state, &template_key, None).await?;
+    let as_pdf = match query.format.as_deref().unwrap_or("xlsx") {
+        "xlsx" => false,
+        "pdf" => true,
+        _ => return Err(AppError::bad_request("invalid preview format")),
+    };
+    let template_path = export_template_file_path(&state, &template_key);
+    if !template_path.exists() {
+        return Err(AppError::bad_request("export template not configured"));
     }
+    let orientation = if template_meta.orientation == "landscape" {
+        umya_spreadsheet::structs::OrientationValues::Landscape
+    } else {
+        umya_spreadsheet::structs::OrientationValues::Portrait
+    };
+
+    let custom_fields: Vec<(String, String)> = FormField::find()
+        .filter(form_fields::Column::FormType.eq("contest"))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|field| (field.field_key, field.label))
+        .collect();
+    let single_values = crate::export_template::sample_single_values();
+    let list_values = crate::export_template::sample_list_values(&custom_fields);
+    let unresolved_placeholders =
+        crate::export_template::unresolved_placeholders(&template_path, &single_values, &list_values)?;
+
+    let (temp_dir, output_xlsx) = tokio::task::spawn_blocking(move || {
+        let temp_dir = tempfile::tempdir()
+            .map_err(|_| AppError::internal("create temp dir failed"))?;
+        let output_xlsx = temp_dir.path().join("preview.xlsx");
+        crate::export_template::render_template_to_xlsx(
+            &template_path,
+            &output_xlsx,
+            &single_values,
+            &list_values,
+            orientation,
+        )?;
+        Ok::<_, AppError>((temp_dir, output_xlsx))
+    })
+    .await
+    .map_err(|err| AppError::internal(&format!("render preview failed: {err}")))??;
+    // PDF 转换与正式导出共用转换队列。
+    let bytes = if as_pdf {
+        state.pdf_converter.convert(&output_xlsx, temp_dir.path()).await?
+    } else {
+        tokio::fs::read(&output_xlsx)
+            .await
+            .map_err(|_| AppError::internal("read preview failed"))?
+    };
 
-    let (file_bytes, file_name, fields) = read_upload_file_with_fields(&mut multipart).await?;
-    let orientation = fields
-        .get("orientation")
-        .map(|value| value.as_str())
-        .unwrap_or("portrait");
-    if orientation != "portrait" && orientation != "landscape" {
-        return Err(AppError::bad_request("invalid orientation"));
-    }
-    let issues = crate::export_template::validate_export_template_bytes(&file_bytes)?;
+    let (extension, content_type) = if as_pdf {
+        ("pdf", "application/pdf")
+    } else {
+        ("xlsx", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+    };
+    Ok(Json(ExportTemplatePreviewResponse {
+        file_name: format!("{template_key}-preview.{extension}"),
+        content_type: content_type.to_string(),
+        content_base64: base64::engine::general_purpose::STANDARD.encode(bytes),
+        unresolved_placeholders,
+    }))
+}
 
-    let template_path = export_template_file_path(&state, &template_key);
-    if let Some(parent) = template_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|err| AppError::internal(&format!("create template dir failed: {err}")))?;
-    }
-    std::fs::write(&template_path, &file_bytes)
-        .map_err(|err| AppError::internal(&format!("save template failed: {err}")))?;
+/// 已删除记录列表查询参数。
+#[derive(Debug, Default, Deserialize)]
+pub struct DeletedRecordQuery {
+    /// 是否内嵌学生学号、姓名与班级。
+    #[serde(default)]
+    pub include_student: bool,
+}
 
-    let updated =
-        upsert_export_template_meta(&state, &template_key, file_name, issues, orientation.to_string()).await?;
-    Ok(Json(export_template_to_response(updated)))
+/// 记录列表中内嵌的学生信息。
+#[derive(Debug, Serialize)]
+pub struct RecordStudentInfo {
+    /// 学号。
+    pub student_no: String,
+    /// 姓名。
+    pub name: String,
+    /// 院系。
+    pub department: String,
+    /// 班级。
+    pub class_name: String,
+    /// 学生档案是否已删除。
+    pub is_deleted: bool,
+}
+
+impl From<students::Model> for RecordStudentInfo {
+    fn from(student: students::Model) -> Self {
+        Self {
+            student_no: student.student_no,
+            name: student.name,
+            department: student.department,
+            class_name: student.class_name,
+            is_deleted: student.is_deleted,
+        }
+    }
 }
 
 /// 已删除竞赛记录响应。
@@ -1162,6 +3523,9 @@ pub struct DeletedContestRecordResponse {
     pub id: Uuid,
     /// 学生 ID。
     pub student_id: Uuid,
+    /// 学生信息（`include_student=true` 时返回）。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub student: Option<RecordStudentInfo>,
     /// 竞赛名称。
     pub contest_name: String,
     /// 状态。
@@ -1170,6 +3534,32 @@ pub struct DeletedContestRecordResponse {
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// 已删除志愿服务记录响应。
+#[derive(Debug, Serialize)]
+pub struct DeletedVolunteerRecordResponse {
+    /// 记录 ID。
+    pub id: Uuid,
+    /// 学生 ID。
+    pub student_id: Uuid,
+    /// 学生信息（`include_student=true` 时返回）。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub student: Option<RecordStudentInfo>,
+    /// 标题。
+    pub title: String,
+    /// 状态。
+    pub status: String,
+    /// 创建时间。
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 恢复学生查询参数。
+#[derive(Debug, Default, Deserialize)]
+pub struct RestoreStudentQuery {
+    /// 同时恢复该学生已删除的竞赛与志愿服务记录。
+    #[serde(default)]
+    pub cascade: bool,
+}
+
 /// 更新学生登录权限请求。
 #[derive(Debug, Deserialize)]
 pub struct UpdateStudentLoginRequest {
@@ -1177,19 +3567,6 @@ pub struct UpdateStudentLoginRequest {
     pub allow_login: bool,
 }
 
-/// 学生用户密码规则。
-#[derive(Debug, Deserialize)]
-pub struct StudentPasswordRule {
-    /// 固定前缀。
-    pub prefix: Option<String>,
-    /// 固定后缀。
-    pub suffix: Option<String>,
-    /// 是否包含学号。
-    pub include_student_no: bool,
-    /// 是否包含手机号。
-    pub include_phone: bool,
-}
-
 /// 批量为学生创建用户请求。
 #[derive(Debug, Deserialize)]
 pub struct CreateStudentUsersRequest {
@@ -1219,37 +3596,6 @@ pub struct GeneratedStudentPassword {
     pub password: String,
 }
 
-fn build_student_password(
-    rule: &StudentPasswordRule,
-    student: &students::Model,
-) -> Result<String, AppError> {
-    let mut parts: Vec<String> = Vec::new();
-    if let Some(prefix) = rule.prefix.as_ref() {
-        if !prefix.is_empty() {
-            parts.push(prefix.clone());
-        }
-    }
-    if rule.include_student_no {
-        parts.push(student.student_no.clone());
-    }
-    if rule.include_phone {
-        if student.phone.is_empty() {
-            return Err(AppError::bad_request("student phone missing"));
-        }
-        parts.push(student.phone.clone());
-    }
-    if let Some(suffix) = rule.suffix.as_ref() {
-        if !suffix.is_empty() {
-            parts.push(suffix.clone());
-        }
-    }
-    let password = parts.join("");
-    if password.is_empty() {
-        return Err(AppError::bad_request("password rule produces empty password"));
-    }
-    Ok(password)
-}
-
 /// 修改学生是否允许密码登录（仅管理员）。
 pub async fn update_student_login(
     State(state): State<AppState>,
@@ -1286,7 +3632,9 @@ pub async fn update_student_login(
             .await
             .map_err(|err| AppError::Database(err.to_string()))?;
     } else {
-        let default_password = format!("st{}", student.student_no);
+        let default_password = load_student_password_rule(&state.db)
+            .await?
+            .build(&student.student_no, &student.phone)?;
         let default_hash = hash_password(&default_password)?;
         let model = users::ActiveModel {
             id: Set(Uuid::new_v4()),
@@ -1299,6 +3647,9 @@ pub async fn update_student_login(
             password_updated_at: Set(Some(now)),
             must_change_password: Set(payload.allow_login),
             is_active: Set(true),
+            department: Set(None),
+            notify_review_email: Set(true),
+            locale: Set(None),
             created_at: Set(now),
             updated_at: Set(now),
         };
@@ -1346,7 +3697,7 @@ pub async fn create_student_users(
             skipped += 1;
             continue;
         }
-        let password = build_student_password(&payload.password_rule, &student)?;
+        let password = payload.password_rule.build(&student.student_no, &student.phone)?;
         let hash = hash_password(&password)?;
         let user_id = Uuid::new_v4();
         let model = users::ActiveModel {
@@ -1360,6 +3711,9 @@ pub async fn create_student_users(
             password_updated_at: Set(Some(now)),
             must_change_password: Set(true),
             is_active: Set(true),
+            department: Set(None),
+            notify_review_email: Set(true),
+            locale: Set(None),
             created_at: Set(now),
             updated_at: Set(now),
         };
@@ -1381,6 +3735,223 @@ pub async fn create_student_users(
     }))
 }
 
+/// 学生账号修复请求。
+#[derive(Debug, Deserialize)]
+pub struct RepairStudentUsersRequest {
+    /// 是否为缺少档案的学生账号创建占位档案。
+    #[serde(default)]
+    pub create_student_stubs: bool,
+    /// 仅报告不修复。
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// 检查学生档案与登录账号的一致性（仅管理员）。
+pub async fn check_student_user_links(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<ReconcileReport>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    Ok(Json(check_student_users(&state.db).await?))
+}
+
+/// 修复学生档案与登录账号的对应关系（仅管理员）。
+pub async fn repair_student_user_links(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<RepairStudentUsersRequest>,
+) -> Result<Json<ReconcileReport>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    if payload.dry_run {
+        return Ok(Json(check_student_users(&state.db).await?));
+    }
+
+    let txn = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let report = repair_student_users(&txn, payload.create_student_stubs).await?;
+    if !report.created_users.is_empty() || !report.created_students.is_empty() {
+        record_audit(
+            &txn,
+            Some(user.id),
+            "student_user_repair",
+            "student",
+            None,
+            Some(serde_json::json!({
+                "created_users": report.created_users,
+                "created_students": report.created_students,
+            })),
+        )
+        .await?;
+    }
+    txn.commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(report))
+}
+
+/// 审核指派请求。
+#[derive(Debug, Deserialize)]
+pub struct AssignmentRequest {
+    /// 记录类型：contest/volunteer。
+    pub record_type: String,
+    /// 指派方式：manual/round_robin。
+    pub mode: String,
+    /// 指定记录；轮询分配时为空表示全部未指派的待审记录。
+    pub record_ids: Option<Vec<Uuid>>,
+    /// 手动指派的审核人；为空表示取消指派。
+    pub reviewer_id: Option<Uuid>,
+    /// 参与轮询的审核人；为空表示全部启用的教师与审核人员。
+    #[serde(default)]
+    pub reviewer_ids: Vec<Uuid>,
+    /// 轮询分组：record/class/department，默认 record。
+    pub group_by: Option<String>,
+    /// 轮询时是否重新分配已指派的记录。
+    #[serde(default)]
+    pub include_assigned: bool,
+}
+
+/// 审核指派结果。
+#[derive(Debug, Serialize)]
+pub struct AssignmentResponse {
+    /// 已更新的记录数。
+    pub assigned: usize,
+    /// 没有院系范围匹配的审核人而未分配的记录。
+    pub skipped: Vec<Uuid>,
+    /// 指派后的审核人工作量。
+    pub workload: Vec<ReviewerWorkload>,
+}
+
+/// 查询审核人工作量（仅管理员）。
+pub async fn list_reviewer_workloads(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<ReviewerWorkload>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    Ok(Json(reviewer_workloads(&state.db).await?))
+}
+
+/// 将待审记录指派给审核人：手动指派或按记录/班级/院系轮询（仅管理员）。
+pub async fn create_assignments(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<AssignmentRequest>,
+) -> Result<Json<AssignmentResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let record_type = AssignRecordType::parse(payload.record_type.trim())?;
+    let reviewers = load_assignable_reviewers(&state.db).await?;
+    let find_reviewer = |reviewer_id: Uuid| {
+        reviewers
+            .iter()
+            .find(|reviewer| reviewer.id == reviewer_id)
+            .ok_or_else(|| AppError::validation("invalid reviewer"))
+    };
+
+    let txn = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let (assigned, skipped) = match payload.mode.trim() {
+        "manual" => {
+            let mut record_ids = payload
+                .record_ids
+                .clone()
+                .filter(|ids| !ids.is_empty())
+                .ok_or_else(|| AppError::validation("record_ids is required"))?;
+            record_ids.sort();
+            record_ids.dedup();
+            let candidates = load_candidates(&txn, record_type, Some(&record_ids), false, true).await?;
+            if candidates.len() != record_ids.len() {
+                return Err(AppError::not_found("record not found"));
+            }
+            if let Some(reviewer_id) = payload.reviewer_id {
+                let reviewer = find_reviewer(reviewer_id)?;
+                if let Some(scope) = department_scope(reviewer) {
+                    if candidates.iter().any(|candidate| candidate.department != scope) {
+                        return Err(AppError::validation("reviewer department does not cover record"));
+                    }
+                }
+            }
+            apply_assignment(&txn, record_type, &record_ids, payload.reviewer_id).await?;
+            (record_ids.len(), Vec::new())
+        }
+        "round_robin" => {
+            let group = AssignGroup::parse(payload.group_by.as_deref().unwrap_or("record").trim())?;
+            let selected: Vec<&users::Model> = if payload.reviewer_ids.is_empty() {
+                reviewers.iter().collect()
+            } else {
+                payload
+                    .reviewer_ids
+                    .iter()
+                    .map(|reviewer_id| find_reviewer(*reviewer_id))
+                    .collect::<Result<_, _>>()?
+            };
+            if selected.is_empty() {
+                return Err(AppError::validation("no reviewers available"));
+            }
+            let workload = pending_workload(&txn).await?;
+            let mut slots: Vec<ReviewerSlot> = selected
+                .into_iter()
+                .map(|reviewer| ReviewerSlot {
+                    id: reviewer.id,
+                    department: department_scope(reviewer).map(str::to_string),
+                    load: workload.get(&reviewer.id).copied().unwrap_or(0),
+                })
+                .collect();
+            let candidates = load_candidates(
+                &txn,
+                record_type,
+                payload.record_ids.as_deref(),
+                true,
+                payload.include_assigned,
+            )
+            .await?;
+            let plan = plan_round_robin(&candidates, &mut slots, group);
+            let mut by_reviewer: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+            for (record_id, reviewer_id) in &plan.assignments {
+                by_reviewer.entry(*reviewer_id).or_default().push(*record_id);
+            }
+            for (reviewer_id, record_ids) in by_reviewer {
+                apply_assignment(&txn, record_type, &record_ids, Some(reviewer_id)).await?;
+            }
+            (plan.assignments.len(), plan.skipped)
+        }
+        _ => return Err(AppError::validation("invalid mode")),
+    };
+    txn.commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "record_assign",
+        &format!("{}_record", payload.record_type.trim()),
+        None,
+        Some(serde_json::json!({
+            "mode": payload.mode.trim(),
+            "reviewer_id": payload.reviewer_id,
+            "group_by": payload.group_by,
+            "assigned": assigned,
+            "skipped": skipped.len(),
+        })),
+    )
+    .await?;
+    Ok(Json(AssignmentResponse {
+        assigned,
+        skipped,
+        workload: reviewer_workloads(&state.db).await?,
+    }))
+}
+
 /// 重置学生默认密码（仅管理员）。
 pub async fn reset_student_password(
     State(state): State<AppState>,
@@ -1398,15 +3969,18 @@ pub async fn reset_student_password(
         .map_err(|err| AppError::Database(err.to_string()))?
         .ok_or_else(|| AppError::not_found("student not found"))?;
 
-    let default_password = format!("st{}", student.student_no);
+    let default_password = load_student_password_rule(&state.db)
+        .await?
+        .build(&student.student_no, &student.phone)?;
     let default_hash = hash_password(&default_password)?;
     let now = Utc::now();
-    if let Some(existing) = User::find()
+    let user_id = if let Some(existing) = User::find()
         .filter(users::Column::Username.eq(&student.student_no))
         .one(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?
     {
+        let user_id = existing.id;
         let mut active: users::ActiveModel = existing.into();
         active.password_hash = Set(Some(default_hash));
         active.allow_password_login = Set(true);
@@ -1417,9 +3991,11 @@ pub async fn reset_student_password(
             .update(&state.db)
             .await
             .map_err(|err| AppError::Database(err.to_string()))?;
+        user_id
     } else {
+        let user_id = Uuid::new_v4();
         let model = users::ActiveModel {
-            id: Set(Uuid::new_v4()),
+            id: Set(user_id),
             username: Set(student.student_no.clone()),
             display_name: Set(student.name.clone()),
             role: Set("student".to_string()),
@@ -1429,6 +4005,9 @@ pub async fn reset_student_password(
             password_updated_at: Set(Some(now)),
             must_change_password: Set(true),
             is_active: Set(true),
+            department: Set(None),
+            notify_review_email: Set(true),
+            locale: Set(None),
             created_at: Set(now),
             updated_at: Set(now),
         };
@@ -1436,7 +4015,18 @@ pub async fn reset_student_password(
             .exec_without_returning(&state.db)
             .await
             .map_err(|err| AppError::Database(err.to_string()))?;
-    }
+        user_id
+    };
+    push_notifications(
+        &state,
+        vec![NewNotification::new(
+            user_id,
+            KIND_PASSWORD_RESET,
+            "密码已被管理员重置",
+            "管理员已将你的密码重置为默认密码，请登录后立即修改。",
+        )],
+    )
+    .await;
 
     Ok(Json(serde_json::json!({ "status": "ok" })))
 }
@@ -1485,22 +4075,29 @@ pub async fn list_deleted_students(
 pub async fn list_deleted_contest_records(
     State(state): State<AppState>,
     jar: CookieJar,
+    Query(query): Query<DeletedRecordQuery>,
 ) -> Result<Json<Vec<DeletedContestRecordResponse>>, AppError> {
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
 
-    let records = ContestRecord::find()
-        .filter(contest_records::Column::IsDeleted.eq(true))
-        .all(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
+    let finder = ContestRecord::find().filter(contest_records::Column::IsDeleted.eq(true));
+    let records: Vec<(contest_records::Model, Option<students::Model>)> = if query.include_student {
+        finder.find_also_related(Student).all(&state.db).await
+    } else {
+        finder
+            .all(&state.db)
+            .await
+            .map(|rows| rows.into_iter().map(|record| (record, None)).collect())
+    }
+    .map_err(|err| AppError::Database(err.to_string()))?;
 
     Ok(Json(
         records
             .into_iter()
-            .map(|record| DeletedContestRecordResponse {
+            .map(|(record, student)| DeletedContestRecordResponse {
                 id: record.id,
                 student_id: record.student_id,
+                student: student.map(RecordStudentInfo::from),
                 contest_name: record.contest_name,
                 status: record.status,
                 created_at: record.created_at,
@@ -1509,6 +4106,95 @@ pub async fn list_deleted_contest_records(
     ))
 }
 
+/// 获取已删除志愿服务记录（仅管理员）。
+pub async fn list_deleted_volunteer_records(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<DeletedRecordQuery>,
+) -> Result<Json<Vec<DeletedVolunteerRecordResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let finder = VolunteerRecord::find().filter(volunteer_records::Column::IsDeleted.eq(true));
+    let records: Vec<(volunteer_records::Model, Option<students::Model>)> = if query.include_student {
+        finder.find_also_related(Student).all(&state.db).await
+    } else {
+        finder
+            .all(&state.db)
+            .await
+            .map(|rows| rows.into_iter().map(|record| (record, None)).collect())
+    }
+    .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(
+        records
+            .into_iter()
+            .map(|(record, student)| DeletedVolunteerRecordResponse {
+                id: record.id,
+                student_id: record.student_id,
+                student: student.map(RecordStudentInfo::from),
+                title: record.title,
+                status: record.status,
+                created_at: record.created_at,
+            })
+            .collect(),
+    ))
+}
+
+/// 学生数据导出包查询参数。
+#[derive(Debug, Default, Deserialize)]
+pub struct StudentBundleQuery {
+    /// `json`（默认）或 `zip`（附带附件文件）。
+    pub format: Option<String>,
+}
+
+/// 导出单个学生的全部个人数据（仅管理员，含已删除学生），用于个人信息查询与跨系统迁移。
+pub async fn export_student_bundle(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(student_no): Path<String>,
+    Query(query): Query<StudentBundleQuery>,
+) -> Result<Response, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let format = query.format.as_deref().unwrap_or("json");
+    if format != "json" && format != "zip" {
+        return Err(AppError::validation("format must be json or zip"));
+    }
+
+    let student = Student::find()
+        .filter(students::Column::StudentNo.eq(&student_no))
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("student not found"))?;
+    let bundle = build_student_bundle(&state.db, student).await?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "student_data_export",
+        "student",
+        Some(&bundle.student.id.to_string()),
+        Some(serde_json::json!({
+            "student_no": bundle.student.student_no,
+            "format": format,
+            "contest_records": bundle.contest_records.len(),
+            "volunteer_records": bundle.volunteer_records.len(),
+            "attachments": bundle.attachments.len(),
+        })),
+    )
+    .await?;
+
+    if format == "json" {
+        return Ok(Json(bundle).into_response());
+    }
+    let name = format!("student-{student_no}-data.zip");
+    let file = tokio::task::spawn_blocking(move || write_bundle_zip(&bundle))
+        .await
+        .map_err(|err| AppError::internal(&format!("write zip failed: {err}")))??;
+    stream_file_response(&name, "application/zip", file)
+}
+
 /// 删除学生（仅管理员，软删除）。
 pub async fn delete_student(
     State(state): State<AppState>,
@@ -1540,11 +4226,12 @@ pub async fn delete_student(
     Ok(Json(serde_json::json!({ "deleted": true })))
 }
 
-/// 恢复已删除学生（仅管理员）。
+/// 恢复已删除学生（仅管理员）；`cascade=true` 时同时恢复其已删除的记录。
 pub async fn restore_student(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(student_no): Path<String>,
+    Query(query): Query<RestoreStudentQuery>,
 ) -> Result<Json<serde_json::Value>, AppError> {
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
@@ -1556,27 +4243,79 @@ pub async fn restore_student(
         .map_err(|err| AppError::Database(err.to_string()))?
         .ok_or_else(|| AppError::not_found("student not found"))?;
 
-    if !student.is_deleted {
-        return Ok(Json(serde_json::json!({ "restored": true })));
+    let student_id = student.id;
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if student.is_deleted {
+        let mut active: students::ActiveModel = student.into();
+        active.is_deleted = Set(false);
+        active.updated_at = Set(Utc::now());
+        active
+            .update(&transaction)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
     }
+    let (contest_records, volunteer_records) = if query.cascade {
+        restore_student_records(&transaction, student_id).await?
+    } else {
+        (0, 0)
+    };
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
 
-    let mut active: students::ActiveModel = student.into();
-    active.is_deleted = Set(false);
-    active.updated_at = Set(Utc::now());
-    active
-        .update(&state.db)
+    Ok(Json(serde_json::json!({
+        "restored": true,
+        "contest_records": contest_records,
+        "volunteer_records": volunteer_records,
+    })))
+}
+
+/// 恢复学生名下已删除的竞赛与志愿服务记录，返回各自恢复的数量。
+async fn restore_student_records<C>(db: &C, student_id: Uuid) -> Result<(u64, u64), AppError>
+where
+    C: ConnectionTrait,
+{
+    let now = Utc::now();
+    let contest = ContestRecord::update_many()
+        .col_expr(contest_records::Column::IsDeleted, Expr::value(false))
+        .col_expr(contest_records::Column::UpdatedAt, Expr::value(now))
+        .filter(contest_records::Column::StudentId.eq(student_id))
+        .filter(contest_records::Column::IsDeleted.eq(true))
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let volunteer = VolunteerRecord::update_many()
+        .col_expr(volunteer_records::Column::IsDeleted, Expr::value(false))
+        .col_expr(volunteer_records::Column::UpdatedAt, Expr::value(now))
+        .filter(volunteer_records::Column::StudentId.eq(student_id))
+        .filter(volunteer_records::Column::IsDeleted.eq(true))
+        .exec(db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok((contest.rows_affected, volunteer.rows_affected))
+}
 
-    Ok(Json(serde_json::json!({ "restored": true })))
+/// 彻底删除的响应。
+#[derive(Debug, Serialize)]
+pub struct PurgeResponse {
+    /// 是否已删除。
+    pub deleted: bool,
+    /// 级联删除的数据行数。
+    #[serde(flatten)]
+    pub summary: PurgeSummary,
 }
 
-/// 彻底删除学生（仅管理员）。
+/// 彻底删除学生及其全部记录与附件（仅管理员）。
 pub async fn purge_student(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(student_no): Path<String>,
-) -> Result<Json<serde_json::Value>, AppError> {
+) -> Result<Json<PurgeResponse>, AppError> {
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
 
@@ -1596,55 +4335,50 @@ pub async fn purge_student(
         .begin()
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
-
-    let contest_records = ContestRecord::find()
-        .filter(contest_records::Column::StudentId.eq(student.id))
-        .all(&transaction)
+    let summary = purge::purge_student(&transaction, student.id).await?;
+    transaction
+        .commit()
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
-    let contest_ids: Vec<Uuid> = contest_records.iter().map(|record| record.id).collect();
+    purge::remove_purged_files(&state.config.upload_dir, &summary.files).await;
 
-    if !contest_ids.is_empty() {
-        FormFieldValue::delete_many()
-            .filter(form_field_values::Column::RecordType.eq("contest"))
-            .filter(form_field_values::Column::RecordId.is_in(contest_ids.iter().cloned()))
-            .exec(&transaction)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?;
-        ReviewSignature::delete_many()
-            .filter(review_signatures::Column::RecordType.eq("contest"))
-            .filter(review_signatures::Column::RecordId.is_in(contest_ids.iter().cloned()))
-            .exec(&transaction)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?;
-    }
+    Ok(Json(PurgeResponse { deleted: true, summary }))
+}
 
-    Attachment::delete_many()
-        .filter(attachments::Column::StudentId.eq(student.id))
-        .exec(&transaction)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
+/// 删除未审核竞赛记录（仅管理员，软删除）。
+pub async fn delete_contest_record(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(record_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
 
-    ContestRecord::delete_many()
-        .filter(contest_records::Column::StudentId.eq(student.id))
-        .exec(&transaction)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-    Student::delete_by_id(student.id)
-        .exec(&transaction)
+    let record = ContestRecord::find()
+        .filter(contest_records::Column::Id.eq(record_id))
+        .filter(contest_records::Column::IsDeleted.eq(false))
+        .one(&state.db)
         .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("record not found"))?;
 
-    transaction
-        .commit()
+    if record.status != "submitted" {
+        return Err(AppError::bad_request("reviewed record cannot be deleted"));
+    }
+
+    let mut active: contest_records::ActiveModel = record.into();
+    active.is_deleted = Set(true);
+    active.updated_at = Set(Utc::now());
+    active
+        .update(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
 
     Ok(Json(serde_json::json!({ "deleted": true })))
 }
 
-/// 删除未审核竞赛记录（仅管理员，软删除）。
-pub async fn delete_contest_record(
+/// 恢复已删除竞赛记录（仅管理员）。
+pub async fn restore_contest_record(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(record_id): Path<Uuid>,
@@ -1654,7 +4388,35 @@ pub async fn delete_contest_record(
 
     let record = ContestRecord::find()
         .filter(contest_records::Column::Id.eq(record_id))
-        .filter(contest_records::Column::IsDeleted.eq(false))
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("record not found"))?;
+    if !record.is_deleted {
+        return Ok(Json(serde_json::json!({ "restored": true })));
+    }
+    let mut active: contest_records::ActiveModel = record.into();
+    active.is_deleted = Set(false);
+    active.updated_at = Set(Utc::now());
+    active
+        .update(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(serde_json::json!({ "restored": true })))
+}
+
+/// 删除志愿服务记录（仅管理员，软删除）。
+pub async fn delete_volunteer_record(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(record_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let record = VolunteerRecord::find()
+        .filter(volunteer_records::Column::Id.eq(record_id))
+        .filter(volunteer_records::Column::IsDeleted.eq(false))
         .one(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?
@@ -1664,7 +4426,7 @@ pub async fn delete_contest_record(
         return Err(AppError::bad_request("reviewed record cannot be deleted"));
     }
 
-    let mut active: contest_records::ActiveModel = record.into();
+    let mut active: volunteer_records::ActiveModel = record.into();
     active.is_deleted = Set(true);
     active.updated_at = Set(Utc::now());
     active
@@ -1675,8 +4437,8 @@ pub async fn delete_contest_record(
     Ok(Json(serde_json::json!({ "deleted": true })))
 }
 
-/// 恢复已删除竞赛记录（仅管理员）。
-pub async fn restore_contest_record(
+/// 恢复已删除志愿服务记录（仅管理员）。
+pub async fn restore_volunteer_record(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(record_id): Path<Uuid>,
@@ -1684,8 +4446,8 @@ pub async fn restore_contest_record(
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
 
-    let record = ContestRecord::find()
-        .filter(contest_records::Column::Id.eq(record_id))
+    let record = VolunteerRecord::find()
+        .filter(volunteer_records::Column::Id.eq(record_id))
         .one(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?
@@ -1693,7 +4455,7 @@ pub async fn restore_contest_record(
     if !record.is_deleted {
         return Ok(Json(serde_json::json!({ "restored": true })));
     }
-    let mut active: contest_records::ActiveModel = record.into();
+    let mut active: volunteer_records::ActiveModel = record.into();
     active.is_deleted = Set(false);
     active.updated_at = Set(Utc::now());
     active
@@ -1708,7 +4470,7 @@ pub async fn purge_contest_record(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(record_id): Path<Uuid>,
-) -> Result<Json<serde_json::Value>, AppError> {
+) -> Result<Json<PurgeResponse>, AppError> {
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
 
@@ -1721,42 +4483,138 @@ pub async fn purge_contest_record(
     if !record.is_deleted {
         return Err(AppError::bad_request("record must be soft deleted first"));
     }
+    purge_deleted_record(&state, "contest", record_id).await
+}
+
+/// 彻底删除志愿服务记录（仅管理员）。
+pub async fn purge_volunteer_record(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(record_id): Path<Uuid>,
+) -> Result<Json<PurgeResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let record = VolunteerRecord::find()
+        .filter(volunteer_records::Column::Id.eq(record_id))
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("record not found"))?;
+    if !record.is_deleted {
+        return Err(AppError::bad_request("record must be soft deleted first"));
+    }
+    purge_deleted_record(&state, "volunteer", record_id).await
+}
 
+async fn purge_deleted_record(
+    state: &AppState,
+    record_type: &str,
+    record_id: Uuid,
+) -> Result<Json<PurgeResponse>, AppError> {
     let transaction = state
         .db
         .begin()
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
-
-    FormFieldValue::delete_many()
-        .filter(form_field_values::Column::RecordType.eq("contest"))
-        .filter(form_field_values::Column::RecordId.eq(record_id))
-        .exec(&transaction)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-    ReviewSignature::delete_many()
-        .filter(review_signatures::Column::RecordType.eq("contest"))
-        .filter(review_signatures::Column::RecordId.eq(record_id))
-        .exec(&transaction)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-    Attachment::delete_many()
-        .filter(attachments::Column::RecordType.eq("contest"))
-        .filter(attachments::Column::RecordId.eq(record_id))
-        .exec(&transaction)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-    ContestRecord::delete_by_id(record_id)
-        .exec(&transaction)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-
+    let summary = purge::purge_records(&transaction, record_type, &[record_id]).await?;
     transaction
         .commit()
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    purge::remove_purged_files(&state.config.upload_dir, &summary.files).await;
 
-    Ok(Json(serde_json::json!({ "deleted": true })))
+    Ok(Json(PurgeResponse { deleted: true, summary }))
+}
+
+/// 按条件批量彻底删除记录的请求。
+#[derive(Debug, Deserialize)]
+pub struct BulkPurgeRequest {
+    /// 筛选条件。
+    #[serde(flatten)]
+    pub filter: BulkPurgeFilter,
+    /// 只统计匹配数并签发确认令牌，不删除。
+    #[serde(default)]
+    pub dry_run: bool,
+    /// 试运行返回的确认令牌，正式删除时必填。
+    pub confirm_token: Option<String>,
+}
+
+/// 按条件批量彻底删除记录的响应。
+#[derive(Debug, Serialize)]
+pub struct BulkPurgeResponse {
+    /// 是否为试运行。
+    pub dry_run: bool,
+    /// 匹配的记录数。
+    pub matched: u64,
+    /// 确认令牌（仅试运行返回）。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm_token: Option<String>,
+    /// 确认令牌过期时间（仅试运行返回）。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<chrono::DateTime<Utc>>,
+    /// 已提交的删除批次数。
+    pub batches: u64,
+    /// 级联删除的数据行数。
+    #[serde(flatten)]
+    pub summary: PurgeSummary,
+}
+
+/// 按条件批量彻底删除记录（仅管理员）：先试运行获取确认令牌，再携带令牌分批删除。
+pub async fn bulk_purge_records(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<BulkPurgeRequest>,
+) -> Result<Json<BulkPurgeResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let filter = payload.filter.normalized()?;
+    let record_ids = purge::matching_record_ids(&state.db, &filter).await?;
+    let matched = record_ids.len() as u64;
+    let now = Utc::now();
+
+    if payload.dry_run {
+        let (token, expires_at) =
+            purge::issue_purge_confirm_token(&state.config.auth_secret_key, user.id, &filter, &record_ids, now);
+        return Ok(Json(BulkPurgeResponse {
+            dry_run: true,
+            matched,
+            confirm_token: Some(token),
+            expires_at: Some(expires_at),
+            batches: 0,
+            summary: PurgeSummary::default(),
+        }));
+    }
+
+    let token = payload
+        .confirm_token
+        .as_deref()
+        .filter(|token| !token.trim().is_empty())
+        .ok_or_else(|| AppError::validation("confirm_token is required, run a dry run first"))?;
+    purge::verify_purge_confirm_token(&state.config.auth_secret_key, token, user.id, &filter, &record_ids, now)?;
+
+    let (summary, batches) =
+        purge::purge_records_in_batches(&state.db, &state.config.upload_dir, &filter.record_type, &record_ids)
+            .await?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "bulk_purge",
+        &format!("{}_record", filter.record_type),
+        None,
+        Some(serde_json::json!({ "filter": filter, "batches": batches, "summary": summary })),
+    )
+    .await?;
+
+    Ok(Json(BulkPurgeResponse {
+        dry_run: false,
+        matched,
+        confirm_token: None,
+        expires_at: None,
+        batches,
+        summary,
+    }))
 }
 
 /// 批量导入竞赛记录（仅管理员）。
@@ -1768,56 +4626,79 @@ pub async fn import_contest_records(
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
 
-    let (file_bytes, fields) = read_upload_payload(&mut multipart).await?;
+    let (file_bytes, fields) = read_upload_payload(&mut multipart, state.config.max_upload_bytes).await?;
     let field_map = fields
         .get("field_map")
         .map(|value| serde_json::from_str::<HashMap<String, String>>(value))
         .transpose()
         .map_err(|_| AppError::bad_request("invalid field_map"))?;
-    let mut workbook = calamine::Xlsx::new(Cursor::new(file_bytes))
-        .map_err(|_| AppError::bad_request("invalid xlsx file"))?;
-    let sheet_name = workbook
-        .sheet_names()
-        .first()
-        .cloned()
-        .ok_or_else(|| AppError::bad_request("xlsx has no sheets"))?;
-    let range = workbook
-        .worksheet_range(&sheet_name)
-        .map_err(|_| AppError::bad_request("failed to read worksheet"))?;
-
-    let header_index = build_header_index(range.rows().next());
+    let header_options = HeaderOptions::from_fields(&fields)?;
+    let workbook = read_upload_workbook(file_bytes)?;
+    let range = workbook.first_sheet()?;
+
+    let expected = expected_headers(
+        CONTEST_IMPORT_HEADERS.iter().map(|(_, candidates)| *candidates),
+        field_map.as_ref(),
+    );
+    let header = locate_header(range, header_options, &expected)?;
+    let data_start = header.data_start();
+    let header_index = header.index;
     let base_index = build_contest_field_map(&header_index, field_map.as_ref())?;
 
     let custom_field_map = load_form_field_map(&state, "contest").await?;
     let reserved_headers = collect_reserved_headers_by_index(&header_index, &base_index);
+    let defined_semesters = Semester::find()
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
 
+    let batch_id = Uuid::new_v4();
     let transaction = state
         .db
         .begin()
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
 
+    // 预先批量读取表中出现的学生，避免逐行查询。
+    let mut student_nos: Vec<String> = range
+        .rows()
+        .skip(data_start)
+        .map(|row| read_cell_by_index_opt(base_index.get("student_no"), row))
+        .filter(|student_no| !student_no.is_empty())
+        .collect();
+    student_nos.sort();
+    student_nos.dedup();
+    let mut student_ids: HashMap<String, Uuid> = HashMap::new();
+    for chunk in student_nos.chunks(IMPORT_LOOKUP_CHUNK_SIZE) {
+        let rows: Vec<(String, Uuid)> = Student::find()
+            .select_only()
+            .column(students::Column::StudentNo)
+            .column(students::Column::Id)
+            .filter(students::Column::StudentNo.is_in(chunk.to_vec()))
+            .filter(students::Column::IsDeleted.eq(false))
+            .into_tuple()
+            .all(&transaction)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        student_ids.extend(rows);
+    }
+
     let mut inserted = 0usize;
     let mut skipped = 0usize;
-    for row in range.rows().skip(1) {
+    let mut errors = Vec::new();
+    let mut notes = Vec::new();
+    let mut record_models = Vec::new();
+    let mut value_models = Vec::new();
+    for (row_index, row) in range.rows().enumerate().skip(data_start) {
         let student_no = read_cell_by_index_opt(base_index.get("student_no"), row);
         if student_no.is_empty() {
             skipped += 1;
             continue;
         }
 
-        let student = Student::find()
-            .filter(students::Column::StudentNo.eq(&student_no))
-            .filter(students::Column::IsDeleted.eq(false))
-            .one(&transaction)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?;
-        let student = match student {
-            Some(student) => student,
-            None => {
-                skipped += 1;
-                continue;
-            }
+        let Some(&student_id) = student_ids.get(&student_no) else {
+            skipped += 1;
+            continue;
         };
 
         let contest_name = read_cell_by_index_opt(base_index.get("contest_name"), row);
@@ -1846,12 +4727,31 @@ pub async fn import_contest_records(
         let rejection = read_cell_by_index_opt(base_index.get("rejection_reason"), row);
         let status = resolve_status(&status_value, first_review, final_review);
 
+        let field_errors = check_custom_cells(row, &header_index, &custom_field_map, &reserved_headers);
+        if !field_errors.is_empty() {
+            skipped += 1;
+            // Excel 行号从 1 开始。
+            errors.extend(field_errors.into_iter().map(|(field_key, message)| {
+                serde_json::json!({ "row": row_index + 1, "field_key": field_key, "message": message })
+            }));
+            continue;
+        }
+
         let now = Utc::now();
         let award_date = parse_award_date_cell(&award_date)?;
+        let semester_date = contest_semester_date(award_date, now);
+        let semester_id = semester_containing(&defined_semesters, semester_date);
+        // 未定义任何学期时不逐行提示。
+        if semester_id.is_none() && !defined_semesters.is_empty() {
+            notes.push(serde_json::json!({
+                "row": row_index + 1,
+                "message": format!("{semester_date} is outside every defined semester"),
+            }));
+        }
         let record_id = Uuid::new_v4();
         let model = contest_records::ActiveModel {
             id: Set(record_id),
-            student_id: Set(student.id),
+            student_id: Set(student_id),
             contest_year: Set(contest_year),
             contest_category: Set(if contest_category.is_empty() { None } else { Some(contest_category.to_uppercase()) }),
             contest_name: Set(contest_name),
@@ -1864,56 +4764,73 @@ pub async fn import_contest_records(
             final_review_hours: Set(final_review),
             first_reviewer_id: Set(None),
             final_reviewer_id: Set(None),
+            competition_id: Set(None),
+            semester_id: Set(semester_id),
+            assigned_reviewer_id: Set(None),
             status: Set(status),
             rejection_reason: Set(if rejection.is_empty() { None } else { Some(rejection) }),
+            version: Set(0),
+            recommended_hours: Set(None),
+            first_reviewed_at: Set(None),
+            final_reviewed_at: Set(None),
+            import_batch_id: Set(Some(batch_id)),
             is_deleted: Set(false),
             created_at: Set(now),
             updated_at: Set(now),
         };
-        contest_records::Entity::insert(model)
-            .exec_without_returning(&transaction)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?;
-
-        insert_custom_fields(
-            &transaction,
+        record_models.push(model);
+        value_models.extend(build_custom_field_values(
             "contest",
             record_id,
             row,
             &header_index,
             &custom_field_map,
             &reserved_headers,
-        )
-        .await?;
+        ));
         inserted += 1;
     }
+    insert_chunked(&transaction, record_models).await?;
+    insert_chunked(&transaction, value_models).await?;
 
+    let import_batch_id = if inserted > 0 {
+        record_import_batch(&transaction, batch_id, IMPORT_KIND_CONTEST, user.id).await?;
+        Some(batch_id)
+    } else {
+        None
+    };
     transaction
         .commit()
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    if inserted > 0 {
+        state.events.publish(QueueEvent::imported("contest", inserted));
+    }
 
-    Ok(Json(serde_json::json!({ "inserted": inserted, "skipped": skipped })))
+    Ok(Json(serde_json::json!({
+        "inserted": inserted,
+        "skipped": skipped,
+        "errors": errors,
+        "notes": notes,
+        "encoding": workbook.encoding,
+        "import_batch_id": import_batch_id,
+    })))
 }
 
 async fn read_upload_payload(
     multipart: &mut Multipart,
+    max_bytes: usize,
 ) -> Result<(Vec<u8>, HashMap<String, String>), AppError> {
     let mut file_bytes = None;
     let mut fields = HashMap::new();
     while let Some(field) = multipart
         .next_field()
         .await
-        .map_err(|_| AppError::bad_request("invalid multipart"))?
+        .map_err(multipart_error)?
     {
         let name = field.name().map(|value| value.to_string());
         match name.as_deref() {
             Some("file") => {
-                let bytes = field
-                    .bytes()
-                    .await
-                    .map_err(|_| AppError::bad_request("failed to read file"))?;
-                file_bytes = Some(bytes.to_vec());
+                file_bytes = Some(read_file_field(field, max_bytes).await?);
             }
             Some(key) => {
                 let value = field
@@ -1929,26 +4846,6 @@ async fn read_upload_payload(
     Ok((file_bytes, fields))
 }
 
-fn build_header_index(header_row: Option<&[Data]>) -> HashMap<String, usize> {
-    let mut header_index = HashMap::new();
-    if let Some(header_row) = header_row {
-        for (idx, cell) in header_row.iter().enumerate() {
-            let trimmed = cell.to_string().trim().to_string();
-            if !trimmed.is_empty() {
-                header_index.insert(trimmed, idx);
-            }
-        }
-    }
-    header_index
-}
-
-fn find_header_index(
-    header_index: &HashMap<String, usize>,
-    candidates: &[&str],
-) -> Option<usize> {
-    candidates.iter().find_map(|key| header_index.get(*key).cloned())
-}
-
 #[cfg(test)]
 fn map_base_indices(
     header_index: &HashMap<String, usize>,
@@ -2007,26 +4904,16 @@ fn build_contest_field_map(
     Ok(result)
 }
 
+/// 已映射到基础字段的列的全部表头名称（多行表头的一列可能有多个名称）。
 fn collect_reserved_headers_by_index(
     header_index: &HashMap<String, usize>,
     base_index: &HashMap<String, usize>,
 ) -> Vec<String> {
-    let mut reserved = Vec::new();
-    for idx in base_index.values() {
-        if let Some(name) = header_name_for_index(header_index, *idx) {
-            reserved.push(name);
-        }
-    }
-    reserved
-}
-
-fn header_name_for_index(
-    header_index: &HashMap<String, usize>,
-    index: usize,
-) -> Option<String> {
     header_index
         .iter()
-        .find_map(|(key, value)| (*value == index).then(|| key.clone()))
+        .filter(|(_, idx)| base_index.values().any(|reserved| reserved == *idx))
+        .map(|(name, _)| name.clone())
+        .collect()
 }
 
 fn parse_column_reference(value: &str) -> Option<usize> {
@@ -2065,17 +4952,26 @@ fn normalize_category(value: &str, suffix: Option<&str>) -> String {
     normalized.trim().to_string()
 }
 
-fn export_template_to_response(template: ExportTemplateConfig) -> ExportTemplateResponse {
+/// 转为响应；模板文件不存在时清空文件名与校验问题。
+fn export_template_to_response(state: &AppState, mut template: ExportTemplateConfig) -> ExportTemplateResponse {
+    if !export_template_file_path(state, &template.template_key).exists() {
+        template.name.clear();
+        template.issues.clear();
+    }
     ExportTemplateResponse {
         template_key: template.template_key,
         name: template.name,
         issues: template.issues,
         orientation: template.orientation,
+        display_name: template.display_name,
+        description: template.description,
+        export_type: template.export_type,
     }
 }
 
 async fn read_upload_file_with_fields(
     multipart: &mut Multipart,
+    max_bytes: usize,
 ) -> Result<(Vec<u8>, String, HashMap<String, String>), AppError> {
     let mut file_bytes = None;
     let mut file_name = None;
@@ -2083,7 +4979,7 @@ async fn read_upload_file_with_fields(
     while let Some(field) = multipart
         .next_field()
         .await
-        .map_err(|_| AppError::bad_request("invalid multipart"))?
+        .map_err(multipart_error)?
     {
         let name = field.name().map(|value| value.to_string());
         match name.as_deref() {
@@ -2092,11 +4988,7 @@ async fn read_upload_file_with_fields(
                     .file_name()
                     .map(|value| value.to_string())
                     .unwrap_or_else(|| "template.xlsx".to_string());
-                let bytes = field
-                    .bytes()
-                    .await
-                    .map_err(|_| AppError::bad_request("failed to read file"))?;
-                file_bytes = Some(bytes.to_vec());
+                file_bytes = Some(read_file_field(field, max_bytes).await?);
                 file_name = Some(name);
             }
             Some(key) => {
@@ -2127,20 +5019,6 @@ fn ensure_required_headers(
     Ok(())
 }
 
-fn read_cell_by_index_opt(index: Option<&usize>, row: &[Data]) -> String {
-    let idx = match index {
-        Some(value) => *value,
-        None => return String::new(),
-    };
-    read_cell_by_index(idx, row)
-}
-
-fn read_cell_by_index(idx: usize, row: &[Data]) -> String {
-    row.get(idx)
-        .map(|cell| cell.to_string().trim().to_string())
-        .unwrap_or_default()
-}
-
 fn parse_award_date_cell(value: &str) -> Result<Option<chrono::DateTime<Utc>>, AppError> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -2158,26 +5036,6 @@ fn parse_award_date_cell(value: &str) -> Result<Option<chrono::DateTime<Utc>>, A
     Err(AppError::validation("invalid award date"))
 }
 
-fn parse_hours(value: String) -> Option<i32> {
-    if value.is_empty() {
-        return None;
-    }
-    value.parse::<f32>().ok().map(|num| num.round() as i32)
-}
-
-fn resolve_status(status_value: &str, first_review: Option<i32>, final_review: Option<i32>) -> String {
-    if status_value == "不通过" || status_value == "rejected" {
-        return "rejected".to_string();
-    }
-    if status_value == "已复审" || status_value == "final_reviewed" || final_review.is_some() {
-        return "final_reviewed".to_string();
-    }
-    if status_value == "已初审" || status_value == "first_reviewed" || first_review.is_some() {
-        return "first_reviewed".to_string();
-    }
-    "submitted".to_string()
-}
-
 async fn load_form_field_map(
     state: &AppState,
     form_type: &str,
@@ -2224,15 +5082,47 @@ fn collect_reserved_headers(
     reserved
 }
 
-async fn insert_custom_fields(
-    txn: &sea_orm::DatabaseTransaction,
+/// 按字段类型校验一行中的自定义字段单元格，返回 (field_key, 错误说明)。
+fn check_custom_cells(
+    row: &[Data],
+    header_index: &HashMap<String, usize>,
+    field_map: &HashMap<String, form_fields::Model>,
+    reserved_headers: &[String],
+) -> Vec<(String, String)> {
+    let mut errors = Vec::new();
+    for (header, idx) in header_index {
+        if reserved_headers.contains(header) {
+            continue;
+        }
+        let Some(field) = field_map.get(header) else {
+            continue;
+        };
+        let value = read_cell_by_index(*idx, row);
+        if value.is_empty() {
+            if field.required {
+                errors.push((field.field_key.clone(), "is required".to_string()));
+            }
+            continue;
+        }
+        if let Err(message) = check_field_value(field, &value) {
+            errors.push((field.field_key.clone(), message));
+        }
+    }
+    errors.sort();
+    errors
+}
+
+/// 按自定义字段列生成导入行的字段值（空单元格跳过）。
+fn build_custom_field_values(
     record_type: &str,
     record_id: Uuid,
     row: &[Data],
     header_index: &HashMap<String, usize>,
     field_map: &HashMap<String, form_fields::Model>,
     reserved_headers: &[String],
-) -> Result<(), AppError> {
+) -> Vec<form_field_values::ActiveModel> {
+    let now = Utc::now();
+    let mut values = Vec::new();
     for (header, idx) in header_index {
         if reserved_headers.contains(header) {
             continue;
@@ -2242,26 +5132,126 @@ async fn insert_custom_fields(
             if value.is_empty() {
                 continue;
             }
-            let value_model = form_field_values::ActiveModel {
+            values.push(form_field_values::ActiveModel {
                 id: Set(Uuid::new_v4()),
                 record_type: Set(record_type.to_string()),
                 record_id: Set(record_id),
                 field_key: Set(field.field_key.clone()),
                 value: Set(value),
-                created_at: Set(Utc::now()),
-            };
-            form_field_values::Entity::insert(value_model)
-                .exec_without_returning(txn)
-                .await
-                .map_err(|err| AppError::Database(err.to_string()))?;
+                created_at: Set(now),
+            });
         }
     }
+    values
+}
+
+/// 导出列不再保存在表单字段中，拒绝以导出 key 作为 form_type。
+fn reject_export_form_type(form_type: &str) -> Result<(), AppError> {
+    if export_profile_def(form_type.trim()).is_some() {
+        return Err(AppError::validation(&format!(
+            "form_type {} is reserved for export columns, use /admin/export-profiles",
+            form_type.trim()
+        )));
+    }
     Ok(())
 }
 
+/// 拆分表单配置文档为表单字段与导出列配置，并校验导出列。
+///
+/// 版本 1 文档中以导出 key 为 form_type 的字段按排序转为导出列（与迁移规则一致）；
+/// 版本 2 文档中出现这类字段直接拒绝。
+fn split_form_schema(
+    document: FormSchemaDocument,
+) -> Result<(Vec<FormSchemaField>, Vec<(&'static ExportProfileDef, Vec<ExportColumn>)>), AppError> {
+    let mut fields = Vec::with_capacity(document.fields.len());
+    let mut legacy: Vec<(&'static ExportProfileDef, FormSchemaField)> = Vec::new();
+    for field in document.fields {
+        match export_profile_def(field.form_type.trim()) {
+            Some(def) if document.version == LEGACY_FORM_SCHEMA_VERSION => legacy.push((def, field)),
+            Some(_) => reject_export_form_type(&field.form_type)?,
+            None => fields.push(field),
+        }
+    }
+    legacy.sort_by_key(|(_, field)| field.order_index);
+
+    let mut raw: Vec<(&'static ExportProfileDef, Vec<ExportColumn>)> = Vec::new();
+    for profile in document.export_profiles {
+        let def = export_profile_def(profile.profile_key.trim()).ok_or_else(|| {
+            AppError::validation(&format!("unknown export profile: {}", profile.profile_key.trim()))
+        })?;
+        if raw.iter().any(|(existing, _)| existing.key == def.key) {
+            return Err(AppError::validation(&format!("duplicate export profile: {}", def.key)));
+        }
+        raw.push((def, profile.columns));
+    }
+    for (def, field) in legacy {
+        let index = match raw.iter().position(|(existing, _)| existing.key == def.key) {
+            Some(index) => index,
+            None => {
+                raw.push((def, Vec::new()));
+                raw.len() - 1
+            }
+        };
+        let columns = &mut raw[index].1;
+        // 旧版表单字段未约束 key 唯一，重复的 key 只保留排序靠前的一个。
+        let key = field.field_key.trim();
+        if !columns.iter().any(|column| column.field_key == key) {
+            columns.push(ExportColumn {
+                field_key: key.to_string(),
+                label: Some(field.label),
+                number_format: None,
+            });
+        }
+    }
+
+    let mut profiles = Vec::with_capacity(raw.len());
+    for (def, columns) in raw {
+        profiles.push((def, def.normalize_columns(columns)?));
+    }
+    Ok((fields, profiles))
+}
+
+/// 规范化并校验文档中的表单字段（去除首尾空白，规则同新增字段接口），返回字段及可选值 JSON。
+fn normalize_form_schema_fields(
+    fields: Vec<FormSchemaField>,
+) -> Result<Vec<(FormSchemaField, Option<String>)>, AppError> {
+    let within = |value: &str, max: usize| !value.is_empty() && value.chars().count() <= max;
+    let mut seen = std::collections::HashSet::new();
+    let mut normalized = Vec::with_capacity(fields.len());
+    for field in fields {
+        let field = FormSchemaField {
+            form_type: field.form_type.trim().to_string(),
+            field_key: field.field_key.trim().to_string(),
+            label: field.label.trim().to_string(),
+            field_type: field.field_type.trim().to_string(),
+            ..field
+        };
+        if !within(&field.form_type, 32)
+            || !within(&field.field_key, 64)
+            || !within(&field.label, 64)
+            || !within(&field.field_type, 32)
+        {
+            return Err(AppError::validation("invalid form field payload"));
+        }
+        if !seen.insert((field.form_type.clone(), field.field_key.clone())) {
+            return Err(AppError::validation("duplicate field_key in schema"));
+        }
+        let options = normalize_field_definition(
+            &field.field_type,
+            field.options.as_deref(),
+            field.min_value,
+            field.max_value,
+            field.max_length,
+        )?;
+        normalized.push((field, options));
+    }
+    Ok(normalized)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::spreadsheet::build_header_index;
     use calamine::Data;
 
     #[test]
@@ -2326,4 +5316,200 @@ mod tests {
         assert!(reserved.contains(&"审核状态".to_string()));
         assert!(reserved.contains(&"备注".to_string()));
     }
+
+    #[test]
+    fn check_custom_cells_reports_invalid_values() {
+        let now = Utc::now();
+        let field = |field_key: &str, field_type: &str| form_fields::Model {
+            id: Uuid::new_v4(),
+            form_type: "contest".to_string(),
+            field_key: field_key.to_string(),
+            label: field_key.to_string(),
+            field_type: field_type.to_string(),
+            required: true,
+            order_index: 1,
+            options: None,
+            min_value: Some(1),
+            max_value: None,
+            max_length: None,
+            created_at: now,
+            updated_at: now,
+        };
+        let mut field_map = HashMap::new();
+        field_map.insert("队员人数".to_string(), field("team_size", "number"));
+        field_map.insert("答辩日期".to_string(), field("defense_date", "date"));
+        let mut header_index = HashMap::new();
+        header_index.insert("队员人数".to_string(), 0);
+        header_index.insert("答辩日期".to_string(), 1);
+
+        let row = vec![Data::String("0".to_string()), Data::String("2025-09-01".to_string())];
+        let errors = check_custom_cells(&row, &header_index, &field_map, &[]);
+        assert_eq!(errors, vec![("team_size".to_string(), "must be at least 1".to_string())]);
+
+        let row = vec![Data::String("3".to_string()), Data::Empty];
+        let errors = check_custom_cells(&row, &header_index, &field_map, &[]);
+        assert_eq!(errors, vec![("defense_date".to_string(), "is required".to_string())]);
+    }
+
+    fn schema_field(form_type: &str, field_key: &str) -> FormSchemaField {
+        FormSchemaField {
+            form_type: form_type.to_string(),
+            field_key: field_key.to_string(),
+            label: "标签".to_string(),
+            field_type: "text".to_string(),
+            required: false,
+            order_index: 1,
+            options: None,
+            min_value: None,
+            max_value: None,
+            max_length: None,
+        }
+    }
+
+    #[test]
+    fn normalize_form_schema_fields_trims_and_validates() {
+        let fields = vec![schema_field("contest", "team"), schema_field(" contest ", "team ")];
+        assert!(normalize_form_schema_fields(fields).is_err());
+        let fields = vec![schema_field("contest", "team"), schema_field("volunteer", "team")];
+        assert!(normalize_form_schema_fields(fields).is_ok());
+        assert!(normalize_form_schema_fields(vec![schema_field("contest", " ")]).is_err());
+
+        let normalized = normalize_form_schema_fields(vec![schema_field(" contest", " team ")]).unwrap();
+        assert_eq!(normalized[0].0.form_type, "contest");
+        assert_eq!(normalized[0].0.field_key, "team");
+        assert_eq!(normalized[0].1, None);
+
+        let mut unknown = schema_field("contest", "note");
+        unknown.field_type = "textarea".to_string();
+        assert!(normalize_form_schema_fields(vec![unknown]).is_err());
+        let mut select = schema_field("contest", "kind");
+        select.field_type = " select ".to_string();
+        assert!(normalize_form_schema_fields(vec![select.clone()]).is_err());
+        select.options = Some(vec!["个人".to_string(), "团体".to_string()]);
+        let normalized = normalize_form_schema_fields(vec![select]).unwrap();
+        assert_eq!(normalized[0].0.field_type, "select");
+        assert_eq!(normalized[0].1.as_deref(), Some(r#"["个人","团体"]"#));
+        let mut ranged = schema_field("contest", "size");
+        ranged.min_value = Some(1);
+        assert!(normalize_form_schema_fields(vec![ranged]).is_err());
+    }
+
+    #[test]
+    fn split_form_schema_maps_legacy_export_fields() {
+        let mut late = schema_field("summary", "name");
+        late.order_index = 2;
+        let legacy = FormSchemaDocument {
+            version: LEGACY_FORM_SCHEMA_VERSION,
+            fields: vec![late, schema_field("contest", "team"), schema_field("summary", "student_no")],
+            export_profiles: Vec::new(),
+        };
+        let (fields, profiles) = split_form_schema(legacy).unwrap();
+        assert_eq!(fields.len(), 1);
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].0.key, "summary");
+        let keys: Vec<&str> = profiles[0].1.iter().map(|column| column.field_key.as_str()).collect();
+        assert_eq!(keys, ["student_no", "name"]);
+
+        let current = FormSchemaDocument {
+            version: FORM_SCHEMA_VERSION,
+            fields: vec![schema_field(" summary", "name")],
+            export_profiles: Vec::new(),
+        };
+        assert!(split_form_schema(current).is_err());
+
+        let profile = |key: &str, column: &str| FormSchemaExportProfile {
+            profile_key: key.to_string(),
+            columns: vec![ExportColumn {
+                field_key: column.to_string(),
+                label: None,
+                number_format: None,
+            }],
+        };
+        let document = |export_profiles| FormSchemaDocument {
+            version: FORM_SCHEMA_VERSION,
+            fields: Vec::new(),
+            export_profiles,
+        };
+        assert!(split_form_schema(document(vec![profile("labor_hours_excel", "index")])).is_ok());
+        assert!(split_form_schema(document(vec![profile("contest", "index")])).is_err());
+        assert!(split_form_schema(document(vec![profile("summary", "index")])).is_err());
+        assert!(split_form_schema(document(vec![profile("summary", "name"), profile("summary", "name")])).is_err());
+    }
+
+    fn account(role: &str) -> users::Model {
+        users::Model {
+            id: Uuid::new_v4(),
+            username: "reviewer01".to_string(),
+            display_name: "审核员".to_string(),
+            role: role.to_string(),
+            email: None,
+            password_hash: None,
+            allow_password_login: false,
+            password_updated_at: None,
+            must_change_password: false,
+            is_active: true,
+            department: None,
+            notify_review_email: true,
+            locale: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn validate_reset_purpose_matches_role() {
+        assert!(validate_reset_purpose(&account("reviewer"), "totp").is_ok());
+        assert!(validate_reset_purpose(&account("reviewer"), "password").is_err());
+        assert!(validate_reset_purpose(&account("student"), "password").is_ok());
+        assert!(validate_reset_purpose(&account("student"), "passkey").is_err());
+        assert!(validate_reset_purpose(&account("reviewer"), "email").is_err());
+    }
+
+    #[test]
+    fn build_reset_code_workbook_writes_rows() {
+        let response = BatchResetCodeResponse {
+            expires_in_minutes: RESET_TTL_MINUTES,
+            items: vec![
+                BatchResetCodeItem {
+                    username: "reviewer01".to_string(),
+                    display_name: Some("审核员".to_string()),
+                    code: Some("ABCD1234".to_string()),
+                    error: None,
+                },
+                BatchResetCodeItem {
+                    username: "missing".to_string(),
+                    display_name: None,
+                    code: None,
+                    error: Some("user not found".to_string()),
+                },
+            ],
+        };
+        let bytes = build_reset_code_workbook(&response, "totp").expect("workbook");
+        assert!(!bytes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_reset_insert_rolls_back_user() {
+        let db = crate::services::test_support::memory_db().await;
+        db.execute_unprepared(
+            "CREATE TRIGGER fail_reset_insert BEFORE INSERT ON auth_resets BEGIN SELECT RAISE(ABORT, 'injected'); END;",
+        )
+        .await
+        .expect("trigger");
+        let user = account("reviewer");
+        let now = Utc::now();
+        let reset = auth_resets::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            token_hash: Set(hash_token("ABCD1234")),
+            user_id: Set(user.id),
+            purpose: Set("totp".to_string()),
+            expires_at: Set(now + ChronoDuration::minutes(RESET_TTL_MINUTES)),
+            created_at: Set(now),
+            used_at: Set(None),
+        };
+
+        assert!(insert_user_with_reset(&db, user.clone().into(), reset).await.is_err());
+        let orphan = User::find_by_id(user.id).one(&db).await.expect("query");
+        assert!(orphan.is_none());
+    }
 }
@@ -0,0 +1,340 @@
+//! 公告接口：管理员维护公告，前端按当前用户读取生效中的公告。
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder, Set, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    access::{normalize_department, require_role, require_session_user, require_student_profile},
+    announcements::{
+        is_effective, normalize_roles, severity_rank, target_roles, targets, validate_range,
+        validate_severity,
+    },
+    audit::record_audit,
+    entities::{announcements, Announcement},
+    error::AppError,
+    state::AppState,
+};
+
+/// 公告新增/更新请求。
+#[derive(Debug, Deserialize, Validate)]
+pub struct AnnouncementRequest {
+    /// 标题。
+    #[validate(length(min = 1, max = 100))]
+    pub title: String,
+    /// 正文。
+    #[validate(length(max = 2000))]
+    #[serde(default)]
+    pub content: String,
+    /// 严重程度（info/warning/critical），默认 info。
+    pub severity: Option<String>,
+    /// 目标角色；为空时面向所有人（含未登录用户）。
+    #[serde(default)]
+    pub target_roles: Vec<String>,
+    /// 目标院系；为空时不限院系。
+    #[validate(length(max = 64))]
+    pub target_department: Option<String>,
+    /// 生效时间，默认立即生效。
+    pub starts_at: Option<DateTime<Utc>>,
+    /// 截止时间；为空时长期有效。
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// 公告响应。
+#[derive(Debug, Serialize)]
+pub struct AnnouncementResponse {
+    /// 公告 ID。
+    pub id: Uuid,
+    /// 标题。
+    pub title: String,
+    /// 正文。
+    pub content: String,
+    /// 严重程度。
+    pub severity: String,
+    /// 目标角色。
+    pub target_roles: Vec<String>,
+    /// 目标院系。
+    pub target_department: Option<String>,
+    /// 生效时间。
+    pub starts_at: String,
+    /// 截止时间。
+    pub ends_at: Option<String>,
+    /// 当前是否生效。
+    pub active: bool,
+    /// 创建时间。
+    pub created_at: String,
+    /// 更新时间。
+    pub updated_at: String,
+}
+
+/// 列出全部公告（管理员，按生效时间倒序）。
+pub async fn list_announcements(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<AnnouncementResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let now = Utc::now();
+    let items = Announcement::find()
+        .order_by_desc(announcements::Column::StartsAt)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(
+        items
+            .into_iter()
+            .map(|item| announcement_response(item, now))
+            .collect(),
+    ))
+}
+
+/// 读取当前用户可见且生效中的公告（无需登录；未登录时仅返回不限角色与院系的公告）。
+pub async fn list_active_announcements(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<AnnouncementResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await.ok();
+    let department = match &user {
+        Some(user) if user.role == "student" => require_student_profile(&state, user)
+            .await
+            .ok()
+            .map(|student| student.department),
+        Some(user) => user.department.clone(),
+        None => None,
+    };
+    let role = user.as_ref().map(|user| user.role.as_str());
+
+    let now = Utc::now();
+    let mut items: Vec<announcements::Model> = Announcement::find()
+        .order_by_desc(announcements::Column::StartsAt)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .filter(|item| is_effective(item, now) && targets(item, role, department.as_deref()))
+        .collect();
+    items.sort_by(|a, b| severity_rank(&b.severity).cmp(&severity_rank(&a.severity)));
+    Ok(Json(
+        items
+            .into_iter()
+            .map(|item| announcement_response(item, now))
+            .collect(),
+    ))
+}
+
+/// 发布公告（管理员）。
+pub async fn create_announcement(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<AnnouncementRequest>,
+) -> Result<Json<AnnouncementResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let fields = validate_request(&payload)?;
+
+    let now = Utc::now();
+    let model = announcements::Model {
+        id: Uuid::new_v4(),
+        title: fields.title,
+        content: fields.content,
+        severity: fields.severity,
+        target_roles: fields.target_roles,
+        target_department: fields.target_department,
+        starts_at: payload.starts_at.unwrap_or(now),
+        ends_at: payload.ends_at,
+        created_by: Some(user.id),
+        created_at: now,
+        updated_at: now,
+    };
+    validate_range(model.starts_at, model.ends_at)?;
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let active: announcements::ActiveModel = model.clone().into();
+    Announcement::insert(active)
+        .exec_without_returning(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "announcement_create",
+        "announcement",
+        Some(&model.id.to_string()),
+        Some(audit_detail(&model)),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(announcement_response(model, now)))
+}
+
+/// 更新公告（管理员），请求与校验同发布。
+pub async fn update_announcement(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(announcement_id): Path<Uuid>,
+    Json(payload): Json<AnnouncementRequest>,
+) -> Result<Json<AnnouncementResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let fields = validate_request(&payload)?;
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let existing = Announcement::find_by_id(announcement_id)
+        .one(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("announcement not found"))?;
+    let starts_at = payload.starts_at.unwrap_or(existing.starts_at);
+    validate_range(starts_at, payload.ends_at)?;
+    let previous = audit_detail(&existing);
+
+    let now = Utc::now();
+    let mut active: announcements::ActiveModel = existing.into();
+    active.title = Set(fields.title);
+    active.content = Set(fields.content);
+    active.severity = Set(fields.severity);
+    active.target_roles = Set(fields.target_roles);
+    active.target_department = Set(fields.target_department);
+    active.starts_at = Set(starts_at);
+    active.ends_at = Set(payload.ends_at);
+    active.updated_at = Set(now);
+    let model = active
+        .update(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "announcement_update",
+        "announcement",
+        Some(&model.id.to_string()),
+        Some(serde_json::json!({ "from": previous, "to": audit_detail(&model) })),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(announcement_response(model, now)))
+}
+
+/// 删除公告（管理员）。
+pub async fn delete_announcement(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(announcement_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let existing = Announcement::find_by_id(announcement_id)
+        .one(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("announcement not found"))?;
+    Announcement::delete_by_id(announcement_id)
+        .exec(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "announcement_delete",
+        "announcement",
+        Some(&announcement_id.to_string()),
+        Some(audit_detail(&existing)),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(serde_json::json!({ "deleted": true })))
+}
+
+/// 校验后的公告字段。
+struct AnnouncementFields {
+    title: String,
+    content: String,
+    severity: String,
+    target_roles: Option<String>,
+    target_department: Option<String>,
+}
+
+fn validate_request(payload: &AnnouncementRequest) -> Result<AnnouncementFields, AppError> {
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid announcement payload"))?;
+    let title = payload.title.trim().to_string();
+    if title.is_empty() {
+        return Err(AppError::validation("title is required"));
+    }
+    let severity = payload
+        .severity
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or("info")
+        .to_string();
+    validate_severity(&severity)?;
+    Ok(AnnouncementFields {
+        title,
+        content: payload.content.trim().to_string(),
+        severity,
+        target_roles: normalize_roles(&payload.target_roles)?,
+        target_department: normalize_department(payload.target_department.as_deref()),
+    })
+}
+
+fn audit_detail(model: &announcements::Model) -> serde_json::Value {
+    serde_json::json!({
+        "title": model.title,
+        "severity": model.severity,
+        "target_roles": model.target_roles,
+        "target_department": model.target_department,
+        "starts_at": model.starts_at.to_rfc3339(),
+        "ends_at": model.ends_at.map(|value| value.to_rfc3339()),
+    })
+}
+
+fn announcement_response(model: announcements::Model, now: DateTime<Utc>) -> AnnouncementResponse {
+    AnnouncementResponse {
+        active: is_effective(&model, now),
+        id: model.id,
+        target_roles: target_roles(model.target_roles.as_deref()),
+        title: model.title,
+        content: model.content,
+        severity: model.severity,
+        target_department: model.target_department,
+        starts_at: model.starts_at.to_rfc3339(),
+        ends_at: model.ends_at.map(|value| value.to_rfc3339()),
+        created_at: model.created_at.to_rfc3339(),
+        updated_at: model.updated_at.to_rfc3339(),
+    }
+}
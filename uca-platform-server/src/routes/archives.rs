@@ -0,0 +1,111 @@
+//! 数据保留策略与归档管理接口（仅管理员）。
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use sea_orm::{EntityTrait, QueryOrder};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    access::{require_role, require_session_user},
+    archival::{
+        archived_students, load_archival_policy, restore_archive as restore_archive_batch,
+        run_archival, save_archival_policy, ArchivalPolicy, ArchivalReport,
+    },
+    entities::{archives, students, Archive},
+    error::AppError,
+    state::AppState,
+};
+
+/// 归档详情。
+#[derive(Debug, Serialize)]
+pub struct ArchiveDetail {
+    /// 归档批次。
+    pub archive: archives::Model,
+    /// 归档时的学生档案。
+    pub students: Vec<students::Model>,
+}
+
+/// 获取数据保留策略。
+pub async fn get_archival_policy(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<ArchivalPolicy>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    Ok(Json(load_archival_policy(&state.db).await?))
+}
+
+/// 更新数据保留策略。
+pub async fn update_archival_policy(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<ArchivalPolicy>,
+) -> Result<Json<ArchivalPolicy>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    Ok(Json(save_archival_policy(&state, user.id, payload).await?))
+}
+
+/// 列出归档批次（按创建时间倒序）。
+pub async fn list_archives(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<archives::Model>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let items = Archive::find()
+        .order_by_desc(archives::Column::CreatedAt)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(items))
+}
+
+/// 查看归档批次及其中的学生。
+pub async fn get_archive(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(archive_id): Path<Uuid>,
+) -> Result<Json<ArchiveDetail>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let archive = Archive::find_by_id(archive_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("archive not found"))?;
+    let mut students = archived_students(&state.db, archive_id).await?;
+    students.sort_by(|left, right| left.student_no.cmp(&right.student_no));
+    Ok(Json(ArchiveDetail { archive, students }))
+}
+
+/// 立即按当前策略归档到期年级（无论是否启用自动归档）。
+pub async fn run_archival_now(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<ArchivalReport>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let policy = load_archival_policy(&state.db).await?;
+    Ok(Json(run_archival(&state, &policy, Some(user.id)).await?))
+}
+
+/// 恢复归档批次。
+pub async fn restore_archive(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(archive_id): Path<Uuid>,
+) -> Result<Json<archives::Model>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    Ok(Json(
+        restore_archive_batch(&state, archive_id, user.id).await?,
+    ))
+}
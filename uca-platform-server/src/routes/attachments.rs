@@ -9,20 +9,52 @@ use axum::{
 };
 use axum_extra::extract::cookie::CookieJar;
 use chrono::Utc;
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, Set};
+use image::{ImageFormat, ImageOutputFormat};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, Set, TransactionTrait};
 use serde::Serialize;
+use std::io::Cursor;
 use std::path::{Path as StdPath, PathBuf};
 use tokio::fs;
 use uuid::Uuid;
 
 use crate::{
-    access::require_session_user,
-    entities::{attachments, review_signatures, students, Attachment, ContestRecord, Student},
+    access::{department_scope, ensure_student_in_scope, require_session_user, require_student_profile},
+    audit::record_audit,
+    av_scan::{self, ScanVerdict},
+    config::AttachmentConfig,
+    entities::{attachments, review_signatures, students, users, Attachment, ContestRecord, Student},
     error::AppError,
+    services::review::ensure_review_permission,
     state::AppState,
+    storage_usage::ensure_storage_quota,
+    uploads::{field_read_error, multipart_error},
 };
 
+/// 附件类型：学生上传的佐证材料。
+pub const ATTACHMENT_KIND_EVIDENCE: &str = "evidence";
+/// 附件类型：审核签名，导出 PDF 时只取该类型。
+pub const ATTACHMENT_KIND_SIGNATURE: &str = "signature";
+/// 附件类型：供下载填写的模板文件。
+pub const ATTACHMENT_KIND_TEMPLATE: &str = "template";
+
 const MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+/// 个人签名图片大小上限。
+pub(crate) const MAX_SIGNATURE_BYTES: usize = 5 * 1024 * 1024;
+/// 个人签名图片允许的类型（导出 PDF 时需能解码）。
+const SIGNATURE_IMAGE_TYPES: [&str; 2] = ["image/png", "image/jpeg"];
+/// 重新编码 JPEG 时使用的质量。
+const JPEG_REENCODE_QUALITY: u8 = 90;
+/// 缩略图最长边（像素）。
+const THUMBNAIL_MAX_SIDE: u32 = 300;
+/// 缩略图 JPEG 质量。
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+
+/// 从表单读取的单个文件。
+struct UploadedFile {
+    bytes: Vec<u8>,
+    original_name: String,
+    mime_type: String,
+}
 
 /// 附件上传响应。
 #[derive(Debug, Serialize)]
@@ -42,13 +74,13 @@ pub struct SignatureResponse {
     pub signature_path: String,
 }
 
-/// 上传竞赛附件（学生本人）。
+/// 上传竞赛附件（学生本人，可一次上传多个文件）。
 pub async fn upload_contest_attachment(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(record_id): Path<Uuid>,
     multipart: Multipart,
-) -> Result<Json<AttachmentResponse>, AppError> {
+) -> Result<Json<Vec<AttachmentResponse>>, AppError> {
     upload_record_attachment(&state, &jar, "contest", record_id, multipart).await
 }
 
@@ -60,7 +92,7 @@ pub async fn upload_review_signature(
     multipart: Multipart,
 ) -> Result<Json<SignatureResponse>, AppError> {
     let user = require_session_user(&state, &jar).await?;
-    ensure_review_permission(&user.role, &stage)?;
+    let authority = ensure_review_permission(&state.db, &user, &stage).await?;
 
     let student = match record_type.as_str() {
         "contest" => {
@@ -87,29 +119,61 @@ pub async fn upload_review_signature(
         }
         _ => return Err(AppError::bad_request("invalid record type")),
     };
+    ensure_student_in_scope(authority.scope_user(&user), &student)?;
 
-    let (bytes, original_name, _mime_type) = read_multipart_file(multipart).await?;
+    let file = read_multipart_files(multipart, MAX_UPLOAD_BYTES, 1)
+        .await?
+        .pop()
+        .ok_or_else(|| AppError::bad_request("file field required"))?;
     let stored_name = build_stored_name(
         &student.student_no,
         &student.name,
         "signature",
-        &original_name,
+        &file.original_name,
     );
     let dir = build_upload_dir(&state.config.upload_dir, "signatures", &record_type, Some(&stage));
-    let path = save_bytes(&dir, &stored_name, &bytes).await?;
+    let path = save_bytes(&dir, &stored_name, &file.bytes).await?;
 
+    // 签名文件同时登记为签名类附件，与审核签名共用 ID；审核阶段与审核人仍记在审核签名表。
     let id = Uuid::new_v4();
+    let now = Utc::now();
+    let signature_path = path.to_string_lossy().to_string();
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    attachments::Entity::insert(attachments::ActiveModel {
+        id: Set(id),
+        student_id: Set(student.id),
+        record_type: Set(record_type.clone()),
+        record_id: Set(record_id),
+        original_name: Set(file.original_name),
+        stored_name: Set(signature_path.clone()),
+        mime_type: Set(file.mime_type),
+        kind: Set(ATTACHMENT_KIND_SIGNATURE.to_string()),
+        size_bytes: Set(file.bytes.len() as i64),
+        created_at: Set(now),
+    })
+    .exec_without_returning(&transaction)
+    .await
+    .map_err(|err| AppError::Database(err.to_string()))?;
     let model = review_signatures::ActiveModel {
         id: Set(id),
         record_type: Set(record_type),
         record_id: Set(record_id),
         reviewer_user_id: Set(user.id),
+        delegator_user_id: Set(authority.delegator_id()),
         stage: Set(stage),
-        signature_path: Set(path.to_string_lossy().to_string()),
-        created_at: Set(Utc::now()),
+        signature_path: Set(signature_path),
+        created_at: Set(now),
     };
     review_signatures::Entity::insert(model)
-        .exec_without_returning(&state.db)
+        .exec_without_returning(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    transaction
+        .commit()
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
 
@@ -126,27 +190,12 @@ pub async fn download_attachment(
     Path(attachment_id): Path<Uuid>,
 ) -> Result<Response, AppError> {
     let user = require_session_user(&state, &jar).await?;
-    let attachment = Attachment::find_by_id(attachment_id)
-        .one(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-        .ok_or_else(|| AppError::not_found("attachment not found"))?;
-
-    if user.role == "student" {
-        let student = Student::find()
-            .filter(students::Column::StudentNo.eq(&user.username))
-            .filter(students::Column::IsDeleted.eq(false))
-            .one(&state.db)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?
-            .ok_or_else(|| AppError::not_found("student not found"))?;
-        if attachment.student_id != student.id {
-            return Err(AppError::auth("forbidden"));
-        }
-    } else if user.role != "admin" && user.role != "reviewer" && user.role != "teacher" {
-        return Err(AppError::auth("forbidden"));
-    }
+    let attachment = load_accessible_attachment(&state, &user, attachment_id).await?;
+    attachment_response(&attachment).await
+}
 
+/// 读取附件文件并构建内联下载响应。
+pub(crate) async fn attachment_response(attachment: &attachments::Model) -> Result<Response, AppError> {
     let bytes = fs::read(&attachment.stored_name)
         .await
         .map_err(|_| AppError::not_found("file not found"))?;
@@ -171,61 +220,214 @@ pub async fn download_attachment(
     Ok(response)
 }
 
+/// 获取图片附件的缩略图（JPEG）；缺失时按原图补生成，非图片附件返回 404。
+pub async fn download_attachment_thumbnail(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    let attachment = load_accessible_attachment(&state, &user, attachment_id).await?;
+    thumbnail_response(&state, &attachment).await
+}
+
+/// 构建缩略图响应；缺失时按原图补生成。
+pub(crate) async fn thumbnail_response(state: &AppState, attachment: &attachments::Model) -> Result<Response, AppError> {
+    let path = thumbnail_path(&state.config.upload_dir, attachment.id);
+    let bytes = match fs::read(&path).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            let original = fs::read(&attachment.stored_name)
+                .await
+                .map_err(|_| AppError::not_found("file not found"))?;
+            store_thumbnail(&state.config.upload_dir, attachment.id, &attachment.mime_type, original)
+                .await
+                .ok_or_else(|| AppError::not_found("thumbnail not available"))?
+        }
+    };
+
+    let mut response = Response::new(Body::from(bytes));
+    *response.status_mut() = StatusCode::OK;
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("image/jpeg"));
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("private, max-age=86400"));
+    Ok(response)
+}
+
+/// 缩略图存放路径：`upload_dir/thumbnails/<attachment_id>.jpg`。
+pub(crate) fn thumbnail_path(upload_dir: &StdPath, attachment_id: Uuid) -> PathBuf {
+    upload_dir
+        .join("thumbnails")
+        .join(format!("{}.jpg", attachment_id.simple()))
+}
+
+/// 删除附件对应的缩略图（文件不存在时忽略）。
+pub(crate) async fn remove_thumbnails(upload_dir: &StdPath, attachment_ids: &[Uuid]) {
+    for attachment_id in attachment_ids {
+        let _ = fs::remove_file(thumbnail_path(upload_dir, *attachment_id)).await;
+    }
+}
+
+/// 查询附件并校验访问权限。
+pub(crate) async fn load_accessible_attachment(
+    state: &AppState,
+    user: &users::Model,
+    attachment_id: Uuid,
+) -> Result<attachments::Model, AppError> {
+    let attachment = Attachment::find_by_id(attachment_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("attachment not found"))?;
+
+    if user.role == "student" {
+        let student = Student::find()
+            .filter(students::Column::StudentNo.eq(&user.username))
+            .filter(students::Column::IsDeleted.eq(false))
+            .one(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .ok_or_else(|| AppError::not_found("student not found"))?;
+        if attachment.student_id != student.id {
+            return Err(AppError::auth("forbidden"));
+        }
+    } else if user.role != "admin" && user.role != "reviewer" && user.role != "teacher" {
+        return Err(AppError::auth("forbidden"));
+    } else if department_scope(&user).is_some() {
+        let student = Student::find_by_id(attachment.student_id)
+            .one(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .ok_or_else(|| AppError::not_found("student not found"))?;
+        ensure_student_in_scope(user, &student)?;
+    }
+    Ok(attachment)
+}
+
 async fn upload_record_attachment(
     state: &AppState,
     jar: &CookieJar,
     record_type: &str,
     record_id: Uuid,
     multipart: Multipart,
-) -> Result<Json<AttachmentResponse>, AppError> {
+) -> Result<Json<Vec<AttachmentResponse>>, AppError> {
     let user = require_session_user(state, jar).await?;
     if user.role != "student" {
         return Err(AppError::auth("forbidden"));
     }
 
-    let student = Student::find()
-        .filter(students::Column::StudentNo.eq(&user.username))
-        .filter(students::Column::IsDeleted.eq(false))
-        .one(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-        .ok_or_else(|| AppError::not_found("student not found"))?;
+    let student = require_student_profile(state, &user).await?;
 
     ensure_record_ownership(state, record_type, record_id, student.id).await?;
 
-    let (bytes, original_name, mime_type) = read_multipart_file(multipart).await?;
-    if !is_supported_attachment(&mime_type) {
-        return Err(AppError::bad_request("unsupported file type"));
+    let policy = &state.config.attachments;
+    let existing = Attachment::find()
+        .filter(attachments::Column::RecordType.eq(record_type))
+        .filter(attachments::Column::RecordId.eq(record_id))
+        .filter(attachments::Column::Kind.eq(ATTACHMENT_KIND_EVIDENCE))
+        .count(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))? as usize;
+    let remaining = policy.max_per_record.saturating_sub(existing);
+    if remaining == 0 {
+        return Err(AppError::validation(&format!(
+            "attachment limit reached: at most {} per record",
+            policy.max_per_record
+        )));
     }
-    let stored_name = build_stored_name(
-        &student.student_no,
-        &student.name,
-        record_type,
-        &original_name,
-    );
-    let dir = build_upload_dir(&state.config.upload_dir, "attachments", record_type, None);
-    let path = save_bytes(&dir, &stored_name, &bytes).await?;
 
-    let id = Uuid::new_v4();
-    let model = attachments::ActiveModel {
-        id: Set(id),
-        student_id: Set(student.id),
-        record_type: Set(record_type.to_string()),
-        record_id: Set(record_id),
-        original_name: Set(original_name),
-        stored_name: Set(path.to_string_lossy().to_string()),
-        mime_type: Set(mime_type),
-        created_at: Set(Utc::now()),
-    };
-    attachments::Entity::insert(model)
+    let files = read_multipart_files(multipart, policy.max_file_bytes, remaining).await?;
+    let mut prepared = Vec::with_capacity(files.len());
+    for file in files {
+        let mime_type = validate_attachment(policy, &file)?;
+        scan_attachment(state, user.id, &student, record_type, record_id, &file).await?;
+        let bytes = sanitize_attachment_bytes(file.bytes, &mime_type, &file.original_name).await?;
+        prepared.push((file.original_name, mime_type, bytes));
+    }
+    let incoming_bytes: u64 = prepared.iter().map(|(_, _, bytes)| bytes.len() as u64).sum();
+    ensure_storage_quota(&state.db, policy, student.id, incoming_bytes).await?;
+
+    let dir = build_upload_dir(&state.config.upload_dir, "attachments", record_type, None);
+    let record_tag = record_id.simple().to_string();
+    let now = Utc::now();
+    let mut models = Vec::with_capacity(prepared.len());
+    let mut responses = Vec::with_capacity(prepared.len());
+    for (idx, (original_name, mime_type, bytes)) in prepared.into_iter().enumerate() {
+        let file_type = format!("{record_type}-{}-{}", &record_tag[..8], existing + idx + 1);
+        let stored_name = build_stored_name(&student.student_no, &student.name, &file_type, &original_name);
+        let path = save_bytes(&dir, &stored_name, &bytes).await?;
+        let id = Uuid::new_v4();
+        let size_bytes = bytes.len() as i64;
+        store_thumbnail(&state.config.upload_dir, id, &mime_type, bytes).await;
+        models.push(attachments::ActiveModel {
+            id: Set(id),
+            student_id: Set(student.id),
+            record_type: Set(record_type.to_string()),
+            record_id: Set(record_id),
+            original_name: Set(original_name),
+            stored_name: Set(path.to_string_lossy().to_string()),
+            mime_type: Set(mime_type),
+            kind: Set(ATTACHMENT_KIND_EVIDENCE.to_string()),
+            size_bytes: Set(size_bytes),
+            created_at: Set(now),
+        });
+        responses.push(AttachmentResponse {
+            id,
+            stored_name: path.to_string_lossy().to_string(),
+        });
+    }
+    attachments::Entity::insert_many(models)
         .exec_without_returning(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
 
-    Ok(Json(AttachmentResponse {
-        id,
-        stored_name: path.to_string_lossy().to_string(),
-    }))
+    Ok(Json(responses))
+}
+
+/// 启用 clamd 时扫描原始内容：感染文件移入隔离目录并记审计日志后拒绝上传。
+async fn scan_attachment(
+    state: &AppState,
+    actor_id: Uuid,
+    student: &students::Model,
+    record_type: &str,
+    record_id: Uuid,
+    file: &UploadedFile,
+) -> Result<(), AppError> {
+    let Some(clamav) = state.config.clamav.as_ref() else {
+        return Ok(());
+    };
+    let signature = match av_scan::scan_bytes(clamav, &file.bytes).await {
+        Ok(ScanVerdict::Clean) => return Ok(()),
+        Ok(ScanVerdict::Infected(signature)) => signature,
+        Err(err) if clamav.fail_open => {
+            tracing::warn!(error = %err, file = %file.original_name, "virus scan skipped (fail open)");
+            return Ok(());
+        }
+        Err(err) => return Err(err),
+    };
+
+    let quarantine_dir = state.config.upload_dir.join("quarantine");
+    let quarantine_name = format!("{}-{}", Uuid::new_v4().simple(), sanitize_component(&file.original_name));
+    let quarantine_path = save_bytes(&quarantine_dir, &quarantine_name, &file.bytes).await?;
+    let target_id = record_id.to_string();
+    record_audit(
+        &state.db,
+        Some(actor_id),
+        "attachment_quarantined",
+        record_type,
+        Some(&target_id),
+        Some(serde_json::json!({
+            "student_no": student.student_no,
+            "original_name": file.original_name,
+            "signature": signature,
+            "quarantine_path": quarantine_path.to_string_lossy(),
+        })),
+    )
+    .await?;
+    Err(AppError::validation(&format!(
+        "infected file rejected: {} ({signature})",
+        file.original_name
+    )))
 }
 
 async fn ensure_record_ownership(
@@ -253,50 +455,189 @@ async fn ensure_record_ownership(
     Ok(())
 }
 
-async fn read_multipart_file(mut multipart: Multipart) -> Result<(Vec<u8>, String, String), AppError> {
-    let mut file_bytes = None;
-    let mut filename = None;
-    let mut mime_type = None;
+/// 保存用户个人签名图片到 `signatures/users/<user_id>`：仅接受 PNG/JPEG，重新编码去除元数据。
+pub(crate) async fn store_user_signature(
+    upload_dir: &PathBuf,
+    user_id: Uuid,
+    multipart: Multipart,
+) -> Result<PathBuf, AppError> {
+    let file = read_multipart_files(multipart, MAX_SIGNATURE_BYTES, 1)
+        .await?
+        .pop()
+        .ok_or_else(|| AppError::bad_request("file field required"))?;
+    let policy = AttachmentConfig {
+        max_file_bytes: MAX_SIGNATURE_BYTES,
+        allowed_types: SIGNATURE_IMAGE_TYPES.iter().map(|value| value.to_string()).collect(),
+        max_per_record: 1,
+        max_student_bytes: None,
+        max_total_bytes: None,
+    };
+    let mime_type = validate_attachment(&policy, &file)?;
+    let bytes = sanitize_attachment_bytes(file.bytes, &mime_type, &file.original_name).await?;
+    let extension = if mime_type == "image/png" { "png" } else { "jpg" };
+    let stored_name = format!("signature_{}.{extension}", Utc::now().format("%Y%m%d%H%M%S"));
+    let dir = build_upload_dir(upload_dir, "signatures", "users", Some(&user_id.to_string()));
+    save_bytes(&dir, &stored_name, &bytes).await
+}
+
+/// 删除已存储的文件；文件不存在时忽略，其他失败只记录日志。
+pub(crate) async fn remove_stored_file(path: &str) {
+    if let Err(err) = fs::remove_file(path).await {
+        if err.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!(path, error = %err, "remove stored file failed");
+        }
+    }
+}
 
-    while let Some(field) = multipart
+/// 读取表单中 `file`/`files` 字段的全部文件，逐块检查大小与数量上限。
+async fn read_multipart_files(
+    mut multipart: Multipart,
+    max_file_bytes: usize,
+    max_files: usize,
+) -> Result<Vec<UploadedFile>, AppError> {
+    let mut files = Vec::new();
+    while let Some(mut field) = multipart
         .next_field()
         .await
-        .map_err(|_| AppError::bad_request("invalid multipart"))?
+        .map_err(multipart_error)?
     {
-        if field.name() != Some("file") {
+        if !matches!(field.name(), Some("file") | Some("files")) {
             continue;
         }
-        let name = field
+        if files.len() >= max_files {
+            return Err(AppError::validation(&format!("too many files: at most {max_files} allowed")));
+        }
+        let original_name = field
             .file_name()
             .map(|v| v.to_string())
             .unwrap_or_else(|| "upload.bin".to_string());
-        let content_type = field
+        let mime_type = field
             .content_type()
             .map(|v| v.to_string())
             .unwrap_or_else(|| "application/octet-stream".to_string());
-        let bytes = field
-            .bytes()
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field
+            .chunk()
             .await
-            .map_err(|_| AppError::bad_request("failed to read file"))?;
-        if bytes.len() > MAX_UPLOAD_BYTES {
-            return Err(AppError::bad_request("file too large"));
+            .map_err(field_read_error)?
+        {
+            if bytes.len() + chunk.len() > max_file_bytes {
+                return Err(AppError::payload_too_large(&format!(
+                    "file too large: {original_name} exceeds {max_file_bytes} bytes"
+                )));
+            }
+            bytes.extend_from_slice(&chunk);
         }
-        file_bytes = Some(bytes.to_vec());
-        filename = Some(name);
-        mime_type = Some(content_type);
-        break;
+        files.push(UploadedFile {
+            bytes,
+            original_name,
+            mime_type,
+        });
     }
 
-    let bytes = file_bytes.ok_or_else(|| AppError::bad_request("file field required"))?;
-    let filename = filename.ok_or_else(|| AppError::bad_request("file name required"))?;
-    let mime_type = mime_type.ok_or_else(|| AppError::bad_request("mime required"))?;
+    if files.is_empty() {
+        return Err(AppError::bad_request("file field required"));
+    }
+    Ok(files)
+}
+
+/// 校验附件类型与扩展名，返回规范化后的 MIME 类型。
+fn validate_attachment(policy: &AttachmentConfig, file: &UploadedFile) -> Result<String, AppError> {
+    let name = &file.original_name;
+    if file.bytes.is_empty() {
+        return Err(AppError::validation(&format!("empty file: {name}")));
+    }
+    let mime_type = file
+        .mime_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    if !policy.allowed_types.iter().any(|allowed| *allowed == mime_type) {
+        return Err(AppError::validation(&format!(
+            "unsupported file type: {name} ({mime_type}), allowed: {}",
+            policy.allowed_types.join(", ")
+        )));
+    }
+    let extension = StdPath::new(name)
+        .extension()
+        .and_then(|v| v.to_str())
+        .map(|v| v.to_ascii_lowercase())
+        .unwrap_or_default();
+    let expected: &[&str] = match mime_type.as_str() {
+        "image/jpeg" => &["jpg", "jpeg"],
+        "image/png" => &["png"],
+        "application/pdf" => &["pdf"],
+        _ => &[],
+    };
+    if !expected.is_empty() && !expected.contains(&extension.as_str()) {
+        return Err(AppError::validation(&format!(
+            "file extension does not match type: {name} ({mime_type})"
+        )));
+    }
+    Ok(mime_type)
+}
+
+/// 图片附件重新编码以去除 EXIF 等元数据；其他类型原样返回。
+async fn sanitize_attachment_bytes(bytes: Vec<u8>, mime_type: &str, name: &str) -> Result<Vec<u8>, AppError> {
+    let format = match mime_type {
+        "image/jpeg" => ImageFormat::Jpeg,
+        "image/png" => ImageFormat::Png,
+        _ => return Ok(bytes),
+    };
+    let name = name.to_string();
+    tokio::task::spawn_blocking(move || reencode_image(&bytes, format))
+        .await
+        .map_err(|err| AppError::internal(&format!("image task failed: {err}")))?
+        .ok_or_else(|| AppError::validation(&format!("invalid image: {name}")))
+}
+
+/// 为图片附件生成并保存缩略图；非图片或生成失败时返回 `None`（不影响上传）。
+async fn store_thumbnail(upload_dir: &StdPath, attachment_id: Uuid, mime_type: &str, bytes: Vec<u8>) -> Option<Vec<u8>> {
+    let format = match mime_type {
+        "image/jpeg" => ImageFormat::Jpeg,
+        "image/png" => ImageFormat::Png,
+        _ => return None,
+    };
+    let thumbnail = tokio::task::spawn_blocking(move || render_thumbnail(&bytes, format))
+        .await
+        .ok()
+        .flatten()?;
+    let path = thumbnail_path(upload_dir, attachment_id);
+    let dir = path.parent()?;
+    let file_name = path.file_name()?.to_str()?;
+    if let Err(err) = save_bytes(dir, file_name, &thumbnail).await {
+        tracing::warn!(%attachment_id, error = %err, "save thumbnail failed");
+    }
+    Some(thumbnail)
+}
 
-    Ok((bytes, filename, mime_type))
+/// 按比例缩小到不超过 `THUMBNAIL_MAX_SIDE`（不放大），统一输出 JPEG。
+fn render_thumbnail(bytes: &[u8], format: ImageFormat) -> Option<Vec<u8>> {
+    let image = image::load_from_memory_with_format(bytes, format).ok()?;
+    let (width, height) = image::GenericImageView::dimensions(&image);
+    let image = if width > THUMBNAIL_MAX_SIDE || height > THUMBNAIL_MAX_SIDE {
+        image.thumbnail(THUMBNAIL_MAX_SIDE, THUMBNAIL_MAX_SIDE)
+    } else {
+        image
+    };
+    let mut buffer = Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgb8(image.to_rgb8())
+        .write_to(&mut buffer, ImageOutputFormat::Jpeg(THUMBNAIL_JPEG_QUALITY))
+        .ok()?;
+    Some(buffer.into_inner())
 }
 
-fn is_supported_attachment(mime_type: &str) -> bool {
-    let lower = mime_type.to_ascii_lowercase();
-    lower == "application/pdf" || lower.starts_with("image/")
+fn reencode_image(bytes: &[u8], format: ImageFormat) -> Option<Vec<u8>> {
+    let image = image::load_from_memory_with_format(bytes, format).ok()?;
+    let output = match format {
+        ImageFormat::Jpeg => ImageOutputFormat::Jpeg(JPEG_REENCODE_QUALITY),
+        _ => ImageOutputFormat::Png,
+    };
+    let mut buffer = Cursor::new(Vec::new());
+    image.write_to(&mut buffer, output).ok()?;
+    Some(buffer.into_inner())
 }
 
 fn build_stored_name(student_no: &str, name: &str, file_type: &str, original: &str) -> String {
@@ -349,19 +690,10 @@ async fn save_bytes(dir: &StdPath, stored_name: &str, bytes: &[u8]) -> Result<Pa
     Ok(path)
 }
 
-fn ensure_review_permission(role: &str, stage: &str) -> Result<(), AppError> {
-    if stage == "first" && (role == "reviewer" || role == "admin") {
-        return Ok(());
-    }
-    if stage == "final" && (role == "teacher" || role == "admin") {
-        return Ok(());
-    }
-    Err(AppError::auth("forbidden"))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::review::role_can_review;
 
     #[test]
     fn sanitize_component_replaces_separators() {
@@ -391,10 +723,68 @@ mod tests {
         assert!(dir.ends_with("data/uploads/signatures/contest/first"));
     }
 
+    fn uploaded(name: &str, mime_type: &str, bytes: &[u8]) -> UploadedFile {
+        UploadedFile {
+            bytes: bytes.to_vec(),
+            original_name: name.to_string(),
+            mime_type: mime_type.to_string(),
+        }
+    }
+
+    #[test]
+    fn validate_attachment_checks_type_and_extension() {
+        let policy = AttachmentConfig::default();
+        let mime = validate_attachment(&policy, &uploaded("proof.PDF", "application/pdf", b"%PDF")).unwrap();
+        assert_eq!(mime, "application/pdf");
+        let mime = validate_attachment(&policy, &uploaded("a.jpeg", "Image/JPEG; q=1", b"x")).unwrap();
+        assert_eq!(mime, "image/jpeg");
+        assert!(validate_attachment(&policy, &uploaded("a.gif", "image/gif", b"x")).is_err());
+        assert!(validate_attachment(&policy, &uploaded("a.png", "application/pdf", b"x")).is_err());
+        assert!(validate_attachment(&policy, &uploaded("a.pdf", "application/pdf", b"")).is_err());
+    }
+
+    #[test]
+    fn reencode_image_strips_metadata_and_rejects_garbage() {
+        let image = image::RgbImage::from_pixel(8, 8, image::Rgb([255, 0, 0]));
+        let mut jpeg = Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgb8(image)
+            .write_to(&mut jpeg, ImageOutputFormat::Jpeg(80))
+            .unwrap();
+        let mut bytes = jpeg.into_inner();
+        // 在 SOI 之后插入 APP1 Exif 段，模拟照片携带的元数据。
+        let mut app1 = vec![0xFF, 0xE1, 0x00, 0x0E];
+        app1.extend_from_slice(b"Exif\0\0GPS123");
+        bytes.splice(2..2, app1);
+        let cleaned = reencode_image(&bytes, ImageFormat::Jpeg).expect("reencode");
+        assert!(!cleaned.windows(4).any(|window| window == b"Exif"));
+        assert!(reencode_image(b"not an image", ImageFormat::Png).is_none());
+    }
+
+    #[test]
+    fn render_thumbnail_scales_down_only() {
+        let image = image::RgbaImage::from_pixel(1200, 600, image::Rgba([0, 128, 255, 128]));
+        let mut png = Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgba8(image)
+            .write_to(&mut png, ImageOutputFormat::Png)
+            .unwrap();
+        let thumbnail = render_thumbnail(&png.into_inner(), ImageFormat::Png).expect("thumbnail");
+        let decoded = image::load_from_memory_with_format(&thumbnail, ImageFormat::Jpeg).unwrap();
+        assert_eq!(image::GenericImageView::dimensions(&decoded), (300, 150));
+
+        let small = image::RgbImage::from_pixel(40, 20, image::Rgb([10, 10, 10]));
+        let mut jpeg = Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgb8(small)
+            .write_to(&mut jpeg, ImageOutputFormat::Jpeg(80))
+            .unwrap();
+        let thumbnail = render_thumbnail(&jpeg.into_inner(), ImageFormat::Jpeg).expect("thumbnail");
+        let decoded = image::load_from_memory_with_format(&thumbnail, ImageFormat::Jpeg).unwrap();
+        assert_eq!(image::GenericImageView::dimensions(&decoded), (40, 20));
+    }
+
     #[test]
-    fn ensure_review_permission_allows_expected_roles() {
-        assert!(ensure_review_permission("reviewer", "first").is_ok());
-        assert!(ensure_review_permission("teacher", "final").is_ok());
-        assert!(ensure_review_permission("student", "first").is_err());
+    fn review_roles_allow_expected_stages() {
+        assert!(role_can_review("reviewer", "first"));
+        assert!(role_can_review("teacher", "final"));
+        assert!(!role_can_review("student", "first"));
     }
 }
@@ -3,41 +3,64 @@
 use axum::{
     body::Bytes,
     extract::{Path, Query, State},
-    http::HeaderMap,
-    response::IntoResponse,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Redirect, Response},
     Json,
 };
 use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
 use base64::Engine;
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, Set};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, Set,
+    TransactionTrait,
+};
 use sea_orm::prelude::DateTimeUtc;
 use serde::{Deserialize, Serialize};
 use chrono::{Duration as ChronoDuration, Utc};
 use time::{Duration as TimeDuration, OffsetDateTime};
 use uuid::Uuid;
 use webauthn_rs::prelude::{
-    CreationChallengeResponse, PublicKeyCredential, RegisterPublicKeyCredential,
-    RequestChallengeResponse,
+    AuthenticationResult, CreationChallengeResponse, DiscoverableKey, PublicKeyCredential,
+    RegisterPublicKeyCredential, RequestChallengeResponse,
 };
 
 use crate::{
     auth::{
-        decrypt_secret, encrypt_secret, generate_session_token, generate_token, generate_totp,
-        hash_password, hash_session_token, hash_token, verify_password, verify_recovery_code,
-        verify_totp,
+        decrypt_secret, encrypt_secret, generate_recovery_codes, generate_session_token,
+        generate_token, generate_totp, hash_password, hash_session_token, hash_token, totp_backoff_seconds,
+        verify_password, verify_recovery_code, verify_totp,
+        ldap,
+        sso::{self, SsoCallbackQuery},
+    },
+    access::{
+        advisor_class_scope, department_scope, effective_permissions, require_session_user,
+        require_student_profile,
     },
+    auth_flows::{CHALLENGE_TTL_SECONDS, REAUTH_TTL_SECONDS},
+    authenticators::{aaguid_from_attestation, default_passkey_label, normalize_device_label},
     entities::{
-        auth_resets, devices, invites, passkeys, recovery_codes, sessions, totp_secrets, users,
-        AuthReset, Device, Invite, Passkey, RecoveryCode, Session, TotpSecret, User,
+        auth_resets, devices, invites, notifications, passkeys, recovery_codes, sessions, totp_secrets, users,
+        AuthReset, Device, Invite, Notification, Passkey, RecoveryCode, TotpSecret, User,
     },
     error::AppError,
-    mailer::send_mail,
+    health::{check_readiness, ReadinessReport},
+    mail_outbox::enqueue_mail,
+    mailer::{render_mail, PASSWORD_RESET_MAIL},
     policy::load_password_policy,
-    state::{AppState, PasskeyAuthSession, PasskeyRegisterSession, ReauthSession},
+    routes::exports::{file_response, render_recovery_codes_pdf},
+    security::{
+        record_security_event, SecurityEventInput, EVENT_DEVICE_REMOVED, EVENT_LOGIN_FAILED,
+        EVENT_RECOVERY_CODES_GENERATED, EVENT_RECOVERY_CODE_USED, EVENT_ROLE_GRANTED,
+        SEVERITY_HIGH, SEVERITY_MEDIUM,
+    },
+    state::{
+        AppState, PasskeyAuthSession, PasskeyDiscoverableSession, PasskeyRegisterSession, ReauthSession,
+        SsoLoginSession,
+    },
 };
 
 const PASSWORD_RESET_TTL_MINUTES: i64 = 24 * 60;
-const REAUTH_TTL_SECONDS: i64 = 300;
+/// 每次生成的恢复码数量。
+const RECOVERY_CODE_COUNT: usize = 10;
 
 /// 基础健康检查响应。
 #[derive(Debug, Serialize)]
@@ -53,6 +76,27 @@ pub async fn health() -> Json<HealthResponse> {
     })
 }
 
+/// 就绪检查查询参数。
+#[derive(Debug, Deserialize)]
+pub struct ReadinessQuery {
+    /// 是否同时检查 SMTP 连接（默认否）。
+    pub smtp: Option<bool>,
+}
+
+/// 就绪检查接口：必需依赖（数据库、上传目录）不可用时返回 503。
+pub async fn readiness(
+    State(state): State<AppState>,
+    Query(query): Query<ReadinessQuery>,
+) -> (StatusCode, Json<ReadinessReport>) {
+    let report = check_readiness(&state.config, &state.db, query.smtp.unwrap_or(false)).await;
+    let status = if report.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
 /// 当前登录用户信息响应。
 #[derive(Debug, Serialize)]
 pub struct CurrentUserResponse {
@@ -66,20 +110,112 @@ pub struct CurrentUserResponse {
     pub role: String,
     /// 是否必须修改密码（学生账号）。
     pub must_change_password: bool,
+    /// 所属院系（教师/审核人员的数据范围）。
+    pub department: Option<String>,
+    /// 是否已启用 TOTP（仅供前端提示，登录不强制绑定）。
+    pub totp_enrolled: bool,
+    /// 当前生效的权限（含审核委托授予的审核阶段）。
+    pub permissions: Vec<String>,
+    /// 数据范围。
+    pub scope: CurrentUserScope,
+    /// 学生档案摘要（仅学生账号且已关联档案）。
+    pub student: Option<StudentProfileSummary>,
+    /// 待处理事项。
+    pub pending_actions: PendingActions,
+}
+
+/// 当前用户的数据范围。
+#[derive(Debug, Serialize)]
+pub struct CurrentUserScope {
+    /// 限定的院系，不受限时为空。
+    pub department: Option<String>,
+    /// 班主任负责的班级，非班主任为空。
+    pub classes: Option<Vec<String>>,
+}
+
+/// 学生档案摘要。
+#[derive(Debug, Serialize)]
+pub struct StudentProfileSummary {
+    /// 学号。
+    pub student_no: String,
+    /// 姓名。
+    pub name: String,
+    /// 院系。
+    pub department: String,
+    /// 专业。
+    pub major: String,
+    /// 班级。
+    pub class_name: String,
+}
+
+/// 登录后需要用户处理的事项。
+#[derive(Debug, Serialize)]
+pub struct PendingActions {
+    /// 是否必须修改密码。
+    pub must_change_password: bool,
+    /// 未读通知数。
+    pub unread_notifications: u64,
 }
 
-/// 获取当前会话的用户信息。
+/// 获取当前会话的用户信息，附带权限、数据范围与待处理事项。
 pub async fn current_user(
     State(state): State<AppState>,
     jar: CookieJar,
 ) -> Result<Json<CurrentUserResponse>, AppError> {
     let user = require_session(&state, &jar).await?;
+    let permissions = effective_permissions(&state.db, &user, state.config.student_self_export)
+        .await?
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let scope = CurrentUserScope {
+        department: department_scope(&user).map(str::to_string),
+        classes: advisor_class_scope(&state.db, &user).await?,
+    };
+    let student = if user.role == "student" {
+        // 未关联档案的学生账号仍可登录，摘要留空由前端提示联系管理员。
+        match require_student_profile(&state, &user).await {
+            Ok(profile) => Some(StudentProfileSummary {
+                student_no: profile.student_no,
+                name: profile.name,
+                department: profile.department,
+                major: profile.major,
+                class_name: profile.class_name,
+            }),
+            Err(AppError::NotFound(_)) => None,
+            Err(err) => return Err(err),
+        }
+    } else {
+        None
+    };
+    let totp_enrolled = TotpSecret::find()
+        .filter(totp_secrets::Column::UserId.eq(user.id))
+        .filter(totp_secrets::Column::Enabled.eq(true))
+        .count(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        > 0;
+    let unread_notifications = Notification::find()
+        .filter(notifications::Column::UserId.eq(user.id))
+        .filter(notifications::Column::ReadAt.is_null())
+        .count(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
     Ok(Json(CurrentUserResponse {
         id: user.id,
         username: user.username,
         display_name: user.display_name,
         role: user.role,
         must_change_password: user.must_change_password,
+        department: user.department,
+        totp_enrolled,
+        permissions,
+        scope,
+        student,
+        pending_actions: PendingActions {
+            must_change_password: user.must_change_password,
+            unread_notifications,
+        },
     }))
 }
 
@@ -92,10 +228,11 @@ pub async fn logout(
     if let Some(cookie) = jar.get(&cookie_name) {
         let token_hash = hash_session_token(cookie.value());
         sessions::Entity::delete_many()
-            .filter(sessions::Column::TokenHash.eq(token_hash))
+            .filter(sessions::Column::TokenHash.eq(token_hash.clone()))
             .exec(&state.db)
             .await
             .map_err(|err| AppError::Database(err.to_string()))?;
+        state.session_cache.invalidate_token(&token_hash).await;
     }
 
     let expired = Cookie::build((cookie_name, ""))
@@ -350,6 +487,9 @@ pub async fn login_options(
     if user.role == "student" && user.allow_password_login && user.password_hash.is_some() {
         methods.push("password".to_string());
     }
+    if state.config.ldap.is_some() && matches!(user.role.as_str(), "teacher" | "reviewer") {
+        methods.push("ldap".to_string());
+    }
 
     Ok(Json(LoginOptionsResponse { methods }))
 }
@@ -389,11 +529,7 @@ pub async fn reauth_totp(
         .await
         .map_err(|err| AppError::Database(err.to_string()))?
         .ok_or_else(|| AppError::bad_request("no TOTP enrolled"))?;
-
-    let raw = decrypt_secret(&secret.secret_enc, &state.config.auth_secret_key)?;
-    if !verify_totp(&raw, &payload.code)? {
-        return Err(AppError::auth("invalid TOTP"));
-    }
+    check_totp(&state, &secret, &payload.code).await?;
 
     issue_reauth_token(&state, user.id).await
 }
@@ -431,11 +567,7 @@ pub async fn reauth_passkey_start(
         state: auth_state,
         created_at: OffsetDateTime::now_utc(),
     };
-    state
-        .reauth_passkey_state
-        .lock()
-        .await
-        .insert(session_id, session);
+    state.auth_flows.insert_reauth_passkey(session_id, &session).await?;
 
     Ok(Json(ReauthPasskeyStartResponse {
         session_id,
@@ -455,10 +587,9 @@ pub async fn reauth_passkey_finish(
             .map_err(|_| AppError::bad_request("invalid json payload"))?
     };
     let session = state
-        .reauth_passkey_state
-        .lock()
-        .await
-        .take(&payload.session_id)
+        .auth_flows
+        .take_reauth_passkey(&payload.session_id)
+        .await?
         .ok_or_else(|| AppError::bad_request("invalid or expired session"))?;
 
     let auth_result = state
@@ -529,6 +660,7 @@ pub async fn bootstrap_admin(
 
     let now = Utc::now();
     let id = Uuid::new_v4();
+    let username = payload.username.clone();
     let user = users::ActiveModel {
         id: Set(id),
         username: Set(payload.username),
@@ -540,6 +672,9 @@ pub async fn bootstrap_admin(
         password_updated_at: Set(None),
         must_change_password: Set(false),
         is_active: Set(true),
+        department: Set(None),
+        notify_review_email: Set(true),
+        locale: Set(None),
         created_at: Set(now),
         updated_at: Set(now),
     };
@@ -548,6 +683,14 @@ pub async fn bootstrap_admin(
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
 
+    record_security_event(
+        &state,
+        SecurityEventInput::new(EVENT_ROLE_GRANTED, SEVERITY_HIGH)
+            .user(Some(id), &username)
+            .detail("role=admin;bootstrap"),
+    )
+    .await;
+
     let (jar, _) = create_session_cookie(&state, jar, id).await?;
 
     Ok((jar, Json(BootstrapResponse { user_id: id })))
@@ -619,11 +762,7 @@ pub async fn passkey_register_start(
         created_at: OffsetDateTime::now_utc(),
     };
 
-    state
-        .passkey_state
-        .lock()
-        .await
-        .insert_register(session_id, session);
+    state.auth_flows.insert_register(session_id, &session).await?;
 
     Ok(Json(PasskeyRegisterStartResponse {
         session_id,
@@ -662,10 +801,9 @@ pub async fn passkey_register_finish(
             .map_err(|_| AppError::bad_request("invalid json payload"))?
     };
     let session = state
-        .passkey_state
-        .lock()
-        .await
+        .auth_flows
         .take_register(&payload.session_id)
+        .await?
         .ok_or_else(|| AppError::bad_request("invalid or expired session"))?;
 
     require_reauth(&state, &headers, session.user_id).await?;
@@ -677,6 +815,13 @@ pub async fn passkey_register_finish(
 
     let cred_id = passkey.cred_id();
     let cred_id_b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(cred_id.as_ref());
+    let attestation_object: &[u8] = payload.credential.response.attestation_object.as_ref();
+    let aaguid = aaguid_from_attestation(attestation_object);
+    let device_label = match payload.device_label.as_deref() {
+        Some(label) => normalize_device_label(label)
+            .ok_or_else(|| AppError::validation("invalid device label"))?,
+        None => default_passkey_label(aaguid.as_ref()),
+    };
 
     let existing = Passkey::find()
         .filter(passkeys::Column::CredentialId.eq(&cred_id_b64))
@@ -697,6 +842,7 @@ pub async fn passkey_register_finish(
         user_id: Set(session.user_id),
         credential_id: Set(cred_id_b64.clone()),
         passkey_json: Set(passkey_json),
+        aaguid: Set(aaguid.map(|value| value.to_string())),
         created_at: Set(now),
         last_used_at: Set(None),
     };
@@ -709,10 +855,9 @@ pub async fn passkey_register_finish(
         id: Set(Uuid::new_v4()),
         user_id: Set(session.user_id),
         device_type: Set("passkey".to_string()),
-        label: Set(payload
-            .device_label
-            .unwrap_or_else(|| format!("Passkey-{passkey_id}"))),
+        label: Set(device_label),
         credential_id: Set(Some(cred_id_b64)),
+        aaguid: Set(aaguid.map(|value| value.to_string())),
         created_at: Set(now),
         last_used_at: Set(None),
     };
@@ -741,55 +886,44 @@ pub struct PasskeyLoginStartResponse {
 }
 
 /// 开始 Passkey 认证。
+///
+/// 提供用户名时只允许该用户的凭据；未提供时发起可发现凭据认证，`allowCredentials` 为空，
+/// 由浏览器列出本机保存的 Passkey，完成时按凭据中的用户句柄确定用户。
 pub async fn passkey_login_start(
     State(state): State<AppState>,
     Json(payload): Json<PasskeyLoginStartRequest>,
 ) -> Result<Json<PasskeyLoginStartResponse>, AppError> {
-    let (passkey_records, session_user_id) = if let Some(username) = payload.username {
-        let user = User::find()
-            .filter(users::Column::Username.eq(username))
-            .one(&state.db)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?
-            .ok_or_else(|| AppError::not_found("user not found"))?;
-        if !user.is_active {
-            return Err(AppError::auth("user disabled"));
-        }
-        let records = Passkey::find()
-            .filter(passkeys::Column::UserId.eq(user.id))
-            .all(&state.db)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?;
-        (records, user.id)
-    } else {
-        let records = Passkey::find()
-            .all(&state.db)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?;
-        (records, Uuid::nil())
+    let session_id = Uuid::new_v4();
+    let Some(username) = payload.username else {
+        let (challenge, auth_state) = state
+            .webauthn
+            .start_discoverable_authentication()
+            .map_err(|err| AppError::internal(&format!("passkey login start failed: {err}")))?;
+        let session = PasskeyDiscoverableSession {
+            state: auth_state,
+            created_at: OffsetDateTime::now_utc(),
+        };
+        state.auth_flows.insert_discoverable(session_id, &session).await?;
+        return Ok(Json(PasskeyLoginStartResponse {
+            session_id,
+            public_key: challenge,
+        }));
     };
-    if passkey_records.is_empty() {
-        return Err(AppError::bad_request("no passkeys registered"));
-    }
 
-    let passkey_records = if session_user_id.is_nil() {
-        let user_ids: Vec<Uuid> = passkey_records.iter().map(|record| record.user_id).collect();
-        let active_users: std::collections::HashSet<Uuid> = User::find()
-            .filter(users::Column::Id.is_in(user_ids))
-            .filter(users::Column::IsActive.eq(true))
-            .all(&state.db)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?
-            .into_iter()
-            .map(|user| user.id)
-            .collect();
-        passkey_records
-            .into_iter()
-            .filter(|record| active_users.contains(&record.user_id))
-            .collect::<Vec<_>>()
-    } else {
-        passkey_records
-    };
+    let user = User::find()
+        .filter(users::Column::Username.eq(username))
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("user not found"))?;
+    if !user.is_active {
+        return Err(AppError::auth("user disabled"));
+    }
+    let passkey_records = Passkey::find()
+        .filter(passkeys::Column::UserId.eq(user.id))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
     if passkey_records.is_empty() {
         return Err(AppError::bad_request("no passkeys registered"));
     }
@@ -805,18 +939,13 @@ pub async fn passkey_login_start(
         .start_passkey_authentication(&passkeys)
         .map_err(|err| AppError::internal(&format!("passkey login start failed: {err}")))?;
 
-    let session_id = Uuid::new_v4();
     let session = PasskeyAuthSession {
-        user_id: session_user_id,
+        user_id: user.id,
         state: auth_state,
         created_at: OffsetDateTime::now_utc(),
     };
 
-    state
-        .passkey_state
-        .lock()
-        .await
-        .insert_auth(session_id, session);
+    state.auth_flows.insert_auth(session_id, &session).await?;
 
     Ok(Json(PasskeyLoginStartResponse {
         session_id,
@@ -824,6 +953,45 @@ pub async fn passkey_login_start(
     }))
 }
 
+/// 按凭据中的用户句柄读取该用户的 Passkey，完成可发现凭据认证。
+async fn finish_discoverable_login(
+    state: &AppState,
+    credential: &PublicKeyCredential,
+    session: PasskeyDiscoverableSession,
+) -> Result<AuthenticationResult, AppError> {
+    let (user_handle, _) = state
+        .webauthn
+        .identify_discoverable_authentication(credential)
+        .map_err(|err| AppError::auth(&format!("passkey login failed: {err}")))?;
+    let user = User::find_by_id(user_handle)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::auth("credential not found"))?;
+    if !user.is_active {
+        return Err(AppError::auth("user disabled"));
+    }
+    let keys = Passkey::find()
+        .filter(passkeys::Column::UserId.eq(user.id))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .iter()
+        .map(|record| {
+            serde_json::from_str::<webauthn_rs::prelude::Passkey>(&record.passkey_json)
+                .map(|passkey| DiscoverableKey::from(&passkey))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| AppError::internal("failed to parse passkey"))?;
+    if keys.is_empty() {
+        return Err(AppError::auth("credential not found"));
+    }
+    state
+        .webauthn
+        .finish_discoverable_authentication(credential, session.state, &keys)
+        .map_err(|err| AppError::auth(&format!("passkey login failed: {err}")))
+}
+
 /// 完成 Passkey 认证的请求体。
 #[derive(Debug, Deserialize)]
 pub struct PasskeyLoginFinishRequest {
@@ -845,6 +1013,10 @@ pub struct PasskeyLoginFinishResponse {
 pub struct AuthConfigResponse {
     /// 重置凭证交付方式（email/code）。
     pub reset_delivery: String,
+    /// 已启用的统一身份认证协议（cas/oauth2）。
+    pub sso_provider: Option<crate::config::SsoProvider>,
+    /// 是否启用教师/审核人员 LDAP 登录。
+    pub ldap_enabled: bool,
 }
 
 /// 完成 Passkey 认证，更新计数并创建会话 Cookie。
@@ -853,17 +1025,21 @@ pub async fn passkey_login_finish(
     jar: CookieJar,
     Json(payload): Json<PasskeyLoginFinishRequest>,
 ) -> Result<impl IntoResponse, AppError> {
-    let session = state
-        .passkey_state
-        .lock()
-        .await
-        .take_auth(&payload.session_id)
-        .ok_or_else(|| AppError::bad_request("invalid or expired session"))?;
-
-    let auth_result = state
-        .webauthn
-        .finish_passkey_authentication(&payload.credential, &session.state)
-        .map_err(|err| AppError::auth(&format!("passkey login failed: {err}")))?;
+    let session = state.auth_flows.take_auth(&payload.session_id).await?;
+    let auth_result = match session {
+        Some(session) => state
+            .webauthn
+            .finish_passkey_authentication(&payload.credential, &session.state)
+            .map_err(|err| AppError::auth(&format!("passkey login failed: {err}")))?,
+        None => {
+            let session = state
+                .auth_flows
+                .take_discoverable(&payload.session_id)
+                .await?
+                .ok_or_else(|| AppError::bad_request("invalid or expired session"))?;
+            finish_discoverable_login(&state, &payload.credential, session).await?
+        }
+    };
 
     let cred_id_b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD
         .encode(auth_result.cred_id().as_ref());
@@ -889,13 +1065,18 @@ pub async fn passkey_login_finish(
             .map_err(|err| AppError::Database(err.to_string()))?;
     }
     let record_user_id = record.user_id;
+    let aaguid = record
+        .aaguid
+        .as_deref()
+        .and_then(|value| Uuid::parse_str(value).ok());
 
     let device = devices::ActiveModel {
         id: Set(Uuid::new_v4()),
         user_id: Set(record_user_id),
         device_type: Set("passkey".to_string()),
-        label: Set("Passkey".to_string()),
+        label: Set(default_passkey_label(aaguid.as_ref())),
         credential_id: Set(Some(cred_id_b64.clone())),
+        aaguid: Set(record.aaguid.clone()),
         created_at: Set(Utc::now()),
         last_used_at: Set(Some(Utc::now())),
     };
@@ -938,12 +1119,14 @@ pub async fn passkey_login_finish(
 pub async fn auth_config(
     State(state): State<AppState>,
 ) -> Result<Json<AuthConfigResponse>, AppError> {
-    let reset_delivery = match state.config.reset_delivery {
+    let reset_delivery = match state.reset_delivery() {
         crate::config::ResetDelivery::Email => "email",
         crate::config::ResetDelivery::Code => "code",
     };
     Ok(Json(AuthConfigResponse {
         reset_delivery: reset_delivery.to_string(),
+        sso_provider: state.config.sso.as_ref().map(|sso| sso.provider),
+        ldap_enabled: state.config.ldap.is_some(),
     }))
 }
 
@@ -970,12 +1153,170 @@ pub async fn password_login(
         .as_ref()
         .ok_or_else(|| AppError::auth("password not set"))?;
     if !verify_password(&payload.password, hash)? {
+        record_security_event(
+            &state,
+            SecurityEventInput::new(EVENT_LOGIN_FAILED, SEVERITY_MEDIUM)
+                .user(Some(user.id), &user.username)
+                .detail("password"),
+        )
+        .await;
         return Err(AppError::auth("invalid password"));
     }
     let (jar, user_id) = create_session_cookie(&state, jar, user.id).await?;
     Ok((jar, Json(serde_json::json!({"user_id": user_id}))))
 }
 
+/// LDAP 登录（仅教师与审核人员），首次登录时创建本地账号。
+pub async fn ldap_login(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<PasswordLoginRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let config = state
+        .config
+        .ldap
+        .as_ref()
+        .ok_or_else(|| AppError::not_found("ldap not configured"))?;
+    let username = payload.username.trim();
+    if username.is_empty() {
+        return Err(AppError::validation("username is required"));
+    }
+    let identity = match ldap::authenticate(config, username, &payload.password).await {
+        Ok(identity) => identity,
+        Err(err) => {
+            if matches!(err, AppError::Auth(_)) {
+                record_security_event(
+                    &state,
+                    SecurityEventInput::new(EVENT_LOGIN_FAILED, SEVERITY_MEDIUM)
+                        .user(None, username)
+                        .detail("ldap"),
+                )
+                .await;
+            }
+            return Err(err);
+        }
+    };
+    let role = ldap::role_for_groups(config, &identity.groups)
+        .ok_or_else(|| AppError::auth("ldap user not authorized"))?;
+    let user = ldap::sync_local_user(&state.db, username, &identity, role).await?;
+    let (jar, user_id) = create_session_cookie(&state, jar, user.id).await?;
+    Ok((jar, Json(serde_json::json!({"user_id": user_id}))))
+}
+
+/// 统一身份认证登录参数。
+#[derive(Debug, Deserialize)]
+pub struct SsoLoginQuery {
+    /// 登录后跳转的前端路径（仅允许站内路径）。
+    pub next: Option<String>,
+}
+
+/// 跳转到统一身份认证登录页。
+pub async fn sso_login(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<SsoLoginQuery>,
+) -> Result<(CookieJar, Redirect), AppError> {
+    let config = state
+        .config
+        .sso
+        .as_ref()
+        .ok_or_else(|| AppError::not_found("sso not configured"))?;
+    let next = query.next.filter(|next| is_local_path(next));
+    let sso_state = generate_token();
+    let url = sso::login_url(config, &sso_state)?;
+    state
+        .auth_flows
+        .insert_sso_login(
+            &sso_state,
+            &SsoLoginSession {
+                next,
+                created_at: OffsetDateTime::now_utc(),
+            },
+        )
+        .await?;
+    // 将 state 绑定到发起登录的浏览器；身份提供方跳回属于跨站顶级导航，需 SameSite=Lax 才会携带。
+    let cookie = Cookie::build((sso_state_cookie_name(&state), sso_state))
+        .http_only(true)
+        .secure(!state.config.allow_http)
+        .same_site(SameSite::Lax)
+        .path("/")
+        .max_age(TimeDuration::seconds(CHALLENGE_TTL_SECONDS))
+        .build();
+    Ok((jar.add(cookie), Redirect::to(url.as_str())))
+}
+
+/// 统一身份认证回调：校验票据，映射本地账号并创建会话后跳回前端。
+pub async fn sso_callback(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<SsoCallbackQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let config = state
+        .config
+        .sso
+        .as_ref()
+        .ok_or_else(|| AppError::not_found("sso not configured"))?;
+    let sso_state = query
+        .state
+        .clone()
+        .ok_or_else(|| AppError::bad_request("missing state"))?;
+    let cookie_name = sso_state_cookie_name(&state);
+    let browser_state = jar.get(&cookie_name).map(|cookie| cookie.value().to_string());
+    let expired = Cookie::build((cookie_name, ""))
+        .http_only(true)
+        .secure(!state.config.allow_http)
+        .same_site(SameSite::Lax)
+        .path("/")
+        .expires(OffsetDateTime::now_utc() - TimeDuration::days(1))
+        .build();
+    let jar = jar.add(expired);
+    // 回调须来自发起登录的浏览器，否则可能是他人发起的登录（登录 CSRF）。
+    if browser_state.as_deref() != Some(sso_state.as_str()) {
+        return Err(AppError::bad_request("sso state does not match this browser"));
+    }
+    let login = state
+        .auth_flows
+        .take_sso_login(&sso_state)
+        .await?
+        .ok_or_else(|| AppError::bad_request("invalid or expired state"))?;
+    let username = match sso::resolve_username(config, &sso_state, &query).await {
+        Ok(username) => username,
+        Err(err) => {
+            record_security_event(
+                &state,
+                SecurityEventInput::new(EVENT_LOGIN_FAILED, SEVERITY_MEDIUM).detail("sso"),
+            )
+            .await;
+            return Err(err);
+        }
+    };
+    let user = sso::resolve_local_user(
+        &state.db,
+        &username,
+        config.auto_provision_students,
+        config.allow_staff,
+    )
+    .await?;
+    let (jar, _) = create_session_cookie(&state, jar, user.id).await?;
+    let frontend = state.config.base_url.as_ref().unwrap_or(&state.config.rp_origin);
+    let target = format!(
+        "{}{}",
+        frontend.as_str().trim_end_matches('/'),
+        login.next.as_deref().unwrap_or("/")
+    );
+    Ok((jar, Redirect::to(&target)))
+}
+
+/// 统一身份认证 state Cookie 的名称（随会话 Cookie 名称区分部署）。
+fn sso_state_cookie_name(state: &AppState) -> String {
+    format!("{}_sso_state", state.config.session_cookie_name)
+}
+
+/// 仅允许站内相对路径，避免开放重定向。
+fn is_local_path(value: &str) -> bool {
+    value.starts_with('/') && !value.starts_with("//") && !value.contains('\\')
+}
+
 /// 开始 TOTP 绑定的请求体。
 #[derive(Debug, Deserialize)]
 pub struct TotpEnrollStartRequest {
@@ -1007,7 +1348,7 @@ pub async fn totp_enroll_start(
     };
 
     let (secret, url) = generate_totp("Labor Hours Platform", &user.username)?;
-    let encrypted = encrypt_secret(&secret, &state.config.auth_secret_key)?;
+    let encrypted = encrypt_secret(&secret, &state.config.secret_keyring())?;
 
     let now = Utc::now();
     let enrollment_id = Uuid::new_v4();
@@ -1019,6 +1360,9 @@ pub async fn totp_enroll_start(
         enabled: Set(false),
         created_at: Set(now),
         verified_at: Set(None),
+        last_used_step: Set(None),
+        failed_attempts: Set(0),
+        locked_until: Set(None),
     };
     totp_secrets::Entity::insert(totp_model)
         .exec_without_returning(&state.db)
@@ -1032,6 +1376,7 @@ pub async fn totp_enroll_start(
             device_type: Set("totp".to_string()),
             label: Set(label),
             credential_id: Set(None),
+            aaguid: Set(None),
             created_at: Set(now),
             last_used_at: Set(None),
         };
@@ -1081,10 +1426,7 @@ pub async fn totp_enroll_finish(
         return Err(AppError::auth("forbidden"));
     }
 
-    let secret = decrypt_secret(&record.secret_enc, &state.config.auth_secret_key)?;
-    if !verify_totp(&secret, &payload.code)? {
-        return Err(AppError::auth("invalid TOTP"));
-    }
+    check_totp(&state, &record, &payload.code).await?;
 
     let mut active: totp_secrets::ActiveModel = record.into();
     active.enabled = Set(true);
@@ -1130,15 +1472,71 @@ pub async fn totp_verify(
         .map_err(|err| AppError::Database(err.to_string()))?
         .ok_or_else(|| AppError::bad_request("no TOTP enrolled"))?;
 
-    let raw = decrypt_secret(&secret.secret_enc, &state.config.auth_secret_key)?;
-    if !verify_totp(&raw, &payload.code)? {
-        return Err(AppError::auth("invalid TOTP"));
+    if let Err(err) = check_totp(&state, &secret, &payload.code).await {
+        record_security_event(
+            &state,
+            SecurityEventInput::new(EVENT_LOGIN_FAILED, SEVERITY_MEDIUM)
+                .user(Some(user.id), &user.username)
+                .detail("totp"),
+        )
+        .await;
+        return Err(err);
     }
 
     let (jar, user_id) = create_session_cookie(&state, jar, user.id).await?;
     Ok((jar, Json(serde_json::json!({"user_id": user_id}))))
 }
 
+/// 校验 TOTP 验证码：锁定期内直接拒绝；失败累计次数并按配置指数退避；
+/// 通过时记录时间步，同一验证码在有效期内不能再次使用。
+async fn check_totp(state: &AppState, record: &totp_secrets::Model, code: &str) -> Result<(), AppError> {
+    let now = Utc::now();
+    if record.locked_until.is_some_and(|until| until > now) {
+        return Err(AppError::too_many_requests("too many failed TOTP attempts, retry later"));
+    }
+    let secret = decrypt_secret(&record.secret_enc, &state.config.secret_keyring())?;
+    let step = verify_totp(&secret, code, state.config.totp.skew_steps, now.timestamp().max(0) as u64)?;
+    if let Some(step) = step {
+        // 条件更新：只接受晚于上次通过的时间步，并发重放同一验证码时只有一个请求成功。
+        let step = step as i64;
+        let result = TotpSecret::update_many()
+            .col_expr(totp_secrets::Column::LastUsedStep, Expr::value(step))
+            .col_expr(totp_secrets::Column::FailedAttempts, Expr::value(0))
+            .col_expr(totp_secrets::Column::LockedUntil, Expr::value(Option::<DateTimeUtc>::None))
+            .filter(totp_secrets::Column::Id.eq(record.id))
+            .filter(
+                Condition::any()
+                    .add(totp_secrets::Column::LastUsedStep.is_null())
+                    .add(totp_secrets::Column::LastUsedStep.lt(step)),
+            )
+            .exec(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        if result.rows_affected > 0 {
+            return Ok(());
+        }
+    }
+
+    let failures = u32::try_from(record.failed_attempts).unwrap_or(0).saturating_add(1);
+    let locked_until = totp_backoff_seconds(&state.config.totp, failures)
+        .map(|seconds| now + ChronoDuration::seconds(seconds as i64));
+    TotpSecret::update_many()
+        .col_expr(
+            totp_secrets::Column::FailedAttempts,
+            Expr::col(totp_secrets::Column::FailedAttempts).add(1),
+        )
+        .col_expr(totp_secrets::Column::LockedUntil, Expr::value(locked_until))
+        .filter(totp_secrets::Column::Id.eq(record.id))
+        .exec(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if step.is_some() {
+        Err(AppError::auth("TOTP code already used"))
+    } else {
+        Err(AppError::auth("invalid TOTP"))
+    }
+}
+
 /// 恢复码验证的请求体。
 #[derive(Debug, Deserialize)]
 pub struct RecoveryVerifyRequest {
@@ -1176,15 +1574,116 @@ pub async fn recovery_verify(
                 .update(&state.db)
                 .await
                 .map_err(|err| AppError::Database(err.to_string()))?;
+            record_security_event(
+                &state,
+                SecurityEventInput::new(EVENT_RECOVERY_CODE_USED, SEVERITY_HIGH)
+                    .user(Some(user.id), &user.username),
+            )
+            .await;
 
             let (jar, user_id) = create_session_cookie(&state, jar, user.id).await?;
             return Ok((jar, Json(serde_json::json!({"user_id": user_id}))));
         }
     }
 
+    record_security_event(
+        &state,
+        SecurityEventInput::new(EVENT_LOGIN_FAILED, SEVERITY_MEDIUM)
+            .user(Some(user.id), &user.username)
+            .detail("recovery_code"),
+    )
+    .await;
     Err(AppError::auth("invalid recovery code"))
 }
 
+/// 恢复码生成响应（明文仅返回一次）。
+#[derive(Debug, Serialize)]
+pub struct RecoveryGenerateResponse {
+    /// 新生成的恢复码明文。
+    pub codes: Vec<String>,
+}
+
+/// 为当前用户生成新的恢复码，旧恢复码全部作废。
+pub async fn recovery_generate(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    headers: HeaderMap,
+) -> Result<Json<RecoveryGenerateResponse>, AppError> {
+    let user = require_session(&state, &jar).await?;
+    let codes = regenerate_recovery_codes(&state, &headers, &user, "json").await?;
+    Ok(Json(RecoveryGenerateResponse { codes }))
+}
+
+/// 为当前用户生成新的恢复码，并以可打印 PDF 返回（含用户名与生成时间），旧恢复码全部作废。
+pub async fn recovery_generate_pdf(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let user = require_session(&state, &jar).await?;
+    let codes = regenerate_recovery_codes(&state, &headers, &user, "pdf").await?;
+    let generated_at = Utc::now();
+    let bytes = render_recovery_codes_pdf(state.locale_for(&user), &user.username, generated_at, &codes)?;
+    let mut response = file_response(
+        format!("recovery-codes-{}.pdf", generated_at.format("%Y%m%d")),
+        "application/pdf",
+        bytes,
+    );
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    Ok(response)
+}
+
+/// 校验二次验证后替换当前用户的恢复码，返回明文。
+async fn regenerate_recovery_codes(
+    state: &AppState,
+    headers: &HeaderMap,
+    user: &users::Model,
+    format: &str,
+) -> Result<Vec<String>, AppError> {
+    require_reauth(state, headers, user.id).await?;
+    if !has_second_factor(state, user.id).await? {
+        return Err(AppError::bad_request("enroll TOTP or passkey first"));
+    }
+
+    let codes = generate_recovery_codes(RECOVERY_CODE_COUNT)?;
+    let now = Utc::now();
+    let txn = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    RecoveryCode::delete_many()
+        .filter(recovery_codes::Column::UserId.eq(user.id))
+        .exec(&txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let models = codes.iter().map(|code| recovery_codes::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        code_hash: Set(code.hash.clone()),
+        used_at: Set(None),
+        created_at: Set(now),
+    });
+    RecoveryCode::insert_many(models)
+        .exec_without_returning(&txn)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    txn.commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    record_security_event(
+        state,
+        SecurityEventInput::new(EVENT_RECOVERY_CODES_GENERATED, SEVERITY_MEDIUM)
+            .user(Some(user.id), &user.username)
+            .detail(format!("count={RECOVERY_CODE_COUNT} format={format}")),
+    )
+    .await;
+    Ok(codes.into_iter().map(|code| code.plain).collect())
+}
+
 /// 绑定学生邮箱（仅学生本人）。
 pub async fn bind_email(
     State(state): State<AppState>,
@@ -1245,7 +1744,7 @@ pub async fn password_reset_request(
     State(state): State<AppState>,
     Json(payload): Json<PasswordResetRequest>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    if matches!(state.config.reset_delivery, crate::config::ResetDelivery::Code) {
+    if matches!(state.reset_delivery(), crate::config::ResetDelivery::Code) {
         return Err(AppError::bad_request("reset delivery set to code"));
     }
     let user = User::find()
@@ -1258,10 +1757,8 @@ pub async fn password_reset_request(
         return Err(AppError::auth("forbidden"));
     }
     let email = user.email.clone().ok_or_else(|| AppError::bad_request("email not bound"))?;
-    let mail_config = state
-        .config
-        .mail
-        .as_ref()
+    state
+        .mail_config()
         .ok_or_else(|| AppError::config("mail config required"))?;
     let base_url = state
         .config
@@ -1289,12 +1786,16 @@ pub async fn password_reset_request(
         .map_err(|err| AppError::Database(err.to_string()))?;
 
     let link = format!("{}/password-reset?token={}", base_url, token);
-    let body = format!(
-        "您好，\n\n请使用以下链接重置您的密码：\n{}\n\n该链接 {} 小时后失效。",
-        link,
-        PASSWORD_RESET_TTL_MINUTES / 60
-    );
-    send_mail(mail_config, &email, "密码重置", &body).await?;
+    let locale = state.locale_for(&user);
+    let hours = (PASSWORD_RESET_TTL_MINUTES / 60).to_string();
+    let (subject, body) = render_mail(
+        &state.db,
+        &PASSWORD_RESET_MAIL,
+        locale,
+        &[("name", &user.display_name), ("link", &link), ("hours", &hours)],
+    )
+    .await?;
+    enqueue_mail(&state, &email, &subject, &body).await?;
 
     Ok(Json(serde_json::json!({"status": "ok"})))
 }
@@ -1436,6 +1937,9 @@ pub async fn invite_accept(
         password_updated_at: Set(None),
         must_change_password: Set(false),
         is_active: Set(true),
+        department: Set(invite.department.clone()),
+        notify_review_email: Set(true),
+        locale: Set(None),
         created_at: Set(now),
         updated_at: Set(now),
     };
@@ -1539,6 +2043,7 @@ pub async fn reset_consume(
         .exec(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    state.session_cache.invalidate_users(&[record.user_id]).await;
 
     let mut active: auth_resets::ActiveModel = record.into();
     active.used_at = Set(Some(Utc::now()));
@@ -1557,18 +2062,50 @@ pub async fn reset_consume(
     ))
 }
 
+/// 恢复码数量统计。
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodeCounts {
+    /// 当前有效批次的恢复码总数。
+    pub total: u64,
+    /// 尚未使用的恢复码数量。
+    pub unused: u64,
+}
+
+/// 设备列表响应。
+#[derive(Debug, Serialize)]
+pub struct DeviceListResponse {
+    /// 已绑定设备。
+    pub devices: Vec<devices::Model>,
+    /// 恢复码统计。
+    pub recovery_codes: RecoveryCodeCounts,
+}
+
 /// 列出当前用户的设备。
 pub async fn list_devices(
     State(state): State<AppState>,
     jar: CookieJar,
-) -> Result<Json<Vec<devices::Model>>, AppError> {
+) -> Result<Json<DeviceListResponse>, AppError> {
     let user = require_session(&state, &jar).await?;
     let devices = Device::find()
         .filter(devices::Column::UserId.eq(user.id))
         .all(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
-    Ok(Json(devices))
+    let total = RecoveryCode::find()
+        .filter(recovery_codes::Column::UserId.eq(user.id))
+        .count(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let unused = RecoveryCode::find()
+        .filter(recovery_codes::Column::UserId.eq(user.id))
+        .filter(recovery_codes::Column::UsedAt.is_null())
+        .count(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(DeviceListResponse {
+        devices,
+        recovery_codes: RecoveryCodeCounts { total, unused },
+    }))
 }
 
 /// 删除当前用户的设备。
@@ -1612,9 +2149,51 @@ pub async fn delete_device(
         .exec(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    record_security_event(
+        &state,
+        SecurityEventInput::new(EVENT_DEVICE_REMOVED, SEVERITY_MEDIUM)
+            .user(Some(user.id), &user.username)
+            .detail(format!("{}:{}", device.device_type, device.label)),
+    )
+    .await;
     Ok(Json(serde_json::json!({"status": "ok"})))
 }
 
+/// 设备重命名请求。
+#[derive(Debug, Deserialize)]
+pub struct RenameDeviceRequest {
+    /// 新的设备标签。
+    pub label: String,
+}
+
+/// 重命名当前用户的设备。
+pub async fn rename_device(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(device_id): Path<Uuid>,
+    Json(payload): Json<RenameDeviceRequest>,
+) -> Result<Json<devices::Model>, AppError> {
+    let user = require_session(&state, &jar).await?;
+    let label = normalize_device_label(&payload.label)
+        .ok_or_else(|| AppError::validation("invalid device label"))?;
+
+    let device = Device::find()
+        .filter(devices::Column::UserId.eq(user.id))
+        .filter(devices::Column::Id.eq(device_id))
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("device not found"))?;
+
+    let mut active: devices::ActiveModel = device.into();
+    active.label = Set(label);
+    let updated = active
+        .update(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(updated))
+}
+
 async fn issue_reauth_token(
     state: &AppState,
     user_id: Uuid,
@@ -1624,18 +2203,14 @@ async fn issue_reauth_token(
         user_id,
         created_at: OffsetDateTime::now_utc(),
     };
-    state
-        .reauth_state
-        .lock()
-        .await
-        .insert(token.clone(), session);
+    state.auth_flows.insert_reauth_token(&token, &session).await?;
     Ok(Json(ReauthTokenResponse {
         token,
         expires_in: REAUTH_TTL_SECONDS,
     }))
 }
 
-async fn require_reauth(
+pub(crate) async fn require_reauth(
     state: &AppState,
     headers: &HeaderMap,
     user_id: Uuid,
@@ -1650,10 +2225,9 @@ async fn require_reauth(
         .ok_or_else(|| AppError::auth("reauth required"))?;
 
     let session = state
-        .reauth_state
-        .lock()
-        .await
-        .take(token)
+        .auth_flows
+        .take_reauth_token(token)
+        .await?
         .ok_or_else(|| AppError::auth("invalid reauth token"))?;
     if session.user_id != user_id {
         return Err(AppError::auth("invalid reauth token"));
@@ -1672,7 +2246,11 @@ async fn needs_reauth(state: &AppState, user_id: Uuid) -> Result<bool, AppError>
     if user.allow_password_login && user.password_hash.is_some() {
         return Ok(true);
     }
+    has_second_factor(state, user_id).await
+}
 
+/// 用户是否已绑定 Passkey 或已启用 TOTP。
+async fn has_second_factor(state: &AppState, user_id: Uuid) -> Result<bool, AppError> {
     let passkey_count = Passkey::find()
         .filter(passkeys::Column::UserId.eq(user_id))
         .count(&state.db)
@@ -1758,30 +2336,5 @@ async fn create_session_cookie(
 }
 
 async fn require_session(state: &AppState, jar: &CookieJar) -> Result<users::Model, AppError> {
-    let token = jar
-        .get(&state.config.session_cookie_name)
-        .ok_or_else(|| AppError::auth("missing session"))?
-        .value()
-        .to_string();
-    let token_hash = hash_session_token(&token);
-    let now = Utc::now();
-
-    let session = Session::find()
-        .filter(sessions::Column::TokenHash.eq(token_hash))
-        .one(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-        .ok_or_else(|| AppError::auth("invalid session"))?;
-
-    if session.expires_at < now {
-        return Err(AppError::auth("session expired"));
-    }
-
-    let user = User::find_by_id(session.user_id)
-        .one(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-        .ok_or_else(|| AppError::auth("user not found"))?;
-
-    Ok(user)
+    require_session_user(state, jar).await
 }
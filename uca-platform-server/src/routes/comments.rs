@@ -0,0 +1,255 @@
+//! 记录评论接口：审核人员与学生围绕单条记录沟通（如要求补充附件），不改变审核状态。
+
+use std::collections::{BTreeSet, HashMap};
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::Utc;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    access::{ensure_student_in_scope, require_session_user},
+    entities::{
+        record_comments, students, users, ContestRecord, RecordComment, Student, User, VolunteerRecord,
+    },
+    error::AppError,
+    notify::{push_notifications, NewNotification, KIND_RECORD_COMMENT},
+    state::AppState,
+};
+
+/// 单条评论的最大字符数。
+pub const MAX_COMMENT_CHARS: usize = 2000;
+
+/// 发表评论请求。
+#[derive(Debug, Deserialize)]
+pub struct CreateCommentRequest {
+    /// 评论内容。
+    pub body: String,
+}
+
+/// 评论响应。
+#[derive(Debug, Serialize)]
+pub struct RecordCommentResponse {
+    /// 评论 ID。
+    pub id: Uuid,
+    /// 记录类型（contest/volunteer）。
+    pub record_type: String,
+    /// 记录 ID。
+    pub record_id: Uuid,
+    /// 作者 ID。
+    pub author_id: Uuid,
+    /// 作者显示名称。
+    pub author_name: Option<String>,
+    /// 作者角色。
+    pub author_role: Option<String>,
+    /// 评论内容。
+    pub body: String,
+    /// 发表时间。
+    pub created_at: String,
+}
+
+/// 评论所属记录的概要。
+struct CommentTarget {
+    record_type: &'static str,
+    title: String,
+    student: students::Model,
+    assigned_reviewer_id: Option<Uuid>,
+}
+
+/// 获取记录的评论（按时间正序；学生本人、审核人员与管理员可见）。
+pub async fn list_record_comments(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path((record_type, record_id)): Path<(String, Uuid)>,
+) -> Result<Json<Vec<RecordCommentResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    let target = load_comment_target(&state, &record_type, record_id).await?;
+    ensure_comment_access(&user, &target)?;
+
+    let comments = RecordComment::find()
+        .filter(record_comments::Column::RecordType.eq(target.record_type))
+        .filter(record_comments::Column::RecordId.eq(record_id))
+        .order_by_asc(record_comments::Column::CreatedAt)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let author_ids: Vec<Uuid> = comments.iter().map(|comment| comment.author_id).collect();
+    let authors = load_authors(&state, author_ids).await?;
+    Ok(Json(
+        comments
+            .into_iter()
+            .map(|comment| {
+                let author = authors.get(&comment.author_id);
+                comment_response(comment, author)
+            })
+            .collect(),
+    ))
+}
+
+/// 在记录下发表评论，并通知对方。
+pub async fn create_record_comment(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path((record_type, record_id)): Path<(String, Uuid)>,
+    Json(payload): Json<CreateCommentRequest>,
+) -> Result<Json<RecordCommentResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    let target = load_comment_target(&state, &record_type, record_id).await?;
+    ensure_comment_access(&user, &target)?;
+
+    let body = payload.body.trim();
+    if body.is_empty() || body.chars().count() > MAX_COMMENT_CHARS {
+        return Err(AppError::validation(&format!(
+            "comment body must be 1-{MAX_COMMENT_CHARS} characters"
+        )));
+    }
+
+    let model = record_comments::Model {
+        id: Uuid::new_v4(),
+        record_type: target.record_type.to_string(),
+        record_id,
+        author_id: user.id,
+        body: body.to_string(),
+        created_at: Utc::now(),
+    };
+    let active: record_comments::ActiveModel = model.clone().into();
+    RecordComment::insert(active)
+        .exec_without_returning(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let recipients = comment_recipients(&state, &user, &target, record_id).await?;
+    let title = format!("「{}」有新的评论", target.title);
+    let preview: String = body.chars().take(100).collect();
+    push_notifications(
+        &state,
+        recipients
+            .into_iter()
+            .map(|recipient| {
+                NewNotification::new(
+                    recipient,
+                    KIND_RECORD_COMMENT,
+                    title.clone(),
+                    format!("{}：{preview}", user.display_name),
+                )
+                .target(target.record_type, record_id)
+            })
+            .collect(),
+    )
+    .await;
+
+    Ok(Json(comment_response(model, Some(&user))))
+}
+
+async fn load_comment_target(
+    state: &AppState,
+    record_type: &str,
+    record_id: Uuid,
+) -> Result<CommentTarget, AppError> {
+    let (record_type, title, student_id, assigned_reviewer_id) = match record_type {
+        "contest" => {
+            let record = ContestRecord::find_by_id(record_id)
+                .one(&state.db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?
+                .filter(|record| !record.is_deleted)
+                .ok_or_else(|| AppError::not_found("record not found"))?;
+            ("contest", record.contest_name, record.student_id, record.assigned_reviewer_id)
+        }
+        "volunteer" => {
+            let record = VolunteerRecord::find_by_id(record_id)
+                .one(&state.db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?
+                .filter(|record| !record.is_deleted)
+                .ok_or_else(|| AppError::not_found("record not found"))?;
+            ("volunteer", record.title, record.student_id, record.assigned_reviewer_id)
+        }
+        _ => return Err(AppError::bad_request("invalid record type")),
+    };
+    let student = Student::find_by_id(student_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .filter(|student| !student.is_deleted)
+        .ok_or_else(|| AppError::not_found("student not found"))?;
+    Ok(CommentTarget {
+        record_type,
+        title,
+        student,
+        assigned_reviewer_id,
+    })
+}
+
+fn ensure_comment_access(user: &users::Model, target: &CommentTarget) -> Result<(), AppError> {
+    match user.role.as_str() {
+        "student" if user.username == target.student.student_no => Ok(()),
+        "admin" | "teacher" | "reviewer" => ensure_student_in_scope(user, &target.student),
+        _ => Err(AppError::auth("forbidden")),
+    }
+}
+
+/// 通知对象：审核人员评论时通知学生；学生评论时通知指派的审核人与参与过讨论的审核人员。
+async fn comment_recipients(
+    state: &AppState,
+    author: &users::Model,
+    target: &CommentTarget,
+    record_id: Uuid,
+) -> Result<BTreeSet<Uuid>, AppError> {
+    let mut recipients = BTreeSet::new();
+    if author.role == "student" {
+        recipients.extend(target.assigned_reviewer_id);
+        let participants = RecordComment::find()
+            .filter(record_comments::Column::RecordType.eq(target.record_type))
+            .filter(record_comments::Column::RecordId.eq(record_id))
+            .all(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        recipients.extend(participants.into_iter().map(|comment| comment.author_id));
+    } else {
+        let student_user = User::find()
+            .filter(users::Column::Username.eq(&target.student.student_no))
+            .filter(users::Column::Role.eq("student"))
+            .one(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        recipients.extend(student_user.map(|student_user| student_user.id));
+    }
+    recipients.remove(&author.id);
+    Ok(recipients)
+}
+
+async fn load_authors(
+    state: &AppState,
+    author_ids: Vec<Uuid>,
+) -> Result<HashMap<Uuid, users::Model>, AppError> {
+    if author_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    Ok(User::find()
+        .filter(users::Column::Id.is_in(author_ids))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|user| (user.id, user))
+        .collect())
+}
+
+fn comment_response(comment: record_comments::Model, author: Option<&users::Model>) -> RecordCommentResponse {
+    RecordCommentResponse {
+        id: comment.id,
+        record_type: comment.record_type,
+        record_id: comment.record_id,
+        author_id: comment.author_id,
+        author_name: author.map(|author| author.display_name.clone()),
+        author_role: author.map(|author| author.role.clone()),
+        body: comment.body,
+        created_at: comment.created_at.to_rfc3339(),
+    }
+}
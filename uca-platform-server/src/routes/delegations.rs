@@ -0,0 +1,280 @@
+//! 审核委托接口：教职工请假前将指定阶段的审核委托给他人，到期或撤销后自动失效。
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    access::require_session_user,
+    audit::record_audit,
+    delegations::{is_active, validate_range, DELEGATE_ROLES},
+    entities::{review_delegations, users, ReviewDelegation, User},
+    error::AppError,
+    services::review::{role_can_review, REVIEW_STAGE_FINAL, REVIEW_STAGE_FIRST},
+    state::AppState,
+};
+
+/// 创建审核委托请求。
+#[derive(Debug, Deserialize)]
+pub struct CreateDelegationRequest {
+    /// 受托人用户 ID。
+    pub delegate_id: Uuid,
+    /// 委托的审核阶段：first/final。
+    pub stage: String,
+    /// 开始时间，默认立即生效。
+    pub starts_at: Option<DateTime<Utc>>,
+    /// 截止时间。
+    pub ends_at: DateTime<Utc>,
+    /// 委托人用户 ID，仅管理员可代他人创建；默认为当前用户。
+    pub delegator_id: Option<Uuid>,
+}
+
+/// 审核委托响应。
+#[derive(Debug, Serialize)]
+pub struct DelegationResponse {
+    /// 委托 ID。
+    pub id: Uuid,
+    /// 委托人用户 ID。
+    pub delegator_id: Uuid,
+    /// 委托人显示名。
+    pub delegator_name: Option<String>,
+    /// 受托人用户 ID。
+    pub delegate_id: Uuid,
+    /// 受托人显示名。
+    pub delegate_name: Option<String>,
+    /// 审核阶段。
+    pub stage: String,
+    /// 开始时间。
+    pub starts_at: String,
+    /// 截止时间。
+    pub ends_at: String,
+    /// 撤销时间。
+    pub revoked_at: Option<String>,
+    /// 当前是否生效。
+    pub active: bool,
+    /// 创建时间。
+    pub created_at: String,
+}
+
+/// 列出审核委托：管理员可见全部，其他用户可见自己委托或受托的记录。
+pub async fn list_delegations(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<DelegationResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    if !DELEGATE_ROLES.contains(&user.role.as_str()) {
+        return Err(AppError::auth("forbidden"));
+    }
+
+    let mut query = ReviewDelegation::find().order_by_desc(review_delegations::Column::CreatedAt);
+    if user.role != "admin" {
+        query = query.filter(
+            Condition::any()
+                .add(review_delegations::Column::DelegatorId.eq(user.id))
+                .add(review_delegations::Column::DelegateId.eq(user.id)),
+        );
+    }
+    let items = query
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let mut user_ids: Vec<Uuid> = items
+        .iter()
+        .flat_map(|item| [item.delegator_id, item.delegate_id])
+        .collect();
+    user_ids.sort();
+    user_ids.dedup();
+    let names: HashMap<Uuid, String> = User::find()
+        .filter(users::Column::Id.is_in(user_ids))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|row| (row.id, row.display_name))
+        .collect();
+
+    let now = Utc::now();
+    Ok(Json(
+        items
+            .into_iter()
+            .map(|item| delegation_response(item, &names, now))
+            .collect(),
+    ))
+}
+
+/// 创建审核委托：委托人需具备该阶段的审核角色，受托人需为启用中的教职工账号。
+pub async fn create_delegation(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<CreateDelegationRequest>,
+) -> Result<Json<DelegationResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    let stage = payload.stage.trim().to_string();
+    if stage != REVIEW_STAGE_FIRST && stage != REVIEW_STAGE_FINAL {
+        return Err(AppError::validation("stage must be first or final"));
+    }
+
+    let delegator = match payload.delegator_id {
+        Some(delegator_id) if delegator_id != user.id => {
+            if user.role != "admin" {
+                return Err(AppError::auth("forbidden"));
+            }
+            User::find_by_id(delegator_id)
+                .one(&state.db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?
+                .ok_or_else(|| AppError::not_found("delegator not found"))?
+        }
+        _ => user.clone(),
+    };
+    if !role_can_review(&delegator.role, &stage) {
+        return Err(AppError::validation("delegator cannot review this stage"));
+    }
+    if payload.delegate_id == delegator.id {
+        return Err(AppError::validation("cannot delegate to yourself"));
+    }
+    let delegate = User::find_by_id(payload.delegate_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("delegate not found"))?;
+    if !delegate.is_active || !DELEGATE_ROLES.contains(&delegate.role.as_str()) {
+        return Err(AppError::validation("delegate must be an active staff account"));
+    }
+
+    let now = Utc::now();
+    let starts_at = payload.starts_at.unwrap_or(now);
+    validate_range(starts_at, payload.ends_at, now)?;
+
+    let model = review_delegations::Model {
+        id: Uuid::new_v4(),
+        delegator_id: delegator.id,
+        delegate_id: delegate.id,
+        stage,
+        starts_at,
+        ends_at: payload.ends_at,
+        revoked_at: None,
+        created_by: Some(user.id),
+        created_at: now,
+    };
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let active: review_delegations::ActiveModel = model.clone().into();
+    ReviewDelegation::insert(active)
+        .exec_without_returning(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "review_delegation_create",
+        "review_delegation",
+        Some(&model.id.to_string()),
+        Some(audit_detail(&model)),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let names = HashMap::from([
+        (delegator.id, delegator.display_name),
+        (delegate.id, delegate.display_name),
+    ]);
+    Ok(Json(delegation_response(model, &names, now)))
+}
+
+/// 撤销审核委托（委托人或管理员），撤销后立即失效。
+pub async fn revoke_delegation(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(delegation_id): Path<Uuid>,
+) -> Result<Json<DelegationResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let existing = ReviewDelegation::find_by_id(delegation_id)
+        .one(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("delegation not found"))?;
+    if user.role != "admin" && existing.delegator_id != user.id {
+        return Err(AppError::auth("forbidden"));
+    }
+
+    let now = Utc::now();
+    let model = if existing.revoked_at.is_some() {
+        existing
+    } else {
+        let mut active: review_delegations::ActiveModel = existing.into();
+        active.revoked_at = Set(Some(now));
+        let model = active
+            .update(&transaction)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        record_audit(
+            &transaction,
+            Some(user.id),
+            "review_delegation_revoke",
+            "review_delegation",
+            Some(&model.id.to_string()),
+            Some(audit_detail(&model)),
+        )
+        .await?;
+        model
+    };
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(delegation_response(model, &HashMap::new(), now)))
+}
+
+fn audit_detail(model: &review_delegations::Model) -> serde_json::Value {
+    serde_json::json!({
+        "delegator_id": model.delegator_id,
+        "delegate_id": model.delegate_id,
+        "stage": model.stage,
+        "starts_at": model.starts_at.to_rfc3339(),
+        "ends_at": model.ends_at.to_rfc3339(),
+        "revoked_at": model.revoked_at.map(|value| value.to_rfc3339()),
+    })
+}
+
+fn delegation_response(
+    model: review_delegations::Model,
+    names: &HashMap<Uuid, String>,
+    now: DateTime<Utc>,
+) -> DelegationResponse {
+    DelegationResponse {
+        active: is_active(&model, now),
+        id: model.id,
+        delegator_name: names.get(&model.delegator_id).cloned(),
+        delegator_id: model.delegator_id,
+        delegate_name: names.get(&model.delegate_id).cloned(),
+        delegate_id: model.delegate_id,
+        stage: model.stage,
+        starts_at: model.starts_at.to_rfc3339(),
+        ends_at: model.ends_at.to_rfc3339(),
+        revoked_at: model.revoked_at.map(|value| value.to_rfc3339()),
+        created_at: model.created_at.to_rfc3339(),
+    }
+}
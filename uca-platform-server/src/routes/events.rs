@@ -0,0 +1,48 @@
+//! 审核队列实时事件推送（SSE）。
+
+use std::convert::Infallible;
+
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use axum_extra::extract::cookie::CookieJar;
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    Stream, StreamExt,
+};
+
+use crate::{
+    access::{department_scope, require_session_user},
+    error::AppError,
+    events::QueueEvent,
+    state::AppState,
+};
+
+/// 订阅审核队列事件（管理员/教师/审核人员，受院系数据范围限制）。
+pub async fn event_stream(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    if !matches!(user.role.as_str(), "admin" | "teacher" | "reviewer") {
+        return Err(AppError::auth("forbidden"));
+    }
+    let scope = department_scope(&user).map(|value| value.to_string());
+    let stream = BroadcastStream::new(state.events.subscribe()).filter_map(move |item| match item {
+        Ok(event) if event.visible_to(scope.as_deref()) => Some(Ok(to_sse_event(&event))),
+        Ok(_) => None,
+        // 客户端处理过慢丢失了事件，提示其重新拉取列表。
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+            Some(Ok(Event::default().event("lagged").data(skipped.to_string())))
+        }
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+fn to_sse_event(event: &QueueEvent) -> Event {
+    Event::default()
+        .event(event.kind)
+        .json_data(event)
+        .unwrap_or_else(|_| Event::default().event(event.kind))
+}
@@ -0,0 +1,226 @@
+//! 导出列配置接口：管理员配置各 Excel 导出的列、顺序、标题与数字格式。
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, EntityTrait, Set, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    access::{require_role, require_session_user},
+    audit::record_audit,
+    entities::{export_profiles, ExportProfile},
+    error::AppError,
+    export_profiles::{
+        export_profile_def, find_export_profile, parse_export_columns, ExportColumn, ExportProfileDef,
+        EXPORT_PROFILES,
+    },
+    i18n::Locale,
+    state::AppState,
+};
+
+/// 导出列配置更新请求。
+#[derive(Debug, Deserialize)]
+pub struct ExportProfileRequest {
+    /// 导出列，数组顺序即列序。
+    pub columns: Vec<ExportColumn>,
+}
+
+/// 可选列说明。
+#[derive(Debug, Serialize)]
+pub struct ExportColumnOption {
+    /// 列 key。
+    pub field_key: String,
+    /// 内置标题（按当前用户语言）。
+    pub label: String,
+    /// 是否为数值列。
+    pub numeric: bool,
+}
+
+/// 导出列配置响应。
+#[derive(Debug, Serialize)]
+pub struct ExportProfileResponse {
+    /// 导出 key。
+    pub profile_key: String,
+    /// 当前生效的列。
+    pub columns: Vec<ExportColumn>,
+    /// 是否已在后台配置。
+    pub customized: bool,
+    /// 可选列。
+    pub available_columns: Vec<ExportColumnOption>,
+    /// 内置默认列。
+    pub default_columns: Vec<ExportColumn>,
+    /// 最后修改时间（仅配置时）。
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 列出全部可配置导出及生效列（管理员）。
+pub async fn list_export_profiles(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<ExportProfileResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let locale = state.locale_for(&user);
+
+    let profiles = ExportProfile::find()
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let mut items = Vec::new();
+    for def in EXPORT_PROFILES {
+        let row = profiles.iter().find(|row| row.profile_key == def.key);
+        items.push(profile_response(def, locale, row)?);
+    }
+    Ok(Json(items))
+}
+
+/// 获取单个导出的列配置（管理员）。
+pub async fn get_export_profile(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(profile_key): Path<String>,
+) -> Result<Json<ExportProfileResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let def = resolve_profile(&profile_key)?;
+
+    let row = find_export_profile(&state.db, def).await?;
+    Ok(Json(profile_response(def, state.locale_for(&user), row.as_ref())?))
+}
+
+/// 保存导出的列配置（管理员）。
+pub async fn update_export_profile(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(profile_key): Path<String>,
+    Json(payload): Json<ExportProfileRequest>,
+) -> Result<Json<ExportProfileResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let def = resolve_profile(&profile_key)?;
+    let columns = def.normalize_columns(payload.columns)?;
+    let columns_json =
+        serde_json::to_string(&columns).map_err(|_| AppError::internal("serialize export profile failed"))?;
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let existing = find_export_profile(&transaction, def).await?;
+    let now = Utc::now();
+    let model = match existing {
+        Some(existing) => {
+            let mut active: export_profiles::ActiveModel = existing.into();
+            active.columns_json = Set(columns_json);
+            active.updated_by = Set(Some(user.id));
+            active.updated_at = Set(now);
+            active
+                .update(&transaction)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?
+        }
+        None => export_profiles::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            profile_key: Set(def.key.to_string()),
+            columns_json: Set(columns_json),
+            updated_by: Set(Some(user.id)),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?,
+    };
+    let keys: Vec<&str> = columns.iter().map(|column| column.field_key.as_str()).collect();
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "export_profile_update",
+        "export_profile",
+        Some(def.key),
+        Some(serde_json::json!({ "columns": keys })),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(profile_response(def, state.locale_for(&user), Some(&model))?))
+}
+
+/// 删除列配置，恢复为内置默认列（管理员）。
+pub async fn reset_export_profile(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(profile_key): Path<String>,
+) -> Result<Json<ExportProfileResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let def = resolve_profile(&profile_key)?;
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if let Some(existing) = find_export_profile(&transaction, def).await? {
+        ExportProfile::delete_by_id(existing.id)
+            .exec(&transaction)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        record_audit(
+            &transaction,
+            Some(user.id),
+            "export_profile_reset",
+            "export_profile",
+            Some(def.key),
+            None,
+        )
+        .await?;
+    }
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(profile_response(def, state.locale_for(&user), None)?))
+}
+
+fn resolve_profile(profile_key: &str) -> Result<&'static ExportProfileDef, AppError> {
+    export_profile_def(profile_key).ok_or_else(|| AppError::not_found("export profile not found"))
+}
+
+fn profile_response(
+    def: &ExportProfileDef,
+    locale: Locale,
+    row: Option<&export_profiles::Model>,
+) -> Result<ExportProfileResponse, AppError> {
+    let default_columns = def.default_columns();
+    let columns = match row {
+        Some(row) => parse_export_columns(row)?,
+        None => default_columns.clone(),
+    };
+    Ok(ExportProfileResponse {
+        profile_key: def.key.to_string(),
+        columns,
+        customized: row.is_some(),
+        available_columns: def
+            .columns
+            .iter()
+            .map(|column| ExportColumnOption {
+                field_key: column.key.to_string(),
+                label: locale.text(column.label_key).to_string(),
+                numeric: column.numeric,
+            })
+            .collect(),
+        default_columns,
+        updated_at: row.map(|row| row.updated_at),
+    })
+}
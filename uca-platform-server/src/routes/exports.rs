@@ -1,106 +1,196 @@
 //! 导出 PDF / Excel 接口。
 
 use axum::{
-    extract::{Path, State},
+    body::Body,
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, HeaderValue},
     response::{IntoResponse, Response},
     Json,
 };
 use axum_extra::extract::cookie::CookieJar;
 use printpdf::{BuiltinFont, Color, Image, ImageTransform, Line, Mm, PdfDocument, Point, Rgb};
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
-use serde::Deserialize;
+use sea_orm::{
+    sea_query::Expr,
+    ColumnTrait, Condition, ConnectionTrait, EntityTrait, FromQueryResult, JoinType, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::io::{BufWriter, Cursor};
+use std::io::{BufWriter, Cursor, Seek, SeekFrom, Write};
+use std::net::SocketAddr;
 use std::path::Path as StdPath;
-use std::process::Command;
+use tokio_util::io::ReaderStream;
 use uuid::Uuid;
 
 use crate::{
-    access::require_session_user,
+    access::{
+        advisor_class_scope, authorize_export, department_scope, ensure_student_in_scope, require_session_user,
+        student_scope_condition, ExportRecordScope, ExportTarget,
+    },
+    db::sum_as_i64,
+    document_verification::{
+        client_key, render_qr_image, verify_document_token, DocumentVerificationResult, PendingVerification,
+        DOCUMENT_LABOR_HOURS_EXCEL, DOCUMENT_LABOR_HOURS_PDF, DOCUMENT_RECORD_PDF,
+    },
     entities::{
-        contest_records, form_field_values, form_fields, review_signatures, students,
-        ContestRecord, FormField, FormFieldValue, ReviewSignature, Student, UserSignature,
+        attachments, contest_records, form_field_values, form_fields, review_signatures, students, users,
+        Attachment, ContestRecord, FormField, FormFieldValue, ReviewSignature, Student, User, UserSignature,
     },
     error::AppError,
+    export_profiles::{
+        load_export_columns, ExportColumn, ExportProfileDef, LABOR_HOURS_EXCEL_EXPORT, STUDENT_EXPORT,
+        SUMMARY_EXPORT,
+    },
     export_template::render_template_to_xlsx,
-    labor_hours::{compute_recommended_hours, load_labor_hour_rules},
+    i18n::Locale,
+    labor_hours::{
+        approved_hours_by_year, approved_hours_expr, capped_total, group_hours_by_year, load_labor_hour_rules,
+        record_recommended_hours,
+    },
+    record_filter::RecordFilter,
+    routes::{attachments::ATTACHMENT_KIND_SIGNATURE, records::record_ids_with_match_status},
+    semesters::load_semester_years,
+    services::record::load_form_fields,
     state::AppState,
-    templates::{export_template_file_path, load_export_template},
+    templates::{
+        export_template_file_path, list_export_templates, require_export_template, ExportTemplateConfig,
+        BUILTIN_EXPORT_TEMPLATE_KEY,
+    },
 };
 
 /// 汇总导出筛选条件。
 #[derive(Debug, Deserialize)]
 pub struct ExportSummaryQuery {
+    /// 结构化筛选（与竞赛记录查询一致）；记录级条件只统计命中的记录。
+    #[serde(flatten)]
+    pub filter: RecordFilter,
+    /// 本次导出的列（覆盖已配置的导出字段）。
+    pub columns: Option<Vec<String>>,
+    /// 是否包含不通过记录（默认包含）。
+    pub include_rejected: Option<bool>,
+    /// 是否包含已撤回记录（默认不包含）。
+    pub include_withdrawn: Option<bool>,
+    /// 是否按班级分组并在每个班级后插入小计行（默认否）。
+    pub group_by_class: Option<bool>,
+}
+
+/// 审核签名审计表筛选条件。
+#[derive(Debug, Deserialize)]
+pub struct SignatureAuditQuery {
+    /// 学期（如 `2025-2026-1`），按记录终审时间筛选。
+    pub semester: Option<String>,
     /// 院系筛选。
     pub department: Option<String>,
-    /// 专业筛选。
-    pub major: Option<String>,
-    /// 班级筛选。
-    pub class_name: Option<String>,
 }
 
-/// 导出学院/专业/班级汇总表。
+/// 汇总统计时的记录可见性。
+#[derive(Debug, Clone, Copy)]
+struct RecordVisibility {
+    include_rejected: bool,
+    include_withdrawn: bool,
+}
+
+impl Default for RecordVisibility {
+    fn default() -> Self {
+        Self {
+            include_rejected: true,
+            include_withdrawn: false,
+        }
+    }
+}
+
+/// 不通过/撤回记录明细表的列标题（消息目录键）。
+const RECORD_DETAIL_HEADERS: [&str; 8] = [
+    "field.student_no",
+    "field.name",
+    "field.class_name",
+    "record.contest_name",
+    "record.award_level",
+    "record.self_hours",
+    "record.status",
+    "record.reason",
+];
+
+/// 审核签名审计表的列标题（消息目录键）。
+const SIGNATURE_AUDIT_HEADERS: [&str; 12] = [
+    "field.student_no",
+    "field.name",
+    "field.department",
+    "record.contest_name",
+    "record.award_level",
+    "audit.final_hours",
+    "audit.first_reviewer",
+    "audit.first_signed_at",
+    "audit.first_signature_sha256",
+    "audit.final_reviewer",
+    "audit.final_signed_at",
+    "audit.final_signature_sha256",
+];
+
+/// 单次批量导出 PDF 的学生数量上限。
+const MAX_BATCH_PDF_STUDENTS: usize = 500;
+
+/// 按 ID 批量查询时每批的数量，避免超出数据库参数上限。
+const ID_BATCH_SIZE: usize = 500;
+
+/// 记录 PDF 中核验二维码的边长（毫米）。
+const VERIFICATION_QR_SIZE_MM: f32 = 24.0;
+
+/// 导出学院/专业/班级汇总表（班主任限负责班级）。
 pub async fn export_summary_excel(
     State(state): State<AppState>,
     jar: CookieJar,
     Json(query): Json<ExportSummaryQuery>,
 ) -> Result<Response, AppError> {
     let user = require_session_user(&state, &jar).await?;
-    if user.role != "admin" && user.role != "teacher" && user.role != "reviewer" {
-        return Err(AppError::auth("forbidden"));
-    }
-
-    let mut finder = Student::find();
-    if let Some(value) = query.department {
-        finder = finder.filter(students::Column::Department.eq(value));
-    }
-    if let Some(value) = query.major {
-        finder = finder.filter(students::Column::Major.eq(value));
-    }
-    if let Some(value) = query.class_name {
-        finder = finder.filter(students::Column::ClassName.eq(value));
-    }
-
-    let students = finder
-        .filter(students::Column::IsDeleted.eq(false))
-        .all(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
+    authorize_export(&user, ExportTarget::Summary, state.config.student_self_export)?;
+
+    query.filter.validate()?;
+    let locale = state.locale_for(&user);
+    let condition = summary_student_condition(&state, &user, &query).await?;
+    let group_by_class = query.group_by_class.unwrap_or(false);
+    let mut students = summary_students(&state, condition.clone(), group_by_class).await?;
+    let condition = summary_record_condition(&state, condition, &query.filter).await?;
+
+    let profile = load_export_columns(&state.db, &SUMMARY_EXPORT).await?;
+    let export_fields = match query.columns.as_deref() {
+        Some(columns) => select_summary_columns(locale, &user.role, columns, &profile)?,
+        None => export_fields(locale, &SUMMARY_EXPORT, &profile),
+    };
 
-    let fields = load_export_fields(&state, "summary").await?;
-    let export_fields = if fields.is_empty() {
-        default_summary_fields()
-    } else {
-        fields
+    let visibility = RecordVisibility {
+        include_rejected: query.include_rejected.unwrap_or(true),
+        include_withdrawn: query.include_withdrawn.unwrap_or(false),
     };
 
+    let mut totals = aggregate_student_hours(&state.db, condition.clone(), visibility).await?;
+    let rule_config = load_labor_hour_rules(&state).await?;
+    apply_hour_caps(&state.db, condition.clone(), rule_config.annual_cap_hours, &mut totals).await?;
+    if query.filter.has_record_filters() {
+        students.retain(|student| totals.contains_key(&student.id));
+    }
     let mut workbook = rust_xlsxwriter::Workbook::new();
-    let worksheet = workbook.add_worksheet();
-    for (idx, field) in export_fields.iter().enumerate() {
-        worksheet
-            .write_string(0, idx as u16, &field.label)
-            .map_err(|_| AppError::internal("write excel failed"))?;
+    let mut sheet = FieldSheet::new(workbook.add_worksheet(), &export_fields)?;
+    let empty = HourTotals::default();
+    write_student_rows(&mut sheet, locale, &students, group_by_class, |_, student| {
+        let total = totals.get(&student.id).unwrap_or(&empty);
+        export_fields
+            .iter()
+            .map(|field| resolve_export_value(field.field_key.as_str(), student, total))
+            .collect()
+    })?;
+    sheet.finish()?;
+    if visibility.include_rejected {
+        let rows = load_summary_detail_rows(&state, condition.clone(), false).await?;
+        write_record_detail_sheet(&mut workbook, locale, locale.text("export.sheet.rejected"), &rows)?;
     }
-
-    for (idx, student) in students.iter().enumerate() {
-        let (self_hours, approved_hours, reason) =
-            compute_student_hours(&state, student.id).await?;
-        let row = (idx + 1) as u32;
-        for (col, field) in export_fields.iter().enumerate() {
-            let value = resolve_export_value(field.field_key.as_str(), student, self_hours, approved_hours, &reason);
-            write_cell(worksheet, row, col as u16, &value)?;
-        }
+    if visibility.include_withdrawn {
+        let rows = load_summary_detail_rows(&state, condition, true).await?;
+        write_record_detail_sheet(&mut workbook, locale, locale.text("export.sheet.withdrawn"), &rows)?;
     }
 
-    let buffer = workbook
-        .save_to_buffer()
-        .map_err(|_| AppError::internal("save excel failed"))?;
-
-    Ok(file_response(
-        "summary.xlsx",
-        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
-        buffer,
-    ))
+    workbook_response("summary.xlsx", workbook).await
 }
 
 /// 导出个人学时专项表（管理员/教师/本人）。
@@ -110,9 +200,11 @@ pub async fn export_student_excel(
     Path(student_no): Path<String>,
 ) -> Result<Response, AppError> {
     let user = require_session_user(&state, &jar).await?;
-    if user.role == "student" && user.username != student_no {
-        return Err(AppError::auth("forbidden"));
-    }
+    let scope = authorize_export(
+        &user,
+        ExportTarget::Student { student_no: &student_no },
+        state.config.student_self_export,
+    )?;
 
     let student = Student::find()
         .filter(students::Column::StudentNo.eq(&student_no))
@@ -121,29 +213,21 @@ pub async fn export_student_excel(
         .await
         .map_err(|err| AppError::Database(err.to_string()))?
         .ok_or_else(|| AppError::not_found("student not found"))?;
+    ensure_student_in_scope(&user, &student)?;
 
-    let (self_hours, approved_hours, reason) =
-        compute_student_hours(&state, student.id).await?;
+    let totals = compute_student_hours(&state, student.id, scope).await?;
 
-    let fields = load_export_fields(&state, "student_export").await?;
-    let export_fields = if fields.is_empty() {
-        default_student_fields()
-    } else {
-        fields
-    };
+    let profile = load_export_columns(&state.db, &STUDENT_EXPORT).await?;
+    let export_fields = export_fields(state.locale_for(&user), &STUDENT_EXPORT, &profile);
 
     let mut workbook = rust_xlsxwriter::Workbook::new();
-    let worksheet = workbook.add_worksheet();
-    for (idx, field) in export_fields.iter().enumerate() {
-        worksheet
-            .write_string(0, idx as u16, &field.label)
-            .map_err(|_| AppError::internal("write excel failed"))?;
-    }
-
-    for (col, field) in export_fields.iter().enumerate() {
-        let value = resolve_export_value(field.field_key.as_str(), &student, self_hours, approved_hours, &reason);
-        write_cell(worksheet, 1, col as u16, &value)?;
-    }
+    let mut sheet = FieldSheet::new(workbook.add_worksheet(), &export_fields)?;
+    let values: Vec<ExportValue> = export_fields
+        .iter()
+        .map(|field| resolve_export_value(field.field_key.as_str(), &student, &totals))
+        .collect();
+    sheet.write_row(&values)?;
+    sheet.finish()?;
 
     let buffer = workbook
         .save_to_buffer()
@@ -163,68 +247,212 @@ pub async fn export_labor_hours_summary_excel(
     Json(query): Json<ExportSummaryQuery>,
 ) -> Result<Response, AppError> {
     let user = require_session_user(&state, &jar).await?;
-    if user.role != "admin" && user.role != "teacher" && user.role != "reviewer" {
-        return Err(AppError::auth("forbidden"));
+    authorize_export(&user, ExportTarget::Summary, state.config.student_self_export)?;
+
+    query.filter.validate()?;
+    let locale = state.locale_for(&user);
+    let condition = summary_student_condition(&state, &user, &query).await?;
+    let group_by_class = query.group_by_class.unwrap_or(false);
+    let mut students = summary_students(&state, condition.clone(), group_by_class).await?;
+    let condition = summary_record_condition(&state, condition, &query.filter).await?;
+    let mut totals = aggregate_student_hours(&state.db, condition.clone(), RecordVisibility::default()).await?;
+    let rule_config = load_labor_hour_rules(&state).await?;
+    apply_hour_caps(&state.db, condition, rule_config.annual_cap_hours, &mut totals).await?;
+    if query.filter.has_record_filters() {
+        students.retain(|student| totals.contains_key(&student.id));
     }
 
-    let mut finder = Student::find();
-    if let Some(value) = query.department {
-        finder = finder.filter(students::Column::Department.eq(value));
+    let profile = load_export_columns(&state.db, &LABOR_HOURS_EXCEL_EXPORT).await?;
+    let export_fields = export_fields(locale, &LABOR_HOURS_EXCEL_EXPORT, &profile);
+
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let mut sheet = FieldSheet::new(workbook.add_worksheet(), &export_fields)?;
+    let empty = HourTotals::default();
+    write_student_rows(&mut sheet, locale, &students, group_by_class, |idx, student| {
+        let total = totals.get(&student.id).unwrap_or(&empty);
+        export_fields
+            .iter()
+            .map(|field| resolve_labor_hours_export_value(field.field_key.as_str(), idx + 1, student, total))
+            .collect()
+    })?;
+    sheet.finish()?;
+
+    workbook_response("labor-hours-summary.xlsx", workbook).await
+}
+
+/// 导出审核签名审计表（已终审记录的各阶段签名人、签名时间与签名文件哈希）。
+pub async fn export_signature_audit_excel(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(query): Json<SignatureAuditQuery>,
+) -> Result<Response, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    authorize_export(&user, ExportTarget::SignatureAudit, state.config.student_self_export)?;
+    let locale = state.locale_for(&user);
+
+    let mut condition = Condition::all()
+        .add(students::Column::IsDeleted.eq(false))
+        .add(contest_records::Column::IsDeleted.eq(false))
+        .add(contest_records::Column::Status.eq("final_reviewed"));
+    if let Some(department) = department_scope(&user) {
+        condition = condition.add(students::Column::Department.eq(department));
     }
-    if let Some(value) = query.major {
-        finder = finder.filter(students::Column::Major.eq(value));
+    if let Some(value) = &query.department {
+        condition = condition.add(students::Column::Department.eq(value.as_str()));
     }
-    if let Some(value) = query.class_name {
-        finder = finder.filter(students::Column::ClassName.eq(value));
+    if let Some(semester) = query.semester.as_deref() {
+        let (start, end) = parse_semester(semester)?;
+        condition = condition
+            .add(contest_records::Column::UpdatedAt.gte(start))
+            .add(contest_records::Column::UpdatedAt.lt(end));
     }
 
-    let students = finder
-        .filter(students::Column::IsDeleted.eq(false))
+    let records: Vec<(contest_records::Model, students::Model)> = ContestRecord::find()
+        .find_also_related(Student)
+        .filter(condition)
+        .order_by_asc(students::Column::StudentNo)
+        .order_by_asc(contest_records::Column::CreatedAt)
         .all(&state.db)
         .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .filter_map(|(record, student)| student.map(|student| (record, student)))
+        .collect();
+
+    let record_ids: Vec<Uuid> = records.iter().map(|(record, _)| record.id).collect();
+    let mut signatures: HashMap<(Uuid, String), review_signatures::Model> = HashMap::new();
+    for chunk in record_ids.chunks(ID_BATCH_SIZE) {
+        let rows = ReviewSignature::find()
+            .filter(review_signatures::Column::RecordType.eq("contest"))
+            .filter(review_signatures::Column::RecordId.is_in(chunk.to_vec()))
+            .order_by_asc(review_signatures::Column::CreatedAt)
+            .all(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        for row in rows {
+            // 同一阶段多次签名时保留最新一次。
+            signatures.insert((row.record_id, row.stage.clone()), row);
+        }
+    }
 
-    let fields = load_export_fields(&state, "labor_hours_excel").await?;
-    let export_fields = if fields.is_empty() {
-        default_labor_hours_excel_fields()
-    } else {
-        fields
-    };
+    let mut reviewer_ids: Vec<Uuid> = records
+        .iter()
+        .flat_map(|(record, _)| [record.first_reviewer_id, record.final_reviewer_id])
+        .flatten()
+        .chain(signatures.values().map(|signature| signature.reviewer_user_id))
+        .chain(signatures.values().filter_map(|signature| signature.delegator_user_id))
+        .collect();
+    reviewer_ids.sort();
+    reviewer_ids.dedup();
+    let mut reviewer_names: HashMap<Uuid, String> = HashMap::new();
+    for chunk in reviewer_ids.chunks(ID_BATCH_SIZE) {
+        let rows = User::find()
+            .filter(users::Column::Id.is_in(chunk.to_vec()))
+            .all(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        reviewer_names.extend(rows.into_iter().map(|row| (row.id, row.display_name)));
+    }
+
+    let mut file_hashes: HashMap<String, String> = HashMap::new();
+    for signature in signatures.values() {
+        if !file_hashes.contains_key(&signature.signature_path) {
+            let hash = match tokio::fs::read(&signature.signature_path).await {
+                Ok(bytes) => sha256_hex(&bytes),
+                Err(_) => locale.text("export.file_missing").to_string(),
+            };
+            file_hashes.insert(signature.signature_path.clone(), hash);
+        }
+    }
 
     let mut workbook = rust_xlsxwriter::Workbook::new();
     let worksheet = workbook.add_worksheet();
-    for (idx, field) in export_fields.iter().enumerate() {
+    for (idx, header) in SIGNATURE_AUDIT_HEADERS.iter().enumerate() {
         worksheet
-            .write_string(0, idx as u16, &field.label)
+            .write_string(0, idx as u16, locale.text(*header))
             .map_err(|_| AppError::internal("write excel failed"))?;
     }
-
-    for (idx, student) in students.iter().enumerate() {
-        let (self_hours, approved_hours, reason) =
-            compute_student_hours(&state, student.id).await?;
+    for (idx, (record, student)) in records.iter().enumerate() {
         let row = (idx + 1) as u32;
-        for (col, field) in export_fields.iter().enumerate() {
-            let value = resolve_labor_hours_export_value(
-                field.field_key.as_str(),
-                idx + 1,
-                student,
-                self_hours,
-                approved_hours,
-                &reason,
-            );
-            write_cell(worksheet, row, col as u16, &value)?;
+        let mut values = vec![
+            ExportValue::Text(student.student_no.clone()),
+            ExportValue::Text(student.name.clone()),
+            ExportValue::Text(student.department.clone()),
+            ExportValue::Text(record.contest_name.clone()),
+            ExportValue::Text(record.award_level.clone()),
+            ExportValue::Number(record.final_review_hours.unwrap_or(0) as f64),
+        ];
+        for (stage, fallback_reviewer) in [
+            ("first", record.first_reviewer_id),
+            ("final", record.final_reviewer_id),
+        ] {
+            let signature = signatures.get(&(record.id, stage.to_string()));
+            let reviewer_id = signature
+                .map(|signature| signature.reviewer_user_id)
+                .or(fallback_reviewer);
+            let reviewer = reviewer_id
+                .and_then(|id| reviewer_names.get(&id).cloned())
+                .unwrap_or_default();
+            // 代为签名时同时列出委托人。
+            let delegator = signature
+                .and_then(|signature| signature.delegator_user_id)
+                .and_then(|id| reviewer_names.get(&id));
+            let reviewer = match delegator {
+                Some(delegator) => locale.format(
+                    "audit.on_behalf_of",
+                    &[("actor", reviewer.as_str()), ("delegator", delegator.as_str())],
+                ),
+                None => reviewer,
+            };
+            values.push(ExportValue::Text(reviewer));
+            values.push(ExportValue::Text(
+                signature
+                    .map(|signature| signature.created_at.to_rfc3339())
+                    .unwrap_or_default(),
+            ));
+            values.push(ExportValue::Text(
+                signature
+                    .and_then(|signature| file_hashes.get(&signature.signature_path).cloned())
+                    .unwrap_or_default(),
+            ));
+        }
+        for (col, value) in values.iter().enumerate() {
+            write_cell(worksheet, row, col as u16, value)?;
         }
     }
 
-    let buffer = workbook
-        .save_to_buffer()
-        .map_err(|_| AppError::internal("save excel failed"))?;
+    workbook_response("signature-audit.xlsx", workbook).await
+}
 
-    Ok(file_response(
-        "labor-hours-summary.xlsx",
-        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
-        buffer,
-    ))
+/// 解析学期（`起始年-结束年-1/2`），返回 [开始, 结束) 时间范围。
+/// 第一学期为起始年 9 月 1 日至次年 2 月 1 日，第二学期为次年 2 月 1 日至 9 月 1 日。
+fn parse_semester(value: &str) -> Result<(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>), AppError> {
+    let invalid = || AppError::validation("invalid semester");
+    let parts: Vec<&str> = value.trim().split('-').collect();
+    let [start_year, end_year, term] = parts.as_slice() else {
+        return Err(invalid());
+    };
+    let start_year: i32 = start_year.parse().map_err(|_| invalid())?;
+    let end_year: i32 = end_year.parse().map_err(|_| invalid())?;
+    if end_year != start_year + 1 {
+        return Err(invalid());
+    }
+    let date = |year: i32, month: u32| {
+        chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, year, month, 1, 0, 0, 0)
+            .single()
+            .ok_or_else(invalid)
+    };
+    match *term {
+        "1" => Ok((date(start_year, 9)?, date(end_year, 2)?)),
+        "2" => Ok((date(end_year, 2)?, date(end_year, 9)?)),
+        _ => Err(invalid()),
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    hex::encode(hasher.finalize())
 }
 
 /// 导出记录 PDF（志愿/竞赛）。
@@ -234,8 +462,10 @@ pub async fn export_record_pdf(
     Path((record_type, record_id)): Path<(String, Uuid)>,
 ) -> Result<Response, AppError> {
     let user = require_session_user(&state, &jar).await?;
+    let locale = state.locale_for(&user);
+    let label = |key: &'static str| locale.text(key).to_string();
 
-    let (student, summary) = match record_type.as_str() {
+    let (student, summary, approved_hours) = match record_type.as_str() {
         "contest" => {
             let record = ContestRecord::find()
                 .filter(contest_records::Column::Id.eq(record_id))
@@ -251,73 +481,74 @@ pub async fn export_record_pdf(
                 .map_err(|err| AppError::Database(err.to_string()))?
                 .ok_or_else(|| AppError::not_found("student not found"))?;
 
-            if user.role == "student" && user.username != student.student_no {
-                return Err(AppError::auth("forbidden"));
-            }
+            authorize_export(
+                &user,
+                ExportTarget::Record {
+                    student_no: &student.student_no,
+                    status: &record.status,
+                },
+                state.config.student_self_export,
+            )?;
+            ensure_student_in_scope(&user, &student)?;
             let rule = load_labor_hour_rules(&state).await?;
-            let recommended = compute_recommended_hours(
-                rule,
-                record.contest_category.as_deref(),
-                record.contest_level.as_deref(),
-                record.contest_role.as_deref(),
-            );
+            let recommended = record_recommended_hours(rule, &record);
+            let approved_hours = if record.status == "final_reviewed" {
+                record.final_review_hours.unwrap_or(0)
+            } else {
+                0
+            };
             let summary = vec![
-                ("记录类型".to_string(), "竞赛获奖".to_string()),
+                (label("record.record_type"), label("record.contest")),
                 (
-                    "竞赛年份".to_string(),
+                    label("record.contest_year"),
                     record
                         .contest_year
                         .map(|value| value.to_string())
                         .unwrap_or_default(),
                 ),
                 (
-                    "竞赛类型".to_string(),
+                    label("record.contest_category"),
                     record.contest_category.clone().unwrap_or_default(),
                 ),
-                ("竞赛名称".to_string(), record.contest_name),
+                (label("record.contest_name"), record.contest_name),
                 (
-                    "竞赛级别".to_string(),
+                    label("record.contest_level"),
                     record.contest_level.clone().unwrap_or_default(),
                 ),
                 (
-                    "竞赛角色".to_string(),
+                    label("record.contest_role"),
                     record.contest_role.clone().unwrap_or_default(),
                 ),
-                ("获奖等级".to_string(), record.award_level),
+                (label("record.award_level"), record.award_level),
                 (
-                    "获奖时间".to_string(),
+                    label("record.award_date"),
                     record
                         .award_date
                         .map(|value| value.to_rfc3339())
                         .unwrap_or_default(),
                 ),
-                ("自评学时".to_string(), record.self_hours.to_string()),
-                ("推荐学时".to_string(), recommended.to_string()),
+                (label("record.self_hours"), record.self_hours.to_string()),
+                (label("record.recommended_hours"), recommended.to_string()),
                 (
-                    "初审学时".to_string(),
+                    label("record.first_review_hours"),
                     record.first_review_hours.map_or("".to_string(), |v| v.to_string()),
                 ),
                 (
-                    "复审学时".to_string(),
+                    label("record.final_review_hours"),
                     record.final_review_hours.map_or("".to_string(), |v| v.to_string()),
                 ),
-                ("状态".to_string(), record.status),
+                (label("record.status"), record.status),
                 (
-                    "不通过原因".to_string(),
+                    label("record.rejection_reason"),
                     record.rejection_reason.unwrap_or_default(),
                 ),
             ];
-            (student, summary)
+            (student, summary, approved_hours)
         }
         _ => return Err(AppError::bad_request("invalid record type")),
     };
 
-    let signatures = ReviewSignature::find()
-        .filter(review_signatures::Column::RecordType.eq(record_type.clone()))
-        .filter(review_signatures::Column::RecordId.eq(record_id))
-        .all(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
+    let signatures = load_signature_attachments(&state, &record_type, record_id).await?;
 
     let mut summary = summary;
     let custom_fields = load_custom_fields(&state, &record_type, record_id).await?;
@@ -335,8 +566,25 @@ pub async fn export_record_pdf(
     let mut y: f32 = 280.0;
     layer.set_outline_color(Color::Rgb(Rgb::new(0.2, 0.2, 0.2, None)));
 
+    // 核验二维码放在首页底部留白处，正文不会低于 30mm。
+    let verification = PendingVerification::issue(&state.config);
+    let qr = render_qr_image(&verification.url)?;
+    let (qr_width, _) = image::GenericImageView::dimensions(&qr);
+    let qr_dpi = qr_width as f32 * 25.4 / VERIFICATION_QR_SIZE_MM;
+    Image::from_dynamic_image(&qr).add_to_layer(
+        layer.clone(),
+        ImageTransform {
+            translate_x: Some(Mm(190.0 - VERIFICATION_QR_SIZE_MM)),
+            translate_y: Some(Mm(4.0)),
+            dpi: Some(qr_dpi),
+            ..Default::default()
+        },
+    );
+    layer.use_text(locale.text("pdf.record.verification"), 10.0, Mm(20.0), Mm(16.0), &font);
+    layer.use_text(verification.url.as_str(), 8.0, Mm(20.0), Mm(10.0), &font);
+
     layer.use_text(
-        "审核记录详情",
+        locale.text("pdf.record.title"),
         16.0,
         Mm(20.0),
         Mm(y),
@@ -344,14 +592,17 @@ pub async fn export_record_pdf(
     );
     y -= 12.0;
     layer.use_text(
-        format!("学生: {} ({})", student.name, student.student_no),
+        locale.format(
+            "pdf.record.student",
+            &[("name", &student.name), ("student_no", &student.student_no)],
+        ),
         12.0,
         Mm(20.0),
         Mm(y),
         &font,
     );
     y -= 10.0;
-    y = draw_table_header(&layer, &font, y);
+    y = draw_table_header(&layer, &font, locale, y);
 
     for (label, value) in summary {
         let lines = wrap_text(&value, 26);
@@ -363,14 +614,14 @@ pub async fn export_record_pdf(
             current_page += 1;
             y = 280.0;
             layer.use_text(
-                format!("审核记录详情（续页 {current_page}）"),
+                locale.format("pdf.record.continued", &[("page", &current_page.to_string())]),
                 14.0,
                 Mm(20.0),
                 Mm(y),
                 &font,
             );
             y -= 10.0;
-            y = draw_table_header(&layer, &font, y);
+            y = draw_table_header(&layer, &font, locale, y);
         }
         y = draw_table_row(&layer, &font, y, &label, &lines);
     }
@@ -383,7 +634,7 @@ pub async fn export_record_pdf(
         current_page += 1;
         y = 280.0;
         layer.use_text(
-            format!("审核记录详情（续页 {current_page}）"),
+            locale.format("pdf.record.continued", &[("page", &current_page.to_string())]),
             14.0,
             Mm(20.0),
             Mm(y),
@@ -392,11 +643,15 @@ pub async fn export_record_pdf(
         y -= 14.0;
     }
 
-    layer.use_text("审核签名", 12.0, Mm(20.0), Mm(y), &font);
+    layer.use_text(locale.text("pdf.record.signatures"), 12.0, Mm(20.0), Mm(y), &font);
     y -= 8.0;
 
-    for sig in signatures {
-        let label = format!("{}签名", if sig.stage == "first" { "初审" } else { "复审" });
+    for (stage, sig) in signatures {
+        let label = if stage == "first" {
+            locale.text("pdf.record.first_signature")
+        } else {
+            locale.text("pdf.record.final_signature")
+        };
         if y < 50.0 {
             let (page, layer_id) = doc.add_page(Mm(210.0), Mm(297.0), "Layer");
             layer = doc.get_page(page).get_layer(layer_id);
@@ -404,18 +659,18 @@ pub async fn export_record_pdf(
             current_page += 1;
             y = 280.0;
             layer.use_text(
-                format!("审核记录详情（续页 {current_page}）"),
+                locale.format("pdf.record.continued", &[("page", &current_page.to_string())]),
                 14.0,
                 Mm(20.0),
                 Mm(y),
                 &font,
             );
             y -= 14.0;
-            layer.use_text("审核签名", 12.0, Mm(20.0), Mm(y), &font);
+            layer.use_text(locale.text("pdf.record.signatures"), 12.0, Mm(20.0), Mm(y), &font);
             y -= 8.0;
         }
         layer.use_text(label, 10.0, Mm(20.0), Mm(y), &font);
-        if let Some(image) = load_signature_image(&sig.signature_path) {
+        if let Some(image) = load_signature_image(&sig.stored_name) {
             let transform = ImageTransform {
                 translate_x: Some(Mm(60.0)),
                 translate_y: Some(Mm(y - 6.0)),
@@ -425,7 +680,7 @@ pub async fn export_record_pdf(
             };
             image.add_to_layer(layer.clone(), transform);
         } else {
-            layer.use_text("未找到签名文件", 10.0, Mm(60.0), Mm(y), &font);
+            layer.use_text(locale.text("pdf.record.signature_missing"), 10.0, Mm(60.0), Mm(y), &font);
         }
         y -= 24.0;
     }
@@ -437,6 +692,9 @@ pub async fn export_record_pdf(
         .into_inner()
         .map_err(|_| AppError::internal("save pdf failed"))?;
     let buffer = cursor.into_inner();
+    verification
+        .record(&state.db, DOCUMENT_RECORD_PDF, &student, approved_hours, &buffer, user.id)
+        .await?;
 
     Ok(file_response(
         format!("record-{}.pdf", record_id),
@@ -445,111 +703,801 @@ pub async fn export_record_pdf(
     ))
 }
 
+/// 核验导出文档（公开接口，无需登录），按客户端限流。
+pub async fn verify_document(
+    State(state): State<AppState>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Path(token): Path<String>,
+) -> Result<Json<DocumentVerificationResult>, AppError> {
+    let client = client_key(&headers, connect_info.map(|ConnectInfo(addr)| addr.ip()));
+    if !state.verify_limiter.lock().await.allow(&client) {
+        return Err(AppError::too_many_requests("document verification rate limited"));
+    }
+    Ok(Json(verify_document_token(&state.db, &token).await?))
+}
+
+/// 生成可打印的恢复码 PDF（单页，含用户名与生成时间），供纸质备份留存。
+pub(crate) fn render_recovery_codes_pdf(
+    locale: Locale,
+    username: &str,
+    generated_at: chrono::DateTime<chrono::Utc>,
+    codes: &[String],
+) -> Result<Vec<u8>, AppError> {
+    let (doc, page1, layer1) = PdfDocument::new("recovery codes", Mm(210.0), Mm(297.0), "Layer 1");
+    let layer = doc.get_page(page1).get_layer(layer1);
+    let font = doc
+        .add_builtin_font(BuiltinFont::Helvetica)
+        .map_err(|_| AppError::internal("load font failed"))?;
+    let mono = doc
+        .add_builtin_font(BuiltinFont::Courier)
+        .map_err(|_| AppError::internal("load font failed"))?;
+    layer.set_outline_color(Color::Rgb(Rgb::new(0.2, 0.2, 0.2, None)));
+
+    let mut y: f32 = 280.0;
+    layer.use_text(locale.text("pdf.recovery.title"), 16.0, Mm(20.0), Mm(y), &font);
+    y -= 12.0;
+    layer.use_text(
+        locale.format("pdf.recovery.username", &[("username", username)]),
+        12.0,
+        Mm(20.0),
+        Mm(y),
+        &font,
+    );
+    y -= 8.0;
+    let generated = generated_at.format("%Y-%m-%d %H:%M UTC").to_string();
+    layer.use_text(
+        locale.format("pdf.recovery.generated_at", &[("time", &generated)]),
+        12.0,
+        Mm(20.0),
+        Mm(y),
+        &font,
+    );
+    y -= 12.0;
+    for line in wrap_text(locale.text("pdf.recovery.notice"), 60) {
+        layer.use_text(line, 10.0, Mm(20.0), Mm(y), &font);
+        y -= 6.0;
+    }
+    y -= 6.0;
+    draw_line(&layer, 20.0, y, 190.0, y);
+    y -= 12.0;
+    for (idx, code) in codes.iter().enumerate() {
+        layer.use_text(format!("{:>2}.  {code}", idx + 1), 14.0, Mm(30.0), Mm(y), &mono);
+        y -= 10.0;
+    }
+
+    let mut writer = BufWriter::new(Cursor::new(Vec::new()));
+    doc.save(&mut writer)
+        .map_err(|_| AppError::internal("save pdf failed"))?;
+    let cursor = writer
+        .into_inner()
+        .map_err(|_| AppError::internal("save pdf failed"))?;
+    Ok(cursor.into_inner())
+}
+
+/// 读取记录的签名类附件及其审核阶段，按上传时间排序；学生上传的佐证材料不会进入 PDF。
+async fn load_signature_attachments(
+    state: &AppState,
+    record_type: &str,
+    record_id: Uuid,
+) -> Result<Vec<(String, attachments::Model)>, AppError> {
+    let files = Attachment::find()
+        .filter(attachments::Column::RecordType.eq(record_type))
+        .filter(attachments::Column::RecordId.eq(record_id))
+        .filter(attachments::Column::Kind.eq(ATTACHMENT_KIND_SIGNATURE))
+        .order_by_asc(attachments::Column::CreatedAt)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let stages: HashMap<Uuid, String> = ReviewSignature::find()
+        .filter(review_signatures::Column::RecordType.eq(record_type))
+        .filter(review_signatures::Column::RecordId.eq(record_id))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|signature| (signature.id, signature.stage))
+        .collect();
+    // 签名附件与审核签名共用 ID，缺少审核签名的文件无法确定阶段，不予输出。
+    Ok(files
+        .into_iter()
+        .filter_map(|file| stages.get(&file.id).map(|stage| (stage.clone(), file)))
+        .collect())
+}
+
+/// 可选导出模板。
+#[derive(Debug, Serialize)]
+pub struct ExportTemplateOption {
+    /// 模板 key。
+    pub template_key: String,
+    /// 显示名称。
+    pub display_name: String,
+    /// 模板说明。
+    pub description: Option<String>,
+}
+
+/// 列出已上传文件、可用于学时认定表导出的模板（管理员/教师/审核人员）。
+pub async fn list_labor_hours_templates(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<ExportTemplateOption>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    if user.role != "admin" && user.role != "teacher" && user.role != "reviewer" {
+        return Err(AppError::auth("forbidden"));
+    }
+
+    let templates = list_export_templates(&state).await?;
+    Ok(Json(
+        templates
+            .into_iter()
+            .filter(|template| template.export_type == "labor_hours")
+            .filter(|template| export_template_file_path(&state, &template.template_key).exists())
+            .map(|template| ExportTemplateOption {
+                template_key: template.template_key,
+                display_name: template.display_name,
+                description: template.description,
+            })
+            .collect(),
+    ))
+}
+
+/// 导出模板选择参数。
+#[derive(Debug, Deserialize)]
+pub struct ExportTemplateSelection {
+    /// 导出模板 key，默认内置的 `labor_hours`。
+    pub template_key: Option<String>,
+}
+
 /// 导出劳动教育学时认定表 PDF（每学生一份）。
 pub async fn export_labor_hours_pdf(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(student_no): Path<String>,
+    Query(selection): Query<ExportTemplateSelection>,
 ) -> Result<Response, AppError> {
-    let user = require_session_user(&state, &jar).await?;
-    if user.role == "student" && user.username != student_no {
-        return Err(AppError::auth("forbidden"));
-    }
-    if user.role != "student" && user.role != "admin" && user.role != "teacher" && user.role != "reviewer" {
-        return Err(AppError::auth("forbidden"));
-    }
+    let (user, student, template, scope) = labor_hours_export_target(&state, &jar, &student_no, &selection).await?;
+    let buffer = render_labor_hours_pdf(&state, &student, &template, scope, user.id).await?;
+
+    Ok(file_response(
+        format!("{}-labor-hours.pdf", student.student_no),
+        "application/pdf",
+        buffer,
+    ))
+}
+
+/// 导出填充后的劳动教育学时认定表 XLSX（不转换为 PDF，便于打印前继续编辑）。
+pub async fn export_labor_hours_excel(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(student_no): Path<String>,
+    Query(selection): Query<ExportTemplateSelection>,
+) -> Result<Response, AppError> {
+    let (user, student, template, scope) = labor_hours_export_target(&state, &jar, &student_no, &selection).await?;
+    let verification = PendingVerification::issue(&state.config);
+    let rendered = render_labor_hours_xlsx(&state, &student, &template, scope, &verification).await?;
+    let buffer = tokio::fs::read(&rendered.output_xlsx)
+        .await
+        .map_err(|err| AppError::internal(&format!("read rendered workbook failed: {err}")))?;
+    verification
+        .record(
+            &state.db,
+            DOCUMENT_LABOR_HOURS_EXCEL,
+            &student,
+            rendered.approved_hours,
+            &buffer,
+            user.id,
+        )
+        .await?;
+
+    Ok(file_response(
+        format!("{}-labor-hours.xlsx", student.student_no),
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        buffer,
+    ))
+}
+
+/// 校验学时认定表导出权限，返回当前用户、目标学生、所选模板与可包含的记录范围。
+async fn labor_hours_export_target(
+    state: &AppState,
+    jar: &CookieJar,
+    student_no: &str,
+    selection: &ExportTemplateSelection,
+) -> Result<(users::Model, students::Model, ExportTemplateConfig, ExportRecordScope), AppError> {
+    let user = require_session_user(state, jar).await?;
+    let scope = authorize_export(&user, ExportTarget::Student { student_no }, state.config.student_self_export)?;
 
     let student = Student::find()
-        .filter(students::Column::StudentNo.eq(&student_no))
+        .filter(students::Column::StudentNo.eq(student_no))
         .filter(students::Column::IsDeleted.eq(false))
         .one(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?
         .ok_or_else(|| AppError::not_found("student not found"))?;
+    ensure_student_in_scope(&user, &student)?;
+    let template = labor_hours_template(state, selection.template_key.as_deref()).await?;
+    Ok((user, student, template, scope))
+}
+
+/// 批量导出劳动教育学时认定表的筛选条件。
+#[derive(Debug, Deserialize)]
+pub struct LaborHoursBatchQuery {
+    /// 院系筛选。
+    pub department: Option<String>,
+    /// 专业筛选。
+    pub major: Option<String>,
+    /// 班级筛选。
+    pub class_name: Option<String>,
+    /// 导出模板 key，默认内置的 `labor_hours`。
+    pub template_key: Option<String>,
+}
+
+/// 批量导出劳动教育学时认定表（每位学生一份 PDF，打包为 ZIP）。
+pub async fn export_labor_hours_batch(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(query): Json<LaborHoursBatchQuery>,
+) -> Result<Response, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    authorize_export(&user, ExportTarget::LaborHoursBatch, state.config.student_self_export)?;
 
-    let records = ContestRecord::find()
-        .filter(contest_records::Column::StudentId.eq(student.id))
-        .filter(contest_records::Column::IsDeleted.eq(false))
+    let filter = RecordFilter {
+        department: query.department,
+        major: query.major,
+        class_name: query.class_name,
+        ..Default::default()
+    };
+    let mut condition = Condition::all()
+        .add(students::Column::IsDeleted.eq(false))
+        .add(filter.student_condition());
+    if let Some(department) = department_scope(&user) {
+        condition = condition.add(students::Column::Department.eq(department));
+    }
+    let students = Student::find()
+        .filter(condition)
+        .order_by_asc(students::Column::StudentNo)
         .all(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    if students.is_empty() {
+        return Err(AppError::not_found("no students matched"));
+    }
+    if students.len() > MAX_BATCH_PDF_STUDENTS {
+        return Err(AppError::validation(&format!(
+            "too many students: at most {MAX_BATCH_PDF_STUDENTS} per batch"
+        )));
+    }
+    let template = labor_hours_template(&state, query.template_key.as_deref()).await?;
+    if !export_template_file_path(&state, &template.template_key).exists() {
+        return Err(AppError::bad_request("export template not configured"));
+    }
 
-    let form_fields = load_export_fields(&state, "contest").await?;
+    let user_id = user.id;
+    let mut tasks = tokio::task::JoinSet::new();
+    for student in students {
+        let state = state.clone();
+        let template = template.clone();
+        tasks.spawn(async move {
+            let result =
+                render_labor_hours_pdf(&state, &student, &template, ExportRecordScope::All, user_id).await;
+            (student, result)
+        });
+    }
+    let mut rendered = Vec::new();
+    let mut failures = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        let (student, result) =
+            joined.map_err(|err| AppError::internal(&format!("render task failed: {err}")))?;
+        match result {
+            Ok(bytes) => rendered.push((labor_hours_pdf_entry_name(&student), bytes)),
+            Err(err) => {
+                tracing::warn!(student_no = %student.student_no, error = %err, "batch labor hours pdf failed");
+                failures.push(format!("{}\t{}\t{}", student.student_no, student.name, err));
+            }
+        }
+    }
+    if rendered.is_empty() {
+        return Err(AppError::internal("all pdf renders failed"));
+    }
+    rendered.sort_by(|a, b| a.0.cmp(&b.0));
+    failures.sort();
+
+    let file = tokio::task::spawn_blocking(move || write_zip_archive(rendered, failures))
+        .await
+        .map_err(|err| AppError::internal(&format!("write zip failed: {err}")))??;
+    stream_file_response("labor-hours.zip", "application/zip", file)
+}
+
+/// 解析学时认定表导出使用的模板（默认内置模板）。
+async fn labor_hours_template(state: &AppState, template_key: Option<&str>) -> Result<ExportTemplateConfig, AppError> {
+    let template_key = template_key
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(BUILTIN_EXPORT_TEMPLATE_KEY);
+    require_export_template(state, template_key, Some("labor_hours")).await
+}
+
+/// 渲染单个学生的劳动教育学时认定表 PDF 并登记核验令牌；转换在全局队列中排队执行。
+async fn render_labor_hours_pdf(
+    state: &AppState,
+    student: &students::Model,
+    template: &ExportTemplateConfig,
+    scope: ExportRecordScope,
+    issued_by: Uuid,
+) -> Result<Vec<u8>, AppError> {
+    let verification = PendingVerification::issue(&state.config);
+    let rendered = render_labor_hours_xlsx(state, student, template, scope, &verification).await?;
+    let buffer = state
+        .pdf_converter
+        .convert(&rendered.output_xlsx, rendered.temp_dir.path())
+        .await?;
+    verification
+        .record(
+            &state.db,
+            DOCUMENT_LABOR_HOURS_PDF,
+            student,
+            rendered.approved_hours,
+            &buffer,
+            issued_by,
+        )
+        .await?;
+    Ok(buffer)
+}
+
+/// 填充后的劳动教育学时认定表。
+struct RenderedLaborHours {
+    /// 临时目录，删除后其中的文件随之删除。
+    temp_dir: tempfile::TempDir,
+    /// 临时目录中的 XLSX 路径。
+    output_xlsx: std::path::PathBuf,
+    /// 表中的计入学时。
+    approved_hours: i32,
+}
+
+/// 用导出模板填充单个学生的劳动教育学时认定表，并写入核验链接与二维码。
+async fn render_labor_hours_xlsx(
+    state: &AppState,
+    student: &students::Model,
+    template: &ExportTemplateConfig,
+    scope: ExportRecordScope,
+    verification: &PendingVerification,
+) -> Result<RenderedLaborHours, AppError> {
+    let records = load_student_contest_records(state, student.id, false, scope).await?;
+
+    let form_fields = load_form_fields(&state.db, "contest").await?;
     let record_ids: Vec<Uuid> = records.iter().map(|record| record.id).collect();
-    let custom_fields = load_custom_field_values(&state, "contest", &record_ids, &form_fields).await?;
-    let (self_hours, approved_hours, reason) =
-        compute_student_hours(&state, student.id).await?;
+    let custom_fields = load_custom_field_values(state, "contest", &record_ids, &form_fields).await?;
+    let totals = compute_student_hours(state, student.id, scope).await?;
 
-    let rule_config = load_labor_hour_rules(&state).await?;
-    let signature_bundle = load_reviewer_signatures(&state, &records).await?;
+    let rule_config = load_labor_hour_rules(state).await?;
+    let signature_bundle = load_reviewer_signatures(state, &records).await?;
 
-    let template_meta = load_export_template(&state, "labor_hours").await?;
-    let template_path = export_template_file_path(&state, "labor_hours");
+    let template_path = export_template_file_path(state, &template.template_key);
     if !template_path.exists() {
         return Err(AppError::bad_request("export template not configured"));
     }
 
-    let single_values = build_single_values(
-        &student,
-        self_hours,
-        approved_hours,
-        &reason,
+    let mut single_values = build_single_values(
+        student,
+        totals.self_hours,
+        totals.capped_hours,
+        &totals.reason,
         &signature_bundle,
     );
+    single_values.insert("verification_url".to_string(), verification.url.clone());
+    let qr = render_qr_image(&verification.url)?;
+    let approved_hours = totals.capped_hours;
     let list_values = build_list_values(&records, &custom_fields, rule_config);
-
-    let temp_dir = tempfile::tempdir()
-        .map_err(|_| AppError::internal("create temp dir failed"))?;
-    let output_xlsx = temp_dir.path().join("labor_hours.xlsx");
-    let orientation = if template_meta.orientation == "landscape" {
+    let orientation = if template.orientation == "landscape" {
         umya_spreadsheet::structs::OrientationValues::Landscape
     } else {
         umya_spreadsheet::structs::OrientationValues::Portrait
     };
-    render_template_to_xlsx(
-        &template_path,
-        &output_xlsx,
-        &single_values,
-        &list_values,
-        orientation,
-    )?;
-    let buffer = convert_xlsx_to_pdf(&state.config.libreoffice_path, &output_xlsx, temp_dir.path())?;
 
-    Ok(file_response(
-        format!("{}-labor-hours.pdf", student.student_no),
-        "application/pdf",
-        buffer,
-    ))
+    tokio::task::spawn_blocking(move || {
+        let temp_dir = tempfile::tempdir()
+            .map_err(|_| AppError::internal("create temp dir failed"))?;
+        let qr_path = temp_dir.path().join("verification_qr.png");
+        qr.save(&qr_path)
+            .map_err(|err| AppError::internal(&format!("write qr code failed: {err}")))?;
+        single_values.insert(
+            "verification_qr_image".to_string(),
+            qr_path.to_string_lossy().into_owned(),
+        );
+        let output_xlsx = temp_dir.path().join("labor_hours.xlsx");
+        render_template_to_xlsx(
+            &template_path,
+            &output_xlsx,
+            &single_values,
+            &list_values,
+            orientation,
+        )?;
+        Ok::<_, AppError>(RenderedLaborHours {
+            temp_dir,
+            output_xlsx,
+            approved_hours,
+        })
+    })
+    .await
+    .map_err(|err| AppError::internal(&format!("render template failed: {err}")))?
 }
 
+/// ZIP 内的 PDF 文件名（学号-姓名）。
+fn labor_hours_pdf_entry_name(student: &students::Model) -> String {
+    format!(
+        "{}-{}-labor-hours.pdf",
+        sanitize_file_component(&student.student_no),
+        sanitize_file_component(&student.name)
+    )
+}
+
+fn sanitize_file_component(value: &str) -> String {
+    value
+        .trim()
+        .chars()
+        .map(|ch| if matches!(ch, '/' | '\\' | ':' | '\0') { '_' } else { ch })
+        .collect()
+}
+
+/// 将 PDF 写入临时 ZIP 文件；渲染失败的学生列在 `errors.txt` 中。
+fn write_zip_archive(entries: Vec<(String, Vec<u8>)>, failures: Vec<String>) -> Result<std::fs::File, AppError> {
+    let file = tempfile::tempfile()
+        .map_err(|err| AppError::internal(&format!("create temp file failed: {err}")))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (name, bytes) in entries {
+        zip.start_file(name, options)
+            .map_err(|err| AppError::internal(&format!("write zip failed: {err}")))?;
+        zip.write_all(&bytes)
+            .map_err(|err| AppError::internal(&format!("write zip failed: {err}")))?;
+    }
+    if !failures.is_empty() {
+        zip.start_file("errors.txt", options)
+            .map_err(|err| AppError::internal(&format!("write zip failed: {err}")))?;
+        zip.write_all(failures.join("\n").as_bytes())
+            .map_err(|err| AppError::internal(&format!("write zip failed: {err}")))?;
+    }
+    let mut file = zip
+        .finish()
+        .map_err(|err| AppError::internal(&format!("write zip failed: {err}")))?;
+    file.seek(SeekFrom::Start(0))
+        .map_err(|err| AppError::internal(&format!("rewind temp file failed: {err}")))?;
+    Ok(file)
+}
+
+/// 计算单个学生的学时汇总；计入学时按每学年上限截断。
 async fn compute_student_hours(
     state: &AppState,
     student_id: Uuid,
-) -> Result<(i32, i32, String), AppError> {
-    let contest = ContestRecord::find()
-        .filter(contest_records::Column::StudentId.eq(student_id))
-        .filter(contest_records::Column::IsDeleted.eq(false))
+    scope: ExportRecordScope,
+) -> Result<HourTotals, AppError> {
+    let contest = load_student_contest_records(state, student_id, false, scope).await?;
+    let (self_hours, approved_hours, reason) = summarize_records(&contest, RecordVisibility::default());
+    let rule_config = load_labor_hour_rules(state).await?;
+    let capped_hours = match rule_config.annual_cap_hours {
+        Some(cap) => {
+            let semester_years = load_semester_years(&state.db).await?;
+            let rows = contest
+                .iter()
+                .filter(|record| !record.is_deleted && record.status == "final_reviewed")
+                .map(|record| (record.student_id, record.semester_id, record.created_at, record.final_review_hours));
+            group_hours_by_year(rows, &semester_years)
+                .get(&student_id)
+                .map(|years| capped_total(years, Some(cap)))
+                .unwrap_or(0)
+        }
+        None => approved_hours,
+    };
+    Ok(HourTotals {
+        self_hours,
+        approved_hours,
+        capped_hours,
+        reason,
+    })
+}
+
+async fn load_student_contest_records(
+    state: &AppState,
+    student_id: Uuid,
+    include_withdrawn: bool,
+    scope: ExportRecordScope,
+) -> Result<Vec<contest_records::Model>, AppError> {
+    let mut finder = ContestRecord::find().filter(contest_records::Column::StudentId.eq(student_id));
+    if !include_withdrawn {
+        finder = finder.filter(contest_records::Column::IsDeleted.eq(false));
+    }
+    if scope == ExportRecordScope::FinalReviewedOnly {
+        finder = finder.filter(contest_records::Column::Status.eq("final_reviewed"));
+    }
+    finder
         .all(&state.db)
         .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
+        .map_err(|err| AppError::Database(err.to_string()))
+}
 
+/// 汇总自评学时、通过学时与不通过原因；撤回记录不计入汇总。
+fn summarize_records(
+    records: &[contest_records::Model],
+    visibility: RecordVisibility,
+) -> (i32, i32, String) {
     let mut self_hours = 0;
     let mut approved = 0;
     let mut reasons = Vec::new();
 
-    for record in contest {
+    for record in records.iter().filter(|record| !record.is_deleted) {
+        if record.status == "rejected" {
+            if !visibility.include_rejected {
+                continue;
+            }
+            if let Some(reason) = record.rejection_reason.as_ref() {
+                reasons.push(reason.clone());
+            }
+        }
         self_hours += record.self_hours;
         if record.status == "final_reviewed" {
             approved += record.final_review_hours.unwrap_or(0);
         }
-        if record.status == "rejected" {
-            if let Some(reason) = record.rejection_reason {
-                reasons.push(reason);
-            }
+    }
+
+    (self_hours, approved, reasons.join(";"))
+}
+
+/// 单个学生的学时汇总。
+#[derive(Debug, Default, Clone, PartialEq)]
+struct HourTotals {
+    self_hours: i32,
+    approved_hours: i32,
+    /// 按每学年上限截断后的计入学时；未配置上限时等于通过学时。
+    capped_hours: i32,
+    reason: String,
+}
+
+/// 分组聚合查询的结果行。
+#[derive(Debug, FromQueryResult)]
+struct HourTotalsRow {
+    student_id: Uuid,
+    self_hours: Option<i64>,
+    approved_hours: Option<i64>,
+}
+
+/// 汇总导出的学生筛选条件（含院系与班主任班级数据范围）。
+async fn summary_student_condition(
+    state: &AppState,
+    user: &users::Model,
+    query: &ExportSummaryQuery,
+) -> Result<Condition, AppError> {
+    let classes = advisor_class_scope(&state.db, user).await?;
+    Ok(Condition::all()
+        .add(students::Column::IsDeleted.eq(false))
+        .add(student_scope_condition(user, classes.as_deref()))
+        .add(query.filter.student_condition()))
+}
+
+/// 汇总导出的学生，按学号排序；按班级分组时先按班级排序。
+async fn summary_students(
+    state: &AppState,
+    condition: Condition,
+    group_by_class: bool,
+) -> Result<Vec<students::Model>, AppError> {
+    let mut finder = Student::find().filter(condition);
+    if group_by_class {
+        finder = finder.order_by_asc(students::Column::ClassName);
+    }
+    finder
+        .order_by_asc(students::Column::StudentNo)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))
+}
+
+/// 汇总导出的记录筛选条件：学生条件叠加记录级筛选，匹配状态与竞赛库比对后转为 ID 条件。
+async fn summary_record_condition(
+    state: &AppState,
+    student_condition: Condition,
+    filter: &RecordFilter,
+) -> Result<Condition, AppError> {
+    let mut condition = student_condition.add(filter.record_condition());
+    if let Some(status) = filter.match_status.as_deref() {
+        let ids = record_ids_with_match_status(state, condition.clone(), status).await?;
+        condition = condition.add(contest_records::Column::Id.is_in(ids));
+    }
+    Ok(condition)
+}
+
+/// 按学生分组聚合学时（一次查询），不通过原因另查一次；撤回记录不计入汇总。
+async fn aggregate_student_hours<C>(
+    db: &C,
+    condition: Condition,
+    visibility: RecordVisibility,
+) -> Result<HashMap<Uuid, HourTotals>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let backend = db.get_database_backend();
+    let mut finder = ContestRecord::find()
+        .select_only()
+        .column(contest_records::Column::StudentId)
+        .column_as(
+            sum_as_i64(backend, Expr::col((contest_records::Entity, contest_records::Column::SelfHours))),
+            "self_hours",
+        )
+        .column_as(sum_as_i64(backend, approved_hours_expr()), "approved_hours")
+        .join(JoinType::InnerJoin, contest_records::Relation::Student.def())
+        .filter(condition.clone())
+        .filter(contest_records::Column::IsDeleted.eq(false));
+    if !visibility.include_rejected {
+        finder = finder.filter(contest_records::Column::Status.ne("rejected"));
+    }
+    let rows = finder
+        .group_by(contest_records::Column::StudentId)
+        .into_model::<HourTotalsRow>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let mut totals: HashMap<Uuid, HourTotals> = rows
+        .into_iter()
+        .map(|row| {
+            let approved_hours = row.approved_hours.unwrap_or(0) as i32;
+            let total = HourTotals {
+                self_hours: row.self_hours.unwrap_or(0) as i32,
+                approved_hours,
+                capped_hours: approved_hours,
+                reason: String::new(),
+            };
+            (row.student_id, total)
+        })
+        .collect();
+
+    if visibility.include_rejected {
+        let reasons = ContestRecord::find()
+            .select_only()
+            .column(contest_records::Column::StudentId)
+            .column(contest_records::Column::RejectionReason)
+            .join(JoinType::InnerJoin, contest_records::Relation::Student.def())
+            .filter(condition)
+            .filter(contest_records::Column::IsDeleted.eq(false))
+            .filter(contest_records::Column::Status.eq("rejected"))
+            .filter(contest_records::Column::RejectionReason.is_not_null())
+            .order_by_asc(contest_records::Column::CreatedAt)
+            .into_tuple::<(Uuid, String)>()
+            .all(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        merge_rejection_reasons(&mut totals, reasons);
+    }
+    Ok(totals)
+}
+
+/// 按每学年上限重新计算计入学时（仅在配置了上限时额外查询一次）。
+async fn apply_hour_caps<C>(
+    db: &C,
+    condition: Condition,
+    annual_cap: Option<i32>,
+    totals: &mut HashMap<Uuid, HourTotals>,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    if annual_cap.is_none() {
+        return Ok(());
+    }
+    let yearly = approved_hours_by_year(db, condition).await?;
+    for (student_id, total) in totals.iter_mut() {
+        total.capped_hours = yearly
+            .get(student_id)
+            .map(|years| capped_total(years, annual_cap))
+            .unwrap_or(0);
+    }
+    Ok(())
+}
+
+fn merge_rejection_reasons(totals: &mut HashMap<Uuid, HourTotals>, reasons: Vec<(Uuid, String)>) {
+    for (student_id, reason) in reasons {
+        let total = totals.entry(student_id).or_default();
+        if !total.reason.is_empty() {
+            total.reason.push(';');
         }
+        total.reason.push_str(&reason);
     }
+}
+
+/// 读取汇总范围内的不通过（`withdrawn=false`）或已撤回记录明细。
+async fn load_summary_detail_rows(
+    state: &AppState,
+    condition: Condition,
+    withdrawn: bool,
+) -> Result<Vec<(students::Model, contest_records::Model)>, AppError> {
+    let mut finder = ContestRecord::find()
+        .find_also_related(Student)
+        .filter(condition)
+        .filter(contest_records::Column::IsDeleted.eq(withdrawn));
+    if !withdrawn {
+        finder = finder.filter(contest_records::Column::Status.eq("rejected"));
+    }
+    let rows = finder
+        .order_by_asc(students::Column::StudentNo)
+        .order_by_asc(contest_records::Column::CreatedAt)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(record, student)| student.map(|student| (student, record)))
+        .collect())
+}
+
+/// 将工作簿写入匿名临时文件后分块流式返回，避免在内存中额外保留完整文件字节。
+async fn workbook_response(
+    name: &str,
+    mut workbook: rust_xlsxwriter::Workbook,
+) -> Result<Response, AppError> {
+    let file = tokio::task::spawn_blocking(move || -> Result<std::fs::File, AppError> {
+        let mut file = tempfile::tempfile()
+            .map_err(|err| AppError::internal(&format!("create temp file failed: {err}")))?;
+        workbook
+            .save_to_writer(&mut file)
+            .map_err(|_| AppError::internal("save excel failed"))?;
+        file.seek(SeekFrom::Start(0))
+            .map_err(|err| AppError::internal(&format!("rewind temp file failed: {err}")))?;
+        Ok(file)
+    })
+    .await
+    .map_err(|err| AppError::internal(&format!("save excel failed: {err}")))??;
+
+    stream_file_response(
+        name,
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        file,
+    )
+}
 
-    Ok((self_hours, approved, reasons.join(";")))
+/// 以分块流式响应返回已写好的临时文件。
+pub(crate) fn stream_file_response(name: &str, mime: &'static str, file: std::fs::File) -> Result<Response, AppError> {
+    let body = Body::from_stream(ReaderStream::new(tokio::fs::File::from_std(file)));
+    let mut response = Response::new(body);
+    let headers = response.headers_mut();
+    headers.insert(axum::http::header::CONTENT_TYPE, HeaderValue::from_static(mime));
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        HeaderValue::from_str(&format!("attachment; filename=\"{name}\""))
+            .map_err(|_| AppError::internal("invalid file name"))?,
+    );
+    Ok(response)
+}
+
+fn write_record_detail_sheet(
+    workbook: &mut rust_xlsxwriter::Workbook,
+    locale: Locale,
+    name: &str,
+    rows: &[(students::Model, contest_records::Model)],
+) -> Result<(), AppError> {
+    let worksheet = workbook
+        .add_worksheet()
+        .set_name(name)
+        .map_err(|_| AppError::internal("write excel failed"))?;
+    for (idx, header) in RECORD_DETAIL_HEADERS.iter().enumerate() {
+        worksheet
+            .write_string(0, idx as u16, locale.text(*header))
+            .map_err(|_| AppError::internal("write excel failed"))?;
+    }
+    for (idx, (student, record)) in rows.iter().enumerate() {
+        let row = (idx + 1) as u32;
+        let values = [
+            ExportValue::Text(student.student_no.clone()),
+            ExportValue::Text(student.name.clone()),
+            ExportValue::Text(student.class_name.clone()),
+            ExportValue::Text(record.contest_name.clone()),
+            ExportValue::Text(record.award_level.clone()),
+            ExportValue::Number(record.self_hours as f64),
+            ExportValue::Text(record.status.clone()),
+            ExportValue::Text(record.rejection_reason.clone().unwrap_or_default()),
+        ];
+        for (col, value) in values.iter().enumerate() {
+            write_cell(worksheet, row, col as u16, value)?;
+        }
+    }
+    Ok(())
 }
 
 struct SignatureBundle {
@@ -646,12 +1594,7 @@ fn build_list_values(
 ) -> Vec<HashMap<String, String>> {
     let mut items = Vec::new();
     for record in records {
-        let recommended = compute_recommended_hours(
-            rule_config,
-            record.contest_category.as_deref(),
-            record.contest_level.as_deref(),
-            record.contest_role.as_deref(),
-        );
+        let recommended = record_recommended_hours(rule_config, record);
         let mut map = HashMap::new();
         map.insert(
             "contest_year".to_string(),
@@ -720,54 +1663,11 @@ fn build_list_values(
     items
 }
 
-fn convert_xlsx_to_pdf(
-    libreoffice_path: &str,
-    input_path: &StdPath,
-    output_dir: &StdPath,
-) -> Result<Vec<u8>, AppError> {
-    // 测试环境使用内置简易 PDF，避免依赖外部转换程序。
-    if libreoffice_path == "internal" {
-        let (doc, page, layer) =
-            PdfDocument::new("劳动教育学时认定表", Mm(210.0), Mm(297.0), "Layer");
-        let font = doc
-            .add_builtin_font(BuiltinFont::Helvetica)
-            .map_err(|_| AppError::internal("load font failed"))?;
-        let layer = doc.get_page(page).get_layer(layer);
-        layer.use_text("模板转换未启用（internal）", 12.0, Mm(20.0), Mm(280.0), &font);
-        let mut writer = BufWriter::new(Cursor::new(Vec::new()));
-        doc.save(&mut writer)
-            .map_err(|_| AppError::internal("save pdf failed"))?;
-        let cursor = writer
-            .into_inner()
-            .map_err(|_| AppError::internal("save pdf failed"))?;
-        return Ok(cursor.into_inner());
-    }
-
-    let status = Command::new(libreoffice_path)
-        .arg("--headless")
-        .arg("--convert-to")
-        .arg("pdf")
-        .arg("--outdir")
-        .arg(output_dir)
-        .arg(input_path)
-        .status()
-        .map_err(|_| AppError::internal("run libreoffice failed"))?;
-    if !status.success() {
-        return Err(AppError::internal("libreoffice conversion failed"));
-    }
-    let file_stem = input_path
-        .file_stem()
-        .and_then(|value| value.to_str())
-        .ok_or_else(|| AppError::internal("invalid template filename"))?;
-    let pdf_path = output_dir.join(format!("{file_stem}.pdf"));
-    std::fs::read(&pdf_path).map_err(|_| AppError::internal("read pdf failed"))
-}
-
 async fn load_custom_field_values(
     state: &AppState,
     record_type: &str,
     record_ids: &[Uuid],
-    fields: &[ExportField],
+    fields: &[form_fields::Model],
 ) -> Result<HashMap<Uuid, HashMap<String, String>>, AppError> {
     if record_ids.is_empty() {
         return Ok(HashMap::new());
@@ -797,7 +1697,7 @@ async fn load_custom_field_values(
     Ok(grouped)
 }
 
-fn file_response(name: impl Into<String>, mime: &str, bytes: Vec<u8>) -> Response {
+pub(crate) fn file_response(name: impl Into<String>, mime: &str, bytes: Vec<u8>) -> Response {
     let mut response = bytes.into_response();
     let name = name.into();
     let headers = response.headers_mut();
@@ -828,70 +1728,82 @@ struct ExportField {
     field_key: String,
     label: String,
     order_index: i32,
+    number_format: Option<String>,
+    numeric: bool,
+    summable: bool,
 }
 
-async fn load_export_fields(state: &AppState, form_type: &str) -> Result<Vec<ExportField>, AppError> {
-    let mut fields = FormField::find()
-        .filter(form_fields::Column::FormType.eq(form_type))
-        .all(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-        .into_iter()
-        .map(|field| ExportField {
-            field_key: field.field_key,
-            label: field.label,
-            order_index: field.order_index,
+fn permitted_summary_columns(role: &str) -> Vec<&'static str> {
+    SUMMARY_EXPORT
+        .columns
+        .iter()
+        .map(|column| column.key)
+        .filter(|key| role != "reviewer" || *key != "phone")
+        .collect()
+}
+
+/// 按本次请求指定的列导出汇总表；标题与数字格式沿用已配置的导出列。
+fn select_summary_columns(
+    locale: Locale,
+    role: &str,
+    columns: &[String],
+    configured: &[ExportColumn],
+) -> Result<Vec<ExportField>, AppError> {
+    if columns.is_empty() {
+        return Err(AppError::validation("columns must not be empty"));
+    }
+    let permitted = permitted_summary_columns(role);
+    let mut selected: Vec<ExportField> = Vec::with_capacity(columns.len());
+    for (idx, column) in columns.iter().enumerate() {
+        let key = column.trim();
+        if !permitted.contains(&key) {
+            return Err(AppError::validation(&format!("column not allowed: {key}")));
+        }
+        if selected.iter().any(|field| field.field_key == key) {
+            return Err(AppError::validation(&format!("duplicate column: {key}")));
+        }
+        let column = configured
+            .iter()
+            .find(|field| field.field_key == key)
+            .cloned()
+            .unwrap_or_else(|| ExportColumn {
+                field_key: key.to_string(),
+                label: None,
+                number_format: None,
+            });
+        let def = SUMMARY_EXPORT.column(key);
+        selected.push(ExportField {
+            label: SUMMARY_EXPORT.column_label(locale, &column),
+            field_key: column.field_key,
+            order_index: idx as i32 + 1,
+            number_format: column.number_format,
+            numeric: def.is_some_and(|def| def.numeric),
+            summable: def.is_some_and(|def| def.summable),
+        });
+    }
+    Ok(selected)
+}
+
+/// 按导出列配置生成导出列，顺序即列序。
+fn export_fields(locale: Locale, def: &ExportProfileDef, columns: &[ExportColumn]) -> Vec<ExportField> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| {
+            let column_def = def.column(&column.field_key);
+            ExportField {
+                field_key: column.field_key.clone(),
+                label: def.column_label(locale, column),
+                order_index: idx as i32 + 1,
+                number_format: column.number_format.clone(),
+                numeric: column_def.is_some_and(|item| item.numeric),
+                summable: column_def.is_some_and(|item| item.summable),
+            }
         })
-        .collect::<Vec<_>>();
-    fields.sort_by_key(|item| item.order_index);
-    Ok(fields)
-}
-
-fn default_summary_fields() -> Vec<ExportField> {
-    vec![
-        ExportField { field_key: "student_no".to_string(), label: "学号".to_string(), order_index: 1 },
-        ExportField { field_key: "name".to_string(), label: "姓名".to_string(), order_index: 2 },
-        ExportField { field_key: "class_name".to_string(), label: "班级".to_string(), order_index: 3 },
-        ExportField { field_key: "self_hours".to_string(), label: "个人自评学时".to_string(), order_index: 4 },
-        ExportField { field_key: "approved_hours".to_string(), label: "审核通过学时".to_string(), order_index: 5 },
-        ExportField { field_key: "reason".to_string(), label: "备注".to_string(), order_index: 6 },
-    ]
-}
-
-fn default_student_fields() -> Vec<ExportField> {
-    vec![
-        ExportField { field_key: "student_no".to_string(), label: "学号".to_string(), order_index: 1 },
-        ExportField { field_key: "name".to_string(), label: "姓名".to_string(), order_index: 2 },
-        ExportField { field_key: "self_hours".to_string(), label: "个人自评学时".to_string(), order_index: 3 },
-        ExportField { field_key: "approved_hours".to_string(), label: "审核通过学时".to_string(), order_index: 4 },
-        ExportField { field_key: "reason".to_string(), label: "备注".to_string(), order_index: 5 },
-    ]
-}
-
-fn default_labor_hours_excel_fields() -> Vec<ExportField> {
-    vec![
-        ExportField { field_key: "index".to_string(), label: "序号".to_string(), order_index: 1 },
-        ExportField { field_key: "major".to_string(), label: "专业".to_string(), order_index: 2 },
-        ExportField { field_key: "class_name".to_string(), label: "班级".to_string(), order_index: 3 },
-        ExportField { field_key: "student_no".to_string(), label: "学号".to_string(), order_index: 4 },
-        ExportField { field_key: "name".to_string(), label: "姓名".to_string(), order_index: 5 },
-        ExportField { field_key: "planned_hours".to_string(), label: "拟加学时".to_string(), order_index: 6 },
-        ExportField {
-            field_key: "module_hours".to_string(),
-            label: "生产劳动教育模块学时（不少于4学时）".to_string(),
-            order_index: 7,
-        },
-        ExportField { field_key: "reason".to_string(), label: "备注".to_string(), order_index: 8 },
-    ]
+        .collect()
 }
 
-fn resolve_export_value(
-    field_key: &str,
-    student: &students::Model,
-    self_hours: i32,
-    approved_hours: i32,
-    reason: &str,
-) -> ExportValue {
+fn resolve_export_value(field_key: &str, student: &students::Model, totals: &HourTotals) -> ExportValue {
     match field_key {
         "student_no" => ExportValue::Text(student.student_no.clone()),
         "name" => ExportValue::Text(student.name.clone()),
@@ -900,9 +1812,10 @@ fn resolve_export_value(
         "major" => ExportValue::Text(student.major.clone()),
         "class_name" => ExportValue::Text(student.class_name.clone()),
         "phone" => ExportValue::Text(student.phone.clone()),
-        "self_hours" => ExportValue::Number(self_hours as f64),
-        "approved_hours" => ExportValue::Number(approved_hours as f64),
-        "reason" => ExportValue::Text(reason.to_string()),
+        "self_hours" => ExportValue::Number(totals.self_hours as f64),
+        "approved_hours" => ExportValue::Number(totals.approved_hours as f64),
+        "capped_hours" => ExportValue::Number(totals.capped_hours as f64),
+        "reason" => ExportValue::Text(totals.reason.clone()),
         _ => ExportValue::Text(String::new()),
     }
 }
@@ -911,9 +1824,7 @@ fn resolve_labor_hours_export_value(
     field_key: &str,
     index: usize,
     student: &students::Model,
-    self_hours: i32,
-    approved_hours: i32,
-    reason: &str,
+    totals: &HourTotals,
 ) -> ExportValue {
     match field_key {
         "index" => ExportValue::Number(index as f64),
@@ -924,9 +1835,9 @@ fn resolve_labor_hours_export_value(
         "major" => ExportValue::Text(student.major.clone()),
         "class_name" => ExportValue::Text(student.class_name.clone()),
         "phone" => ExportValue::Text(student.phone.clone()),
-        "planned_hours" => ExportValue::Number(self_hours as f64),
-        "module_hours" => ExportValue::Number(approved_hours as f64),
-        "reason" => ExportValue::Text(reason.to_string()),
+        "planned_hours" => ExportValue::Number(totals.self_hours as f64),
+        "module_hours" => ExportValue::Number(totals.capped_hours as f64),
+        "reason" => ExportValue::Text(totals.reason.clone()),
         _ => ExportValue::Text(String::new()),
     }
 }
@@ -954,6 +1865,180 @@ fn write_cell(
     }
 }
 
+/// 数值列未配置数字格式时的格式：学时按整数显示。
+const DEFAULT_NUMBER_FORMAT: &str = "0";
+/// 自动列宽的上下限（字符宽度，全角字符按 2 计）。
+const MIN_COLUMN_WIDTH: usize = 8;
+const MAX_COLUMN_WIDTH: usize = 50;
+
+/// 按导出列写入的工作表：表头加粗并冻结，数值按列的数字格式写入，
+/// 可写入小计/合计公式行，结束时按内容设置列宽。
+struct FieldSheet<'a> {
+    worksheet: &'a mut rust_xlsxwriter::Worksheet,
+    fields: &'a [ExportField],
+    number_formats: Vec<rust_xlsxwriter::Format>,
+    total_formats: Vec<rust_xlsxwriter::Format>,
+    widths: Vec<usize>,
+    /// 下一行的行号（0 起始）。
+    row: u32,
+    /// 当前分组内各列的合计。
+    group_sums: Vec<f64>,
+    /// 全部数据行各列的合计。
+    grand_sums: Vec<f64>,
+}
+
+impl<'a> FieldSheet<'a> {
+    fn new(worksheet: &'a mut rust_xlsxwriter::Worksheet, fields: &'a [ExportField]) -> Result<Self, AppError> {
+        let header = rust_xlsxwriter::Format::new().set_bold();
+        for (col, field) in fields.iter().enumerate() {
+            worksheet
+                .write_string_with_format(0, col as u16, &field.label, &header)
+                .map_err(excel_error)?;
+        }
+        worksheet.set_freeze_panes(1, 0).map_err(excel_error)?;
+        let number_formats: Vec<rust_xlsxwriter::Format> = fields
+            .iter()
+            .map(|field| {
+                rust_xlsxwriter::Format::new()
+                    .set_num_format(field.number_format.as_deref().unwrap_or(DEFAULT_NUMBER_FORMAT))
+            })
+            .collect();
+        let total_formats = number_formats.iter().map(|format| format.clone().set_bold()).collect();
+        Ok(Self {
+            worksheet,
+            fields,
+            number_formats,
+            total_formats,
+            widths: fields.iter().map(|field| display_width(&field.label)).collect(),
+            row: 1,
+            group_sums: vec![0.0; fields.len()],
+            grand_sums: vec![0.0; fields.len()],
+        })
+    }
+
+    /// 写入一行数据，返回其行号。
+    fn write_row(&mut self, values: &[ExportValue]) -> Result<u32, AppError> {
+        let row = self.row;
+        for (col, value) in values.iter().enumerate().take(self.fields.len()) {
+            let width = match value {
+                ExportValue::Text(text) => {
+                    self.worksheet
+                        .write_string(row, col as u16, text)
+                        .map_err(excel_error)?;
+                    display_width(text)
+                }
+                ExportValue::Number(number) => {
+                    self.worksheet
+                        .write_number_with_format(row, col as u16, *number, &self.number_formats[col])
+                        .map_err(excel_error)?;
+                    if self.fields[col].summable {
+                        self.group_sums[col] += number;
+                        self.grand_sums[col] += number;
+                    }
+                    number.to_string().len()
+                }
+            };
+            self.widths[col] = self.widths[col].max(width);
+        }
+        self.row += 1;
+        Ok(row)
+    }
+
+    /// 写入分组小计行（`SUBTOTAL(9, ...)`，合计行不会重复计入），覆盖 `first_row` 至上一行。
+    fn write_subtotal_row(&mut self, label: &str, first_row: u32) -> Result<(), AppError> {
+        let sums = std::mem::replace(&mut self.group_sums, vec![0.0; self.fields.len()]);
+        self.write_formula_row(label, "SUBTOTAL(9,", first_row, &sums)
+    }
+
+    /// 写入合计行，覆盖 `first_row` 至上一行；含小计行时用 `SUBTOTAL` 跳过小计。
+    fn write_total_row(&mut self, label: &str, first_row: u32, has_subtotals: bool) -> Result<(), AppError> {
+        let sums = self.grand_sums.clone();
+        let function = if has_subtotals { "SUBTOTAL(9," } else { "SUM(" };
+        self.write_formula_row(label, function, first_row, &sums)
+    }
+
+    fn write_formula_row(&mut self, label: &str, function: &str, first_row: u32, sums: &[f64]) -> Result<(), AppError> {
+        let row = self.row;
+        let last_row = row.saturating_sub(1);
+        // 标题写在第一个非数值列；全部为数值列时省略。
+        if let Some(col) = self.fields.iter().position(|field| !field.numeric) {
+            let bold = rust_xlsxwriter::Format::new().set_bold();
+            self.worksheet
+                .write_string_with_format(row, col as u16, label, &bold)
+                .map_err(excel_error)?;
+            self.widths[col] = self.widths[col].max(display_width(label));
+        }
+        for (col, field) in self.fields.iter().enumerate() {
+            if !field.summable {
+                continue;
+            }
+            let name = rust_xlsxwriter::utility::column_number_to_name(col as u16);
+            let formula = rust_xlsxwriter::Formula::new(format!(
+                "={function}{name}{}:{name}{})",
+                first_row + 1,
+                last_row + 1
+            ))
+            .set_result(sums[col].to_string());
+            self.worksheet
+                .write_formula_with_format(row, col as u16, formula, &self.total_formats[col])
+                .map_err(excel_error)?;
+        }
+        self.row += 1;
+        Ok(())
+    }
+
+    /// 按内容设置列宽。
+    fn finish(self) -> Result<(), AppError> {
+        for (col, width) in self.widths.iter().enumerate() {
+            let width = (*width + 2).clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH);
+            self.worksheet
+                .set_column_width(col as u16, width as f64)
+                .map_err(excel_error)?;
+        }
+        Ok(())
+    }
+}
+
+/// 逐个学生写入数据行并写入合计行；按班级分组时学生须已按班级排序，每个班级后写入小计行。
+fn write_student_rows<F>(
+    sheet: &mut FieldSheet<'_>,
+    locale: Locale,
+    students: &[students::Model],
+    group_by_class: bool,
+    values: F,
+) -> Result<(), AppError>
+where
+    F: Fn(usize, &students::Model) -> Vec<ExportValue>,
+{
+    if students.is_empty() {
+        return Ok(());
+    }
+    let first_row = sheet.row;
+    let mut group_start = first_row;
+    for (idx, student) in students.iter().enumerate() {
+        sheet.write_row(&values(idx, student))?;
+        let group_ends = !matches!(
+            students.get(idx + 1),
+            Some(next) if next.class_name == student.class_name
+        );
+        if group_by_class && group_ends {
+            let label = locale.format("export.subtotal", &[("class", student.class_name.as_str())]);
+            sheet.write_subtotal_row(&label, group_start)?;
+            group_start = sheet.row;
+        }
+    }
+    sheet.write_total_row(locale.text("export.total"), first_row, group_by_class)
+}
+
+/// 估算单元格显示宽度（全角字符按 2 计）。
+fn display_width(text: &str) -> usize {
+    text.chars().map(|ch| if ch.is_ascii() { 1 } else { 2 }).sum()
+}
+
+fn excel_error(_: rust_xlsxwriter::XlsxError) -> AppError {
+    AppError::internal("write excel failed")
+}
+
 struct CustomFieldEntry {
     label: String,
     value: String,
@@ -1007,6 +2092,7 @@ async fn load_custom_fields(
 fn draw_table_header(
     layer: &printpdf::PdfLayerReference,
     font: &printpdf::IndirectFontRef,
+    locale: Locale,
     y: f32,
 ) -> f32 {
     let left: f32 = 20.0;
@@ -1018,8 +2104,8 @@ fn draw_table_header(
     draw_line(layer, left, y, left, y - header_height);
     draw_line(layer, mid, y, mid, y - header_height);
     draw_line(layer, right, y, right, y - header_height);
-    layer.use_text("字段", 10.0, Mm(left + 2.0), Mm(y - 7.0), font);
-    layer.use_text("内容", 10.0, Mm(mid + 2.0), Mm(y - 7.0), font);
+    layer.use_text(locale.text("pdf.record.field"), 10.0, Mm(left + 2.0), Mm(y - 7.0), font);
+    layer.use_text(locale.text("pdf.record.value"), 10.0, Mm(mid + 2.0), Mm(y - 7.0), font);
     y - header_height
 }
 
@@ -1091,12 +2177,20 @@ mod tests {
             major: "软件工程".to_string(),
             class_name: "软工1班".to_string(),
             phone: "13800000000".to_string(),
+            import_batch_id: None,
             is_deleted: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
     }
 
+    #[test]
+    fn recovery_codes_pdf_renders() {
+        let codes = vec!["ABCD-EFGH".to_string(), "IJKL-MNOP".to_string()];
+        let bytes = render_recovery_codes_pdf(Locale::ZhCn, "2023001", Utc::now(), &codes).unwrap();
+        assert!(bytes.starts_with(b"%PDF"));
+    }
+
     #[test]
     fn wrap_text_handles_empty() {
         let lines = wrap_text("", 4);
@@ -1112,37 +2206,259 @@ mod tests {
     #[test]
     fn resolve_export_value_maps_fields() {
         let student = build_student();
-        let value = resolve_export_value("student_no", &student, 3, 2, "原因");
+        let totals = HourTotals {
+            self_hours: 3,
+            approved_hours: 2,
+            capped_hours: 1,
+            reason: "原因".to_string(),
+        };
+        let value = resolve_export_value("student_no", &student, &totals);
         match value {
             ExportValue::Text(text) => assert_eq!(text, "2023001"),
             _ => panic!("unexpected value"),
         }
 
-        let value = resolve_export_value("approved_hours", &student, 3, 2, "原因");
+        let value = resolve_export_value("approved_hours", &student, &totals);
         match value {
             ExportValue::Number(num) => assert_eq!(num, 2.0),
             _ => panic!("unexpected value"),
         }
 
-        let value = resolve_export_value("reason", &student, 3, 2, "原因");
+        let value = resolve_export_value("capped_hours", &student, &totals);
+        match value {
+            ExportValue::Number(num) => assert_eq!(num, 1.0),
+            _ => panic!("unexpected value"),
+        }
+
+        let value = resolve_export_value("reason", &student, &totals);
         match value {
             ExportValue::Text(text) => assert_eq!(text, "原因"),
             _ => panic!("unexpected value"),
         }
 
-        let value = resolve_export_value("unknown", &student, 3, 2, "原因");
+        let value = resolve_export_value("unknown", &student, &totals);
         match value {
             ExportValue::Text(text) => assert!(text.is_empty()),
             _ => panic!("unexpected value"),
         }
     }
 
+    fn build_record(status: &str, hours: i32, reason: Option<&str>, is_deleted: bool) -> contest_records::Model {
+        contest_records::Model {
+            id: Uuid::new_v4(),
+            student_id: Uuid::new_v4(),
+            contest_year: None,
+            contest_category: None,
+            contest_name: "数学建模".to_string(),
+            contest_level: None,
+            contest_role: None,
+            award_level: "一等奖".to_string(),
+            award_date: None,
+            self_hours: hours,
+            first_review_hours: None,
+            final_review_hours: Some(hours),
+            first_reviewer_id: None,
+            final_reviewer_id: None,
+            competition_id: None,
+            semester_id: None,
+            assigned_reviewer_id: None,
+            status: status.to_string(),
+            rejection_reason: reason.map(|value| value.to_string()),
+            version: 0,
+            recommended_hours: None,
+            first_reviewed_at: None,
+            final_reviewed_at: None,
+            import_batch_id: None,
+            is_deleted,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn summarize_records_respects_visibility() {
+        let records = vec![
+            build_record("final_reviewed", 4, None, false),
+            build_record("rejected", 2, Some("材料不全"), false),
+            build_record("final_reviewed", 8, None, true),
+        ];
+        let (self_hours, approved, reason) = summarize_records(&records, RecordVisibility::default());
+        assert_eq!((self_hours, approved, reason.as_str()), (6, 4, "材料不全"));
+
+        let hidden = RecordVisibility {
+            include_rejected: false,
+            include_withdrawn: true,
+        };
+        let (self_hours, approved, reason) = summarize_records(&records, hidden);
+        assert_eq!((self_hours, approved, reason.as_str()), (4, 4, ""));
+    }
+
+    #[tokio::test]
+    async fn aggregate_student_hours_matches_per_record_summary() {
+        let db = crate::services::test_support::memory_db().await;
+        let student = build_student();
+        let active: students::ActiveModel = student.clone().into();
+        students::Entity::insert(active)
+            .exec_without_returning(&db)
+            .await
+            .expect("insert student");
+        let records = vec![
+            build_record("final_reviewed", 4, None, false),
+            build_record("rejected", 2, Some("材料不全"), false),
+            build_record("final_reviewed", 8, None, true),
+        ];
+        for mut record in records {
+            record.student_id = student.id;
+            let active: contest_records::ActiveModel = record.into();
+            contest_records::Entity::insert(active)
+                .exec_without_returning(&db)
+                .await
+                .expect("insert record");
+        }
+        let condition = Condition::all().add(students::Column::IsDeleted.eq(false));
+
+        let totals = aggregate_student_hours(&db, condition.clone(), RecordVisibility::default())
+            .await
+            .expect("aggregate");
+        let total = totals.get(&student.id).expect("student totals");
+        assert_eq!((total.self_hours, total.approved_hours, total.reason.as_str()), (6, 4, "材料不全"));
+
+        let hidden = RecordVisibility {
+            include_rejected: false,
+            include_withdrawn: false,
+        };
+        let totals = aggregate_student_hours(&db, condition, hidden).await.expect("aggregate");
+        let total = totals.get(&student.id).expect("student totals");
+        assert_eq!((total.self_hours, total.approved_hours, total.reason.as_str()), (4, 4, ""));
+    }
+
+    #[test]
+    fn parse_semester_maps_terms_to_date_ranges() {
+        let (start, end) = parse_semester("2025-2026-1").expect("first term");
+        assert_eq!(start.to_rfc3339(), "2025-09-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2026-02-01T00:00:00+00:00");
+        let (start, end) = parse_semester("2025-2026-2").expect("second term");
+        assert_eq!(start.to_rfc3339(), "2026-02-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2026-09-01T00:00:00+00:00");
+        assert!(parse_semester("2025-2027-1").is_err());
+        assert!(parse_semester("2025-2026-3").is_err());
+        assert!(parse_semester("2025").is_err());
+    }
+
+    #[test]
+    fn select_summary_columns_validates_role_and_keeps_order() {
+        let configured = vec![ExportColumn {
+            field_key: "approved_hours".to_string(),
+            label: Some("通过学时".to_string()),
+            number_format: Some("0.0".to_string()),
+        }];
+        let columns = vec!["approved_hours".to_string(), "student_no".to_string()];
+        let selected = select_summary_columns(Locale::ZhCn, "teacher", &columns, &configured).expect("select");
+        assert_eq!(selected[0].label, "通过学时");
+        assert_eq!(selected[0].number_format.as_deref(), Some("0.0"));
+        assert_eq!(selected[1].label, "学号");
+        assert_eq!(selected[1].order_index, 2);
+
+        assert!(select_summary_columns(Locale::ZhCn, "reviewer", &["phone".to_string()], &[]).is_err());
+        assert!(select_summary_columns(Locale::ZhCn, "admin", &["phone".to_string()], &[]).is_ok());
+        assert!(select_summary_columns(Locale::ZhCn, "admin", &["unknown".to_string()], &[]).is_err());
+        assert!(select_summary_columns(Locale::ZhCn, "admin", &["name".to_string(), "name".to_string()], &[]).is_err());
+        assert!(select_summary_columns(Locale::ZhCn, "admin", &[], &[]).is_err());
+    }
+
     #[test]
     fn default_fields_are_ordered() {
-        let summary = default_summary_fields();
+        let summary = export_fields(Locale::ZhCn, &SUMMARY_EXPORT, &SUMMARY_EXPORT.default_columns());
         assert!(summary.windows(2).all(|pair| pair[0].order_index < pair[1].order_index));
-        let student = default_student_fields();
+        let student = export_fields(Locale::ZhCn, &STUDENT_EXPORT, &STUDENT_EXPORT.default_columns());
         assert!(student.windows(2).all(|pair| pair[0].order_index < pair[1].order_index));
+        assert_eq!(student[0].label, "学号");
+        let labor = export_fields(
+            Locale::EnUs,
+            &LABOR_HOURS_EXCEL_EXPORT,
+            &LABOR_HOURS_EXCEL_EXPORT.default_columns(),
+        );
+        assert_eq!(labor[0].field_key, "index");
+        assert_eq!(labor[3].label, "Student No.");
+    }
+
+    #[test]
+    fn write_zip_archive_lists_entries_and_failures() {
+        let mut student = build_student();
+        student.name = "张/三".to_string();
+        let name = labor_hours_pdf_entry_name(&student);
+        assert!(name.ends_with("-张_三-labor-hours.pdf"));
+
+        let file = write_zip_archive(
+            vec![(name.clone(), b"%PDF-1.4".to_vec())],
+            vec!["2023002\t李四\trender failed".to_string()],
+        )
+        .unwrap();
+        let mut archive = zip::ZipArchive::new(file).unwrap();
+        assert_eq!(archive.len(), 2);
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("errors.txt").unwrap(), &mut content).unwrap();
+        assert!(content.contains("2023002"));
+        assert!(archive.by_name(&name).is_ok());
+    }
+
+    fn build_class_student(student_no: &str, class_name: &str) -> students::Model {
+        let mut student = build_student();
+        student.student_no = student_no.to_string();
+        student.class_name = class_name.to_string();
+        student
+    }
+
+    #[test]
+    fn field_sheet_writes_subtotals_and_totals() {
+        let fields = export_fields(
+            Locale::ZhCn,
+            &LABOR_HOURS_EXCEL_EXPORT,
+            &LABOR_HOURS_EXCEL_EXPORT.default_columns(),
+        );
+        let students = vec![
+            build_class_student("2023001", "软工1班"),
+            build_class_student("2023002", "软工1班"),
+            build_class_student("2023003", "软工2班"),
+        ];
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let mut sheet = FieldSheet::new(workbook.add_worksheet(), &fields).unwrap();
+        write_student_rows(&mut sheet, Locale::ZhCn, &students, true, |idx, student| {
+            let totals = HourTotals {
+                self_hours: 2,
+                capped_hours: idx as i32 + 1,
+                ..Default::default()
+            };
+            fields
+                .iter()
+                .map(|field| resolve_labor_hours_export_value(&field.field_key, idx + 1, student, &totals))
+                .collect()
+        })
+        .unwrap();
+        // 3 行数据 + 2 行小计 + 1 行合计。
+        assert_eq!(sheet.row, 7);
+        assert_eq!(sheet.grand_sums[6], 6.0);
+        assert_eq!(sheet.grand_sums[0], 0.0);
+        assert!(sheet.widths[6] <= MAX_COLUMN_WIDTH);
+        sheet.finish().unwrap();
+        assert!(workbook.save_to_buffer().is_ok());
+
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let mut sheet = FieldSheet::new(workbook.add_worksheet(), &fields).unwrap();
+        write_student_rows(&mut sheet, Locale::ZhCn, &students, false, |idx, student| {
+            fields
+                .iter()
+                .map(|field| resolve_labor_hours_export_value(&field.field_key, idx + 1, student, &HourTotals::default()))
+                .collect()
+        })
+        .unwrap();
+        assert_eq!(sheet.row, 5);
+    }
+
+    #[test]
+    fn display_width_counts_wide_characters() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("学号"), 4);
     }
 
     #[test]
@@ -0,0 +1,336 @@
+//! 签名下载链接：签发、下载，以及导出接口的链接交付。
+
+use axum::{
+    body::Body,
+    extract::{Path, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::Utc;
+use sea_orm::EntityTrait;
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::{
+    access::{ensure_student_in_scope, require_session_user},
+    entities::{users, ContestRecord, ReviewSignature, Student, User, UserSignature},
+    error::AppError,
+    routes::attachments::{attachment_response, load_accessible_attachment, thumbnail_response},
+    signed_urls::{
+        export_artifact_dir, export_artifact_path, issue_signed_url, load_export_artifact,
+        save_export_artifact, verify_token, ExportArtifact, SignedFileKind, SignedUrl,
+    },
+    state::AppState,
+};
+
+/// 签发链接请求。
+#[derive(Debug, Deserialize)]
+pub struct SignFileRequest {
+    /// 文件类型。
+    pub kind: SignedFileKind,
+    /// 对象 ID（附件、审核签名或导出文件 ID；个人签名为用户 ID）。
+    pub id: Uuid,
+}
+
+/// 为当前用户有权访问的文件签发短时下载链接。
+pub async fn sign_file_url(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<SignFileRequest>,
+) -> Result<Json<SignedUrl>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    if !user.is_active {
+        return Err(AppError::auth("user disabled"));
+    }
+    authorize_file(&state, &user, payload.kind, payload.id).await?;
+    Ok(Json(issue_signed_url(
+        &state,
+        payload.kind,
+        payload.id,
+        user.id,
+    )))
+}
+
+/// 通过签名链接下载文件（无需会话 Cookie）。
+pub async fn download_signed_file(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Response, AppError> {
+    let claims = verify_token(&state.config.auth_secret_key, &token, Utc::now())?;
+    let user = User::find_by_id(claims.user_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .filter(|user| user.is_active)
+        .ok_or_else(|| AppError::auth("invalid signed url"))?;
+    match claims.kind {
+        SignedFileKind::Attachment => {
+            let attachment = load_accessible_attachment(&state, &user, claims.id).await?;
+            attachment_response(&attachment).await
+        }
+        SignedFileKind::Thumbnail => {
+            let attachment = load_accessible_attachment(&state, &user, claims.id).await?;
+            thumbnail_response(&state, &attachment).await
+        }
+        SignedFileKind::ReviewSignature | SignedFileKind::UserSignature => {
+            let path = signature_path(&state, &user, claims.kind, claims.id).await?;
+            let bytes = tokio::fs::read(&path)
+                .await
+                .map_err(|_| AppError::not_found("file not found"))?;
+            Ok(file_download(bytes, image_mime(&path), "signature"))
+        }
+        SignedFileKind::Export => {
+            let artifact = load_owned_export(&state, &user, claims.id).await?;
+            let file =
+                tokio::fs::File::open(export_artifact_path(&state.config.upload_dir, artifact.id))
+                    .await
+                    .map_err(|_| AppError::not_found("export not found"))?;
+            let mut response =
+                Response::new(Body::from_stream(tokio_util::io::ReaderStream::new(file)));
+            let headers = response.headers_mut();
+            headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_str(&artifact.content_type)
+                    .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream")),
+            );
+            headers.insert(
+                header::CONTENT_DISPOSITION,
+                HeaderValue::from_str(&format!("attachment; filename=\"{}\"", artifact.file_name))
+                    .unwrap_or_else(|_| HeaderValue::from_static("attachment")),
+            );
+            Ok(response)
+        }
+    }
+}
+
+/// 导出接口带 `delivery=link` 查询参数时，把生成的文件暂存并返回签名链接而非文件内容。
+pub async fn export_link_delivery(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let wants_link = request
+        .uri()
+        .query()
+        .is_some_and(|query| query.split('&').any(|pair| pair == "delivery=link"));
+    if !wants_link {
+        return next.run(request).await;
+    }
+    let jar = CookieJar::from_headers(request.headers());
+    let user = match require_session_user(&state, &jar).await {
+        Ok(user) => user,
+        Err(err) => return err.into_response(),
+    };
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+    match store_export_artifact(&state, user.id, response).await {
+        Ok(artifact) => Json(issue_signed_url(
+            &state,
+            SignedFileKind::Export,
+            artifact.id,
+            user.id,
+        ))
+        .into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+async fn store_export_artifact(
+    state: &AppState,
+    owner_id: Uuid,
+    response: Response,
+) -> Result<ExportArtifact, AppError> {
+    let (parts, body) = response.into_parts();
+    let artifact = ExportArtifact {
+        id: Uuid::new_v4(),
+        owner_id,
+        file_name: attachment_file_name(&parts.headers).unwrap_or_else(|| "export.bin".to_string()),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("application/octet-stream")
+            .to_string(),
+        created_at: Utc::now(),
+    };
+    let upload_dir = &state.config.upload_dir;
+    tokio::fs::create_dir_all(export_artifact_dir(upload_dir))
+        .await
+        .map_err(|err| AppError::internal(&format!("create export dir failed: {err}")))?;
+    let path = export_artifact_path(upload_dir, artifact.id);
+    let mut file = tokio::fs::File::create(&path)
+        .await
+        .map_err(|err| AppError::internal(&format!("write export artifact failed: {err}")))?;
+    let mut stream = body.into_data_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk =
+            chunk.map_err(|err| AppError::internal(&format!("read export body failed: {err}")))?;
+        file.write_all(&chunk)
+            .await
+            .map_err(|err| AppError::internal(&format!("write export artifact failed: {err}")))?;
+    }
+    file.flush()
+        .await
+        .map_err(|err| AppError::internal(&format!("write export artifact failed: {err}")))?;
+    save_export_artifact(upload_dir, &artifact).await?;
+    Ok(artifact)
+}
+
+/// 从 `Content-Disposition` 中取出文件名。
+fn attachment_file_name(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(header::CONTENT_DISPOSITION)?.to_str().ok()?;
+    let (_, name) = value.split_once("filename=\"")?;
+    let name = name.split('"').next()?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// 校验用户当前是否可以访问指定文件。
+async fn authorize_file(
+    state: &AppState,
+    user: &users::Model,
+    kind: SignedFileKind,
+    id: Uuid,
+) -> Result<(), AppError> {
+    match kind {
+        SignedFileKind::Attachment | SignedFileKind::Thumbnail => {
+            load_accessible_attachment(state, user, id)
+                .await
+                .map(|_| ())
+        }
+        SignedFileKind::ReviewSignature | SignedFileKind::UserSignature => {
+            signature_path(state, user, kind, id).await.map(|_| ())
+        }
+        SignedFileKind::Export => load_owned_export(state, user, id).await.map(|_| ()),
+    }
+}
+
+/// 查询签名文件路径并校验权限：审核签名限记录所属学生与数据范围内的审核人员，
+/// 个人签名限本人与管理员。
+async fn signature_path(
+    state: &AppState,
+    user: &users::Model,
+    kind: SignedFileKind,
+    id: Uuid,
+) -> Result<String, AppError> {
+    if kind == SignedFileKind::UserSignature {
+        if user.id != id && user.role != "admin" {
+            return Err(AppError::auth("forbidden"));
+        }
+        return UserSignature::find_by_id(id)
+            .one(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .map(|signature| signature.signature_path)
+            .ok_or_else(|| AppError::not_found("signature not found"));
+    }
+
+    let signature = ReviewSignature::find_by_id(id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("signature not found"))?;
+    if signature.record_type != "contest" {
+        return Err(AppError::not_found("signature not found"));
+    }
+    let record = ContestRecord::find_by_id(signature.record_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .filter(|record| !record.is_deleted)
+        .ok_or_else(|| AppError::not_found("record not found"))?;
+    let student = Student::find_by_id(record.student_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .filter(|student| !student.is_deleted)
+        .ok_or_else(|| AppError::not_found("student not found"))?;
+    match user.role.as_str() {
+        "student" if user.username == student.student_no => {}
+        "admin" | "teacher" | "reviewer" => ensure_student_in_scope(user, &student)?,
+        _ => return Err(AppError::auth("forbidden")),
+    }
+    Ok(signature.signature_path)
+}
+
+/// 读取导出文件元数据，仅发起导出的用户可以访问。
+async fn load_owned_export(
+    state: &AppState,
+    user: &users::Model,
+    id: Uuid,
+) -> Result<ExportArtifact, AppError> {
+    let artifact = load_export_artifact(&state.config.upload_dir, id).await?;
+    if artifact.owner_id != user.id {
+        return Err(AppError::auth("forbidden"));
+    }
+    Ok(artifact)
+}
+
+fn image_mime(path: &str) -> &'static str {
+    let lower = path.to_ascii_lowercase();
+    if lower.ends_with(".png") {
+        "image/png"
+    } else if lower.ends_with(".jpg") || lower.ends_with(".jpeg") {
+        "image/jpeg"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+fn file_download(bytes: Vec<u8>, mime: &'static str, name: &str) -> Response {
+    let mut response = Response::new(Body::from(bytes));
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(mime));
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    let disposition = if mime.starts_with("image/") {
+        "inline"
+    } else {
+        "attachment"
+    };
+    if let Ok(value) = HeaderValue::from_str(&format!("{disposition}; filename=\"{name}\"")) {
+        headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attachment_file_name_reads_content_disposition() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_DISPOSITION,
+            HeaderValue::from_static("attachment; filename=\"summary.xlsx\""),
+        );
+        assert_eq!(
+            attachment_file_name(&headers).as_deref(),
+            Some("summary.xlsx")
+        );
+        headers.insert(
+            header::CONTENT_DISPOSITION,
+            HeaderValue::from_static("inline"),
+        );
+        assert_eq!(attachment_file_name(&headers), None);
+    }
+
+    #[test]
+    fn image_mime_follows_extension() {
+        assert_eq!(image_mime("/data/signatures/a.PNG"), "image/png");
+        assert_eq!(image_mime("/data/signatures/a.jpeg"), "image/jpeg");
+        assert_eq!(
+            image_mime("/data/signatures/a.pdf"),
+            "application/octet-stream"
+        );
+    }
+}
@@ -1,17 +1,24 @@
 //! 表单配置读取接口。
 
-use axum::{extract::Path, Json};
+use axum::{
+    extract::{Path, Query},
+    Json,
+};
 use axum::extract::State;
 use axum_extra::extract::cookie::CookieJar;
+use chrono::{DateTime, Utc};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    access::require_session_user,
-    entities::{form_fields, FormField},
+    access::{require_session_user, require_student_profile},
+    attachment_requirements::load_requirements,
+    entities::{attachment_requirements, form_fields, FormField},
     error::AppError,
+    services::record::field_options,
     state::AppState,
+    submission_windows::{load_window, validate_record_type, window_phase, WindowPhase},
 };
 
 /// 表单字段响应。
@@ -31,6 +38,40 @@ pub struct FormFieldResponse {
     pub required: bool,
     /// 排序序号。
     pub order_index: i32,
+    /// select/radio 字段的可选值。
+    pub options: Option<Vec<String>>,
+    /// number 字段的最小值。
+    pub min_value: Option<i32>,
+    /// number 字段的最大值。
+    pub max_value: Option<i32>,
+    /// 取值最大字符数。
+    pub max_length: Option<i32>,
+}
+
+/// 提交窗口查询参数。
+#[derive(Debug, Deserialize)]
+pub struct SubmissionWindowQuery {
+    /// 院系（非学生账号可选；学生固定使用本人档案的院系）。
+    pub department: Option<String>,
+}
+
+/// 提交窗口状态响应，供前端展示倒计时。
+#[derive(Debug, Serialize)]
+pub struct SubmissionWindowStatus {
+    /// 记录类型。
+    pub record_type: String,
+    /// 是否配置了适用的窗口；未配置时不限制提交时间。
+    pub configured: bool,
+    /// 命中的院系覆盖；为空表示默认窗口。
+    pub department: Option<String>,
+    /// 开放时间。
+    pub opens_at: Option<DateTime<Utc>>,
+    /// 截止时间。
+    pub closes_at: Option<DateTime<Utc>>,
+    /// 当前状态：upcoming/open/closed。
+    pub phase: WindowPhase,
+    /// 服务器当前时间，用于校正客户端时钟。
+    pub now: DateTime<Utc>,
 }
 
 /// 按类型读取表单字段。
@@ -52,6 +93,7 @@ pub async fn list_form_fields_for_type(
         fields
             .into_iter()
             .map(|field| FormFieldResponse {
+                options: field_options(&field),
                 id: field.id,
                 form_type: field.form_type,
                 field_key: field.field_key,
@@ -59,7 +101,60 @@ pub async fn list_form_fields_for_type(
                 field_type: field.field_type,
                 required: field.required,
                 order_index: field.order_index,
+                min_value: field.min_value,
+                max_value: field.max_value,
+                max_length: field.max_length,
             })
             .collect(),
     ))
 }
+
+/// 读取记录类型当前适用的提交窗口。
+pub async fn get_submission_window(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(form_type): Path<String>,
+    Query(query): Query<SubmissionWindowQuery>,
+) -> Result<Json<SubmissionWindowStatus>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    validate_record_type(&form_type)?;
+
+    let department = if user.role == "student" {
+        Some(require_student_profile(&state, &user).await?.department)
+    } else {
+        query.department
+    };
+    let window = load_window(&state.db, &form_type, department.as_deref()).await?;
+    let now = Utc::now();
+    Ok(Json(match window {
+        Some(window) => SubmissionWindowStatus {
+            record_type: form_type,
+            configured: true,
+            phase: window_phase(&window, now),
+            department: window.department,
+            opens_at: Some(window.opens_at),
+            closes_at: Some(window.closes_at),
+            now,
+        },
+        None => SubmissionWindowStatus {
+            record_type: form_type,
+            configured: false,
+            department: None,
+            opens_at: None,
+            closes_at: None,
+            phase: WindowPhase::Open,
+            now,
+        },
+    }))
+}
+
+/// 读取记录类型的附件要求规则，供学生提交时提示需上传的佐证材料。
+pub async fn list_attachment_requirements(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(form_type): Path<String>,
+) -> Result<Json<Vec<attachment_requirements::Model>>, AppError> {
+    require_session_user(&state, &jar).await?;
+    validate_record_type(&form_type)?;
+    Ok(Json(load_requirements(&state.db, &form_type).await?))
+}
@@ -0,0 +1,525 @@
+//! 只读 GraphQL 查询接口：按需组合学生、竞赛记录、自定义字段与统计。
+//!
+//! 会话鉴权与角色/院系数据范围与对应的 REST 接口一致，需开启 `graphql_enabled`。
+
+use std::sync::OnceLock;
+
+use async_graphql::{
+    ComplexObject, Context, EmptyMutation, EmptySubscription, ErrorExtensions, InputObject, Object, Schema,
+    SimpleObject,
+};
+use axum::{extract::State, Json};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ColumnTrait, Condition, EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect, RelationTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    access::{
+        advisor_class_scope, department_scope, ensure_student_in_scope, require_session_user,
+        student_scope_condition,
+    },
+    entities::{contest_records, students, users, ContestRecord, Student},
+    error::AppError,
+    routes::{
+        records::{fetch_custom_fields, CustomFieldValueResponse},
+        stats::{build_overview, ensure_stats_role, load_stat_groups, StatsOverviewResponse},
+    },
+    services::{record::load_form_fields, review::STATUS_FINAL_REVIEWED},
+    state::AppState,
+};
+
+/// 列表查询默认条数。
+const DEFAULT_PAGE_SIZE: u64 = 50;
+/// 列表查询最大条数。
+const MAX_PAGE_SIZE: u64 = 200;
+/// 查询最大嵌套深度。
+const MAX_QUERY_DEPTH: usize = 8;
+/// 查询最大复杂度。
+const MAX_QUERY_COMPLEXITY: usize = 2000;
+
+/// GraphQL Schema（仅查询）。
+pub type ApiSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// 获取全局 Schema。
+pub fn schema() -> &'static ApiSchema {
+    static SCHEMA: OnceLock<ApiSchema> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+            .limit_depth(MAX_QUERY_DEPTH)
+            .limit_complexity(MAX_QUERY_COMPLEXITY)
+            .finish()
+    })
+}
+
+/// 执行 GraphQL 查询（需会话 Cookie）。
+pub async fn execute(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(request): Json<async_graphql::Request>,
+) -> Result<Json<async_graphql::Response>, AppError> {
+    ensure_enabled(&state)?;
+    let user = require_session_user(&state, &jar).await?;
+    let request = request.data(RequestContext { state, user });
+    Ok(Json(schema().execute(request).await))
+}
+
+/// 导出 Schema 定义（SDL），便于前端生成类型（需会话 Cookie）。
+pub async fn schema_sdl(State(state): State<AppState>, jar: CookieJar) -> Result<String, AppError> {
+    ensure_enabled(&state)?;
+    require_session_user(&state, &jar).await?;
+    Ok(schema().sdl())
+}
+
+fn ensure_enabled(state: &AppState) -> Result<(), AppError> {
+    if state.config.graphql_enabled {
+        Ok(())
+    } else {
+        Err(AppError::not_found("graphql disabled"))
+    }
+}
+
+/// 单次请求的上下文：应用状态与当前登录用户。
+struct RequestContext {
+    state: AppState,
+    user: users::Model,
+}
+
+fn request_context<'a>(ctx: &Context<'a>) -> async_graphql::Result<&'a RequestContext> {
+    ctx.data::<RequestContext>()
+}
+
+/// 将业务错误转换为 GraphQL 错误，`extensions.code` 与 REST 错误码一致。
+fn gql_error(err: AppError) -> async_graphql::Error {
+    let (_, code) = err.status_and_code();
+    async_graphql::Error::new(err.to_string()).extend_with(|_, extensions| extensions.set("code", code))
+}
+
+fn db_error(err: sea_orm::DbErr) -> async_graphql::Error {
+    gql_error(AppError::Database(err.to_string()))
+}
+
+fn ensure_staff(user: &users::Model) -> Result<(), AppError> {
+    if matches!(user.role.as_str(), "admin" | "teacher" | "reviewer") {
+        Ok(())
+    } else {
+        Err(AppError::auth("forbidden"))
+    }
+}
+
+fn page_size(limit: Option<u64>) -> u64 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+/// 当前登录用户。
+#[derive(Debug, SimpleObject)]
+pub struct Viewer {
+    /// 用户 ID。
+    pub id: Uuid,
+    /// 用户名（学生为学号）。
+    pub username: String,
+    /// 显示名称。
+    pub display_name: String,
+    /// 角色。
+    pub role: String,
+    /// 所属院系（教师/审核人员）。
+    pub department: Option<String>,
+}
+
+/// 学生档案。
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(complex, name = "Student")]
+pub struct StudentNode {
+    /// 学生 ID。
+    pub id: Uuid,
+    /// 学号。
+    pub student_no: String,
+    /// 姓名。
+    pub name: String,
+    /// 性别。
+    pub gender: String,
+    /// 院系。
+    pub department: String,
+    /// 专业。
+    pub major: String,
+    /// 班级。
+    pub class_name: String,
+    /// 手机号。
+    pub phone: String,
+}
+
+impl From<students::Model> for StudentNode {
+    fn from(model: students::Model) -> Self {
+        Self {
+            id: model.id,
+            student_no: model.student_no,
+            name: model.name,
+            gender: model.gender,
+            department: model.department,
+            major: model.major,
+            class_name: model.class_name,
+            phone: model.phone,
+        }
+    }
+}
+
+#[ComplexObject]
+impl StudentNode {
+    /// 该学生的竞赛记录。
+    async fn contest_records(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<String>,
+    ) -> async_graphql::Result<Vec<ContestRecordNode>> {
+        let RequestContext { state, .. } = request_context(ctx)?;
+        let mut finder = ContestRecord::find()
+            .filter(contest_records::Column::StudentId.eq(self.id))
+            .filter(contest_records::Column::IsDeleted.eq(false));
+        if let Some(status) = status {
+            finder = finder.filter(contest_records::Column::Status.eq(status));
+        }
+        let records = finder
+            .order_by_desc(contest_records::Column::CreatedAt)
+            .all(&state.db)
+            .await
+            .map_err(db_error)?;
+        contest_nodes(state, records).await.map_err(gql_error)
+    }
+
+    /// 终审通过的学时合计。
+    async fn approved_hours(&self, ctx: &Context<'_>) -> async_graphql::Result<i64> {
+        let RequestContext { state, .. } = request_context(ctx)?;
+        let records = ContestRecord::find()
+            .filter(contest_records::Column::StudentId.eq(self.id))
+            .filter(contest_records::Column::IsDeleted.eq(false))
+            .filter(contest_records::Column::Status.eq(STATUS_FINAL_REVIEWED))
+            .all(&state.db)
+            .await
+            .map_err(db_error)?;
+        Ok(records
+            .iter()
+            .map(|record| i64::from(record.final_review_hours.unwrap_or(0)))
+            .sum())
+    }
+}
+
+/// 自定义字段值。
+#[derive(Debug, Clone, SimpleObject)]
+pub struct CustomField {
+    /// 字段 key。
+    pub field_key: String,
+    /// 字段标签。
+    pub label: String,
+    /// 字段值。
+    pub value: String,
+}
+
+impl From<CustomFieldValueResponse> for CustomField {
+    fn from(value: CustomFieldValueResponse) -> Self {
+        Self {
+            field_key: value.field_key,
+            label: value.label,
+            value: value.value,
+        }
+    }
+}
+
+/// 竞赛记录。
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(complex, name = "ContestRecord")]
+pub struct ContestRecordNode {
+    /// 记录 ID。
+    pub id: Uuid,
+    /// 学生 ID。
+    pub student_id: Uuid,
+    /// 竞赛名称。
+    pub contest_name: String,
+    /// 竞赛年份。
+    pub contest_year: Option<i32>,
+    /// 竞赛类型。
+    pub contest_category: Option<String>,
+    /// 竞赛级别。
+    pub contest_level: Option<String>,
+    /// 竞赛角色。
+    pub contest_role: Option<String>,
+    /// 获奖等级。
+    pub award_level: String,
+    /// 获奖时间。
+    pub award_date: Option<DateTime<Utc>>,
+    /// 自评学时。
+    pub self_hours: i32,
+    /// 初审学时。
+    pub first_review_hours: Option<i32>,
+    /// 复审学时。
+    pub final_review_hours: Option<i32>,
+    /// 状态。
+    pub status: String,
+    /// 不通过原因。
+    pub rejection_reason: Option<String>,
+    /// 所属学期 ID。
+    pub semester_id: Option<Uuid>,
+    /// 指派的审核人。
+    pub assigned_reviewer_id: Option<Uuid>,
+    /// 记录版本号。
+    pub version: i32,
+    /// 提交时间。
+    pub created_at: DateTime<Utc>,
+    /// 自定义字段。
+    pub custom_fields: Vec<CustomField>,
+}
+
+#[ComplexObject]
+impl ContestRecordNode {
+    /// 提交记录的学生。
+    async fn student(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<StudentNode>> {
+        let RequestContext { state, .. } = request_context(ctx)?;
+        let student = Student::find_by_id(self.student_id)
+            .filter(students::Column::IsDeleted.eq(false))
+            .one(&state.db)
+            .await
+            .map_err(db_error)?;
+        Ok(student.map(StudentNode::from))
+    }
+}
+
+/// 批量加载自定义字段并组装竞赛记录节点。
+async fn contest_nodes(
+    state: &AppState,
+    records: Vec<contest_records::Model>,
+) -> Result<Vec<ContestRecordNode>, AppError> {
+    let form_fields = load_form_fields(&state.db, "contest").await?;
+    let ids: Vec<Uuid> = records.iter().map(|record| record.id).collect();
+    let mut custom_values = fetch_custom_fields(state, "contest", &ids, &form_fields).await?;
+    Ok(records
+        .into_iter()
+        .map(|record| ContestRecordNode {
+            custom_fields: custom_values
+                .remove(&record.id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(CustomField::from)
+                .collect(),
+            id: record.id,
+            student_id: record.student_id,
+            contest_name: record.contest_name,
+            contest_year: record.contest_year,
+            contest_category: record.contest_category,
+            contest_level: record.contest_level,
+            contest_role: record.contest_role,
+            award_level: record.award_level,
+            award_date: record.award_date,
+            self_hours: record.self_hours,
+            first_review_hours: record.first_review_hours,
+            final_review_hours: record.final_review_hours,
+            status: record.status,
+            rejection_reason: record.rejection_reason,
+            semester_id: record.semester_id,
+            assigned_reviewer_id: record.assigned_reviewer_id,
+            version: record.version,
+            created_at: record.created_at,
+        })
+        .collect())
+}
+
+/// 学生筛选条件。
+#[derive(Debug, Default, InputObject)]
+pub struct StudentFilter {
+    /// 院系。
+    pub department: Option<String>,
+    /// 专业。
+    pub major: Option<String>,
+    /// 班级。
+    pub class_name: Option<String>,
+    /// 学号或姓名关键词。
+    pub keyword: Option<String>,
+}
+
+/// 竞赛记录筛选条件。
+#[derive(Debug, Default, InputObject)]
+pub struct ContestRecordFilter {
+    /// 状态。
+    pub status: Option<String>,
+    /// 学号。
+    pub student_no: Option<String>,
+    /// 院系。
+    pub department: Option<String>,
+    /// 班级。
+    pub class_name: Option<String>,
+    /// 竞赛年份。
+    pub contest_year: Option<i32>,
+}
+
+/// 查询入口。
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// 当前登录用户。
+    async fn me(&self, ctx: &Context<'_>) -> async_graphql::Result<Viewer> {
+        let RequestContext { user, .. } = request_context(ctx)?;
+        Ok(Viewer {
+            id: user.id,
+            username: user.username.clone(),
+            display_name: user.display_name.clone(),
+            role: user.role.clone(),
+            department: user.department.clone(),
+        })
+    }
+
+    /// 学生列表（管理员/教师/审核人员，受院系数据范围限制）。
+    async fn students(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<StudentFilter>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> async_graphql::Result<Vec<StudentNode>> {
+        let RequestContext { state, user } = request_context(ctx)?;
+        ensure_staff(user).map_err(gql_error)?;
+        let filter = filter.unwrap_or_default();
+
+        let mut finder = Student::find().filter(students::Column::IsDeleted.eq(false));
+        if let Some(department) = department_scope(user) {
+            finder = finder.filter(students::Column::Department.eq(department));
+        }
+        if let Some(value) = filter.department {
+            finder = finder.filter(students::Column::Department.eq(value));
+        }
+        if let Some(value) = filter.major {
+            finder = finder.filter(students::Column::Major.eq(value));
+        }
+        if let Some(value) = filter.class_name {
+            finder = finder.filter(students::Column::ClassName.eq(value));
+        }
+        if let Some(keyword) = filter.keyword {
+            let condition = Condition::any()
+                .add(students::Column::StudentNo.contains(&keyword))
+                .add(students::Column::Name.contains(&keyword));
+            finder = finder.filter(condition);
+        }
+
+        let rows = finder
+            .order_by_asc(students::Column::StudentNo)
+            .limit(page_size(limit))
+            .offset(offset.unwrap_or(0))
+            .all(&state.db)
+            .await
+            .map_err(db_error)?;
+        Ok(rows.into_iter().map(StudentNode::from).collect())
+    }
+
+    /// 按学号查询学生；学生仅可查询本人。
+    async fn student(
+        &self,
+        ctx: &Context<'_>,
+        student_no: String,
+    ) -> async_graphql::Result<Option<StudentNode>> {
+        let RequestContext { state, user } = request_context(ctx)?;
+        if user.role == "student" {
+            if user.username != student_no {
+                return Err(gql_error(AppError::auth("forbidden")));
+            }
+        } else {
+            ensure_staff(user).map_err(gql_error)?;
+        }
+        let student = Student::find()
+            .filter(students::Column::StudentNo.eq(&student_no))
+            .filter(students::Column::IsDeleted.eq(false))
+            .one(&state.db)
+            .await
+            .map_err(db_error)?;
+        if let Some(student) = &student {
+            ensure_student_in_scope(user, student).map_err(gql_error)?;
+        }
+        Ok(student.map(StudentNode::from))
+    }
+
+    /// 竞赛记录列表；学生仅可查看本人记录，其他角色受院系数据范围限制。
+    async fn contest_records(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<ContestRecordFilter>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) -> async_graphql::Result<Vec<ContestRecordNode>> {
+        let RequestContext { state, user } = request_context(ctx)?;
+        let filter = filter.unwrap_or_default();
+
+        let mut finder = ContestRecord::find()
+            .join(JoinType::InnerJoin, contest_records::Relation::Student.def())
+            .filter(contest_records::Column::IsDeleted.eq(false))
+            .filter(students::Column::IsDeleted.eq(false));
+        if user.role == "student" {
+            finder = finder.filter(students::Column::StudentNo.eq(&user.username));
+        } else {
+            ensure_staff(user).map_err(gql_error)?;
+            if let Some(department) = department_scope(user) {
+                finder = finder.filter(students::Column::Department.eq(department));
+            }
+        }
+        if let Some(status) = filter.status {
+            finder = finder.filter(contest_records::Column::Status.eq(status));
+        }
+        if let Some(student_no) = filter.student_no {
+            finder = finder.filter(students::Column::StudentNo.eq(student_no));
+        }
+        if let Some(department) = filter.department {
+            finder = finder.filter(students::Column::Department.eq(department));
+        }
+        if let Some(class_name) = filter.class_name {
+            finder = finder.filter(students::Column::ClassName.eq(class_name));
+        }
+        if let Some(year) = filter.contest_year {
+            finder = finder.filter(contest_records::Column::ContestYear.eq(year));
+        }
+
+        let records = finder
+            .order_by_desc(contest_records::Column::CreatedAt)
+            .limit(page_size(limit))
+            .offset(offset.unwrap_or(0))
+            .all(&state.db)
+            .await
+            .map_err(db_error)?;
+        contest_nodes(state, records).await.map_err(gql_error)
+    }
+
+    /// 竞赛记录审核总览（管理员/教师/审核人员/班主任，受数据范围限制）。
+    async fn stats(
+        &self,
+        ctx: &Context<'_>,
+        year: Option<i32>,
+        department: Option<String>,
+    ) -> async_graphql::Result<StatsOverviewResponse> {
+        let RequestContext { state, user } = request_context(ctx)?;
+        ensure_stats_role(&user.role).map_err(gql_error)?;
+        let classes = advisor_class_scope(&state.db, user).await.map_err(gql_error)?;
+        let scope = student_scope_condition(user, classes.as_deref());
+        let rows = load_stat_groups(&state.db, scope, department.as_deref(), year)
+            .await
+            .map_err(gql_error)?;
+        Ok(build_overview(&rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_exposes_read_only_queries() {
+        let sdl = schema().sdl();
+        assert!(sdl.contains("contestRecords("));
+        assert!(sdl.contains("type StatsOverview"));
+        assert!(sdl.contains("customFields: [CustomField!]!"));
+        assert!(!sdl.contains("type Mutation"));
+    }
+
+    #[test]
+    fn page_size_is_clamped() {
+        assert_eq!(page_size(None), DEFAULT_PAGE_SIZE);
+        assert_eq!(page_size(Some(0)), 1);
+        assert_eq!(page_size(Some(10_000)), MAX_PAGE_SIZE);
+    }
+}
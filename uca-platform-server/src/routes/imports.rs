@@ -0,0 +1,207 @@
+//! 导入批次接口：管理员查看历次导入，并按批次撤销误导入的学生或竞赛记录。
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    access::{require_role, require_session_user},
+    audit::record_audit,
+    entities::{import_batches, users, ImportBatch, User},
+    error::AppError,
+    import_batches::{batch_row_counts, soft_delete_batch_rows, BatchRowCounts, IMPORT_KIND_CONTEST, IMPORT_KIND_STUDENT},
+    import_jobs::JOB_RUNNING,
+    state::AppState,
+};
+
+/// 导入批次查询参数。
+#[derive(Debug, Deserialize)]
+pub struct ImportBatchQuery {
+    /// 导入类型（student/contest）。
+    pub kind: Option<String>,
+    /// 返回条数上限（默认 50，最大 500）。
+    pub limit: Option<u64>,
+}
+
+/// 导入批次响应。
+#[derive(Debug, Serialize)]
+pub struct ImportBatchResponse {
+    /// 批次 ID（后台导入时与任务 ID 相同）。
+    pub id: Uuid,
+    /// 导入类型。
+    pub kind: String,
+    /// 导入人用户 ID。
+    pub created_by: Option<Uuid>,
+    /// 导入人显示名。
+    pub created_by_name: Option<String>,
+    /// 导入时间。
+    pub created_at: DateTime<Utc>,
+    /// 新建的行数。
+    pub created_rows: i64,
+    /// 仍未删除的行数。
+    pub active_rows: i64,
+    /// 撤销时间。
+    pub rolled_back_at: Option<DateTime<Utc>>,
+    /// 撤销人用户 ID。
+    pub rolled_back_by: Option<Uuid>,
+}
+
+/// 撤销导入批次的结果。
+#[derive(Debug, Serialize)]
+pub struct ImportRollbackResponse {
+    /// 批次 ID。
+    pub id: Uuid,
+    /// 导入类型。
+    pub kind: String,
+    /// 本次软删除的行数。
+    pub deleted: u64,
+    /// 撤销时间。
+    pub rolled_back_at: DateTime<Utc>,
+}
+
+/// 列出导入批次（按导入时间倒序，管理员）。
+pub async fn list_import_batches(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<ImportBatchQuery>,
+) -> Result<Json<Vec<ImportBatchResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let mut finder = ImportBatch::find();
+    if let Some(kind) = query.kind.filter(|value| !value.is_empty()) {
+        if kind != IMPORT_KIND_STUDENT && kind != IMPORT_KIND_CONTEST {
+            return Err(AppError::validation("kind must be student or contest"));
+        }
+        finder = finder.filter(import_batches::Column::Kind.eq(kind));
+    }
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let batches = finder
+        .order_by_desc(import_batches::Column::CreatedAt)
+        .limit(limit)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let mut counts = HashMap::new();
+    for kind in [IMPORT_KIND_STUDENT, IMPORT_KIND_CONTEST] {
+        let ids = batches
+            .iter()
+            .filter(|batch| batch.kind == kind)
+            .map(|batch| batch.id)
+            .collect();
+        counts.extend(batch_row_counts(&state.db, kind, ids).await?);
+    }
+    let mut user_ids: Vec<Uuid> = batches.iter().filter_map(|batch| batch.created_by).collect();
+    user_ids.sort();
+    user_ids.dedup();
+    let names: HashMap<Uuid, String> = User::find()
+        .filter(users::Column::Id.is_in(user_ids))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|row| (row.id, row.display_name))
+        .collect();
+
+    Ok(Json(
+        batches
+            .into_iter()
+            .map(|batch| {
+                let count = counts.get(&batch.id).copied().unwrap_or_default();
+                batch_response(batch, count, &names)
+            })
+            .collect(),
+    ))
+}
+
+/// 撤销导入批次：软删除该批次新建且仍有效的行（管理员）。
+pub async fn rollback_import_batch(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<ImportRollbackResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let running = state
+        .import_jobs
+        .lock()
+        .await
+        .get(&batch_id)
+        .is_some_and(|job| job.status == JOB_RUNNING);
+    if running {
+        return Err(AppError::bad_request("import job is still running"));
+    }
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let batch = ImportBatch::find_by_id(batch_id)
+        .one(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("import batch not found"))?;
+    if batch.rolled_back_at.is_some() {
+        return Err(AppError::bad_request("import batch already rolled back"));
+    }
+
+    let deleted = soft_delete_batch_rows(&transaction, &batch).await?;
+    let now = Utc::now();
+    let kind = batch.kind.clone();
+    let mut active: import_batches::ActiveModel = batch.into();
+    active.rolled_back_at = Set(Some(now));
+    active.rolled_back_by = Set(Some(user.id));
+    active
+        .update(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "import_rollback",
+        "import_batch",
+        Some(&batch_id.to_string()),
+        Some(serde_json::json!({ "kind": kind, "deleted": deleted })),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(ImportRollbackResponse {
+        id: batch_id,
+        kind,
+        deleted,
+        rolled_back_at: now,
+    }))
+}
+
+fn batch_response(
+    batch: import_batches::Model,
+    count: BatchRowCounts,
+    names: &HashMap<Uuid, String>,
+) -> ImportBatchResponse {
+    ImportBatchResponse {
+        created_by_name: batch.created_by.and_then(|id| names.get(&id).cloned()),
+        id: batch.id,
+        kind: batch.kind,
+        created_by: batch.created_by,
+        created_at: batch.created_at,
+        created_rows: count.created,
+        active_rows: count.active,
+        rolled_back_at: batch.rolled_back_at,
+        rolled_back_by: batch.rolled_back_by,
+    }
+}
@@ -0,0 +1,56 @@
+//! 后台导入任务查询与取消。
+
+use axum::{extract::{Path, State}, Json};
+use axum_extra::extract::cookie::CookieJar;
+use uuid::Uuid;
+
+use crate::{
+    access::require_session_user,
+    entities::users,
+    error::AppError,
+    import_jobs::ImportJobStatus,
+    state::AppState,
+};
+
+/// 查询导入任务状态（发起人或管理员）。
+pub async fn get_import_job(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<ImportJobStatus>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    let status = state
+        .import_jobs
+        .lock()
+        .await
+        .get(&job_id)
+        .ok_or_else(|| AppError::not_found("job not found"))?;
+    ensure_job_access(&user, &status)?;
+    Ok(Json(status))
+}
+
+/// 请求取消运行中的导入任务；当前批次回滚，已提交批次保留在报告中。
+pub async fn cancel_import_job(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<ImportJobStatus>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    let mut store = state.import_jobs.lock().await;
+    let status = store
+        .get(&job_id)
+        .ok_or_else(|| AppError::not_found("job not found"))?;
+    ensure_job_access(&user, &status)?;
+    let status = store
+        .request_cancel(&job_id)
+        .ok_or_else(|| AppError::not_found("job not found"))?;
+    Ok(Json(status))
+}
+
+fn ensure_job_access(user: &users::Model, status: &ImportJobStatus) -> Result<(), AppError> {
+    if user.role == "admin" || user.id == status.owner_id {
+        Ok(())
+    } else {
+        Err(AppError::auth("forbidden"))
+    }
+}
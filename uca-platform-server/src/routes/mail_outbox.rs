@@ -0,0 +1,120 @@
+//! 邮件发件箱接口：管理员查看待发送与发送失败的邮件，并将失败邮件重新排队。
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    access::{require_role, require_session_user},
+    audit::record_audit,
+    entities::{mail_outbox, MailOutbox},
+    error::AppError,
+    mail_outbox::{OUTBOX_FAILED, OUTBOX_PENDING},
+    state::AppState,
+};
+
+/// 发件箱查询参数。
+#[derive(Debug, Deserialize)]
+pub struct OutboxQuery {
+    /// 发送状态（pending/sent/failed）。
+    pub status: Option<String>,
+    /// 返回条数上限（默认 50，最大 500）。
+    pub limit: Option<u64>,
+}
+
+/// 发件箱条目（不含正文，正文可能包含重置链接）。
+#[derive(Debug, Serialize)]
+pub struct OutboxItem {
+    pub id: Uuid,
+    pub recipient: String,
+    pub subject: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+impl From<mail_outbox::Model> for OutboxItem {
+    fn from(item: mail_outbox::Model) -> Self {
+        Self {
+            id: item.id,
+            recipient: item.recipient,
+            subject: item.subject,
+            status: item.status,
+            attempts: item.attempts,
+            last_error: item.last_error,
+            next_attempt_at: item.next_attempt_at,
+            created_at: item.created_at,
+            sent_at: item.sent_at,
+        }
+    }
+}
+
+/// 查看发件箱（按创建时间倒序）。
+pub async fn list_mail_outbox(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<OutboxQuery>,
+) -> Result<Json<Vec<OutboxItem>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let mut finder = MailOutbox::find();
+    if let Some(status) = query.status.filter(|value| !value.is_empty()) {
+        finder = finder.filter(mail_outbox::Column::Status.eq(status));
+    }
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let items = finder
+        .order_by_desc(mail_outbox::Column::CreatedAt)
+        .limit(limit)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(items.into_iter().map(OutboxItem::from).collect()))
+}
+
+/// 将发送失败的邮件重新排队，重置尝试次数并立即发送。
+pub async fn retry_mail(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(mail_id): Path<Uuid>,
+) -> Result<Json<OutboxItem>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let item = MailOutbox::find_by_id(mail_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("mail not found"))?;
+    if item.status != OUTBOX_FAILED {
+        return Err(AppError::bad_request("only failed mail can be retried"));
+    }
+    let mut active: mail_outbox::ActiveModel = item.into();
+    active.status = Set(OUTBOX_PENDING.to_string());
+    active.attempts = Set(0);
+    active.next_attempt_at = Set(Utc::now());
+    let item = active
+        .update(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "mail_outbox_retry",
+        "mail_outbox",
+        Some(&mail_id.to_string()),
+        Some(serde_json::json!({ "recipient": item.recipient })),
+    )
+    .await?;
+    state.mail_wake.notify_one();
+    Ok(Json(OutboxItem::from(item)))
+}
@@ -0,0 +1,235 @@
+//! 邮件模板接口：管理员按语言覆盖邀请、重置与审核结果邮件的主题和正文。
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    access::{require_role, require_session_user},
+    audit::record_audit,
+    entities::{mail_templates, MailTemplate},
+    error::AppError,
+    i18n::Locale,
+    mailer::{mail_template_def, MailTemplateDef, MAIL_BODY_MAX_CHARS, MAIL_SUBJECT_MAX_CHARS, MAIL_TEMPLATES},
+    state::AppState,
+};
+
+/// 邮件模板更新请求。
+#[derive(Debug, Deserialize)]
+pub struct MailTemplateRequest {
+    /// 邮件主题，支持 `{{name}}` 占位符。
+    pub subject: String,
+    /// 邮件正文，支持 `{{name}}` 占位符。
+    pub body: String,
+}
+
+/// 邮件模板响应（每个模板 × 语言一项）。
+#[derive(Debug, Serialize)]
+pub struct MailTemplateResponse {
+    /// 模板 key。
+    pub template_key: String,
+    /// 语言标签。
+    pub locale: String,
+    /// 可用占位符。
+    pub variables: Vec<String>,
+    /// 当前生效的主题。
+    pub subject: String,
+    /// 当前生效的正文。
+    pub body: String,
+    /// 是否已在后台覆盖。
+    pub customized: bool,
+    /// 内置主题。
+    pub default_subject: String,
+    /// 内置正文。
+    pub default_body: String,
+    /// 最后修改时间（仅覆盖时）。
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 列出全部邮件模板及各语言的生效内容（管理员）。
+pub async fn list_mail_templates(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<MailTemplateResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let overrides = MailTemplate::find()
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let mut items = Vec::new();
+    for def in MAIL_TEMPLATES {
+        for locale in Locale::ALL {
+            let row = overrides
+                .iter()
+                .find(|row| row.template_key == def.key && Locale::parse(&row.locale) == Some(locale));
+            items.push(template_response(def, locale, row));
+        }
+    }
+    Ok(Json(items))
+}
+
+/// 覆盖指定模板在某个语言下的主题与正文（管理员）。
+pub async fn update_mail_template(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path((template_key, locale)): Path<(String, String)>,
+    Json(payload): Json<MailTemplateRequest>,
+) -> Result<Json<MailTemplateResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let (def, locale) = resolve_template(&template_key, &locale)?;
+    let subject = payload.subject.trim().to_string();
+    let body = payload.body;
+    validate_template(def, &subject, &body)?;
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let existing = find_override(&transaction, def, locale).await?;
+    let now = Utc::now();
+    let model = match existing {
+        Some(existing) => {
+            let mut active: mail_templates::ActiveModel = existing.into();
+            active.subject = Set(subject);
+            active.body = Set(body);
+            active.updated_by = Set(Some(user.id));
+            active.updated_at = Set(now);
+            active
+                .update(&transaction)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?
+        }
+        None => mail_templates::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            template_key: Set(def.key.to_string()),
+            locale: Set(locale.as_str().to_string()),
+            subject: Set(subject),
+            body: Set(body),
+            updated_by: Set(Some(user.id)),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?,
+    };
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "mail_template_update",
+        "mail_template",
+        Some(&format!("{}:{}", def.key, locale.as_str())),
+        Some(serde_json::json!({ "subject": model.subject })),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(template_response(def, locale, Some(&model))))
+}
+
+/// 删除覆盖，恢复为内置文本（管理员）。
+pub async fn reset_mail_template(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path((template_key, locale)): Path<(String, String)>,
+) -> Result<Json<MailTemplateResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let (def, locale) = resolve_template(&template_key, &locale)?;
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if let Some(existing) = find_override(&transaction, def, locale).await? {
+        MailTemplate::delete_by_id(existing.id)
+            .exec(&transaction)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        record_audit(
+            &transaction,
+            Some(user.id),
+            "mail_template_reset",
+            "mail_template",
+            Some(&format!("{}:{}", def.key, locale.as_str())),
+            None,
+        )
+        .await?;
+    }
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(template_response(def, locale, None)))
+}
+
+fn resolve_template(template_key: &str, locale: &str) -> Result<(&'static MailTemplateDef, Locale), AppError> {
+    let def = mail_template_def(template_key).ok_or_else(|| AppError::not_found("mail template not found"))?;
+    let locale = Locale::parse(locale).ok_or_else(|| AppError::validation("unsupported locale"))?;
+    Ok((def, locale))
+}
+
+fn validate_template(def: &MailTemplateDef, subject: &str, body: &str) -> Result<(), AppError> {
+    if subject.is_empty() || subject.chars().count() > MAIL_SUBJECT_MAX_CHARS {
+        return Err(AppError::validation("subject must be 1-200 characters"));
+    }
+    if subject.contains(['\r', '\n']) {
+        return Err(AppError::validation("subject must be a single line"));
+    }
+    if body.trim().is_empty() || body.chars().count() > MAIL_BODY_MAX_CHARS {
+        return Err(AppError::validation("body must be 1-20000 characters"));
+    }
+    def.validate(subject)?;
+    def.validate(body)
+}
+
+async fn find_override<C>(
+    db: &C,
+    def: &MailTemplateDef,
+    locale: Locale,
+) -> Result<Option<mail_templates::Model>, AppError>
+where
+    C: ConnectionTrait,
+{
+    MailTemplate::find()
+        .filter(mail_templates::Column::TemplateKey.eq(def.key))
+        .filter(mail_templates::Column::Locale.eq(locale.as_str()))
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))
+}
+
+fn template_response(
+    def: &MailTemplateDef,
+    locale: Locale,
+    row: Option<&mail_templates::Model>,
+) -> MailTemplateResponse {
+    let default_subject = def.default_subject(locale);
+    let default_body = def.default_body(locale);
+    MailTemplateResponse {
+        template_key: def.key.to_string(),
+        locale: locale.as_str().to_string(),
+        variables: def.variables.iter().map(|name| name.to_string()).collect(),
+        subject: row.map(|row| row.subject.clone()).unwrap_or_else(|| default_subject.clone()),
+        body: row.map(|row| row.body.clone()).unwrap_or_else(|| default_body.clone()),
+        customized: row.is_some(),
+        default_subject,
+        default_body,
+        updated_at: row.map(|row| row.updated_at),
+    }
+}
@@ -1,25 +1,84 @@
 //! HTTP 路由处理器。
 
-use axum::{routing::{delete, get, post, put}, Router};
+use axum::{extract::DefaultBodyLimit, middleware, routing::{delete, get, post, put}, Router};
 
 use crate::state::AppState;
 
 pub mod auth;
+pub mod announcements;
 pub mod attachments;
 pub mod admin;
+pub mod archives;
+pub mod comments;
+pub mod delegations;
+pub mod events;
+pub mod export_profiles;
 pub mod exports;
+pub mod files;
 pub mod students;
 pub mod records;
 pub mod forms;
+pub mod graphql;
+pub mod imports;
+pub mod jobs;
+pub mod mail_outbox;
+pub mod mail_templates;
+pub mod notifications;
 pub mod profile;
+pub mod settings;
+pub mod stats;
+pub mod webhooks;
+
+/// 上传请求体中表单字段等额外开销的预留字节数。
+const MULTIPART_OVERHEAD_BYTES: usize = 64 * 1024;
 
 /// 构建应用路由。
 pub fn router(state: AppState) -> Router {
+    let attachments_config = &state.config.attachments;
+    let attachment_body_limit = attachments_config
+        .max_file_bytes
+        .saturating_mul(attachments_config.max_per_record)
+        .saturating_add(MULTIPART_OVERHEAD_BYTES);
+    let backup_body_limit = crate::backup::MAX_BACKUP_BYTES.saturating_add(MULTIPART_OVERHEAD_BYTES);
+    let signature_body_limit = attachments::MAX_SIGNATURE_BYTES.saturating_add(MULTIPART_OVERHEAD_BYTES);
+    // 全局请求体上限；附件、签名与备份导入在路由上单独覆盖。
+    let default_body_limit = state.config.max_upload_bytes.saturating_add(MULTIPART_OVERHEAD_BYTES);
+    // 导出接口支持 `?delivery=link`：暂存生成的文件并返回签名下载链接。
+    let export_routes = Router::new()
+        .route("/export/summary/excel", post(exports::export_summary_excel))
+        .route("/export/student/:student_no/excel", post(exports::export_student_excel))
+        .route("/export/record/:record_type/:record_id/pdf", post(exports::export_record_pdf))
+        .route("/export/labor-hours/batch", post(exports::export_labor_hours_batch))
+        .route("/export/labor-hours/:student_no/pdf", post(exports::export_labor_hours_pdf))
+        .route("/export/labor-hours/:student_no/excel", post(exports::export_labor_hours_excel))
+        .route("/export/labor-hours/summary/excel", post(exports::export_labor_hours_summary_excel))
+        .route("/export/signatures/audit/excel", post(exports::export_signature_audit_excel))
+        .route_layer(middleware::from_fn_with_state(state.clone(), files::export_link_delivery));
+    // 提交与导入接口支持 `Idempotency-Key`：重复提交回放首次响应。
+    let submission_routes = Router::new()
+        .route("/students/import", post(students::import_students))
+        .route("/students/contacts/import", post(students::import_student_contacts))
+        .route("/records/contest", post(records::create_contest_record))
+        .route("/records/contest/:record_id/review", post(records::review_contest_record))
+        .route("/records/contest/:record_id/competition", post(records::confirm_contest_competition))
+        .route("/records/contest/review/batch", post(records::batch_review_contest_records))
+        .route("/records/volunteer/review/batch", post(records::batch_review_volunteer_records))
+        .route("/admin/competitions/import", post(admin::import_competitions))
+        .route("/admin/form-schema/import", post(admin::import_form_schema))
+        .route("/admin/records/contest/import", post(admin::import_contest_records))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::idempotency::replay_idempotent,
+        ));
     Router::new()
         .route("/health", get(auth::health))
+        .route("/health/ready", get(auth::readiness))
         .route("/auth/bootstrap/status", get(auth::bootstrap_status))
         .route("/auth/bootstrap", post(auth::bootstrap_admin))
         .route("/auth/config", get(auth::auth_config))
+        .route("/settings/runtime", get(settings::get_runtime_settings))
+        .route("/announcements/active", get(announcements::list_active_announcements))
+        .route("/verify/:token", get(exports::verify_document))
         .route("/auth/login/options", get(auth::login_options))
         .route("/auth/password-policy", get(auth::password_policy))
         .route("/auth/reauth/password", post(auth::reauth_password))
@@ -31,12 +90,17 @@ pub fn router(state: AppState) -> Router {
         .route("/auth/passkey/login/start", post(auth::passkey_login_start))
         .route("/auth/passkey/login/finish", post(auth::passkey_login_finish))
         .route("/auth/password/login", post(auth::password_login))
+        .route("/auth/ldap/login", post(auth::ldap_login))
+        .route("/auth/sso/login", get(auth::sso_login))
+        .route("/auth/sso/callback", get(auth::sso_callback))
         .route("/auth/me", get(auth::current_user))
         .route("/auth/logout", post(auth::logout))
         .route("/auth/totp/enroll/start", post(auth::totp_enroll_start))
         .route("/auth/totp/enroll/finish", post(auth::totp_enroll_finish))
         .route("/auth/totp/verify", post(auth::totp_verify))
         .route("/auth/recovery/verify", post(auth::recovery_verify))
+        .route("/auth/recovery/generate", post(auth::recovery_generate))
+        .route("/auth/recovery/generate/pdf", post(auth::recovery_generate_pdf))
         .route("/auth/email/bind", post(auth::bind_email))
         .route("/auth/password/change", post(auth::change_password))
         .route("/auth/password/reset/request", post(auth::password_reset_request))
@@ -46,54 +110,223 @@ pub fn router(state: AppState) -> Router {
         .route("/auth/reset/status", get(auth::reset_status))
         .route("/auth/reset/consume", post(auth::reset_consume))
         .route("/auth/devices", get(auth::list_devices))
-        .route("/auth/devices/:device_id", delete(auth::delete_device))
-        .route("/profile/signature", get(profile::get_signature).post(profile::upload_signature))
+        .route("/auth/devices/:device_id", delete(auth::delete_device).put(auth::rename_device))
+        .route(
+            "/auth/signature",
+            get(profile::get_signature)
+                .post(profile::upload_auth_signature)
+                .delete(profile::delete_signature)
+                .layer(DefaultBodyLimit::max(signature_body_limit)),
+        )
+        .route(
+            "/profile/signature",
+            get(profile::get_signature)
+                .post(profile::upload_auth_signature)
+                .layer(DefaultBodyLimit::max(signature_body_limit)),
+        )
+        .route(
+            "/profile/notifications",
+            get(profile::get_notification_preferences).put(profile::update_notification_preferences),
+        )
+        .route(
+            "/profile/locale",
+            get(profile::get_locale_preference).put(profile::update_locale_preference),
+        )
         .route("/forms/:form_type/fields", get(forms::list_form_fields_for_type))
+        .route("/forms/:form_type/window", get(forms::get_submission_window))
+        .route("/forms/:form_type/attachment-requirements", get(forms::list_attachment_requirements))
         .route("/competitions", get(admin::list_competitions_public))
+        .route("/semesters", get(admin::list_semesters))
         .route("/students", post(students::create_student))
         .route("/students/me", get(students::get_current_student))
+        .route("/students/me/peer-stats", get(students::get_peer_stats))
         .route("/students/:student_no", put(students::update_student))
+        .route("/students/:student_no/hours", get(students::get_student_hours))
         .route("/students/query", post(students::list_students))
-        .route("/students/import", post(students::import_students))
-        .route("/records/contest", post(records::create_contest_record))
+        .route("/jobs/:job_id", get(jobs::get_import_job))
+        .route("/jobs/:job_id/cancel", post(jobs::cancel_import_job))
+        .route("/events/stream", get(events::event_stream))
+        .route("/notifications", get(notifications::list_notifications))
+        .route("/notifications/:notification_id/read", post(notifications::mark_notification_read))
         .route("/records/contest/query", post(records::list_contest_records))
-        .route("/records/contest/:record_id/review", post(records::review_contest_record))
-        .route("/attachments/contest/:record_id", post(attachments::upload_contest_attachment))
+        .route(
+            "/reviews/delegations",
+            get(delegations::list_delegations).post(delegations::create_delegation),
+        )
+        .route("/reviews/delegations/:delegation_id", delete(delegations::revoke_delegation))
+        .route(
+            "/comments/:record_type/:record_id",
+            get(comments::list_record_comments).post(comments::create_record_comment),
+        )
+        .route(
+            "/attachments/contest/:record_id",
+            post(attachments::upload_contest_attachment).layer(DefaultBodyLimit::max(attachment_body_limit)),
+        )
         .route("/attachments/:attachment_id", get(attachments::download_attachment))
+        .route(
+            "/attachments/:attachment_id/thumbnail",
+            get(attachments::download_attachment_thumbnail),
+        )
         .route("/signatures/:record_type/:record_id/:stage", post(attachments::upload_review_signature))
-        .route("/export/summary/excel", post(exports::export_summary_excel))
-        .route("/export/student/:student_no/excel", post(exports::export_student_excel))
-        .route("/export/record/:record_type/:record_id/pdf", post(exports::export_record_pdf))
-        .route("/export/labor-hours/:student_no/pdf", post(exports::export_labor_hours_pdf))
-        .route("/export/labor-hours/summary/excel", post(exports::export_labor_hours_summary_excel))
+        .route("/stats/overview", get(stats::stats_overview))
+        .route("/stats/by-department", get(stats::stats_by_department))
+        .route("/stats/reviewers", get(stats::stats_reviewers))
+        .route("/graphql", post(graphql::execute))
+        .route("/graphql/schema", get(graphql::schema_sdl))
+        .merge(export_routes)
+        .merge(submission_routes)
+        .route("/export/labor-hours/templates", get(exports::list_labor_hours_templates))
+        .route("/files/sign", post(files::sign_file_url))
+        .route("/files/signed/:token", get(files::download_signed_file))
         .route("/admin/competitions", get(admin::list_competitions))
         .route("/admin/competitions", post(admin::create_competition))
         .route("/admin/competitions/:competition_id", put(admin::update_competition))
         .route("/admin/competitions/:competition_id", delete(admin::delete_competition))
-        .route("/admin/competitions/import", post(admin::import_competitions))
+        .route("/admin/competitions/similar", get(admin::list_similar_competitions))
+        .route("/admin/semesters", post(admin::create_semester))
+        .route(
+            "/admin/submission-windows",
+            get(admin::list_submission_windows).post(admin::create_submission_window),
+        )
+        .route(
+            "/admin/submission-windows/:window_id",
+            put(admin::update_submission_window).delete(admin::delete_submission_window),
+        )
+        .route(
+            "/admin/attachment-requirements",
+            get(admin::list_attachment_requirements).post(admin::create_attachment_requirement),
+        )
+        .route(
+            "/admin/attachment-requirements/:requirement_id",
+            put(admin::update_attachment_requirement).delete(admin::delete_attachment_requirement),
+        )
+        .route("/admin/mail/outbox", get(mail_outbox::list_mail_outbox))
+        .route("/admin/mail/outbox/:mail_id/retry", post(mail_outbox::retry_mail))
+        .route("/admin/mail-templates", get(mail_templates::list_mail_templates))
+        .route(
+            "/admin/mail-templates/:template_key/:locale",
+            put(mail_templates::update_mail_template).delete(mail_templates::reset_mail_template),
+        )
+        .route(
+            "/admin/announcements",
+            get(announcements::list_announcements).post(announcements::create_announcement),
+        )
+        .route(
+            "/admin/announcements/:announcement_id",
+            put(announcements::update_announcement).delete(announcements::delete_announcement),
+        )
+        .route(
+            "/admin/semesters/:semester_id",
+            put(admin::update_semester).delete(admin::delete_semester),
+        )
         .route("/admin/users", post(admin::create_user))
         .route("/admin/users/reset/totp", post(admin::reset_user_totp))
         .route("/admin/users/reset/passkey", post(admin::reset_user_passkey))
         .route("/admin/users/reset/code", post(admin::generate_reset_code))
+        .route("/admin/users/:user_id/department", put(admin::update_user_department))
+        .route(
+            "/admin/users/:user_id/advisor-classes",
+            get(admin::get_advisor_classes).put(admin::update_advisor_classes),
+        )
+        .route("/admin/users/reset/code/batch", post(admin::batch_generate_reset_codes))
+        .route("/admin/security-events", get(admin::list_security_events))
+        .route("/admin/webhooks", get(webhooks::list_webhooks).post(webhooks::create_webhook))
+        .route(
+            "/admin/webhooks/:webhook_id",
+            put(webhooks::update_webhook).delete(webhooks::delete_webhook),
+        )
+        .route("/admin/webhooks/:webhook_id/deliveries", get(webhooks::list_webhook_deliveries))
+        .route("/admin/archives", get(archives::list_archives))
+        .route("/admin/archives/run", post(archives::run_archival_now))
+        .route("/admin/archives/:archive_id", get(archives::get_archive))
+        .route("/admin/archives/:archive_id/restore", post(archives::restore_archive))
         .route("/admin/password-policy", get(admin::get_password_policy))
         .route("/admin/password-policy", post(admin::update_password_policy))
+        .route("/admin/student-password-rule", get(admin::get_student_password_rule))
+        .route("/admin/student-password-rule", post(admin::update_student_password_rule))
         .route("/admin/labor-hour-rules", get(admin::get_labor_hour_rules))
         .route("/admin/labor-hour-rules", post(admin::update_labor_hour_rules))
+        .route("/admin/labor-hours/recompute", post(admin::recompute_labor_hours))
         .route("/admin/form-fields", get(admin::list_form_fields))
         .route("/admin/form-fields", post(admin::create_form_field))
-        .route("/admin/export-templates/:template_key", get(admin::get_export_template))
+        .route(
+            "/admin/form-fields/:field_id",
+            put(admin::update_form_field).delete(admin::delete_form_field),
+        )
+        .route("/admin/form-schema/export", get(admin::export_form_schema))
+        .route(
+            "/admin/export-templates",
+            get(admin::list_export_templates).post(admin::create_export_template),
+        )
+        .route(
+            "/admin/export-templates/:template_key",
+            get(admin::get_export_template)
+                .put(admin::update_export_template)
+                .delete(admin::delete_export_template),
+        )
         .route("/admin/export-templates/:template_key/upload", post(admin::upload_export_template))
+        .route("/admin/export-templates/:template_key/preview", post(admin::preview_export_template))
+        .route("/admin/export-profiles", get(export_profiles::list_export_profiles))
+        .route(
+            "/admin/export-profiles/:profile_key",
+            get(export_profiles::get_export_profile)
+                .put(export_profiles::update_export_profile)
+                .delete(export_profiles::reset_export_profile),
+        )
         .route("/admin/deleted/students", get(admin::list_deleted_students))
         .route("/admin/deleted/records/contest", get(admin::list_deleted_contest_records))
+        .route("/admin/deleted/records/volunteer", get(admin::list_deleted_volunteer_records))
+        .route("/admin/restore/students/:student_no", post(admin::restore_student))
+        .route("/admin/restore/records/contest/:record_id", post(admin::restore_contest_record))
+        .route("/admin/restore/records/volunteer/:record_id", post(admin::restore_volunteer_record))
         .route("/admin/students/:student_no", delete(admin::delete_student))
         .route("/admin/students/:student_no/restore", post(admin::restore_student))
+        .route("/admin/students/:student_no/export-bundle", get(admin::export_student_bundle))
         .route("/admin/students/:student_no/allow-login", post(admin::update_student_login))
         .route("/admin/students/:student_no/reset-password", post(admin::reset_student_password))
         .route("/admin/students/create-users", post(admin::create_student_users))
+        .route("/admin/students/user-links", get(admin::check_student_user_links))
+        .route("/admin/students/user-links/repair", post(admin::repair_student_user_links))
+        .route("/admin/reconcile/students-users", post(admin::repair_student_user_links))
+        .route("/admin/assignments", post(admin::create_assignments))
+        .route("/admin/assignments/workload", get(admin::list_reviewer_workloads))
         .route("/admin/records/contest/:record_id", delete(admin::delete_contest_record))
         .route("/admin/records/contest/:record_id/restore", post(admin::restore_contest_record))
+        .route("/admin/records/volunteer/:record_id", delete(admin::delete_volunteer_record))
+        .route("/admin/records/volunteer/:record_id/restore", post(admin::restore_volunteer_record))
         .route("/admin/purge/students/:student_no", delete(admin::purge_student))
         .route("/admin/purge/records/contest/:record_id", delete(admin::purge_contest_record))
-        .route("/admin/records/contest/import", post(admin::import_contest_records))
+        .route("/admin/purge/records/volunteer/:record_id", delete(admin::purge_volunteer_record))
+        .route("/admin/purge/records", post(admin::bulk_purge_records))
+        .route("/admin/imports", get(imports::list_import_batches))
+        .route("/admin/imports/:batch_id", delete(imports::rollback_import_batch))
+        .route("/admin/system/info", get(admin::get_system_info))
+        .route("/admin/config", get(admin::get_effective_config))
+        .route("/admin/secrets", get(admin::get_secret_usage))
+        .route("/admin/secrets/rotate", post(admin::rotate_secret_keys))
+        .route("/admin/storage/usage", get(admin::get_storage_usage))
+        .route("/admin/storage/orphans", get(admin::get_orphan_report))
+        .route("/admin/storage/orphans/cleanup", post(admin::cleanup_orphan_data))
+        .route("/admin/backup/export", post(admin::export_backup))
+        .route("/admin/dev/seed", post(admin::seed_dev_data))
+        .route(
+            "/admin/backup/import",
+            post(admin::import_backup).layer(DefaultBodyLimit::max(backup_body_limit)),
+        )
+        .route(
+            "/admin/settings/runtime",
+            get(settings::get_admin_runtime_settings).put(settings::update_runtime_settings),
+        )
+        .route(
+            "/admin/settings/archival",
+            get(archives::get_archival_policy).put(archives::update_archival_policy),
+        )
+        .layer(DefaultBodyLimit::max(default_body_limit))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::settings::maintenance_guard,
+        ))
+        .layer(middleware::from_fn(crate::uploads::structured_payload_limit))
+        .layer(middleware::from_fn(crate::request_id::track_request))
         .with_state(state)
 }
@@ -0,0 +1,90 @@
+//! 站内通知接口。
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    access::require_session_user,
+    entities::{notifications, Notification},
+    error::AppError,
+    state::AppState,
+};
+
+/// 通知查询参数。
+#[derive(Debug, Deserialize)]
+pub struct NotificationQuery {
+    /// 仅返回未读通知。
+    pub unread_only: Option<bool>,
+    /// 返回条数上限（默认 50，最大 200）。
+    pub limit: Option<u64>,
+}
+
+/// 通知列表响应。
+#[derive(Debug, Serialize)]
+pub struct NotificationListResponse {
+    /// 未读数量。
+    pub unread: u64,
+    /// 通知（按时间倒序）。
+    pub items: Vec<notifications::Model>,
+}
+
+/// 获取当前用户的通知。
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<NotificationQuery>,
+) -> Result<Json<NotificationListResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    let unread = Notification::find()
+        .filter(notifications::Column::UserId.eq(user.id))
+        .filter(notifications::Column::ReadAt.is_null())
+        .count(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let mut finder = Notification::find().filter(notifications::Column::UserId.eq(user.id));
+    if query.unread_only.unwrap_or(false) {
+        finder = finder.filter(notifications::Column::ReadAt.is_null());
+    }
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let items = finder
+        .order_by_desc(notifications::Column::CreatedAt)
+        .limit(limit)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(NotificationListResponse { unread, items }))
+}
+
+/// 将通知标记为已读（仅本人）。
+pub async fn mark_notification_read(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(notification_id): Path<Uuid>,
+) -> Result<Json<notifications::Model>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    let notification = Notification::find_by_id(notification_id)
+        .filter(notifications::Column::UserId.eq(user.id))
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("notification not found"))?;
+    if notification.read_at.is_some() {
+        return Ok(Json(notification));
+    }
+    let mut active: notifications::ActiveModel = notification.into();
+    active.read_at = Set(Some(Utc::now()));
+    let notification = active
+        .update(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(notification))
+}
@@ -1,21 +1,24 @@
-//! 个人中心（签名图片管理）。
+//! 个人中心（签名图片与通知设置）。
 
-use axum::{extract::{Multipart, State}, Json};
+use axum::{extract::{Multipart, State}, http::HeaderMap, Json};
 use axum_extra::extract::cookie::CookieJar;
 use chrono::Utc;
 use sea_orm::{ActiveModelTrait, EntityTrait, Set};
-use serde::Serialize;
-use tokio::fs;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     access::require_session_user,
-    entities::{user_signatures, UserSignature},
+    audit::record_audit,
+    entities::{user_signatures, users, UserSignature},
     error::AppError,
+    i18n::Locale,
+    routes::{
+        attachments::{remove_stored_file, store_user_signature},
+        auth::require_reauth,
+    },
     state::AppState,
 };
 
-const MAX_UPLOAD_BYTES: usize = 5 * 1024 * 1024;
-
 /// 当前用户签名信息。
 #[derive(Debug, Serialize)]
 pub struct SignatureProfile {
@@ -41,41 +44,169 @@ pub async fn get_signature(
     }))
 }
 
-/// 上传当前用户签名图片（审核人员/管理员/教师）。
-pub async fn upload_signature(
+/// 上传或替换当前用户签名图片（需二次验证），用于劳动教育学时认定表等导出。
+///
+/// `/auth/signature` 与 `/profile/signature` 的 POST 均由此处理。
+pub async fn upload_auth_signature(
     State(state): State<AppState>,
     jar: CookieJar,
+    headers: HeaderMap,
     multipart: Multipart,
 ) -> Result<Json<SignatureProfile>, AppError> {
     let user = require_session_user(&state, &jar).await?;
+    ensure_signature_role(&user)?;
+    require_reauth(&state, &headers, user.id).await?;
+    save_user_signature(&state, &user, multipart).await.map(Json)
+}
+
+/// 删除当前用户签名图片（需二次验证）；删除后导出不再附带该签名。
+pub async fn delete_signature(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    headers: HeaderMap,
+) -> Result<Json<SignatureProfile>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_reauth(&state, &headers, user.id).await?;
+    let existing = UserSignature::find_by_id(user.id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("signature not found"))?;
+    UserSignature::delete_by_id(user.id)
+        .exec(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    remove_stored_file(&existing.signature_path).await;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "user_signature_delete",
+        "user_signature",
+        Some(&user.id.to_string()),
+        None,
+    )
+    .await?;
+    Ok(Json(SignatureProfile {
+        uploaded: false,
+        signature_path: None,
+    }))
+}
+
+/// 通知设置。
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    /// 是否接收审核结果邮件。
+    pub review_email: bool,
+}
+
+/// 获取当前用户通知设置。
+pub async fn get_notification_preferences(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<NotificationPreferences>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    Ok(Json(NotificationPreferences {
+        review_email: user.notify_review_email,
+    }))
+}
+
+/// 更新当前用户通知设置。
+pub async fn update_notification_preferences(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<NotificationPreferences>,
+) -> Result<Json<NotificationPreferences>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    let mut active: users::ActiveModel = user.into();
+    active.notify_review_email = Set(payload.review_email);
+    active.updated_at = Set(Utc::now());
+    let user = active
+        .update(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(NotificationPreferences {
+        review_email: user.notify_review_email,
+    }))
+}
+
+/// 语言偏好。
+#[derive(Debug, Serialize)]
+pub struct LocalePreference {
+    /// 个人语言偏好，为空时跟随部署默认语言。
+    pub locale: Option<Locale>,
+    /// 邮件与导出实际使用的语言。
+    pub effective_locale: Locale,
+}
+
+/// 更新语言偏好请求。
+#[derive(Debug, Deserialize)]
+pub struct UpdateLocalePreference {
+    /// `zh-CN`/`en-US`，传 `null` 恢复为部署默认语言。
+    pub locale: Option<String>,
+}
+
+/// 获取当前用户语言偏好。
+pub async fn get_locale_preference(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<LocalePreference>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    Ok(Json(locale_preference(&state, &user)))
+}
+
+/// 更新当前用户语言偏好。
+pub async fn update_locale_preference(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<UpdateLocalePreference>,
+) -> Result<Json<LocalePreference>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    let locale = payload
+        .locale
+        .as_deref()
+        .map(|value| Locale::parse(value).ok_or_else(|| AppError::validation("unsupported locale")))
+        .transpose()?;
+    let mut active: users::ActiveModel = user.into();
+    active.locale = Set(locale.map(|value| value.as_str().to_string()));
+    active.updated_at = Set(Utc::now());
+    let user = active
+        .update(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(locale_preference(&state, &user)))
+}
+
+fn locale_preference(state: &AppState, user: &users::Model) -> LocalePreference {
+    LocalePreference {
+        locale: user.locale.as_deref().and_then(Locale::parse),
+        effective_locale: state.locale_for(user),
+    }
+}
+
+fn ensure_signature_role(user: &users::Model) -> Result<(), AppError> {
     if user.role != "admin" && user.role != "reviewer" && user.role != "teacher" {
         return Err(AppError::auth("forbidden"));
     }
+    Ok(())
+}
 
-    let (bytes, original_name) = read_signature_file(multipart).await?;
-    let filename = build_signature_filename(&original_name);
-    let dir = state
-        .config
-        .upload_dir
-        .join("signatures")
-        .join("users")
-        .join(user.id.to_string());
-    fs::create_dir_all(&dir)
-        .await
-        .map_err(|err| AppError::internal(&format!("failed to create dir: {err}")))?;
-    let path = dir.join(filename);
-    fs::write(&path, bytes)
-        .await
-        .map_err(|err| AppError::internal(&format!("failed to write file: {err}")))?;
+/// 保存签名文件并更新签名记录；替换时删除旧文件。
+async fn save_user_signature(
+    state: &AppState,
+    user: &users::Model,
+    multipart: Multipart,
+) -> Result<SignatureProfile, AppError> {
+    let path = store_user_signature(&state.config.upload_dir, user.id, multipart).await?;
+    let signature_path = path.to_string_lossy().to_string();
 
     let now = Utc::now();
-    if let Some(existing) = UserSignature::find_by_id(user.id)
+    let previous = UserSignature::find_by_id(user.id)
         .one(&state.db)
         .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-    {
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if let Some(existing) = previous.clone() {
         let mut active: user_signatures::ActiveModel = existing.into();
-        active.signature_path = Set(path.to_string_lossy().to_string());
+        active.signature_path = Set(signature_path.clone());
         active.updated_at = Set(now);
         active
             .update(&state.db)
@@ -84,7 +215,7 @@ pub async fn upload_signature(
     } else {
         let model = user_signatures::ActiveModel {
             user_id: Set(user.id),
-            signature_path: Set(path.to_string_lossy().to_string()),
+            signature_path: Set(signature_path.clone()),
             created_at: Set(now),
             updated_at: Set(now),
         };
@@ -93,43 +224,21 @@ pub async fn upload_signature(
             .await
             .map_err(|err| AppError::Database(err.to_string()))?;
     }
-
-    Ok(Json(SignatureProfile {
-        uploaded: true,
-        signature_path: Some(path.to_string_lossy().to_string()),
-    }))
-}
-
-async fn read_signature_file(mut multipart: Multipart) -> Result<(Vec<u8>, String), AppError> {
-    while let Some(field) = multipart
-        .next_field()
-        .await
-        .map_err(|_| AppError::bad_request("invalid multipart"))?
-    {
-        if field.name() != Some("file") {
-            continue;
-        }
-        let filename = field
-            .file_name()
-            .map(|value| value.to_string())
-            .unwrap_or_else(|| "signature.png".to_string());
-        let bytes = field
-            .bytes()
-            .await
-            .map_err(|_| AppError::bad_request("failed to read file"))?;
-        if bytes.len() > MAX_UPLOAD_BYTES {
-            return Err(AppError::bad_request("signature file too large"));
-        }
-        return Ok((bytes.to_vec(), filename));
+    if let Some(previous) = previous.filter(|item| item.signature_path != signature_path) {
+        remove_stored_file(&previous.signature_path).await;
     }
-    Err(AppError::bad_request("file field required"))
-}
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "user_signature_upload",
+        "user_signature",
+        Some(&user.id.to_string()),
+        None,
+    )
+    .await?;
 
-fn build_signature_filename(original: &str) -> String {
-    let ext = std::path::Path::new(original)
-        .extension()
-        .and_then(|value| value.to_str())
-        .unwrap_or("png");
-    let timestamp = Utc::now().format("%Y%m%d%H%M%S");
-    format!("signature_{timestamp}.{ext}")
+    Ok(SignatureProfile {
+        uploaded: true,
+        signature_path: Some(signature_path),
+    })
 }
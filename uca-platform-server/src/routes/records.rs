@@ -2,10 +2,8 @@
 
 use axum::{extract::State, Json, extract::Path};
 use axum_extra::extract::cookie::CookieJar;
-use chrono::{TimeZone, Utc};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait,
-    Set,
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait, Set,
 };
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
@@ -13,24 +11,37 @@ use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    access::{require_role, require_session_user},
+    access::{
+        advisor_class_scope, ensure_student_in_scope, require_role, require_session_user, require_student_profile,
+        student_scope_condition, ROLE_CLASS_ADVISOR,
+    },
+    assignments::{auto_assign_record, AssignRecordType},
+    audit::record_audit,
     entities::{
         attachments, competition_library, contest_records, form_field_values, form_fields, students,
-        Attachment, CompetitionLibrary, ContestRecord, FormField, FormFieldValue, Student,
+        volunteer_records, Attachment, CompetitionLibrary, ContestRecord, FormFieldValue, Student,
+        VolunteerRecord,
     },
     error::AppError,
-    labor_hours::{compute_recommended_hours, load_labor_hour_rules},
+    events::{publish_review_events, QueueEvent, EVENT_RECORD_REVIEWED, EVENT_RECORD_SUBMITTED},
+    fuzzy::{self, MatchConfidence},
+    labor_hours::{
+        compute_recommended_hours, hour_cap_warnings, load_labor_hour_rules, record_recommended_hours,
+        HourCapWarning, LaborHourRuleConfig,
+    },
+    notify::{notify_review_results, ReviewNotice},
+    record_filter::RecordFilter,
+    services::{
+        record::load_form_fields,
+        review::{ensure_review_permission, REVIEW_STAGE_FINAL},
+        DbRecordService, DbReviewService, NewContestRecord, RecordService, ReviewDecision, ReviewOutcome,
+        ReviewService,
+    },
+    routes::{admin::load_competition_snapshot, attachments::ATTACHMENT_KIND_EVIDENCE},
     state::AppState,
+    submission_windows::ensure_window_open,
 };
 
-const STATUS_SUBMITTED: &str = "submitted";
-const STATUS_FIRST_REVIEWED: &str = "first_reviewed";
-const STATUS_FINAL_REVIEWED: &str = "final_reviewed";
-const STATUS_REJECTED: &str = "rejected";
-
-const REVIEW_STAGE_FIRST: &str = "first";
-const REVIEW_STAGE_FINAL: &str = "final";
-
 /// 竞赛获奖提交请求。
 #[derive(Debug, Deserialize, Validate)]
 pub struct CreateContestRequest {
@@ -97,14 +108,28 @@ pub struct ContestRecordResponse {
     pub status: String,
     /// 不通过原因。
     pub rejection_reason: Option<String>,
-    /// 竞赛名称匹配标识。
+    /// 所属学期 ID（按提交时间归属）。
+    pub semester_id: Option<Uuid>,
+    /// 指派的审核人。
+    pub assigned_reviewer_id: Option<Uuid>,
+    /// 记录版本号，审核时作为 `expected_version` 提交。
+    pub version: i32,
+    /// 竞赛名称匹配标识（matched/fuzzy/unmatched）。
     pub match_status: String,
-    /// 推荐学时。
+    /// 最佳匹配或已确认的竞赛库条目 ID。
+    pub matched_competition_id: Option<Uuid>,
+    /// 匹配置信度。
+    pub match_confidence: Option<MatchConfidence>,
+    /// 推荐学时（提交时匹配竞赛库保存的值，未保存时按当前规则计算）。
     pub recommended_hours: i32,
+    /// 自评学时与提交时推荐学时之差；提交时未计算推荐学时则为空。
+    pub hours_deviation: Option<i32>,
     /// 自定义字段。
     pub custom_fields: Vec<CustomFieldValueResponse>,
     /// 附件列表。
     pub attachments: Vec<AttachmentInfo>,
+    /// 终审通过后超出每学年学时上限的提醒（仅审核接口返回）。
+    pub hour_cap_warnings: Vec<HourCapWarning>,
 }
 
 /// 附件信息。
@@ -118,6 +143,8 @@ pub struct AttachmentInfo {
     pub mime_type: String,
     /// 下载地址。
     pub download_url: String,
+    /// 缩略图地址（仅图片附件）。
+    pub thumbnail_url: Option<String>,
 }
 
 /// 自定义字段响应。
@@ -136,6 +163,16 @@ pub struct CustomFieldValueResponse {
 pub struct ContestQuery {
     /// 状态筛选。
     pub status: Option<String>,
+    /// 只看指派给当前账号的记录（“我的队列”）。
+    #[serde(default)]
+    pub assigned_to_me: bool,
+    /// 按指派的审核人筛选。
+    pub assigned_reviewer_id: Option<Uuid>,
+    /// 是否内嵌学生学号、姓名、院系、专业与班级（默认 `true`）。
+    pub include_student: Option<bool>,
+    /// 结构化筛选（学号、班级、院系、获奖时间、学时、竞赛类型、匹配状态）。
+    #[serde(flatten)]
+    pub filter: RecordFilter,
 }
 
 /// 审核请求。
@@ -151,6 +188,52 @@ pub struct ReviewRequest {
     pub status: String,
     /// 不通过原因。
     pub rejection_reason: Option<String>,
+    /// 客户端看到的记录版本号；与当前版本不一致时返回 409。
+    pub expected_version: i32,
+}
+
+/// 批量审核请求。
+#[derive(Debug, Deserialize, Validate)]
+pub struct BatchReviewRequest {
+    /// 待审核记录 ID 列表。
+    #[validate(length(min = 1, max = 500))]
+    pub record_ids: Vec<Uuid>,
+    /// 审核阶段：first/final。
+    #[validate(length(min = 1, max = 16))]
+    pub stage: String,
+    /// 审核学时。
+    pub hours: i32,
+    /// 状态：approved/rejected。
+    #[validate(length(min = 1, max = 16))]
+    pub status: String,
+    /// 不通过原因。
+    pub rejection_reason: Option<String>,
+}
+
+/// 批量审核单条结果。
+#[derive(Debug, Serialize)]
+pub struct BatchReviewItem {
+    /// 记录 ID。
+    pub record_id: Uuid,
+    /// 是否已更新。
+    pub updated: bool,
+    /// 审核后状态。
+    pub status: Option<String>,
+    /// 未更新原因。
+    pub error: Option<String>,
+}
+
+/// 批量审核响应。
+#[derive(Debug, Serialize)]
+pub struct BatchReviewResponse {
+    /// 已更新数量。
+    pub updated: usize,
+    /// 未更新数量。
+    pub failed: usize,
+    /// 逐条结果。
+    pub results: Vec<BatchReviewItem>,
+    /// 终审通过后超出每学年学时上限的提醒。
+    pub hour_cap_warnings: Vec<HourCapWarning>,
 }
 
 /// 提交竞赛获奖记录（学生）。
@@ -164,116 +247,85 @@ pub async fn create_contest_record(
     payload
         .validate()
         .map_err(|_| AppError::validation("invalid contest payload"))?;
-    if payload
-        .contest_level
-        .as_deref()
-        .map(|value| value.trim().is_empty())
-        .unwrap_or(true)
-    {
-        return Err(AppError::validation("contest_level required"));
-    }
-    if payload
-        .contest_role
-        .as_deref()
-        .map(|value| value.trim().is_empty())
-        .unwrap_or(true)
-    {
-        return Err(AppError::validation("contest_role required"));
-    }
+    state.settings.current().ensure_submission_open(chrono::Utc::now())?;
 
-    let student = Student::find()
-        .filter(students::Column::StudentNo.eq(&user.username))
-        .filter(students::Column::IsDeleted.eq(false))
-        .one(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-        .ok_or_else(|| AppError::not_found("student not found"))?;
-
-    let custom_fields = payload.custom_fields.unwrap_or_default();
-    let form_fields = load_form_fields(&state, "contest").await?;
-    validate_custom_fields(&form_fields, &custom_fields)?;
-
-    let now = Utc::now();
-    let id = Uuid::new_v4();
-    let award_date = parse_award_date(payload.award_date.as_deref())?;
-    let model = contest_records::ActiveModel {
-        id: Set(id),
-        student_id: Set(student.id),
-        contest_year: Set(payload.contest_year),
-        contest_category: Set(payload.contest_category.as_ref().map(|value| value.to_uppercase())),
-        contest_name: Set(payload.contest_name.clone()),
-        contest_level: Set(payload.contest_level.clone()),
-        contest_role: Set(payload.contest_role.clone()),
-        award_level: Set(payload.award_level.clone()),
-        award_date: Set(award_date),
-        self_hours: Set(payload.self_hours),
-        first_review_hours: Set(None),
-        final_review_hours: Set(None),
-        first_reviewer_id: Set(None),
-        final_reviewer_id: Set(None),
-        status: Set(STATUS_SUBMITTED.to_string()),
-        rejection_reason: Set(None),
-        is_deleted: Set(false),
-        created_at: Set(now),
-        updated_at: Set(now),
-    };
-    contest_records::Entity::insert(model)
-        .exec_without_returning(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-
-    let match_status = contest_match_status(&state, &payload.contest_name).await?;
-    let rule_config = load_labor_hour_rules(&state).await?;
-    let recommended_hours = compute_recommended_hours(
-        rule_config,
-        payload.contest_category.as_deref(),
-        payload.contest_level.as_deref(),
-        payload.contest_role.as_deref(),
-    );
-    let model_id = id;
-    insert_custom_fields(&state, "contest", model_id, &form_fields, &custom_fields).await?;
-    let custom_values = fetch_custom_fields(&state, "contest", &[model_id], &form_fields).await?;
-    let attachments = Vec::new();
-    let model = contest_records::Model {
-        id,
-        student_id: student.id,
-        contest_year: payload.contest_year,
-        contest_category: payload.contest_category.map(|value| value.to_uppercase()),
+    let student = require_student_profile(&state, &user).await?;
+    ensure_window_open(&state.db, "contest", Some(&student.department), chrono::Utc::now()).await?;
+    let mut input = NewContestRecord {
         contest_name: payload.contest_name,
         contest_level: payload.contest_level,
         contest_role: payload.contest_role,
+        contest_year: payload.contest_year,
+        contest_category: payload.contest_category,
         award_level: payload.award_level,
-        award_date,
+        award_date: payload.award_date,
         self_hours: payload.self_hours,
-        first_review_hours: None,
-        final_review_hours: None,
-        first_reviewer_id: None,
-        final_reviewer_id: None,
-        status: STATUS_SUBMITTED.to_string(),
-        rejection_reason: None,
-        is_deleted: false,
-        created_at: now,
-        updated_at: now,
+        recommended_hours: None,
+        custom_fields: payload.custom_fields.unwrap_or_default(),
     };
+    let rule_config = load_labor_hour_rules(&state).await?;
+    let library = load_competition_snapshot(&state).await?;
+    input.recommended_hours = submission_recommended_hours(
+        &library,
+        rule_config,
+        &input,
+        state.config.competition_match_max_edit_distance,
+    );
+    let mut model = DbRecordService::new(&state.db)
+        .create_contest_record(&student, input)
+        .await?;
+    if state.settings.current().auto_assign_reviewers {
+        model.assigned_reviewer_id =
+            auto_assign_record(&state.db, AssignRecordType::Contest, model.id).await?;
+        if let Some(reviewer_id) = model.assigned_reviewer_id {
+            record_audit(
+                &state.db,
+                None,
+                "record_auto_assign",
+                "contest_record",
+                Some(&model.id.to_string()),
+                Some(serde_json::json!({ "reviewer_id": reviewer_id })),
+            )
+            .await?;
+        }
+    }
+    state.events.publish(QueueEvent::record(
+        EVENT_RECORD_SUBMITTED,
+        "contest",
+        model.id,
+        &model.status,
+        Some(student.department.clone()),
+    ));
+
+    let contest_match = match_contest(&library, &model, state.config.competition_match_max_edit_distance);
+    let recommended_hours = record_recommended_hours(rule_config, &model);
+    let model_id = model.id;
+    let form_fields = load_form_fields(&state.db, "contest").await?;
+    let custom_values = fetch_custom_fields(&state, "contest", &[model_id], &form_fields).await?;
     Ok(Json(model_to_contest_response(
         model,
-        &match_status,
+        &contest_match,
         recommended_hours,
         custom_values.get(&model_id).cloned().unwrap_or_default(),
         Some(&student),
-        attachments,
+        Vec::new(),
     )))
 }
 
-/// 查询竞赛记录（学生或审核角色）。
+/// 查询竞赛记录（学生、审核角色或班主任）。
 pub async fn list_contest_records(
     State(state): State<AppState>,
     jar: CookieJar,
     Json(query): Json<ContestQuery>,
 ) -> Result<Json<Vec<ContestRecordResponse>>, AppError> {
     let user = require_session_user(&state, &jar).await?;
+    query.filter.validate()?;
 
-    let mut finder = ContestRecord::find().filter(contest_records::Column::IsDeleted.eq(false));
+    let include_student = query.include_student.unwrap_or(true);
+    let mut finder = ContestRecord::find()
+        .find_also_related(Student)
+        .filter(contest_records::Column::IsDeleted.eq(false))
+        .filter(query.filter.record_condition());
     if user.role == "student" {
         let student = Student::find()
             .filter(students::Column::StudentNo.eq(&user.username))
@@ -283,52 +335,58 @@ pub async fn list_contest_records(
             .map_err(|err| AppError::Database(err.to_string()))?
             .ok_or_else(|| AppError::not_found("student not found"))?;
         finder = finder.filter(contest_records::Column::StudentId.eq(student.id));
-    } else if user.role != "admin" && user.role != "teacher" && user.role != "reviewer" {
+    } else if !matches!(user.role.as_str(), "admin" | "teacher" | "reviewer" | ROLE_CLASS_ADVISOR) {
         return Err(AppError::auth("forbidden"));
     } else {
+        let classes = advisor_class_scope(&state.db, &user).await?;
         finder = finder
-            .join(JoinType::InnerJoin, contest_records::Relation::Student.def())
-            .filter(students::Column::IsDeleted.eq(false));
+            .filter(students::Column::IsDeleted.eq(false))
+            .filter(student_scope_condition(&user, classes.as_deref()))
+            .filter(query.filter.student_condition());
     }
 
     if let Some(status) = query.status {
         finder = finder.filter(contest_records::Column::Status.eq(status));
     }
+    if query.assigned_to_me {
+        finder = finder.filter(contest_records::Column::AssignedReviewerId.eq(user.id));
+    } else if let Some(reviewer_id) = query.assigned_reviewer_id {
+        finder = finder.filter(contest_records::Column::AssignedReviewerId.eq(reviewer_id));
+    }
 
+    // 学生信息与记录在同一个 JOIN 查询中读取。
     let records = finder
         .all(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
 
-    let form_fields = load_form_fields(&state, "contest").await?;
-    let ids: Vec<Uuid> = records.iter().map(|record| record.id).collect();
+    let form_fields = load_form_fields(&state.db, "contest").await?;
+    let ids: Vec<Uuid> = records.iter().map(|(record, _)| record.id).collect();
     let custom_values = fetch_custom_fields(&state, "contest", &ids, &form_fields).await?;
-    let student_ids: Vec<Uuid> = records.iter().map(|record| record.student_id).collect();
-    let students_map = load_students_map(&state, &student_ids).await?;
     let attachments_map = load_attachments_map(&state, "contest", &ids).await?;
 
     let rule_config = load_labor_hour_rules(&state).await?;
+    let library = load_competition_snapshot(&state).await?;
     let mut responses = Vec::with_capacity(records.len());
-    for record in records {
-        let match_status = contest_match_status(&state, &record.contest_name).await?;
-        let recommended_hours = compute_recommended_hours(
-            rule_config,
-            record.contest_category.as_deref(),
-            record.contest_level.as_deref(),
-            record.contest_role.as_deref(),
-        );
+    for (record, student) in records {
+        let contest_match =
+            match_contest(&library, &record, state.config.competition_match_max_edit_distance);
+        if !query.filter.accepts_match_status(contest_match.status) {
+            continue;
+        }
+        let recommended_hours = record_recommended_hours(rule_config, &record);
         let values = custom_values.get(&record.id).cloned().unwrap_or_default();
-        let student = students_map.get(&record.student_id);
+        let student = student.filter(|student| include_student && !student.is_deleted);
         let attachments = attachments_map
             .get(&record.id)
             .cloned()
             .unwrap_or_default();
         responses.push(model_to_contest_response(
             record,
-            &match_status,
+            &contest_match,
             recommended_hours,
             values,
-            student,
+            student.as_ref(),
             attachments,
         ));
     }
@@ -344,68 +402,141 @@ pub async fn review_contest_record(
     Json(payload): Json<ReviewRequest>,
 ) -> Result<Json<ContestRecordResponse>, AppError> {
     let user = require_session_user(&state, &jar).await?;
-    ensure_review_permission(&user, &payload.stage)?;
+    ensure_review_permission(&state.db, &user, &payload.stage).await?;
     payload
         .validate()
         .map_err(|_| AppError::validation("invalid review payload"))?;
 
-    let record = ContestRecord::find()
-        .filter(contest_records::Column::Id.eq(record_id))
+    let decision = payload.to_decision();
+    let model = DbReviewService::new(&state.db)
+        .review_contest_record(&user, record_id, payload.expected_version, &decision)
+        .await?;
+    notify_review_results(&state, vec![ReviewNotice::from_contest(&model)]).await;
+    let warnings = review_cap_warnings(&state, &decision, vec![model.student_id]).await;
+    let mut response = contest_record_response(&state, model).await?;
+    response.hour_cap_warnings = warnings;
+    state.events.publish(QueueEvent::record(
+        EVENT_RECORD_REVIEWED,
+        "contest",
+        response.id,
+        &response.status,
+        response.department.clone(),
+    ));
+    Ok(Json(response))
+}
+
+/// 确认竞赛记录对应的竞赛库条目请求。
+#[derive(Debug, Deserialize)]
+pub struct ConfirmCompetitionRequest {
+    /// 竞赛库条目 ID；为空时清除确认。
+    pub competition_id: Option<Uuid>,
+}
+
+/// 确认或清除竞赛记录与竞赛库条目的对应关系（审核人员/教师/管理员）。
+pub async fn confirm_contest_competition(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(record_id): Path<Uuid>,
+    Json(payload): Json<ConfirmCompetitionRequest>,
+) -> Result<Json<ContestRecordResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    if !matches!(user.role.as_str(), "admin" | "teacher" | "reviewer") {
+        return Err(AppError::auth("forbidden"));
+    }
+    let (record, student) = ContestRecord::find_by_id(record_id)
         .filter(contest_records::Column::IsDeleted.eq(false))
+        .find_also_related(Student)
         .one(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?
         .ok_or_else(|| AppError::not_found("record not found"))?;
-
-    let mut active: contest_records::ActiveModel = record.into();
-    apply_review_update(&payload, &mut active.status, &mut active.rejection_reason)?;
-    if payload.stage == REVIEW_STAGE_FIRST {
-        active.first_review_hours = Set(Some(payload.hours));
-        active.first_reviewer_id = Set(Some(user.id));
-    } else {
-        active.final_review_hours = Set(Some(payload.hours));
-        active.final_reviewer_id = Set(Some(user.id));
+    let student = student.ok_or_else(|| AppError::not_found("record not found"))?;
+    ensure_student_in_scope(&user, &student)?;
+    if let Some(competition_id) = payload.competition_id {
+        CompetitionLibrary::find_by_id(competition_id)
+            .one(&state.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .ok_or_else(|| AppError::not_found("competition not found"))?;
     }
-    active.updated_at = Set(Utc::now());
 
+    let previous = record.competition_id;
+    let mut active: contest_records::ActiveModel = record.into();
+    // 仅修改对应关系，不更新 updated_at，避免影响按审核时间统计的导出。
+    active.competition_id = Set(payload.competition_id);
     let model = active
         .update(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(user.id),
+        "contest_competition_confirm",
+        "contest_record",
+        Some(&model.id.to_string()),
+        Some(serde_json::json!({
+            "previous": previous,
+            "competition_id": payload.competition_id,
+        })),
+    )
+    .await?;
+    Ok(Json(contest_record_response(&state, model).await?))
+}
 
-    let match_status = contest_match_status(&state, &model.contest_name).await?;
-    let rule_config = load_labor_hour_rules(&state).await?;
-    let recommended_hours = compute_recommended_hours(
-        rule_config,
-        model.contest_category.as_deref(),
-        model.contest_level.as_deref(),
-        model.contest_role.as_deref(),
-    );
-    let form_fields = load_form_fields(&state, "contest").await?;
+/// 组装单条竞赛记录的完整响应。
+async fn contest_record_response(
+    state: &AppState,
+    model: contest_records::Model,
+) -> Result<ContestRecordResponse, AppError> {
+    let contest_match = contest_match(state, &model).await?;
+    let rule_config = load_labor_hour_rules(state).await?;
+    let recommended_hours = record_recommended_hours(rule_config, &model);
+    let form_fields = load_form_fields(&state.db, "contest").await?;
     let model_id = model.id;
-    let custom_values = fetch_custom_fields(&state, "contest", &[model_id], &form_fields).await?;
+    let custom_values = fetch_custom_fields(state, "contest", &[model_id], &form_fields).await?;
     let student = Student::find_by_id(model.student_id)
         .filter(students::Column::IsDeleted.eq(false))
         .one(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
-    let attachments = load_attachments_map(&state, "contest", &[model_id])
+    let attachments = load_attachments_map(state, "contest", &[model_id])
         .await?
         .remove(&model_id)
         .unwrap_or_default();
-    Ok(Json(model_to_contest_response(
+    Ok(model_to_contest_response(
         model,
-        &match_status,
+        &contest_match,
         recommended_hours,
         custom_values.get(&model_id).cloned().unwrap_or_default(),
         student.as_ref(),
         attachments,
-    )))
+    ))
+}
+
+/// 提交时的推荐学时：名称匹配竞赛库且类型、级别、角色齐全时按学时规则计算，类型缺省时取竞赛库条目的类型。
+fn submission_recommended_hours(
+    library: &[competition_library::Model],
+    rules: LaborHourRuleConfig,
+    input: &NewContestRecord,
+    max_edit_distance: Option<usize>,
+) -> Option<i32> {
+    let present = |value: Option<&str>| value.map(str::trim).filter(|value| !value.is_empty()).map(str::to_string);
+    let entry = fuzzy::best_match(
+        &input.contest_name,
+        library.iter().map(|item| (item.name.as_str(), item)),
+        max_edit_distance,
+    )
+    .filter(|found| found.confidence >= MatchConfidence::High)?
+    .item;
+    let category = present(input.contest_category.as_deref()).or_else(|| present(entry.category.as_deref()))?;
+    let level = present(input.contest_level.as_deref())?;
+    let role = present(input.contest_role.as_deref())?;
+    Some(compute_recommended_hours(rules, Some(&category), Some(&level), Some(&role)))
 }
 
 fn model_to_contest_response(
     model: contest_records::Model,
-    match_status: &str,
+    contest_match: &ContestMatch,
     recommended_hours: i32,
     custom_fields: Vec<CustomFieldValueResponse>,
     student: Option<&students::Model>,
@@ -431,33 +562,20 @@ fn model_to_contest_response(
         final_review_hours: model.final_review_hours,
         status: model.status,
         rejection_reason: model.rejection_reason,
-        match_status: match_status.to_string(),
+        semester_id: model.semester_id,
+        assigned_reviewer_id: model.assigned_reviewer_id,
+        version: model.version,
+        match_status: contest_match.status.to_string(),
+        matched_competition_id: contest_match.competition_id,
+        match_confidence: contest_match.confidence,
+        hours_deviation: model.recommended_hours.map(|recommended| model.self_hours - recommended),
         recommended_hours,
         custom_fields,
         attachments,
+        hour_cap_warnings: Vec::new(),
     }
 }
 
-async fn load_students_map(
-    state: &AppState,
-    student_ids: &[Uuid],
-) -> Result<HashMap<Uuid, students::Model>, AppError> {
-    if student_ids.is_empty() {
-        return Ok(HashMap::new());
-    }
-    let rows = Student::find()
-        .filter(students::Column::Id.is_in(student_ids.iter().cloned()))
-        .filter(students::Column::IsDeleted.eq(false))
-        .all(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-    let mut map = HashMap::new();
-    for row in rows {
-        map.insert(row.id, row);
-    }
-    Ok(map)
-}
-
 async fn load_attachments_map(
     state: &AppState,
     record_type: &str,
@@ -469,6 +587,7 @@ async fn load_attachments_map(
     let rows = Attachment::find()
         .filter(attachments::Column::RecordType.eq(record_type))
         .filter(attachments::Column::RecordId.is_in(record_ids.iter().cloned()))
+        .filter(attachments::Column::Kind.eq(ATTACHMENT_KIND_EVIDENCE))
         .all(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
@@ -480,6 +599,10 @@ async fn load_attachments_map(
             .push(AttachmentInfo {
                 id: row.id,
                 original_name: row.original_name,
+                thumbnail_url: row
+                    .mime_type
+                    .starts_with("image/")
+                    .then(|| format!("/attachments/{}/thumbnail", row.id)),
                 mime_type: row.mime_type,
                 download_url: format!("/attachments/{}", row.id),
             });
@@ -487,146 +610,249 @@ async fn load_attachments_map(
     Ok(grouped)
 }
 
-async fn contest_match_status(state: &AppState, contest_name: &str) -> Result<String, AppError> {
-    let match_exists = CompetitionLibrary::find()
-        .filter(competition_library::Column::Name.eq(contest_name))
-        .one(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-        .is_some();
+/// 竞赛名称与竞赛库的匹配结果。
+#[derive(Debug, Clone, PartialEq)]
+struct ContestMatch {
+    /// matched：原文一致、归一化后一致或已确认；fuzzy：相似待确认；unmatched：无相似条目。
+    status: &'static str,
+    competition_id: Option<Uuid>,
+    confidence: Option<MatchConfidence>,
+}
 
-    if match_exists {
-        Ok("matched".to_string())
-    } else {
-        Ok("unmatched".to_string())
-    }
+async fn contest_match(state: &AppState, record: &contest_records::Model) -> Result<ContestMatch, AppError> {
+    let library = load_competition_snapshot(state).await?;
+    Ok(match_contest(&library, record, state.config.competition_match_max_edit_distance))
 }
 
-fn ensure_review_permission(user: &crate::entities::users::Model, stage: &str) -> Result<(), AppError> {
-    if stage == REVIEW_STAGE_FIRST && (user.role == "reviewer" || user.role == "admin") {
-        return Ok(());
-    }
-    if stage == REVIEW_STAGE_FINAL && (user.role == "teacher" || user.role == "admin") {
-        return Ok(());
-    }
-    Err(AppError::auth("forbidden"))
-}
-
-fn apply_review_update(
-    payload: &ReviewRequest,
-    status: &mut sea_orm::ActiveValue<String>,
-    rejection_reason: &mut sea_orm::ActiveValue<Option<String>>,
-) -> Result<(), AppError> {
-    if payload.status == "rejected" {
-        *status = Set(STATUS_REJECTED.to_string());
-        *rejection_reason = Set(payload.rejection_reason.clone());
-        return Ok(());
+/// 匹配竞赛库：已确认的条目优先，其次取置信度最高的名称匹配。
+fn match_contest(
+    library: &[competition_library::Model],
+    record: &contest_records::Model,
+    max_edit_distance: Option<usize>,
+) -> ContestMatch {
+    if let Some(confirmed) = record
+        .competition_id
+        .filter(|id| library.iter().any(|item| item.id == *id))
+    {
+        return ContestMatch {
+            status: "matched",
+            competition_id: Some(confirmed),
+            confidence: Some(MatchConfidence::Confirmed),
+        };
     }
-
-    if payload.stage == REVIEW_STAGE_FIRST {
-        *status = Set(STATUS_FIRST_REVIEWED.to_string());
-    } else if payload.stage == REVIEW_STAGE_FINAL {
-        *status = Set(STATUS_FINAL_REVIEWED.to_string());
-    } else {
-        return Err(AppError::bad_request("invalid review stage"));
+    let best = fuzzy::best_match(
+        &record.contest_name,
+        library.iter().map(|item| (item.name.as_str(), item.id)),
+        max_edit_distance,
+    );
+    match best {
+        Some(found) => ContestMatch {
+            status: if found.confidence >= MatchConfidence::High {
+                "matched"
+            } else {
+                "fuzzy"
+            },
+            competition_id: Some(found.item),
+            confidence: Some(found.confidence),
+        },
+        None => ContestMatch {
+            status: "unmatched",
+            competition_id: None,
+            confidence: None,
+        },
     }
+}
 
-    *rejection_reason = Set(None);
-    Ok(())
+/// 按竞赛名称匹配状态筛选记录 ID（条件需基于关联学生的竞赛记录查询）。
+pub(crate) async fn record_ids_with_match_status(
+    state: &AppState,
+    condition: Condition,
+    status: &str,
+) -> Result<Vec<Uuid>, AppError> {
+    let records = ContestRecord::find()
+        .join(JoinType::InnerJoin, contest_records::Relation::Student.def())
+        .filter(condition)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let library = load_competition_snapshot(state).await?;
+    Ok(records
+        .into_iter()
+        .filter(|record| {
+            match_contest(&library, record, state.config.competition_match_max_edit_distance).status == status
+        })
+        .map(|record| record.id)
+        .collect())
 }
 
-fn parse_award_date(value: Option<&str>) -> Result<Option<chrono::DateTime<chrono::Utc>>, AppError> {
-    let Some(raw) = value else {
-        return Ok(None);
-    };
-    let trimmed = raw.trim();
-    if trimmed.is_empty() {
-        return Ok(None);
-    }
-    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(trimmed) {
-        return Ok(Some(dt.with_timezone(&Utc)));
-    }
-    if let Ok(date) = chrono::NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
-        let dt = date.and_hms_opt(0, 0, 0).ok_or_else(|| AppError::validation("invalid award date"))?;
-        return Ok(Some(Utc.from_utc_datetime(&dt)));
-    }
-    Err(AppError::validation("invalid award date"))
+/// 批量审核竞赛记录（单个事务内完成）。
+pub async fn batch_review_contest_records(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<BatchReviewRequest>,
+) -> Result<Json<BatchReviewResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    ensure_review_permission(&state.db, &user, &payload.stage).await?;
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid review payload"))?;
+    let decision = payload.to_decision();
+    let outcomes = DbReviewService::new(&state.db)
+        .batch_review_contest_records(&user, &payload.record_ids, &decision)
+        .await?;
+    let reviewed = ContestRecord::find()
+        .filter(contest_records::Column::Id.is_in(updated_record_ids(&outcomes)))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    publish_review_events(
+        &state,
+        "contest",
+        reviewed
+            .iter()
+            .map(|record| (record.id, record.student_id, record.status.clone()))
+            .collect(),
+    )
+    .await;
+    notify_review_results(&state, reviewed.iter().map(ReviewNotice::from_contest).collect()).await;
+    let student_ids = reviewed.iter().map(|record| record.student_id).collect();
+    let warnings = review_cap_warnings(&state, &decision, student_ids).await;
+
+    let mut response = BatchReviewResponse::from_outcomes(outcomes);
+    response.hour_cap_warnings = warnings;
+    Ok(Json(response))
 }
 
-async fn load_form_fields(
-    state: &AppState,
-    form_type: &str,
-) -> Result<Vec<form_fields::Model>, AppError> {
-    FormField::find()
-        .filter(form_fields::Column::FormType.eq(form_type))
+/// 批量审核志愿服务记录（单个事务内完成）。
+pub async fn batch_review_volunteer_records(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<BatchReviewRequest>,
+) -> Result<Json<BatchReviewResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    ensure_review_permission(&state.db, &user, &payload.stage).await?;
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid review payload"))?;
+    let outcomes = DbReviewService::new(&state.db)
+        .batch_review_volunteer_records(&user, &payload.record_ids, &payload.to_decision())
+        .await?;
+    let reviewed = VolunteerRecord::find()
+        .filter(volunteer_records::Column::Id.is_in(updated_record_ids(&outcomes)))
         .all(&state.db)
         .await
-        .map_err(|err| AppError::Database(err.to_string()))
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    publish_review_events(
+        &state,
+        "volunteer",
+        reviewed
+            .iter()
+            .map(|record| (record.id, record.student_id, record.status.clone()))
+            .collect(),
+    )
+    .await;
+    notify_review_results(&state, reviewed.iter().map(ReviewNotice::from_volunteer).collect()).await;
+
+    Ok(Json(BatchReviewResponse::from_outcomes(outcomes)))
 }
 
-fn validate_custom_fields(
-    fields: &[form_fields::Model],
-    payload: &HashMap<String, String>,
-) -> Result<(), AppError> {
-    let mut field_map = HashMap::new();
-    for field in fields {
-        field_map.insert(field.field_key.as_str(), field);
+/// 终审通过后检查相关学生的每学年学时上限；检查失败只记录日志，不影响审核结果。
+async fn review_cap_warnings(
+    state: &AppState,
+    decision: &ReviewDecision,
+    mut student_ids: Vec<Uuid>,
+) -> Vec<HourCapWarning> {
+    if decision.stage != REVIEW_STAGE_FINAL || decision.status == "rejected" {
+        return Vec::new();
     }
+    student_ids.sort();
+    student_ids.dedup();
+    hour_cap_warnings(state, &student_ids).await.unwrap_or_else(|err| {
+        tracing::warn!(error = %err, "hour cap check failed");
+        Vec::new()
+    })
+}
 
-    for field in fields {
-        if field.required {
-            let value = payload.get(&field.field_key);
-            if value.is_none() || value.is_some_and(|val| val.trim().is_empty()) {
-                return Err(AppError::validation("missing required custom field"));
-            }
+fn updated_record_ids(outcomes: &[ReviewOutcome]) -> Vec<Uuid> {
+    outcomes
+        .iter()
+        .filter(|outcome| outcome.status.is_some())
+        .map(|outcome| outcome.record_id)
+        .collect()
+}
+
+impl ReviewRequest {
+    fn to_decision(&self) -> ReviewDecision {
+        ReviewDecision {
+            stage: self.stage.clone(),
+            hours: self.hours,
+            status: self.status.clone(),
+            rejection_reason: self.rejection_reason.clone(),
         }
     }
+}
 
-    for key in payload.keys() {
-        if !field_map.contains_key(key.as_str()) {
-            return Err(AppError::validation("unknown custom field"));
+impl BatchReviewRequest {
+    fn to_decision(&self) -> ReviewDecision {
+        ReviewDecision {
+            stage: self.stage.clone(),
+            hours: self.hours,
+            status: self.status.clone(),
+            rejection_reason: self.rejection_reason.clone(),
         }
     }
-
-    Ok(())
 }
 
-async fn insert_custom_fields(
-    state: &AppState,
-    record_type: &str,
-    record_id: Uuid,
-    fields: &[form_fields::Model],
-    payload: &HashMap<String, String>,
-) -> Result<(), AppError> {
-    let mut field_map = HashMap::new();
-    for field in fields {
-        field_map.insert(field.field_key.as_str(), field);
+impl BatchReviewItem {
+    fn updated(record_id: Uuid, status: String) -> Self {
+        Self {
+            record_id,
+            updated: true,
+            status: Some(status),
+            error: None,
+        }
     }
 
-    for (key, value) in payload {
-        if value.trim().is_empty() {
-            continue;
-        }
-        if let Some(field) = field_map.get(key.as_str()) {
-            let value_model = form_field_values::ActiveModel {
-                id: Set(Uuid::new_v4()),
-                record_type: Set(record_type.to_string()),
-                record_id: Set(record_id),
-                field_key: Set(field.field_key.clone()),
-                value: Set(value.to_string()),
-                created_at: Set(Utc::now()),
-            };
-            form_field_values::Entity::insert(value_model)
-                .exec_without_returning(&state.db)
-                .await
-                .map_err(|err| AppError::Database(err.to_string()))?;
+    fn failed(record_id: Uuid, error: &str) -> Self {
+        Self {
+            record_id,
+            updated: false,
+            status: None,
+            error: Some(error.to_string()),
         }
     }
+}
+
+impl BatchReviewResponse {
+    fn from_outcomes(outcomes: Vec<ReviewOutcome>) -> Self {
+        Self::from_results(
+            outcomes
+                .into_iter()
+                .map(|outcome| match outcome.status {
+                    Some(status) => BatchReviewItem::updated(outcome.record_id, status),
+                    None if outcome.conflict => BatchReviewItem::failed(outcome.record_id, "version conflict"),
+                    None if outcome.missing_evidence.is_some() => BatchReviewItem::failed(
+                        outcome.record_id,
+                        outcome.missing_evidence.as_deref().unwrap_or_default(),
+                    ),
+                    None => BatchReviewItem::failed(outcome.record_id, "record not found"),
+                })
+                .collect(),
+        )
+    }
 
-    Ok(())
+    fn from_results(results: Vec<BatchReviewItem>) -> Self {
+        let updated = results.iter().filter(|item| item.updated).count();
+        Self {
+            updated,
+            failed: results.len() - updated,
+            results,
+            hour_cap_warnings: Vec::new(),
+        }
+    }
 }
 
-async fn fetch_custom_fields(
+pub(crate) async fn fetch_custom_fields(
     state: &AppState,
     record_type: &str,
     record_ids: &[Uuid],
@@ -676,97 +902,9 @@ async fn fetch_custom_fields(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::review::{STATUS_FIRST_REVIEWED, STATUS_SUBMITTED};
     use chrono::Utc;
 
-    #[test]
-    fn apply_review_update_rejects() {
-        let payload = ReviewRequest {
-            stage: REVIEW_STAGE_FIRST.to_string(),
-            hours: 2,
-            status: "rejected".to_string(),
-            rejection_reason: Some("no proof".to_string()),
-        };
-        let mut status = sea_orm::ActiveValue::set("".to_string());
-        let mut reason = sea_orm::ActiveValue::set(None);
-        apply_review_update(&payload, &mut status, &mut reason).expect("apply");
-        assert_eq!(status.unwrap(), STATUS_REJECTED.to_string());
-        assert_eq!(reason.unwrap(), Some("no proof".to_string()));
-    }
-
-    #[test]
-    fn apply_review_update_first_pass() {
-        let payload = ReviewRequest {
-            stage: REVIEW_STAGE_FIRST.to_string(),
-            hours: 2,
-            status: "approved".to_string(),
-            rejection_reason: None,
-        };
-        let mut status = sea_orm::ActiveValue::set("".to_string());
-        let mut reason = sea_orm::ActiveValue::set(None);
-        apply_review_update(&payload, &mut status, &mut reason).expect("apply");
-        assert_eq!(status.unwrap(), STATUS_FIRST_REVIEWED.to_string());
-        assert_eq!(reason.unwrap(), None);
-    }
-
-    #[test]
-    fn ensure_review_permission_allows_expected_roles() {
-        let user = crate::entities::users::Model {
-            id: Uuid::new_v4(),
-            username: "u1".to_string(),
-            display_name: "u1".to_string(),
-            role: "reviewer".to_string(),
-            email: None,
-            password_hash: None,
-            allow_password_login: false,
-            password_updated_at: None,
-            must_change_password: false,
-            is_active: true,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        };
-        ensure_review_permission(&user, REVIEW_STAGE_FIRST).expect("reviewer allowed");
-        assert!(ensure_review_permission(&user, REVIEW_STAGE_FINAL).is_err());
-    }
-
-    #[test]
-    fn validate_custom_fields_rejects_missing_required_and_unknown() {
-        let fields = vec![
-            form_fields::Model {
-                id: Uuid::new_v4(),
-                form_type: "contest".to_string(),
-                field_key: "location".to_string(),
-                label: "地点".to_string(),
-                field_type: "text".to_string(),
-                required: true,
-                order_index: 1,
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-            },
-            form_fields::Model {
-                id: Uuid::new_v4(),
-                form_type: "contest".to_string(),
-                field_key: "note".to_string(),
-                label: "备注".to_string(),
-                field_type: "text".to_string(),
-                required: false,
-                order_index: 2,
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-            },
-        ];
-
-        let empty_payload = HashMap::new();
-        assert!(validate_custom_fields(&fields, &empty_payload).is_err());
-
-        let mut unknown_payload = HashMap::new();
-        unknown_payload.insert("unknown".to_string(), "value".to_string());
-        assert!(validate_custom_fields(&fields, &unknown_payload).is_err());
-
-        let mut ok_payload = HashMap::new();
-        ok_payload.insert("location".to_string(), "校内".to_string());
-        assert!(validate_custom_fields(&fields, &ok_payload).is_ok());
-    }
-
     #[test]
     fn model_to_response_copies_fields() {
         let contest = contest_records::Model {
@@ -784,14 +922,134 @@ mod tests {
             final_review_hours: None,
             first_reviewer_id: None,
             final_reviewer_id: None,
+            competition_id: None,
+            semester_id: None,
+            assigned_reviewer_id: None,
             status: STATUS_SUBMITTED.to_string(),
             rejection_reason: None,
+            version: 0,
+            recommended_hours: None,
+            first_reviewed_at: None,
+            final_reviewed_at: None,
+            import_batch_id: None,
             is_deleted: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
-        let contest_resp = model_to_contest_response(contest, "matched", 2, Vec::new(), None, Vec::new());
+        let contest_match = ContestMatch {
+            status: "matched",
+            competition_id: None,
+            confidence: Some(MatchConfidence::Exact),
+        };
+        let contest_resp = model_to_contest_response(contest, &contest_match, 2, Vec::new(), None, Vec::new());
         assert_eq!(contest_resp.match_status, "matched");
+        assert_eq!(contest_resp.match_confidence, Some(MatchConfidence::Exact));
         assert_eq!(contest_resp.contest_name, "竞赛");
     }
+
+    #[test]
+    fn match_contest_prefers_confirmed_then_best_name() {
+        let entry = |name: &str| competition_library::Model {
+            id: Uuid::new_v4(),
+            year: None,
+            category: None,
+            name: name.to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let library = vec![entry("互联网+大学生创新创业大赛"), entry("全国大学生数学建模竞赛")];
+        let mut record = contest_records::Model {
+            id: Uuid::new_v4(),
+            student_id: Uuid::new_v4(),
+            contest_year: None,
+            contest_category: None,
+            contest_name: "“互联网+”大学生创新创业大赛".to_string(),
+            contest_level: None,
+            contest_role: None,
+            award_level: "一等奖".to_string(),
+            award_date: None,
+            self_hours: 0,
+            first_review_hours: None,
+            final_review_hours: None,
+            first_reviewer_id: None,
+            final_reviewer_id: None,
+            competition_id: None,
+            semester_id: None,
+            assigned_reviewer_id: None,
+            status: STATUS_SUBMITTED.to_string(),
+            rejection_reason: None,
+            version: 0,
+            recommended_hours: None,
+            first_reviewed_at: None,
+            final_reviewed_at: None,
+            import_batch_id: None,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let result = match_contest(&library, &record, None);
+        assert_eq!(result.status, "matched");
+        assert_eq!(result.competition_id, Some(library[0].id));
+        assert_eq!(result.confidence, Some(MatchConfidence::High));
+
+        record.contest_name = "全国大学生数学建模大赛".to_string();
+        let result = match_contest(&library, &record, Some(1));
+        assert_eq!(result.status, "fuzzy");
+        assert_eq!(result.competition_id, Some(library[1].id));
+
+        record.competition_id = Some(library[0].id);
+        let result = match_contest(&library, &record, Some(1));
+        assert_eq!(result.confidence, Some(MatchConfidence::Confirmed));
+        assert_eq!(result.competition_id, Some(library[0].id));
+
+        record.competition_id = None;
+        record.contest_name = "蓝桥杯".to_string();
+        assert_eq!(match_contest(&library, &record, None).status, "unmatched");
+    }
+
+    #[test]
+    fn submission_recommendation_requires_library_match_and_full_details() {
+        let library = vec![competition_library::Model {
+            id: Uuid::new_v4(),
+            year: None,
+            category: Some("A".to_string()),
+            name: "全国大学生数学建模竞赛".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }];
+        let rules = LaborHourRuleConfig::default();
+        let input = NewContestRecord {
+            contest_name: "全国大学生数学建模竞赛".to_string(),
+            contest_level: Some("国家级".to_string()),
+            contest_role: Some("负责人".to_string()),
+            ..NewContestRecord::default()
+        };
+        assert_eq!(
+            submission_recommended_hours(&library, rules, &input, None),
+            Some(rules.base_hours_a + rules.national_leader_hours)
+        );
+
+        let unmatched = NewContestRecord {
+            contest_name: "蓝桥杯".to_string(),
+            ..input.clone()
+        };
+        assert_eq!(submission_recommended_hours(&library, rules, &unmatched, None), None);
+
+        let missing_role = NewContestRecord {
+            contest_role: Some(" ".to_string()),
+            ..input
+        };
+        assert_eq!(submission_recommended_hours(&library, rules, &missing_role, None), None);
+    }
+
+    #[test]
+    fn batch_review_response_counts_results() {
+        let response = BatchReviewResponse::from_results(vec![
+            BatchReviewItem::updated(Uuid::new_v4(), STATUS_FIRST_REVIEWED.to_string()),
+            BatchReviewItem::failed(Uuid::new_v4(), "record not found"),
+        ]);
+        assert_eq!(response.updated, 1);
+        assert_eq!(response.failed, 1);
+        assert_eq!(response.results[1].error.as_deref(), Some("record not found"));
+    }
 }
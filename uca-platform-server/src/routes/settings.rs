@@ -0,0 +1,42 @@
+//! 运行时设置查询与更新。
+
+use axum::{extract::State, Json};
+use axum_extra::extract::cookie::CookieJar;
+
+use crate::{
+    access::{require_role, require_session_user},
+    error::AppError,
+    settings::{save_runtime_settings, RuntimeSettings},
+    state::AppState,
+};
+
+/// 获取当前生效的运行时设置（公开，供前端展示维护提示与提交时间窗口）。
+pub async fn get_runtime_settings(
+    State(state): State<AppState>,
+) -> Result<Json<RuntimeSettings>, AppError> {
+    let mut settings = state.settings.current();
+    settings.reset_delivery = Some(state.reset_delivery());
+    Ok(Json(settings))
+}
+
+/// 获取已保存的运行时设置（管理员；`reset_delivery` 为空表示沿用启动配置）。
+pub async fn get_admin_runtime_settings(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<RuntimeSettings>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    Ok(Json(state.settings.current()))
+}
+
+/// 更新运行时设置，立即生效（管理员）。
+pub async fn update_runtime_settings(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<RuntimeSettings>,
+) -> Result<Json<RuntimeSettings>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    let updated = save_runtime_settings(&state, user.id, payload).await?;
+    Ok(Json(updated))
+}
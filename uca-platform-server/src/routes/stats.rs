@@ -0,0 +1,533 @@
+//! 审核统计看板接口。
+
+use std::collections::{BTreeMap, HashMap};
+
+use async_graphql::SimpleObject;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::{Expr, SimpleExpr}, ColumnTrait, Condition, ConnectionTrait, EntityTrait, FromQueryResult, JoinType,
+    QueryFilter, QuerySelect, RelationTrait,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    access::{
+        advisor_class_scope, require_role, require_session_user, student_scope_condition, ROLE_CLASS_ADVISOR,
+    },
+    assignments::{load_assignable_reviewers, pending_workload},
+    db::{seconds_between, sum_as_i64},
+    entities::{contest_records, students, users, ContestRecord, User},
+    error::AppError,
+    labor_hours::approved_hours_expr,
+    services::review::{STATUS_FINAL_REVIEWED, STATUS_FIRST_REVIEWED, STATUS_REJECTED, STATUS_SUBMITTED},
+    state::AppState,
+};
+
+/// 统计筛选条件。
+#[derive(Debug, Default, Deserialize)]
+pub struct StatsQuery {
+    /// 竞赛年份（可选）。
+    pub year: Option<i32>,
+    /// 院系（可选，仅 overview 使用）。
+    pub department: Option<String>,
+}
+
+/// 各状态记录数。
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, SimpleObject)]
+pub struct StatusCounts {
+    pub submitted: i64,
+    pub first_reviewed: i64,
+    pub final_reviewed: i64,
+    pub rejected: i64,
+}
+
+impl StatusCounts {
+    fn add(&mut self, status: &str, count: i64) {
+        match status {
+            STATUS_SUBMITTED => self.submitted += count,
+            STATUS_FIRST_REVIEWED => self.first_reviewed += count,
+            STATUS_FINAL_REVIEWED => self.final_reviewed += count,
+            STATUS_REJECTED => self.rejected += count,
+            _ => {}
+        }
+    }
+}
+
+/// 班级分布。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, SimpleObject)]
+pub struct ClassStats {
+    /// 院系。
+    pub department: String,
+    /// 班级。
+    pub class_name: String,
+    /// 各状态记录数。
+    pub counts: StatusCounts,
+    /// 通过学时合计。
+    pub approved_hours: i64,
+}
+
+/// 总览统计。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, SimpleObject)]
+#[graphql(name = "StatsOverview")]
+pub struct StatsOverviewResponse {
+    /// 各状态记录数。
+    pub counts: StatusCounts,
+    /// 通过学时合计。
+    pub approved_hours: i64,
+    /// 按班级分布。
+    pub classes: Vec<ClassStats>,
+}
+
+/// 院系统计。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DepartmentStats {
+    /// 院系。
+    pub department: String,
+    /// 各状态记录数。
+    pub counts: StatusCounts,
+    /// 通过学时合计。
+    pub approved_hours: i64,
+    /// 按班级分布。
+    pub classes: Vec<ClassStats>,
+}
+
+/// 审核人员统计筛选条件（按审核时间）。
+#[derive(Debug, Default, Deserialize)]
+pub struct ReviewerStatsQuery {
+    /// 起始时间（RFC3339，含）。
+    pub since: Option<DateTime<Utc>>,
+    /// 截止时间（RFC3339，不含）。
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// 单个审核人员的审核统计。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewerStats {
+    /// 用户 ID。
+    pub reviewer_id: Uuid,
+    /// 用户名。
+    pub username: String,
+    /// 显示名称。
+    pub display_name: String,
+    /// 角色。
+    pub role: String,
+    /// 完成初审的记录数（含不通过）。
+    pub first_reviewed: i64,
+    /// 完成终审的记录数（含不通过）。
+    pub final_reviewed: i64,
+    /// 审核不通过的记录数。
+    pub rejected: i64,
+    /// 不通过率（不通过数 / 初审与终审合计），无审核时为 0。
+    pub rejection_rate: f64,
+    /// 提交到初审的平均耗时（秒），无可计算记录时为空。
+    pub avg_first_turnaround_seconds: Option<i64>,
+    /// 提交到终审的平均耗时（秒），无可计算记录时为空。
+    pub avg_final_turnaround_seconds: Option<i64>,
+    /// 已指派且仍待审核的记录数（竞赛与志愿合计）。
+    pub pending: u64,
+}
+
+/// 按审核人聚合的单阶段审核结果行。
+#[derive(Debug, Clone, FromQueryResult)]
+pub(crate) struct ReviewerStageRow {
+    reviewer_id: Uuid,
+    reviewed: i64,
+    rejected: Option<i64>,
+    /// 有审核时间、可计算耗时的记录数。
+    timed: i64,
+    turnaround_seconds: Option<i64>,
+}
+
+/// 分组聚合结果行（院系 × 班级 × 状态）。
+#[derive(Debug, Clone, FromQueryResult)]
+pub(crate) struct StatGroupRow {
+    department: String,
+    class_name: String,
+    status: String,
+    record_count: i64,
+    approved_hours: Option<i64>,
+}
+
+/// 获取竞赛记录审核总览（管理员/教师/审核人员/班主任，受数据范围限制）。
+pub async fn stats_overview(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<StatsQuery>,
+) -> Result<Json<StatsOverviewResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    ensure_stats_role(&user.role)?;
+    let classes = advisor_class_scope(&state.db, &user).await?;
+    let rows = load_stat_groups(
+        &state.db,
+        student_scope_condition(&user, classes.as_deref()),
+        query.department.as_deref(),
+        query.year,
+    )
+    .await?;
+    Ok(Json(build_overview(&rows)))
+}
+
+/// 按院系获取竞赛记录审核统计（管理员/教师/审核人员/班主任，受数据范围限制）。
+pub async fn stats_by_department(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<StatsQuery>,
+) -> Result<Json<Vec<DepartmentStats>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    ensure_stats_role(&user.role)?;
+    let classes = advisor_class_scope(&state.db, &user).await?;
+    let rows =
+        load_stat_groups(&state.db, student_scope_condition(&user, classes.as_deref()), None, query.year).await?;
+    Ok(Json(build_by_department(&rows)))
+}
+
+/// 按审核人员统计竞赛记录的审核量、平均审核周期、不通过率与待审数量（仅管理员）。
+pub async fn stats_reviewers(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Query(query): Query<ReviewerStatsQuery>,
+) -> Result<Json<Vec<ReviewerStats>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+    if let (Some(since), Some(until)) = (query.since, query.until) {
+        if since >= until {
+            return Err(AppError::validation("since must be before until"));
+        }
+    }
+
+    let first_rows = load_reviewer_stage(
+        &state.db,
+        contest_records::Column::FirstReviewerId,
+        contest_records::Column::FirstReviewedAt,
+        Expr::col((contest_records::Entity, contest_records::Column::Status))
+            .eq(STATUS_REJECTED)
+            .and(Expr::col((contest_records::Entity, contest_records::Column::FinalReviewerId)).is_null()),
+        &query,
+    )
+    .await?;
+    let final_rows = load_reviewer_stage(
+        &state.db,
+        contest_records::Column::FinalReviewerId,
+        contest_records::Column::FinalReviewedAt,
+        Expr::col((contest_records::Entity, contest_records::Column::Status)).eq(STATUS_REJECTED),
+        &query,
+    )
+    .await?;
+    let pending = pending_workload(&state.db).await?;
+
+    let mut reviewer_ids: Vec<Uuid> = first_rows
+        .iter()
+        .chain(final_rows.iter())
+        .map(|row| row.reviewer_id)
+        .chain(pending.keys().copied())
+        .collect();
+    reviewer_ids.sort();
+    reviewer_ids.dedup();
+    let mut reviewers = User::find()
+        .filter(users::Column::Id.is_in(reviewer_ids))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    for reviewer in load_assignable_reviewers(&state.db).await? {
+        if !reviewers.iter().any(|existing| existing.id == reviewer.id) {
+            reviewers.push(reviewer);
+        }
+    }
+    Ok(Json(build_reviewer_stats(reviewers, &first_rows, &final_rows, &pending)))
+}
+
+/// 按审核人分组统计单个审核阶段；`rejected` 为该阶段不通过的判定条件。
+async fn load_reviewer_stage<C>(
+    db: &C,
+    reviewer: contest_records::Column,
+    reviewed_at: contest_records::Column,
+    rejected: SimpleExpr,
+    query: &ReviewerStatsQuery,
+) -> Result<Vec<ReviewerStageRow>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let backend = db.get_database_backend();
+    let turnaround = seconds_between(
+        backend,
+        Expr::col((contest_records::Entity, contest_records::Column::CreatedAt)).into(),
+        Expr::col((contest_records::Entity, reviewed_at)).into(),
+    );
+    let mut finder = ContestRecord::find()
+        .select_only()
+        .column_as(Expr::col((contest_records::Entity, reviewer)), "reviewer_id")
+        .column_as(
+            Expr::col((contest_records::Entity, contest_records::Column::Id)).count(),
+            "reviewed",
+        )
+        .column_as(sum_as_i64(backend, Expr::case(rejected, 1).finally(0)), "rejected")
+        .column_as(Expr::col((contest_records::Entity, reviewed_at)).count(), "timed")
+        .column_as(sum_as_i64(backend, turnaround), "turnaround_seconds")
+        .filter(contest_records::Column::IsDeleted.eq(false))
+        .filter(reviewer.is_not_null());
+    if let Some(since) = query.since {
+        finder = finder.filter(reviewed_at.gte(since));
+    }
+    if let Some(until) = query.until {
+        finder = finder.filter(reviewed_at.lt(until));
+    }
+    finder
+        .group_by(reviewer)
+        .into_model::<ReviewerStageRow>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))
+}
+
+/// 合并初审、终审统计与待审数量，按用户名排序。
+pub(crate) fn build_reviewer_stats(
+    reviewers: Vec<users::Model>,
+    first_rows: &[ReviewerStageRow],
+    final_rows: &[ReviewerStageRow],
+    pending: &HashMap<Uuid, u64>,
+) -> Vec<ReviewerStats> {
+    let first: HashMap<Uuid, &ReviewerStageRow> = first_rows.iter().map(|row| (row.reviewer_id, row)).collect();
+    let last: HashMap<Uuid, &ReviewerStageRow> = final_rows.iter().map(|row| (row.reviewer_id, row)).collect();
+    let mut stats: Vec<ReviewerStats> = reviewers
+        .into_iter()
+        .map(|reviewer| {
+            let first = first.get(&reviewer.id).copied();
+            let last = last.get(&reviewer.id).copied();
+            let first_reviewed = first.map(|row| row.reviewed).unwrap_or(0);
+            let final_reviewed = last.map(|row| row.reviewed).unwrap_or(0);
+            let rejected = first.and_then(|row| row.rejected).unwrap_or(0)
+                + last.and_then(|row| row.rejected).unwrap_or(0);
+            let total = first_reviewed + final_reviewed;
+            ReviewerStats {
+                reviewer_id: reviewer.id,
+                username: reviewer.username,
+                display_name: reviewer.display_name,
+                role: reviewer.role,
+                first_reviewed,
+                final_reviewed,
+                rejected,
+                rejection_rate: if total > 0 { rejected as f64 / total as f64 } else { 0.0 },
+                avg_first_turnaround_seconds: first.and_then(average_turnaround),
+                avg_final_turnaround_seconds: last.and_then(average_turnaround),
+                pending: pending.get(&reviewer.id).copied().unwrap_or(0),
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| a.username.cmp(&b.username));
+    stats
+}
+
+fn average_turnaround(row: &ReviewerStageRow) -> Option<i64> {
+    if row.timed == 0 {
+        return None;
+    }
+    row.turnaround_seconds.map(|total| total / row.timed)
+}
+
+pub(crate) fn ensure_stats_role(role: &str) -> Result<(), AppError> {
+    if matches!(role, "admin" | "teacher" | "reviewer" | ROLE_CLASS_ADVISOR) {
+        Ok(())
+    } else {
+        Err(AppError::auth("forbidden"))
+    }
+}
+
+/// 按院系、班级与状态分组统计记录数与通过学时；`scope` 为学生数据范围条件。
+pub(crate) async fn load_stat_groups<C>(
+    db: &C,
+    scope: Condition,
+    department: Option<&str>,
+    year: Option<i32>,
+) -> Result<Vec<StatGroupRow>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut finder = ContestRecord::find()
+        .select_only()
+        .column(students::Column::Department)
+        .column(students::Column::ClassName)
+        .column(contest_records::Column::Status)
+        .column_as(
+            Expr::col((contest_records::Entity, contest_records::Column::Id)).count(),
+            "record_count",
+        )
+        .column_as(sum_as_i64(db.get_database_backend(), approved_hours_expr()), "approved_hours")
+        .join(JoinType::InnerJoin, contest_records::Relation::Student.def())
+        .filter(contest_records::Column::IsDeleted.eq(false))
+        .filter(students::Column::IsDeleted.eq(false))
+        .filter(scope);
+    if let Some(department) = department {
+        finder = finder.filter(students::Column::Department.eq(department));
+    }
+    if let Some(year) = year {
+        finder = finder.filter(contest_records::Column::ContestYear.eq(year));
+    }
+    finder
+        .group_by(students::Column::Department)
+        .group_by(students::Column::ClassName)
+        .group_by(contest_records::Column::Status)
+        .into_model::<StatGroupRow>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))
+}
+
+fn build_classes<'a>(rows: impl Iterator<Item = &'a StatGroupRow>) -> Vec<ClassStats> {
+    let mut classes: BTreeMap<(String, String), ClassStats> = BTreeMap::new();
+    for row in rows {
+        let entry = classes
+            .entry((row.department.clone(), row.class_name.clone()))
+            .or_insert_with(|| ClassStats {
+                department: row.department.clone(),
+                class_name: row.class_name.clone(),
+                counts: StatusCounts::default(),
+                approved_hours: 0,
+            });
+        entry.counts.add(&row.status, row.record_count);
+        entry.approved_hours += row.approved_hours.unwrap_or(0);
+    }
+    classes.into_values().collect()
+}
+
+pub(crate) fn build_overview(rows: &[StatGroupRow]) -> StatsOverviewResponse {
+    let mut counts = StatusCounts::default();
+    let mut approved_hours = 0;
+    for row in rows {
+        counts.add(&row.status, row.record_count);
+        approved_hours += row.approved_hours.unwrap_or(0);
+    }
+    StatsOverviewResponse {
+        counts,
+        approved_hours,
+        classes: build_classes(rows.iter()),
+    }
+}
+
+fn build_by_department(rows: &[StatGroupRow]) -> Vec<DepartmentStats> {
+    let mut departments: BTreeMap<&str, Vec<&StatGroupRow>> = BTreeMap::new();
+    for row in rows {
+        departments.entry(row.department.as_str()).or_default().push(row);
+    }
+    departments
+        .into_iter()
+        .map(|(department, group)| {
+            let mut counts = StatusCounts::default();
+            let mut approved_hours = 0;
+            for row in &group {
+                counts.add(&row.status, row.record_count);
+                approved_hours += row.approved_hours.unwrap_or(0);
+            }
+            DepartmentStats {
+                department: department.to_string(),
+                counts,
+                approved_hours,
+                classes: build_classes(group.into_iter()),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(department: &str, class_name: &str, status: &str, count: i64, hours: i64) -> StatGroupRow {
+        StatGroupRow {
+            department: department.to_string(),
+            class_name: class_name.to_string(),
+            status: status.to_string(),
+            record_count: count,
+            approved_hours: Some(hours),
+        }
+    }
+
+    #[test]
+    fn build_overview_and_departments_fold_groups() {
+        let rows = vec![
+            row("信息学院", "软工1班", STATUS_FINAL_REVIEWED, 3, 12),
+            row("信息学院", "软工1班", STATUS_SUBMITTED, 2, 0),
+            row("信息学院", "软工2班", STATUS_REJECTED, 1, 0),
+            row("机械学院", "机械1班", STATUS_FIRST_REVIEWED, 4, 0),
+        ];
+        let overview = build_overview(&rows);
+        assert_eq!(
+            overview.counts,
+            StatusCounts {
+                submitted: 2,
+                first_reviewed: 4,
+                final_reviewed: 3,
+                rejected: 1,
+            }
+        );
+        assert_eq!(overview.approved_hours, 12);
+        assert_eq!(overview.classes.len(), 3);
+        assert_eq!(overview.classes[0].class_name, "软工1班");
+        assert_eq!(overview.classes[0].counts.submitted, 2);
+
+        let departments = build_by_department(&rows);
+        assert_eq!(departments.len(), 2);
+        assert_eq!(departments[0].department, "信息学院");
+        assert_eq!(departments[0].approved_hours, 12);
+        assert_eq!(departments[0].classes.len(), 2);
+        assert_eq!(departments[1].counts.first_reviewed, 4);
+    }
+
+    fn reviewer(username: &str) -> users::Model {
+        let now = Utc::now();
+        users::Model {
+            id: Uuid::new_v4(),
+            username: username.to_string(),
+            display_name: username.to_string(),
+            role: "reviewer".to_string(),
+            email: None,
+            password_hash: None,
+            allow_password_login: false,
+            password_updated_at: None,
+            must_change_password: false,
+            is_active: true,
+            department: None,
+            notify_review_email: false,
+            locale: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn stage(reviewer_id: Uuid, reviewed: i64, rejected: i64, timed: i64, seconds: i64) -> ReviewerStageRow {
+        ReviewerStageRow {
+            reviewer_id,
+            reviewed,
+            rejected: Some(rejected),
+            timed,
+            turnaround_seconds: Some(seconds),
+        }
+    }
+
+    #[test]
+    fn build_reviewer_stats_merges_stages_and_pending() {
+        let busy = reviewer("busy");
+        let idle = reviewer("idle");
+        let first = vec![stage(busy.id, 3, 1, 2, 600)];
+        let last = vec![stage(busy.id, 1, 0, 0, 0)];
+        let pending = HashMap::from([(idle.id, 4)]);
+
+        let stats = build_reviewer_stats(vec![idle.clone(), busy.clone()], &first, &last, &pending);
+        assert_eq!(stats[0].username, "busy");
+        assert_eq!(stats[0].first_reviewed, 3);
+        assert_eq!(stats[0].final_reviewed, 1);
+        assert_eq!(stats[0].rejected, 1);
+        assert_eq!(stats[0].rejection_rate, 0.25);
+        assert_eq!(stats[0].avg_first_turnaround_seconds, Some(300));
+        assert_eq!(stats[0].avg_final_turnaround_seconds, None);
+        assert_eq!(stats[0].pending, 0);
+        assert_eq!(stats[1].first_reviewed, 0);
+        assert_eq!(stats[1].rejection_rate, 0.0);
+        assert_eq!(stats[1].pending, 4);
+    }
+}
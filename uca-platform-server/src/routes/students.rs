@@ -2,39 +2,43 @@
 
 use axum::{extract::{State, Multipart, Path}, Json};
 use axum_extra::extract::cookie::CookieJar;
-use calamine::Reader;
-#[cfg(test)]
-use calamine::Data;
 use chrono::Utc;
-use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter, Set, TransactionTrait};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect, Set,
+    TransactionTrait,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::io::Cursor;
+use std::collections::{HashMap, HashSet};
+use tracing::Instrument;
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidateEmail};
 
 use crate::{
-    access::{require_role, require_session_user},
+    access::{
+        department_scope, ensure_student_visible, require_role, require_session_user,
+        require_student_profile,
+    },
+    audit::record_audit,
     auth::hash_password,
-    entities::{students, users, Student, User},
+    db::insert_chunked,
+    entities::{contest_records, students, users, ContestRecord, Student, User},
     error::AppError,
-    templates::{build_header_index, read_cell_by_index},
+    events::QueueEvent,
+    import_batches::{record_import_batch, IMPORT_KIND_STUDENT},
+    import_jobs::{ImportJobHandle, IMPORT_BATCH_SIZE, IMPORT_LOOKUP_CHUNK_SIZE, JOB_CANCELLED, JOB_COMPLETED, JOB_FAILED},
+    labor_hours::{load_hour_ledger, HourLedger},
+    notify::{push_notifications, NewNotification, KIND_IMPORT_COMPLETED},
+    policy::StudentPasswordRule,
+    services::{
+        student::fetch_student_login_flag, DbStudentService, StudentInput, StudentService,
+    },
+    spreadsheet::{
+        expected_headers, locate_header, read_cell_by_index_opt, read_upload_workbook, HeaderOptions,
+    },
     state::AppState,
+    uploads::{multipart_error, read_file_field},
 };
 
-/// 学生导入时的密码规则。
-#[derive(Debug, Deserialize)]
-pub struct StudentPasswordRule {
-    /// 固定前缀。
-    pub prefix: Option<String>,
-    /// 固定后缀。
-    pub suffix: Option<String>,
-    /// 是否包含学号。
-    pub include_student_no: bool,
-    /// 是否包含手机号。
-    pub include_phone: bool,
-}
-
 /// 学生列表响应。
 #[derive(Debug, Serialize)]
 pub struct StudentResponse {
@@ -136,74 +140,17 @@ pub async fn create_student(
         .validate()
         .map_err(|_| AppError::validation("invalid student payload"))?;
 
-    let exists = Student::find()
-        .filter(students::Column::StudentNo.eq(&payload.student_no))
-        .one(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-    if let Some(existing) = exists {
-        if existing.is_deleted {
-            let mut active: students::ActiveModel = existing.into();
-            active.name = Set(payload.name.clone());
-            active.gender = Set(payload.gender.clone());
-            active.department = Set(payload.department.clone());
-            active.major = Set(payload.major.clone());
-            active.class_name = Set(payload.class_name.clone());
-            active.phone = Set(payload.phone.clone());
-            active.is_deleted = Set(false);
-            active.updated_at = Set(Utc::now());
-            let model = active
-                .update(&state.db)
-                .await
-                .map_err(|err| AppError::Database(err.to_string()))?;
-            upsert_student_user(&state.db, &payload.student_no, &payload.name, None).await?;
-            let allow_password_login =
-                fetch_student_login_flag(&state.db, &payload.student_no).await?;
-            return Ok(Json(StudentResponse::from_model(
-                model,
-                allow_password_login,
-            )));
-        }
-        return Err(AppError::bad_request("student number exists"));
-    }
-
-    let now = Utc::now();
-    let id = Uuid::new_v4();
-    let model = students::ActiveModel {
-        id: Set(id),
-        student_no: Set(payload.student_no.clone()),
-        name: Set(payload.name.clone()),
-        gender: Set(payload.gender.clone()),
-        department: Set(payload.department.clone()),
-        major: Set(payload.major.clone()),
-        class_name: Set(payload.class_name.clone()),
-        phone: Set(payload.phone.clone()),
-        is_deleted: Set(false),
-        created_at: Set(now),
-        updated_at: Set(now),
-    };
-    students::Entity::insert(model)
-        .exec_without_returning(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-
-    upsert_student_user(&state.db, &payload.student_no, &payload.name, Some(false)).await?;
-    let allow_password_login =
-        fetch_student_login_flag(&state.db, &payload.student_no).await?;
-
-    let model = students::Model {
-        id,
-        student_no: payload.student_no,
+    let input = StudentInput {
         name: payload.name,
         gender: payload.gender,
         department: payload.department,
         major: payload.major,
         class_name: payload.class_name,
         phone: payload.phone,
-        is_deleted: false,
-        created_at: now,
-        updated_at: now,
     };
+    let (model, allow_password_login) = DbStudentService::new(&state.db)
+        .create_student(&payload.student_no, &input)
+        .await?;
     Ok(Json(StudentResponse::from_model(
         model,
         allow_password_login,
@@ -218,13 +165,7 @@ pub async fn get_current_student(
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "student")?;
 
-    let student = Student::find()
-        .filter(students::Column::StudentNo.eq(&user.username))
-        .filter(students::Column::IsDeleted.eq(false))
-        .one(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-        .ok_or_else(|| AppError::not_found("student not found"))?;
+    let student = require_student_profile(&state, &user).await?;
     let allow_password_login =
         fetch_student_login_flag(&state.db, &student.student_no).await?;
     Ok(Json(StudentResponse::from_model(
@@ -233,19 +174,30 @@ pub async fn get_current_student(
     )))
 }
 
-/// 更新学生信息（仅管理员）。
-pub async fn update_student(
+/// 学生学时台账响应。
+#[derive(Debug, Serialize)]
+pub struct StudentHoursResponse {
+    /// 学生 ID。
+    pub student_id: Uuid,
+    /// 学号。
+    pub student_no: String,
+    /// 姓名。
+    pub name: String,
+    /// 逐条记录明细与合计。
+    #[serde(flatten)]
+    pub ledger: HourLedger,
+}
+
+/// 查询学生学时台账（本人或数据范围内的管理员/教师/审核人员/班主任）。
+pub async fn get_student_hours(
     State(state): State<AppState>,
     jar: CookieJar,
     Path(student_no): Path<String>,
-    Json(payload): Json<UpdateStudentRequest>,
-) -> Result<Json<StudentResponse>, AppError> {
+) -> Result<Json<StudentHoursResponse>, AppError> {
     let user = require_session_user(&state, &jar).await?;
-    require_role(&user, "admin")?;
-
-    payload
-        .validate()
-        .map_err(|_| AppError::validation("invalid student payload"))?;
+    if user.role == "student" && user.username != student_no {
+        return Err(AppError::auth("forbidden"));
+    }
 
     let student = Student::find()
         .filter(students::Column::StudentNo.eq(&student_no))
@@ -254,22 +206,156 @@ pub async fn update_student(
         .await
         .map_err(|err| AppError::Database(err.to_string()))?
         .ok_or_else(|| AppError::not_found("student not found"))?;
+    ensure_student_visible(&state, &user, &student).await?;
+
+    let ledger = load_hour_ledger(&state, student.id).await?;
+    Ok(Json(StudentHoursResponse {
+        student_id: student.id,
+        student_no: student.student_no,
+        name: student.name,
+        ledger,
+    }))
+}
+
+/// 同伴统计的最小分组人数（k-匿名）。
+const PEER_STATS_MIN_GROUP: usize = 10;
+
+/// 匿名同伴统计响应。
+#[derive(Debug, Serialize)]
+pub struct PeerStatsResponse {
+    /// 是否启用同伴统计。
+    pub enabled: bool,
+    /// 本人审核通过学时。
+    pub approved_hours: i32,
+    /// 班级内排位。
+    pub class: Option<PeerPlacement>,
+    /// 专业内排位。
+    pub major: Option<PeerPlacement>,
+}
+
+/// 分组内的匿名排位。
+#[derive(Debug, Serialize)]
+pub struct PeerPlacement {
+    /// 分组人数。
+    pub group_size: usize,
+    /// 百分位（向下取整到 5 的倍数）。
+    pub percentile: u32,
+}
+
+/// 获取当前学生在班级/专业内的匿名学时排位。
+pub async fn get_peer_stats(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<PeerStatsResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "student")?;
+
+    let student = require_student_profile(&state, &user).await?;
+
+    if !state.config.peer_stats_enabled {
+        return Ok(Json(PeerStatsResponse {
+            enabled: false,
+            approved_hours: 0,
+            class: None,
+            major: None,
+        }));
+    }
 
-    let mut active: students::ActiveModel = student.into();
-    active.name = Set(payload.name.clone());
-    active.gender = Set(payload.gender.clone());
-    active.department = Set(payload.department.clone());
-    active.major = Set(payload.major.clone());
-    active.class_name = Set(payload.class_name.clone());
-    active.phone = Set(payload.phone.clone());
-    active.updated_at = Set(Utc::now());
-    let model = active
-        .update(&state.db)
+    let class_hours = load_group_approved_hours(
+        &state,
+        students::Column::ClassName,
+        &student.class_name,
+    )
+    .await?;
+    let major_hours = load_group_approved_hours(&state, students::Column::Major, &student.major).await?;
+    let approved_hours = class_hours.get(&student.id).copied().unwrap_or(0);
+
+    Ok(Json(PeerStatsResponse {
+        enabled: true,
+        approved_hours,
+        class: build_peer_placement(approved_hours, &class_hours),
+        major: build_peer_placement(approved_hours, &major_hours),
+    }))
+}
+
+async fn load_group_approved_hours(
+    state: &AppState,
+    column: students::Column,
+    value: &str,
+) -> Result<HashMap<Uuid, i32>, AppError> {
+    let student_ids = Student::find()
+        .select_only()
+        .column(students::Column::Id)
+        .filter(column.eq(value))
+        .filter(students::Column::IsDeleted.eq(false))
+        .into_tuple::<Uuid>()
+        .all(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    let mut hours: HashMap<Uuid, i32> = student_ids.iter().map(|id| (*id, 0)).collect();
+    let rows = ContestRecord::find()
+        .select_only()
+        .column(contest_records::Column::StudentId)
+        .column(contest_records::Column::FinalReviewHours)
+        .filter(contest_records::Column::StudentId.is_in(student_ids))
+        .filter(contest_records::Column::Status.eq("final_reviewed"))
+        .filter(contest_records::Column::IsDeleted.eq(false))
+        .into_tuple::<(Uuid, Option<i32>)>()
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    for (student_id, value) in rows {
+        *hours.entry(student_id).or_insert(0) += value.unwrap_or(0);
+    }
+    Ok(hours)
+}
+
+fn build_peer_placement(own: i32, group: &HashMap<Uuid, i32>) -> Option<PeerPlacement> {
+    if group.len() < PEER_STATS_MIN_GROUP {
+        return None;
+    }
+    let values = group.values().copied().collect::<Vec<_>>();
+    Some(PeerPlacement {
+        group_size: values.len(),
+        percentile: percentile_bucket(own, &values),
+    })
+}
+
+fn percentile_bucket(own: i32, values: &[i32]) -> u32 {
+    if values.is_empty() {
+        return 0;
+    }
+    let below = values.iter().filter(|value| **value < own).count() as f64;
+    let equal = values.iter().filter(|value| **value == own).count() as f64;
+    let percentile = (below + equal / 2.0) / values.len() as f64 * 100.0;
+    ((percentile / 5.0).floor() as u32 * 5).min(100)
+}
+
+/// 更新学生信息（仅管理员）。
+pub async fn update_student(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(student_no): Path<String>,
+    Json(payload): Json<UpdateStudentRequest>,
+) -> Result<Json<StudentResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
 
-    upsert_student_user(&state.db, &student_no, &payload.name, None).await?;
-    let allow_password_login = fetch_student_login_flag(&state.db, &student_no).await?;
+    payload
+        .validate()
+        .map_err(|_| AppError::validation("invalid student payload"))?;
+
+    let input = StudentInput {
+        name: payload.name,
+        gender: payload.gender,
+        department: payload.department,
+        major: payload.major,
+        class_name: payload.class_name,
+        phone: payload.phone,
+    };
+    let (model, allow_password_login) = DbStudentService::new(&state.db)
+        .update_student(&student_no, &input)
+        .await?;
 
     Ok(Json(StudentResponse::from_model(
         model,
@@ -302,6 +388,9 @@ pub async fn list_students(
     }
 
     let mut finder = Student::find().filter(students::Column::IsDeleted.eq(false));
+    if let Some(department) = department_scope(&user) {
+        finder = finder.filter(students::Column::Department.eq(department));
+    }
     if let Some(value) = query.department {
         finder = finder.filter(students::Column::Department.eq(value));
     }
@@ -358,16 +447,21 @@ pub async fn import_students(
     let user = require_session_user(&state, &jar).await?;
     require_role(&user, "admin")?;
 
-    let (file_bytes, fields) = read_upload_payload(&mut multipart).await?;
+    let (file_bytes, fields) = read_upload_payload(&mut multipart, state.config.max_upload_bytes).await?;
     let field_map = fields
         .get("field_map")
         .map(|value| serde_json::from_str::<HashMap<String, String>>(value))
         .transpose()
         .map_err(|_| AppError::bad_request("invalid field_map"))?;
+    let header_options = HeaderOptions::from_fields(&fields)?;
     let create_user = fields
         .get("create_user")
         .map(|value| value == "true" || value == "1")
         .unwrap_or(false);
+    let background = fields
+        .get("background")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false);
     let password_rule = if create_user {
         Some(
             fields
@@ -384,145 +478,677 @@ pub async fn import_students(
         ),
         None => None,
     };
-    let mut workbook = calamine::Xlsx::new(Cursor::new(file_bytes))
-        .map_err(|_| AppError::bad_request("invalid xlsx file"))?;
-    let sheet_name = workbook
-        .sheet_names()
-        .first()
-        .cloned()
-        .ok_or_else(|| AppError::bad_request("xlsx has no sheets"))?;
-    let range = workbook
-        .worksheet_range(&sheet_name)
-        .map_err(|_| AppError::bad_request("failed to read worksheet"))?;
-
-    let header_index = build_header_index(range.rows().next());
-    let base_index = build_student_field_map(&header_index, field_map.as_ref())?;
+    let conflict_policy = match fields.get("conflict_policy") {
+        Some(value) => ConflictPolicy::parse(value)?,
+        None => ConflictPolicy::Overwrite,
+    };
+    let workbook = read_upload_workbook(file_bytes)?;
+    let range = workbook.first_sheet()?;
+
+    let expected = expected_headers(
+        STUDENT_IMPORT_HEADERS.iter().map(|(_, candidates, _)| *candidates),
+        field_map.as_ref(),
+    );
+    let header = locate_header(range, header_options, &expected)?;
+    let base_index = build_student_field_map(&header.index, field_map.as_ref())?;
+
+    let mut rows = Vec::new();
+    for (row_index, row) in range.rows().enumerate().skip(header.data_start()) {
+        let item = StudentImportRow {
+            // Excel 行号从 1 开始。
+            row: row_index + 1,
+            student_no: read_cell_by_index_opt(base_index.get("student_no"), row),
+            name: read_cell_by_index_opt(base_index.get("name"), row),
+            gender: read_cell_by_index_opt(base_index.get("gender"), row),
+            department: read_cell_by_index_opt(base_index.get("department"), row),
+            major: read_cell_by_index_opt(base_index.get("major"), row),
+            class_name: read_cell_by_index_opt(base_index.get("class_name"), row),
+            phone: read_cell_by_index_opt(base_index.get("phone"), row),
+        };
+        if item.student_no.is_empty() || item.name.is_empty() {
+            continue;
+        }
+        rows.push(item);
+    }
+
+    if background {
+        // 后台任务分批提交，需在开始前整体检查冲突，避免提交部分批次后才失败。
+        if conflict_policy == ConflictPolicy::Error {
+            let existing = load_existing_students(&state.db, &rows).await?;
+            let conflicts: Vec<StudentImportConflict> =
+                rows.iter().filter_map(|row| row_conflict(row, &existing)).collect();
+            if !conflicts.is_empty() {
+                return Err(conflicts_error(conflicts));
+            }
+        }
+        let handle = state
+            .import_jobs
+            .lock()
+            .await
+            .create("student_import", user.id, rows.len());
+        let job_id = handle.id;
+        // 后台任务按块提交，批次先于首块登记，以便撤销部分完成的任务；批次 ID 与任务 ID 相同。
+        record_import_batch(&state.db, job_id, IMPORT_KIND_STUDENT, user.id).await?;
+        tokio::spawn(
+            run_student_import_job(state.clone(), handle, rows, password_rule, conflict_policy)
+                .instrument(tracing::Span::current()),
+        );
+        return Ok(Json(serde_json::json!({ "job_id": job_id, "import_batch_id": job_id })));
+    }
 
+    let batch_id = Uuid::new_v4();
     let transaction = state
         .db
         .begin()
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    let counters =
+        apply_student_import_rows(&transaction, &rows, batch_id, password_rule.as_ref(), conflict_policy).await?;
+    let import_batch_id = if counters.inserted > 0 {
+        record_import_batch(&transaction, batch_id, IMPORT_KIND_STUDENT, user.id).await?;
+        Some(batch_id)
+    } else {
+        None
+    };
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
 
-    let mut inserted = 0usize;
-    let mut updated = 0usize;
-    let mut created_users = 0usize;
-    let mut skipped_users = 0usize;
-
-    for row in range.rows().skip(1) {
-        let student_no = read_cell_by_index_opt(base_index.get("student_no"), row);
-        let name = read_cell_by_index_opt(base_index.get("name"), row);
-        let gender = read_cell_by_index_opt(base_index.get("gender"), row);
-        let department = read_cell_by_index_opt(base_index.get("department"), row);
-        let major = read_cell_by_index_opt(base_index.get("major"), row);
-        let class_name = read_cell_by_index_opt(base_index.get("class_name"), row);
-        let phone = read_cell_by_index_opt(base_index.get("phone"), row);
-
-        if student_no.is_empty() || name.is_empty() {
-            continue;
+    let mut response = serde_json::to_value(&counters).unwrap_or_default();
+    response["import_batch_id"] = serde_json::json!(import_batch_id);
+    Ok(Json(response))
+}
+
+/// 学号已存在且姓名或班级不同时的处理方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConflictPolicy {
+    /// 跳过该行，保留已有数据。
+    Skip,
+    /// 以导入数据覆盖（默认）。
+    Overwrite,
+    /// 存在冲突时整体不导入。
+    Error,
+}
+
+impl ConflictPolicy {
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value.trim() {
+            "skip" => Ok(Self::Skip),
+            "overwrite" | "" => Ok(Self::Overwrite),
+            "error" => Ok(Self::Error),
+            _ => Err(AppError::bad_request("conflict_policy must be skip, overwrite or error")),
         }
+    }
+}
+
+/// 学生导入的单行数据。
+#[derive(Debug, Clone)]
+struct StudentImportRow {
+    /// Excel 行号。
+    row: usize,
+    student_no: String,
+    name: String,
+    gender: String,
+    department: String,
+    major: String,
+    class_name: String,
+    phone: String,
+}
 
-        let existing = Student::find()
-            .filter(students::Column::StudentNo.eq(&student_no))
-            .one(&transaction)
+/// 冲突双方的姓名与班级。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct ConflictValues {
+    name: String,
+    class_name: String,
+}
+
+/// 学号已存在但姓名或班级不同的导入行。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct StudentImportConflict {
+    row: usize,
+    student_no: String,
+    existing: ConflictValues,
+    incoming: ConflictValues,
+}
+
+/// 学生导入统计。
+#[derive(Debug, Default, Clone, Serialize)]
+struct StudentImportCounters {
+    inserted: usize,
+    updated: usize,
+    created_users: usize,
+    skipped_users: usize,
+    /// 因冲突跳过的行数（`conflict_policy=skip`）。
+    skipped_conflicts: usize,
+    conflicts: Vec<StudentImportConflict>,
+}
+
+impl StudentImportCounters {
+    fn merge(&mut self, other: StudentImportCounters) {
+        self.inserted += other.inserted;
+        self.updated += other.updated;
+        self.created_users += other.created_users;
+        self.skipped_users += other.skipped_users;
+        self.skipped_conflicts += other.skipped_conflicts;
+        self.conflicts.extend(other.conflicts);
+    }
+}
+
+/// 按学号批量读取已有学生（含已删除）。
+async fn load_existing_students<C>(
+    db: &C,
+    rows: &[StudentImportRow],
+) -> Result<HashMap<String, students::Model>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut student_nos: Vec<String> = rows.iter().map(|row| row.student_no.clone()).collect();
+    student_nos.sort();
+    student_nos.dedup();
+    let mut existing = HashMap::new();
+    for chunk in student_nos.chunks(IMPORT_LOOKUP_CHUNK_SIZE) {
+        let found = Student::find()
+            .filter(students::Column::StudentNo.is_in(chunk.to_vec()))
+            .all(db)
             .await
             .map_err(|err| AppError::Database(err.to_string()))?;
+        existing.extend(found.into_iter().map(|student| (student.student_no.clone(), student)));
+    }
+    Ok(existing)
+}
 
-        let now = Utc::now();
-        if let Some(record) = existing {
-            let mut active: students::ActiveModel = record.into();
-            active.name = Set(name.clone());
-            active.gender = Set(gender);
-            active.department = Set(department);
-            active.major = Set(major);
-            active.class_name = Set(class_name);
-            active.phone = Set(phone.clone());
+/// 学号已存在且姓名或班级不同时返回冲突。
+fn row_conflict(row: &StudentImportRow, existing: &HashMap<String, students::Model>) -> Option<StudentImportConflict> {
+    let record = existing.get(&row.student_no)?;
+    if record.name == row.name && record.class_name == row.class_name {
+        return None;
+    }
+    Some(StudentImportConflict {
+        row: row.row,
+        student_no: row.student_no.clone(),
+        existing: ConflictValues {
+            name: record.name.clone(),
+            class_name: record.class_name.clone(),
+        },
+        incoming: ConflictValues {
+            name: row.name.clone(),
+            class_name: row.class_name.clone(),
+        },
+    })
+}
+
+fn conflicts_error(conflicts: Vec<StudentImportConflict>) -> AppError {
+    AppError::conflict(
+        "student import has conflicting rows",
+        Some(serde_json::json!({ "conflicts": conflicts })),
+    )
+}
+
+/// 导入一批行：已有学号则更新，否则新建并标记导入批次。
+///
+/// 已有学生与登录账号按学号预先批量读取，新建的学生与账号分块批量插入；文件内重复的学号以最后一行为准。
+/// 学号已存在但姓名或班级不同的行按 `policy` 处理，并全部列入 `conflicts`。
+async fn apply_student_import_rows<C>(
+    db: &C,
+    rows: &[StudentImportRow],
+    batch_id: Uuid,
+    password_rule: Option<&StudentPasswordRule>,
+    policy: ConflictPolicy,
+) -> Result<StudentImportCounters, AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut counters = StudentImportCounters::default();
+    let existing = load_existing_students(db, rows).await?;
+    counters.conflicts = rows.iter().filter_map(|row| row_conflict(row, &existing)).collect();
+    if policy == ConflictPolicy::Error && !counters.conflicts.is_empty() {
+        return Err(conflicts_error(counters.conflicts));
+    }
+    let skipped_rows: HashSet<usize> = match policy {
+        ConflictPolicy::Skip => counters.conflicts.iter().map(|conflict| conflict.row).collect(),
+        _ => HashSet::new(),
+    };
+    counters.skipped_conflicts = skipped_rows.len();
+    let rows: Vec<&StudentImportRow> = rows.iter().filter(|row| !skipped_rows.contains(&row.row)).collect();
+
+    let now = Utc::now();
+    let mut inserts: Vec<students::ActiveModel> = Vec::new();
+    let mut pending: HashMap<&str, usize> = HashMap::new();
+    for row in rows.iter().copied() {
+        if let Some(record) = existing.get(&row.student_no) {
+            let mut active: students::ActiveModel = record.clone().into();
+            active.name = Set(row.name.clone());
+            active.gender = Set(row.gender.clone());
+            active.department = Set(row.department.clone());
+            active.major = Set(row.major.clone());
+            active.class_name = Set(row.class_name.clone());
+            active.phone = Set(row.phone.clone());
             active.updated_at = Set(now);
             active.is_deleted = Set(false);
             active
-                .update(&transaction)
+                .update(db)
                 .await
                 .map_err(|err| AppError::Database(err.to_string()))?;
-            if let Some(rule) = password_rule.as_ref() {
-                let created = ensure_student_user(&transaction, &student_no, &name, &phone, rule)
-                .await?;
-                if created {
-                    created_users += 1;
-                } else {
-                    skipped_users += 1;
-                }
+            counters.updated += 1;
+            continue;
+        }
+        let model = students::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            student_no: Set(row.student_no.clone()),
+            name: Set(row.name.clone()),
+            gender: Set(row.gender.clone()),
+            department: Set(row.department.clone()),
+            major: Set(row.major.clone()),
+            class_name: Set(row.class_name.clone()),
+            phone: Set(row.phone.clone()),
+            import_batch_id: Set(Some(batch_id)),
+            is_deleted: Set(false),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+        match pending.get(row.student_no.as_str()) {
+            Some(&index) => {
+                inserts[index] = model;
+                counters.updated += 1;
+            }
+            None => {
+                pending.insert(row.student_no.as_str(), inserts.len());
+                inserts.push(model);
+                counters.inserted += 1;
             }
-            updated += 1;
+        }
+    }
+    insert_chunked(db, inserts).await?;
+
+    if let Some(rule) = password_rule {
+        let created = create_student_users(db, &rows, rule).await?;
+        counters.created_users += created;
+        counters.skipped_users += rows.len() - created;
+    }
+    Ok(counters)
+}
+
+/// 后台分批导入；每批一个事务，取消时回滚当前批次并保留已提交批次的统计。
+async fn run_student_import_job(
+    state: AppState,
+    handle: ImportJobHandle,
+    rows: Vec<StudentImportRow>,
+    password_rule: Option<StudentPasswordRule>,
+    conflict_policy: ConflictPolicy,
+) {
+    let mut committed = StudentImportCounters::default();
+    let mut committed_rows = 0usize;
+    for batch in rows.chunks(IMPORT_BATCH_SIZE) {
+        let result = if handle.is_cancelled() {
+            Ok(None)
         } else {
-            let model = students::ActiveModel {
-                id: Set(Uuid::new_v4()),
-                student_no: Set(student_no.clone()),
-                name: Set(name.clone()),
-                gender: Set(gender),
-                department: Set(department),
-                major: Set(major),
-                class_name: Set(class_name),
-                phone: Set(phone.clone()),
-                is_deleted: Set(false),
-                created_at: Set(now),
-                updated_at: Set(now),
-            };
-            students::Entity::insert(model)
-                .exec_without_returning(&transaction)
-                .await
-                .map_err(|err| AppError::Database(err.to_string()))?;
-            if let Some(rule) = password_rule.as_ref() {
-                let created = ensure_student_user(&transaction, &student_no, &name, &phone, rule)
-                .await?;
-                if created {
-                    created_users += 1;
-                } else {
-                    skipped_users += 1;
-                }
+            import_student_batch(&state, &handle, batch, password_rule.as_ref(), conflict_policy).await
+        };
+        match result {
+            Ok(Some(counters)) => {
+                committed.merge(counters);
+                committed_rows += batch.len();
+                state.import_jobs.lock().await.update_progress(
+                    &handle.id,
+                    committed_rows,
+                    serde_json::to_value(&committed).unwrap_or_default(),
+                );
+            }
+            Ok(None) => {
+                state.import_jobs.lock().await.finish(&handle.id, JOB_CANCELLED, None);
+                return;
+            }
+            Err(err) => {
+                tracing::warn!(job_id = %handle.id, error = %err, "student import job failed");
+                state
+                    .import_jobs
+                    .lock()
+                    .await
+                    .finish(&handle.id, JOB_FAILED, Some(err.to_string()));
+                notify_import_finished(
+                    &state,
+                    &handle,
+                    "学生名单导入失败",
+                    format!("已提交 {committed_rows} 行，失败原因：{err}"),
+                )
+                .await;
+                return;
             }
-            inserted += 1;
         }
     }
+    state.import_jobs.lock().await.finish(&handle.id, JOB_COMPLETED, None);
+    state.events.publish(QueueEvent::imported("student", committed_rows));
+    notify_import_finished(
+        &state,
+        &handle,
+        "学生名单导入完成",
+        format!(
+            "共导入 {committed_rows} 行：新增 {}，更新 {}，新建账号 {}，姓名或班级冲突 {}。",
+            committed.inserted,
+            committed.updated,
+            committed.created_users,
+            committed.conflicts.len()
+        ),
+    )
+    .await;
+}
+
+/// 向导入任务发起人发送站内通知。
+async fn notify_import_finished(state: &AppState, handle: &ImportJobHandle, title: &str, body: String) {
+    let owner_id = state.import_jobs.lock().await.get(&handle.id).map(|job| job.owner_id);
+    if let Some(owner_id) = owner_id {
+        push_notifications(
+            state,
+            vec![NewNotification::new(owner_id, KIND_IMPORT_COMPLETED, title, body)
+                .target("import_job", handle.id)],
+        )
+        .await;
+    }
+}
 
+async fn import_student_batch(
+    state: &AppState,
+    handle: &ImportJobHandle,
+    batch: &[StudentImportRow],
+    password_rule: Option<&StudentPasswordRule>,
+    conflict_policy: ConflictPolicy,
+) -> Result<Option<StudentImportCounters>, AppError> {
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let counters = apply_student_import_rows(&transaction, batch, handle.id, password_rule, conflict_policy).await?;
+    if handle.is_cancelled() {
+        transaction
+            .rollback()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        return Ok(None);
+    }
     transaction
         .commit()
         .await
         .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Some(counters))
+}
 
-    Ok(Json(serde_json::json!({
-        "inserted": inserted,
-        "updated": updated,
-        "created_users": created_users,
-        "skipped_users": skipped_users
-    })))
+/// 联系方式变更项。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContactChange {
+    /// 学号。
+    pub student_no: String,
+    /// 字段（phone/email）。
+    pub field: String,
+    /// 原值。
+    pub old_value: Option<String>,
+    /// 新值。
+    pub new_value: String,
 }
 
-fn read_cell_by_index_opt(index: Option<&usize>, row: &[calamine::Data]) -> String {
-    let idx = match index {
-        Some(value) => *value,
-        None => return String::new(),
+/// 联系方式导入的行错误。
+#[derive(Debug, Serialize)]
+pub struct ContactImportError {
+    /// 表格行号（从 2 开始，含表头偏移）。
+    pub row: usize,
+    /// 学号。
+    pub student_no: String,
+    /// 错误说明。
+    pub message: String,
+}
+
+/// 联系方式导入结果。
+#[derive(Debug, Serialize)]
+pub struct ContactImportResponse {
+    /// 是否为预览（未写入）。
+    pub dry_run: bool,
+    /// 文件格式（xlsx/xls/csv）。
+    pub format: String,
+    /// 检测到的文本编码（仅 CSV）。
+    pub encoding: Option<String>,
+    /// 变更列表。
+    pub changes: Vec<ContactChange>,
+    /// 无变化的学生数。
+    pub unchanged: usize,
+    /// 未找到的学号。
+    pub not_found: Vec<String>,
+    /// 校验失败的行。
+    pub errors: Vec<ContactImportError>,
+}
+
+/// 从表格批量更新学生联系方式（仅手机号与邮箱），默认只返回差异预览。
+pub async fn import_student_contacts(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    mut multipart: Multipart,
+) -> Result<Json<ContactImportResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let (file_bytes, fields) = read_upload_payload(&mut multipart, state.config.max_upload_bytes).await?;
+    let field_map = fields
+        .get("field_map")
+        .map(|value| serde_json::from_str::<HashMap<String, String>>(value))
+        .transpose()
+        .map_err(|_| AppError::bad_request("invalid field_map"))?;
+    let dry_run = fields
+        .get("dry_run")
+        .map(|value| !(value == "false" || value == "0"))
+        .unwrap_or(true);
+    let header_options = HeaderOptions::from_fields(&fields)?;
+    let workbook = read_upload_workbook(file_bytes)?;
+    let range = workbook.first_sheet()?;
+
+    let expected = expected_headers(
+        CONTACT_IMPORT_HEADERS.iter().map(|(_, candidates)| *candidates),
+        field_map.as_ref(),
+    );
+    let header = locate_header(range, header_options, &expected)?;
+    let index = build_contact_field_map(&header.index, field_map.as_ref())?;
+
+    let mut rows = Vec::new();
+    for (row_index, row) in range.rows().enumerate().skip(header.data_start()) {
+        let student_no = read_cell_by_index_opt(index.get("student_no"), row);
+        if student_no.is_empty() {
+            continue;
+        }
+        let phone = read_cell_by_index_opt(index.get("phone"), row);
+        let email = read_cell_by_index_opt(index.get("email"), row);
+        // Excel 行号从 1 开始。
+        rows.push((row_index + 1, student_no, phone, email));
+    }
+    let student_nos: Vec<String> = rows.iter().map(|(_, no, _, _)| no.clone()).collect();
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let students_by_no: HashMap<String, students::Model> = Student::find()
+        .filter(students::Column::StudentNo.is_in(student_nos.clone()))
+        .filter(students::Column::IsDeleted.eq(false))
+        .all(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|student| (student.student_no.clone(), student))
+        .collect();
+    let users_by_name: HashMap<String, users::Model> = User::find()
+        .filter(users::Column::Username.is_in(student_nos))
+        .filter(users::Column::Role.eq("student"))
+        .all(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|user| (user.username.clone(), user))
+        .collect();
+
+    let mut response = ContactImportResponse {
+        dry_run,
+        format: workbook.format.to_string(),
+        encoding: workbook.encoding.map(str::to_string),
+        changes: Vec::new(),
+        unchanged: 0,
+        not_found: Vec::new(),
+        errors: Vec::new(),
     };
-    read_cell_by_index(idx, row)
+    let now = Utc::now();
+    for (row, student_no, phone, email) in rows {
+        let Some(student) = students_by_no.get(&student_no) else {
+            response.not_found.push(student_no);
+            continue;
+        };
+        let account = users_by_name.get(&student_no);
+        let changes = match diff_contact_row(student, account, &phone, &email) {
+            Ok(changes) => changes,
+            Err(message) => {
+                response.errors.push(ContactImportError { row, student_no, message });
+                continue;
+            }
+        };
+        if changes.is_empty() {
+            response.unchanged += 1;
+            continue;
+        }
+        if !dry_run {
+            for change in &changes {
+                match change.field.as_str() {
+                    "phone" => {
+                        let mut active: students::ActiveModel = student.clone().into();
+                        active.phone = Set(change.new_value.clone());
+                        active.updated_at = Set(now);
+                        active
+                            .update(&transaction)
+                            .await
+                            .map_err(|err| AppError::Database(err.to_string()))?;
+                    }
+                    _ => {
+                        if let Some(account) = account {
+                            let mut active: users::ActiveModel = account.clone().into();
+                            active.email = Set(Some(change.new_value.clone()));
+                            active.updated_at = Set(now);
+                            active
+                                .update(&transaction)
+                                .await
+                                .map_err(|err| AppError::Database(err.to_string()))?;
+                        }
+                    }
+                }
+                record_audit(
+                    &transaction,
+                    Some(user.id),
+                    "student_contact_update",
+                    "student",
+                    Some(&change.student_no),
+                    Some(serde_json::json!({
+                        "field": change.field,
+                        "old": change.old_value,
+                        "new": change.new_value,
+                    })),
+                )
+                .await?;
+            }
+        }
+        response.changes.extend(changes);
+    }
+
+    if dry_run {
+        transaction
+            .rollback()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+    } else {
+        transaction
+            .commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+    }
+    Ok(Json(response))
+}
+
+/// 联系方式导入的列与候选表头。
+const CONTACT_IMPORT_HEADERS: [(&str, &[&str]); 3] = [
+    ("student_no", &["学号", "student_no"]),
+    ("phone", &["手机号", "phone"]),
+    ("email", &["邮箱", "email"]),
+];
+
+fn build_contact_field_map(
+    header_index: &HashMap<String, usize>,
+    field_map: Option<&HashMap<String, String>>,
+) -> Result<HashMap<String, usize>, AppError> {
+    let mut result = HashMap::new();
+    for (key, candidates) in CONTACT_IMPORT_HEADERS {
+        let override_value = field_map.and_then(|map| map.get(key).map(|value| value.as_str()));
+        if let Some(idx) = resolve_column_index(header_index, override_value, candidates) {
+            result.insert(key.to_string(), idx);
+        }
+    }
+    if !result.contains_key("student_no") {
+        return Err(AppError::bad_request("missing required header"));
+    }
+    if !result.contains_key("phone") && !result.contains_key("email") {
+        return Err(AppError::bad_request("phone or email column required"));
+    }
+    Ok(result)
+}
+
+/// 计算单行的联系方式差异；空单元格表示不修改该字段。
+fn diff_contact_row(
+    student: &students::Model,
+    account: Option<&users::Model>,
+    phone: &str,
+    email: &str,
+) -> Result<Vec<ContactChange>, String> {
+    let mut changes = Vec::new();
+    if !phone.is_empty() {
+        let valid = (6..=32).contains(&phone.len())
+            && phone.chars().all(|ch| ch.is_ascii_digit() || ch == '+' || ch == '-');
+        if !valid {
+            return Err("invalid phone".to_string());
+        }
+        if student.phone != phone {
+            changes.push(ContactChange {
+                student_no: student.student_no.clone(),
+                field: "phone".to_string(),
+                old_value: Some(student.phone.clone()),
+                new_value: phone.to_string(),
+            });
+        }
+    }
+    if !email.is_empty() {
+        if !email.validate_email() {
+            return Err("invalid email".to_string());
+        }
+        let account = account.ok_or_else(|| "student account not found".to_string())?;
+        if account.email.as_deref() != Some(email) {
+            changes.push(ContactChange {
+                student_no: student.student_no.clone(),
+                field: "email".to_string(),
+                old_value: account.email.clone(),
+                new_value: email.to_string(),
+            });
+        }
+    }
+    Ok(changes)
 }
 
 async fn read_upload_payload(
     multipart: &mut Multipart,
+    max_bytes: usize,
 ) -> Result<(Vec<u8>, HashMap<String, String>), AppError> {
     let mut file_bytes = None;
     let mut fields = HashMap::new();
     while let Some(field) = multipart
         .next_field()
         .await
-        .map_err(|_| AppError::bad_request("invalid multipart"))?
+        .map_err(multipart_error)?
     {
         let name = field.name().map(|value| value.to_string());
         match name.as_deref() {
             Some("file") => {
-                let bytes = field
-                    .bytes()
-                    .await
-                    .map_err(|_| AppError::bad_request("failed to read file"))?;
-                file_bytes = Some(bytes.to_vec());
+                file_bytes = Some(read_file_field(field, max_bytes).await?);
             }
             Some(key) => {
                 let value = field
@@ -538,20 +1164,23 @@ async fn read_upload_payload(
     Ok((file_bytes, fields))
 }
 
+/// 学生导入的列、候选表头与是否必填。
+const STUDENT_IMPORT_HEADERS: [(&str, &[&str], bool); 7] = [
+    ("student_no", &["学号", "student_no"], true),
+    ("name", &["姓名", "name"], true),
+    ("gender", &["性别", "gender"], false),
+    ("department", &["院系", "department"], false),
+    ("major", &["专业", "major"], false),
+    ("class_name", &["班级", "class_name"], false),
+    ("phone", &["手机号", "phone"], false),
+];
+
 fn build_student_field_map(
     header_index: &HashMap<String, usize>,
     field_map: Option<&HashMap<String, String>>,
 ) -> Result<HashMap<String, usize>, AppError> {
     let mut result = HashMap::new();
-    for (key, candidates, required) in [
-        ("student_no", &["学号", "student_no"][..], true),
-        ("name", &["姓名", "name"][..], true),
-        ("gender", &["性别", "gender"][..], false),
-        ("department", &["院系", "department"][..], false),
-        ("major", &["专业", "major"][..], false),
-        ("class_name", &["班级", "class_name"][..], false),
-        ("phone", &["手机号", "phone"][..], false),
-    ] {
+    for (key, candidates, required) in STUDENT_IMPORT_HEADERS {
         let override_value = field_map.and_then(|map| map.get(key).map(|value| value.as_str()));
         let idx = resolve_column_index(header_index, override_value, candidates);
         if required && idx.is_none() {
@@ -604,171 +1233,162 @@ fn parse_column_reference(value: &str) -> Option<usize> {
     None
 }
 
-async fn upsert_student_user<C>(
+/// 为尚无登录账号的学号批量创建学生用户，返回新建数量；文件内重复的学号只创建一次。
+async fn create_student_users<C>(
     db: &C,
-    student_no: &str,
-    name: &str,
-    allow_login: Option<bool>,
-) -> Result<(), AppError>
+    rows: &[&StudentImportRow],
+    rule: &StudentPasswordRule,
+) -> Result<usize, AppError>
 where
     C: ConnectionTrait,
 {
-    let default_password = format!("st{student_no}");
-    let default_hash = hash_password(&default_password)?;
-    let now = Utc::now();
-    if let Some(existing) = User::find()
-        .filter(users::Column::Username.eq(student_no))
-        .one(db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-    {
-        let missing_password = existing.password_hash.is_none();
-        let mut active: users::ActiveModel = existing.into();
-        active.display_name = Set(name.to_string());
-        active.role = Set("student".to_string());
-        if missing_password {
-            active.password_hash = Set(Some(default_hash));
-        }
-        if let Some(value) = allow_login {
-            active.allow_password_login = Set(value);
-            if value {
-                active.must_change_password = Set(true);
-            }
-        }
-        active.updated_at = Set(now);
-        active
-            .update(db)
+    let mut student_nos: Vec<String> = rows.iter().map(|row| row.student_no.clone()).collect();
+    student_nos.sort();
+    student_nos.dedup();
+    let mut taken: HashSet<String> = HashSet::new();
+    for chunk in student_nos.chunks(IMPORT_LOOKUP_CHUNK_SIZE) {
+        let usernames: Vec<String> = User::find()
+            .select_only()
+            .column(users::Column::Username)
+            .filter(users::Column::Username.is_in(chunk.to_vec()))
+            .into_tuple()
+            .all(db)
             .await
             .map_err(|err| AppError::Database(err.to_string()))?;
-        return Ok(());
-    }
-
-    let model = users::ActiveModel {
-        id: Set(Uuid::new_v4()),
-        username: Set(student_no.to_string()),
-        display_name: Set(name.to_string()),
-        role: Set("student".to_string()),
-        email: Set(None),
-        password_hash: Set(Some(default_hash)),
-        allow_password_login: Set(allow_login.unwrap_or(false)),
-        password_updated_at: Set(Some(now)),
-        must_change_password: Set(allow_login.unwrap_or(false)),
-        is_active: Set(true),
-        created_at: Set(now),
-        updated_at: Set(now),
-    };
-    users::Entity::insert(model)
-        .exec_without_returning(db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-    Ok(())
-}
-
-async fn ensure_student_user<C>(
-    db: &C,
-    student_no: &str,
-    name: &str,
-    phone: &str,
-    rule: &StudentPasswordRule,
-) -> Result<bool, AppError>
-where
-    C: ConnectionTrait,
-{
-    let exists = User::find()
-        .filter(users::Column::Username.eq(student_no))
-        .one(db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-    if exists.is_some() {
-        return Ok(false);
+        taken.extend(usernames);
     }
 
-    let mut parts = Vec::new();
-    if let Some(prefix) = rule.prefix.as_ref() {
-        if !prefix.is_empty() {
-            parts.push(prefix.clone());
-        }
-    }
-    if rule.include_student_no {
-        parts.push(student_no.to_string());
-    }
-    if rule.include_phone {
-        if phone.is_empty() {
-            return Err(AppError::bad_request("student phone missing"));
-        }
-        parts.push(phone.to_string());
-    }
-    if let Some(suffix) = rule.suffix.as_ref() {
-        if !suffix.is_empty() {
-            parts.push(suffix.clone());
+    let now = Utc::now();
+    let mut models = Vec::new();
+    for row in rows {
+        if !taken.insert(row.student_no.clone()) {
+            continue;
         }
+        let password = rule.build(&row.student_no, &row.phone)?;
+        let hash = hash_password(&password)?;
+        models.push(users::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            username: Set(row.student_no.clone()),
+            display_name: Set(row.name.clone()),
+            role: Set("student".to_string()),
+            email: Set(None),
+            password_hash: Set(Some(hash)),
+            allow_password_login: Set(true),
+            password_updated_at: Set(Some(now)),
+            must_change_password: Set(true),
+            is_active: Set(true),
+            department: Set(None),
+            notify_review_email: Set(true),
+            locale: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        });
     }
-    let password = parts.join("");
-    if password.is_empty() {
-        return Err(AppError::bad_request("password rule produces empty password"));
-    }
-    let hash = hash_password(&password)?;
-    let now = Utc::now();
-    let model = users::ActiveModel {
-        id: Set(Uuid::new_v4()),
-        username: Set(student_no.to_string()),
-        display_name: Set(name.to_string()),
-        role: Set("student".to_string()),
-        email: Set(None),
-        password_hash: Set(Some(hash)),
-        allow_password_login: Set(true),
-        password_updated_at: Set(Some(now)),
-        must_change_password: Set(true),
-        is_active: Set(true),
-        created_at: Set(now),
-        updated_at: Set(now),
-    };
-    users::Entity::insert(model)
-        .exec_without_returning(db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-    Ok(true)
-}
-
-async fn fetch_student_login_flag<C>(db: &C, student_no: &str) -> Result<bool, AppError>
-where
-    C: ConnectionTrait,
-{
-    let record = User::find()
-        .filter(users::Column::Username.eq(student_no))
-        .one(db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?;
-    Ok(record.map(|item| item.allow_password_login).unwrap_or(false))
-}
-
-#[cfg(test)]
-fn read_cell(index: &std::collections::HashMap<String, usize>, key: &str, row: &[Data]) -> String {
-    let idx = match index.get(key) {
-        Some(idx) => *idx,
-        None => return String::new(),
-    };
-    row.get(idx)
-        .map(|cell| cell.to_string().trim().to_string())
-        .unwrap_or_default()
+    let created = models.len();
+    insert_chunked(db, models).await?;
+    Ok(created)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::spreadsheet::read_cell_by_title;
+    use calamine::Data;
 
     #[test]
     fn read_cell_returns_trimmed_string() {
         let mut index = std::collections::HashMap::new();
         index.insert("学号".to_string(), 0);
         let row = vec![Data::String(" 2023001 ".to_string())];
-        assert_eq!(read_cell(&index, "学号", &row), "2023001");
+        assert_eq!(read_cell_by_title(&index, "学号", &row), "2023001");
     }
 
     #[test]
     fn read_cell_returns_empty_on_missing_header() {
         let index = std::collections::HashMap::new();
         let row = vec![Data::String(" 2023001 ".to_string())];
-        assert_eq!(read_cell(&index, "学号", &row), "");
+        assert_eq!(read_cell_by_title(&index, "学号", &row), "");
+    }
+
+    #[test]
+    fn percentile_bucket_rounds_down_and_splits_ties() {
+        let values = (1..=10).collect::<Vec<_>>();
+        assert_eq!(percentile_bucket(10, &values), 95);
+        assert_eq!(percentile_bucket(1, &values), 5);
+        assert_eq!(percentile_bucket(5, &[5; 10]), 50);
+        assert_eq!(percentile_bucket(3, &[]), 0);
+    }
+
+    #[test]
+    fn build_peer_placement_requires_minimum_group() {
+        let small: HashMap<Uuid, i32> = (0..PEER_STATS_MIN_GROUP - 1).map(|_| (Uuid::new_v4(), 1)).collect();
+        assert!(build_peer_placement(1, &small).is_none());
+        let large: HashMap<Uuid, i32> = (0..PEER_STATS_MIN_GROUP).map(|_| (Uuid::new_v4(), 1)).collect();
+        assert_eq!(build_peer_placement(1, &large).unwrap().group_size, PEER_STATS_MIN_GROUP);
+    }
+
+    #[test]
+    fn diff_contact_row_reports_changed_fields_only() {
+        let now = Utc::now();
+        let student = students::Model {
+            id: Uuid::new_v4(),
+            student_no: "2023001".to_string(),
+            name: "张三".to_string(),
+            gender: "男".to_string(),
+            department: "信息学院".to_string(),
+            major: "软件工程".to_string(),
+            class_name: "软工1班".to_string(),
+            phone: "13800000000".to_string(),
+            import_batch_id: None,
+            is_deleted: false,
+            created_at: now,
+            updated_at: now,
+        };
+        let account = users::Model {
+            id: Uuid::new_v4(),
+            username: "2023001".to_string(),
+            display_name: "张三".to_string(),
+            role: "student".to_string(),
+            email: Some("old@example.com".to_string()),
+            password_hash: None,
+            allow_password_login: false,
+            password_updated_at: None,
+            must_change_password: false,
+            is_active: true,
+            department: None,
+            notify_review_email: true,
+            locale: None,
+            created_at: now,
+            updated_at: now,
+        };
+        let changes = diff_contact_row(&student, Some(&account), "13800000000", "new@example.com").unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "email");
+        assert_eq!(changes[0].old_value.as_deref(), Some("old@example.com"));
+        assert!(diff_contact_row(&student, Some(&account), "", "").unwrap().is_empty());
+        assert!(diff_contact_row(&student, Some(&account), "abc", "").is_err());
+        assert!(diff_contact_row(&student, None, "", "new@example.com").is_err());
+    }
+
+    #[test]
+    fn student_import_counters_merge_batches() {
+        let mut total = StudentImportCounters::default();
+        total.merge(StudentImportCounters {
+            inserted: 3,
+            updated: 1,
+            created_users: 2,
+            skipped_users: 0,
+            ..Default::default()
+        });
+        total.merge(StudentImportCounters {
+            inserted: 1,
+            updated: 0,
+            created_users: 0,
+            skipped_users: 1,
+            ..Default::default()
+        });
+        assert_eq!((total.inserted, total.updated, total.created_users, total.skipped_users), (4, 1, 2, 1));
+        let report = serde_json::to_value(&total).unwrap();
+        assert_eq!(report["inserted"], 4);
     }
 }
@@ -0,0 +1,332 @@
+//! 出站 Webhook 管理接口（仅管理员）：登记回调地址、订阅事件并查看投递日志。
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    access::{require_role, require_session_user},
+    audit::record_audit,
+    auth::{encrypt_secret, generate_token},
+    entities::{webhook_deliveries, webhooks, Webhook, WebhookDelivery},
+    error::AppError,
+    state::AppState,
+    webhooks::{normalize_events, subscribed_events, validate_webhook_url},
+};
+
+/// 自定义签名密钥的长度范围（字符）。
+const SECRET_MIN_CHARS: usize = 16;
+const SECRET_MAX_CHARS: usize = 256;
+
+/// 新建 Webhook 请求。
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    /// 回调地址。
+    pub url: String,
+    /// 订阅的事件。
+    pub events: Vec<String>,
+    /// 是否启用，默认启用。
+    pub is_active: Option<bool>,
+    /// 签名密钥；为空时自动生成。
+    pub secret: Option<String>,
+}
+
+/// 更新 Webhook 请求；未提供的字段保持不变。
+#[derive(Debug, Deserialize)]
+pub struct UpdateWebhookRequest {
+    /// 回调地址。
+    pub url: Option<String>,
+    /// 订阅的事件。
+    pub events: Option<Vec<String>>,
+    /// 是否启用。
+    pub is_active: Option<bool>,
+    /// 是否重新生成签名密钥。
+    #[serde(default)]
+    pub rotate_secret: bool,
+}
+
+/// Webhook 响应。
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    /// Webhook ID。
+    pub id: Uuid,
+    /// 回调地址。
+    pub url: String,
+    /// 订阅的事件。
+    pub events: Vec<String>,
+    /// 是否启用。
+    pub is_active: bool,
+    /// 签名密钥，仅在创建或重新生成时返回一次。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// 创建时间。
+    pub created_at: String,
+    /// 更新时间。
+    pub updated_at: String,
+}
+
+/// 投递日志查询参数。
+#[derive(Debug, Deserialize)]
+pub struct DeliveryQuery {
+    /// 投递状态（pending/succeeded/failed）。
+    pub status: Option<String>,
+    /// 返回条数上限（默认 50，最大 500）。
+    pub limit: Option<u64>,
+}
+
+/// 列出全部 Webhook。
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<WebhookResponse>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let hooks = Webhook::find()
+        .order_by_asc(webhooks::Column::CreatedAt)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(
+        hooks
+            .into_iter()
+            .map(|hook| webhook_response(hook, None))
+            .collect(),
+    ))
+}
+
+/// 登记 Webhook，响应中返回签名密钥。
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<Json<WebhookResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let url = validate_webhook_url(&payload.url)?;
+    let events = normalize_events(&payload.events)?.join(",");
+    let secret = match payload.secret.as_deref().map(str::trim) {
+        Some(secret) if !secret.is_empty() => validate_secret(secret)?,
+        _ => generate_token(),
+    };
+    let now = Utc::now();
+    let model = webhooks::Model {
+        id: Uuid::new_v4(),
+        url,
+        secret_enc: encrypt_secret(secret.as_bytes(), &state.config.secret_keyring())?,
+        events,
+        is_active: payload.is_active.unwrap_or(true),
+        created_by: Some(user.id),
+        created_at: now,
+        updated_at: now,
+    };
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let active: webhooks::ActiveModel = model.clone().into();
+    Webhook::insert(active)
+        .exec_without_returning(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "webhook_create",
+        "webhook",
+        Some(&model.id.to_string()),
+        Some(serde_json::json!({
+            "url": model.url,
+            "events": model.events,
+            "is_active": model.is_active,
+        })),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(webhook_response(model, Some(secret))))
+}
+
+/// 更新 Webhook；重新生成密钥时在响应中返回新密钥。
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(webhook_id): Path<Uuid>,
+    Json(payload): Json<UpdateWebhookRequest>,
+) -> Result<Json<WebhookResponse>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let existing = Webhook::find_by_id(webhook_id)
+        .one(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("webhook not found"))?;
+    let previous = serde_json::json!({
+        "url": existing.url,
+        "events": existing.events,
+        "is_active": existing.is_active,
+    });
+    let mut active: webhooks::ActiveModel = existing.into();
+    if let Some(url) = payload.url.as_deref() {
+        active.url = Set(validate_webhook_url(url)?);
+    }
+    if let Some(events) = payload.events.as_deref() {
+        active.events = Set(normalize_events(events)?.join(","));
+    }
+    if let Some(is_active) = payload.is_active {
+        active.is_active = Set(is_active);
+    }
+    let secret = if payload.rotate_secret {
+        let secret = generate_token();
+        active.secret_enc = Set(encrypt_secret(secret.as_bytes(), &state.config.secret_keyring())?);
+        Some(secret)
+    } else {
+        None
+    };
+    active.updated_at = Set(Utc::now());
+    let model = active
+        .update(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "webhook_update",
+        "webhook",
+        Some(&model.id.to_string()),
+        Some(serde_json::json!({
+            "from": previous,
+            "to": {
+                "url": model.url,
+                "events": model.events,
+                "is_active": model.is_active,
+            },
+            "secret_rotated": payload.rotate_secret,
+        })),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(webhook_response(model, secret)))
+}
+
+/// 删除 Webhook 及其投递日志。
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    let transaction = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let existing = Webhook::find_by_id(webhook_id)
+        .one(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("webhook not found"))?;
+    WebhookDelivery::delete_many()
+        .filter(webhook_deliveries::Column::WebhookId.eq(webhook_id))
+        .exec(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Webhook::delete_by_id(webhook_id)
+        .exec(&transaction)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &transaction,
+        Some(user.id),
+        "webhook_delete",
+        "webhook",
+        Some(&webhook_id.to_string()),
+        Some(serde_json::json!({ "url": existing.url })),
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    Ok(Json(serde_json::json!({ "deleted": true })))
+}
+
+/// 查看 Webhook 的投递日志（按创建时间倒序）。
+pub async fn list_webhook_deliveries(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(webhook_id): Path<Uuid>,
+    Query(query): Query<DeliveryQuery>,
+) -> Result<Json<Vec<webhook_deliveries::Model>>, AppError> {
+    let user = require_session_user(&state, &jar).await?;
+    require_role(&user, "admin")?;
+
+    Webhook::find_by_id(webhook_id)
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .ok_or_else(|| AppError::not_found("webhook not found"))?;
+    let mut finder =
+        WebhookDelivery::find().filter(webhook_deliveries::Column::WebhookId.eq(webhook_id));
+    if let Some(status) = query.status.filter(|value| !value.is_empty()) {
+        finder = finder.filter(webhook_deliveries::Column::Status.eq(status));
+    }
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let deliveries = finder
+        .order_by_desc(webhook_deliveries::Column::CreatedAt)
+        .limit(limit)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(Json(deliveries))
+}
+
+fn validate_secret(secret: &str) -> Result<String, AppError> {
+    let chars = secret.chars().count();
+    if !(SECRET_MIN_CHARS..=SECRET_MAX_CHARS).contains(&chars) {
+        return Err(AppError::validation(&format!(
+            "webhook secret must be {SECRET_MIN_CHARS}-{SECRET_MAX_CHARS} characters"
+        )));
+    }
+    Ok(secret.to_string())
+}
+
+fn webhook_response(hook: webhooks::Model, secret: Option<String>) -> WebhookResponse {
+    WebhookResponse {
+        id: hook.id,
+        url: hook.url,
+        events: subscribed_events(&hook.events),
+        is_active: hook.is_active,
+        secret,
+        created_at: hook.created_at.to_rfc3339(),
+        updated_at: hook.updated_at.to_rfc3339(),
+    }
+}
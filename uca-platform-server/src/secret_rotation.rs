@@ -0,0 +1,260 @@
+//! 应用密钥轮换：统计各表密文使用的密钥，并将旧密钥加密的密文改用当前密钥重新加密。
+//!
+//! 轮换步骤：将旧密钥移入 `AUTH_PREVIOUS_SECRET_KEYS`，配置新的 `AUTH_SECRET_KEY` 与
+//! `AUTH_SECRET_KEY_ID` 后重启，再执行重新加密；全部密文改用新密钥后即可移除旧密钥。
+
+use std::collections::BTreeMap;
+
+use sea_orm::{sea_query::Expr, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    auth::{decrypt_secret, encrypt_secret, secret_key_id, SecretKeyring},
+    entities::{totp_secrets, webhooks, TotpSecret, Webhook},
+    error::AppError,
+};
+
+/// 旧版密文（未记录密钥 ID）在统计中的键名。
+pub const LEGACY_KEY_ID: &str = "legacy";
+
+const TABLE_TOTP_SECRETS: &str = "totp_secrets";
+const TABLE_WEBHOOKS: &str = "webhooks";
+
+/// 单张表的密文统计。
+#[derive(Debug, Default, Serialize)]
+pub struct SecretTableUsage {
+    /// 表名。
+    pub table: String,
+    /// 密文总数。
+    pub total: u64,
+    /// 按密钥 ID 统计的密文数量。
+    pub by_key: BTreeMap<String, u64>,
+    /// 未使用当前密钥的密文数量。
+    pub outdated: u64,
+}
+
+/// 密钥使用情况。
+#[derive(Debug, Serialize)]
+pub struct SecretUsageReport {
+    /// 当前密钥 ID。
+    pub primary_key_id: String,
+    /// 已配置的历史密钥 ID。
+    pub previous_key_ids: Vec<String>,
+    /// 各表统计。
+    pub tables: Vec<SecretTableUsage>,
+}
+
+/// 重新加密失败的密文。
+#[derive(Debug, Serialize)]
+pub struct SecretRotationFailure {
+    /// 表名。
+    pub table: String,
+    /// 行 ID。
+    pub id: Uuid,
+    /// 失败原因。
+    pub error: String,
+}
+
+/// 重新加密结果。
+#[derive(Debug, Serialize)]
+pub struct SecretRotationReport {
+    /// 当前密钥 ID。
+    pub primary_key_id: String,
+    /// 按表统计的重新加密数量。
+    pub rotated: BTreeMap<String, u64>,
+    /// 无法解密或写回的密文（通常是对应的历史密钥未配置）。
+    pub failures: Vec<SecretRotationFailure>,
+}
+
+/// 统计各表密文使用的密钥。
+pub async fn secret_usage<C>(db: &C, keyring: &SecretKeyring<'_>) -> Result<SecretUsageReport, AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut tables = Vec::new();
+    for (table, rows) in load_secrets(db).await? {
+        let mut usage = SecretTableUsage {
+            table: table.to_string(),
+            ..Default::default()
+        };
+        for (_, encoded) in rows {
+            let key_id = secret_key_id(&encoded).unwrap_or(LEGACY_KEY_ID);
+            usage.total += 1;
+            if key_id != keyring.primary_id {
+                usage.outdated += 1;
+            }
+            *usage.by_key.entry(key_id.to_string()).or_default() += 1;
+        }
+        tables.push(usage);
+    }
+    Ok(SecretUsageReport {
+        primary_key_id: keyring.primary_id.to_string(),
+        previous_key_ids: keyring.previous.iter().map(|key| key.id.clone()).collect(),
+        tables,
+    })
+}
+
+/// 将未使用当前密钥的密文重新加密；单行失败不影响其他行。
+pub async fn rotate_secrets<C>(db: &C, keyring: &SecretKeyring<'_>) -> Result<SecretRotationReport, AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut rotated = BTreeMap::new();
+    let mut failures = Vec::new();
+    for (table, rows) in load_secrets(db).await? {
+        let mut count = 0;
+        for (id, encoded) in rows {
+            if secret_key_id(&encoded) == Some(keyring.primary_id) {
+                continue;
+            }
+            match reencrypt(db, table, id, &encoded, keyring).await {
+                Ok(true) => count += 1,
+                // 期间已被用户重新绑定或修改，新密文已使用当前密钥。
+                Ok(false) => {}
+                Err(err) => failures.push(SecretRotationFailure {
+                    table: table.to_string(),
+                    id,
+                    error: err.to_string(),
+                }),
+            }
+        }
+        rotated.insert(table.to_string(), count);
+    }
+    Ok(SecretRotationReport {
+        primary_key_id: keyring.primary_id.to_string(),
+        rotated,
+        failures,
+    })
+}
+
+type SecretRows = Vec<(Uuid, String)>;
+
+async fn load_secrets<C>(db: &C) -> Result<Vec<(&'static str, SecretRows)>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let totp = TotpSecret::find()
+        .select_only()
+        .column(totp_secrets::Column::Id)
+        .column(totp_secrets::Column::SecretEnc)
+        .into_tuple::<(Uuid, String)>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let hooks = Webhook::find()
+        .select_only()
+        .column(webhooks::Column::Id)
+        .column(webhooks::Column::SecretEnc)
+        .into_tuple::<(Uuid, String)>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(vec![(TABLE_TOTP_SECRETS, totp), (TABLE_WEBHOOKS, hooks)])
+}
+
+/// 重新加密单行；仅在密文未被并发修改时写回。
+async fn reencrypt<C>(
+    db: &C,
+    table: &str,
+    id: Uuid,
+    encoded: &str,
+    keyring: &SecretKeyring<'_>,
+) -> Result<bool, AppError>
+where
+    C: ConnectionTrait,
+{
+    let secret = decrypt_secret(encoded, keyring)?;
+    let updated = encrypt_secret(&secret, keyring)?;
+    let result = match table {
+        TABLE_TOTP_SECRETS => {
+            TotpSecret::update_many()
+                .col_expr(totp_secrets::Column::SecretEnc, Expr::value(updated))
+                .filter(totp_secrets::Column::Id.eq(id))
+                .filter(totp_secrets::Column::SecretEnc.eq(encoded))
+                .exec(db)
+                .await
+        }
+        _ => {
+            Webhook::update_many()
+                .col_expr(webhooks::Column::SecretEnc, Expr::value(updated))
+                .filter(webhooks::Column::Id.eq(id))
+                .filter(webhooks::Column::SecretEnc.eq(encoded))
+                .exec(db)
+                .await
+        }
+    }
+    .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(result.rows_affected > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use sea_orm::{ActiveModelTrait, Set};
+
+    use crate::{config::SecretKey, services::test_support::memory_db};
+
+    async fn insert_webhook(db: &sea_orm::DatabaseConnection, secret_enc: String) -> Uuid {
+        let now = Utc::now();
+        webhooks::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            url: Set("https://hooks.example.com".to_string()),
+            secret_enc: Set(secret_enc),
+            events: Set("record.submitted".to_string()),
+            is_active: Set(true),
+            created_by: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(db)
+        .await
+        .unwrap()
+        .id
+    }
+
+    #[tokio::test]
+    async fn rotation_reencrypts_rows_under_previous_keys() {
+        let db = memory_db().await;
+        let old_key = vec![3u8; 32];
+        let new_key = vec![5u8; 32];
+        let old_keyring = SecretKeyring {
+            primary_id: "k1",
+            primary_key: &old_key,
+            previous: &[],
+        };
+        let rotated_id = insert_webhook(&db, encrypt_secret(b"hook-secret", &old_keyring).unwrap()).await;
+        let broken_id = insert_webhook(&db, "SECv2:k0:AAAA".to_string()).await;
+
+        let previous = [SecretKey {
+            id: "k1".to_string(),
+            key: old_key.clone(),
+        }];
+        let keyring = SecretKeyring {
+            primary_id: "k2",
+            primary_key: &new_key,
+            previous: &previous,
+        };
+        let usage = secret_usage(&db, &keyring).await.unwrap();
+        let hooks = usage.tables.iter().find(|table| table.table == "webhooks").unwrap();
+        assert_eq!(hooks.total, 2);
+        assert_eq!(hooks.outdated, 2);
+        assert_eq!(hooks.by_key["k1"], 1);
+
+        let report = rotate_secrets(&db, &keyring).await.unwrap();
+        assert_eq!(report.rotated["webhooks"], 1);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].id, broken_id);
+
+        let hook = Webhook::find_by_id(rotated_id).one(&db).await.unwrap().unwrap();
+        assert_eq!(secret_key_id(&hook.secret_enc), Some("k2"));
+        let current_only = SecretKeyring {
+            primary_id: "k2",
+            primary_key: &new_key,
+            previous: &[],
+        };
+        assert_eq!(decrypt_secret(&hook.secret_enc, &current_only).unwrap(), b"hook-secret");
+        assert_eq!(rotate_secrets(&db, &keyring).await.unwrap().rotated["webhooks"], 0);
+    }
+}
@@ -0,0 +1,178 @@
+//! 安全事件记录与告警转发。
+
+use chrono::Utc;
+use sea_orm::{EntityTrait, Set};
+use uuid::Uuid;
+
+use crate::{entities::security_events, i18n::Locale, mail_outbox::enqueue_mail, state::AppState};
+
+pub const SEVERITY_LOW: &str = "low";
+pub const SEVERITY_MEDIUM: &str = "medium";
+pub const SEVERITY_HIGH: &str = "high";
+
+pub const EVENT_LOGIN_FAILED: &str = "login_failed";
+pub const EVENT_RECOVERY_CODE_USED: &str = "recovery_code_used";
+pub const EVENT_RECOVERY_CODES_GENERATED: &str = "recovery_codes_generated";
+pub const EVENT_DEVICE_REMOVED: &str = "device_removed";
+pub const EVENT_ROLE_GRANTED: &str = "role_granted";
+pub const EVENT_RESET_ISSUED: &str = "reset_issued";
+
+/// 待记录的安全事件。
+#[derive(Debug, Clone)]
+pub struct SecurityEventInput {
+    /// 事件类型。
+    pub event_type: &'static str,
+    /// 严重级别（low/medium/high）。
+    pub severity: &'static str,
+    /// 受影响用户 ID。
+    pub user_id: Option<Uuid>,
+    /// 受影响用户名。
+    pub username: Option<String>,
+    /// 操作人 ID（管理员操作时）。
+    pub actor_id: Option<Uuid>,
+    /// 事件说明。
+    pub detail: Option<String>,
+}
+
+impl SecurityEventInput {
+    /// 创建事件。
+    pub fn new(event_type: &'static str, severity: &'static str) -> Self {
+        Self {
+            event_type,
+            severity,
+            user_id: None,
+            username: None,
+            actor_id: None,
+            detail: None,
+        }
+    }
+
+    /// 设置受影响用户。
+    pub fn user(mut self, user_id: Option<Uuid>, username: &str) -> Self {
+        self.user_id = user_id;
+        self.username = Some(username.to_string());
+        self
+    }
+
+    /// 设置操作人。
+    pub fn actor(mut self, actor_id: Uuid) -> Self {
+        self.actor_id = Some(actor_id);
+        self
+    }
+
+    /// 设置事件说明。
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// 授予角色事件的严重级别（管理员角色视为高危）。
+pub fn role_grant_severity(role: &str) -> &'static str {
+    if role == "admin" {
+        SEVERITY_HIGH
+    } else {
+        SEVERITY_MEDIUM
+    }
+}
+
+/// 记录安全事件；写入失败只记日志，不影响业务请求。高危事件按配置转发告警。
+pub async fn record_security_event(state: &AppState, event: SecurityEventInput) {
+    let model = security_events::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        event_type: Set(event.event_type.to_string()),
+        severity: Set(event.severity.to_string()),
+        user_id: Set(event.user_id),
+        username: Set(event.username.clone()),
+        actor_id: Set(event.actor_id),
+        detail: Set(event.detail.clone()),
+        created_at: Set(Utc::now()),
+    };
+    if let Err(err) = security_events::Entity::insert(model)
+        .exec_without_returning(&state.db)
+        .await
+    {
+        tracing::warn!(event_type = event.event_type, error = %err, "record security event failed");
+    }
+
+    if event.severity == SEVERITY_HIGH {
+        forward_alert(state, &event);
+    }
+}
+
+fn forward_alert(state: &AppState, event: &SecurityEventInput) {
+    let locale = state.config.locale;
+    let body = format_alert(locale, event);
+    if let (Some(to), Some(_)) = (
+        state.config.security_alert_email.clone(),
+        state.mail_config(),
+    ) {
+        let subject = locale.format("mail.security_alert.subject", &[("event", event.event_type)]);
+        let body = body.clone();
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(err) = enqueue_mail(&state, &to, &subject, &body).await {
+                tracing::warn!(error = %err, "enqueue security alert email failed");
+            }
+        });
+    }
+    if let Some(url) = state.config.security_alert_webhook.clone() {
+        let payload = serde_json::json!({
+            "event_type": event.event_type,
+            "severity": event.severity,
+            "user_id": event.user_id,
+            "username": event.username,
+            "actor_id": event.actor_id,
+            "detail": event.detail,
+            "text": body,
+        });
+        tokio::spawn(async move {
+            let result = reqwest::Client::new()
+                .post(url)
+                .timeout(std::time::Duration::from_secs(10))
+                .json(&payload)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(err) = result {
+                tracing::warn!(error = %err, "send security alert webhook failed");
+            }
+        });
+    }
+}
+
+fn format_alert(locale: Locale, event: &SecurityEventInput) -> String {
+    let time = Utc::now().to_rfc3339();
+    locale.format(
+        "mail.security_alert.body",
+        &[
+            ("event", event.event_type),
+            ("severity", event.severity),
+            ("user", event.username.as_deref().unwrap_or("-")),
+            ("detail", event.detail.as_deref().unwrap_or("-")),
+            ("time", &time),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_alert_includes_event_fields() {
+        let event = SecurityEventInput::new(EVENT_RESET_ISSUED, SEVERITY_HIGH)
+            .user(None, "teacher01")
+            .detail("purpose=totp");
+        let text = format_alert(Locale::ZhCn, &event);
+        assert!(text.contains("reset_issued"));
+        assert!(text.contains("teacher01"));
+        assert!(text.contains("purpose=totp"));
+    }
+
+    #[test]
+    fn role_grant_severity_flags_admin() {
+        assert_eq!(role_grant_severity("admin"), SEVERITY_HIGH);
+        assert_eq!(role_grant_severity("teacher"), SEVERITY_MEDIUM);
+    }
+}
@@ -0,0 +1,279 @@
+//! 开发环境演示数据：学生、各角色账号、表单字段、竞赛库与竞赛记录。
+//!
+//! 重复执行是幂等的：已存在的学号、用户名、字段键与竞赛名称会被跳过，
+//! 竞赛记录只为本次新建的学生生成。
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter, Set};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    auth::hash_password,
+    entities::{
+        competition_library, contest_records, form_fields, students, users, CompetitionLibrary,
+        FormField, Student, User,
+    },
+    error::AppError,
+    semesters::semester_for_date,
+    services::review::{STATUS_FINAL_REVIEWED, STATUS_FIRST_REVIEWED, STATUS_REJECTED, STATUS_SUBMITTED},
+};
+
+/// 演示账号的统一登录密码。
+pub const DEMO_PASSWORD: &str = "Demo@123456";
+
+/// 演示学生：学号、姓名、性别、院系、专业、班级。
+const DEMO_STUDENTS: &[(&str, &str, &str, &str, &str, &str)] = &[
+    ("D2026000001", "张晓明", "男", "计算机科学与工程学院", "软件工程", "软工2401"),
+    ("D2026000002", "李雨桐", "女", "计算机科学与工程学院", "软件工程", "软工2401"),
+    ("D2026000003", "王浩然", "男", "计算机科学与工程学院", "计算机科学与技术", "计科2402"),
+    ("D2026000004", "陈思雨", "女", "机械工程学院", "机械设计制造及其自动化", "机械2401"),
+    ("D2026000005", "刘子墨", "男", "机械工程学院", "车辆工程", "车辆2401"),
+    ("D2026000006", "赵一诺", "女", "机械工程学院", "车辆工程", "车辆2401"),
+];
+
+/// 演示教职工账号：用户名、显示名、角色、院系。
+const DEMO_STAFF: &[(&str, &str, &str, Option<&str>)] = &[
+    ("demo_admin", "演示管理员", "admin", None),
+    ("demo_teacher", "演示教师", "teacher", Some("计算机科学与工程学院")),
+    ("demo_teacher_me", "演示教师（机械）", "teacher", Some("机械工程学院")),
+    ("demo_reviewer", "演示审核员", "reviewer", None),
+];
+
+/// 演示竞赛：名称、年份、类别。
+const DEMO_COMPETITIONS: &[(&str, i32, &str)] = &[
+    ("全国大学生数学建模竞赛", 2025, "A类"),
+    ("蓝桥杯全国软件和信息技术专业人才大赛", 2025, "A类"),
+    ("全国大学生机械创新设计大赛", 2025, "B类"),
+];
+
+/// 演示数据写入结果（仅统计本次新建的条目）。
+#[derive(Debug, Default, Serialize)]
+pub struct SeedSummary {
+    /// 新建学生数。
+    pub students: usize,
+    /// 新建账号数（含学生账号）。
+    pub users: usize,
+    /// 新建表单字段数。
+    pub form_fields: usize,
+    /// 新建竞赛数。
+    pub competitions: usize,
+    /// 新建竞赛记录数。
+    pub records: usize,
+    /// 演示账号密码。
+    pub password: String,
+}
+
+/// 写入演示数据。
+pub async fn seed_demo_data<C>(db: &C) -> Result<SeedSummary, AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut summary = SeedSummary {
+        password: DEMO_PASSWORD.to_string(),
+        ..SeedSummary::default()
+    };
+    let now = Utc::now();
+    let password_hash = hash_password(DEMO_PASSWORD)?;
+
+    let mut created_students = Vec::new();
+    for (student_no, name, gender, department, major, class_name) in DEMO_STUDENTS {
+        let exists = Student::find()
+            .filter(students::Column::StudentNo.eq(*student_no))
+            .count(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            > 0;
+        if exists {
+            continue;
+        }
+        let student_id = Uuid::new_v4();
+        let model = students::ActiveModel {
+            id: Set(student_id),
+            student_no: Set(student_no.to_string()),
+            name: Set(name.to_string()),
+            gender: Set(gender.to_string()),
+            department: Set(department.to_string()),
+            major: Set(major.to_string()),
+            class_name: Set(class_name.to_string()),
+            phone: Set(format!("1380000{}", &student_no[student_no.len() - 4..])),
+            import_batch_id: Set(None),
+            is_deleted: Set(false),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+        Student::insert(model)
+            .exec_without_returning(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        created_students.push(student_id);
+        summary.students += 1;
+    }
+
+    let student_accounts = DEMO_STUDENTS
+        .iter()
+        .map(|(student_no, name, _, _, _, _)| (*student_no, *name, "student", None));
+    for (username, display_name, role, department) in DEMO_STAFF.iter().copied().chain(student_accounts) {
+        let exists = User::find()
+            .filter(users::Column::Username.eq(username))
+            .count(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            > 0;
+        if exists {
+            continue;
+        }
+        let model = users::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            username: Set(username.to_string()),
+            display_name: Set(display_name.to_string()),
+            role: Set(role.to_string()),
+            email: Set(None),
+            password_hash: Set(Some(password_hash.clone())),
+            allow_password_login: Set(true),
+            password_updated_at: Set(Some(now)),
+            must_change_password: Set(false),
+            is_active: Set(true),
+            department: Set(department.map(str::to_string)),
+            notify_review_email: Set(false),
+            locale: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+        User::insert(model)
+            .exec_without_returning(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        summary.users += 1;
+    }
+
+    let demo_fields: [(&str, &str, &str, bool, Option<&str>, Option<i32>, Option<i32>); 4] = [
+        ("team_name", "团队名称", "text", false, None, None, None),
+        ("award_type", "获奖类型", "select", false, Some(r#"["个人","团体"]"#), None, None),
+        ("advisor", "指导教师", "text", true, None, None, None),
+        ("team_size", "团队人数", "number", false, None, Some(1), Some(10)),
+    ];
+    let mut order_index = FormField::find()
+        .filter(form_fields::Column::FormType.eq("contest"))
+        .count(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))? as i32;
+    for (field_key, label, field_type, required, options, min_value, max_value) in demo_fields {
+        let exists = FormField::find()
+            .filter(form_fields::Column::FormType.eq("contest"))
+            .filter(form_fields::Column::FieldKey.eq(field_key))
+            .count(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            > 0;
+        if exists {
+            continue;
+        }
+        let model = form_fields::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            form_type: Set("contest".to_string()),
+            field_key: Set(field_key.to_string()),
+            label: Set(label.to_string()),
+            field_type: Set(field_type.to_string()),
+            required: Set(required),
+            order_index: Set(order_index),
+            options: Set(options.map(str::to_string)),
+            min_value: Set(min_value),
+            max_value: Set(max_value),
+            max_length: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+        FormField::insert(model)
+            .exec_without_returning(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        order_index += 1;
+        summary.form_fields += 1;
+    }
+
+    let mut competition_ids = Vec::new();
+    for (name, year, category) in DEMO_COMPETITIONS {
+        let existing = CompetitionLibrary::find()
+            .filter(competition_library::Column::Name.eq(*name))
+            .one(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        if let Some(existing) = existing {
+            competition_ids.push((existing.id, *name, *year, *category));
+            continue;
+        }
+        let id = Uuid::new_v4();
+        let model = competition_library::ActiveModel {
+            id: Set(id),
+            year: Set(Some(*year)),
+            category: Set(Some(category.to_string())),
+            name: Set(name.to_string()),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+        CompetitionLibrary::insert(model)
+            .exec_without_returning(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        competition_ids.push((id, *name, *year, *category));
+        summary.competitions += 1;
+    }
+
+    let reviewer_id = User::find()
+        .filter(users::Column::Username.eq("demo_reviewer"))
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .map(|user| user.id);
+    let statuses = [STATUS_SUBMITTED, STATUS_FIRST_REVIEWED, STATUS_FINAL_REVIEWED, STATUS_REJECTED];
+    for (index, student_id) in created_students.iter().enumerate() {
+        for offset in 0..2 {
+            let slot = index * 2 + offset;
+            let (competition_id, name, year, category) = competition_ids[slot % competition_ids.len()];
+            let status = statuses[slot % statuses.len()];
+            let award_date = NaiveDate::from_ymd_opt(year, 5 + (slot % 6) as u32, 15)
+                .ok_or_else(|| AppError::internal("invalid demo award date"))?;
+            let semester_id = semester_for_date(db, award_date).await?;
+            let self_hours = 2 + (slot % 3) as i32;
+            let first_review_hours = (status != STATUS_SUBMITTED).then_some(self_hours);
+            let final_review_hours = (status == STATUS_FINAL_REVIEWED).then_some(self_hours);
+            let model = contest_records::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                student_id: Set(*student_id),
+                contest_year: Set(Some(year)),
+                contest_category: Set(Some(category.to_string())),
+                contest_name: Set(name.to_string()),
+                contest_level: Set(Some(if offset == 0 { "国家级" } else { "省级" }.to_string())),
+                contest_role: Set(Some(if offset == 0 { "负责人" } else { "成员" }.to_string())),
+                award_level: Set(if offset == 0 { "一等奖" } else { "二等奖" }.to_string()),
+                award_date: Set(award_date.and_hms_opt(0, 0, 0).map(|value| Utc.from_utc_datetime(&value))),
+                self_hours: Set(self_hours),
+                first_review_hours: Set(first_review_hours),
+                final_review_hours: Set(final_review_hours),
+                first_reviewer_id: Set(first_review_hours.and(reviewer_id)),
+                final_reviewer_id: Set(final_review_hours.and(reviewer_id)),
+                competition_id: Set(Some(competition_id)),
+                semester_id: Set(semester_id),
+                assigned_reviewer_id: Set(None),
+                status: Set(status.to_string()),
+                rejection_reason: Set((status == STATUS_REJECTED).then(|| "证明材料不清晰".to_string())),
+                version: Set(0),
+                recommended_hours: Set(None),
+                first_reviewed_at: Set(None),
+                final_reviewed_at: Set(None),
+                import_batch_id: Set(None),
+                is_deleted: Set(false),
+                created_at: Set(now),
+                updated_at: Set(now),
+            };
+            contest_records::Entity::insert(model)
+                .exec_without_returning(db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+            summary.records += 1;
+        }
+    }
+
+    Ok(summary)
+}
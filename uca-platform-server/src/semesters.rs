@@ -0,0 +1,242 @@
+//! 学期：按日期确定记录所属学期，并在学期范围变化时重新归属记录。
+//!
+//! 竞赛记录按获奖时间归属学期，未填写获奖时间时按提交时间；志愿服务记录按提交时间。
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use uuid::Uuid;
+
+use crate::{
+    entities::{contest_records, semesters, volunteer_records, ContestRecord, Semester, VolunteerRecord},
+    error::AppError,
+};
+
+/// 校验学年格式（`2025-2026`，结束年为起始年加一）。
+pub fn validate_academic_year(value: &str) -> Result<(), AppError> {
+    let invalid = || AppError::validation("academic_year must look like 2025-2026");
+    let (start, end) = value.trim().split_once('-').ok_or_else(invalid)?;
+    let start: i32 = start.parse().map_err(|_| invalid())?;
+    let end: i32 = end.parse().map_err(|_| invalid())?;
+    if end != start + 1 {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+/// 校验学期日期范围，且不得与其他学期重叠（记录按日期唯一归属）。
+pub async fn ensure_semester_range<C>(
+    db: &C,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    exclude_id: Option<Uuid>,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    if start_date > end_date {
+        return Err(AppError::validation("start_date must not be after end_date"));
+    }
+    let existing = Semester::find()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let overlapping = existing.iter().any(|semester| {
+        Some(semester.id) != exclude_id
+            && ranges_overlap(start_date, end_date, semester.start_date, semester.end_date)
+    });
+    if overlapping {
+        return Err(AppError::validation("semester overlaps an existing semester"));
+    }
+    Ok(())
+}
+
+/// 查找包含给定日期的学期。
+pub async fn semester_for_date<C>(db: &C, date: NaiveDate) -> Result<Option<Uuid>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let semester = Semester::find()
+        .filter(semesters::Column::StartDate.lte(date))
+        .filter(semesters::Column::EndDate.gte(date))
+        .order_by_asc(semesters::Column::StartDate)
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(semester.map(|semester| semester.id))
+}
+
+/// 在已读取的学期中查找包含给定日期的学期（批量导入时避免逐行查询）。
+pub fn semester_containing(semesters: &[semesters::Model], date: NaiveDate) -> Option<Uuid> {
+    semesters
+        .iter()
+        .filter(|semester| semester.start_date <= date && date <= semester.end_date)
+        .min_by_key(|semester| semester.start_date)
+        .map(|semester| semester.id)
+}
+
+/// 竞赛记录归属学期所依据的日期：获奖时间，未填写时为提交时间。
+pub fn contest_semester_date(award_date: Option<DateTime<Utc>>, created_at: DateTime<Utc>) -> NaiveDate {
+    award_date.unwrap_or(created_at).date_naive()
+}
+
+/// 按日期推算学年（9 月 1 日起为新学年），用于未归属学期的记录。
+pub fn academic_year_of(date: NaiveDate) -> String {
+    let start = if date.month() >= 9 { date.year() } else { date.year() - 1 };
+    format!("{}-{}", start, start + 1)
+}
+
+/// 读取学期 ID 到所属学年的映射。
+pub async fn load_semester_years<C>(db: &C) -> Result<HashMap<Uuid, String>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let rows = Semester::find()
+        .select_only()
+        .column(semesters::Column::Id)
+        .column(semesters::Column::AcademicYear)
+        .into_tuple::<(Uuid, String)>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(rows.into_iter().collect())
+}
+
+/// 重新归属学期记录：先清除原归属，再把日期落在范围内且未归属的记录划入该学期。
+///
+/// 竞赛记录按获奖时间判断，未填写获奖时间时按提交时间；志愿服务记录按提交时间。
+pub async fn reassign_semester_records<C>(db: &C, semester: &semesters::Model) -> Result<u64, AppError>
+where
+    C: ConnectionTrait,
+{
+    clear_semester_records(db, semester.id).await?;
+    let (start, end) = date_range_bounds(semester.start_date, semester.end_date);
+    let contest = ContestRecord::update_many()
+        .col_expr(contest_records::Column::SemesterId, Expr::value(Some(semester.id)))
+        .filter(contest_records::Column::SemesterId.is_null())
+        .filter(
+            Condition::any()
+                .add(
+                    contest_records::Column::AwardDate
+                        .gte(start)
+                        .and(contest_records::Column::AwardDate.lt(end)),
+                )
+                .add(
+                    contest_records::Column::AwardDate
+                        .is_null()
+                        .and(contest_records::Column::CreatedAt.gte(start))
+                        .and(contest_records::Column::CreatedAt.lt(end)),
+                ),
+        )
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let volunteer = VolunteerRecord::update_many()
+        .col_expr(volunteer_records::Column::SemesterId, Expr::value(Some(semester.id)))
+        .filter(volunteer_records::Column::SemesterId.is_null())
+        .filter(volunteer_records::Column::CreatedAt.gte(start))
+        .filter(volunteer_records::Column::CreatedAt.lt(end))
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(contest.rows_affected + volunteer.rows_affected)
+}
+
+/// 清除记录的学期归属（删除学期或调整范围前）。
+pub async fn clear_semester_records<C>(db: &C, semester_id: Uuid) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    ContestRecord::update_many()
+        .col_expr(contest_records::Column::SemesterId, Expr::value(Option::<Uuid>::None))
+        .filter(contest_records::Column::SemesterId.eq(semester_id))
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    VolunteerRecord::update_many()
+        .col_expr(volunteer_records::Column::SemesterId, Expr::value(Option::<Uuid>::None))
+        .filter(volunteer_records::Column::SemesterId.eq(semester_id))
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(())
+}
+
+/// 闭区间日期范围转换为 [开始, 结束) 时间范围（UTC）。
+fn date_range_bounds(start_date: NaiveDate, end_date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = start_date.and_hms_opt(0, 0, 0).expect("midnight is valid").and_utc();
+    let end = end_date
+        .checked_add_days(Days::new(1))
+        .unwrap_or(end_date)
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is valid")
+        .and_utc();
+    (start, end)
+}
+
+fn ranges_overlap(a_start: NaiveDate, a_end: NaiveDate, b_start: NaiveDate, b_end: NaiveDate) -> bool {
+    a_start <= b_end && b_start <= a_end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn academic_year_requires_consecutive_years() {
+        assert!(validate_academic_year("2025-2026").is_ok());
+        assert!(validate_academic_year("2025-2027").is_err());
+        assert!(validate_academic_year("2025").is_err());
+        assert!(validate_academic_year("abcd-efgh").is_err());
+    }
+
+    #[test]
+    fn academic_year_of_starts_in_september() {
+        assert_eq!(academic_year_of(date(2025, 9, 1)), "2025-2026");
+        assert_eq!(academic_year_of(date(2026, 8, 31)), "2025-2026");
+        assert_eq!(academic_year_of(date(2026, 1, 15)), "2025-2026");
+    }
+
+    #[test]
+    fn ranges_overlap_includes_shared_boundary_day() {
+        let first = (date(2025, 9, 1), date(2026, 1, 31));
+        assert!(ranges_overlap(first.0, first.1, date(2026, 1, 31), date(2026, 7, 15)));
+        assert!(!ranges_overlap(first.0, first.1, date(2026, 2, 1), date(2026, 7, 15)));
+    }
+
+    #[test]
+    fn semester_lookup_prefers_award_date() {
+        let now = Utc::now();
+        let first = semesters::Model {
+            id: Uuid::new_v4(),
+            name: "2025-2026 学年第一学期".to_string(),
+            academic_year: "2025-2026".to_string(),
+            start_date: date(2025, 9, 1),
+            end_date: date(2026, 1, 31),
+            created_at: now,
+            updated_at: now,
+        };
+        let semesters = vec![first.clone()];
+        let award = date(2025, 11, 20).and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let submitted = date(2026, 3, 1).and_hms_opt(8, 0, 0).unwrap().and_utc();
+        assert_eq!(contest_semester_date(Some(award), submitted), date(2025, 11, 20));
+        assert_eq!(contest_semester_date(None, submitted), date(2026, 3, 1));
+        assert_eq!(semester_containing(&semesters, date(2025, 11, 20)), Some(first.id));
+        assert_eq!(semester_containing(&semesters, date(2026, 1, 31)), Some(first.id));
+        assert_eq!(semester_containing(&semesters, date(2026, 3, 1)), None);
+    }
+
+    #[test]
+    fn date_range_bounds_end_is_exclusive_next_day() {
+        let (start, end) = date_range_bounds(date(2026, 2, 1), date(2026, 7, 15));
+        assert_eq!(start.to_rfc3339(), "2026-02-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2026-07-16T00:00:00+00:00");
+    }
+}
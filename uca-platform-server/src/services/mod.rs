@@ -0,0 +1,24 @@
+//! 业务逻辑层：封装校验与数据库操作，路由处理函数只负责会话解析与响应序列化。
+
+pub mod record;
+pub mod review;
+pub mod student;
+
+pub use record::{DbRecordService, NewContestRecord, RecordService};
+pub use review::{DbReviewService, ReviewDecision, ReviewOutcome, ReviewService};
+pub use student::{DbStudentService, StudentInput, StudentService};
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use sea_orm::{Database, DatabaseConnection};
+    use sea_orm_migration::MigratorTrait;
+
+    use crate::migration::Migrator;
+
+    /// 创建已执行迁移的内存 SQLite 数据库。
+    pub async fn memory_db() -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.expect("connect sqlite");
+        Migrator::up(&db, None).await.expect("migrate");
+        db
+    }
+}
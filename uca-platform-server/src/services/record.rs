@@ -0,0 +1,596 @@
+//! 竞赛记录提交业务逻辑。
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, TransactionTrait};
+use uuid::Uuid;
+
+use crate::{
+    entities::{contest_records, form_field_values, form_fields, students, FormField},
+    error::AppError,
+    semesters::{contest_semester_date, semester_for_date},
+    services::review::STATUS_SUBMITTED,
+};
+
+/// 新竞赛记录。
+#[derive(Debug, Clone, Default)]
+pub struct NewContestRecord {
+    /// 竞赛名称。
+    pub contest_name: String,
+    /// 竞赛级别。
+    pub contest_level: Option<String>,
+    /// 竞赛角色。
+    pub contest_role: Option<String>,
+    /// 竞赛年份。
+    pub contest_year: Option<i32>,
+    /// 竞赛类型（A/B）。
+    pub contest_category: Option<String>,
+    /// 获奖等级。
+    pub award_level: String,
+    /// 获奖时间（ISO 8601 日期或时间）。
+    pub award_date: Option<String>,
+    /// 自评学时。
+    pub self_hours: i32,
+    /// 提交时计算的推荐学时。
+    pub recommended_hours: Option<i32>,
+    /// 自定义字段。
+    pub custom_fields: HashMap<String, String>,
+}
+
+/// 记录服务。
+#[async_trait::async_trait]
+pub trait RecordService {
+    /// 为学生创建竞赛记录（含自定义字段）。
+    async fn create_contest_record(
+        &self,
+        student: &students::Model,
+        input: NewContestRecord,
+    ) -> Result<contest_records::Model, AppError>;
+}
+
+/// 基于数据库连接的记录服务。
+pub struct DbRecordService<'a> {
+    db: &'a DatabaseConnection,
+}
+
+impl<'a> DbRecordService<'a> {
+    /// 创建记录服务。
+    pub fn new(db: &'a DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait::async_trait]
+impl RecordService for DbRecordService<'_> {
+    async fn create_contest_record(
+        &self,
+        student: &students::Model,
+        input: NewContestRecord,
+    ) -> Result<contest_records::Model, AppError> {
+        if input
+            .contest_level
+            .as_deref()
+            .map(|value| value.trim().is_empty())
+            .unwrap_or(true)
+        {
+            return Err(AppError::validation("contest_level required"));
+        }
+        if input
+            .contest_role
+            .as_deref()
+            .map(|value| value.trim().is_empty())
+            .unwrap_or(true)
+        {
+            return Err(AppError::validation("contest_role required"));
+        }
+        let form_fields = load_form_fields(self.db, "contest").await?;
+        validate_custom_fields(&form_fields, &input.custom_fields)?;
+        let award_date = parse_award_date(input.award_date.as_deref())?;
+
+        let now = Utc::now();
+        let semester_id = semester_for_date(self.db, contest_semester_date(award_date, now)).await?;
+        let model = contest_records::Model {
+            id: Uuid::new_v4(),
+            student_id: student.id,
+            contest_year: input.contest_year,
+            contest_category: input.contest_category.map(|value| value.to_uppercase()),
+            contest_name: input.contest_name,
+            contest_level: input.contest_level,
+            contest_role: input.contest_role,
+            award_level: input.award_level,
+            award_date,
+            self_hours: input.self_hours,
+            first_review_hours: None,
+            final_review_hours: None,
+            first_reviewer_id: None,
+            final_reviewer_id: None,
+            competition_id: None,
+            semester_id,
+            assigned_reviewer_id: None,
+            status: STATUS_SUBMITTED.to_string(),
+            rejection_reason: None,
+            version: 0,
+            recommended_hours: input.recommended_hours,
+            first_reviewed_at: None,
+            final_reviewed_at: None,
+            import_batch_id: None,
+            is_deleted: false,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        let active: contest_records::ActiveModel = model.clone().into();
+        contest_records::Entity::insert(active)
+            .exec_without_returning(&txn)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        insert_custom_fields(&txn, "contest", model.id, &form_fields, &input.custom_fields).await?;
+        txn.commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        Ok(model)
+    }
+}
+
+/// 解析获奖时间（RFC3339 或 YYYY-MM-DD）。
+pub fn parse_award_date(value: Option<&str>) -> Result<Option<chrono::DateTime<chrono::Utc>>, AppError> {
+    let Some(raw) = value else {
+        return Ok(None);
+    };
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(trimmed) {
+        return Ok(Some(dt.with_timezone(&Utc)));
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+        let dt = date.and_hms_opt(0, 0, 0).ok_or_else(|| AppError::validation("invalid award date"))?;
+        return Ok(Some(Utc.from_utc_datetime(&dt)));
+    }
+    Err(AppError::validation("invalid award date"))
+}
+
+/// 读取指定表单类型的自定义字段。
+pub async fn load_form_fields<C>(db: &C, form_type: &str) -> Result<Vec<form_fields::Model>, AppError>
+where
+    C: ConnectionTrait,
+{
+    FormField::find()
+        .filter(form_fields::Column::FormType.eq(form_type))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))
+}
+
+/// 支持的自定义字段类型。
+pub const FIELD_TYPES: [&str; 5] = ["text", "number", "date", "select", "radio"];
+
+/// 需要配置可选值的字段类型。
+pub const OPTION_FIELD_TYPES: [&str; 2] = ["select", "radio"];
+
+/// 规范化字段可选值：select/radio 必须提供非空且不重复的选项，其他类型不允许设置。
+pub fn normalize_field_options(
+    field_type: &str,
+    options: Option<&[String]>,
+) -> Result<Option<String>, AppError> {
+    let options: Vec<String> = options
+        .unwrap_or_default()
+        .iter()
+        .map(|option| option.trim().to_string())
+        .filter(|option| !option.is_empty())
+        .collect();
+    if !OPTION_FIELD_TYPES.contains(&field_type) {
+        if !options.is_empty() {
+            return Err(AppError::validation("options are only allowed for select/radio fields"));
+        }
+        return Ok(None);
+    }
+    if options.is_empty() {
+        return Err(AppError::validation("select/radio fields require options"));
+    }
+    let mut seen = std::collections::HashSet::new();
+    if !options.iter().all(|option| seen.insert(option.as_str())) {
+        return Err(AppError::validation("duplicate field option"));
+    }
+    serde_json::to_string(&options)
+        .map(Some)
+        .map_err(|_| AppError::internal("failed to serialize field options"))
+}
+
+/// 读取字段可选值。
+pub fn field_options(field: &form_fields::Model) -> Option<Vec<String>> {
+    field
+        .options
+        .as_deref()
+        .and_then(|value| serde_json::from_str(value).ok())
+}
+
+/// 自定义字段取值的默认最大字符数。
+pub const DEFAULT_FIELD_MAX_LENGTH: usize = 500;
+
+/// 校验字段约束配置：数值范围仅用于 number 字段，最大长度须为正数。
+pub fn validate_field_constraints(
+    field_type: &str,
+    min_value: Option<i32>,
+    max_value: Option<i32>,
+    max_length: Option<i32>,
+) -> Result<(), AppError> {
+    if field_type != "number" && (min_value.is_some() || max_value.is_some()) {
+        return Err(AppError::validation("min_value/max_value are only allowed for number fields"));
+    }
+    if let (Some(min), Some(max)) = (min_value, max_value) {
+        if min > max {
+            return Err(AppError::validation("min_value must not exceed max_value"));
+        }
+    }
+    if max_length.is_some_and(|value| value <= 0) {
+        return Err(AppError::validation("max_length must be positive"));
+    }
+    Ok(())
+}
+
+/// 校验字段定义（类型、可选值与约束），返回规范化后的可选值 JSON。
+pub fn normalize_field_definition(
+    field_type: &str,
+    options: Option<&[String]>,
+    min_value: Option<i32>,
+    max_value: Option<i32>,
+    max_length: Option<i32>,
+) -> Result<Option<String>, AppError> {
+    if !FIELD_TYPES.contains(&field_type) {
+        return Err(AppError::validation(&format!("unsupported field_type: {field_type}")));
+    }
+    let options = normalize_field_options(field_type, options)?;
+    validate_field_constraints(field_type, min_value, max_value, max_length)?;
+    Ok(options)
+}
+
+/// 按字段类型校验单个非空取值，返回错误说明。
+pub fn check_field_value(field: &form_fields::Model, value: &str) -> Result<(), String> {
+    let max_length = field
+        .max_length
+        .and_then(|value| usize::try_from(value).ok())
+        .unwrap_or(DEFAULT_FIELD_MAX_LENGTH);
+    if value.chars().count() > max_length {
+        return Err(format!("must be at most {max_length} characters"));
+    }
+    match field.field_type.as_str() {
+        "number" => {
+            let number = value
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .ok_or_else(|| "must be a number".to_string())?;
+            if let Some(min) = field.min_value.filter(|min| number < f64::from(*min)) {
+                return Err(format!("must be at least {min}"));
+            }
+            if let Some(max) = field.max_value.filter(|max| number > f64::from(*max)) {
+                return Err(format!("must be at most {max}"));
+            }
+        }
+        "date" => {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| "must be a date like 2025-09-01".to_string())?;
+        }
+        field_type if OPTION_FIELD_TYPES.contains(&field_type) => {
+            let options = field_options(field).unwrap_or_default();
+            if !options.iter().any(|option| option == value) {
+                return Err("must be one of the options".to_string());
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// 校验自定义字段：必填字段不可为空，不允许未定义字段，取值须符合字段类型；按字段 key 返回错误。
+pub fn validate_custom_fields(
+    fields: &[form_fields::Model],
+    payload: &HashMap<String, String>,
+) -> Result<(), AppError> {
+    let mut field_map = HashMap::new();
+    for field in fields {
+        field_map.insert(field.field_key.as_str(), field);
+    }
+
+    let mut errors = BTreeMap::new();
+    for field in fields {
+        if field.required {
+            let value = payload.get(&field.field_key);
+            if value.is_none() || value.is_some_and(|val| val.trim().is_empty()) {
+                errors.insert(field.field_key.clone(), "is required".to_string());
+            }
+        }
+    }
+
+    for (key, value) in payload {
+        let Some(field) = field_map.get(key.as_str()) else {
+            errors.insert(key.clone(), "unknown custom field".to_string());
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        if let Err(message) = check_field_value(field, value) {
+            errors.insert(key.clone(), message);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::fields(errors))
+    }
+}
+
+async fn insert_custom_fields<C>(
+    db: &C,
+    record_type: &str,
+    record_id: Uuid,
+    fields: &[form_fields::Model],
+    payload: &HashMap<String, String>,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut field_map = HashMap::new();
+    for field in fields {
+        field_map.insert(field.field_key.as_str(), field);
+    }
+
+    for (key, value) in payload {
+        if value.trim().is_empty() {
+            continue;
+        }
+        if let Some(field) = field_map.get(key.as_str()) {
+            let value_model = form_field_values::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                record_type: Set(record_type.to_string()),
+                record_id: Set(record_id),
+                field_key: Set(field.field_key.clone()),
+                value: Set(value.to_string()),
+                created_at: Set(Utc::now()),
+            };
+            form_field_values::Entity::insert(value_model)
+                .exec_without_returning(db)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_support::memory_db;
+
+    #[test]
+    fn validate_custom_fields_rejects_missing_required_and_unknown() {
+        let fields = vec![
+            form_fields::Model {
+                id: Uuid::new_v4(),
+                form_type: "contest".to_string(),
+                field_key: "location".to_string(),
+                label: "地点".to_string(),
+                field_type: "text".to_string(),
+                required: true,
+                order_index: 1,
+                options: None,
+                min_value: None,
+                max_value: None,
+                max_length: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            form_fields::Model {
+                id: Uuid::new_v4(),
+                form_type: "contest".to_string(),
+                field_key: "note".to_string(),
+                label: "备注".to_string(),
+                field_type: "text".to_string(),
+                required: false,
+                order_index: 2,
+                options: None,
+                min_value: None,
+                max_value: None,
+                max_length: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+        ];
+
+        let empty_payload = HashMap::new();
+        assert!(validate_custom_fields(&fields, &empty_payload).is_err());
+
+        let mut unknown_payload = HashMap::new();
+        unknown_payload.insert("unknown".to_string(), "value".to_string());
+        assert!(validate_custom_fields(&fields, &unknown_payload).is_err());
+
+        let mut ok_payload = HashMap::new();
+        ok_payload.insert("location".to_string(), "校内".to_string());
+        assert!(validate_custom_fields(&fields, &ok_payload).is_ok());
+    }
+
+    #[test]
+    fn validate_custom_fields_enforces_select_options() {
+        let options = vec!["校内".to_string(), "校外".to_string()];
+        let field = form_fields::Model {
+            id: Uuid::new_v4(),
+            form_type: "contest".to_string(),
+            field_key: "location".to_string(),
+            label: "地点".to_string(),
+            field_type: "select".to_string(),
+            required: false,
+            order_index: 1,
+            options: normalize_field_options("select", Some(&options)).unwrap(),
+            min_value: None,
+            max_value: None,
+            max_length: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let fields = vec![field];
+
+        let mut payload = HashMap::new();
+        payload.insert("location".to_string(), "校外".to_string());
+        assert!(validate_custom_fields(&fields, &payload).is_ok());
+        payload.insert("location".to_string(), "线上".to_string());
+        assert!(validate_custom_fields(&fields, &payload).is_err());
+        payload.insert("location".to_string(), " ".to_string());
+        assert!(validate_custom_fields(&fields, &payload).is_ok());
+    }
+
+    fn typed_field(field_type: &str) -> form_fields::Model {
+        form_fields::Model {
+            id: Uuid::new_v4(),
+            form_type: "contest".to_string(),
+            field_key: "value".to_string(),
+            label: "取值".to_string(),
+            field_type: field_type.to_string(),
+            required: false,
+            order_index: 1,
+            options: None,
+            min_value: None,
+            max_value: None,
+            max_length: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn check_field_value_enforces_type_semantics() {
+        let mut number = typed_field("number");
+        number.min_value = Some(0);
+        number.max_value = Some(10);
+        assert!(check_field_value(&number, "3.5").is_ok());
+        assert!(check_field_value(&number, "abc").is_err());
+        assert!(check_field_value(&number, "-1").is_err());
+        assert!(check_field_value(&number, "11").is_err());
+        assert!(check_field_value(&number, "NaN").is_err());
+
+        let date = typed_field("date");
+        assert!(check_field_value(&date, "2025-09-01").is_ok());
+        assert!(check_field_value(&date, "2025/09/01").is_err());
+
+        let mut text = typed_field("text");
+        assert!(check_field_value(&text, &"长".repeat(DEFAULT_FIELD_MAX_LENGTH)).is_ok());
+        assert!(check_field_value(&text, &"长".repeat(DEFAULT_FIELD_MAX_LENGTH + 1)).is_err());
+        text.max_length = Some(4);
+        assert!(check_field_value(&text, "校内操场").is_ok());
+        assert!(check_field_value(&text, "校内大操场").is_err());
+    }
+
+    #[test]
+    fn validate_custom_fields_reports_errors_by_field_key() {
+        let mut required = typed_field("text");
+        required.field_key = "location".to_string();
+        required.required = true;
+        let fields = vec![required, typed_field("number")];
+        let mut payload = HashMap::new();
+        payload.insert("value".to_string(), "many".to_string());
+        payload.insert("extra".to_string(), "x".to_string());
+        match validate_custom_fields(&fields, &payload) {
+            Err(AppError::FieldValidation(errors)) => {
+                assert_eq!(errors.len(), 3);
+                assert_eq!(errors["location"], "is required");
+                assert_eq!(errors["value"], "must be a number");
+                assert_eq!(errors["extra"], "unknown custom field");
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn validate_field_constraints_rejects_invalid_ranges() {
+        assert!(validate_field_constraints("number", Some(0), Some(10), None).is_ok());
+        assert!(validate_field_constraints("number", Some(10), Some(0), None).is_err());
+        assert!(validate_field_constraints("text", Some(0), None, None).is_err());
+        assert!(validate_field_constraints("text", None, None, Some(0)).is_err());
+        assert!(validate_field_constraints("text", None, None, Some(20)).is_ok());
+    }
+
+    #[test]
+    fn normalize_field_options_matches_field_type() {
+        let options = vec![" A ".to_string(), "B".to_string(), "".to_string()];
+        assert_eq!(
+            normalize_field_options("radio", Some(&options)).unwrap().as_deref(),
+            Some(r#"["A","B"]"#)
+        );
+        assert!(normalize_field_options("select", None).is_err());
+        assert!(normalize_field_options("select", Some(&["A".to_string(), "A".to_string()])).is_err());
+        assert!(normalize_field_options("text", Some(&options)).is_err());
+        assert_eq!(normalize_field_options("text", None).unwrap(), None);
+    }
+
+    #[test]
+    fn normalize_field_definition_rejects_unknown_types() {
+        assert!(normalize_field_definition("date", None, None, None, Some(10)).is_ok());
+        assert!(normalize_field_definition("textarea", None, None, None, None).is_err());
+        assert!(normalize_field_definition(" text", None, None, None, None).is_err());
+        assert!(normalize_field_definition("radio", None, None, None, None).is_err());
+        assert!(normalize_field_definition("number", None, Some(5), Some(1), None).is_err());
+    }
+
+    #[test]
+    fn parse_award_date_accepts_date_and_rfc3339() {
+        assert!(parse_award_date(None).unwrap().is_none());
+        assert!(parse_award_date(Some("2024-05-01")).unwrap().is_some());
+        assert!(parse_award_date(Some("2024-05-01T08:00:00+08:00")).unwrap().is_some());
+        assert!(parse_award_date(Some("05/01/2024")).is_err());
+    }
+
+    #[tokio::test]
+    async fn create_contest_record_requires_level_and_role() {
+        let db = memory_db().await;
+        let now = Utc::now();
+        let student = students::Model {
+            id: Uuid::new_v4(),
+            student_no: "2023001".to_string(),
+            name: "张三".to_string(),
+            gender: "男".to_string(),
+            department: "信息学院".to_string(),
+            major: "软件工程".to_string(),
+            class_name: "软工1班".to_string(),
+            phone: "13800000000".to_string(),
+            import_batch_id: None,
+            is_deleted: false,
+            created_at: now,
+            updated_at: now,
+        };
+        let students_active: students::ActiveModel = student.clone().into();
+        students::Entity::insert(students_active)
+            .exec_without_returning(&db)
+            .await
+            .expect("insert student");
+
+        let service = DbRecordService::new(&db);
+        let input = NewContestRecord {
+            contest_name: "数学建模".to_string(),
+            contest_category: Some("a".to_string()),
+            award_level: "一等奖".to_string(),
+            self_hours: 4,
+            ..NewContestRecord::default()
+        };
+        assert!(service.create_contest_record(&student, input.clone()).await.is_err());
+
+        let input = NewContestRecord {
+            contest_level: Some("国家级".to_string()),
+            contest_role: Some("负责人".to_string()),
+            ..input
+        };
+        let model = service.create_contest_record(&student, input).await.expect("create");
+        assert_eq!(model.status, STATUS_SUBMITTED);
+        assert_eq!(model.contest_category.as_deref(), Some("A"));
+    }
+}
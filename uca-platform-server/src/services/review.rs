@@ -0,0 +1,651 @@
+//! 记录审核业务逻辑。
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, JoinType, QueryFilter,
+    QuerySelect, RelationTrait, Select, Set, TransactionTrait,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    access::department_scope,
+    attachment_requirements::{ensure_required_evidence, records_missing_evidence},
+    audit::record_audit,
+    delegations::{active_delegation, DELEGATE_ROLES},
+    entities::{contest_records, students, users, volunteer_records, ContestRecord, VolunteerRecord},
+    error::AppError,
+};
+
+pub const STATUS_SUBMITTED: &str = "submitted";
+pub const STATUS_FIRST_REVIEWED: &str = "first_reviewed";
+pub const STATUS_FINAL_REVIEWED: &str = "final_reviewed";
+pub const STATUS_REJECTED: &str = "rejected";
+
+pub const REVIEW_STAGE_FIRST: &str = "first";
+pub const REVIEW_STAGE_FINAL: &str = "final";
+
+/// 审核决定。
+#[derive(Debug, Clone)]
+pub struct ReviewDecision {
+    /// 审核阶段：first/final。
+    pub stage: String,
+    /// 审核学时。
+    pub hours: i32,
+    /// 状态：approved/rejected。
+    pub status: String,
+    /// 不通过原因。
+    pub rejection_reason: Option<String>,
+}
+
+/// 批量审核中单条记录的结果；`status` 为空表示记录不存在或已被他人修改。
+#[derive(Debug, Clone)]
+pub struct ReviewOutcome {
+    /// 记录 ID。
+    pub record_id: Uuid,
+    /// 审核后状态。
+    pub status: Option<String>,
+    /// 是否因版本号变化未写入。
+    pub conflict: bool,
+    /// 缺少要求的佐证附件而未通过时的说明。
+    pub missing_evidence: Option<String>,
+}
+
+/// 审核权限来源；通过审核委托获得权限时记录委托人。
+#[derive(Debug, Clone, Default)]
+pub struct ReviewAuthority {
+    /// 委托人；本人角色即可审核时为空。
+    pub delegator: Option<users::Model>,
+}
+
+impl ReviewAuthority {
+    /// 决定数据范围的用户：代为审核时按委托人的院系范围。
+    pub fn scope_user<'a>(&'a self, reviewer: &'a users::Model) -> &'a users::Model {
+        self.delegator.as_ref().unwrap_or(reviewer)
+    }
+
+    /// 委托人 ID。
+    pub fn delegator_id(&self) -> Option<Uuid> {
+        self.delegator.as_ref().map(|delegator| delegator.id)
+    }
+}
+
+/// 审核服务。
+#[async_trait::async_trait]
+pub trait ReviewService {
+    /// 审核单条竞赛记录；记录版本号与 `expected_version` 不一致时返回冲突错误。
+    async fn review_contest_record(
+        &self,
+        reviewer: &users::Model,
+        record_id: Uuid,
+        expected_version: i32,
+        decision: &ReviewDecision,
+    ) -> Result<contest_records::Model, AppError>;
+
+    /// 在单个事务内批量审核竞赛记录。
+    async fn batch_review_contest_records(
+        &self,
+        reviewer: &users::Model,
+        record_ids: &[Uuid],
+        decision: &ReviewDecision,
+    ) -> Result<Vec<ReviewOutcome>, AppError>;
+
+    /// 在单个事务内批量审核志愿服务记录。
+    async fn batch_review_volunteer_records(
+        &self,
+        reviewer: &users::Model,
+        record_ids: &[Uuid],
+        decision: &ReviewDecision,
+    ) -> Result<Vec<ReviewOutcome>, AppError>;
+}
+
+/// 基于数据库连接的审核服务。
+pub struct DbReviewService<'a> {
+    db: &'a DatabaseConnection,
+}
+
+impl<'a> DbReviewService<'a> {
+    /// 创建审核服务。
+    pub fn new(db: &'a DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait::async_trait]
+impl ReviewService for DbReviewService<'_> {
+    async fn review_contest_record(
+        &self,
+        reviewer: &users::Model,
+        record_id: Uuid,
+        expected_version: i32,
+        decision: &ReviewDecision,
+    ) -> Result<contest_records::Model, AppError> {
+        let authority = ensure_review_permission(self.db, reviewer, &decision.stage).await?;
+        let record = scoped_contest_records(authority.scope_user(reviewer))
+            .filter(contest_records::Column::Id.eq(record_id))
+            .filter(contest_records::Column::IsDeleted.eq(false))
+            .one(self.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .ok_or_else(|| AppError::not_found("record not found"))?;
+        if record.version != expected_version {
+            return Err(version_conflict(&record));
+        }
+        if decision.status != "rejected" {
+            ensure_required_evidence(self.db, "contest", record.id).await?;
+        }
+
+        let mut active: contest_records::ActiveModel = record.into();
+        apply_contest_review(&mut active, reviewer, decision)?;
+        let updated = update_contest_if_current(self.db, active, expected_version).await?;
+        let current = ContestRecord::find_by_id(record_id)
+            .one(self.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .ok_or_else(|| AppError::not_found("record not found"))?;
+        if !updated {
+            return Err(version_conflict(&current));
+        }
+        audit_delegated_review(self.db, reviewer, &authority, "contest_record", record_id, decision).await?;
+        Ok(current)
+    }
+
+    async fn batch_review_contest_records(
+        &self,
+        reviewer: &users::Model,
+        record_ids: &[Uuid],
+        decision: &ReviewDecision,
+    ) -> Result<Vec<ReviewOutcome>, AppError> {
+        let authority = ensure_review_permission(self.db, reviewer, &decision.stage).await?;
+        let records = scoped_contest_records(authority.scope_user(reviewer))
+            .filter(contest_records::Column::Id.is_in(record_ids.to_vec()))
+            .filter(contest_records::Column::IsDeleted.eq(false))
+            .all(self.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        let mut record_map: HashMap<Uuid, contest_records::Model> =
+            records.into_iter().map(|record| (record.id, record)).collect();
+        let missing = missing_evidence_for(self.db, "contest", &record_map, decision).await?;
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        let mut outcomes = Vec::with_capacity(record_ids.len());
+        for record_id in record_ids {
+            let Some(record) = record_map.remove(record_id) else {
+                outcomes.push(ReviewOutcome::missing(*record_id));
+                continue;
+            };
+            if let Some(message) = missing.get(record_id) {
+                outcomes.push(ReviewOutcome::blocked(*record_id, message));
+                continue;
+            }
+            let version = record.version;
+            let mut active: contest_records::ActiveModel = record.into();
+            apply_contest_review(&mut active, reviewer, decision)?;
+            let status = active.status.clone().unwrap();
+            if update_contest_if_current(&txn, active, version).await? {
+                audit_delegated_review(&txn, reviewer, &authority, "contest_record", *record_id, decision).await?;
+                outcomes.push(ReviewOutcome::updated(*record_id, status));
+            } else {
+                outcomes.push(ReviewOutcome::conflicted(*record_id));
+            }
+        }
+        txn.commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        Ok(outcomes)
+    }
+
+    async fn batch_review_volunteer_records(
+        &self,
+        reviewer: &users::Model,
+        record_ids: &[Uuid],
+        decision: &ReviewDecision,
+    ) -> Result<Vec<ReviewOutcome>, AppError> {
+        let authority = ensure_review_permission(self.db, reviewer, &decision.stage).await?;
+        let records = scoped_volunteer_records(authority.scope_user(reviewer))
+            .filter(volunteer_records::Column::Id.is_in(record_ids.to_vec()))
+            .filter(volunteer_records::Column::IsDeleted.eq(false))
+            .all(self.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        let mut record_map: HashMap<Uuid, volunteer_records::Model> =
+            records.into_iter().map(|record| (record.id, record)).collect();
+        let missing = missing_evidence_for(self.db, "volunteer", &record_map, decision).await?;
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        let now = Utc::now();
+        let mut outcomes = Vec::with_capacity(record_ids.len());
+        for record_id in record_ids {
+            let Some(record) = record_map.remove(record_id) else {
+                outcomes.push(ReviewOutcome::missing(*record_id));
+                continue;
+            };
+            if let Some(message) = missing.get(record_id) {
+                outcomes.push(ReviewOutcome::blocked(*record_id, message));
+                continue;
+            }
+            let version = record.version;
+            let mut active: volunteer_records::ActiveModel = record.into();
+            apply_review_update(decision, &mut active.status, &mut active.rejection_reason)?;
+            if decision.stage == REVIEW_STAGE_FIRST {
+                active.first_review_hours = Set(Some(decision.hours));
+            } else {
+                active.final_review_hours = Set(Some(decision.hours));
+            }
+            active.updated_at = Set(now);
+            active.version = Set(version + 1);
+            let status = active.status.clone().unwrap();
+            let result = VolunteerRecord::update_many()
+                .set(active)
+                .filter(volunteer_records::Column::Id.eq(*record_id))
+                .filter(volunteer_records::Column::Version.eq(version))
+                .exec(&txn)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+            if result.rows_affected == 1 {
+                audit_delegated_review(&txn, reviewer, &authority, "volunteer_record", *record_id, decision).await?;
+                outcomes.push(ReviewOutcome::updated(*record_id, status));
+            } else {
+                outcomes.push(ReviewOutcome::conflicted(*record_id));
+            }
+        }
+        txn.commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        Ok(outcomes)
+    }
+}
+
+impl ReviewOutcome {
+    fn updated(record_id: Uuid, status: String) -> Self {
+        Self { record_id, status: Some(status), conflict: false, missing_evidence: None }
+    }
+
+    fn missing(record_id: Uuid) -> Self {
+        Self { record_id, status: None, conflict: false, missing_evidence: None }
+    }
+
+    fn conflicted(record_id: Uuid) -> Self {
+        Self { record_id, status: None, conflict: true, missing_evidence: None }
+    }
+
+    fn blocked(record_id: Uuid, message: &str) -> Self {
+        Self { record_id, status: None, conflict: false, missing_evidence: Some(message.to_string()) }
+    }
+}
+
+/// 审核通过时找出缺少佐证附件的记录；不通过的决定不受附件要求限制。
+async fn missing_evidence_for<C, M>(
+    db: &C,
+    record_type: &str,
+    records: &HashMap<Uuid, M>,
+    decision: &ReviewDecision,
+) -> Result<HashMap<Uuid, String>, AppError>
+where
+    C: ConnectionTrait,
+{
+    if decision.status == "rejected" {
+        return Ok(HashMap::new());
+    }
+    let record_ids: Vec<Uuid> = records.keys().copied().collect();
+    records_missing_evidence(db, record_type, &record_ids).await
+}
+
+/// 通过委托代为审核时写入审计日志，记录实际操作人与委托人。
+async fn audit_delegated_review<C>(
+    db: &C,
+    reviewer: &users::Model,
+    authority: &ReviewAuthority,
+    target_type: &str,
+    record_id: Uuid,
+    decision: &ReviewDecision,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    let Some(delegator_id) = authority.delegator_id() else {
+        return Ok(());
+    };
+    record_audit(
+        db,
+        Some(reviewer.id),
+        "record_review_delegated",
+        target_type,
+        Some(&record_id.to_string()),
+        Some(serde_json::json!({
+            "delegator_id": delegator_id,
+            "stage": decision.stage,
+            "status": decision.status,
+            "hours": decision.hours,
+        })),
+    )
+    .await
+}
+
+/// 仅在版本号仍为 `version` 时写入竞赛记录并递增版本号，返回是否写入成功。
+async fn update_contest_if_current<C>(
+    db: &C,
+    mut active: contest_records::ActiveModel,
+    version: i32,
+) -> Result<bool, AppError>
+where
+    C: ConnectionTrait,
+{
+    let record_id = active.id.clone().unwrap();
+    active.version = Set(version + 1);
+    let result = ContestRecord::update_many()
+        .set(active)
+        .filter(contest_records::Column::Id.eq(record_id))
+        .filter(contest_records::Column::Version.eq(version))
+        .exec(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(result.rows_affected == 1)
+}
+
+/// 版本冲突错误，附带记录当前状态供客户端刷新。
+fn version_conflict(current: &impl Serialize) -> AppError {
+    AppError::conflict("record was modified by another reviewer", serde_json::to_value(current).ok())
+}
+
+/// 按审核人员的院系范围限定竞赛记录查询；范围外的记录视为不存在。
+fn scoped_contest_records(reviewer: &users::Model) -> Select<ContestRecord> {
+    let finder = ContestRecord::find();
+    match department_scope(reviewer) {
+        Some(department) => finder
+            .join(JoinType::InnerJoin, contest_records::Relation::Student.def())
+            .filter(students::Column::Department.eq(department)),
+        None => finder,
+    }
+}
+
+/// 按审核人员的院系范围限定志愿服务记录查询。
+fn scoped_volunteer_records(reviewer: &users::Model) -> Select<VolunteerRecord> {
+    let finder = VolunteerRecord::find();
+    match department_scope(reviewer) {
+        Some(department) => finder
+            .join(JoinType::InnerJoin, volunteer_records::Relation::Student.def())
+            .filter(students::Column::Department.eq(department)),
+        None => finder,
+    }
+}
+
+/// 角色本身是否可处理指定审核阶段。
+pub fn role_can_review(role: &str, stage: &str) -> bool {
+    (stage == REVIEW_STAGE_FIRST && (role == "reviewer" || role == "admin"))
+        || (stage == REVIEW_STAGE_FINAL && (role == "teacher" || role == "admin"))
+}
+
+/// 校验审核人员是否可处理指定阶段；角色本身不具备权限时查找生效中的审核委托。
+pub async fn ensure_review_permission<C>(
+    db: &C,
+    user: &users::Model,
+    stage: &str,
+) -> Result<ReviewAuthority, AppError>
+where
+    C: ConnectionTrait,
+{
+    if role_can_review(&user.role, stage) {
+        return Ok(ReviewAuthority::default());
+    }
+    if !DELEGATE_ROLES.contains(&user.role.as_str()) {
+        return Err(AppError::auth("forbidden"));
+    }
+    match active_delegation(db, user.id, stage).await? {
+        Some((_, delegator)) => Ok(ReviewAuthority { delegator: Some(delegator) }),
+        None => Err(AppError::auth("forbidden")),
+    }
+}
+
+fn apply_contest_review(
+    active: &mut contest_records::ActiveModel,
+    reviewer: &users::Model,
+    decision: &ReviewDecision,
+) -> Result<(), AppError> {
+    apply_review_update(decision, &mut active.status, &mut active.rejection_reason)?;
+    let now = Utc::now();
+    if decision.stage == REVIEW_STAGE_FIRST {
+        active.first_review_hours = Set(Some(decision.hours));
+        active.first_reviewer_id = Set(Some(reviewer.id));
+        active.first_reviewed_at = Set(Some(now));
+    } else {
+        active.final_review_hours = Set(Some(decision.hours));
+        active.final_reviewer_id = Set(Some(reviewer.id));
+        active.final_reviewed_at = Set(Some(now));
+    }
+    active.updated_at = Set(now);
+    Ok(())
+}
+
+/// 根据审核决定更新状态与不通过原因。
+pub fn apply_review_update(
+    payload: &ReviewDecision,
+    status: &mut sea_orm::ActiveValue<String>,
+    rejection_reason: &mut sea_orm::ActiveValue<Option<String>>,
+) -> Result<(), AppError> {
+    if payload.status == "rejected" {
+        *status = Set(STATUS_REJECTED.to_string());
+        *rejection_reason = Set(payload.rejection_reason.clone());
+        return Ok(());
+    }
+
+    if payload.stage == REVIEW_STAGE_FIRST {
+        *status = Set(STATUS_FIRST_REVIEWED.to_string());
+    } else if payload.stage == REVIEW_STAGE_FINAL {
+        *status = Set(STATUS_FINAL_REVIEWED.to_string());
+    } else {
+        return Err(AppError::bad_request("invalid review stage"));
+    }
+
+    *rejection_reason = Set(None);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{entities::review_delegations, services::test_support::memory_db};
+
+    fn reviewer(role: &str) -> users::Model {
+        users::Model {
+            id: Uuid::new_v4(),
+            username: "u1".to_string(),
+            display_name: "u1".to_string(),
+            role: role.to_string(),
+            email: None,
+            password_hash: None,
+            allow_password_login: false,
+            password_updated_at: None,
+            must_change_password: false,
+            is_active: true,
+            department: None,
+            notify_review_email: true,
+            locale: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn decision(stage: &str, status: &str, reason: Option<&str>) -> ReviewDecision {
+        ReviewDecision {
+            stage: stage.to_string(),
+            hours: 2,
+            status: status.to_string(),
+            rejection_reason: reason.map(|value| value.to_string()),
+        }
+    }
+
+    #[test]
+    fn apply_review_update_rejects() {
+        let payload = decision(REVIEW_STAGE_FIRST, "rejected", Some("no proof"));
+        let mut status = sea_orm::ActiveValue::set("".to_string());
+        let mut reason = sea_orm::ActiveValue::set(None);
+        apply_review_update(&payload, &mut status, &mut reason).expect("apply");
+        assert_eq!(status.unwrap(), STATUS_REJECTED.to_string());
+        assert_eq!(reason.unwrap(), Some("no proof".to_string()));
+    }
+
+    #[test]
+    fn apply_review_update_first_pass() {
+        let payload = decision(REVIEW_STAGE_FIRST, "approved", None);
+        let mut status = sea_orm::ActiveValue::set("".to_string());
+        let mut reason = sea_orm::ActiveValue::set(None);
+        apply_review_update(&payload, &mut status, &mut reason).expect("apply");
+        assert_eq!(status.unwrap(), STATUS_FIRST_REVIEWED.to_string());
+        assert_eq!(reason.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn ensure_review_permission_allows_expected_roles() {
+        let db = memory_db().await;
+        let user = reviewer("reviewer");
+        ensure_review_permission(&db, &user, REVIEW_STAGE_FIRST).await.expect("reviewer allowed");
+        assert!(ensure_review_permission(&db, &user, REVIEW_STAGE_FINAL).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn active_delegation_grants_delegator_stage() {
+        let db = memory_db().await;
+        let teacher = reviewer("teacher");
+        users::Entity::insert(users::ActiveModel::from(teacher.clone()))
+            .exec_without_returning(&db)
+            .await
+            .expect("insert teacher");
+        let mut delegate = reviewer("reviewer");
+        delegate.username = "u2".to_string();
+        let now = Utc::now();
+        let delegation = review_delegations::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            delegator_id: Set(teacher.id),
+            delegate_id: Set(delegate.id),
+            stage: Set(REVIEW_STAGE_FINAL.to_string()),
+            starts_at: Set(now - chrono::Duration::hours(1)),
+            ends_at: Set(now + chrono::Duration::days(7)),
+            revoked_at: Set(None),
+            created_by: Set(Some(teacher.id)),
+            created_at: Set(now),
+        };
+        review_delegations::Entity::insert(delegation)
+            .exec_without_returning(&db)
+            .await
+            .expect("insert delegation");
+
+        let authority = ensure_review_permission(&db, &delegate, REVIEW_STAGE_FINAL)
+            .await
+            .expect("delegate allowed");
+        assert_eq!(authority.delegator_id(), Some(teacher.id));
+        let own = ensure_review_permission(&db, &delegate, REVIEW_STAGE_FIRST)
+            .await
+            .expect("own stage allowed");
+        assert!(own.delegator.is_none());
+        let mut other = reviewer("reviewer");
+        other.username = "u3".to_string();
+        assert!(ensure_review_permission(&db, &other, REVIEW_STAGE_FINAL).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn batch_review_updates_existing_and_reports_missing() {
+        let db = memory_db().await;
+        let now = Utc::now();
+        let student_id = Uuid::new_v4();
+        students::Entity::insert(students::ActiveModel {
+            id: Set(student_id),
+            student_no: Set("2023001".to_string()),
+            name: Set("张三".to_string()),
+            gender: Set("男".to_string()),
+            department: Set("信息学院".to_string()),
+            major: Set("软件工程".to_string()),
+            class_name: Set("软工1班".to_string()),
+            phone: Set("13800000000".to_string()),
+            import_batch_id: Set(None),
+            is_deleted: Set(false),
+            created_at: Set(now),
+            updated_at: Set(now),
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("insert student");
+        let record_id = Uuid::new_v4();
+        contest_records::Entity::insert(contest_records::ActiveModel {
+            id: Set(record_id),
+            student_id: Set(student_id),
+            contest_year: Set(None),
+            contest_category: Set(None),
+            contest_name: Set("数学建模".to_string()),
+            contest_level: Set(None),
+            contest_role: Set(None),
+            award_level: Set("一等奖".to_string()),
+            award_date: Set(None),
+            self_hours: Set(4),
+            first_review_hours: Set(None),
+            final_review_hours: Set(None),
+            first_reviewer_id: Set(None),
+            final_reviewer_id: Set(None),
+            competition_id: Set(None),
+            semester_id: Set(None),
+            assigned_reviewer_id: Set(None),
+            status: Set(STATUS_SUBMITTED.to_string()),
+            rejection_reason: Set(None),
+            version: Set(0),
+            recommended_hours: Set(None),
+            first_reviewed_at: Set(None),
+            final_reviewed_at: Set(None),
+            import_batch_id: Set(None),
+            is_deleted: Set(false),
+            created_at: Set(now),
+            updated_at: Set(now),
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("insert record");
+
+        let service = DbReviewService::new(&db);
+        let user = reviewer("reviewer");
+        let missing = Uuid::new_v4();
+        let outcomes = service
+            .batch_review_contest_records(&user, &[record_id, missing], &decision(REVIEW_STAGE_FIRST, "approved", None))
+            .await
+            .expect("batch review");
+        assert_eq!(outcomes[0].status.as_deref(), Some(STATUS_FIRST_REVIEWED));
+        assert!(outcomes[1].status.is_none());
+
+        let denied = service
+            .review_contest_record(&user, record_id, 1, &decision(REVIEW_STAGE_FINAL, "approved", None))
+            .await;
+        assert!(denied.is_err());
+
+        let stale = service
+            .review_contest_record(&user, record_id, 0, &decision(REVIEW_STAGE_FIRST, "rejected", None))
+            .await;
+        match stale {
+            Err(AppError::Conflict(_, Some(current))) => {
+                assert_eq!(current["version"], 1);
+                assert_eq!(current["status"], STATUS_FIRST_REVIEWED);
+            }
+            other => panic!("expected conflict, got {other:?}"),
+        }
+        let reviewed = service
+            .review_contest_record(&user, record_id, 1, &decision(REVIEW_STAGE_FIRST, "rejected", Some("材料不全")))
+            .await
+            .expect("review with current version");
+        assert_eq!((reviewed.status.as_str(), reviewed.version), (STATUS_REJECTED, 2));
+
+        let mut other_department = reviewer("reviewer");
+        other_department.department = Some("机械学院".to_string());
+        let outcomes = service
+            .batch_review_contest_records(&other_department, &[record_id], &decision(REVIEW_STAGE_FIRST, "rejected", None))
+            .await
+            .expect("scoped batch review");
+        assert!(outcomes[0].status.is_none());
+    }
+}
@@ -0,0 +1,333 @@
+//! 学生档案业务逻辑。
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    auth::hash_password,
+    entities::{students, users, Student, User},
+    error::AppError,
+    policy::load_student_password_rule,
+};
+
+/// 学生档案字段。
+#[derive(Debug, Clone)]
+pub struct StudentInput {
+    /// 姓名。
+    pub name: String,
+    /// 性别。
+    pub gender: String,
+    /// 院系。
+    pub department: String,
+    /// 专业。
+    pub major: String,
+    /// 班级。
+    pub class_name: String,
+    /// 手机号。
+    pub phone: String,
+}
+
+/// 学生服务。
+#[async_trait::async_trait]
+pub trait StudentService {
+    /// 读取学生账号关联的学生档案。
+    async fn profile_for_user(&self, user: &users::Model) -> Result<students::Model, AppError>;
+
+    /// 创建学生（已软删除的同学号档案会被恢复），同步学生账号，返回档案与密码登录开关。
+    async fn create_student(
+        &self,
+        student_no: &str,
+        input: &StudentInput,
+    ) -> Result<(students::Model, bool), AppError>;
+
+    /// 更新学生档案并同步账号显示名，返回档案与密码登录开关。
+    async fn update_student(
+        &self,
+        student_no: &str,
+        input: &StudentInput,
+    ) -> Result<(students::Model, bool), AppError>;
+}
+
+/// 基于数据库连接的学生服务。
+pub struct DbStudentService<'a> {
+    db: &'a DatabaseConnection,
+}
+
+impl<'a> DbStudentService<'a> {
+    /// 创建学生服务。
+    pub fn new(db: &'a DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait::async_trait]
+impl StudentService for DbStudentService<'_> {
+    async fn profile_for_user(&self, user: &users::Model) -> Result<students::Model, AppError> {
+        Student::find()
+            .filter(students::Column::StudentNo.eq(&user.username))
+            .filter(students::Column::IsDeleted.eq(false))
+            .one(self.db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .ok_or_else(|| AppError::not_found("student profile not linked to this account, please contact admin"))
+    }
+
+    async fn create_student(
+        &self,
+        student_no: &str,
+        input: &StudentInput,
+    ) -> Result<(students::Model, bool), AppError> {
+        // 档案与账号在同一事务中写入，账号同步失败时不会留下没有账号的学生档案。
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        let exists = Student::find()
+            .filter(students::Column::StudentNo.eq(student_no))
+            .one(&txn)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        if let Some(existing) = exists {
+            if !existing.is_deleted {
+                return Err(AppError::bad_request("student number exists"));
+            }
+            let mut active: students::ActiveModel = existing.into();
+            apply_student_input(&mut active, input);
+            active.is_deleted = Set(false);
+            let model = active
+                .update(&txn)
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+            upsert_student_user(&txn, student_no, &input.name, &input.phone, None).await?;
+            let allow_password_login = fetch_student_login_flag(&txn, student_no).await?;
+            txn.commit()
+                .await
+                .map_err(|err| AppError::Database(err.to_string()))?;
+            return Ok((model, allow_password_login));
+        }
+
+        let now = Utc::now();
+        let model = students::Model {
+            id: Uuid::new_v4(),
+            student_no: student_no.to_string(),
+            name: input.name.clone(),
+            gender: input.gender.clone(),
+            department: input.department.clone(),
+            major: input.major.clone(),
+            class_name: input.class_name.clone(),
+            phone: input.phone.clone(),
+            import_batch_id: None,
+            is_deleted: false,
+            created_at: now,
+            updated_at: now,
+        };
+        let active: students::ActiveModel = model.clone().into();
+        students::Entity::insert(active)
+            .exec_without_returning(&txn)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        upsert_student_user(&txn, student_no, &input.name, &input.phone, Some(false)).await?;
+        let allow_password_login = fetch_student_login_flag(&txn, student_no).await?;
+        txn.commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        Ok((model, allow_password_login))
+    }
+
+    async fn update_student(
+        &self,
+        student_no: &str,
+        input: &StudentInput,
+    ) -> Result<(students::Model, bool), AppError> {
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        let student = Student::find()
+            .filter(students::Column::StudentNo.eq(student_no))
+            .filter(students::Column::IsDeleted.eq(false))
+            .one(&txn)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .ok_or_else(|| AppError::not_found("student not found"))?;
+
+        let mut active: students::ActiveModel = student.into();
+        apply_student_input(&mut active, input);
+        let model = active
+            .update(&txn)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        upsert_student_user(&txn, student_no, &input.name, &input.phone, None).await?;
+        let allow_password_login = fetch_student_login_flag(&txn, student_no).await?;
+        txn.commit()
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        Ok((model, allow_password_login))
+    }
+}
+
+fn apply_student_input(active: &mut students::ActiveModel, input: &StudentInput) {
+    active.name = Set(input.name.clone());
+    active.gender = Set(input.gender.clone());
+    active.department = Set(input.department.clone());
+    active.major = Set(input.major.clone());
+    active.class_name = Set(input.class_name.clone());
+    active.phone = Set(input.phone.clone());
+    active.updated_at = Set(Utc::now());
+}
+
+/// 创建或同步学号对应的学生账号，初始密码按学生默认密码规则生成。
+pub(crate) async fn upsert_student_user<C>(
+    db: &C,
+    student_no: &str,
+    name: &str,
+    phone: &str,
+    allow_login: Option<bool>,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    let rule = load_student_password_rule(db).await?;
+    let now = Utc::now();
+    if let Some(existing) = User::find()
+        .filter(users::Column::Username.eq(student_no))
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+    {
+        let missing_password = existing.password_hash.is_none();
+        let mut active: users::ActiveModel = existing.into();
+        active.display_name = Set(name.to_string());
+        active.role = Set("student".to_string());
+        if missing_password {
+            active.password_hash = Set(Some(hash_password(&rule.build(student_no, phone)?)?));
+        }
+        if let Some(value) = allow_login {
+            active.allow_password_login = Set(value);
+            if value {
+                active.must_change_password = Set(true);
+            }
+        }
+        active.updated_at = Set(now);
+        active
+            .update(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        return Ok(());
+    }
+
+    let default_hash = hash_password(&rule.build(student_no, phone)?)?;
+    let model = users::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        username: Set(student_no.to_string()),
+        display_name: Set(name.to_string()),
+        role: Set("student".to_string()),
+        email: Set(None),
+        password_hash: Set(Some(default_hash)),
+        allow_password_login: Set(allow_login.unwrap_or(false)),
+        password_updated_at: Set(Some(now)),
+        must_change_password: Set(allow_login.unwrap_or(false)),
+        is_active: Set(true),
+        department: Set(None),
+        notify_review_email: Set(true),
+        locale: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+    users::Entity::insert(model)
+        .exec_without_returning(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(())
+}
+
+/// 读取学生账号是否允许密码登录。
+pub(crate) async fn fetch_student_login_flag<C>(db: &C, student_no: &str) -> Result<bool, AppError>
+where
+    C: ConnectionTrait,
+{
+    let record = User::find()
+        .filter(users::Column::Username.eq(student_no))
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(record.map(|item| item.allow_password_login).unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_support::memory_db;
+
+    fn input(name: &str) -> StudentInput {
+        StudentInput {
+            name: name.to_string(),
+            gender: "男".to_string(),
+            department: "信息学院".to_string(),
+            major: "软件工程".to_string(),
+            class_name: "软工1班".to_string(),
+            phone: "13800000000".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn create_and_update_student_sync_account() {
+        let db = memory_db().await;
+        let service = DbStudentService::new(&db);
+        let (created, allow_login) = service.create_student("2023001", &input("张三")).await.expect("create");
+        assert_eq!(created.student_no, "2023001");
+        assert!(!allow_login);
+        assert!(service.create_student("2023001", &input("张三")).await.is_err());
+
+        let (updated, _) = service.update_student("2023001", &input("李四")).await.expect("update");
+        assert_eq!(updated.name, "李四");
+        let account = User::find()
+            .filter(users::Column::Username.eq("2023001"))
+            .one(&db)
+            .await
+            .expect("query")
+            .expect("account");
+        assert_eq!(account.display_name, "李四");
+        assert_eq!(service.profile_for_user(&account).await.expect("profile").id, created.id);
+        assert!(service.update_student("2023999", &input("王五")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_account_sync_rolls_back_student() {
+        let db = memory_db().await;
+        let service = DbStudentService::new(&db);
+        service.create_student("2023001", &input("张三")).await.expect("create");
+        db.execute_unprepared(
+            "CREATE TRIGGER fail_user_write BEFORE INSERT ON users BEGIN SELECT RAISE(ABORT, 'injected'); END;",
+        )
+        .await
+        .expect("trigger");
+        db.execute_unprepared(
+            "CREATE TRIGGER fail_user_update BEFORE UPDATE ON users BEGIN SELECT RAISE(ABORT, 'injected'); END;",
+        )
+        .await
+        .expect("trigger");
+
+        assert!(service.create_student("2023002", &input("李四")).await.is_err());
+        let orphan = Student::find()
+            .filter(students::Column::StudentNo.eq("2023002"))
+            .one(&db)
+            .await
+            .expect("query");
+        assert!(orphan.is_none());
+
+        assert!(service.update_student("2023001", &input("王五")).await.is_err());
+        let student = Student::find()
+            .filter(students::Column::StudentNo.eq("2023001"))
+            .one(&db)
+            .await
+            .expect("query")
+            .expect("student");
+        assert_eq!(student.name, "张三");
+    }
+}
@@ -0,0 +1,142 @@
+//! 会话令牌缓存：缓存令牌哈希到用户 ID 的映射，减少每个请求的会话表查询。
+//!
+//! 只缓存会话本身（用户 ID 与过期时间），用户资料仍每次读取，角色与停用状态即时生效。
+//! 退出登录、重置凭证与归档删除会话时同步清除缓存；多实例部署时其他实例的缓存
+//! 最多保留 `SESSION_CACHE_TTL_SECONDS`。
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// 缓存条目上限，超出时先清理过期项，仍超出则清空。
+const MAX_ENTRIES: usize = 10_000;
+
+#[derive(Debug, Clone)]
+struct CachedSession {
+    user_id: Uuid,
+    session_expires_at: DateTime<Utc>,
+    cached_until: DateTime<Utc>,
+}
+
+/// 进程内会话缓存，TTL 为 0 时不缓存。
+#[derive(Debug)]
+pub struct SessionCache {
+    ttl_seconds: u64,
+    entries: Mutex<HashMap<String, CachedSession>>,
+}
+
+impl SessionCache {
+    /// 按缓存有效期（秒）创建。
+    pub fn new(ttl_seconds: u64) -> Self {
+        Self {
+            ttl_seconds,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 是否启用缓存。
+    pub fn enabled(&self) -> bool {
+        self.ttl_seconds > 0
+    }
+
+    /// 读取缓存的会话，返回用户 ID 与会话过期时间。
+    pub async fn get(&self, token_hash: &str) -> Option<(Uuid, DateTime<Utc>)> {
+        if !self.enabled() {
+            return None;
+        }
+        let now = Utc::now();
+        let mut entries = self.entries.lock().await;
+        match entries.get(token_hash) {
+            Some(entry) if entry.cached_until > now => Some((entry.user_id, entry.session_expires_at)),
+            Some(_) => {
+                entries.remove(token_hash);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// 写入会话；缓存时间不超过会话本身的过期时间。
+    pub async fn insert(&self, token_hash: String, user_id: Uuid, session_expires_at: DateTime<Utc>) {
+        if !self.enabled() {
+            return;
+        }
+        let now = Utc::now();
+        let cached_until = (now + Duration::seconds(self.ttl_seconds as i64)).min(session_expires_at);
+        let mut entries = self.entries.lock().await;
+        if entries.len() >= MAX_ENTRIES {
+            entries.retain(|_, entry| entry.cached_until > now);
+            if entries.len() >= MAX_ENTRIES {
+                entries.clear();
+            }
+        }
+        entries.insert(
+            token_hash,
+            CachedSession {
+                user_id,
+                session_expires_at,
+                cached_until,
+            },
+        );
+    }
+
+    /// 清除指定令牌的缓存（退出登录）。
+    pub async fn invalidate_token(&self, token_hash: &str) {
+        if self.enabled() {
+            self.entries.lock().await.remove(token_hash);
+        }
+    }
+
+    /// 清除指定用户全部会话的缓存（重置凭证、停用账号）。
+    pub async fn invalidate_users(&self, user_ids: &[Uuid]) {
+        if !self.enabled() || user_ids.is_empty() {
+            return;
+        }
+        self.entries
+            .lock()
+            .await
+            .retain(|_, entry| !user_ids.contains(&entry.user_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn disabled_cache_stores_nothing() {
+        let cache = SessionCache::new(0);
+        cache
+            .insert("hash".to_string(), Uuid::new_v4(), Utc::now() + Duration::hours(1))
+            .await;
+        assert!(cache.get("hash").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn invalidation_removes_token_and_user_entries() {
+        let cache = SessionCache::new(60);
+        let expires_at = Utc::now() + Duration::hours(1);
+        let user_id = Uuid::new_v4();
+        cache.insert("a".to_string(), user_id, expires_at).await;
+        cache.insert("b".to_string(), user_id, expires_at).await;
+        cache.insert("c".to_string(), Uuid::new_v4(), expires_at).await;
+        assert_eq!(cache.get("a").await, Some((user_id, expires_at)));
+
+        cache.invalidate_token("a").await;
+        assert!(cache.get("a").await.is_none());
+        cache.invalidate_users(&[user_id]).await;
+        assert!(cache.get("b").await.is_none());
+        assert!(cache.get("c").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn entries_never_outlive_the_session() {
+        let cache = SessionCache::new(600);
+        cache
+            .insert("hash".to_string(), Uuid::new_v4(), Utc::now() - Duration::seconds(1))
+            .await;
+        assert!(cache.get("hash").await.is_none());
+    }
+}
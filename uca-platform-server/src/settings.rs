@@ -0,0 +1,351 @@
+//! 运行时设置：存储于数据库，修改后无需重启即可生效。
+//!
+//! 每个进程持有一份设置快照，通过 `watch` 通道通知订阅者；
+//! 后台任务定期比对数据库中的更新时间，使多个实例之间的修改也能及时同步。
+
+use std::time::Duration;
+
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{ConnectionTrait, EntityTrait, Set};
+use sea_orm::sea_query::OnConflict;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use crate::{
+    audit::record_audit,
+    config::ResetDelivery,
+    entities::{runtime_settings, RuntimeSetting},
+    error::AppError,
+    state::AppState,
+};
+
+/// 运行时设置在数据库中的键。
+const RUNTIME_SETTINGS_KEY: &str = "runtime";
+/// 跨实例同步设置的轮询间隔。
+const SETTINGS_REFRESH_SECONDS: u64 = 5;
+/// 维护提示与公告的最大长度。
+const MAINTENANCE_MESSAGE_MAX_LEN: usize = 500;
+/// 发件人名称的最大长度。
+const MAIL_FROM_NAME_MAX_LEN: usize = 64;
+
+/// 竞赛记录提交时间窗口（留空表示不限制）。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmissionWindow {
+    /// 开始时间。
+    pub start: Option<DateTime<Utc>>,
+    /// 结束时间。
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl SubmissionWindow {
+    /// 判断给定时间是否在窗口内。
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        self.start.map(|start| now >= start).unwrap_or(true)
+            && self.end.map(|end| now <= end).unwrap_or(true)
+    }
+}
+
+/// 可在运行时修改的设置。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeSettings {
+    /// 重置凭证交付方式；留空时使用启动配置。
+    pub reset_delivery: Option<ResetDelivery>,
+    /// 是否处于维护模式（仅允许只读请求与管理操作）。
+    pub maintenance_mode: bool,
+    /// 维护提示信息。
+    pub maintenance_message: Option<String>,
+    /// 学生提交记录的时间窗口。
+    pub submission_window: Option<SubmissionWindow>,
+    /// 全站公告横幅（为空时不展示）。
+    pub announcement: Option<String>,
+    /// 邮件发件人显示名称；留空时使用启动配置。
+    pub mail_from_name: Option<String>,
+    /// 学生提交记录后是否自动指派给待审数量最少的审核人。
+    pub auto_assign_reviewers: bool,
+}
+
+impl RuntimeSettings {
+    /// 校验设置内容。
+    pub fn validate(&self) -> Result<(), AppError> {
+        if let Some(message) = &self.maintenance_message {
+            if message.chars().count() > MAINTENANCE_MESSAGE_MAX_LEN {
+                return Err(AppError::validation("maintenance_message too long"));
+            }
+        }
+        if let Some(announcement) = &self.announcement {
+            if announcement.chars().count() > MAINTENANCE_MESSAGE_MAX_LEN {
+                return Err(AppError::validation("announcement too long"));
+            }
+        }
+        if let Some(name) = &self.mail_from_name {
+            if name.trim().is_empty() || name.chars().count() > MAIL_FROM_NAME_MAX_LEN {
+                return Err(AppError::validation("invalid mail_from_name"));
+            }
+        }
+        if let Some(SubmissionWindow {
+            start: Some(start),
+            end: Some(end),
+        }) = &self.submission_window
+        {
+            if start >= end {
+                return Err(AppError::validation("submission window start must be before end"));
+            }
+        }
+        Ok(())
+    }
+
+    /// 当前是否允许提交记录。
+    pub fn ensure_submission_open(&self, now: DateTime<Utc>) -> Result<(), AppError> {
+        match &self.submission_window {
+            Some(window) if !window.contains(now) => {
+                Err(AppError::bad_request("submission window closed"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 进程内的设置快照与变更通知。
+#[derive(Debug)]
+pub struct SettingsStore {
+    sender: watch::Sender<RuntimeSettings>,
+    version: std::sync::Mutex<Option<DateTime<Utc>>>,
+}
+
+impl Default for SettingsStore {
+    fn default() -> Self {
+        let (sender, _) = watch::channel(RuntimeSettings::default());
+        Self {
+            sender,
+            version: std::sync::Mutex::new(None),
+        }
+    }
+}
+
+impl SettingsStore {
+    /// 当前生效的设置。
+    pub fn current(&self) -> RuntimeSettings {
+        self.sender.borrow().clone()
+    }
+
+    /// 订阅设置变更。
+    pub fn subscribe(&self) -> watch::Receiver<RuntimeSettings> {
+        self.sender.subscribe()
+    }
+
+    /// 发布新设置；版本未变化时忽略，返回是否发生更新。
+    pub fn publish(&self, settings: RuntimeSettings, version: Option<DateTime<Utc>>) -> bool {
+        let mut current = self
+            .version
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if *current == version && version.is_some() {
+            return false;
+        }
+        *current = version;
+        self.sender.send_if_modified(|value| {
+            if *value == settings {
+                return false;
+            }
+            *value = settings;
+            true
+        })
+    }
+}
+
+/// 从数据库读取运行时设置及其更新时间。
+pub async fn load_runtime_settings<C>(
+    db: &C,
+) -> Result<(RuntimeSettings, Option<DateTime<Utc>>), AppError>
+where
+    C: ConnectionTrait,
+{
+    let record = RuntimeSetting::find_by_id(RUNTIME_SETTINGS_KEY.to_string())
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let Some(record) = record else {
+        return Ok((RuntimeSettings::default(), None));
+    };
+    let settings = serde_json::from_str(&record.value).unwrap_or_else(|err| {
+        tracing::warn!(error = %err, "invalid runtime settings, using defaults");
+        RuntimeSettings::default()
+    });
+    Ok((settings, Some(record.updated_at)))
+}
+
+/// 保存运行时设置并立即在本进程生效。
+pub async fn save_runtime_settings(
+    state: &AppState,
+    actor_id: Uuid,
+    settings: RuntimeSettings,
+) -> Result<RuntimeSettings, AppError> {
+    settings.validate()?;
+    let value = serde_json::to_value(&settings)
+        .map_err(|err| AppError::internal(&format!("serialize settings failed: {err}")))?;
+    let now = Utc::now();
+    let model = runtime_settings::ActiveModel {
+        key: Set(RUNTIME_SETTINGS_KEY.to_string()),
+        value: Set(value.to_string()),
+        updated_by: Set(Some(actor_id)),
+        updated_at: Set(now),
+    };
+    RuntimeSetting::insert(model)
+        .on_conflict(
+            OnConflict::column(runtime_settings::Column::Key)
+                .update_columns([
+                    runtime_settings::Column::Value,
+                    runtime_settings::Column::UpdatedBy,
+                    runtime_settings::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    record_audit(
+        &state.db,
+        Some(actor_id),
+        "runtime_settings_update",
+        "runtime_settings",
+        Some(RUNTIME_SETTINGS_KEY),
+        Some(value),
+    )
+    .await?;
+    state.settings.publish(settings.clone(), Some(now));
+    Ok(settings)
+}
+
+/// 从数据库刷新本进程的设置快照，返回是否发生变化。
+pub async fn refresh_runtime_settings(state: &AppState) -> Result<bool, AppError> {
+    let (settings, version) = load_runtime_settings(&state.db).await?;
+    Ok(state.settings.publish(settings, version))
+}
+
+/// 启动跨实例设置同步任务。
+pub fn spawn_settings_refresh(state: AppState) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(SETTINGS_REFRESH_SECONDS));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match refresh_runtime_settings(&state).await {
+                Ok(true) => tracing::info!("runtime settings reloaded"),
+                Ok(false) => {}
+                Err(err) => tracing::warn!(error = %err, "runtime settings refresh failed"),
+            }
+        }
+    });
+}
+
+/// 维护模式下拒绝非只读请求（认证、管理与设置接口除外）。
+pub async fn maintenance_guard(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let settings = state.settings.current();
+    if settings.maintenance_mode && !is_maintenance_exempt(request.method(), request.uri().path()) {
+        let message = settings
+            .maintenance_message
+            .unwrap_or_else(|| "system under maintenance".to_string());
+        return AppError::service_unavailable(&message).into_response();
+    }
+    next.run(request).await
+}
+
+fn is_maintenance_exempt(method: &Method, path: &str) -> bool {
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return true;
+    }
+    ["/auth/", "/admin/", "/settings/"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn submission_window_bounds_are_inclusive_and_optional() {
+        let start = Utc.with_ymd_and_hms(2026, 9, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2026, 12, 31, 0, 0, 0).unwrap();
+        let window = SubmissionWindow {
+            start: Some(start),
+            end: Some(end),
+        };
+        assert!(window.contains(start));
+        assert!(window.contains(end));
+        assert!(!window.contains(end + chrono::Duration::seconds(1)));
+        let open_ended = SubmissionWindow {
+            start: Some(start),
+            end: None,
+        };
+        assert!(open_ended.contains(end + chrono::Duration::days(365)));
+
+        let settings = RuntimeSettings {
+            submission_window: Some(window),
+            ..RuntimeSettings::default()
+        };
+        assert!(settings.ensure_submission_open(start).is_ok());
+        assert!(settings.ensure_submission_open(start - chrono::Duration::days(1)).is_err());
+        assert!(RuntimeSettings::default().ensure_submission_open(start).is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_inverted_window() {
+        let start = Utc.with_ymd_and_hms(2026, 9, 1, 0, 0, 0).unwrap();
+        let settings = RuntimeSettings {
+            submission_window: Some(SubmissionWindow {
+                start: Some(start),
+                end: Some(start - chrono::Duration::days(1)),
+            }),
+            ..RuntimeSettings::default()
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn maintenance_exempts_reads_and_admin_paths() {
+        assert!(is_maintenance_exempt(&Method::GET, "/records/contest"));
+        assert!(is_maintenance_exempt(&Method::POST, "/auth/password/login"));
+        assert!(is_maintenance_exempt(&Method::PUT, "/admin/settings/runtime"));
+        assert!(!is_maintenance_exempt(&Method::POST, "/records/contest"));
+        assert!(!is_maintenance_exempt(&Method::PUT, "/students/2023001"));
+    }
+
+    #[test]
+    fn publish_notifies_subscribers_only_on_change() {
+        let store = SettingsStore::default();
+        let mut receiver = store.subscribe();
+        let version = Some(Utc::now());
+        let settings = RuntimeSettings {
+            maintenance_mode: true,
+            ..RuntimeSettings::default()
+        };
+        assert!(store.publish(settings.clone(), version));
+        assert!(receiver.has_changed().unwrap());
+        receiver.borrow_and_update();
+        assert!(!store.publish(settings, version));
+        assert!(!receiver.has_changed().unwrap());
+        assert!(store.current().maintenance_mode);
+    }
+
+    #[tokio::test]
+    async fn load_runtime_settings_defaults_when_missing() {
+        let db = crate::services::test_support::memory_db().await;
+        let (settings, version) = load_runtime_settings(&db).await.unwrap();
+        assert_eq!(settings, RuntimeSettings::default());
+        assert!(version.is_none());
+    }
+}
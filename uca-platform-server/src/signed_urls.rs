@@ -0,0 +1,245 @@
+//! 短时有效的签名下载链接与导出文件暂存。
+//!
+//! 令牌由 [`crate::signed_tokens`] 签名，载荷记录文件类型、对象 ID、签发用户与过期时间。
+//! 下载时重新校验签发用户的权限，
+//! 因此账号停用或失去访问范围后链接立即失效。
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{error::AppError, signed_tokens, state::AppState};
+
+/// 签名链接的路径前缀。
+pub const SIGNED_URL_PREFIX: &str = "/files/signed/";
+
+/// 签名链接令牌的上下文标签。
+const TOKEN_CONTEXT: &str = "signed-url";
+
+/// 可签名下载的文件类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignedFileKind {
+    /// 记录附件。
+    Attachment,
+    /// 图片附件缩略图。
+    Thumbnail,
+    /// 审核签名图片。
+    ReviewSignature,
+    /// 个人签名图片（ID 为用户 ID）。
+    UserSignature,
+    /// 已完成的导出文件。
+    Export,
+}
+
+/// 令牌载荷。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedFileClaims {
+    /// 文件类型。
+    pub kind: SignedFileKind,
+    /// 对象 ID。
+    pub id: Uuid,
+    /// 签发用户 ID。
+    pub user_id: Uuid,
+    /// 过期时间（Unix 秒）。
+    pub exp: i64,
+}
+
+impl SignedFileClaims {
+    /// 过期时间。
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        Utc.timestamp_opt(self.exp, 0)
+            .single()
+            .unwrap_or_else(Utc::now)
+    }
+}
+
+/// 签发链接响应。
+#[derive(Debug, Serialize)]
+pub struct SignedUrl {
+    /// 相对 API 根路径的下载地址。
+    pub url: String,
+    /// 过期时间。
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 按配置的有效期为指定文件签发链接。
+pub fn issue_signed_url(
+    state: &AppState,
+    kind: SignedFileKind,
+    id: Uuid,
+    user_id: Uuid,
+) -> SignedUrl {
+    let claims = SignedFileClaims {
+        kind,
+        id,
+        user_id,
+        exp: Utc::now().timestamp() + state.config.signed_url_ttl_seconds as i64,
+    };
+    SignedUrl {
+        url: format!(
+            "{SIGNED_URL_PREFIX}{}",
+            sign_claims(&state.config.auth_secret_key, &claims)
+        ),
+        expires_at: claims.expires_at(),
+    }
+}
+
+/// 生成令牌。
+pub fn sign_claims(secret: &[u8], claims: &SignedFileClaims) -> String {
+    signed_tokens::sign(secret, TOKEN_CONTEXT, claims)
+}
+
+/// 校验令牌签名与有效期，返回载荷。
+pub fn verify_token(
+    secret: &[u8],
+    token: &str,
+    now: DateTime<Utc>,
+) -> Result<SignedFileClaims, AppError> {
+    let claims: SignedFileClaims = signed_tokens::verify(secret, TOKEN_CONTEXT, token)
+        .ok_or_else(|| AppError::auth("invalid signed url"))?;
+    if claims.exp < now.timestamp() {
+        return Err(AppError::auth("signed url expired"));
+    }
+    Ok(claims)
+}
+
+/// 暂存导出文件的元数据。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportArtifact {
+    /// 导出文件 ID。
+    pub id: Uuid,
+    /// 发起导出的用户 ID。
+    pub owner_id: Uuid,
+    /// 下载文件名。
+    pub file_name: String,
+    /// MIME 类型。
+    pub content_type: String,
+    /// 生成时间。
+    pub created_at: DateTime<Utc>,
+}
+
+/// 导出文件暂存目录：`upload_dir/exports`。
+pub fn export_artifact_dir(upload_dir: &Path) -> PathBuf {
+    upload_dir.join("exports")
+}
+
+/// 导出文件路径。
+pub fn export_artifact_path(upload_dir: &Path, id: Uuid) -> PathBuf {
+    export_artifact_dir(upload_dir).join(format!("{}.bin", id.simple()))
+}
+
+fn export_artifact_meta_path(upload_dir: &Path, id: Uuid) -> PathBuf {
+    export_artifact_dir(upload_dir).join(format!("{}.json", id.simple()))
+}
+
+/// 写入导出文件元数据（文件内容由调用方先写入 [`export_artifact_path`]）。
+pub async fn save_export_artifact(
+    upload_dir: &Path,
+    artifact: &ExportArtifact,
+) -> Result<(), AppError> {
+    let json = serde_json::to_vec(artifact)
+        .map_err(|err| AppError::internal(&format!("serialize export artifact failed: {err}")))?;
+    tokio::fs::write(export_artifact_meta_path(upload_dir, artifact.id), json)
+        .await
+        .map_err(|err| AppError::internal(&format!("write export artifact failed: {err}")))
+}
+
+/// 读取导出文件元数据；不存在时返回 404。
+pub async fn load_export_artifact(upload_dir: &Path, id: Uuid) -> Result<ExportArtifact, AppError> {
+    let bytes = tokio::fs::read(export_artifact_meta_path(upload_dir, id))
+        .await
+        .map_err(|_| AppError::not_found("export not found"))?;
+    serde_json::from_slice(&bytes).map_err(|_| AppError::not_found("export not found"))
+}
+
+/// 删除超过链接有效期的暂存导出文件。
+pub async fn cleanup_export_artifacts(state: &AppState) -> Result<u64, AppError> {
+    let dir = export_artifact_dir(&state.config.upload_dir);
+    let max_age = Duration::from_secs(state.config.signed_url_ttl_seconds);
+    tokio::task::spawn_blocking(move || remove_stale_files(&dir, max_age, SystemTime::now()))
+        .await
+        .map_err(|err| AppError::internal(&format!("export cleanup failed: {err}")))?
+}
+
+fn remove_stale_files(dir: &Path, max_age: Duration, now: SystemTime) -> Result<u64, AppError> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let entries = std::fs::read_dir(dir)
+        .map_err(|err| AppError::internal(&format!("read export dir failed: {err}")))?;
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let stale = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > max_age);
+        if metadata.is_file() && stale && std::fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(exp: i64) -> SignedFileClaims {
+        SignedFileClaims {
+            kind: SignedFileKind::Attachment,
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            exp,
+        }
+    }
+
+    #[test]
+    fn token_round_trips_and_rejects_tampering() {
+        let now = Utc::now();
+        let original = claims(now.timestamp() + 60);
+        let token = sign_claims(b"secret", &original);
+        assert_eq!(verify_token(b"secret", &token, now).unwrap(), original);
+        assert!(verify_token(b"other", &token, now).is_err());
+
+        let forged = sign_claims(b"secret", &claims(now.timestamp() + 60));
+        let (_, forged_mac) = forged.split_once('.').unwrap();
+        let (payload, _) = token.split_once('.').unwrap();
+        assert!(verify_token(b"secret", &format!("{payload}.{forged_mac}"), now).is_err());
+        assert!(verify_token(b"secret", "not-a-token", now).is_err());
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        let now = Utc::now();
+        let token = sign_claims(b"secret", &claims(now.timestamp() - 1));
+        assert!(verify_token(b"secret", &token, now).is_err());
+    }
+
+    #[test]
+    fn remove_stale_files_keeps_recent_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.bin"), b"data").unwrap();
+        let now = SystemTime::now();
+        assert_eq!(
+            remove_stale_files(dir.path(), Duration::from_secs(60), now).unwrap(),
+            0
+        );
+        let later = now + Duration::from_secs(120);
+        assert_eq!(
+            remove_stale_files(dir.path(), Duration::from_secs(60), later).unwrap(),
+            1
+        );
+        assert_eq!(
+            remove_stale_files(&dir.path().join("missing"), Duration::ZERO, now).unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,610 @@
+//! 导入文件解析：识别 xlsx/xls/CSV，CSV 自动检测文本编码（UTF-8/UTF-16/GBK）后转为工作表；
+//! 并提供各导入接口共用的表头定位（含标题行与合并的多行表头）、单元格读取与取值转换工具。
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek};
+
+use calamine::{Data, Range, Reader};
+use encoding_rs::{Encoding, GBK, UTF_16BE, UTF_16LE, UTF_8};
+
+use crate::error::AppError;
+
+/// CSV 解析后使用的工作表名称。
+pub const CSV_SHEET_NAME: &str = "Sheet1";
+
+/// 用于判断无 BOM 的 UTF-16 文本的采样长度。
+const ENCODING_SAMPLE_BYTES: usize = 4096;
+
+/// 自动识别表头时扫描的最大行数。
+const HEADER_SCAN_ROWS: usize = 10;
+/// 多行表头允许的最大行数；自动识别时只尝试单行与两行。
+pub const MAX_HEADER_ROWS: usize = 3;
+/// 多行表头拼接上下级标题时使用的分隔符，如 `联系方式/手机号`。
+pub const HEADER_PATH_SEPARATOR: &str = "/";
+
+/// 导入请求中的表头位置参数；均为空时自动识别。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeaderOptions {
+    /// 表头起始行（从 0 开始）。
+    pub row_index: Option<usize>,
+    /// 表头占用的行数。
+    pub rows: Option<usize>,
+}
+
+impl HeaderOptions {
+    /// 校验表头行数范围。
+    pub fn new(row_index: Option<usize>, rows: Option<usize>) -> Result<Self, AppError> {
+        if let Some(rows) = rows {
+            if !(1..=MAX_HEADER_ROWS).contains(&rows) {
+                return Err(AppError::bad_request(&format!(
+                    "header_rows must be 1-{MAX_HEADER_ROWS}"
+                )));
+            }
+        }
+        Ok(Self { row_index, rows })
+    }
+
+    /// 从 multipart 表单字段 `header_row_index`/`header_rows` 读取。
+    pub fn from_fields(fields: &HashMap<String, String>) -> Result<Self, AppError> {
+        let parse = |key: &str| -> Result<Option<usize>, AppError> {
+            match fields.get(key).map(|value| value.trim()) {
+                None | Some("") => Ok(None),
+                Some(value) => value
+                    .parse::<usize>()
+                    .map(Some)
+                    .map_err(|_| AppError::bad_request(&format!("invalid {key}"))),
+            }
+        };
+        Self::new(parse("header_row_index")?, parse("header_rows")?)
+    }
+}
+
+/// 已定位的表头。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetHeader {
+    /// 表头名称到列索引的映射；多行表头同时包含完整路径与最末级标题。
+    pub index: HashMap<String, usize>,
+    /// 表头起始行（从 0 开始）。
+    pub row_index: usize,
+    /// 表头占用的行数。
+    pub rows: usize,
+}
+
+impl SheetHeader {
+    /// 第一行数据所在行（从 0 开始）。
+    pub fn data_start(&self) -> usize {
+        self.row_index + self.rows
+    }
+}
+
+/// 已解析的上传表格。
+#[derive(Debug)]
+pub struct UploadWorkbook {
+    /// 文件格式（xlsx/xls/csv）。
+    pub format: &'static str,
+    /// 检测到的文本编码（仅 CSV；xlsx/xls 由文件自身声明编码）。
+    pub encoding: Option<&'static str>,
+    sheets: Vec<(String, Range<Data>)>,
+}
+
+impl UploadWorkbook {
+    /// 工作表名称（按文件中的顺序）。
+    pub fn sheet_names(&self) -> Vec<String> {
+        self.sheets.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// 第一个工作表。
+    pub fn first_sheet(&self) -> Result<&Range<Data>, AppError> {
+        self.sheets
+            .first()
+            .map(|(_, range)| range)
+            .ok_or_else(|| AppError::bad_request("spreadsheet has no sheets"))
+    }
+
+    /// 按名称获取工作表。
+    pub fn sheet(&self, name: &str) -> Result<&Range<Data>, AppError> {
+        self.sheets
+            .iter()
+            .find(|(sheet_name, _)| sheet_name == name)
+            .map(|(_, range)| range)
+            .ok_or_else(|| AppError::bad_request(&format!("worksheet not found: {name}")))
+    }
+}
+
+/// 按文件头识别格式并读取全部工作表。
+pub fn read_upload_workbook(bytes: Vec<u8>) -> Result<UploadWorkbook, AppError> {
+    if bytes.starts_with(b"PK\x03\x04") {
+        let workbook = calamine::Xlsx::new(Cursor::new(bytes))
+            .map_err(|_| AppError::bad_request("invalid xlsx file"))?;
+        return read_sheets(workbook, "xlsx");
+    }
+    if bytes.starts_with(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]) {
+        let workbook = calamine::Xls::new(Cursor::new(bytes))
+            .map_err(|_| AppError::bad_request("invalid xls file"))?;
+        return read_sheets(workbook, "xls");
+    }
+
+    let (encoding, text) = decode_text(&bytes)?;
+    Ok(UploadWorkbook {
+        format: "csv",
+        encoding: Some(encoding.name()),
+        sheets: vec![(CSV_SHEET_NAME.to_string(), rows_to_range(parse_delimited(&text)))],
+    })
+}
+
+/// 构建表头名称到列索引的映射（去除首尾空白，忽略空表头）。
+pub fn build_header_index(header_row: Option<&[Data]>) -> HashMap<String, usize> {
+    let mut header_index = HashMap::new();
+    if let Some(header_row) = header_row {
+        for (idx, cell) in header_row.iter().enumerate() {
+            let trimmed = cell.to_string().trim().to_string();
+            if !trimmed.is_empty() {
+                header_index.insert(trimmed, idx);
+            }
+        }
+    }
+    header_index
+}
+
+/// 定位表头：未指定位置时在前几行中选择与 `expected` 匹配最多的行（及两行表头），
+/// 都不匹配时退回首行单行表头。
+pub fn locate_header(
+    range: &Range<Data>,
+    options: HeaderOptions,
+    expected: &[&str],
+) -> Result<SheetHeader, AppError> {
+    let rows: Vec<&[Data]> = range.rows().collect();
+    if let (Some(row_index), Some(height)) = (options.row_index, options.rows) {
+        if row_index + height > rows.len() {
+            return Err(AppError::bad_request("header row is beyond the end of the sheet"));
+        }
+        return Ok(header_at(&rows, row_index, height));
+    }
+    if let Some(row_index) = options.row_index {
+        if row_index >= rows.len() {
+            return Err(AppError::bad_request("header row is beyond the end of the sheet"));
+        }
+    }
+
+    let starts: Vec<usize> = match options.row_index {
+        Some(row_index) => vec![row_index],
+        None => (0..rows.len().min(HEADER_SCAN_ROWS)).collect(),
+    };
+    let heights: Vec<usize> = match options.rows {
+        Some(height) => vec![height],
+        None => vec![1, 2],
+    };
+    let mut best: Option<(usize, SheetHeader)> = None;
+    for &start in &starts {
+        for &height in &heights {
+            if start + height > rows.len() {
+                continue;
+            }
+            let header = header_at(&rows, start, height);
+            let score = expected
+                .iter()
+                .filter(|name| header.index.contains_key(**name))
+                .count();
+            // 得分相同时优先行数少的表头，避免把标题行误当作上级表头。
+            let better = best.as_ref().is_none_or(|(best_score, best_header)| {
+                score > *best_score || (score == *best_score && height < best_header.rows)
+            });
+            if score > 0 && better {
+                best = Some((score, header));
+            }
+        }
+    }
+    Ok(match best {
+        Some((_, header)) => header,
+        None => header_at(&rows, starts.first().copied().unwrap_or(0), heights[0]),
+    })
+}
+
+/// 汇总各列的候选表头与 `field_map` 中指定的列名，作为自动识别表头时的匹配依据。
+pub fn expected_headers<'a>(
+    candidates: impl IntoIterator<Item = &'a [&'a str]>,
+    field_map: Option<&'a HashMap<String, String>>,
+) -> Vec<&'a str> {
+    let mut expected: Vec<&str> = candidates.into_iter().flatten().copied().collect();
+    if let Some(field_map) = field_map {
+        expected.extend(field_map.values().map(|value| value.trim()));
+    }
+    expected
+}
+
+fn header_at(rows: &[&[Data]], row_index: usize, height: usize) -> SheetHeader {
+    let index = if height == 1 {
+        build_header_index(rows.get(row_index).copied())
+    } else {
+        rows.get(row_index..row_index + height)
+            .map(flatten_header_rows)
+            .unwrap_or_default()
+    };
+    SheetHeader {
+        index,
+        row_index,
+        rows: height,
+    }
+}
+
+/// 展开多行表头：合并单元格只在左上角有值，横向合并的上级标题向右延续到其下级列，
+/// 纵向合并的标题直接使用本身。每列登记 `上级/下级` 完整路径，并在不冲突时登记最末级标题。
+fn flatten_header_rows(rows: &[&[Data]]) -> HashMap<String, usize> {
+    let height = rows.len();
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut carried = vec![String::new(); height];
+    let mut paths = Vec::new();
+    for column in 0..width {
+        let cells: Vec<String> = rows
+            .iter()
+            .map(|row| read_cell_by_index(column, row))
+            .collect();
+        if cells.iter().all(String::is_empty) {
+            carried.iter_mut().for_each(String::clear);
+            continue;
+        }
+        let mut parts = Vec::new();
+        for level in 0..height {
+            if !cells[level].is_empty() {
+                carried[level] = cells[level].clone();
+                carried[level + 1..].iter_mut().for_each(String::clear);
+                parts.push(cells[level].clone());
+            } else if cells[level + 1..].iter().any(|cell| !cell.is_empty())
+                && !carried[level].is_empty()
+            {
+                parts.push(carried[level].clone());
+            }
+        }
+        paths.push((column, parts));
+    }
+
+    let mut index = HashMap::new();
+    for (column, parts) in &paths {
+        index.insert(parts.join(HEADER_PATH_SEPARATOR), *column);
+    }
+    for (column, parts) in paths {
+        if let Some(leaf) = parts.last() {
+            index.entry(leaf.clone()).or_insert(column);
+        }
+    }
+    index
+}
+
+/// 按候选表头名称依次查找列索引。
+pub fn find_header_index(header_index: &HashMap<String, usize>, candidates: &[&str]) -> Option<usize> {
+    candidates.iter().find_map(|key| header_index.get(*key).cloned())
+}
+
+/// 根据表头名称读取单元格内容。
+pub fn read_cell_by_title(header_index: &HashMap<String, usize>, title: &str, row: &[Data]) -> String {
+    if let Some(idx) = header_index.get(title) {
+        return read_cell_by_index(*idx, row);
+    }
+    String::new()
+}
+
+/// 根据列索引读取单元格内容（去除首尾空白）。
+pub fn read_cell_by_index(idx: usize, row: &[Data]) -> String {
+    row.get(idx)
+        .map(|cell| cell.to_string().trim().to_string())
+        .unwrap_or_default()
+}
+
+/// 根据可选列索引读取单元格内容，缺列时返回空字符串。
+pub fn read_cell_by_index_opt(index: Option<&usize>, row: &[Data]) -> String {
+    match index {
+        Some(idx) => read_cell_by_index(*idx, row),
+        None => String::new(),
+    }
+}
+
+/// 解析学时单元格（四舍五入到整数），空值或非数字返回 `None`。
+pub fn parse_hours(value: String) -> Option<i32> {
+    if value.is_empty() {
+        return None;
+    }
+    value.parse::<f32>().ok().map(|num| num.round() as i32)
+}
+
+/// 根据审核状态列与初审/复审学时推断导入记录的状态。
+pub fn resolve_status(status_value: &str, first_review: Option<i32>, final_review: Option<i32>) -> String {
+    if status_value == "不通过" || status_value == "rejected" {
+        return "rejected".to_string();
+    }
+    if status_value == "已复审" || status_value == "final_reviewed" || final_review.is_some() {
+        return "final_reviewed".to_string();
+    }
+    if status_value == "已初审" || status_value == "first_reviewed" || first_review.is_some() {
+        return "first_reviewed".to_string();
+    }
+    "submitted".to_string()
+}
+
+fn read_sheets<RS, R>(mut workbook: R, format: &'static str) -> Result<UploadWorkbook, AppError>
+where
+    RS: Read + Seek,
+    R: Reader<RS>,
+{
+    let mut sheets = Vec::new();
+    for name in workbook.sheet_names() {
+        let range = workbook
+            .worksheet_range(&name)
+            .map_err(|_| AppError::bad_request("failed to read worksheet"))?;
+        sheets.push((name, range));
+    }
+    if sheets.is_empty() {
+        return Err(AppError::bad_request("spreadsheet has no sheets"));
+    }
+    Ok(UploadWorkbook {
+        format,
+        encoding: None,
+        sheets,
+    })
+}
+
+/// 检测编码并解码：优先 BOM，其次按零字节分布识别 UTF-16，合法 UTF-8 按 UTF-8，否则按 GBK。
+fn decode_text(bytes: &[u8]) -> Result<(&'static Encoding, String), AppError> {
+    let (encoding, body) = match Encoding::for_bom(bytes) {
+        Some((encoding, bom_len)) => (encoding, &bytes[bom_len..]),
+        None => (detect_encoding(bytes), bytes),
+    };
+    let text = encoding
+        .decode_without_bom_handling_and_without_replacement(body)
+        .ok_or_else(|| AppError::bad_request(&format!("file is not valid {}", encoding.name())))?;
+    Ok((encoding, text.into_owned()))
+}
+
+fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+    let sample = &bytes[..bytes.len().min(ENCODING_SAMPLE_BYTES)];
+    let even_zeros = sample.iter().step_by(2).filter(|byte| **byte == 0).count();
+    let odd_zeros = sample.iter().skip(1).step_by(2).filter(|byte| **byte == 0).count();
+    // 表头与分隔符多为 ASCII，UTF-16 编码下其高位字节为 0。
+    let threshold = (sample.len() / 2).max(1) / 5;
+    if odd_zeros > threshold && odd_zeros > even_zeros * 4 {
+        return UTF_16LE;
+    }
+    if even_zeros > threshold && even_zeros > odd_zeros * 4 {
+        return UTF_16BE;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+    GBK
+}
+
+/// 解析逗号或制表符分隔的文本（支持双引号转义与引号内换行），忽略空行。
+fn parse_delimited(text: &str) -> Vec<Vec<String>> {
+    let first_line = text.lines().next().unwrap_or_default();
+    let delimiter = if first_line.matches('\t').count() > first_line.matches(',').count() {
+        '\t'
+    } else {
+        ','
+    };
+
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(ch),
+            }
+            continue;
+        }
+        match ch {
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' | '\n' => {
+                if ch == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                row.push(std::mem::take(&mut field));
+                push_row(&mut rows, std::mem::take(&mut row));
+            }
+            _ if ch == delimiter => row.push(std::mem::take(&mut field)),
+            _ => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        push_row(&mut rows, row);
+    }
+    rows
+}
+
+fn push_row(rows: &mut Vec<Vec<String>>, row: Vec<String>) {
+    if row.iter().any(|value| !value.trim().is_empty()) {
+        rows.push(row);
+    }
+}
+
+fn rows_to_range(rows: Vec<Vec<String>>) -> Range<Data> {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if rows.is_empty() || width == 0 {
+        return Range::empty();
+    }
+    let mut range = Range::new((0, 0), ((rows.len() - 1) as u32, (width - 1) as u32));
+    for (row_idx, row) in rows.into_iter().enumerate() {
+        for (col_idx, value) in row.into_iter().enumerate() {
+            if !value.is_empty() {
+                range.set_value((row_idx as u32, col_idx as u32), Data::String(value));
+            }
+        }
+    }
+    range
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_rows(workbook: &UploadWorkbook) -> Vec<Vec<String>> {
+        workbook
+            .first_sheet()
+            .unwrap()
+            .rows()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn csv_in_gbk_is_decoded() {
+        let (bytes, _, _) = GBK.encode("学号,姓名\r\n2023001,张三\r\n");
+        let workbook = read_upload_workbook(bytes.into_owned()).unwrap();
+        assert_eq!(workbook.format, "csv");
+        assert_eq!(workbook.encoding, Some("GBK"));
+        assert_eq!(
+            first_rows(&workbook),
+            vec![vec!["学号", "姓名"], vec!["2023001", "张三"]]
+        );
+    }
+
+    #[test]
+    fn csv_in_utf8_with_bom_is_decoded() {
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend_from_slice("学号,姓名\n2023001,\"李, 四\"\n\n".as_bytes());
+        let workbook = read_upload_workbook(bytes).unwrap();
+        assert_eq!(workbook.encoding, Some("UTF-8"));
+        assert_eq!(
+            first_rows(&workbook),
+            vec![vec!["学号", "姓名"], vec!["2023001", "李, 四"]]
+        );
+    }
+
+    #[test]
+    fn utf16_tab_separated_text_is_detected_without_bom() {
+        let text = "学号\t姓名\r\n2023001\t王五\r\n";
+        let bytes: Vec<u8> = text.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+        let workbook = read_upload_workbook(bytes).unwrap();
+        assert_eq!(workbook.encoding, Some("UTF-16LE"));
+        assert_eq!(
+            first_rows(&workbook),
+            vec![vec!["学号", "姓名"], vec!["2023001", "王五"]]
+        );
+
+        let mut bytes = vec![0xFE, 0xFF];
+        bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_be_bytes()));
+        let workbook = read_upload_workbook(bytes).unwrap();
+        assert_eq!(workbook.encoding, Some("UTF-16BE"));
+        assert_eq!(first_rows(&workbook)[1], vec!["2023001", "王五"]);
+    }
+
+    #[test]
+    fn parse_delimited_handles_quotes_and_ragged_rows() {
+        let rows = parse_delimited("a,\"b \"\"c\"\"\nd\",e\nf\n");
+        assert_eq!(rows, vec![vec!["a", "b \"c\"\nd", "e"], vec!["f"]]);
+        let range = rows_to_range(rows);
+        assert_eq!(range.get_size(), (2, 3));
+        assert_eq!(range.get_value((1, 1)), Some(&Data::Empty));
+    }
+
+    #[test]
+    fn build_header_index_maps_columns() {
+        let header = vec![Data::String("学号".into()), Data::String("竞赛名称".into())];
+        let index = build_header_index(Some(&header));
+        assert_eq!(index.get("学号"), Some(&0));
+        assert_eq!(index.get("竞赛名称"), Some(&1));
+    }
+
+    #[test]
+    fn read_cell_by_title_uses_header_index() {
+        let header = vec![Data::String("学号".into()), Data::String("竞赛名称".into())];
+        let index = build_header_index(Some(&header));
+        let row = vec![Data::String("2023001".into()), Data::String("竞赛A".into())];
+        let value = read_cell_by_title(&index, "竞赛名称", &row);
+        assert_eq!(value, "竞赛A");
+    }
+
+    #[test]
+    fn read_cell_by_index_opt_handles_missing_column() {
+        let header = vec![Data::String("学号".into()), Data::String("姓名".into())];
+        let index = build_header_index(Some(&header));
+        let row = vec![Data::String(" 2023001 ".into())];
+        assert_eq!(read_cell_by_index_opt(index.get("学号"), &row), "2023001");
+        assert_eq!(read_cell_by_index_opt(index.get("姓名"), &row), "");
+        assert_eq!(read_cell_by_index_opt(None, &row), "");
+        assert_eq!(find_header_index(&index, &["学生学号", "姓名"]), Some(1));
+        assert_eq!(find_header_index(&index, &["备注"]), None);
+    }
+
+    fn sheet(rows: &[&[&str]]) -> Range<Data> {
+        rows_to_range(
+            rows.iter()
+                .map(|row| row.iter().map(|value| value.to_string()).collect())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn locate_header_skips_title_rows() {
+        let range = sheet(&[
+            &["2025 级学生名单"],
+            &["学号", "姓名", "院系"],
+            &["2023001", "张三", "机械学院"],
+        ]);
+        let header = locate_header(&range, HeaderOptions::default(), &["学号", "姓名"]).unwrap();
+        assert_eq!((header.row_index, header.rows, header.data_start()), (1, 1, 2));
+        assert_eq!(header.index.get("院系"), Some(&2));
+    }
+
+    #[test]
+    fn locate_header_flattens_merged_two_row_headers() {
+        // 学号、姓名纵向合并两行；“联系方式”横向合并两列。
+        let range = sheet(&[
+            &["计算机学院学生信息表"],
+            &["学号", "姓名", "联系方式", "", "备注"],
+            &["", "", "手机号", "邮箱", ""],
+            &["2023001", "张三", "13800000000", "a@example.com", ""],
+        ]);
+        let header =
+            locate_header(&range, HeaderOptions::default(), &["学号", "姓名", "手机号"]).unwrap();
+        assert_eq!((header.row_index, header.rows, header.data_start()), (1, 2, 3));
+        assert_eq!(header.index.get("学号"), Some(&0));
+        assert_eq!(header.index.get("联系方式/手机号"), Some(&2));
+        assert_eq!(header.index.get("手机号"), Some(&2));
+        assert_eq!(header.index.get("邮箱"), Some(&3));
+        assert_eq!(header.index.get("备注"), Some(&4));
+    }
+
+    #[test]
+    fn locate_header_respects_explicit_position() {
+        let range = sheet(&[&["标题"], &["学号", "姓名"], &["2023001", "张三"]]);
+        let options = HeaderOptions {
+            row_index: Some(1),
+            rows: Some(1),
+        };
+        let header = locate_header(&range, options, &[]).unwrap();
+        assert_eq!(header.data_start(), 2);
+        let options = HeaderOptions {
+            row_index: Some(3),
+            rows: None,
+        };
+        assert!(locate_header(&range, options, &[]).is_err());
+        // 无法识别时沿用首行单行表头。
+        let header = locate_header(&range, HeaderOptions::default(), &["竞赛名称"]).unwrap();
+        assert_eq!((header.row_index, header.rows), (0, 1));
+    }
+
+    #[test]
+    fn header_options_are_parsed_from_fields() {
+        let mut fields = HashMap::new();
+        assert_eq!(HeaderOptions::from_fields(&fields).unwrap(), HeaderOptions::default());
+        fields.insert("header_row_index".to_string(), "2".to_string());
+        fields.insert("header_rows".to_string(), "2".to_string());
+        let options = HeaderOptions::from_fields(&fields).unwrap();
+        assert_eq!((options.row_index, options.rows), (Some(2), Some(2)));
+        fields.insert("header_rows".to_string(), "5".to_string());
+        assert!(HeaderOptions::from_fields(&fields).is_err());
+    }
+
+    #[test]
+    fn invalid_xlsx_is_rejected() {
+        assert!(read_upload_workbook(b"PK\x03\x04garbage".to_vec()).is_err());
+    }
+}
@@ -3,23 +3,37 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use tokio::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, Notify};
 use time::{Duration, OffsetDateTime};
 use uuid::Uuid;
-use webauthn_rs::prelude::{PasskeyAuthentication, PasskeyRegistration, Webauthn};
+use webauthn_rs::prelude::{
+    DiscoverableAuthentication, PasskeyAuthentication, PasskeyRegistration, Webauthn,
+};
 
 use sea_orm::DatabaseConnection;
 
-use crate::config::Config;
+use crate::auth_flows::AuthFlowStore;
+use crate::config::{Config, MailConfig, ResetDelivery};
+use crate::document_verification::VerifyRateLimiter;
+use crate::entities::{competition_library, users};
 use crate::error::AppError;
-
-/// 认证流程状态的有效期。
-const CHALLENGE_TTL_SECONDS: i64 = 300;
-/// 二次验证令牌有效期。
-const REAUTH_TTL_SECONDS: i64 = 300;
+use crate::events::EventBus;
+use crate::i18n::{resolve_locale, Locale};
+use crate::import_jobs::ImportJobStore;
+use crate::pdf_convert::PdfConverter;
+use crate::session_cache::SessionCache;
+use crate::settings::SettingsStore;
+
+/// 竞赛库相似查询缓存有效期。
+const COMPETITION_CACHE_TTL_SECONDS: i64 = 60;
+/// 相似查询限流窗口。
+const SIMILAR_RATE_WINDOW_SECONDS: i64 = 10;
+/// 每个用户在限流窗口内允许的相似查询次数。
+const SIMILAR_RATE_LIMIT: usize = 20;
 
 /// 进行中的 Passkey 注册会话。
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PasskeyRegisterSession {
     /// 注册用户 ID。
     pub user_id: Uuid,
@@ -30,7 +44,7 @@ pub struct PasskeyRegisterSession {
 }
 
 /// 进行中的 Passkey 认证会话。
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PasskeyAuthSession {
     /// 认证用户 ID。
     pub user_id: Uuid,
@@ -40,94 +54,71 @@ pub struct PasskeyAuthSession {
     pub created_at: OffsetDateTime,
 }
 
-/// Passkey 流程的内存状态存储。
-#[derive(Debug, Default)]
-pub struct PasskeyStateStore {
-    register: HashMap<Uuid, PasskeyRegisterSession>,
-    authenticate: HashMap<Uuid, PasskeyAuthSession>,
-}
-
-impl PasskeyStateStore {
-    /// 写入注册会话。
-    pub fn insert_register(&mut self, session_id: Uuid, session: PasskeyRegisterSession) {
-        self.register.insert(session_id, session);
-    }
-
-    /// 写入认证会话。
-    pub fn insert_auth(&mut self, session_id: Uuid, session: PasskeyAuthSession) {
-        self.authenticate.insert(session_id, session);
-    }
-
-    /// 取出并移除有效的注册会话。
-    pub fn take_register(&mut self, session_id: &Uuid) -> Option<PasskeyRegisterSession> {
-        self.evict_expired();
-        self.register.remove(session_id)
-    }
-
-    /// 取出并移除有效的认证会话。
-    pub fn take_auth(&mut self, session_id: &Uuid) -> Option<PasskeyAuthSession> {
-        self.evict_expired();
-        self.authenticate.remove(session_id)
-    }
-
-    fn evict_expired(&mut self) {
-        let expiry = OffsetDateTime::now_utc() - Duration::seconds(CHALLENGE_TTL_SECONDS);
-        self.register
-            .retain(|_, session| session.created_at > expiry);
-        self.authenticate
-            .retain(|_, session| session.created_at > expiry);
-    }
-}
-
-/// 进行中的 Passkey 重新验证会话。
-#[derive(Debug, Default)]
-pub struct ReauthPasskeyStore {
-    sessions: HashMap<Uuid, PasskeyAuthSession>,
-}
-
-impl ReauthPasskeyStore {
-    pub fn insert(&mut self, session_id: Uuid, session: PasskeyAuthSession) {
-        self.sessions.insert(session_id, session);
-    }
-
-    pub fn take(&mut self, session_id: &Uuid) -> Option<PasskeyAuthSession> {
-        self.evict_expired();
-        self.sessions.remove(session_id)
-    }
-
-    fn evict_expired(&mut self) {
-        let expiry = OffsetDateTime::now_utc() - Duration::seconds(CHALLENGE_TTL_SECONDS);
-        self.sessions
-            .retain(|_, session| session.created_at > expiry);
-    }
+/// 进行中的可发现凭据认证会话（未提供用户名，由凭据中的用户句柄确定用户）。
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasskeyDiscoverableSession {
+    /// WebAuthn 认证状态。
+    pub state: DiscoverableAuthentication,
+    /// 创建时间，用于过期检查。
+    pub created_at: OffsetDateTime,
 }
 
 /// 二次验证令牌会话。
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ReauthSession {
     pub user_id: Uuid,
     pub created_at: OffsetDateTime,
 }
 
-/// 二次验证令牌存储。
+/// 进行中的统一身份认证登录。
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SsoLoginSession {
+    /// 登录后跳转的前端路径。
+    pub next: Option<String>,
+    pub created_at: OffsetDateTime,
+}
+
+/// 竞赛库相似查询的缓存与限流状态。
 #[derive(Debug, Default)]
-pub struct ReauthStateStore {
-    sessions: HashMap<String, ReauthSession>,
+pub struct CompetitionMatchStore {
+    snapshot: Option<(OffsetDateTime, Arc<Vec<competition_library::Model>>)>,
+    requests: HashMap<Uuid, Vec<OffsetDateTime>>,
 }
 
-impl ReauthStateStore {
-    pub fn insert(&mut self, token: String, session: ReauthSession) {
-        self.sessions.insert(token, session);
+impl CompetitionMatchStore {
+    /// 读取未过期的竞赛库快照。
+    pub fn snapshot(&self) -> Option<Arc<Vec<competition_library::Model>>> {
+        let expiry = OffsetDateTime::now_utc() - Duration::seconds(COMPETITION_CACHE_TTL_SECONDS);
+        self.snapshot
+            .as_ref()
+            .filter(|(loaded_at, _)| *loaded_at > expiry)
+            .map(|(_, items)| items.clone())
+    }
+
+    /// 写入竞赛库快照。
+    pub fn set_snapshot(&mut self, items: Arc<Vec<competition_library::Model>>) {
+        self.snapshot = Some((OffsetDateTime::now_utc(), items));
     }
 
-    pub fn take(&mut self, token: &str) -> Option<ReauthSession> {
-        self.evict_expired();
-        self.sessions.remove(token)
+    /// 竞赛库变更后清空快照。
+    pub fn invalidate(&mut self) {
+        self.snapshot = None;
     }
 
-    fn evict_expired(&mut self) {
-        let expiry = OffsetDateTime::now_utc() - Duration::seconds(REAUTH_TTL_SECONDS);
-        self.sessions.retain(|_, session| session.created_at > expiry);
+    /// 记录一次查询；超出限流返回 false。
+    pub fn allow_request(&mut self, user_id: Uuid) -> bool {
+        let now = OffsetDateTime::now_utc();
+        let window_start = now - Duration::seconds(SIMILAR_RATE_WINDOW_SECONDS);
+        self.requests.retain(|_, hits| {
+            hits.retain(|hit| *hit > window_start);
+            !hits.is_empty()
+        });
+        let hits = self.requests.entry(user_id).or_default();
+        if hits.len() >= SIMILAR_RATE_LIMIT {
+            return false;
+        }
+        hits.push(now);
+        true
     }
 }
 
@@ -140,24 +131,70 @@ pub struct AppState {
     pub db: DatabaseConnection,
     /// WebAuthn 实例。
     pub webauthn: Arc<Webauthn>,
-    /// Passkey 流程状态。
-    pub passkey_state: Arc<Mutex<PasskeyStateStore>>,
-    /// Passkey 重新验证状态。
-    pub reauth_passkey_state: Arc<Mutex<ReauthPasskeyStore>>,
-    /// 二次验证令牌状态。
-    pub reauth_state: Arc<Mutex<ReauthStateStore>>,
+    /// Passkey 挑战与二次验证令牌等认证流程状态。
+    pub auth_flows: Arc<AuthFlowStore>,
+    /// 会话令牌缓存。
+    pub session_cache: Arc<SessionCache>,
+    /// 竞赛库相似查询状态。
+    pub competition_match: Arc<Mutex<CompetitionMatchStore>>,
+    /// 文档核验接口限流状态。
+    pub verify_limiter: Arc<Mutex<VerifyRateLimiter>>,
+    /// 后台导入任务状态。
+    pub import_jobs: Arc<Mutex<ImportJobStore>>,
+    /// 运行时设置快照。
+    pub settings: Arc<SettingsStore>,
+    /// 服务启动时间，用于计算运行时长。
+    pub started_at: std::time::Instant,
+    /// 审核队列实时事件。
+    pub events: Arc<EventBus>,
+    /// LibreOffice 转换器，限制同时运行的进程数并终止超时进程。
+    pub pdf_converter: Arc<PdfConverter>,
+    /// 有新邮件写入发件箱时唤醒发送任务。
+    pub mail_wake: Arc<Notify>,
 }
 
 impl AppState {
     /// 创建应用共享状态。
     pub fn new(config: Arc<Config>, db: DatabaseConnection, webauthn: Webauthn) -> Result<Self, AppError> {
+        let pdf_converter = Arc::new(PdfConverter::from_config(&config));
+        let auth_flows = Arc::new(AuthFlowStore::new(config.auth_flow_store, db.clone()));
+        let session_cache = Arc::new(SessionCache::new(config.session_cache_ttl_seconds));
         Ok(Self {
             config,
             db,
             webauthn: Arc::new(webauthn),
-            passkey_state: Arc::new(Mutex::new(PasskeyStateStore::default())),
-            reauth_passkey_state: Arc::new(Mutex::new(ReauthPasskeyStore::default())),
-            reauth_state: Arc::new(Mutex::new(ReauthStateStore::default())),
+            auth_flows,
+            session_cache,
+            competition_match: Arc::new(Mutex::new(CompetitionMatchStore::default())),
+            verify_limiter: Arc::new(Mutex::new(VerifyRateLimiter::default())),
+            import_jobs: Arc::new(Mutex::new(ImportJobStore::default())),
+            settings: Arc::new(SettingsStore::default()),
+            started_at: std::time::Instant::now(),
+            events: Arc::new(EventBus::default()),
+            pdf_converter,
+            mail_wake: Arc::new(Notify::new()),
         })
     }
+
+    /// 当前生效的重置凭证交付方式（运行时设置优先于启动配置）。
+    pub fn reset_delivery(&self) -> ResetDelivery {
+        self.settings
+            .current()
+            .reset_delivery
+            .unwrap_or_else(|| self.config.reset_delivery.clone())
+    }
+
+    /// 当前生效的邮件配置（运行时设置的发件人名称优先于启动配置）。
+    pub fn mail_config(&self) -> Option<MailConfig> {
+        let mut mail = self.config.mail.clone()?;
+        if let Some(name) = self.settings.current().mail_from_name {
+            mail.from_name = Some(name);
+        }
+        Some(mail)
+    }
+
+    /// 用户的生效语言（个人偏好优先于部署默认语言）。
+    pub fn locale_for(&self, user: &users::Model) -> Locale {
+        resolve_locale(user.locale.as_deref(), self.config.locale)
+    }
 }
@@ -0,0 +1,348 @@
+//! 附件存储用量：上传前的学生/全局配额校验，以及按记录类型、院系与学生统计的空间占用。
+//!
+//! 统计以附件表登记的文件大小为准，已软删除的学生与记录的附件仍占用磁盘，同样计入。
+
+use std::collections::HashMap;
+
+use sea_orm::{
+    sea_query::{Expr, SimpleExpr},
+    ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, JoinType, Order, QueryFilter, QuerySelect,
+    RelationTrait,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    config::AttachmentConfig,
+    db::sum_as_i64,
+    entities::{attachments, students, Attachment, Student},
+    error::AppError,
+    routes::attachments::ATTACHMENT_KIND_EVIDENCE,
+};
+
+/// 用量排行默认返回的学生数。
+pub const DEFAULT_TOP_STUDENTS: u64 = 20;
+/// 用量排行最多返回的学生数。
+pub const MAX_TOP_STUDENTS: u64 = 100;
+
+/// 按分组统计的用量。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StorageUsageGroup {
+    /// 分组值（记录类型或院系）。
+    pub key: String,
+    /// 文件数。
+    pub files: i64,
+    /// 占用字节数。
+    pub bytes: i64,
+}
+
+/// 单个学生的用量。
+#[derive(Debug, Clone, Serialize)]
+pub struct StudentStorageUsage {
+    /// 学号。
+    pub student_no: String,
+    /// 姓名。
+    pub name: String,
+    /// 院系。
+    pub department: String,
+    /// 文件数。
+    pub files: i64,
+    /// 占用字节数。
+    pub bytes: i64,
+    /// 其中佐证材料占用的字节数（计入学生配额）。
+    pub evidence_bytes: i64,
+}
+
+/// 附件存储用量报告。
+#[derive(Debug, Serialize)]
+pub struct StorageUsageReport {
+    /// 文件总数。
+    pub total_files: i64,
+    /// 占用总字节数。
+    pub total_bytes: i64,
+    /// 全局配额（字节），未设置时为空。
+    pub max_total_bytes: Option<u64>,
+    /// 每名学生的配额（字节），未设置时为空。
+    pub max_student_bytes: Option<u64>,
+    /// 按记录类型统计，按占用降序。
+    pub by_record_type: Vec<StorageUsageGroup>,
+    /// 按学生所属院系统计，按占用降序。
+    pub by_department: Vec<StorageUsageGroup>,
+    /// 占用最多的学生。
+    pub top_students: Vec<StudentStorageUsage>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct GroupUsageRow {
+    key: String,
+    files: i64,
+    bytes: Option<i64>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct StudentUsageRow {
+    student_id: Uuid,
+    files: i64,
+    bytes: Option<i64>,
+    evidence_bytes: Option<i64>,
+}
+
+/// 校验本次上传后是否超出学生配额或全局配额；学生配额只统计本人上传的佐证材料。
+pub async fn ensure_storage_quota<C>(
+    db: &C,
+    policy: &AttachmentConfig,
+    student_id: Uuid,
+    incoming_bytes: u64,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    if let Some(limit) = policy.max_student_bytes {
+        let used = sum_size_bytes(
+            db,
+            Some(
+                attachments::Column::StudentId
+                    .eq(student_id)
+                    .and(attachments::Column::Kind.eq(ATTACHMENT_KIND_EVIDENCE)),
+            ),
+        )
+        .await?;
+        if used.saturating_add(incoming_bytes) > limit {
+            return Err(AppError::validation(&format!(
+                "storage quota exceeded: at most {limit} bytes of attachments per student ({used} bytes used)"
+            )));
+        }
+    }
+    if let Some(limit) = policy.max_total_bytes {
+        let used = sum_size_bytes(db, None).await?;
+        if used.saturating_add(incoming_bytes) > limit {
+            return Err(AppError::validation("storage quota exceeded: attachment storage is full"));
+        }
+    }
+    Ok(())
+}
+
+/// 统计附件存储用量。
+pub async fn storage_usage<C>(db: &C, policy: &AttachmentConfig, top: u64) -> Result<StorageUsageReport, AppError>
+where
+    C: ConnectionTrait,
+{
+    let backend = db.get_database_backend();
+    let size = || sum_as_i64(backend, Expr::col((attachments::Entity, attachments::Column::SizeBytes)));
+    let files = || Expr::col((attachments::Entity, attachments::Column::Id)).count();
+
+    let by_record_type = Attachment::find()
+        .select_only()
+        .column_as(attachments::Column::RecordType, "key")
+        .column_as(files(), "files")
+        .column_as(size(), "bytes")
+        .group_by(attachments::Column::RecordType)
+        .into_model::<GroupUsageRow>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let by_department = Attachment::find()
+        .select_only()
+        .column_as(students::Column::Department, "key")
+        .column_as(files(), "files")
+        .column_as(size(), "bytes")
+        .join(JoinType::InnerJoin, attachments::Relation::Student.def())
+        .group_by(students::Column::Department)
+        .into_model::<GroupUsageRow>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+
+    let evidence: SimpleExpr = Expr::case(
+        attachments::Column::Kind.eq(ATTACHMENT_KIND_EVIDENCE),
+        Expr::col((attachments::Entity, attachments::Column::SizeBytes)),
+    )
+    .finally(0)
+    .into();
+    let student_rows = Attachment::find()
+        .select_only()
+        .column(attachments::Column::StudentId)
+        .column_as(files(), "files")
+        .column_as(size(), "bytes")
+        .column_as(sum_as_i64(backend, evidence), "evidence_bytes")
+        .group_by(attachments::Column::StudentId)
+        .order_by(size(), Order::Desc)
+        .limit(top.min(MAX_TOP_STUDENTS))
+        .into_model::<StudentUsageRow>()
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let students: HashMap<Uuid, students::Model> = Student::find()
+        .filter(students::Column::Id.is_in(student_rows.iter().map(|row| row.student_id)))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|student| (student.id, student))
+        .collect();
+    let top_students = student_rows
+        .into_iter()
+        .filter_map(|row| {
+            let student = students.get(&row.student_id)?;
+            Some(StudentStorageUsage {
+                student_no: student.student_no.clone(),
+                name: student.name.clone(),
+                department: student.department.clone(),
+                files: row.files,
+                bytes: row.bytes.unwrap_or(0),
+                evidence_bytes: row.evidence_bytes.unwrap_or(0),
+            })
+        })
+        .collect();
+
+    let by_record_type = sorted_groups(by_record_type);
+    Ok(StorageUsageReport {
+        total_files: by_record_type.iter().map(|group| group.files).sum(),
+        total_bytes: by_record_type.iter().map(|group| group.bytes).sum(),
+        max_total_bytes: policy.max_total_bytes,
+        max_student_bytes: policy.max_student_bytes,
+        by_record_type,
+        by_department: sorted_groups(by_department),
+        top_students,
+    })
+}
+
+async fn sum_size_bytes<C>(db: &C, condition: Option<SimpleExpr>) -> Result<u64, AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut finder = Attachment::find().select_only().column_as(
+        sum_as_i64(db.get_database_backend(), Expr::col(attachments::Column::SizeBytes)),
+        "bytes",
+    );
+    if let Some(condition) = condition {
+        finder = finder.filter(condition);
+    }
+    let bytes = finder
+        .into_tuple::<Option<i64>>()
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .flatten()
+        .unwrap_or(0);
+    Ok(u64::try_from(bytes).unwrap_or(0))
+}
+
+fn sorted_groups(rows: Vec<GroupUsageRow>) -> Vec<StorageUsageGroup> {
+    let mut groups: Vec<StorageUsageGroup> = rows
+        .into_iter()
+        .map(|row| StorageUsageGroup {
+            key: row.key,
+            files: row.files,
+            bytes: row.bytes.unwrap_or(0),
+        })
+        .collect();
+    groups.sort_by(|left, right| right.bytes.cmp(&left.bytes).then_with(|| left.key.cmp(&right.key)));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use sea_orm::{ActiveModelTrait, Set};
+
+    use crate::services::test_support::memory_db;
+
+    async fn insert_student(db: &sea_orm::DatabaseConnection, student_no: &str, department: &str) -> Uuid {
+        let now = Utc::now();
+        students::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            student_no: Set(student_no.to_string()),
+            name: Set("张三".to_string()),
+            gender: Set("男".to_string()),
+            department: Set(department.to_string()),
+            major: Set("软件工程".to_string()),
+            class_name: Set("软工1班".to_string()),
+            phone: Set("13800000000".to_string()),
+            import_batch_id: Set(None),
+            is_deleted: Set(false),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(db)
+        .await
+        .unwrap()
+        .id
+    }
+
+    async fn insert_attachment(db: &sea_orm::DatabaseConnection, student_id: Uuid, kind: &str, size_bytes: i64) {
+        attachments::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            student_id: Set(student_id),
+            record_type: Set("contest".to_string()),
+            record_id: Set(Uuid::new_v4()),
+            original_name: Set("proof.pdf".to_string()),
+            stored_name: Set("/nonexistent/proof.pdf".to_string()),
+            mime_type: Set("application/pdf".to_string()),
+            kind: Set(kind.to_string()),
+            size_bytes: Set(size_bytes),
+            created_at: Set(Utc::now()),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn usage_groups_by_department_and_ranks_students() {
+        let db = memory_db().await;
+        let first = insert_student(&db, "2023001", "信息学院").await;
+        let second = insert_student(&db, "2023002", "化工学院").await;
+        insert_attachment(&db, first, "evidence", 300).await;
+        insert_attachment(&db, first, "signature", 100).await;
+        insert_attachment(&db, second, "evidence", 1000).await;
+
+        let report = storage_usage(&db, &AttachmentConfig::default(), 1).await.unwrap();
+        assert_eq!(report.total_files, 3);
+        assert_eq!(report.total_bytes, 1400);
+        assert_eq!(
+            report.by_department,
+            vec![
+                StorageUsageGroup {
+                    key: "化工学院".to_string(),
+                    files: 1,
+                    bytes: 1000,
+                },
+                StorageUsageGroup {
+                    key: "信息学院".to_string(),
+                    files: 2,
+                    bytes: 400,
+                },
+            ]
+        );
+        assert_eq!(report.top_students.len(), 1);
+        assert_eq!(report.top_students[0].student_no, "2023002");
+
+        let report = storage_usage(&db, &AttachmentConfig::default(), 10).await.unwrap();
+        assert_eq!(report.top_students[1].bytes, 400);
+        assert_eq!(report.top_students[1].evidence_bytes, 300);
+    }
+
+    #[tokio::test]
+    async fn quota_counts_student_evidence_and_global_usage() {
+        let db = memory_db().await;
+        let student = insert_student(&db, "2023001", "信息学院").await;
+        insert_attachment(&db, student, "evidence", 600).await;
+        insert_attachment(&db, student, "signature", 300).await;
+
+        let policy = AttachmentConfig {
+            max_student_bytes: Some(1000),
+            ..AttachmentConfig::default()
+        };
+        assert!(ensure_storage_quota(&db, &policy, student, 400).await.is_ok());
+        assert!(ensure_storage_quota(&db, &policy, student, 401).await.is_err());
+
+        let policy = AttachmentConfig {
+            max_total_bytes: Some(1000),
+            ..AttachmentConfig::default()
+        };
+        assert!(ensure_storage_quota(&db, &policy, student, 100).await.is_ok());
+        assert!(ensure_storage_quota(&db, &policy, student, 101).await.is_err());
+    }
+}
@@ -0,0 +1,326 @@
+//! 单个学生的个人数据导出包，用于个人信息查询申请与向其他校内系统迁移。
+//!
+//! 导出包包含学生档案、登录账号概要、全部记录（含已撤回）、自定义字段值、评论与附件元数据（不含审核签名）；
+//! ZIP 格式额外附带附件原始文件。
+
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        attachments, contest_records, form_field_values, record_comments, students, users,
+        volunteer_records, Attachment, ContestRecord, FormFieldValue, RecordComment, User,
+        VolunteerRecord,
+    },
+    error::AppError,
+    routes::attachments::ATTACHMENT_KIND_SIGNATURE,
+};
+
+/// 导出包格式版本，结构不兼容变更时递增。
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+/// ZIP 中导出包 JSON 的文件名。
+pub const BUNDLE_ENTRY: &str = "bundle.json";
+
+/// 学生个人数据导出包。
+#[derive(Debug, Serialize)]
+pub struct StudentBundle {
+    /// 导出包格式版本。
+    pub format_version: u32,
+    /// 生成时间。
+    pub generated_at: DateTime<Utc>,
+    /// 学生档案。
+    pub student: students::Model,
+    /// 登录账号概要（不含密码哈希等凭据）。
+    pub account: Option<BundleAccount>,
+    /// 竞赛记录（含已撤回）。
+    pub contest_records: Vec<contest_records::Model>,
+    /// 志愿服务记录（含已撤回）。
+    pub volunteer_records: Vec<volunteer_records::Model>,
+    /// 记录的自定义字段值。
+    pub field_values: Vec<form_field_values::Model>,
+    /// 记录评论。
+    pub comments: Vec<record_comments::Model>,
+    /// 附件元数据。
+    pub attachments: Vec<BundleAttachment>,
+}
+
+/// 导出包中的账号概要。
+#[derive(Debug, Serialize)]
+pub struct BundleAccount {
+    pub username: String,
+    pub display_name: String,
+    pub email: Option<String>,
+    pub is_active: bool,
+    pub allow_password_login: bool,
+    pub locale: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 导出包中的附件元数据。
+#[derive(Debug, Serialize)]
+pub struct BundleAttachment {
+    pub id: Uuid,
+    pub record_type: String,
+    pub record_id: Uuid,
+    pub original_name: String,
+    pub mime_type: String,
+    /// 附件类型（evidence/signature/template）。
+    pub kind: String,
+    pub created_at: DateTime<Utc>,
+    /// ZIP 中的文件路径。
+    pub file: String,
+    #[serde(skip)]
+    stored_name: String,
+}
+
+impl From<users::Model> for BundleAccount {
+    fn from(user: users::Model) -> Self {
+        Self {
+            username: user.username,
+            display_name: user.display_name,
+            email: user.email,
+            is_active: user.is_active,
+            allow_password_login: user.allow_password_login,
+            locale: user.locale,
+            created_at: user.created_at,
+        }
+    }
+}
+
+/// 汇总学生的全部个人数据。
+pub async fn build_student_bundle<C>(db: &C, student: students::Model) -> Result<StudentBundle, AppError>
+where
+    C: ConnectionTrait,
+{
+    let account = User::find()
+        .filter(users::Column::Username.eq(&student.student_no))
+        .filter(users::Column::Role.eq("student"))
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .map(BundleAccount::from);
+    let contest = ContestRecord::find()
+        .filter(contest_records::Column::StudentId.eq(student.id))
+        .order_by_asc(contest_records::Column::CreatedAt)
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let volunteer = VolunteerRecord::find()
+        .filter(volunteer_records::Column::StudentId.eq(student.id))
+        .order_by_asc(volunteer_records::Column::CreatedAt)
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let record_ids: Vec<Uuid> = contest
+        .iter()
+        .map(|record| record.id)
+        .chain(volunteer.iter().map(|record| record.id))
+        .collect();
+
+    let (field_values, comments) = if record_ids.is_empty() {
+        (Vec::new(), Vec::new())
+    } else {
+        let field_values = FormFieldValue::find()
+            .filter(form_field_values::Column::RecordId.is_in(record_ids.clone()))
+            .order_by_asc(form_field_values::Column::CreatedAt)
+            .all(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        let comments = RecordComment::find()
+            .filter(record_comments::Column::RecordId.is_in(record_ids))
+            .order_by_asc(record_comments::Column::CreatedAt)
+            .all(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?;
+        (field_values, comments)
+    };
+
+    let attachments = Attachment::find()
+        .filter(attachments::Column::StudentId.eq(student.id))
+        .filter(attachments::Column::Kind.ne(ATTACHMENT_KIND_SIGNATURE))
+        .order_by_asc(attachments::Column::CreatedAt)
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|attachment| BundleAttachment {
+            file: attachment_entry_name(attachment.id, &attachment.original_name),
+            id: attachment.id,
+            record_type: attachment.record_type,
+            record_id: attachment.record_id,
+            original_name: attachment.original_name,
+            mime_type: attachment.mime_type,
+            kind: attachment.kind,
+            created_at: attachment.created_at,
+            stored_name: attachment.stored_name,
+        })
+        .collect();
+
+    Ok(StudentBundle {
+        format_version: BUNDLE_FORMAT_VERSION,
+        generated_at: Utc::now(),
+        student,
+        account,
+        contest_records: contest,
+        volunteer_records: volunteer,
+        field_values,
+        comments,
+        attachments,
+    })
+}
+
+/// 附件在 ZIP 中的路径；以附件 ID 为前缀避免同名文件冲突。
+fn attachment_entry_name(id: Uuid, original_name: &str) -> String {
+    let name: String = original_name
+        .trim()
+        .chars()
+        .map(|ch| if matches!(ch, '/' | '\\' | ':' | '\0') { '_' } else { ch })
+        .collect();
+    format!("attachments/{id}-{name}")
+}
+
+/// 将导出包与附件文件写入临时 ZIP；文件已丢失的附件列在 `missing_files.txt` 中。
+pub fn write_bundle_zip(bundle: &StudentBundle) -> Result<std::fs::File, AppError> {
+    let file = tempfile::tempfile()
+        .map_err(|err| AppError::internal(&format!("create temp file failed: {err}")))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+
+    zip.start_file(BUNDLE_ENTRY, options).map_err(write_error)?;
+    serde_json::to_writer_pretty(&mut zip, bundle).map_err(write_error)?;
+    let mut missing = Vec::new();
+    for attachment in &bundle.attachments {
+        let Ok(mut source) = std::fs::File::open(Path::new(&attachment.stored_name)) else {
+            missing.push(attachment.file.clone());
+            continue;
+        };
+        zip.start_file(attachment.file.as_str(), options).map_err(write_error)?;
+        std::io::copy(&mut source, &mut zip).map_err(write_error)?;
+    }
+    if !missing.is_empty() {
+        zip.start_file("missing_files.txt", options).map_err(write_error)?;
+        zip.write_all(missing.join("\n").as_bytes()).map_err(write_error)?;
+    }
+    let mut file = zip.finish().map_err(write_error)?;
+    file.flush().map_err(write_error)?;
+    file.seek(SeekFrom::Start(0))
+        .map_err(|err| AppError::internal(&format!("rewind temp file failed: {err}")))?;
+    Ok(file)
+}
+
+fn write_error(err: impl std::fmt::Display) -> AppError {
+    AppError::internal(&format!("write bundle failed: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    use sea_orm::{ActiveModelTrait, Set};
+
+    use crate::services::test_support::memory_db;
+
+    async fn insert_student(db: &sea_orm::DatabaseConnection) -> students::Model {
+        let now = Utc::now();
+        students::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            student_no: Set("2023001".to_string()),
+            name: Set("张三".to_string()),
+            gender: Set("男".to_string()),
+            department: Set("信息学院".to_string()),
+            major: Set("软件工程".to_string()),
+            class_name: Set("软工1班".to_string()),
+            phone: Set("13800000000".to_string()),
+            import_batch_id: Set(None),
+            is_deleted: Set(false),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(db)
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn entry_names_are_prefixed_and_sanitized() {
+        let id = Uuid::nil();
+        assert_eq!(
+            attachment_entry_name(id, " ../证书:1.pdf "),
+            format!("attachments/{id}-.._证书_1.pdf")
+        );
+    }
+
+    #[tokio::test]
+    async fn bundle_collects_records_and_lists_missing_files() {
+        let db = memory_db().await;
+        let student = insert_student(&db).await;
+        let now = Utc::now();
+        let record = volunteer_records::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            student_id: Set(student.id),
+            title: Set("社区服务".to_string()),
+            description: Set("整理图书".to_string()),
+            self_hours: Set(4),
+            first_review_hours: Set(None),
+            final_review_hours: Set(None),
+            status: Set("submitted".to_string()),
+            rejection_reason: Set(None),
+            semester_id: Set(None),
+            assigned_reviewer_id: Set(None),
+            version: Set(0),
+            is_deleted: Set(true),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        attachments::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            student_id: Set(student.id),
+            record_type: Set("volunteer".to_string()),
+            record_id: Set(record.id),
+            original_name: Set("proof.pdf".to_string()),
+            stored_name: Set("/nonexistent/proof.pdf".to_string()),
+            mime_type: Set("application/pdf".to_string()),
+            kind: Set("evidence".to_string()),
+            size_bytes: Set(0),
+            created_at: Set(now),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let bundle = build_student_bundle(&db, student).await.unwrap();
+        assert!(bundle.account.is_none());
+        assert_eq!(bundle.volunteer_records.len(), 1);
+        assert_eq!(bundle.attachments.len(), 1);
+
+        let file = write_bundle_zip(&bundle).unwrap();
+        let mut archive = zip::ZipArchive::new(file).unwrap();
+        let mut json = String::new();
+        archive
+            .by_name(BUNDLE_ENTRY)
+            .unwrap()
+            .read_to_string(&mut json)
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["student"]["student_no"], "2023001");
+        assert!(value["attachments"][0].get("stored_name").is_none());
+        let mut missing = String::new();
+        archive
+            .by_name("missing_files.txt")
+            .unwrap()
+            .read_to_string(&mut missing)
+            .unwrap();
+        assert_eq!(missing, bundle.attachments[0].file);
+    }
+}
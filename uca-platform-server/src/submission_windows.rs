@@ -0,0 +1,203 @@
+//! 记录提交时间窗口：按记录类型配置开放与截止时间，院系覆盖优先于默认窗口。
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    access::normalize_department,
+    entities::{submission_windows, SubmissionWindow},
+    error::AppError,
+};
+
+/// 可配置提交窗口的记录类型。
+pub const WINDOW_RECORD_TYPES: [&str; 2] = ["contest", "volunteer"];
+
+/// 提交窗口在给定时间的状态。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowPhase {
+    /// 尚未开放。
+    Upcoming,
+    /// 开放中。
+    Open,
+    /// 已截止。
+    Closed,
+}
+
+/// 校验记录类型。
+pub fn validate_record_type(record_type: &str) -> Result<(), AppError> {
+    if WINDOW_RECORD_TYPES.contains(&record_type) {
+        Ok(())
+    } else {
+        Err(AppError::validation(
+            "record_type must be contest or volunteer",
+        ))
+    }
+}
+
+/// 校验窗口时间范围。
+pub fn validate_window_range(
+    opens_at: DateTime<Utc>,
+    closes_at: DateTime<Utc>,
+) -> Result<(), AppError> {
+    if opens_at >= closes_at {
+        return Err(AppError::validation("opens_at must be before closes_at"));
+    }
+    Ok(())
+}
+
+/// 计算窗口在给定时间的状态（起止时间均包含在内）。
+pub fn window_phase(window: &submission_windows::Model, now: DateTime<Utc>) -> WindowPhase {
+    if now < window.opens_at {
+        WindowPhase::Upcoming
+    } else if now > window.closes_at {
+        WindowPhase::Closed
+    } else {
+        WindowPhase::Open
+    }
+}
+
+/// 从同一记录类型的窗口中选出适用的一条：院系覆盖优先，其次为默认窗口。
+pub fn select_window<'a>(
+    windows: &'a [submission_windows::Model],
+    department: Option<&str>,
+) -> Option<&'a submission_windows::Model> {
+    let department = normalize_department(department);
+    department
+        .as_deref()
+        .and_then(|department| {
+            windows
+                .iter()
+                .find(|window| window.department.as_deref() == Some(department))
+        })
+        .or_else(|| windows.iter().find(|window| window.department.is_none()))
+}
+
+/// 读取记录类型在指定院系适用的提交窗口；未配置时返回 `None`（不限制）。
+pub async fn load_window<C>(
+    db: &C,
+    record_type: &str,
+    department: Option<&str>,
+) -> Result<Option<submission_windows::Model>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let windows = SubmissionWindow::find()
+        .filter(submission_windows::Column::RecordType.eq(record_type))
+        .all(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(select_window(&windows, department).cloned())
+}
+
+/// 确认当前允许提交指定类型的记录，窗口外返回 400 并给出开放或截止时间。
+pub async fn ensure_window_open<C>(
+    db: &C,
+    record_type: &str,
+    department: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    let Some(window) = load_window(db, record_type, department).await? else {
+        return Ok(());
+    };
+    match window_phase(&window, now) {
+        WindowPhase::Open => Ok(()),
+        WindowPhase::Upcoming => Err(AppError::bad_request(&format!(
+            "submission window closed: {record_type} submissions open at {}",
+            window.opens_at.to_rfc3339()
+        ))),
+        WindowPhase::Closed => Err(AppError::bad_request(&format!(
+            "submission window closed: {record_type} submissions closed at {}",
+            window.closes_at.to_rfc3339()
+        ))),
+    }
+}
+
+/// 确认同一记录类型与院系下不存在其他窗口。
+pub async fn ensure_window_unique<C>(
+    db: &C,
+    record_type: &str,
+    department: Option<&str>,
+    exclude_id: Option<Uuid>,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    let mut finder =
+        SubmissionWindow::find().filter(submission_windows::Column::RecordType.eq(record_type));
+    finder = match department {
+        Some(department) => finder.filter(submission_windows::Column::Department.eq(department)),
+        None => finder.filter(submission_windows::Column::Department.is_null()),
+    };
+    if let Some(id) = exclude_id {
+        finder = finder.filter(submission_windows::Column::Id.ne(id));
+    }
+    let exists = finder
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if exists.is_some() {
+        return Err(AppError::bad_request("submission window exists"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn window(
+        department: Option<&str>,
+        opens_day: u32,
+        closes_day: u32,
+    ) -> submission_windows::Model {
+        let now = Utc::now();
+        submission_windows::Model {
+            id: Uuid::new_v4(),
+            record_type: "contest".to_string(),
+            department: department.map(str::to_string),
+            opens_at: Utc.with_ymd_and_hms(2026, 9, opens_day, 0, 0, 0).unwrap(),
+            closes_at: Utc
+                .with_ymd_and_hms(2026, 9, closes_day, 23, 59, 59)
+                .unwrap(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn department_override_wins_and_bounds_are_inclusive() {
+        let windows = vec![window(None, 1, 10), window(Some("信息学院"), 5, 20)];
+        assert_eq!(
+            select_window(&windows, Some(" 信息学院 "))
+                .unwrap()
+                .department
+                .as_deref(),
+            Some("信息学院")
+        );
+        assert!(select_window(&windows, Some("机械学院"))
+            .unwrap()
+            .department
+            .is_none());
+        assert!(select_window(&windows, None).unwrap().department.is_none());
+        assert!(select_window(&windows[1..], None).is_none());
+
+        let default = &windows[0];
+        assert_eq!(window_phase(default, default.opens_at), WindowPhase::Open);
+        assert_eq!(window_phase(default, default.closes_at), WindowPhase::Open);
+        assert_eq!(
+            window_phase(default, default.opens_at - chrono::Duration::seconds(1)),
+            WindowPhase::Upcoming
+        );
+        assert_eq!(
+            window_phase(default, default.closes_at + chrono::Duration::seconds(1)),
+            WindowPhase::Closed
+        );
+    }
+}
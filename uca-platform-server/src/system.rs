@@ -0,0 +1,243 @@
+//! 系统运行信息（版本、数据库迁移、功能开关），供管理接口与 `status` 命令使用。
+
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend};
+use sea_orm_migration::MigratorTrait;
+use serde::Serialize;
+
+use crate::{
+    config::{Config, ResetDelivery},
+    error::AppError,
+    migration::Migrator,
+};
+
+/// 构建信息。
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    /// 版本号。
+    pub version: &'static str,
+    /// Git 提交。
+    pub git_commit: &'static str,
+    /// 编译所用 rustc 版本。
+    pub rustc_version: &'static str,
+}
+
+/// 数据库迁移状态。
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationInfo {
+    /// 数据库类型（sqlite/mysql/postgres）。
+    pub backend: &'static str,
+    /// 最近一次已执行的迁移。
+    pub latest_applied: Option<String>,
+    /// 已执行的迁移数量。
+    pub applied: usize,
+    /// 待执行的迁移数量。
+    pub pending: usize,
+}
+
+/// 文件存储信息。
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageInfo {
+    /// 存储方式（目前仅支持本地目录）。
+    pub backend: &'static str,
+    /// 上传目录。
+    pub upload_dir: String,
+}
+
+/// 功能开关。
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureFlags {
+    pub developer_mode: bool,
+    pub allow_http: bool,
+    pub mail_configured: bool,
+    pub reset_delivery: &'static str,
+    pub peer_stats_enabled: bool,
+    pub graphql_enabled: bool,
+    pub security_alerts: bool,
+    pub maintenance_mode: bool,
+    pub job_session_purge: bool,
+    pub job_token_cleanup: bool,
+    pub job_orphan_attachment_cleanup: bool,
+    pub job_student_user_repair: bool,
+}
+
+/// 系统运行信息。
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    /// 构建信息。
+    pub build: BuildInfo,
+    /// 服务运行时长（秒）；命令行查询时为空。
+    pub uptime_seconds: Option<u64>,
+    /// 数据库迁移状态。
+    pub database: MigrationInfo,
+    /// 文件存储信息。
+    pub storage: StorageInfo,
+    /// 功能开关。
+    pub features: FeatureFlags,
+}
+
+/// 当前二进制的构建信息。
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_commit: env!("UCA_GIT_COMMIT"),
+        rustc_version: env!("UCA_RUSTC_VERSION"),
+    }
+}
+
+/// 收集系统运行信息。
+pub async fn collect_system_info(
+    config: &Config,
+    db: &DatabaseConnection,
+    reset_delivery: &ResetDelivery,
+    maintenance_mode: bool,
+    uptime: Option<Duration>,
+) -> Result<SystemInfo, AppError> {
+    let applied = Migrator::get_applied_migrations(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let pending = Migrator::get_pending_migrations(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let backend = match db.get_database_backend() {
+        DbBackend::MySql => "mysql",
+        DbBackend::Postgres => "postgres",
+        DbBackend::Sqlite => "sqlite",
+    };
+
+    Ok(SystemInfo {
+        build: build_info(),
+        uptime_seconds: uptime.map(|value| value.as_secs()),
+        database: MigrationInfo {
+            backend,
+            latest_applied: applied.last().map(|migration| migration.name().to_string()),
+            applied: applied.len(),
+            pending: pending.len(),
+        },
+        storage: StorageInfo {
+            backend: "local",
+            upload_dir: config.upload_dir.display().to_string(),
+        },
+        features: FeatureFlags {
+            developer_mode: config.developer_mode,
+            allow_http: config.allow_http,
+            mail_configured: config.mail.is_some(),
+            reset_delivery: match reset_delivery {
+                ResetDelivery::Email => "email",
+                ResetDelivery::Code => "code",
+            },
+            peer_stats_enabled: config.peer_stats_enabled,
+            graphql_enabled: config.graphql_enabled,
+            security_alerts: config.security_alert_email.is_some() || config.security_alert_webhook.is_some(),
+            maintenance_mode,
+            job_session_purge: config.jobs.session_purge,
+            job_token_cleanup: config.jobs.token_cleanup,
+            job_orphan_attachment_cleanup: config.jobs.orphan_attachment_cleanup,
+            job_student_user_repair: config.jobs.student_user_repair,
+        },
+    })
+}
+
+/// 以适合终端阅读的格式输出系统信息。
+pub fn format_system_info(info: &SystemInfo) -> String {
+    let mut out = String::new();
+    let on_off = |value: bool| if value { "on" } else { "off" };
+    let _ = writeln!(out, "version:      {}", info.build.version);
+    let _ = writeln!(out, "git commit:   {}", info.build.git_commit);
+    let _ = writeln!(out, "rustc:        {}", info.build.rustc_version);
+    if let Some(uptime) = info.uptime_seconds {
+        let _ = writeln!(out, "uptime:       {}", format_uptime(uptime));
+    }
+    let _ = writeln!(out, "database:     {}", info.database.backend);
+    let _ = writeln!(
+        out,
+        "migrations:   {} applied, {} pending (latest: {})",
+        info.database.applied,
+        info.database.pending,
+        info.database.latest_applied.as_deref().unwrap_or("-")
+    );
+    let _ = writeln!(out, "storage:      {} ({})", info.storage.backend, info.storage.upload_dir);
+    let features = &info.features;
+    let _ = writeln!(out, "features:");
+    for (name, value) in [
+        ("developer_mode", on_off(features.developer_mode)),
+        ("allow_http", on_off(features.allow_http)),
+        ("mail", on_off(features.mail_configured)),
+        ("reset_delivery", features.reset_delivery),
+        ("peer_stats", on_off(features.peer_stats_enabled)),
+        ("graphql", on_off(features.graphql_enabled)),
+        ("security_alerts", on_off(features.security_alerts)),
+        ("maintenance_mode", on_off(features.maintenance_mode)),
+        ("job_session_purge", on_off(features.job_session_purge)),
+        ("job_token_cleanup", on_off(features.job_token_cleanup)),
+        ("job_orphan_attachment_cleanup", on_off(features.job_orphan_attachment_cleanup)),
+        ("job_student_user_repair", on_off(features.job_student_user_repair)),
+    ] {
+        let _ = writeln!(out, "  {name:<30} {value}");
+    }
+    out
+}
+
+fn format_uptime(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let hours = seconds % 86_400 / 3_600;
+    let minutes = seconds % 3_600 / 60;
+    if days > 0 {
+        format!("{days}d {hours}h {minutes}m")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m {}s", seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_uptime_picks_largest_units() {
+        assert_eq!(format_uptime(42), "0m 42s");
+        assert_eq!(format_uptime(3_725), "1h 2m");
+        assert_eq!(format_uptime(90_061), "1d 1h 1m");
+    }
+
+    #[test]
+    fn format_system_info_lists_versions_and_flags() {
+        let info = SystemInfo {
+            build: build_info(),
+            uptime_seconds: None,
+            database: MigrationInfo {
+                backend: "sqlite",
+                latest_applied: Some("m20261016_000011_user_departments".to_string()),
+                applied: 11,
+                pending: 0,
+            },
+            storage: StorageInfo {
+                backend: "local",
+                upload_dir: "data/uploads".to_string(),
+            },
+            features: FeatureFlags {
+                developer_mode: false,
+                allow_http: true,
+                mail_configured: false,
+                reset_delivery: "code",
+                peer_stats_enabled: true,
+                graphql_enabled: false,
+                security_alerts: false,
+                maintenance_mode: false,
+                job_session_purge: true,
+                job_token_cleanup: true,
+                job_orphan_attachment_cleanup: false,
+                job_student_user_repair: false,
+            },
+        };
+        let text = format_system_info(&info);
+        assert!(text.contains(env!("CARGO_PKG_VERSION")));
+        assert!(text.contains("11 applied, 0 pending (latest: m20261016_000011_user_departments)"));
+        assert!(text.contains("reset_delivery"));
+        assert!(!text.contains("uptime"));
+    }
+}
@@ -1,8 +1,7 @@
-//! 模板配置辅助函数（导入映射等）。
+//! 模板配置辅助函数（导入映射、导出模板登记等）。
 
 use std::collections::HashMap;
 
-use calamine::Data;
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, Set, ActiveModelTrait};
 use serde_json::Value;
 
@@ -12,6 +11,7 @@ use crate::{
         ImportTemplateField,
     },
     error::AppError,
+    i18n::Locale,
     state::AppState,
 };
 
@@ -38,8 +38,16 @@ pub struct ExportTemplateConfig {
     pub name: String,
     pub issues: Vec<String>,
     pub orientation: String,
+    pub display_name: String,
+    pub description: Option<String>,
+    pub export_type: String,
 }
 
+/// 内置导出模板 key（劳动教育学时认定表），无需登记即可使用。
+pub const BUILTIN_EXPORT_TEMPLATE_KEY: &str = "labor_hours";
+/// 可绑定导出模板的导出类型。
+pub const EXPORT_TEMPLATE_TYPES: [&str; 1] = ["labor_hours"];
+
 /// 读取导入模板配置（不存在时返回默认模板）。
 pub async fn load_import_template(
     state: &AppState,
@@ -75,7 +83,7 @@ pub async fn load_import_template(
         });
     }
 
-    Ok(default_import_template(template_key))
+    Ok(default_import_template(state.config.locale, template_key))
 }
 
 /// 读取导出模板配置（不存在时返回默认模板）。
@@ -83,23 +91,153 @@ pub async fn load_export_template(
     state: &AppState,
     template_key: &str,
 ) -> Result<ExportTemplateConfig, AppError> {
-    if let Some(template) = ExportTemplate::find()
-        .filter(export_templates::Column::TemplateKey.eq(template_key))
-        .one(&state.db)
+    Ok(find_export_template_model(state, template_key)
+        .await?
+        .map(export_template_from_model)
+        .unwrap_or_else(|| default_export_template(state.config.locale, template_key)))
+}
+
+/// 读取已登记（或内置）的导出模板；未登记或导出类型不符时返回 400。
+pub async fn require_export_template(
+    state: &AppState,
+    template_key: &str,
+    export_type: Option<&str>,
+) -> Result<ExportTemplateConfig, AppError> {
+    let template = match find_export_template_model(state, template_key).await? {
+        Some(model) => export_template_from_model(model),
+        None if template_key == BUILTIN_EXPORT_TEMPLATE_KEY => default_export_template(state.config.locale, template_key),
+        None => return Err(AppError::bad_request("unknown template key")),
+    };
+    if export_type.is_some_and(|value| value != template.export_type) {
+        return Err(AppError::bad_request("template does not match export type"));
+    }
+    Ok(template)
+}
+
+/// 列出全部导出模板（含尚未上传文件的内置模板），按 key 排序。
+pub async fn list_export_templates(state: &AppState) -> Result<Vec<ExportTemplateConfig>, AppError> {
+    let mut templates: Vec<ExportTemplateConfig> = ExportTemplate::find()
+        .all(&state.db)
         .await
         .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(export_template_from_model)
+        .collect();
+    if !templates
+        .iter()
+        .any(|template| template.template_key == BUILTIN_EXPORT_TEMPLATE_KEY)
     {
-        let issues = parse_export_template_issues(&template.layout_json);
-        let orientation = parse_export_template_orientation(&template.layout_json);
-        return Ok(ExportTemplateConfig {
-            template_key: template.template_key,
-            name: template.name,
-            issues,
-            orientation,
-        });
+        templates.push(default_export_template(state.config.locale, BUILTIN_EXPORT_TEMPLATE_KEY));
     }
+    templates.sort_by(|a, b| a.template_key.cmp(&b.template_key));
+    Ok(templates)
+}
 
-    Ok(default_export_template(template_key))
+/// 校验模板 key：1-64 位小写字母、数字、`_` 或 `-`（同时用作文件名）。
+pub fn validate_export_template_key(template_key: &str) -> Result<(), AppError> {
+    let valid = !template_key.is_empty()
+        && template_key.len() <= 64
+        && template_key
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_' || ch == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::validation("invalid template key"))
+    }
+}
+
+/// 登记新的导出模板（文件稍后上传）。
+pub async fn register_export_template(
+    state: &AppState,
+    template_key: &str,
+    display_name: String,
+    description: Option<String>,
+    export_type: String,
+) -> Result<ExportTemplateConfig, AppError> {
+    validate_export_template_key(template_key)?;
+    if !EXPORT_TEMPLATE_TYPES.contains(&export_type.as_str()) {
+        return Err(AppError::validation("unknown export type"));
+    }
+    if template_key == BUILTIN_EXPORT_TEMPLATE_KEY
+        || find_export_template_model(state, template_key).await?.is_some()
+    {
+        return Err(AppError::bad_request("template key already exists"));
+    }
+    let now = chrono::Utc::now();
+    let model = export_templates::ActiveModel {
+        id: Set(uuid::Uuid::new_v4()),
+        template_key: Set(template_key.to_string()),
+        name: Set(String::new()),
+        layout_json: Set(export_layout_json(&[], "portrait")?),
+        display_name: Set(display_name),
+        description: Set(description),
+        export_type: Set(export_type),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+    let model = model
+        .insert(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(export_template_from_model(model))
+}
+
+/// 更新导出模板的显示名称与说明（内置模板首次修改时补建记录）。
+pub async fn update_export_template_info(
+    state: &AppState,
+    template_key: &str,
+    display_name: String,
+    description: Option<String>,
+) -> Result<ExportTemplateConfig, AppError> {
+    let now = chrono::Utc::now();
+    let model = match find_export_template_model(state, template_key).await? {
+        Some(existing) => {
+            let mut active: export_templates::ActiveModel = existing.into();
+            active.display_name = Set(display_name);
+            active.description = Set(description);
+            active.updated_at = Set(now);
+            active.update(&state.db).await
+        }
+        None if template_key == BUILTIN_EXPORT_TEMPLATE_KEY => {
+            export_templates::ActiveModel {
+                id: Set(uuid::Uuid::new_v4()),
+                template_key: Set(template_key.to_string()),
+                name: Set(String::new()),
+                layout_json: Set(export_layout_json(&[], "portrait")?),
+                display_name: Set(display_name),
+                description: Set(description),
+                export_type: Set(BUILTIN_EXPORT_TEMPLATE_KEY.to_string()),
+                created_at: Set(now),
+                updated_at: Set(now),
+            }
+            .insert(&state.db)
+            .await
+        }
+        None => return Err(AppError::not_found("export template not found")),
+    }
+    .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(export_template_from_model(model))
+}
+
+/// 删除已登记的导出模板及其文件；内置模板不可删除。
+pub async fn delete_export_template(state: &AppState, template_key: &str) -> Result<(), AppError> {
+    if template_key == BUILTIN_EXPORT_TEMPLATE_KEY {
+        return Err(AppError::bad_request("builtin template cannot be deleted"));
+    }
+    let existing = find_export_template_model(state, template_key)
+        .await?
+        .ok_or_else(|| AppError::not_found("export template not found"))?;
+    ExportTemplate::delete_by_id(existing.id)
+        .exec(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    let path = export_template_file_path(state, template_key);
+    if path.exists() {
+        std::fs::remove_file(&path)
+            .map_err(|err| AppError::internal(&format!("remove template failed: {err}")))?;
+    }
+    Ok(())
 }
 
 /// 新增或更新导出模板配置（保存校验问题）。
@@ -111,78 +249,64 @@ pub async fn upsert_export_template_meta(
     orientation: String,
 ) -> Result<ExportTemplateConfig, AppError> {
     let now = chrono::Utc::now();
-    let layout_json = serde_json::to_string(&serde_json::json!({
-        "issues": issues,
-        "orientation": orientation
-    }))
-        .map_err(|_| AppError::bad_request("invalid export template meta"))?;
-    let parsed_issues = parse_export_template_issues(&layout_json);
-    let parsed_orientation = parse_export_template_orientation(&layout_json);
+    let layout_json = export_layout_json(&issues, &orientation)?;
 
-    if let Some(existing) = ExportTemplate::find()
-        .filter(export_templates::Column::TemplateKey.eq(template_key))
-        .one(&state.db)
-        .await
-        .map_err(|err| AppError::Database(err.to_string()))?
-    {
+    let model = if let Some(existing) = find_export_template_model(state, template_key).await? {
         let mut active: export_templates::ActiveModel = existing.into();
-        active.name = Set(name.clone());
-        active.layout_json = Set(layout_json.clone());
+        active.name = Set(name);
+        active.layout_json = Set(layout_json);
         active.updated_at = Set(now);
-        active
-            .update(&state.db)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?;
+        active.update(&state.db).await
     } else {
-        let model = export_templates::ActiveModel {
+        let defaults = default_export_template(state.config.locale, template_key);
+        export_templates::ActiveModel {
             id: Set(uuid::Uuid::new_v4()),
             template_key: Set(template_key.to_string()),
-            name: Set(name.clone()),
+            name: Set(name),
             layout_json: Set(layout_json),
+            display_name: Set(defaults.display_name),
+            description: Set(defaults.description),
+            export_type: Set(defaults.export_type),
             created_at: Set(now),
             updated_at: Set(now),
-        };
-        export_templates::Entity::insert(model)
-            .exec_without_returning(&state.db)
-            .await
-            .map_err(|err| AppError::Database(err.to_string()))?;
+        }
+        .insert(&state.db)
+        .await
     }
+    .map_err(|err| AppError::Database(err.to_string()))?;
 
-    Ok(ExportTemplateConfig {
-        template_key: template_key.to_string(),
-        name,
-        issues: parsed_issues,
-        orientation: parsed_orientation,
-    })
+    Ok(export_template_from_model(model))
 }
 
-/// 构建表头名称到列索引的映射。
-pub fn build_header_index(header_row: Option<&[Data]>) -> HashMap<String, usize> {
-    let mut header_index = HashMap::new();
-    if let Some(header_row) = header_row {
-        for (idx, cell) in header_row.iter().enumerate() {
-            let trimmed = cell.to_string().trim().to_string();
-            if !trimmed.is_empty() {
-                header_index.insert(trimmed, idx);
-            }
-        }
-    }
-    header_index
+async fn find_export_template_model(
+    state: &AppState,
+    template_key: &str,
+) -> Result<Option<export_templates::Model>, AppError> {
+    ExportTemplate::find()
+        .filter(export_templates::Column::TemplateKey.eq(template_key))
+        .one(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))
 }
 
-/// 根据表头名称读取单元格内容。
-pub fn read_cell_by_title(header_index: &HashMap<String, usize>, title: &str, row: &[Data]) -> String {
-    if let Some(idx) = header_index.get(title) {
-        return read_cell_by_index(*idx, row);
+fn export_template_from_model(template: export_templates::Model) -> ExportTemplateConfig {
+    ExportTemplateConfig {
+        issues: parse_export_template_issues(&template.layout_json),
+        orientation: parse_export_template_orientation(&template.layout_json),
+        template_key: template.template_key,
+        name: template.name,
+        display_name: template.display_name,
+        description: template.description,
+        export_type: template.export_type,
     }
-    String::new()
 }
 
-/// 根据列索引读取单元格内容。
-pub fn read_cell_by_index(idx: usize, row: &[Data]) -> String {
-    row.get(idx)
-        .map(|cell| cell.to_string().trim().to_string())
-        .unwrap_or_default()
+fn export_layout_json(issues: &[String], orientation: &str) -> Result<String, AppError> {
+    serde_json::to_string(&serde_json::json!({
+        "issues": issues,
+        "orientation": orientation
+    }))
+    .map_err(|_| AppError::bad_request("invalid export template meta"))
 }
 
 /// 将导入模板字段映射到表头索引。
@@ -207,45 +331,46 @@ pub fn map_import_fields(
     Ok(map)
 }
 
-fn default_import_template(template_key: &str) -> ImportTemplateConfig {
+/// 内置导入模板；名称、字段名与说明按部署语言生成，列标题保持与数据表头一致。
+fn default_import_template(locale: Locale, template_key: &str) -> ImportTemplateConfig {
     match template_key {
         "competition_library" => ImportTemplateConfig {
             template_key: template_key.to_string(),
-            name: "认可竞赛列表".to_string(),
+            name: locale.text("template.competition_library").to_string(),
             fields: vec![
                 ImportFieldConfig {
                     field_key: "contest_year".to_string(),
-                    label: "年份".to_string(),
+                    label: locale.text("template.field.year").to_string(),
                     column_title: "年份".to_string(),
                     required: false,
                     order_index: 1,
-                    description: Some("竞赛年份（可选）".to_string()),
+                    description: Some(locale.text("template.field.year.description").to_string()),
                 },
                 ImportFieldConfig {
                     field_key: "contest_category".to_string(),
-                    label: "竞赛类型".to_string(),
+                    label: locale.text("record.contest_category").to_string(),
                     column_title: "竞赛类型".to_string(),
                     required: true,
                     order_index: 2,
-                    description: Some("A/B 类".to_string()),
+                    description: Some(locale.text("template.field.category.description").to_string()),
                 },
                 ImportFieldConfig {
                     field_key: "contest_name".to_string(),
-                    label: "竞赛名称".to_string(),
+                    label: locale.text("record.contest_name").to_string(),
                     column_title: "竞赛名称".to_string(),
                     required: true,
                     order_index: 3,
-                    description: Some("标准竞赛名称".to_string()),
+                    description: Some(locale.text("template.field.contest_name.description").to_string()),
                 },
             ],
         },
         "students" => ImportTemplateConfig {
             template_key: template_key.to_string(),
-            name: "学生名单".to_string(),
+            name: locale.text("template.students").to_string(),
             fields: vec![
                 ImportFieldConfig {
                     field_key: "student_no".to_string(),
-                    label: "学号".to_string(),
+                    label: locale.text("field.student_no").to_string(),
                     column_title: "学号".to_string(),
                     required: true,
                     order_index: 1,
@@ -253,7 +378,7 @@ fn default_import_template(template_key: &str) -> ImportTemplateConfig {
                 },
                 ImportFieldConfig {
                     field_key: "name".to_string(),
-                    label: "姓名".to_string(),
+                    label: locale.text("field.name").to_string(),
                     column_title: "姓名".to_string(),
                     required: true,
                     order_index: 2,
@@ -261,7 +386,7 @@ fn default_import_template(template_key: &str) -> ImportTemplateConfig {
                 },
                 ImportFieldConfig {
                     field_key: "gender".to_string(),
-                    label: "性别".to_string(),
+                    label: locale.text("field.gender").to_string(),
                     column_title: "性别".to_string(),
                     required: false,
                     order_index: 3,
@@ -269,7 +394,7 @@ fn default_import_template(template_key: &str) -> ImportTemplateConfig {
                 },
                 ImportFieldConfig {
                     field_key: "department".to_string(),
-                    label: "院系".to_string(),
+                    label: locale.text("field.department").to_string(),
                     column_title: "院系".to_string(),
                     required: false,
                     order_index: 4,
@@ -277,7 +402,7 @@ fn default_import_template(template_key: &str) -> ImportTemplateConfig {
                 },
                 ImportFieldConfig {
                     field_key: "major".to_string(),
-                    label: "专业".to_string(),
+                    label: locale.text("field.major").to_string(),
                     column_title: "专业".to_string(),
                     required: false,
                     order_index: 5,
@@ -285,7 +410,7 @@ fn default_import_template(template_key: &str) -> ImportTemplateConfig {
                 },
                 ImportFieldConfig {
                     field_key: "class_name".to_string(),
-                    label: "班级".to_string(),
+                    label: locale.text("field.class_name").to_string(),
                     column_title: "班级".to_string(),
                     required: false,
                     order_index: 6,
@@ -293,7 +418,7 @@ fn default_import_template(template_key: &str) -> ImportTemplateConfig {
                 },
                 ImportFieldConfig {
                     field_key: "phone".to_string(),
-                    label: "手机号".to_string(),
+                    label: locale.text("field.phone").to_string(),
                     column_title: "手机号".to_string(),
                     required: false,
                     order_index: 7,
@@ -303,11 +428,11 @@ fn default_import_template(template_key: &str) -> ImportTemplateConfig {
         },
         _ => ImportTemplateConfig {
             template_key: template_key.to_string(),
-            name: "学生获奖情况清单".to_string(),
+            name: locale.text("template.contest_records").to_string(),
             fields: vec![
                 ImportFieldConfig {
                     field_key: "student_no".to_string(),
-                    label: "学号".to_string(),
+                    label: locale.text("field.student_no").to_string(),
                     column_title: "学号".to_string(),
                     required: true,
                     order_index: 1,
@@ -315,7 +440,7 @@ fn default_import_template(template_key: &str) -> ImportTemplateConfig {
                 },
                 ImportFieldConfig {
                     field_key: "contest_name".to_string(),
-                    label: "竞赛名称".to_string(),
+                    label: locale.text("record.contest_name").to_string(),
                     column_title: "竞赛名称".to_string(),
                     required: true,
                     order_index: 2,
@@ -323,15 +448,15 @@ fn default_import_template(template_key: &str) -> ImportTemplateConfig {
                 },
                 ImportFieldConfig {
                     field_key: "contest_level".to_string(),
-                    label: "竞赛级别".to_string(),
+                    label: locale.text("record.contest_level").to_string(),
                     column_title: "竞赛级别".to_string(),
                     required: true,
                     order_index: 3,
-                    description: Some("国家级/省级/校级".to_string()),
+                    description: Some(locale.text("template.field.contest_level.description").to_string()),
                 },
                 ImportFieldConfig {
                     field_key: "award_level".to_string(),
-                    label: "获奖等级".to_string(),
+                    label: locale.text("record.award_level").to_string(),
                     column_title: "获奖等级".to_string(),
                     required: true,
                     order_index: 4,
@@ -339,23 +464,23 @@ fn default_import_template(template_key: &str) -> ImportTemplateConfig {
                 },
                 ImportFieldConfig {
                     field_key: "contest_role".to_string(),
-                    label: "负责人/成员".to_string(),
+                    label: locale.text("template.field.contest_role").to_string(),
                     column_title: "角色".to_string(),
                     required: true,
                     order_index: 5,
-                    description: Some("负责人/成员".to_string()),
+                    description: Some(locale.text("template.field.contest_role.description").to_string()),
                 },
                 ImportFieldConfig {
                     field_key: "award_date".to_string(),
-                    label: "时间".to_string(),
+                    label: locale.text("template.field.award_date").to_string(),
                     column_title: "时间".to_string(),
                     required: false,
                     order_index: 6,
-                    description: Some("获奖时间".to_string()),
+                    description: Some(locale.text("record.award_date").to_string()),
                 },
                 ImportFieldConfig {
                     field_key: "self_hours".to_string(),
-                    label: "自评学时".to_string(),
+                    label: locale.text("record.self_hours").to_string(),
                     column_title: "自评学时".to_string(),
                     required: true,
                     order_index: 7,
@@ -366,12 +491,19 @@ fn default_import_template(template_key: &str) -> ImportTemplateConfig {
     }
 }
 
-fn default_export_template(template_key: &str) -> ExportTemplateConfig {
+fn default_export_template(locale: Locale, template_key: &str) -> ExportTemplateConfig {
     ExportTemplateConfig {
         template_key: template_key.to_string(),
         name: String::new(),
         issues: Vec::new(),
         orientation: "portrait".to_string(),
+        display_name: if template_key == BUILTIN_EXPORT_TEMPLATE_KEY {
+            locale.text("template.labor_hours").to_string()
+        } else {
+            template_key.to_string()
+        },
+        description: None,
+        export_type: BUILTIN_EXPORT_TEMPLATE_KEY.to_string(),
     }
 }
 
@@ -416,25 +548,9 @@ pub fn export_template_file_path(state: &AppState, template_key: &str) -> std::p
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::spreadsheet::build_header_index;
     use calamine::Data;
 
-    #[test]
-    fn build_header_index_maps_columns() {
-        let header = vec![Data::String("学号".into()), Data::String("竞赛名称".into())];
-        let index = build_header_index(Some(&header));
-        assert_eq!(index.get("学号"), Some(&0));
-        assert_eq!(index.get("竞赛名称"), Some(&1));
-    }
-
-    #[test]
-    fn read_cell_by_title_uses_header_index() {
-        let header = vec![Data::String("学号".into()), Data::String("竞赛名称".into())];
-        let index = build_header_index(Some(&header));
-        let row = vec![Data::String("2023001".into()), Data::String("竞赛A".into())];
-        let value = read_cell_by_title(&index, "竞赛名称", &row);
-        assert_eq!(value, "竞赛A");
-    }
-
     #[test]
     fn map_import_fields_requires_headers() {
         let header = vec![Data::String("学号".into())];
@@ -460,4 +576,25 @@ mod tests {
         let result = map_import_fields(&index, &fields);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn validate_export_template_key_restricts_characters() {
+        assert!(validate_export_template_key("class_summary").is_ok());
+        assert!(validate_export_template_key("college-report-2025").is_ok());
+        assert!(validate_export_template_key("").is_err());
+        assert!(validate_export_template_key("../labor_hours").is_err());
+        assert!(validate_export_template_key("Class").is_err());
+        assert!(validate_export_template_key(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn default_import_template_localizes_labels_but_keeps_column_titles() {
+        let template = default_import_template(Locale::EnUs, "students");
+        assert_eq!(template.name, "Student roster");
+        assert_eq!(template.fields[0].label, "Student No.");
+        assert_eq!(template.fields[0].column_title, "学号");
+        let template = default_import_template(Locale::ZhCn, "contest_records");
+        assert_eq!(template.fields[4].label, "负责人/成员");
+        assert_eq!(template.fields[4].column_title, "角色");
+    }
 }
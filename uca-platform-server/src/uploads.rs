@@ -0,0 +1,108 @@
+//! 上传请求的大小限制：逐块读取或落盘 multipart 文件字段，并统一 413 错误格式。
+
+use std::io::{Seek, SeekFrom};
+
+use axum::{
+    extract::{multipart::Field, multipart::MultipartError, Request},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tokio::io::AsyncWriteExt;
+
+use crate::error::AppError;
+
+/// 将 multipart 解析错误转换为应用错误，请求体超出上限时返回 413。
+pub fn multipart_error(err: MultipartError) -> AppError {
+    if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        AppError::payload_too_large("request body too large")
+    } else {
+        AppError::bad_request("invalid multipart")
+    }
+}
+
+/// 逐块读取文件字段到内存，超过 `max_bytes` 时立即返回 413。
+pub async fn read_file_field(mut field: Field<'_>, max_bytes: usize) -> Result<Vec<u8>, AppError> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(field_read_error)? {
+        ensure_within_limit(bytes.len(), chunk.len(), max_bytes)?;
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// 将文件字段逐块写入临时文件（已回到文件开头），超过 `max_bytes` 时立即返回 413。
+pub async fn spool_file_field(
+    mut field: Field<'_>,
+    max_bytes: usize,
+) -> Result<std::fs::File, AppError> {
+    let file = tempfile::tempfile()
+        .map_err(|err| AppError::internal(&format!("create temp file failed: {err}")))?;
+    let mut file = tokio::fs::File::from_std(file);
+    let mut size = 0usize;
+    while let Some(chunk) = field.chunk().await.map_err(field_read_error)? {
+        ensure_within_limit(size, chunk.len(), max_bytes)?;
+        size += chunk.len();
+        file.write_all(&chunk)
+            .await
+            .map_err(|err| AppError::internal(&format!("write temp file failed: {err}")))?;
+    }
+    file.flush()
+        .await
+        .map_err(|err| AppError::internal(&format!("write temp file failed: {err}")))?;
+    let mut file = file.into_std().await;
+    file.seek(SeekFrom::Start(0))
+        .map_err(|err| AppError::internal(&format!("rewind temp file failed: {err}")))?;
+    Ok(file)
+}
+
+/// 将请求体上限触发的纯文本 413 响应改写为统一的 JSON 错误格式。
+pub async fn structured_payload_limit(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE || is_json(&response) {
+        return response;
+    }
+    AppError::payload_too_large("request body too large").into_response()
+}
+
+/// 将读取文件字段内容时的错误转换为应用错误，请求体超出上限时返回 413。
+pub fn field_read_error(err: MultipartError) -> AppError {
+    if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        AppError::payload_too_large("request body too large")
+    } else {
+        AppError::bad_request("failed to read file")
+    }
+}
+
+fn ensure_within_limit(current: usize, incoming: usize, max_bytes: usize) -> Result<(), AppError> {
+    if current.saturating_add(incoming) > max_bytes {
+        return Err(AppError::payload_too_large(&format!(
+            "file exceeds {max_bytes} bytes"
+        )));
+    }
+    Ok(())
+}
+
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_guard_rejects_oversized_chunks() {
+        assert!(ensure_within_limit(0, 10, 10).is_ok());
+        assert!(ensure_within_limit(6, 4, 10).is_ok());
+        assert!(matches!(
+            ensure_within_limit(6, 5, 10),
+            Err(AppError::PayloadTooLarge(_))
+        ));
+        assert!(ensure_within_limit(usize::MAX, 1, usize::MAX).is_err());
+    }
+}
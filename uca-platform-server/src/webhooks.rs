@@ -0,0 +1,569 @@
+//! 出站 Webhook：将记录提交、审核与学生导入事件以带签名的 JSON 推送给已登记的地址。
+//!
+//! 事件先写入投递日志再发送，失败按指数退避重试。事件来自进程内总线，
+//! 多实例部署时每个实例只投递本实例产生的事件。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use serde::Serialize;
+use sha2::Sha256;
+use tokio::sync::{broadcast::error::RecvError, Notify};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    auth::decrypt_secret,
+    entities::{
+        webhook_deliveries, webhooks, ContestRecord, Student, VolunteerRecord, Webhook,
+        WebhookDelivery,
+    },
+    error::AppError,
+    events::{QueueEvent, EVENT_RECORDS_IMPORTED, EVENT_RECORD_REVIEWED, EVENT_RECORD_SUBMITTED},
+    services::review::{STATUS_FINAL_REVIEWED, STATUS_FIRST_REVIEWED, STATUS_REJECTED},
+    state::AppState,
+};
+
+pub const WEBHOOK_RECORD_SUBMITTED: &str = "record_submitted";
+pub const WEBHOOK_RECORD_APPROVED: &str = "record_approved";
+pub const WEBHOOK_RECORD_REJECTED: &str = "record_rejected";
+pub const WEBHOOK_STUDENTS_IMPORTED: &str = "students_imported";
+
+/// 可订阅的全部事件。
+pub const WEBHOOK_EVENTS: [&str; 4] = [
+    WEBHOOK_RECORD_SUBMITTED,
+    WEBHOOK_RECORD_APPROVED,
+    WEBHOOK_RECORD_REJECTED,
+    WEBHOOK_STUDENTS_IMPORTED,
+];
+
+pub const DELIVERY_PENDING: &str = "pending";
+pub const DELIVERY_SUCCEEDED: &str = "succeeded";
+pub const DELIVERY_FAILED: &str = "failed";
+
+/// 签名请求头，值为 `sha256=<hex>`。
+pub const SIGNATURE_HEADER: &str = "X-UCA-Signature";
+/// 签名时间戳请求头（Unix 秒）。
+pub const TIMESTAMP_HEADER: &str = "X-UCA-Timestamp";
+pub const EVENT_HEADER: &str = "X-UCA-Event";
+pub const DELIVERY_HEADER: &str = "X-UCA-Delivery";
+
+/// 单次投递最多尝试次数，超过后标记为失败。
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 5;
+/// 首次重试等待时间，之后每次翻倍。
+const RETRY_BASE_SECONDS: i64 = 30;
+/// 重试等待时间上限。
+const RETRY_MAX_SECONDS: i64 = 3600;
+/// 定时扫描待重试投递的间隔。
+const DISPATCH_INTERVAL_SECONDS: u64 = 15;
+/// 单次请求超时。
+const DELIVERY_TIMEOUT_SECONDS: u64 = 10;
+/// 每轮最多处理的投递数。
+const DUE_BATCH_SIZE: u64 = 50;
+/// 投递日志中错误信息的最大字符数。
+const MAX_ERROR_CHARS: usize = 500;
+/// 回调地址最大长度。
+pub const MAX_WEBHOOK_URL_LEN: usize = 2048;
+
+/// 推送给订阅方的请求体。
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload {
+    /// 投递 ID，与 `X-UCA-Delivery` 一致，可用于去重。
+    pub id: Uuid,
+    /// 事件名称。
+    pub event: &'static str,
+    /// 事件发生时间。
+    pub occurred_at: DateTime<Utc>,
+    /// 事件数据。
+    pub data: WebhookEventData,
+}
+
+/// 事件数据。
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookEventData {
+    /// 记录类型（contest/volunteer/student）。
+    pub record_type: &'static str,
+    /// 记录 ID（导入事件为空）。
+    pub record_id: Option<Uuid>,
+    /// 记录当前状态。
+    pub status: Option<String>,
+    /// 学生所属院系。
+    pub department: Option<String>,
+    /// 学号。
+    pub student_no: Option<String>,
+    /// 学生姓名。
+    pub student_name: Option<String>,
+    /// 涉及的记录数。
+    pub count: usize,
+}
+
+/// 将审核队列事件映射为 Webhook 事件；初审与复审通过都视为 `record_approved`。
+pub fn webhook_event(event: &QueueEvent) -> Option<&'static str> {
+    match event.kind {
+        EVENT_RECORD_SUBMITTED => Some(WEBHOOK_RECORD_SUBMITTED),
+        EVENT_RECORD_REVIEWED => match event.status.as_deref() {
+            Some(STATUS_REJECTED) => Some(WEBHOOK_RECORD_REJECTED),
+            Some(STATUS_FIRST_REVIEWED | STATUS_FINAL_REVIEWED) => Some(WEBHOOK_RECORD_APPROVED),
+            _ => None,
+        },
+        EVENT_RECORDS_IMPORTED if event.record_type == "student" => Some(WEBHOOK_STUDENTS_IMPORTED),
+        _ => None,
+    }
+}
+
+/// 校验并去重订阅事件，返回按固定顺序排列的事件列表。
+pub fn normalize_events(events: &[String]) -> Result<Vec<&'static str>, AppError> {
+    for event in events {
+        if !WEBHOOK_EVENTS.iter().any(|name| *name == event.trim()) {
+            return Err(AppError::validation(&format!(
+                "unknown webhook event: {event}"
+            )));
+        }
+    }
+    let selected: Vec<&'static str> = WEBHOOK_EVENTS
+        .into_iter()
+        .filter(|name| events.iter().any(|event| event.trim() == *name))
+        .collect();
+    if selected.is_empty() {
+        return Err(AppError::validation(
+            "webhook must subscribe to at least one event",
+        ));
+    }
+    Ok(selected)
+}
+
+/// 解析数据库中逗号分隔的订阅事件。
+pub fn subscribed_events(stored: &str) -> Vec<String> {
+    stored
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 校验回调地址，仅允许 http/https。
+pub fn validate_webhook_url(raw: &str) -> Result<String, AppError> {
+    let trimmed = raw.trim();
+    if trimmed.len() > MAX_WEBHOOK_URL_LEN {
+        return Err(AppError::validation("webhook url is too long"));
+    }
+    let url = Url::parse(trimmed).map_err(|_| AppError::validation("invalid webhook url"))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(AppError::validation("webhook url must be http or https"));
+    }
+    Ok(url.to_string())
+}
+
+/// 计算签名：对 `<timestamp>.<body>` 做 HMAC-SHA256。
+pub fn sign_payload(secret: &[u8], timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("hmac accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// 第 `attempts` 次失败后的重试等待时间。
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    let seconds = RETRY_BASE_SECONDS
+        .saturating_mul(2i64.saturating_pow(exponent))
+        .min(RETRY_MAX_SECONDS);
+    chrono::Duration::seconds(seconds)
+}
+
+/// 启动 Webhook 投递：一个任务把事件写入投递日志，另一个任务发送并重试。
+pub fn spawn_webhook_dispatcher(state: AppState) {
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(DELIVERY_TIMEOUT_SECONDS))
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::warn!(error = %err, "build webhook client failed");
+            return;
+        }
+    };
+    let wake = Arc::new(Notify::new());
+
+    let mut receiver = state.events.subscribe();
+    let enqueue_state = state.clone();
+    let enqueue_wake = wake.clone();
+    tokio::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => match enqueue_event(&enqueue_state, &event).await {
+                    Ok(0) => {}
+                    Ok(_) => enqueue_wake.notify_one(),
+                    Err(err) => tracing::warn!(error = %err, "enqueue webhook deliveries failed"),
+                },
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "webhook dispatcher dropped queue events");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(DISPATCH_INTERVAL_SECONDS));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = wake.notified() => {}
+            }
+            if let Err(err) = deliver_due_webhooks(&state, &client).await {
+                tracing::warn!(error = %err, "webhook delivery round failed");
+            }
+        }
+    });
+}
+
+/// 为订阅了该事件的启用中 Webhook 写入待投递记录，返回写入条数。
+pub async fn enqueue_event(state: &AppState, event: &QueueEvent) -> Result<usize, AppError> {
+    let Some(name) = webhook_event(event) else {
+        return Ok(0);
+    };
+    let targets: Vec<webhooks::Model> = Webhook::find()
+        .filter(webhooks::Column::IsActive.eq(true))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .filter(|hook| {
+            subscribed_events(&hook.events)
+                .iter()
+                .any(|value| value == name)
+        })
+        .collect();
+    if targets.is_empty() {
+        return Ok(0);
+    }
+
+    let student = match event.record_id {
+        Some(record_id) => load_record_student(&state.db, event.record_type, record_id).await?,
+        None => None,
+    };
+    let data = WebhookEventData {
+        record_type: event.record_type,
+        record_id: event.record_id,
+        status: event.status.clone(),
+        department: event
+            .department
+            .clone()
+            .or_else(|| student.as_ref().map(|student| student.department.clone())),
+        student_no: student.as_ref().map(|student| student.student_no.clone()),
+        student_name: student.as_ref().map(|student| student.name.clone()),
+        count: event.count,
+    };
+    let now = Utc::now();
+    let mut deliveries = Vec::with_capacity(targets.len());
+    for hook in &targets {
+        let id = Uuid::new_v4();
+        let payload = serde_json::to_string(&WebhookPayload {
+            id,
+            event: name,
+            occurred_at: event.at,
+            data: data.clone(),
+        })
+        .map_err(|err| AppError::internal(&format!("serialize webhook payload failed: {err}")))?;
+        deliveries.push(webhook_deliveries::ActiveModel {
+            id: Set(id),
+            webhook_id: Set(hook.id),
+            event: Set(name.to_string()),
+            payload: Set(payload),
+            status: Set(DELIVERY_PENDING.to_string()),
+            attempts: Set(0),
+            response_status: Set(None),
+            last_error: Set(None),
+            next_attempt_at: Set(now),
+            created_at: Set(now),
+            delivered_at: Set(None),
+        });
+    }
+    let count = deliveries.len();
+    WebhookDelivery::insert_many(deliveries)
+        .exec_without_returning(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(count)
+}
+
+/// 发送所有到期的待投递记录，返回本轮成功的条数。
+pub async fn deliver_due_webhooks(
+    state: &AppState,
+    client: &reqwest::Client,
+) -> Result<u64, AppError> {
+    let due = WebhookDelivery::find()
+        .filter(webhook_deliveries::Column::Status.eq(DELIVERY_PENDING))
+        .filter(webhook_deliveries::Column::NextAttemptAt.lte(Utc::now()))
+        .order_by_asc(webhook_deliveries::Column::NextAttemptAt)
+        .limit(DUE_BATCH_SIZE)
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    if due.is_empty() {
+        return Ok(0);
+    }
+    let mut webhook_ids: Vec<Uuid> = due.iter().map(|delivery| delivery.webhook_id).collect();
+    webhook_ids.sort();
+    webhook_ids.dedup();
+    let hooks: HashMap<Uuid, webhooks::Model> = Webhook::find()
+        .filter(webhooks::Column::Id.is_in(webhook_ids))
+        .all(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?
+        .into_iter()
+        .map(|hook| (hook.id, hook))
+        .collect();
+
+    let mut succeeded = 0;
+    for delivery in due {
+        let outcome = match hooks
+            .get(&delivery.webhook_id)
+            .filter(|hook| hook.is_active)
+        {
+            Some(hook) => send_delivery(state, client, hook, &delivery).await,
+            None => DeliveryOutcome::abandoned("webhook disabled or deleted"),
+        };
+        if outcome.success {
+            succeeded += 1;
+        }
+        record_outcome(state, delivery, outcome).await?;
+    }
+    Ok(succeeded)
+}
+
+/// 单次发送结果。
+struct DeliveryOutcome {
+    success: bool,
+    /// 不再重试（如 Webhook 已停用）。
+    abandon: bool,
+    response_status: Option<i32>,
+    error: Option<String>,
+}
+
+impl DeliveryOutcome {
+    fn abandoned(reason: &str) -> Self {
+        Self {
+            success: false,
+            abandon: true,
+            response_status: None,
+            error: Some(reason.to_string()),
+        }
+    }
+}
+
+async fn send_delivery(
+    state: &AppState,
+    client: &reqwest::Client,
+    hook: &webhooks::Model,
+    delivery: &webhook_deliveries::Model,
+) -> DeliveryOutcome {
+    let secret = match decrypt_secret(&hook.secret_enc, &state.config.secret_keyring()) {
+        Ok(secret) => secret,
+        Err(_) => return DeliveryOutcome::abandoned("webhook secret cannot be decrypted"),
+    };
+    let timestamp = Utc::now().timestamp();
+    let signature = sign_payload(&secret, timestamp, &delivery.payload);
+    let result = client
+        .post(&hook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, &delivery.event)
+        .header(DELIVERY_HEADER, delivery.id.to_string())
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, signature)
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+    match result {
+        Ok(response) => {
+            let status = response.status();
+            DeliveryOutcome {
+                success: status.is_success(),
+                abandon: false,
+                response_status: Some(i32::from(status.as_u16())),
+                error: (!status.is_success()).then(|| format!("unexpected status {status}")),
+            }
+        }
+        Err(err) => DeliveryOutcome {
+            success: false,
+            abandon: false,
+            response_status: None,
+            error: Some(err.to_string().chars().take(MAX_ERROR_CHARS).collect()),
+        },
+    }
+}
+
+async fn record_outcome(
+    state: &AppState,
+    delivery: webhook_deliveries::Model,
+    outcome: DeliveryOutcome,
+) -> Result<(), AppError> {
+    let now = Utc::now();
+    let attempts = delivery.attempts.saturating_add(1);
+    let status = if outcome.success {
+        DELIVERY_SUCCEEDED
+    } else if outcome.abandon || attempts >= MAX_DELIVERY_ATTEMPTS {
+        DELIVERY_FAILED
+    } else {
+        DELIVERY_PENDING
+    };
+    if !outcome.success {
+        tracing::warn!(
+            delivery_id = %delivery.id,
+            webhook_id = %delivery.webhook_id,
+            attempts,
+            error = outcome.error.as_deref().unwrap_or("-"),
+            "webhook delivery failed"
+        );
+    }
+    let mut active: webhook_deliveries::ActiveModel = delivery.into();
+    active.status = Set(status.to_string());
+    active.attempts = Set(attempts);
+    active.response_status = Set(outcome.response_status);
+    active.last_error = Set(outcome.error);
+    active.next_attempt_at = Set(now + retry_delay(attempts));
+    if outcome.success {
+        active.delivered_at = Set(Some(now));
+    }
+    active
+        .update(&state.db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))?;
+    Ok(())
+}
+
+async fn load_record_student<C>(
+    db: &C,
+    record_type: &str,
+    record_id: Uuid,
+) -> Result<Option<crate::entities::students::Model>, AppError>
+where
+    C: ConnectionTrait,
+{
+    let student_id = match record_type {
+        "contest" => ContestRecord::find_by_id(record_id)
+            .one(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .map(|record| record.student_id),
+        "volunteer" => VolunteerRecord::find_by_id(record_id)
+            .one(db)
+            .await
+            .map_err(|err| AppError::Database(err.to_string()))?
+            .map(|record| record.student_id),
+        _ => None,
+    };
+    let Some(student_id) = student_id else {
+        return Ok(None);
+    };
+    Student::find_by_id(student_id)
+        .one(db)
+        .await
+        .map_err(|err| AppError::Database(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_events_map_to_webhook_events() {
+        let submitted = QueueEvent::record(
+            EVENT_RECORD_SUBMITTED,
+            "contest",
+            Uuid::new_v4(),
+            "submitted",
+            None,
+        );
+        assert_eq!(webhook_event(&submitted), Some(WEBHOOK_RECORD_SUBMITTED));
+        let approved = QueueEvent::record(
+            EVENT_RECORD_REVIEWED,
+            "contest",
+            Uuid::new_v4(),
+            STATUS_FINAL_REVIEWED,
+            None,
+        );
+        assert_eq!(webhook_event(&approved), Some(WEBHOOK_RECORD_APPROVED));
+        let rejected = QueueEvent::record(
+            EVENT_RECORD_REVIEWED,
+            "volunteer",
+            Uuid::new_v4(),
+            STATUS_REJECTED,
+            None,
+        );
+        assert_eq!(webhook_event(&rejected), Some(WEBHOOK_RECORD_REJECTED));
+        assert_eq!(
+            webhook_event(&QueueEvent::imported("student", 3)),
+            Some(WEBHOOK_STUDENTS_IMPORTED)
+        );
+        assert_eq!(webhook_event(&QueueEvent::imported("contest", 3)), None);
+    }
+
+    #[test]
+    fn normalize_events_rejects_unknown_and_empty() {
+        let events = vec![
+            "record_rejected".to_string(),
+            "record_submitted".to_string(),
+            "record_rejected".to_string(),
+        ];
+        assert_eq!(
+            normalize_events(&events).unwrap(),
+            vec![WEBHOOK_RECORD_SUBMITTED, WEBHOOK_RECORD_REJECTED]
+        );
+        assert!(normalize_events(&["record_deleted".to_string()]).is_err());
+        assert!(normalize_events(&[]).is_err());
+        assert_eq!(
+            subscribed_events("record_submitted, students_imported"),
+            vec![
+                "record_submitted".to_string(),
+                "students_imported".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn signature_matches_known_hmac() {
+        // HMAC-SHA256("key", "The quick brown fox jumps over the lazy dog") 的公开测试向量。
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"key").unwrap();
+        mac.update(b"The quick brown fox jumps over the lazy dog");
+        assert_eq!(
+            hex::encode(mac.finalize().into_bytes()),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+        let signature = sign_payload(b"secret", 1700000000, "{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature, sign_payload(b"secret", 1700000000, "{}"));
+        assert_ne!(signature, sign_payload(b"secret", 1700000001, "{}"));
+    }
+
+    #[test]
+    fn retry_delay_grows_and_is_capped() {
+        assert_eq!(retry_delay(1), chrono::Duration::seconds(30));
+        assert_eq!(retry_delay(3), chrono::Duration::seconds(120));
+        assert_eq!(
+            retry_delay(20),
+            chrono::Duration::seconds(RETRY_MAX_SECONDS)
+        );
+    }
+
+    #[test]
+    fn webhook_urls_must_be_http() {
+        assert!(
+            validate_webhook_url("https://qyapi.weixin.qq.com/cgi-bin/webhook/send?key=abc")
+                .is_ok()
+        );
+        assert!(validate_webhook_url("ftp://example.com").is_err());
+        assert!(validate_webhook_url("not a url").is_err());
+    }
+}
@@ -2,7 +2,7 @@ use axum::{
     body::{to_bytes, Body},
     http::{header, Request, StatusCode},
 };
-use sea_orm::{ConnectionTrait, Database, DatabaseConnection, EntityTrait, Set};
+use sea_orm::{ColumnTrait, ConnectionTrait, Database, DatabaseConnection, EntityTrait, QueryFilter, Set};
 use sea_orm_migration::MigratorTrait;
 use serde_json::json;
 use std::sync::Arc;
@@ -11,14 +11,18 @@ use tower::util::ServiceExt;
 use url::Url;
 use uuid::Uuid;
 use ucaplatform::{
-    auth::{encrypt_secret, generate_recovery_codes, generate_session_token, generate_totp, hash_session_token},
+    auth::{
+        encrypt_secret, generate_recovery_codes, generate_session_token, generate_totp,
+        hash_session_token, verify_password,
+    },
     config::Config,
     entities::{
-        recovery_codes, sessions, students, totp_secrets, users,
+        devices, recovery_codes, sessions, students, totp_secrets, user_signatures, users,
     },
+    i18n::Locale,
     migration::Migrator,
     routes,
-    state::AppState,
+    state::{AppState, ReauthSession},
 };
 use webauthn_rs::prelude::WebauthnBuilder;
 
@@ -39,21 +43,46 @@ async fn setup_context() -> TestContext {
         database_url: database_url(),
         rp_id: "localhost".to_string(),
         rp_origin: Url::parse("http://localhost:8443").unwrap(),
+        rp_extra_origins: Vec::new(),
+        rp_allow_subdomains: false,
+        cors_allowed_origins: Vec::new(),
         base_url: Some(Url::parse("http://localhost:5173").unwrap()),
+        verify_base_url: None,
+        locale: Locale::ZhCn,
         tls_cert_path: "data/tls/cert.pem".into(),
         tls_key_path: "data/tls/key.enc".into(),
         tls_import_cert_path: None,
         tls_import_key_path: None,
         tls_key_enc_key: vec![0u8; 32],
         upload_dir: "data/uploads".into(),
+        attachments: ucaplatform::config::AttachmentConfig::default(),
         libreoffice_path: "internal".to_string(),
+        pdf_export_concurrency: 2,
+        libreoffice_timeout_seconds: 120,
         session_cookie_name: "vh_session".to_string(),
         session_ttl_seconds: 3600,
+        session_cache_ttl_seconds: 0,
+        signed_url_ttl_seconds: 300,
+        max_upload_bytes: 20 * 1024 * 1024,
         auth_secret_key: vec![1u8; 32],
+        auth_secret_key_id: "k1".to_string(),
+        auth_previous_secret_keys: Vec::new(),
+        totp: ucaplatform::config::TotpConfig::default(),
         bootstrap_token: None,
         mail: None,
         password_policy: ucaplatform::config::PasswordPolicy::default(),
         reset_delivery: ucaplatform::config::ResetDelivery::Email,
+        jobs: ucaplatform::config::JobsConfig::default(),
+        auth_flow_store: ucaplatform::config::AuthFlowStoreKind::Memory,
+        peer_stats_enabled: true,
+        student_self_export: ucaplatform::config::StudentExportPolicy::default(),
+        graphql_enabled: true,
+        competition_match_max_edit_distance: None,
+        security_alert_email: None,
+        security_alert_webhook: None,
+        sso: None,
+        ldap: None,
+        clamav: None,
     };
 
     let mut builder = WebauthnBuilder::new(&config.rp_id, &config.rp_origin).unwrap();
@@ -90,6 +119,21 @@ fn database_url() -> String {
 
 async fn reset_database(state: &AppState) {
     let tables = [
+        "archive_items",
+        "document_verifications",
+        "advisor_classes",
+        "submission_windows",
+        "announcements",
+        "attachment_requirements",
+        "review_delegations",
+        "mail_templates",
+        "export_profiles",
+        "mail_outbox",
+        "import_batches",
+        "archives",
+        "webhook_deliveries",
+        "webhooks",
+        "record_comments",
         "form_field_values",
         "form_fields",
         "review_signatures",
@@ -97,11 +141,26 @@ async fn reset_database(state: &AppState) {
         "contest_records",
         "volunteer_records",
         "competition_library",
+        "semesters",
         "recovery_codes",
         "totp_secrets",
         "passkeys",
         "sessions",
+        "auth_flow_states",
+        "idempotency_keys",
         "devices",
+        "notifications",
+        "user_signatures",
+        "invites",
+        "auth_resets",
+        "security_events",
+        "audit_logs",
+        "import_template_fields",
+        "import_templates",
+        "export_templates",
+        "labor_hour_rules",
+        "password_policies",
+        "runtime_settings",
         "students",
         "users",
     ];
@@ -191,6 +250,9 @@ async fn create_user(state: &AppState, username: &str, role: &str) -> users::Mod
         password_updated_at: Set(None),
         must_change_password: Set(false),
         is_active: Set(true),
+        department: Set(None),
+        notify_review_email: Set(true),
+        locale: Set(None),
         created_at: Set(now),
         updated_at: Set(now),
     };
@@ -217,6 +279,7 @@ async fn create_student(state: &AppState, student_no: &str) -> students::Model {
         major: Set("软件工程".to_string()),
         class_name: Set("软工1班".to_string()),
         phone: Set("13800000000".to_string()),
+        import_batch_id: Set(None),
         is_deleted: Set(false),
         created_at: Set(now),
         updated_at: Set(now),
@@ -275,9 +338,25 @@ fn multipart_request_with_type(
     filename: &str,
     bytes: Vec<u8>,
     content_type: &str,
+) -> Request<Body> {
+    multipart_request_with_fields(path, filename, bytes, content_type, &[])
+}
+
+fn multipart_request_with_fields(
+    path: &str,
+    filename: &str,
+    bytes: Vec<u8>,
+    content_type: &str,
+    fields: &[(&str, &str)],
 ) -> Request<Body> {
     let boundary = "----volunteerhoursboundary";
     let mut body = Vec::new();
+    for (name, value) in fields {
+        body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+        body.extend_from_slice(
+            format!("Content-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n").as_bytes(),
+        );
+    }
     body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
     body.extend_from_slice(
         format!(
@@ -340,6 +419,33 @@ async fn health_and_bootstrap() {
         .await
         .unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key("x-request-id"));
+
+    let response = ctx
+        .app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .header("x-request-id", "abc-123")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["x-request-id"], "abc-123");
+
+    let response = ctx
+        .app
+        .clone()
+        .oneshot(Request::builder().uri("/health/ready").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response_json(response).await;
+    assert_eq!(report["checks"]["database"]["status"], "ok");
+    assert_eq!(report["checks"]["storage"]["status"], "ok");
+    assert_eq!(report["checks"]["libreoffice"]["status"], "skipped");
 
     let request = json_request(
         "POST",
@@ -353,6 +459,16 @@ async fn health_and_bootstrap() {
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
+    // 未提供用户名时发起可发现凭据登录，不下发任何凭据 ID。
+    let request = json_request("POST", "/auth/passkey/login/start", json!({}));
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let started: serde_json::Value = response_json(response).await;
+    assert!(started["session_id"].is_string());
+    assert!(started["public_key"]["publicKey"]["allowCredentials"]
+        .as_array()
+        .map_or(true, |items| items.is_empty()));
+
     let request = json_request(
         "POST",
         "/auth/passkey/login/finish",
@@ -362,6 +478,199 @@ async fn health_and_bootstrap() {
     assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
 }
 
+#[tokio::test]
+async fn cors_allows_configured_origins() {
+    let ctx = setup_context().await;
+    let mut config = (*ctx.state.config).clone();
+    config.developer_mode = false;
+    config.cors_allowed_origins = vec!["http://10.0.0.5:8080".to_string(), "https://*.example.edu".to_string()];
+    let app = routes::router(ctx.state.clone()).layer(ucaplatform::cors::build_cors_layer(&config).unwrap());
+
+    for (origin, allowed) in [
+        ("http://localhost:8443", true),
+        ("http://10.0.0.5:8080", true),
+        ("https://labor.example.edu", true),
+        ("https://example.edu", false),
+        ("http://localhost:5173", false),
+    ] {
+        let request = Request::builder()
+            .uri("/health")
+            .header(header::ORIGIN, origin)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let allow_origin = response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN);
+        if allowed {
+            assert_eq!(allow_origin.unwrap(), origin);
+        } else {
+            assert!(allow_origin.is_none(), "{origin}");
+        }
+    }
+
+    // 提交接口的预检请求需放行 Idempotency-Key 头。
+    let request = Request::builder()
+        .method("OPTIONS")
+        .uri("/records/contest")
+        .header(header::ORIGIN, "https://labor.example.edu")
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+        .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type,idempotency-key")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(
+        response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+        "https://labor.example.edu"
+    );
+    let allow_headers = response
+        .headers()
+        .get(header::ACCESS_CONTROL_ALLOW_HEADERS)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_ascii_lowercase();
+    assert!(allow_headers.contains("idempotency-key"), "{allow_headers}");
+}
+
+#[tokio::test]
+async fn sso_login_and_student_provisioning() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let request = Request::builder()
+        .uri("/auth/sso/login")
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let mut config = (*ctx.state.config).clone();
+    config.sso = Some(ucaplatform::config::SsoConfig {
+        provider: ucaplatform::config::SsoProvider::Cas,
+        callback_url: Url::parse("http://localhost:8443/auth/sso/callback").unwrap(),
+        cas_url: Some(Url::parse("https://cas.example.edu/cas").unwrap()),
+        authorize_url: None,
+        token_url: None,
+        userinfo_url: None,
+        client_id: None,
+        client_secret: None,
+        scope: "openid profile".to_string(),
+        username_claim: "preferred_username".to_string(),
+        auto_provision_students: true,
+        allow_staff: false,
+    });
+    let mut state = ctx.state.clone();
+    state.config = Arc::new(config);
+    let app = routes::router(state.clone());
+    let request = Request::builder()
+        .uri("/auth/sso/login?next=/records")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let location = response.headers().get(header::LOCATION).unwrap().to_str().unwrap();
+    assert!(location.starts_with("https://cas.example.edu/cas/login?service="));
+    let set_cookie = response.headers().get(header::SET_COOKIE).unwrap().to_str().unwrap();
+    assert!(set_cookie.contains("HttpOnly"));
+    assert!(set_cookie.contains("SameSite=Lax"));
+    let state_cookie = set_cookie.split(';').next().unwrap().to_string();
+    let service = Url::parse(location)
+        .unwrap()
+        .query_pairs()
+        .find(|(key, _)| key == "service")
+        .map(|(_, value)| value.into_owned())
+        .unwrap();
+    let sso_state = Url::parse(&service)
+        .unwrap()
+        .query_pairs()
+        .find(|(key, _)| key == "state")
+        .map(|(_, value)| value.into_owned())
+        .unwrap();
+    assert!(state_cookie.ends_with(&format!("={sso_state}")));
+
+    let request = Request::builder()
+        .uri("/auth/sso/callback?state=forged&ticket=ST-1")
+        .header(header::COOKIE, "vh_session_sso_state=forged")
+        .body(Body::empty())
+        .unwrap();
+    let response = routes::router(state.clone()).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // 他人发起登录得到的回调链接，在没有对应 Cookie 的浏览器中打开会被拒绝。
+    let request = Request::builder()
+        .uri(format!("/auth/sso/callback?state={sso_state}&ticket=ST-1"))
+        .body(Body::empty())
+        .unwrap();
+    let response = routes::router(state.clone()).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response_json(response).await;
+    assert!(body["message"].as_str().unwrap().contains("does not match"));
+
+    create_student(&state, "2023100").await;
+    let user = ucaplatform::auth::sso::resolve_local_user(&state.db, "2023100", true, false)
+        .await
+        .unwrap();
+    assert_eq!(user.role, "student");
+    assert!(!user.allow_password_login);
+    let again = ucaplatform::auth::sso::resolve_local_user(&state.db, "2023100", false, false)
+        .await
+        .unwrap();
+    assert_eq!(again.id, user.id);
+    assert!(ucaplatform::auth::sso::resolve_local_user(&state.db, "unknown", true, false)
+        .await
+        .is_err());
+
+    create_user(&state, "admin_sso", "admin").await;
+    assert!(ucaplatform::auth::sso::resolve_local_user(&state.db, "admin_sso", true, false)
+        .await
+        .is_err());
+    let admin = ucaplatform::auth::sso::resolve_local_user(&state.db, "admin_sso", true, true)
+        .await
+        .unwrap();
+    assert_eq!(admin.role, "admin");
+}
+
+#[tokio::test]
+async fn ldap_login_syncs_staff_accounts() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let response = ctx
+        .app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/auth/ldap/login",
+            json!({"username": "t001", "password": "secret"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let identity = ucaplatform::auth::ldap::LdapIdentity {
+        dn: "uid=t001,ou=people,dc=example,dc=edu".to_string(),
+        display_name: Some("王老师".to_string()),
+        email: Some("t001@example.edu".to_string()),
+        groups: Vec::new(),
+    };
+    let created = ucaplatform::auth::ldap::sync_local_user(&ctx.state.db, "t001", &identity, "teacher")
+        .await
+        .unwrap();
+    assert_eq!(created.role, "teacher");
+    assert_eq!(created.display_name, "王老师");
+    assert!(created.password_hash.is_none());
+
+    let updated = ucaplatform::auth::ldap::sync_local_user(&ctx.state.db, "t001", &identity, "reviewer")
+        .await
+        .unwrap();
+    assert_eq!(updated.id, created.id);
+    assert_eq!(updated.role, "reviewer");
+
+    create_user(&ctx.state, "admin1", "admin").await;
+    assert!(ucaplatform::auth::ldap::sync_local_user(&ctx.state.db, "admin1", &identity, "teacher")
+        .await
+        .is_err());
+}
+
 #[tokio::test]
 async fn admin_competitions_and_forms() {
     let ctx = setup_context().await;
@@ -403,534 +712,4786 @@ async fn admin_competitions_and_forms() {
 }
 
 #[tokio::test]
-async fn admin_templates_and_rules() {
+async fn current_user_includes_permissions_and_pending_actions() {
     let ctx = setup_context().await;
     reset_database(&ctx.state).await;
 
-    let admin = create_user(&ctx.state, "admin6", "admin").await;
-    let cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let student_user = create_user(&ctx.state, "2023016", "student").await;
+    create_student(&ctx.state, "2023016").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+    ucaplatform::entities::Notification::insert(ucaplatform::entities::notifications::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(student_user.id),
+        kind: Set("record_reviewed".to_string()),
+        title: Set("记录已审核".to_string()),
+        body: Set("你的竞赛记录已通过初审".to_string()),
+        target_type: Set(None),
+        target_id: Set(None),
+        read_at: Set(None),
+        created_at: Set(chrono::Utc::now()),
+    })
+    .exec_without_returning(&ctx.state.db)
+    .await
+    .unwrap();
 
     let request = Request::builder()
         .method("GET")
-        .uri("/admin/export-templates/labor_hours")
-        .header(header::COOKIE, cookie.clone())
+        .uri("/auth/me")
+        .header(header::COOKIE, student_cookie)
         .body(Body::empty())
         .unwrap();
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let me: serde_json::Value = response_json(response).await;
+    assert_eq!(me["role"], "student");
+    assert!(me["permissions"].as_array().unwrap().contains(&json!("records:submit")));
+    assert_eq!(me["student"]["student_no"], "2023016");
+    assert_eq!(me["student"]["class_name"], "软工1班");
+    assert_eq!(me["scope"]["department"], serde_json::Value::Null);
+    assert_eq!(me["totp_enrolled"], false);
+    assert_eq!(me["pending_actions"]["unread_notifications"], 1);
 
-    let template_xlsx = build_export_template_xlsx();
-    let request = multipart_request(
-        "/admin/export-templates/labor_hours/upload",
-        "labor-hours.xlsx",
-        template_xlsx,
-    )
-    .with_cookie(&cookie);
-    let response = ctx.app.clone().oneshot(request).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
-
+    let admin = create_user(&ctx.state, "admin_me", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
     let request = Request::builder()
         .method("GET")
-        .uri("/admin/labor-hour-rules")
-        .header(header::COOKIE, cookie.clone())
+        .uri("/auth/me")
+        .header(header::COOKIE, admin_cookie.clone())
         .body(Body::empty())
         .unwrap();
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let me: serde_json::Value = response_json(response).await;
+    let permissions = me["permissions"].as_array().unwrap();
+    assert!(permissions.contains(&json!("admin")));
+    assert!(permissions.contains(&json!("review:final")));
+    assert_eq!(me["student"], serde_json::Value::Null);
+    assert_eq!(me["totp_enrolled"], false);
+    assert!(me["pending_actions"].get("must_enroll_totp").is_none());
+    assert_eq!(me["pending_actions"]["unread_notifications"], 0);
 
-    let request = json_request(
-        "POST",
-        "/admin/labor-hour-rules",
-        json!({
-            "base_hours_a": 2,
-            "base_hours_b": 2,
-            "national_leader_hours": 4,
-            "national_member_hours": 2,
-            "provincial_leader_hours": 2,
-            "provincial_member_hours": 1,
-            "school_leader_hours": 1,
-            "school_member_hours": 1
-        }),
-    )
-    .with_cookie(&cookie);
-    let response = ctx.app.clone().oneshot(request).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
-}
-
-#[tokio::test]
-async fn import_students_and_records() {
-    let ctx = setup_context().await;
-    reset_database(&ctx.state).await;
-    let admin = create_user(&ctx.state, "admin2", "admin").await;
-    let cookie = create_session_cookie(&ctx.state, admin.id).await;
-
-    let competitions_xlsx = build_xlsx(
-        &["年份", "竞赛类型", "竞赛名称"],
-        &[vec!["2024", "A", "全国大学生数学建模竞赛"]],
-    );
-    let request = multipart_request("/admin/competitions/import", "competitions.xlsx", competitions_xlsx)
-        .with_cookie(&cookie);
-    let response = ctx.app.clone().oneshot(request).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let student_xlsx = build_xlsx(
-        &["学号", "姓名", "性别", "院系", "专业", "班级", "手机号"],
-        &[vec!["2023001", "张三", "男", "信息学院", "软件工程", "软工1班", "13800000000"]],
-    );
-    let request = multipart_request("/students/import", "students.xlsx", student_xlsx)
-        .with_cookie(&cookie);
-    let response = ctx.app.clone().oneshot(request).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let contest_xlsx = build_xlsx(
-        &["学号", "竞赛名称", "竞赛级别", "角色", "获奖等级", "自评学时"],
-        &[vec!["2023001", "全国大学生数学建模竞赛", "国家级", "负责人", "省赛一等奖", "8"]],
-    );
-    let request = multipart_request("/admin/records/contest/import", "contest.xlsx", contest_xlsx)
-        .with_cookie(&cookie);
+    totp_secrets::Entity::insert(totp_secrets::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(admin.id),
+        secret_enc: Set("unused".to_string()),
+        enabled: Set(true),
+        verified_at: Set(Some(chrono::Utc::now())),
+        created_at: Set(chrono::Utc::now()),
+        last_used_step: Set(None),
+        failed_attempts: Set(0),
+        locked_until: Set(None),
+    })
+    .exec_without_returning(&ctx.state.db)
+    .await
+    .unwrap();
+    let request = Request::builder()
+        .method("GET")
+        .uri("/auth/me")
+        .header(header::COOKIE, admin_cookie)
+        .body(Body::empty())
+        .unwrap();
     let response = ctx.app.clone().oneshot(request).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
+    let me: serde_json::Value = response_json(response).await;
+    assert_eq!(me["totp_enrolled"], true);
 }
 
 #[tokio::test]
-async fn create_and_review_records() {
+async fn form_field_update_delete_and_options() {
     let ctx = setup_context().await;
     reset_database(&ctx.state).await;
 
-    let student_user = create_user(&ctx.state, "2023002", "student").await;
-    create_student(&ctx.state, "2023002").await;
+    let admin = create_user(&ctx.state, "admin7", "admin").await;
+    let cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let student_user = create_user(&ctx.state, "2023007", "student").await;
+    create_student(&ctx.state, "2023007").await;
     let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
 
-    let now = chrono::Utc::now();
-    let field_id = Uuid::new_v4();
-    let field_model = ucaplatform::entities::form_fields::ActiveModel {
-        id: Set(field_id),
-        form_type: Set("contest".to_string()),
-        field_key: Set("location".to_string()),
-        label: Set("地点".to_string()),
-        field_type: Set("text".to_string()),
-        required: Set(true),
-        order_index: Set(1),
-        created_at: Set(now),
-        updated_at: Set(now),
-    };
-    ucaplatform::entities::form_fields::Entity::insert(field_model)
-        .exec_without_returning(&ctx.state.db)
-        .await
-        .unwrap();
-
     let request = json_request(
         "POST",
-        "/records/contest",
+        "/admin/form-fields",
         json!({
-            "contest_name": "全国大学生数学建模竞赛",
-            "contest_level": "国家级",
-            "contest_role": "负责人",
-            "award_level": "省赛一等奖",
-            "self_hours": 8,
-            "custom_fields": {}
+            "form_type": "contest",
+            "field_key": "venue",
+            "label": "地点",
+            "field_type": "select",
+            "required": false,
+            "order_index": 1
         }),
     )
-    .with_cookie(&student_cookie);
+    .with_cookie(&cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
-    assert_ne!(response.status(), StatusCode::OK);
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
     let request = json_request(
         "POST",
-        "/records/contest",
+        "/admin/form-fields",
         json!({
-            "contest_name": "全国大学生数学建模竞赛",
-            "contest_level": "国家级",
-            "contest_role": "负责人",
-            "award_level": "省赛一等奖",
-            "self_hours": 8,
-            "custom_fields": { "location": "校内操场" }
+            "form_type": "contest",
+            "field_key": "venue",
+            "label": "地点",
+            "field_type": "text",
+            "required": false,
+            "order_index": 1
         }),
     )
-    .with_cookie(&student_cookie);
+    .with_cookie(&cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let field: serde_json::Value = response_json(response).await;
+    let field_id = field["id"].as_str().unwrap().to_string();
 
-    let reviewer = create_user(&ctx.state, "reviewer1", "reviewer").await;
-    let reviewer_cookie = create_session_cookie(&ctx.state, reviewer.id).await;
-
-    let contest_record = ucaplatform::entities::ContestRecord::find()
-        .one(&ctx.state.db)
-        .await
-        .unwrap()
-        .unwrap();
     let request = json_request(
-        "POST",
-        &format!("/records/contest/{}/review", contest_record.id),
-        json!({ "stage": "first", "hours": 2, "status": "approved", "rejection_reason": null }),
+        "PUT",
+        &format!("/admin/form-fields/{field_id}"),
+        json!({
+            "label": "比赛地点",
+            "field_type": "select",
+            "required": true,
+            "order_index": 2,
+            "options": ["校内", "校外"]
+        }),
     )
-    .with_cookie(&reviewer_cookie);
+    .with_cookie(&cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let field: serde_json::Value = response_json(response).await;
+    assert_eq!(field["label"], "比赛地点");
+    assert_eq!(field["options"], json!(["校内", "校外"]));
+
+    let record = |venue: &str| {
+        json_request(
+            "POST",
+            "/records/contest",
+            json!({
+                "contest_name": "全国大学生数学建模竞赛",
+                "award_level": "省赛一等奖",
+                "self_hours": 4,
+                "custom_fields": { "venue": venue }
+            }),
+        )
+        .with_cookie(&student_cookie)
+    };
+    let response = ctx.app.clone().oneshot(record("线上")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = ctx.app.clone().oneshot(record("校外")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let delete = || {
+        Request::builder()
+            .method("DELETE")
+            .uri(format!("/admin/form-fields/{field_id}"))
+            .header(header::COOKIE, cookie.clone())
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = ctx.app.clone().oneshot(delete()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    ucaplatform::entities::FormFieldValue::delete_many()
+        .exec(&ctx.state.db)
+        .await
+        .unwrap();
+    let response = ctx.app.clone().oneshot(delete()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = ctx.app.clone().oneshot(delete()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
-async fn export_endpoints() {
+async fn custom_field_values_are_validated_by_type() {
     let ctx = setup_context().await;
     reset_database(&ctx.state).await;
-    let admin = create_user(&ctx.state, "admin3", "admin").await;
+
+    let admin = create_user(&ctx.state, "admin8", "admin").await;
+    let cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let student_user = create_user(&ctx.state, "2023008", "student").await;
+    create_student(&ctx.state, "2023008").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+
+    let field = |field_key: &str, body: serde_json::Value| {
+        let mut payload = json!({
+            "form_type": "contest",
+            "field_key": field_key,
+            "label": field_key,
+            "required": false,
+            "order_index": 1
+        });
+        payload.as_object_mut().unwrap().extend(body.as_object().unwrap().clone());
+        json_request("POST", "/admin/form-fields", payload).with_cookie(&cookie)
+    };
+    let response = ctx
+        .app
+        .clone()
+        .oneshot(field("team_size", json!({ "field_type": "number", "min_value": 5, "max_value": 1 })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = ctx
+        .app
+        .clone()
+        .oneshot(field("team_size", json!({ "field_type": "number", "min_value": 1, "max_value": 5 })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = ctx
+        .app
+        .clone()
+        .oneshot(field("defense_date", json!({ "field_type": "date" })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let record = |custom_fields: serde_json::Value| {
+        json_request(
+            "POST",
+            "/records/contest",
+            json!({
+                "contest_name": "全国大学生数学建模竞赛",
+                "award_level": "省赛一等奖",
+                "self_hours": 4,
+                "custom_fields": custom_fields
+            }),
+        )
+        .with_cookie(&student_cookie)
+    };
+    let response = ctx
+        .app
+        .clone()
+        .oneshot(record(json!({ "team_size": "9", "defense_date": "2025/09/01" })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["code"], "validation_error");
+    assert_eq!(body["fields"]["team_size"], "must be at most 5");
+    assert_eq!(body["fields"]["defense_date"], "must be a date like 2025-09-01");
+
+    let response = ctx
+        .app
+        .clone()
+        .oneshot(record(json!({ "team_size": "3", "defense_date": "2025-09-01" })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn admin_templates_and_rules() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin6", "admin").await;
+    let cookie = create_session_cookie(&ctx.state, admin.id).await;
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/export-templates/labor_hours")
+        .header(header::COOKIE, cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let template_xlsx = build_export_template_xlsx();
+    let request = multipart_request(
+        "/admin/export-templates/labor_hours/upload",
+        "labor-hours.xlsx",
+        template_xlsx,
+    )
+    .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    for (format, content_type) in [
+        ("xlsx", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        ("pdf", "application/pdf"),
+    ] {
+        let request = Request::builder()
+            .method("POST")
+            .uri(format!("/admin/export-templates/labor_hours/preview?format={format}"))
+            .header(header::COOKIE, cookie.clone())
+            .body(Body::empty())
+            .unwrap();
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let preview: serde_json::Value = response_json(response).await;
+        assert_eq!(preview["content_type"], content_type);
+        assert!(!preview["content_base64"].as_str().unwrap().is_empty());
+        assert_eq!(preview["unresolved_placeholders"], json!([]));
+    }
+
+    let register = || {
+        json_request(
+            "POST",
+            "/admin/export-templates",
+            json!({ "template_key": "class_summary", "display_name": "班级汇总认定表" }),
+        )
+        .with_cookie(&cookie)
+    };
+    let response = ctx.app.clone().oneshot(register()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let template: serde_json::Value = response_json(response).await;
+    assert_eq!(template["export_type"], "labor_hours");
+    assert_eq!(template["name"], "");
+    let response = ctx.app.clone().oneshot(register()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = multipart_request(
+        "/admin/export-templates/college_report/upload",
+        "college.xlsx",
+        build_export_template_xlsx(),
+    )
+    .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let request = multipart_request(
+        "/admin/export-templates/class_summary/upload",
+        "class.xlsx",
+        build_export_template_xlsx(),
+    )
+    .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/export-templates")
+        .header(header::COOKIE, cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let templates: serde_json::Value = response_json(response).await;
+    let keys: Vec<&str> = templates
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["template_key"].as_str().unwrap())
+        .collect();
+    assert_eq!(keys, vec!["class_summary", "labor_hours"]);
+    assert_eq!(templates[0]["name"], "class.xlsx");
+
+    let delete = |template_key: &str| {
+        Request::builder()
+            .method("DELETE")
+            .uri(format!("/admin/export-templates/{template_key}"))
+            .header(header::COOKIE, cookie.clone())
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = ctx.app.clone().oneshot(delete("labor_hours")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = ctx.app.clone().oneshot(delete("class_summary")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = ctx.app.clone().oneshot(delete("class_summary")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/labor-hour-rules")
+        .header(header::COOKIE, cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = json_request(
+        "POST",
+        "/admin/labor-hour-rules",
+        json!({
+            "base_hours_a": 2,
+            "base_hours_b": 2,
+            "national_leader_hours": 4,
+            "national_member_hours": 2,
+            "provincial_leader_hours": 2,
+            "provincial_member_hours": 1,
+            "school_leader_hours": 1,
+            "school_member_hours": 1
+        }),
+    )
+    .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn import_students_and_records() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let admin = create_user(&ctx.state, "admin2", "admin").await;
+    let cookie = create_session_cookie(&ctx.state, admin.id).await;
+
+    let competitions_xlsx = build_xlsx(
+        &["年份", "竞赛类型", "竞赛名称"],
+        &[vec!["2024", "A", "全国大学生数学建模竞赛"]],
+    );
+    let request = multipart_request("/admin/competitions/import", "competitions.xlsx", competitions_xlsx)
+        .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let student_xlsx = build_xlsx(
+        &["学号", "姓名", "性别", "院系", "专业", "班级", "手机号"],
+        &[vec!["2023001", "张三", "男", "信息学院", "软件工程", "软工1班", "13800000000"]],
+    );
+    let request = multipart_request("/students/import", "students.xlsx", student_xlsx)
+        .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let contest_xlsx = build_xlsx(
+        &["学号", "竞赛名称", "竞赛级别", "角色", "获奖等级", "自评学时"],
+        &[vec!["2023001", "全国大学生数学建模竞赛", "国家级", "负责人", "省赛一等奖", "8"]],
+    );
+    let request = multipart_request("/admin/records/contest/import", "contest.xlsx", contest_xlsx)
+        .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn import_batch_rollback_removes_only_created_rows() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let admin = create_user(&ctx.state, "admin_batch", "admin").await;
+    let cookie = create_session_cookie(&ctx.state, admin.id).await;
+    create_student(&ctx.state, "2023001").await;
+
+    let student_xlsx = build_xlsx(
+        &["学号", "姓名", "性别", "院系", "专业", "班级", "手机号"],
+        &[
+            vec!["2023001", "张三", "男", "信息学院", "软件工程", "软工1班", "13800000000"],
+            vec!["2023002", "李四", "女", "信息学院", "软件工程", "软工1班", "13800000001"],
+        ],
+    );
+    let request = multipart_request("/students/import", "students.xlsx", student_xlsx).with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["inserted"], 1);
+    assert_eq!(body["updated"], 1);
+    let batch_id = body["import_batch_id"].as_str().unwrap().to_string();
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/imports?kind=student")
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let items: serde_json::Value = response_json(response).await;
+    assert_eq!(items.as_array().unwrap().len(), 1);
+    assert_eq!(items[0]["id"], json!(batch_id));
+    assert_eq!(items[0]["created_rows"], json!(1));
+    assert_eq!(items[0]["active_rows"], json!(1));
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/admin/imports/{batch_id}"))
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["deleted"], json!(1));
+
+    let active: Vec<String> = students::Entity::find()
+        .filter(students::Column::IsDeleted.eq(false))
+        .all(&ctx.state.db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|student| student.student_no)
+        .collect();
+    assert_eq!(active, vec!["2023001".to_string()]);
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/admin/imports/{batch_id}"))
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn oversized_upload_returns_structured_413() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let admin = create_user(&ctx.state, "admin_upload", "admin").await;
+    let cookie = create_session_cookie(&ctx.state, admin.id).await;
+
+    let mut config = (*ctx.state.config).clone();
+    config.max_upload_bytes = 1024;
+    let mut state = ctx.state.clone();
+    state.config = Arc::new(config);
+
+    // 文件超出上传上限：逐块读取时中止。
+    let request = multipart_request("/students/import", "students.xlsx", vec![0u8; 4096]).with_cookie(&cookie);
+    let response = routes::router(state.clone()).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["code"], "payload_too_large");
+
+    // 整个请求体超出全局上限：由请求体限制层拒绝，同样返回 JSON 错误。
+    let request = multipart_request("/students/import", "students.xlsx", vec![0u8; 256 * 1024]).with_cookie(&cookie);
+    let response = routes::router(state).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["code"], "payload_too_large");
+}
+
+#[tokio::test]
+async fn import_students_with_title_row_and_merged_headers() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let admin = create_user(&ctx.state, "admin2", "admin").await;
+    let cookie = create_session_cookie(&ctx.state, admin.id).await;
+
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let format = rust_xlsxwriter::Format::new();
+    worksheet.merge_range(0, 0, 0, 4, "2025 级学生名单", &format).unwrap();
+    worksheet.merge_range(1, 0, 2, 0, "学号", &format).unwrap();
+    worksheet.merge_range(1, 1, 2, 1, "姓名", &format).unwrap();
+    worksheet.merge_range(1, 2, 2, 2, "院系", &format).unwrap();
+    worksheet.merge_range(1, 3, 1, 4, "联系方式", &format).unwrap();
+    worksheet.write_string(2, 3, "手机号").unwrap();
+    worksheet.write_string(2, 4, "邮箱").unwrap();
+    for (col, value) in ["2023101", "李四", "信息学院", "13900000000", "lisi@example.com"]
+        .iter()
+        .enumerate()
+    {
+        worksheet.write_string(3, col as u16, *value).unwrap();
+    }
+    let bytes = workbook.save_to_buffer().unwrap();
+
+    let request = multipart_request("/students/import", "students.xlsx", bytes).with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["inserted"], 1);
+
+    let student = students::Entity::find()
+        .filter(students::Column::StudentNo.eq("2023101"))
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .expect("student imported");
+    assert_eq!(student.name, "李四");
+    assert_eq!(student.department, "信息学院");
+    assert_eq!(student.phone, "13900000000");
+}
+
+#[tokio::test]
+async fn import_students_reports_conflicts_by_policy() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let admin = create_user(&ctx.state, "admin3", "admin").await;
+    let cookie = create_session_cookie(&ctx.state, admin.id).await;
+    create_student(&ctx.state, "2023201").await;
+
+    let headers = ["学号", "姓名", "性别", "院系", "专业", "班级", "手机号"];
+    let rows = vec![
+        vec!["2023201", "王五", "男", "信息学院", "软件工程", "软工1班", "13800000000"],
+        vec!["2023202", "赵六", "女", "信息学院", "软件工程", "软工2班", "13800000001"],
+    ];
+    let xlsx_type = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+    let request = multipart_request_with_fields(
+        "/students/import",
+        "students.xlsx",
+        build_xlsx(&headers, &rows),
+        xlsx_type,
+        &[("conflict_policy", "error")],
+    )
+    .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let body: serde_json::Value = response_json(response).await;
+    let conflicts = body["current"]["conflicts"].as_array().expect("conflicts");
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0]["row"], 2);
+    assert_eq!(conflicts[0]["existing"]["name"], "张三");
+    assert_eq!(conflicts[0]["incoming"]["name"], "王五");
+    let imported = students::Entity::find()
+        .filter(students::Column::StudentNo.eq("2023202"))
+        .one(&ctx.state.db)
+        .await
+        .unwrap();
+    assert!(imported.is_none());
+
+    let request = multipart_request_with_fields(
+        "/students/import",
+        "students.xlsx",
+        build_xlsx(&headers, &rows),
+        xlsx_type,
+        &[("conflict_policy", "skip")],
+    )
+    .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["inserted"], 1);
+    assert_eq!(body["updated"], 0);
+    assert_eq!(body["skipped_conflicts"], 1);
+    assert_eq!(body["conflicts"][0]["student_no"], "2023201");
+    let existing = students::Entity::find()
+        .filter(students::Column::StudentNo.eq("2023201"))
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(existing.name, "张三");
+
+    let request = multipart_request("/students/import", "students.xlsx", build_xlsx(&headers, &rows))
+        .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["updated"], 2);
+    assert_eq!(body["conflicts"].as_array().unwrap().len(), 1);
+    let existing = students::Entity::find()
+        .filter(students::Column::StudentNo.eq("2023201"))
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(existing.name, "王五");
+}
+
+#[tokio::test]
+async fn create_and_review_records() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let student_user = create_user(&ctx.state, "2023002", "student").await;
+    create_student(&ctx.state, "2023002").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+
+    let now = chrono::Utc::now();
+    let field_id = Uuid::new_v4();
+    let field_model = ucaplatform::entities::form_fields::ActiveModel {
+        id: Set(field_id),
+        form_type: Set("contest".to_string()),
+        field_key: Set("location".to_string()),
+        label: Set("地点".to_string()),
+        field_type: Set("text".to_string()),
+        required: Set(true),
+        order_index: Set(1),
+        options: Set(None),
+        min_value: Set(None),
+        max_value: Set(None),
+        max_length: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+    ucaplatform::entities::form_fields::Entity::insert(field_model)
+        .exec_without_returning(&ctx.state.db)
+        .await
+        .unwrap();
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 8,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_ne!(response.status(), StatusCode::OK);
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 8,
+            "custom_fields": { "location": "校内操场" }
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let reviewer = create_user(&ctx.state, "reviewer1", "reviewer").await;
+    let reviewer_cookie = create_session_cookie(&ctx.state, reviewer.id).await;
+
+    let contest_record = ucaplatform::entities::ContestRecord::find()
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    let request = json_request(
+        "POST",
+        &format!("/records/contest/{}/review", contest_record.id),
+        json!({ "stage": "first", "hours": 2, "status": "approved", "rejection_reason": null, "expected_version": 0 }),
+    )
+    .with_cookie(&reviewer_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let reviewed: serde_json::Value = response_json(response).await;
+    assert_eq!(reviewed["version"], 1);
+
+    // 另一位审核人基于旧版本提交，应返回 409 与记录当前状态。
+    let other_reviewer = create_user(&ctx.state, "reviewer2", "reviewer").await;
+    let other_cookie = create_session_cookie(&ctx.state, other_reviewer.id).await;
+    let request = json_request(
+        "POST",
+        &format!("/records/contest/{}/review", contest_record.id),
+        json!({ "stage": "first", "hours": 1, "status": "rejected", "rejection_reason": "材料不全", "expected_version": 0 }),
+    )
+    .with_cookie(&other_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let conflict: serde_json::Value = response_json(response).await;
+    assert_eq!(conflict["code"], "conflict");
+    assert_eq!(conflict["current"]["version"], 1);
+    assert_eq!(conflict["current"]["status"], "first_reviewed");
+    assert_eq!(conflict["current"]["first_review_hours"], 2);
+}
+
+#[tokio::test]
+async fn assign_records_to_reviewers() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_assign", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let student_user = create_user(&ctx.state, "2023030", "student").await;
+    create_student(&ctx.state, "2023030").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+    let reviewer_a = create_user(&ctx.state, "reviewer_a", "reviewer").await;
+    let reviewer_b = create_user(&ctx.state, "reviewer_b", "reviewer").await;
+    let reviewer_a_cookie = create_session_cookie(&ctx.state, reviewer_a.id).await;
+
+    for name in ["数学建模竞赛", "蓝桥杯", "挑战杯"] {
+        let request = json_request(
+            "POST",
+            "/records/contest",
+            json!({
+                "contest_name": name,
+                "contest_level": "国家级",
+                "contest_role": "负责人",
+                "award_level": "一等奖",
+                "self_hours": 4,
+                "custom_fields": {}
+            }),
+        )
+        .with_cookie(&student_cookie);
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let request = json_request(
+        "POST",
+        "/admin/assignments",
+        json!({
+            "record_type": "contest",
+            "mode": "round_robin",
+            "reviewer_ids": [reviewer_a.id, reviewer_b.id]
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let result: serde_json::Value = response_json(response).await;
+    assert_eq!(result["assigned"], 3);
+    let pending_of = |result: &serde_json::Value, reviewer_id: Uuid| {
+        result["workload"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|item| item["reviewer_id"] == reviewer_id.to_string())
+            .map(|item| item["pending"].as_u64().unwrap())
+            .unwrap()
+    };
+    assert_eq!(pending_of(&result, reviewer_a.id), 2);
+    assert_eq!(pending_of(&result, reviewer_b.id), 1);
+
+    let request = json_request("POST", "/records/contest/query", json!({ "assigned_to_me": true }))
+        .with_cookie(&reviewer_a_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let mine: Vec<serde_json::Value> = response_json(response).await;
+    assert_eq!(mine.len(), 2);
+    assert!(mine
+        .iter()
+        .all(|record| record["assigned_reviewer_id"] == reviewer_a.id.to_string()));
+
+    let record_id = mine[0]["id"].as_str().unwrap().to_string();
+    let request = json_request(
+        "POST",
+        "/admin/assignments",
+        json!({
+            "record_type": "contest",
+            "mode": "manual",
+            "record_ids": [record_id],
+            "reviewer_id": reviewer_b.id
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let result: serde_json::Value = response_json(response).await;
+    assert_eq!(pending_of(&result, reviewer_a.id), 1);
+    assert_eq!(pending_of(&result, reviewer_b.id), 2);
+
+    let request = json_request(
+        "POST",
+        "/admin/assignments",
+        json!({
+            "record_type": "contest",
+            "mode": "manual",
+            "record_ids": [record_id],
+            "reviewer_id": student_user.id
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = json_request(
+        "POST",
+        "/admin/assignments",
+        json!({ "record_type": "contest", "mode": "round_robin" }),
+    )
+    .with_cookie(&reviewer_a_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn review_reminders_notify_reviewers_and_admins() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_reminder", "admin").await;
+    let reviewer = create_user(&ctx.state, "reviewer_reminder", "reviewer").await;
+    let teacher = create_user(&ctx.state, "teacher_reminder", "teacher").await;
+    let student = create_student(&ctx.state, "2023031").await;
+    let stale = chrono::Utc::now() - chrono::Duration::days(10);
+    for (status, updated_at) in [
+        ("submitted", stale),
+        ("submitted", chrono::Utc::now()),
+        ("final_reviewed", stale),
+    ] {
+        let record = ucaplatform::entities::contest_records::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            student_id: Set(student.id),
+            contest_name: Set("全国大学生数学建模竞赛".to_string()),
+            award_level: Set("省赛一等奖".to_string()),
+            self_hours: Set(2),
+            status: Set(status.to_string()),
+            is_deleted: Set(false),
+            created_at: Set(updated_at),
+            updated_at: Set(updated_at),
+            ..Default::default()
+        };
+        ucaplatform::entities::ContestRecord::insert(record)
+            .exec_without_returning(&ctx.state.db)
+            .await
+            .unwrap();
+    }
+
+    let reminded = ucaplatform::review_reminders::send_review_reminders(&ctx.state)
+        .await
+        .unwrap();
+    assert_eq!(reminded, 2);
+    let notifications = ucaplatform::entities::Notification::find()
+        .all(&ctx.state.db)
+        .await
+        .unwrap();
+    let reminder = notifications
+        .iter()
+        .find(|item| item.user_id == reviewer.id)
+        .unwrap();
+    assert_eq!(reminder.kind, "review_reminder");
+    assert_eq!(reminder.title, "1 条记录等待审核超过 7 天");
+    assert!(reminder.body.starts_with("待初审：1 条"), "{}", reminder.body);
+    let digest = notifications.iter().find(|item| item.user_id == admin.id).unwrap();
+    assert_eq!(digest.kind, "review_digest");
+    assert!(digest.body.contains("信息学院：1 条"), "{}", digest.body);
+    assert!(notifications.iter().all(|item| item.user_id != teacher.id));
+
+    let reminded = ucaplatform::review_reminders::send_review_reminders(&ctx.state)
+        .await
+        .unwrap();
+    assert_eq!(reminded, 0);
+}
+
+#[tokio::test]
+async fn idempotency_key_replays_contest_submission() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let student_user = create_user(&ctx.state, "2023045", "student").await;
+    let student = create_student(&ctx.state, "2023045").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+
+    let submit = |path: &str, key: Option<&str>| {
+        let mut request = json_request(
+            "POST",
+            path,
+            json!({
+                "contest_name": "数学建模竞赛",
+                "contest_level": "国家级",
+                "contest_role": "负责人",
+                "award_level": "一等奖",
+                "self_hours": 4,
+                "custom_fields": {}
+            }),
+        )
+        .with_cookie(&student_cookie);
+        if let Some(key) = key {
+            request
+                .headers_mut()
+                .insert("idempotency-key", key.parse().unwrap());
+        }
+        request
+    };
+
+    let response = ctx.app.clone().oneshot(submit("/records/contest", Some("submit-1"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("idempotent-replayed").is_none());
+    let first: serde_json::Value = response_json(response).await;
+
+    let response = ctx.app.clone().oneshot(submit("/records/contest", Some("submit-1"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["idempotent-replayed"], "true");
+    let replayed: serde_json::Value = response_json(response).await;
+    assert_eq!(replayed["id"], first["id"]);
+
+    let records = ucaplatform::entities::ContestRecord::find()
+        .filter(ucaplatform::entities::contest_records::Column::StudentId.eq(student.id))
+        .all(&ctx.state.db)
+        .await
+        .unwrap();
+    assert_eq!(records.len(), 1);
+
+    let review_path = format!("/records/contest/{}/review", first["id"].as_str().unwrap());
+    let response = ctx.app.clone().oneshot(submit(&review_path, Some("submit-1"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = ctx.app.clone().oneshot(submit("/records/contest", Some("bad key"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = ctx.app.clone().oneshot(submit("/records/contest", None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let records = ucaplatform::entities::ContestRecord::find()
+        .filter(ucaplatform::entities::contest_records::Column::StudentId.eq(student.id))
+        .all(&ctx.state.db)
+        .await
+        .unwrap();
+    assert_eq!(records.len(), 2);
+}
+
+#[tokio::test]
+async fn record_comment_threads() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let student_user = create_user(&ctx.state, "2023040", "student").await;
+    create_student(&ctx.state, "2023040").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+    let other_user = create_user(&ctx.state, "2023041", "student").await;
+    create_student(&ctx.state, "2023041").await;
+    let other_cookie = create_session_cookie(&ctx.state, other_user.id).await;
+    let reviewer = create_user(&ctx.state, "reviewer_comment", "reviewer").await;
+    let reviewer_cookie = create_session_cookie(&ctx.state, reviewer.id).await;
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "一等奖",
+            "self_hours": 4,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record: serde_json::Value = response_json(response).await;
+    let comments_path = format!("/comments/contest/{}", record["id"].as_str().unwrap());
+
+    let request = json_request("POST", &comments_path, json!({ "body": "  " })).with_cookie(&reviewer_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = json_request("POST", &comments_path, json!({ "body": "请补充清晰的证书扫描件" }))
+        .with_cookie(&reviewer_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .uri(&comments_path)
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let comments: Vec<serde_json::Value> = response_json(response).await;
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0]["author_role"], "reviewer");
+
+    let request = json_request("POST", &comments_path, json!({ "body": "已重新上传" })).with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    for (user_id, expected) in [(student_user.id, 1), (reviewer.id, 1)] {
+        let notifications = ucaplatform::entities::Notification::find()
+            .filter(ucaplatform::entities::notifications::Column::UserId.eq(user_id))
+            .filter(ucaplatform::entities::notifications::Column::Kind.eq("record_comment"))
+            .all(&ctx.state.db)
+            .await
+            .unwrap();
+        assert_eq!(notifications.len(), expected);
+    }
+
+    let request = Request::builder()
+        .uri(&comments_path)
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&other_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn export_endpoints() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let admin = create_user(&ctx.state, "admin3", "admin").await;
     let cookie = create_session_cookie(&ctx.state, admin.id).await;
 
     let student_user = create_user(&ctx.state, "2023003", "student").await;
     create_student(&ctx.state, "2023003").await;
     let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
 
-    let request = json_request(
-        "POST",
-        "/records/contest",
-        json!({
-            "contest_name": "全国大学生数学建模竞赛",
-            "contest_level": "国家级",
-            "contest_role": "负责人",
-            "award_level": "省赛一等奖",
-            "self_hours": 2,
-            "custom_fields": {}
-        }),
-    )
-    .with_cookie(&student_cookie);
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = json_request(
+        "POST",
+        "/export/summary/excel",
+        json!({ "department": "信息学院" }),
+    )
+    .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/export/student/2023003/excel")
+        .header(header::COOKIE, cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let record = ucaplatform::entities::ContestRecord::find()
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/export/record/contest/{}/pdf", record.id))
+        .header(header::COOKIE, cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/export/labor-hours/2023003/pdf")
+        .header(header::COOKIE, cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/export/labor-hours/2023003/excel")
+        .header(header::COOKIE, cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+    );
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert!(bytes.starts_with(b"PK"));
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/export/labor-hours/2023003/pdf?template_key=missing")
+        .header(header::COOKIE, cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = json_request(
+        "POST",
+        "/export/labor-hours/batch",
+        json!({ "class_name": "软工1班" }),
+    )
+    .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/zip");
+}
+
+#[tokio::test]
+async fn export_permissions_by_role() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let mut cookies = std::collections::HashMap::new();
+    for (username, role) in [
+        ("admin_exp", "admin"),
+        ("teacher_exp", "teacher"),
+        ("reviewer_exp", "reviewer"),
+        ("advisor_exp", "class_advisor"),
+        ("2023021", "student"),
+        ("2023022", "student"),
+    ] {
+        let user = create_user(&ctx.state, username, role).await;
+        cookies.insert(username, create_session_cookie(&ctx.state, user.id).await);
+    }
+    create_student(&ctx.state, "2023021").await;
+    create_student(&ctx.state, "2023022").await;
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&cookies["2023021"]);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record: serde_json::Value = response_json(response).await;
+    let record_pdf = format!("/export/record/contest/{}/pdf", record["id"].as_str().unwrap());
+
+    let status_of = |path: String, username: &'static str, body: serde_json::Value| {
+        let app = ctx.app.clone();
+        let cookie = cookies[username].clone();
+        async move {
+            let request = json_request("POST", &path, body).with_cookie(&cookie);
+            app.oneshot(request).await.unwrap().status()
+        }
+    };
+    let summary = || "/export/summary/excel".to_string();
+    let audit = || "/export/signatures/audit/excel".to_string();
+    let own = || "/export/student/2023021/excel".to_string();
+
+    for username in ["admin_exp", "teacher_exp", "reviewer_exp", "advisor_exp"] {
+        assert_eq!(status_of(summary(), username, json!({})).await, StatusCode::OK, "{username}");
+    }
+    assert_eq!(status_of(summary(), "2023021", json!({})).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(status_of(audit(), "teacher_exp", json!({})).await, StatusCode::OK);
+    assert_eq!(status_of(audit(), "reviewer_exp", json!({})).await, StatusCode::UNAUTHORIZED);
+    for username in ["admin_exp", "teacher_exp", "reviewer_exp"] {
+        assert_eq!(status_of(own(), username, json!({})).await, StatusCode::OK, "{username}");
+        assert_eq!(status_of(record_pdf.clone(), username, json!({})).await, StatusCode::OK, "{username}");
+    }
+    assert_eq!(status_of(own(), "advisor_exp", json!({})).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        status_of("/export/labor-hours/batch".to_string(), "advisor_exp", json!({})).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    // 学生本人：默认仅可导出已终审记录，个人文件只包含已终审记录。
+    assert_eq!(status_of(own(), "2023021", json!({})).await, StatusCode::OK);
+    assert_eq!(status_of(own(), "2023022", json!({})).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(status_of(record_pdf.clone(), "2023021", json!({})).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(status_of(record_pdf.clone(), "2023022", json!({})).await, StatusCode::UNAUTHORIZED);
+    ucaplatform::entities::ContestRecord::update_many()
+        .col_expr(
+            ucaplatform::entities::contest_records::Column::Status,
+            sea_orm::sea_query::Expr::value("final_reviewed"),
+        )
+        .exec(&ctx.state.db)
+        .await
+        .unwrap();
+    assert_eq!(status_of(record_pdf.clone(), "2023021", json!({})).await, StatusCode::OK);
+    assert_eq!(status_of(record_pdf, "2023022", json!({})).await, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn exported_documents_can_be_verified_without_login() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let admin = create_user(&ctx.state, "admin_verify", "admin").await;
+    let cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let student_user = create_user(&ctx.state, "2023031", "student").await;
+    let student = create_student(&ctx.state, "2023031").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 4,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    ucaplatform::entities::ContestRecord::update_many()
+        .col_expr(
+            ucaplatform::entities::contest_records::Column::Status,
+            sea_orm::sea_query::Expr::value("final_reviewed"),
+        )
+        .col_expr(
+            ucaplatform::entities::contest_records::Column::FinalReviewHours,
+            sea_orm::sea_query::Expr::value(3),
+        )
+        .exec(&ctx.state.db)
+        .await
+        .unwrap();
+    let record = ucaplatform::entities::ContestRecord::find()
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/export/record/contest/{}/pdf", record.id))
+        .header(header::COOKIE, cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let pdf = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let verifications = ucaplatform::entities::DocumentVerification::find()
+        .all(&ctx.state.db)
+        .await
+        .unwrap();
+    assert_eq!(verifications.len(), 1);
+    assert_eq!(verifications[0].document_type, "record_pdf");
+    assert_eq!(verifications[0].approved_hours, 3);
+    assert_eq!(verifications[0].issued_by, Some(admin.id));
+    let digest = {
+        use sha2::Digest;
+        hex::encode(sha2::Sha256::digest(&pdf))
+    };
+    assert_eq!(verifications[0].document_sha256, digest);
+
+    // 二维码中的令牌只保存摘要，这里直接签发一个令牌走公开接口。
+    let pending = ucaplatform::document_verification::PendingVerification::issue(&ctx.state.config);
+    assert!(pending.url.starts_with("http://localhost:5173/api/verify/"));
+    pending
+        .record(&ctx.state.db, "labor_hours_pdf", &student, 12, b"document", admin.id)
+        .await
+        .unwrap();
+    let verify = |token: &str| {
+        Request::builder()
+            .method("GET")
+            .uri(format!("/verify/{token}"))
+            .header("x-forwarded-for", "203.0.113.50")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = ctx.app.clone().oneshot(verify(&pending.token)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["document_type"], "labor_hours_pdf");
+    assert_eq!(body["student_name"], "张*");
+    assert_eq!(body["approved_hours"], 12);
+    assert_eq!(body["document_sha256"].as_str().unwrap().len(), 64);
+    assert!(body.get("student_no").is_none());
+
+    let response = ctx.app.clone().oneshot(verify("unknown-token")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let mut last = StatusCode::OK;
+    for _ in 0..30 {
+        last = ctx.app.clone().oneshot(verify(&pending.token)).await.unwrap().status();
+    }
+    assert_eq!(last, StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn upload_attachments_and_signatures() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let student_user = create_user(&ctx.state, "2023010", "student").await;
+    create_student(&ctx.state, "2023010").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let record = ucaplatform::entities::ContestRecord::find()
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let attachment = multipart_request_with_type(
+        &format!("/attachments/contest/{}", record.id),
+        "proof.pdf",
+        b"test".to_vec(),
+        "application/pdf",
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(attachment).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let uploaded: Vec<serde_json::Value> = response_json(response).await;
+    let pdf_id = uploaded[0]["id"].as_str().unwrap().to_string();
+
+    let mut png = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(900, 450, image::Rgb([200, 10, 10])))
+        .write_to(&mut png, image::ImageOutputFormat::Png)
+        .unwrap();
+    let attachment = multipart_request_with_type(
+        &format!("/attachments/contest/{}", record.id),
+        "photo.png",
+        png.into_inner(),
+        "image/png",
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(attachment).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let uploaded: Vec<serde_json::Value> = response_json(response).await;
+    let image_id = uploaded[0]["id"].as_str().unwrap().to_string();
+
+    let request = Request::builder()
+        .uri(format!("/attachments/{image_id}/thumbnail"))
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "image/jpeg");
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let thumbnail = image::load_from_memory(&bytes).unwrap();
+    assert_eq!(image::GenericImageView::dimensions(&thumbnail), (300, 150));
+
+    let request = Request::builder()
+        .uri(format!("/attachments/{pdf_id}/thumbnail"))
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let reviewer = create_user(&ctx.state, "reviewer2", "reviewer").await;
+    let reviewer_cookie = create_session_cookie(&ctx.state, reviewer.id).await;
+    let signature = multipart_request(
+        &format!("/signatures/contest/{}/first", record.id),
+        "sig.png",
+        b"sig".to_vec(),
+    )
+    .with_cookie(&reviewer_cookie);
+    let response = ctx.app.clone().oneshot(signature).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let signature: serde_json::Value = response_json(response).await;
+    let signature_id = Uuid::parse_str(signature["id"].as_str().unwrap()).unwrap();
+    let stored = ucaplatform::entities::Attachment::find_by_id(signature_id)
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .expect("signature attachment");
+    assert_eq!(stored.kind, "signature");
+    let evidence = ucaplatform::entities::Attachment::find()
+        .filter(ucaplatform::entities::attachments::Column::RecordId.eq(record.id))
+        .filter(ucaplatform::entities::attachments::Column::Kind.eq("evidence"))
+        .all(&ctx.state.db)
+        .await
+        .unwrap();
+    assert_eq!(evidence.len(), 2);
+}
+
+#[tokio::test]
+async fn signed_urls_serve_files_without_cookies() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let student_user = create_user(&ctx.state, "2023011", "student").await;
+    create_student(&ctx.state, "2023011").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+    let other_user = create_user(&ctx.state, "2023012", "student").await;
+    create_student(&ctx.state, "2023012").await;
+    let other_cookie = create_session_cookie(&ctx.state, other_user.id).await;
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record = ucaplatform::entities::ContestRecord::find()
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    let attachment = multipart_request_with_type(
+        &format!("/attachments/contest/{}", record.id),
+        "proof.pdf",
+        b"test".to_vec(),
+        "application/pdf",
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(attachment).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let uploaded: Vec<serde_json::Value> = response_json(response).await;
+    let attachment_id = uploaded[0]["id"].as_str().unwrap().to_string();
+
+    let request = json_request("POST", "/files/sign", json!({ "kind": "attachment", "id": attachment_id }))
+        .with_cookie(&other_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let request = json_request("POST", "/files/sign", json!({ "kind": "attachment", "id": attachment_id }))
+        .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let signed: serde_json::Value = response_json(response).await;
+    let url = signed["url"].as_str().unwrap().to_string();
+    assert!(url.starts_with("/files/signed/"));
+    assert!(signed["expires_at"].is_string());
+
+    let request = Request::builder().uri(&url).body(Body::empty()).unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&bytes[..], b"test");
+
+    let tampered = format!("{}x", url);
+    let request = Request::builder().uri(&tampered).body(Body::empty()).unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let admin = create_user(&ctx.state, "admin_signed", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let request = json_request("POST", "/export/summary/excel?delivery=link", json!({}))
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let signed: serde_json::Value = response_json(response).await;
+    let url = signed["url"].as_str().unwrap().to_string();
+
+    let request = Request::builder().uri(&url).body(Body::empty()).unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+    );
+    assert_eq!(
+        response.headers()[header::CONTENT_DISPOSITION],
+        "attachment; filename=\"summary.xlsx\""
+    );
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert!(bytes.starts_with(b"PK"));
+
+    users::Entity::update(users::ActiveModel {
+        id: sea_orm::ActiveValue::Unchanged(admin.id),
+        is_active: Set(false),
+        ..Default::default()
+    })
+    .exec(&ctx.state.db)
+    .await
+    .unwrap();
+    let request = Request::builder().uri(&url).body(Body::empty()).unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn infected_attachment_is_quarantined() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    // 模拟 clamd：读完 INSTREAM 数据后一律报告感染。
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut command = [0u8; 10];
+            socket.read_exact(&mut command).await.unwrap();
+            loop {
+                let mut size = [0u8; 4];
+                socket.read_exact(&mut size).await.unwrap();
+                let size = u32::from_be_bytes(size) as usize;
+                if size == 0 {
+                    break;
+                }
+                let mut chunk = vec![0u8; size];
+                socket.read_exact(&mut chunk).await.unwrap();
+            }
+            socket.write_all(b"stream: Eicar-Signature FOUND\0").await.unwrap();
+        }
+    });
+    let upload_dir = tempfile::tempdir().unwrap();
+    let mut config = (*ctx.state.config).clone();
+    config.upload_dir = upload_dir.path().to_path_buf();
+    config.clamav = Some(ucaplatform::config::ClamavConfig {
+        address,
+        timeout_seconds: 5,
+        fail_open: false,
+    });
+    let mut state = ctx.state.clone();
+    state.config = Arc::new(config);
+    let app = routes::router(state.clone());
+
+    let student_user = create_user(&state, "2023011", "student").await;
+    let student = create_student(&state, "2023011").await;
+    let student_cookie = create_session_cookie(&state, student_user.id).await;
+    let record_id = Uuid::new_v4();
+    let record = ucaplatform::entities::contest_records::ActiveModel {
+        id: Set(record_id),
+        student_id: Set(student.id),
+        contest_name: Set("全国大学生数学建模竞赛".to_string()),
+        award_level: Set("省赛一等奖".to_string()),
+        self_hours: Set(2),
+        status: Set("submitted".to_string()),
+        is_deleted: Set(false),
+        created_at: Set(chrono::Utc::now()),
+        updated_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    };
+    ucaplatform::entities::ContestRecord::insert(record)
+        .exec_without_returning(&state.db)
+        .await
+        .unwrap();
+
+    let attachment = multipart_request_with_type(
+        &format!("/attachments/contest/{record_id}"),
+        "proof.pdf",
+        b"%PDF-1.4 EICAR".to_vec(),
+        "application/pdf",
+    )
+    .with_cookie(&student_cookie);
+    let response = app.oneshot(attachment).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let stored = ucaplatform::entities::Attachment::find()
+        .all(&state.db)
+        .await
+        .unwrap();
+    assert!(stored.is_empty());
+    let audit = ucaplatform::entities::AuditLog::find()
+        .filter(ucaplatform::entities::audit_logs::Column::Action.eq("attachment_quarantined"))
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(audit.target_id.as_deref(), Some(record_id.to_string().as_str()));
+    assert!(audit.detail.unwrap().contains("Eicar-Signature"));
+    let quarantined = std::fs::read_dir(upload_dir.path().join("quarantine"))
+        .unwrap()
+        .count();
+    assert_eq!(quarantined, 1);
+}
+
+#[tokio::test]
+async fn attachment_storage_quota_and_usage_report() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let upload_dir = tempfile::tempdir().unwrap();
+    let mut config = (*ctx.state.config).clone();
+    config.upload_dir = upload_dir.path().to_path_buf();
+    config.attachments.max_student_bytes = Some(20);
+    let mut state = ctx.state.clone();
+    state.config = Arc::new(config);
+    let app = routes::router(state.clone());
+
+    let admin = create_user(&state, "admin", "admin").await;
+    let admin_cookie = create_session_cookie(&state, admin.id).await;
+    let student_user = create_user(&state, "2023012", "student").await;
+    let student = create_student(&state, "2023012").await;
+    let student_cookie = create_session_cookie(&state, student_user.id).await;
+    let record_id = Uuid::new_v4();
+    let record = ucaplatform::entities::contest_records::ActiveModel {
+        id: Set(record_id),
+        student_id: Set(student.id),
+        contest_name: Set("全国大学生数学建模竞赛".to_string()),
+        award_level: Set("省赛一等奖".to_string()),
+        self_hours: Set(2),
+        status: Set("submitted".to_string()),
+        is_deleted: Set(false),
+        created_at: Set(chrono::Utc::now()),
+        updated_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    };
+    ucaplatform::entities::ContestRecord::insert(record)
+        .exec_without_returning(&state.db)
+        .await
+        .unwrap();
+
+    let attachment = multipart_request_with_type(
+        &format!("/attachments/contest/{record_id}"),
+        "proof.pdf",
+        b"%PDF-1.4 proof".to_vec(),
+        "application/pdf",
+    )
+    .with_cookie(&student_cookie);
+    let response = app.clone().oneshot(attachment).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let attachment = multipart_request_with_type(
+        &format!("/attachments/contest/{record_id}"),
+        "more.pdf",
+        b"%PDF-1.4 more".to_vec(),
+        "application/pdf",
+    )
+    .with_cookie(&student_cookie);
+    let response = app.clone().oneshot(attachment).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = response_json(response).await;
+    assert!(body.to_string().contains("storage quota exceeded"), "{body}");
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/storage/usage?top=5")
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&admin_cookie);
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let usage: serde_json::Value = response_json(response).await;
+    assert_eq!(usage["total_files"], 1);
+    assert_eq!(usage["total_bytes"], 14);
+    assert_eq!(usage["max_student_bytes"], 20);
+    assert_eq!(usage["by_record_type"][0]["key"], "contest");
+    assert_eq!(usage["by_department"][0]["key"], "信息学院");
+    assert_eq!(usage["top_students"][0]["student_no"], "2023012");
+    assert_eq!(usage["top_students"][0]["evidence_bytes"], 14);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/storage/usage")
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&student_cookie);
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn manage_personal_signature() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let teacher = create_user(&ctx.state, "teacher_sig", "teacher").await;
+    let cookie = create_session_cookie(&ctx.state, teacher.id).await;
+
+    let request = multipart_request_with_type("/auth/signature", "sig.png", b"not an image".to_vec(), "image/png")
+        .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let mut png = std::io::Cursor::new(Vec::new());
+    image::RgbImage::new(4, 2)
+        .write_to(&mut png, image::ImageOutputFormat::Png)
+        .unwrap();
+    let request = multipart_request_with_type("/auth/signature", "sig.png", png.into_inner(), "image/png")
+        .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let uploaded: serde_json::Value = response_json(response).await;
+    assert_eq!(uploaded["uploaded"], true);
+    let path = uploaded["signature_path"].as_str().unwrap().to_string();
+    assert!(std::path::Path::new(&path).exists());
+
+    let request = Request::builder()
+        .uri("/auth/signature")
+        .header(header::COOKIE, cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let profile: serde_json::Value = response_json(response).await;
+    assert_eq!(profile["signature_path"], path.as_str());
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri("/auth/signature")
+        .header(header::COOKIE, cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!std::path::Path::new(&path).exists());
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri("/auth/signature")
+        .header(header::COOKIE, cookie)
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn signature_upload_requires_reauth() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let teacher = create_user(&ctx.state, "teacher_sig_reauth", "teacher").await;
+    let cookie = create_session_cookie(&ctx.state, teacher.id).await;
+    let (secret, _) = generate_totp("Labor Hours Platform", &teacher.username).unwrap();
+    let encrypted = encrypt_secret(&secret, &ctx.state.config.secret_keyring()).unwrap();
+    totp_secrets::Entity::insert(totp_secrets::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(teacher.id),
+        secret_enc: Set(encrypted),
+        enabled: Set(true),
+        verified_at: Set(Some(chrono::Utc::now())),
+        created_at: Set(chrono::Utc::now()),
+        last_used_step: Set(None),
+        failed_attempts: Set(0),
+        locked_until: Set(None),
+    })
+    .exec_without_returning(&ctx.state.db)
+    .await
+    .unwrap();
+
+    let mut png = std::io::Cursor::new(Vec::new());
+    image::RgbImage::new(4, 2)
+        .write_to(&mut png, image::ImageOutputFormat::Png)
+        .unwrap();
+    let png = png.into_inner();
+
+    for path in ["/auth/signature", "/profile/signature"] {
+        let request = multipart_request_with_type(path, "sig.png", png.clone(), "image/png").with_cookie(&cookie);
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{path}");
+    }
+    let existing = user_signatures::Entity::find_by_id(teacher.id)
+        .one(&ctx.state.db)
+        .await
+        .unwrap();
+    assert!(existing.is_none());
+
+    for (path, token) in [("/auth/signature", "sig-reauth-auth"), ("/profile/signature", "sig-reauth-profile")] {
+        ctx.state
+            .auth_flows
+            .insert_reauth_token(
+                token,
+                &ReauthSession {
+                    user_id: teacher.id,
+                    created_at: time::OffsetDateTime::now_utc(),
+                },
+            )
+            .await
+            .unwrap();
+        let mut request =
+            multipart_request_with_type(path, "sig.png", png.clone(), "image/png").with_cookie(&cookie);
+        request
+            .headers_mut()
+            .insert("x-reauth-token", token.parse().unwrap());
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{path}");
+        let uploaded: serde_json::Value = response_json(response).await;
+        assert_eq!(uploaded["uploaded"], true);
+    }
+}
+
+#[tokio::test]
+async fn locale_preference_round_trip() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let student = create_user(&ctx.state, "2023101", "student").await;
+    let cookie = create_session_cookie(&ctx.state, student.id).await;
+
+    let request = Request::builder()
+        .uri("/profile/locale")
+        .header(header::COOKIE, cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let preference: serde_json::Value = response_json(response).await;
+    assert!(preference["locale"].is_null());
+    assert_eq!(preference["effective_locale"], "zh-CN");
+
+    let request = json_request("PUT", "/profile/locale", json!({ "locale": "en_us" })).with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let preference: serde_json::Value = response_json(response).await;
+    assert_eq!(preference["locale"], "en-US");
+    assert_eq!(preference["effective_locale"], "en-US");
+    let stored = users::Entity::find_by_id(student.id)
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.locale.as_deref(), Some("en-US"));
+
+    let request = json_request("PUT", "/profile/locale", json!({ "locale": "fr-FR" })).with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = json_request("PUT", "/profile/locale", json!({ "locale": null })).with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let preference: serde_json::Value = response_json(response).await;
+    assert!(preference["locale"].is_null());
+    assert_eq!(preference["effective_locale"], "zh-CN");
+}
+
+#[tokio::test]
+async fn graphql_queries_respect_role_scope() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let student_user = create_user(&ctx.state, "2023031", "student").await;
+    create_student(&ctx.state, "2023031").await;
+    create_student(&ctx.state, "2023032").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let query = json!({
+        "query": "query($no: String!) { me { role } student(studentNo: $no) { name approvedHours contestRecords { contestName status } } }",
+        "variables": { "no": "2023031" }
+    });
+    let request = json_request("POST", "/graphql", query).with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response_json(response).await;
+    assert!(body.get("errors").is_none(), "{body}");
+    assert_eq!(body["data"]["me"]["role"], "student");
+    assert_eq!(body["data"]["student"]["approvedHours"], 0);
+    let records = body["data"]["student"]["contestRecords"].as_array().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["status"], "submitted");
+
+    let request = json_request(
+        "POST",
+        "/graphql",
+        json!({ "query": "{ students { studentNo } }" }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "auth_error");
+
+    let request = json_request(
+        "POST",
+        "/graphql",
+        json!({ "query": "{ student(studentNo: \"2023032\") { name } }" }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "auth_error");
+
+    let admin = create_user(&ctx.state, "admin_gql", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let teacher = create_user(&ctx.state, "teacher_gql", "teacher").await;
+    let request = json_request(
+        "PUT",
+        &format!("/admin/users/{}/department", teacher.id),
+        json!({ "department": "机械学院" }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let teacher_cookie = create_session_cookie(&ctx.state, teacher.id).await;
+    let request = json_request(
+        "POST",
+        "/graphql",
+        json!({ "query": "{ students { studentNo } contestRecords { id } stats { counts { submitted } } }" }),
+    )
+    .with_cookie(&teacher_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let body: serde_json::Value = response_json(response).await;
+    assert!(body.get("errors").is_none(), "{body}");
+    assert!(body["data"]["students"].as_array().unwrap().is_empty());
+    assert!(body["data"]["contestRecords"].as_array().unwrap().is_empty());
+    assert_eq!(body["data"]["stats"]["counts"]["submitted"], 0);
+
+    let request = json_request(
+        "POST",
+        "/graphql",
+        json!({ "query": "{ contestRecords(filter: { studentNo: \"2023031\" }) { contestName student { studentNo } } stats { counts { submitted } } }" }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let body: serde_json::Value = response_json(response).await;
+    assert!(body.get("errors").is_none(), "{body}");
+    assert_eq!(body["data"]["contestRecords"][0]["student"]["studentNo"], "2023031");
+    assert_eq!(body["data"]["stats"]["counts"]["submitted"], 1);
+
+    let request = json_request("POST", "/graphql", json!({ "query": "{ me { role } }" }));
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn class_advisor_sees_only_assigned_classes() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_advisor", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let advisor = create_user(&ctx.state, "advisor01", "class_advisor").await;
+    let advisor_cookie = create_session_cookie(&ctx.state, advisor.id).await;
+
+    for student_no in ["2023041", "2023042"] {
+        let user = create_user(&ctx.state, student_no, "student").await;
+        create_student(&ctx.state, student_no).await;
+        let cookie = create_session_cookie(&ctx.state, user.id).await;
+        let request = json_request(
+            "POST",
+            "/records/contest",
+            json!({
+                "contest_name": "全国大学生数学建模竞赛",
+                "contest_level": "国家级",
+                "contest_role": "负责人",
+                "award_level": "省赛一等奖",
+                "self_hours": 2,
+                "custom_fields": {}
+            }),
+        )
+        .with_cookie(&cookie);
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let backend = ctx.state.db.get_database_backend();
+    ctx.state
+        .db
+        .execute(sea_orm::Statement::from_string(
+            backend,
+            "UPDATE students SET class_name = '软工2班' WHERE student_no = '2023042'".to_string(),
+        ))
+        .await
+        .expect("move student");
+
+    let request = json_request("POST", "/records/contest/query", json!({}))
+        .with_cookie(&advisor_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let records: Vec<serde_json::Value> = response_json(response).await;
+    assert!(records.is_empty());
+
+    let request = json_request(
+        "PUT",
+        &format!("/admin/users/{}/advisor-classes", advisor.id),
+        json!({ "classes": [" 软工1班 ", "软工1班"] }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["classes"], json!(["软工1班"]));
+
+    let request = json_request(
+        "PUT",
+        &format!("/admin/users/{}/advisor-classes", admin.id),
+        json!({ "classes": ["软工1班"] }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = json_request("POST", "/records/contest/query", json!({}))
+        .with_cookie(&advisor_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let records: Vec<serde_json::Value> = response_json(response).await;
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["student_no"], "2023041");
+    assert_eq!(records[0]["class_name"], "软工1班");
+    let record_id = records[0]["id"].as_str().unwrap().to_string();
+
+    let request = json_request("POST", "/records/contest/query", json!({ "include_student": false }))
+        .with_cookie(&advisor_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let records: Vec<serde_json::Value> = response_json(response).await;
+    assert_eq!(records.len(), 1);
+    assert!(records[0]["student_no"].is_null());
+
+    let request = json_request(
+        "POST",
+        &format!("/records/contest/{record_id}/review"),
+        json!({ "stage": "first", "hours": 2, "status": "approved" }),
+    )
+    .with_cookie(&advisor_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/students/2023041/hours")
+        .header(header::COOKIE, advisor_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/students/2023042/hours")
+        .header(header::COOKIE, advisor_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/stats/overview")
+        .header(header::COOKIE, advisor_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["counts"]["submitted"], 1);
+}
+
+#[tokio::test]
+async fn submission_windows_gate_contest_records() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_window", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let student_user = create_user(&ctx.state, "2023051", "student").await;
+    create_student(&ctx.state, "2023051").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+    let now = chrono::Utc::now();
+    let contest = json!({
+        "contest_name": "全国大学生数学建模竞赛",
+        "contest_level": "国家级",
+        "contest_role": "负责人",
+        "award_level": "省赛一等奖",
+        "self_hours": 2,
+        "custom_fields": {}
+    });
+
+    let request = json_request(
+        "POST",
+        "/admin/submission-windows",
+        json!({
+            "record_type": "contest",
+            "opens_at": now + chrono::Duration::days(1),
+            "closes_at": now,
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = json_request(
+        "POST",
+        "/admin/submission-windows",
+        json!({
+            "record_type": "contest",
+            "opens_at": now + chrono::Duration::days(1),
+            "closes_at": now + chrono::Duration::days(10),
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = json_request("POST", "/records/contest", contest.clone()).with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response_json(response).await;
+    assert!(body["message"].as_str().unwrap().contains("open at"), "{body}");
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/forms/contest/window")
+        .header(header::COOKIE, student_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["configured"], true);
+    assert_eq!(body["phase"], "upcoming");
+
+    let request = json_request(
+        "POST",
+        "/admin/submission-windows",
+        json!({
+            "record_type": "contest",
+            "department": " 信息学院 ",
+            "opens_at": now - chrono::Duration::days(1),
+            "closes_at": now + chrono::Duration::days(1),
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let override_window: serde_json::Value = response_json(response).await;
+    assert_eq!(override_window["department"], "信息学院");
+
+    let request = json_request(
+        "POST",
+        "/admin/submission-windows",
+        json!({
+            "record_type": "contest",
+            "department": "信息学院",
+            "opens_at": now,
+            "closes_at": now + chrono::Duration::days(1),
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/forms/contest/window")
+        .header(header::COOKIE, student_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["phase"], "open");
+    assert_eq!(body["department"], "信息学院");
+
+    let request = json_request("POST", "/records/contest", contest).with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/admin/submission-windows/{}", override_window["id"].as_str().unwrap()))
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/forms/contest/window")
+        .header(header::COOKIE, student_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["phase"], "upcoming");
+    assert!(body["department"].is_null());
+}
+
+#[tokio::test]
+async fn contest_submission_stores_recommended_hours() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_recommend", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let student_user = create_user(&ctx.state, "2023061", "student").await;
+    create_student(&ctx.state, "2023061").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+
+    let request = json_request(
+        "POST",
+        "/admin/competitions",
+        json!({ "name": "全国大学生数学建模竞赛", "category": "A" }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["recommended_hours"], 6);
+    assert_eq!(body["hours_deviation"], -4);
+    let record_id: Uuid = body["id"].as_str().unwrap().parse().unwrap();
+    let stored = ucaplatform::entities::ContestRecord::find_by_id(record_id)
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.recommended_hours, Some(6));
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "蓝桥杯",
+            "contest_category": "B",
+            "contest_level": "省级",
+            "contest_role": "成员",
+            "award_level": "二等奖",
+            "self_hours": 3,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["recommended_hours"], 3);
+    assert!(body["hours_deviation"].is_null());
+}
+
+#[tokio::test]
+async fn reconcile_links_students_and_users() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_reconcile", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let orphan_user = create_user(&ctx.state, "2023071", "student").await;
+    let orphan_cookie = create_session_cookie(&ctx.state, orphan_user.id).await;
+    create_student(&ctx.state, "2023072").await;
+    let contest = json!({
+        "contest_name": "全国大学生数学建模竞赛",
+        "contest_level": "国家级",
+        "contest_role": "负责人",
+        "award_level": "省赛一等奖",
+        "self_hours": 2,
+        "custom_fields": {}
+    });
+
+    let request = json_request("POST", "/records/contest", contest.clone()).with_cookie(&orphan_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = json_request("POST", "/admin/reconcile/students-users", json!({ "dry_run": true }))
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response_json(response).await;
+    assert_eq!(report["students_without_user"], json!(["2023072"]));
+    assert_eq!(report["users_without_student"], json!(["2023071"]));
+    assert_eq!(report["created_users"], json!([]));
+
+    let request = json_request(
+        "POST",
+        "/admin/reconcile/students-users",
+        json!({ "create_student_stubs": true }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response_json(response).await;
+    assert_eq!(report["created_users"], json!(["2023072"]));
+    assert_eq!(report["created_students"], json!(["2023071"]));
+
+    let request = json_request("POST", "/records/contest", contest).with_cookie(&orphan_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/students/user-links")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let report: serde_json::Value = response_json(response).await;
+    assert_eq!(report["students_without_user"], json!([]));
+    assert_eq!(report["users_without_student"], json!([]));
+}
+
+#[tokio::test]
+async fn admin_config_hides_secrets() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_config", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let teacher = create_user(&ctx.state, "teacher_config", "teacher").await;
+    let teacher_cookie = create_session_cookie(&ctx.state, teacher.id).await;
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/config")
+        .header(header::COOKIE, teacher_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/config")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let config: serde_json::Value = response_json(response).await;
+    assert_eq!(config["session_ttl_seconds"], json!(ctx.state.config.session_ttl_seconds));
+    assert_eq!(config["auth_secret_key"], json!("***"));
+    assert_eq!(config["tls_key_enc_key"], json!("***"));
+    assert!(config["bootstrap_token"].is_null());
+}
+
+#[tokio::test]
+async fn admin_secret_usage_and_rotation() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_secrets", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let (secret, _) = generate_totp("Labor Hours Platform", &admin.username).unwrap();
+    let encrypted = encrypt_secret(&secret, &ctx.state.config.secret_keyring()).unwrap();
+    assert!(encrypted.starts_with("SECv2:k1:"));
+    totp_secrets::Entity::insert(totp_secrets::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(admin.id),
+        secret_enc: Set(encrypted),
+        enabled: Set(true),
+        verified_at: Set(Some(chrono::Utc::now())),
+        created_at: Set(chrono::Utc::now()),
+        last_used_step: Set(None),
+        failed_attempts: Set(0),
+        locked_until: Set(None),
+    })
+    .exec_without_returning(&ctx.state.db)
+    .await
+    .unwrap();
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/secrets")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let usage: serde_json::Value = response_json(response).await;
+    assert_eq!(usage["primary_key_id"], "k1");
+    assert_eq!(usage["tables"][0]["table"], "totp_secrets");
+    assert_eq!(usage["tables"][0]["by_key"]["k1"], 1);
+    assert_eq!(usage["tables"][0]["outdated"], 0);
+
+    let request = json_request("POST", "/admin/secrets/rotate", json!({})).with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    ctx.state
+        .auth_flows
+        .insert_reauth_token(
+            "rotate-reauth",
+            &ReauthSession {
+                user_id: admin.id,
+                created_at: time::OffsetDateTime::now_utc(),
+            },
+        )
+        .await
+        .unwrap();
+    let mut request = json_request("POST", "/admin/secrets/rotate", json!({})).with_cookie(&admin_cookie);
+    request
+        .headers_mut()
+        .insert("x-reauth-token", "rotate-reauth".parse().unwrap());
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response_json(response).await;
+    assert_eq!(report["rotated"]["totp_secrets"], 0);
+    assert_eq!(report["failures"], json!([]));
+}
+
+#[tokio::test]
+async fn runtime_settings_auto_assign_and_announcement() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_runtime", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let reviewer = create_user(&ctx.state, "reviewer_runtime", "reviewer").await;
+    let student_user = create_user(&ctx.state, "2023081", "student").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+    create_student(&ctx.state, "2023081").await;
+
+    let request = json_request(
+        "PUT",
+        "/admin/settings/runtime",
+        json!({
+            "announcement": "本学期竞赛记录请于 12 月 31 日前提交",
+            "mail_from_name": "劳动教育课时平台",
+            "auto_assign_reviewers": true
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(ctx.state.settings.current().auto_assign_reviewers);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/settings/runtime")
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let settings: serde_json::Value = response_json(response).await;
+    assert_eq!(settings["announcement"], json!("本学期竞赛记录请于 12 月 31 日前提交"));
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record: serde_json::Value = response_json(response).await;
+    assert_eq!(record["assigned_reviewer_id"], json!(reviewer.id.to_string()));
+
+    let request = json_request(
+        "PUT",
+        "/admin/settings/runtime",
+        json!({ "mail_from_name": "   " }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn announcements_target_roles_and_departments() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_notice", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let reviewer = create_user(&ctx.state, "reviewer_notice", "reviewer").await;
+    let reviewer_cookie = create_session_cookie(&ctx.state, reviewer.id).await;
+    let student_user = create_user(&ctx.state, "2023091", "student").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+    create_student(&ctx.state, "2023091").await;
+
+    let request = json_request(
+        "POST",
+        "/admin/announcements",
+        json!({
+            "title": "复审截止提醒",
+            "content": "复审截止5月30日",
+            "severity": "warning",
+            "target_roles": ["reviewer", "teacher"]
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let created: serde_json::Value = response_json(response).await;
+    assert_eq!(created["target_roles"], json!(["teacher", "reviewer"]));
+    assert_eq!(created["active"], json!(true));
+
+    let request = json_request(
+        "POST",
+        "/admin/announcements",
+        json!({
+            "title": "信息学院提交提醒",
+            "severity": "critical",
+            "target_department": "信息学院"
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = json_request(
+        "POST",
+        "/admin/announcements",
+        json!({ "title": "全员通知" }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = json_request(
+        "POST",
+        "/admin/announcements",
+        json!({ "title": "无效", "severity": "urgent" }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let titles = |items: &serde_json::Value| -> Vec<String> {
+        items
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["title"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/announcements/active")
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let items: serde_json::Value = response_json(response).await;
+    assert_eq!(titles(&items), vec!["全员通知"]);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/announcements/active")
+        .header(header::COOKIE, student_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let items: serde_json::Value = response_json(response).await;
+    assert_eq!(titles(&items), vec!["信息学院提交提醒", "全员通知"]);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/announcements/active")
+        .header(header::COOKIE, reviewer_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let items: serde_json::Value = response_json(response).await;
+    assert_eq!(titles(&items), vec!["复审截止提醒", "全员通知"]);
+
+    let announcement_id = created["id"].as_str().unwrap();
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/admin/announcements/{announcement_id}"))
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/announcements/active")
+        .header(header::COOKIE, reviewer_cookie)
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let items: serde_json::Value = response_json(response).await;
+    assert_eq!(titles(&items), vec!["全员通知"]);
+}
+
+#[tokio::test]
+async fn review_delegation_allows_final_review() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let student_user = create_user(&ctx.state, "2023093", "student").await;
+    create_student(&ctx.state, "2023093").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+    let teacher = create_user(&ctx.state, "teacher_leave", "teacher").await;
+    let teacher_cookie = create_session_cookie(&ctx.state, teacher.id).await;
+    let reviewer = create_user(&ctx.state, "reviewer_delegate", "reviewer").await;
+    let reviewer_cookie = create_session_cookie(&ctx.state, reviewer.id).await;
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "award_level": "省赛一等奖",
+            "self_hours": 8,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record: serde_json::Value = response_json(response).await;
+    let record_id = record["id"].as_str().unwrap().to_string();
+
+    let request = json_request(
+        "POST",
+        &format!("/records/contest/{record_id}/review"),
+        json!({ "stage": "first", "hours": 4, "status": "approved", "rejection_reason": null, "expected_version": 0 }),
+    )
+    .with_cookie(&reviewer_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let final_review = json!({ "stage": "final", "hours": 4, "status": "approved", "rejection_reason": null, "expected_version": 1 });
+    let request = json_request("POST", &format!("/records/contest/{record_id}/review"), final_review.clone())
+        .with_cookie(&reviewer_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // 委托给学生账号或自己均不允许。
+    let request = json_request(
+        "POST",
+        "/reviews/delegations",
+        json!({ "delegate_id": student_user.id, "stage": "final", "ends_at": chrono::Utc::now() + chrono::Duration::days(7) }),
+    )
+    .with_cookie(&teacher_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = json_request(
+        "POST",
+        "/reviews/delegations",
+        json!({ "delegate_id": reviewer.id, "stage": "final", "ends_at": chrono::Utc::now() + chrono::Duration::days(7) }),
+    )
+    .with_cookie(&teacher_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let delegation: serde_json::Value = response_json(response).await;
+    assert_eq!(delegation["active"], json!(true));
+    assert_eq!(delegation["delegate_name"], "reviewer_delegate");
+
+    let request = json_request("POST", &format!("/records/contest/{record_id}/review"), final_review)
+        .with_cookie(&reviewer_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let reviewed: serde_json::Value = response_json(response).await;
+    assert_eq!(reviewed["status"], "final_reviewed");
+
+    let audit = ucaplatform::entities::audit_logs::Entity::find()
+        .filter(ucaplatform::entities::audit_logs::Column::Action.eq("record_review_delegated"))
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .expect("delegated review audited");
+    assert_eq!(audit.actor_id, Some(reviewer.id));
+    assert_eq!(audit.target_id.as_deref(), Some(record_id.as_str()));
+    let detail: serde_json::Value = serde_json::from_str(audit.detail.as_deref().unwrap()).unwrap();
+    assert_eq!(detail["delegator_id"], json!(teacher.id));
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/reviews/delegations/{}", delegation["id"].as_str().unwrap()))
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&teacher_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let revoked: serde_json::Value = response_json(response).await;
+    assert_eq!(revoked["active"], json!(false));
+
+    let request = Request::builder()
+        .uri("/reviews/delegations")
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&reviewer_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let listed: Vec<serde_json::Value> = response_json(response).await;
+    assert_eq!(listed.len(), 1);
+    assert!(listed[0]["revoked_at"].is_string());
+}
+
+#[tokio::test]
+async fn delete_student_and_records() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin4", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+
+    let student_user = create_user(&ctx.state, "2023011", "student").await;
+    create_student(&ctx.state, "2023011").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let record = ucaplatform::entities::ContestRecord::find()
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/admin/records/contest/{}", record.id))
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = json_request("POST", "/records/contest/query", json!({}))
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let records: Vec<serde_json::Value> = response_json(response).await;
+    assert!(records.is_empty());
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri("/admin/students/2023011")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = json_request("POST", "/students/query", json!({}))
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let students: Vec<serde_json::Value> = response_json(response).await;
+    assert!(students.is_empty());
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/students/2023011/export-bundle")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bundle: serde_json::Value = response_json(response).await;
+    assert_eq!(bundle["student"]["student_no"], "2023011");
+    assert_eq!(bundle["account"]["username"], "2023011");
+    assert!(bundle["account"].get("password_hash").is_none());
+    assert_eq!(bundle["contest_records"][0]["is_deleted"], true);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/students/2023011/export-bundle?format=zip")
+        .header(header::COOKIE, student_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn student_password_rule_applies_to_reset() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_pwrule", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    create_student(&ctx.state, "2023021").await;
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/student-password-rule")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let rule: serde_json::Value = response_json(response).await;
+    assert_eq!(rule["prefix"], "st");
+    assert_eq!(rule["include_student_no"], true);
+
+    let request = json_request(
+        "POST",
+        "/admin/student-password-rule",
+        json!({ "prefix": "", "suffix": null, "include_student_no": false, "include_phone": false }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = json_request(
+        "POST",
+        "/admin/student-password-rule",
+        json!({ "prefix": "uca", "suffix": "!", "include_student_no": true, "include_phone": true }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/admin/students/2023021/reset-password")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let user = users::Entity::find()
+        .filter(users::Column::Username.eq("2023021"))
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .expect("student user created");
+    let hash = user.password_hash.expect("password set");
+    assert!(verify_password("uca202302113800000000!", &hash).unwrap());
+    assert!(!verify_password("st2023021", &hash).unwrap());
+}
+
+#[tokio::test]
+async fn purge_deleted_student_and_record() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin5", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+
+    let student_user = create_user(&ctx.state, "2023012", "student").await;
+    let student = create_student(&ctx.state, "2023012").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let record = ucaplatform::entities::ContestRecord::find()
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/admin/records/contest/{}", record.id))
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/deleted/records/contest?include_student=true")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let deleted_records: serde_json::Value = response_json(response).await;
+    assert_eq!(deleted_records[0]["id"], record.id.to_string());
+    assert_eq!(deleted_records[0]["student"]["student_no"], "2023012");
+    assert_eq!(deleted_records[0]["student"]["name"], "张三");
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/admin/purge/records/contest/{}", record.id))
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let purged: serde_json::Value = response_json(response).await;
+    assert_eq!(purged["deleted"], true);
+    assert_eq!(purged["contest_records"], 1);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/deleted/records/contest")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let deleted_records: Vec<serde_json::Value> = response_json(response).await;
+    assert!(deleted_records.is_empty());
+
+    let now = chrono::Utc::now();
+    let volunteer_id = Uuid::new_v4();
+    ucaplatform::entities::VolunteerRecord::insert(ucaplatform::entities::volunteer_records::ActiveModel {
+        id: Set(volunteer_id),
+        student_id: Set(student.id),
+        title: Set("社区服务".to_string()),
+        description: Set("社区清扫".to_string()),
+        self_hours: Set(3),
+        status: Set("submitted".to_string()),
+        is_deleted: Set(false),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    })
+    .exec_without_returning(&ctx.state.db)
+    .await
+    .unwrap();
+    let files = tempfile::tempdir().unwrap();
+    let evidence = files.path().join("proof.pdf");
+    std::fs::write(&evidence, b"%PDF-1.4").unwrap();
+    ucaplatform::entities::attachments::Entity::insert(ucaplatform::entities::attachments::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        student_id: Set(student.id),
+        record_type: Set("volunteer".to_string()),
+        record_id: Set(volunteer_id),
+        original_name: Set("proof.pdf".to_string()),
+        stored_name: Set(evidence.to_string_lossy().to_string()),
+        mime_type: Set("application/pdf".to_string()),
+        kind: Set("evidence".to_string()),
+        size_bytes: Set(8),
+        created_at: Set(now),
+    })
+    .exec_without_returning(&ctx.state.db)
+    .await
+    .unwrap();
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/admin/purge/records/volunteer/{volunteer_id}"))
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri("/admin/students/2023012")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri("/admin/purge/students/2023012")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let purged: serde_json::Value = response_json(response).await;
+    assert_eq!(purged["volunteer_records"], 1);
+    assert_eq!(purged["attachments"], 1);
+    assert!(!evidence.exists());
+    assert!(ucaplatform::entities::VolunteerRecord::find_by_id(volunteer_id)
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .is_none());
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/deleted/students")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let deleted_students: Vec<serde_json::Value> = response_json(response).await;
+    assert!(deleted_students.is_empty());
+}
+
+#[tokio::test]
+async fn bulk_purge_requires_dry_run_confirmation() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_bulk_purge", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let student = create_student(&ctx.state, "2023015").await;
+
+    let now = chrono::Utc::now();
+    for (title, status) in [("社区服务", "rejected"), ("敬老院服务", "rejected"), ("图书馆整理", "submitted")] {
+        ucaplatform::entities::VolunteerRecord::insert(ucaplatform::entities::volunteer_records::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            student_id: Set(student.id),
+            title: Set(title.to_string()),
+            description: Set(title.to_string()),
+            self_hours: Set(2),
+            status: Set(status.to_string()),
+            is_deleted: Set(false),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        })
+        .exec_without_returning(&ctx.state.db)
+        .await
+        .unwrap();
+    }
+
+    let request = json_request("POST", "/admin/purge/records", json!({ "record_type": "volunteer" }))
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = json_request(
+        "POST",
+        "/admin/purge/records",
+        json!({ "record_type": "volunteer", "status": "rejected" }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = json_request(
+        "POST",
+        "/admin/purge/records",
+        json!({ "record_type": "volunteer", "status": "rejected", "dry_run": true }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let dry_run: serde_json::Value = response_json(response).await;
+    assert_eq!(dry_run["matched"], 2);
+    assert_eq!(dry_run["volunteer_records"], 0);
+    let token = dry_run["confirm_token"].as_str().unwrap().to_string();
+    assert_eq!(ucaplatform::entities::VolunteerRecord::find().all(&ctx.state.db).await.unwrap().len(), 3);
+
+    let request = json_request(
+        "POST",
+        "/admin/purge/records",
+        json!({ "record_type": "volunteer", "status": "submitted", "confirm_token": token }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    // 匹配数不变但匹配的记录已变化：令牌绑定记录 ID 集合，拒绝删除。
+    let set_status = |title: &'static str, status: &'static str| {
+        ucaplatform::entities::VolunteerRecord::update_many()
+            .col_expr(
+                ucaplatform::entities::volunteer_records::Column::Status,
+                sea_orm::sea_query::Expr::value(status),
+            )
+            .filter(ucaplatform::entities::volunteer_records::Column::Title.eq(title))
+            .exec(&ctx.state.db)
+    };
+    set_status("敬老院服务", "submitted").await.unwrap();
+    set_status("图书馆整理", "rejected").await.unwrap();
+    let request = json_request(
+        "POST",
+        "/admin/purge/records",
+        json!({ "record_type": "volunteer", "status": "rejected", "confirm_token": token }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let body: serde_json::Value = response_json(response).await;
+    assert_eq!(body["current"]["dry_run_matched"], 2);
+    assert_eq!(body["current"]["matched"], 2);
+    assert_eq!(ucaplatform::entities::VolunteerRecord::find().all(&ctx.state.db).await.unwrap().len(), 3);
+    set_status("敬老院服务", "rejected").await.unwrap();
+    set_status("图书馆整理", "submitted").await.unwrap();
+
+    let request = json_request(
+        "POST",
+        "/admin/purge/records",
+        json!({ "record_type": "volunteer", "status": "rejected", "confirm_token": token }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let purged: serde_json::Value = response_json(response).await;
+    assert_eq!(purged["dry_run"], false);
+    assert_eq!(purged["volunteer_records"], 2);
+    assert_eq!(purged["batches"], 1);
+    let remaining = ucaplatform::entities::VolunteerRecord::find().all(&ctx.state.db).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].status, "submitted");
+
+    let request = json_request(
+        "POST",
+        "/admin/purge/records",
+        json!({ "record_type": "volunteer", "status": "rejected", "confirm_token": token }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn orphan_cleanup_reports_and_removes_dangling_data() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let upload_dir = tempfile::tempdir().unwrap();
+    let mut config = (*ctx.state.config).clone();
+    config.upload_dir = upload_dir.path().to_path_buf();
+    let mut state = ctx.state.clone();
+    state.config = Arc::new(config);
+    let app = routes::router(state.clone());
+
+    let admin = create_user(&state, "admin_orphans", "admin").await;
+    let admin_cookie = create_session_cookie(&state, admin.id).await;
+    ucaplatform::entities::record_comments::Entity::insert(ucaplatform::entities::record_comments::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        record_type: Set("contest".to_string()),
+        record_id: Set(Uuid::new_v4()),
+        author_id: Set(admin.id),
+        body: Set("记录已不存在".to_string()),
+        created_at: Set(chrono::Utc::now()),
+    })
+    .exec_without_returning(&state.db)
+    .await
+    .unwrap();
+    let stale_dir = upload_dir.path().join("attachments").join("contest");
+    std::fs::create_dir_all(&stale_dir).unwrap();
+    let stale = stale_dir.join("stale.pdf");
+    std::fs::write(&stale, b"%PDF-1.4").unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&stale)
+        .unwrap()
+        .set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(7200))
+        .unwrap();
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/storage/orphans")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response_json(response).await;
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["comments"], 1);
+    assert_eq!(report["files"], 1);
+    assert!(stale.exists());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/admin/storage/orphans/cleanup")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response_json(response).await;
+    assert_eq!(report["dry_run"], false);
+    assert_eq!(report["comments"], 1);
+    assert!(!stale.exists());
+    let comments = ucaplatform::entities::record_comments::Entity::find()
+        .all(&state.db)
+        .await
+        .unwrap();
+    assert!(comments.is_empty());
+}
+
+#[tokio::test]
+async fn archive_expired_cohort_and_restore() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_archive", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+
+    let graduate_user = create_user(&ctx.state, "2010000001", "student").await;
+    let graduate = create_student(&ctx.state, "2010000001").await;
+    let graduate_cookie = create_session_cookie(&ctx.state, graduate_user.id).await;
+    create_student(&ctx.state, "2026000001").await;
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&graduate_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = json_request(
+        "PUT",
+        "/admin/settings/archival",
+        json!({ "enabled": false, "retention_years": 5, "program_years": 4 }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = json_request("POST", "/admin/archives/run", json!({})).with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response_json(response).await;
+    let archives = report["archives"].as_array().unwrap();
+    assert_eq!(archives.len(), 1);
+    assert_eq!(archives[0]["cohort_year"], 2010);
+    assert_eq!(archives[0]["student_count"], 1);
+    assert_eq!(archives[0]["record_count"], 1);
+    let archive_id = archives[0]["id"].as_str().unwrap().to_string();
+
+    assert!(students::Entity::find_by_id(graduate.id)
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        ucaplatform::entities::ContestRecord::find()
+            .all(&ctx.state.db)
+            .await
+            .unwrap()
+            .len(),
+        0
+    );
+    let account = users::Entity::find_by_id(graduate_user.id)
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!account.is_active);
+    assert_eq!(
+        students::Entity::find().all(&ctx.state.db).await.unwrap().len(),
+        1
+    );
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/admin/archives/{archive_id}"))
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let detail: serde_json::Value = response_json(response).await;
+    assert_eq!(detail["students"][0]["student_no"], "2010000001");
+
+    let request = json_request("POST", &format!("/admin/archives/{archive_id}/restore"), json!({}))
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let restored: serde_json::Value = response_json(response).await;
+    assert!(restored["restored_at"].is_string());
+
+    assert!(students::Entity::find_by_id(graduate.id)
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        ucaplatform::entities::ContestRecord::find()
+            .all(&ctx.state.db)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    let account = users::Entity::find_by_id(graduate_user.id)
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(account.is_active);
+
+    let request = json_request("POST", &format!("/admin/archives/{archive_id}/restore"), json!({}))
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = json_request("POST", "/admin/archives/run", json!({})).with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response_json(response).await;
+    assert!(report["archives"].as_array().unwrap().is_empty());
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/archives")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let listed: Vec<serde_json::Value> = response_json(response).await;
+    assert_eq!(listed.len(), 1);
+}
+
+#[tokio::test]
+async fn orphan_cleanup_keeps_archived_review_signatures() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let upload_dir = tempfile::tempdir().unwrap();
+    let mut config = (*ctx.state.config).clone();
+    config.upload_dir = upload_dir.path().to_path_buf();
+    let mut state = ctx.state.clone();
+    state.config = Arc::new(config);
+    let app = routes::router(state.clone());
+
+    let admin = create_user(&state, "admin_archive_sig", "admin").await;
+    let admin_cookie = create_session_cookie(&state, admin.id).await;
+    let graduate_user = create_user(&state, "2010000002", "student").await;
+    create_student(&state, "2010000002").await;
+    let graduate_cookie = create_session_cookie(&state, graduate_user.id).await;
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&graduate_cookie);
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record = ucaplatform::entities::ContestRecord::find()
+        .one(&state.db)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let signature_dir = upload_dir.path().join("signatures").join("contest").join("first");
+    std::fs::create_dir_all(&signature_dir).unwrap();
+    let signature = signature_dir.join("reviewer.png");
+    std::fs::write(&signature, b"\x89PNG").unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&signature)
+        .unwrap()
+        .set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(7200))
+        .unwrap();
+    ucaplatform::entities::review_signatures::Entity::insert(ucaplatform::entities::review_signatures::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        record_type: Set("contest".to_string()),
+        record_id: Set(record.id),
+        reviewer_user_id: Set(admin.id),
+        delegator_user_id: Set(None),
+        stage: Set("first".to_string()),
+        signature_path: Set(signature.to_string_lossy().to_string()),
+        created_at: Set(chrono::Utc::now()),
+    })
+    .exec_without_returning(&state.db)
+    .await
+    .unwrap();
+
+    let request = json_request(
+        "PUT",
+        "/admin/settings/archival",
+        json!({ "enabled": false, "retention_years": 5, "program_years": 4 }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let request = json_request("POST", "/admin/archives/run", json!({})).with_cookie(&admin_cookie);
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response_json(response).await;
+    let archive_id = report["archives"][0]["id"].as_str().unwrap().to_string();
+    assert!(ucaplatform::entities::review_signatures::Entity::find()
+        .all(&state.db)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/admin/storage/orphans/cleanup")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response_json(response).await;
+    assert_eq!(report["files"], 0);
+    assert!(signature.exists());
+
+    let request = json_request("POST", &format!("/admin/archives/{archive_id}/restore"), json!({}))
+        .with_cookie(&admin_cookie);
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let restored = ucaplatform::entities::review_signatures::Entity::find()
+        .all(&state.db)
+        .await
+        .unwrap();
+    assert_eq!(restored.len(), 1);
+    assert_eq!(restored[0].signature_path, signature.to_string_lossy());
+    assert!(signature.exists());
+}
+
+#[tokio::test]
+async fn auth_totp_and_recovery() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let user = create_user(&ctx.state, "2023999", "student").await;
+
+    let (secret, _) = generate_totp("Labor Hours Platform", &user.username).unwrap();
+    let encrypted = encrypt_secret(&secret, &ctx.state.config.secret_keyring()).unwrap();
+    let totp_id = Uuid::new_v4();
+    let totp_model = totp_secrets::ActiveModel {
+        id: Set(totp_id),
+        user_id: Set(user.id),
+        secret_enc: Set(encrypted),
+        enabled: Set(true),
+        verified_at: Set(Some(chrono::Utc::now())),
+        created_at: Set(chrono::Utc::now()),
+        last_used_step: Set(None),
+        failed_attempts: Set(0),
+        locked_until: Set(None),
+    };
+    totp_secrets::Entity::insert(totp_model)
+        .exec_without_returning(&ctx.state.db)
+        .await
+        .unwrap();
+
+    let code = totp_rs::TOTP::new_unchecked(
+        totp_rs::Algorithm::SHA1,
+        6,
+        1,
+        30,
+        secret.clone(),
+        Some(user.username.clone()),
+        "Labor Hours Platform".to_string(),
+    )
+    .generate_current()
+    .unwrap();
+
+    let request = json_request(
+        "POST",
+        "/auth/totp/verify",
+        json!({ "username": user.username, "code": code }),
+    );
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let codes = generate_recovery_codes(1).unwrap();
+    let recovery = &codes[0];
+    let recovery_id = Uuid::new_v4();
+    let recovery_model = recovery_codes::ActiveModel {
+        id: Set(recovery_id),
+        user_id: Set(user.id),
+        code_hash: Set(recovery.hash.clone()),
+        used_at: Set(None),
+        created_at: Set(chrono::Utc::now()),
+    };
+    recovery_codes::Entity::insert(recovery_model)
+        .exec_without_returning(&ctx.state.db)
+        .await
+        .unwrap();
+
+    let request = json_request(
+        "POST",
+        "/auth/recovery/verify",
+        json!({ "username": user.username, "code": recovery.plain }),
+    );
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn totp_verify_rejects_replay_and_backs_off() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let user = create_user(&ctx.state, "2023996", "student").await;
+
+    let (secret, _) = generate_totp("Labor Hours Platform", &user.username).unwrap();
+    let encrypted = encrypt_secret(&secret, &ctx.state.config.secret_keyring()).unwrap();
+    let totp_id = Uuid::new_v4();
+    totp_secrets::Entity::insert(totp_secrets::ActiveModel {
+        id: Set(totp_id),
+        user_id: Set(user.id),
+        secret_enc: Set(encrypted),
+        enabled: Set(true),
+        verified_at: Set(Some(chrono::Utc::now())),
+        created_at: Set(chrono::Utc::now()),
+        last_used_step: Set(None),
+        failed_attempts: Set(0),
+        locked_until: Set(None),
+    })
+    .exec_without_returning(&ctx.state.db)
+    .await
+    .unwrap();
+    let code = totp_rs::TOTP::new_unchecked(
+        totp_rs::Algorithm::SHA1,
+        6,
+        1,
+        30,
+        secret.clone(),
+        Some(user.username.clone()),
+        "Labor Hours Platform".to_string(),
+    )
+    .generate_current()
+    .unwrap();
+    let verify = |code: &str| {
+        json_request(
+            "POST",
+            "/auth/totp/verify",
+            json!({ "username": user.username, "code": code }),
+        )
+    };
+
+    let response = ctx.app.clone().oneshot(verify(&code)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = ctx.app.clone().oneshot(verify(&code)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let max_failures = ctx.state.config.totp.max_failures;
+    for _ in 1..max_failures {
+        let response = ctx.app.clone().oneshot(verify("abcdef")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+    let record = totp_secrets::Entity::find_by_id(totp_id)
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(record.failed_attempts as u32, max_failures);
+    assert!(record.locked_until.is_some());
+
+    let response = ctx.app.clone().oneshot(verify(&code)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn recovery_codes_generate_and_count() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let user = create_user(&ctx.state, "2023998", "student").await;
+    let cookie = create_session_cookie(&ctx.state, user.id).await;
+
+    let request = json_request("POST", "/auth/recovery/generate", json!({})).with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let (secret, _) = generate_totp("Labor Hours Platform", &user.username).unwrap();
+    let encrypted = encrypt_secret(&secret, &ctx.state.config.secret_keyring()).unwrap();
+    let totp_model = totp_secrets::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        secret_enc: Set(encrypted),
+        enabled: Set(true),
+        verified_at: Set(Some(chrono::Utc::now())),
+        created_at: Set(chrono::Utc::now()),
+        last_used_step: Set(None),
+        failed_attempts: Set(0),
+        locked_until: Set(None),
+    };
+    totp_secrets::Entity::insert(totp_model)
+        .exec_without_returning(&ctx.state.db)
+        .await
+        .unwrap();
+    let code = totp_rs::TOTP::new_unchecked(
+        totp_rs::Algorithm::SHA1,
+        6,
+        1,
+        30,
+        secret.clone(),
+        Some(user.username.clone()),
+        "Labor Hours Platform".to_string(),
+    )
+    .generate_current()
+    .unwrap();
+
+    let request = json_request("POST", "/auth/recovery/generate", json!({})).with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let request = json_request("POST", "/auth/reauth/totp", json!({ "code": code })).with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let reauth: serde_json::Value = response_json(response).await;
+    let token = reauth["token"].as_str().unwrap().to_string();
+
+    let mut request = json_request("POST", "/auth/recovery/generate", json!({})).with_cookie(&cookie);
+    request
+        .headers_mut()
+        .insert("x-reauth-token", token.parse().unwrap());
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let generated: serde_json::Value = response_json(response).await;
+    let codes = generated["codes"].as_array().unwrap();
+    assert_eq!(codes.len(), 10);
+
+    let request = json_request(
+        "POST",
+        "/auth/recovery/verify",
+        json!({ "username": user.username, "code": codes[0] }),
+    );
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/auth/devices")
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let devices: serde_json::Value = response_json(response).await;
+    assert_eq!(devices["recovery_codes"]["total"], 10);
+    assert_eq!(devices["recovery_codes"]["unused"], 9);
+}
+
+#[tokio::test]
+async fn recovery_codes_pdf_download() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+    let user = create_user(&ctx.state, "2023997", "student").await;
+    let cookie = create_session_cookie(&ctx.state, user.id).await;
+
+    let (secret, _) = generate_totp("Labor Hours Platform", &user.username).unwrap();
+    let encrypted = encrypt_secret(&secret, &ctx.state.config.secret_keyring()).unwrap();
+    totp_secrets::Entity::insert(totp_secrets::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        secret_enc: Set(encrypted),
+        enabled: Set(true),
+        verified_at: Set(Some(chrono::Utc::now())),
+        created_at: Set(chrono::Utc::now()),
+        last_used_step: Set(None),
+        failed_attempts: Set(0),
+        locked_until: Set(None),
+    })
+    .exec_without_returning(&ctx.state.db)
+    .await
+    .unwrap();
+    ctx.state
+        .auth_flows
+        .insert_reauth_token(
+            "pdf-reauth",
+            &ReauthSession {
+                user_id: user.id,
+                created_at: time::OffsetDateTime::now_utc(),
+            },
+        )
+        .await
+        .unwrap();
+
+    let mut request = json_request("POST", "/auth/recovery/generate/pdf", json!({})).with_cookie(&cookie);
+    request
+        .headers_mut()
+        .insert("x-reauth-token", "pdf-reauth".parse().unwrap());
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert!(bytes.starts_with(b"%PDF"));
+
+    let stored = recovery_codes::Entity::find()
+        .filter(recovery_codes::Column::UserId.eq(user.id))
+        .all(&ctx.state.db)
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 10);
+}
+
+#[tokio::test]
+async fn rename_device_updates_label() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let user = create_user(&ctx.state, "device_owner", "teacher").await;
+    let other = create_user(&ctx.state, "device_other", "teacher").await;
+    let cookie = create_session_cookie(&ctx.state, user.id).await;
+    let other_cookie = create_session_cookie(&ctx.state, other.id).await;
+    let device_id = Uuid::new_v4();
+    devices::Entity::insert(devices::ActiveModel {
+        id: Set(device_id),
+        user_id: Set(user.id),
+        device_type: Set("passkey".to_string()),
+        label: Set("Passkey".to_string()),
+        credential_id: Set(Some("cred".to_string())),
+        aaguid: Set(Some("cb69481e-8ff7-4039-93ec-0a2729a154a8".to_string())),
+        created_at: Set(chrono::Utc::now()),
+        last_used_at: Set(None),
+    })
+    .exec_without_returning(&ctx.state.db)
+    .await
+    .unwrap();
+
+    let path = format!("/auth/devices/{device_id}");
+    let request = json_request("PUT", &path, json!({ "label": "  办公室 YubiKey " })).with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let device: serde_json::Value = response_json(response).await;
+    assert_eq!(device["label"], "办公室 YubiKey");
+    assert_eq!(device["aaguid"], "cb69481e-8ff7-4039-93ec-0a2729a154a8");
+
+    let request = json_request("PUT", &path, json!({ "label": "   " })).with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = json_request("PUT", &path, json!({ "label": "偷改" })).with_cookie(&other_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn semesters_assign_and_filter_records() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_semester", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let student_user = create_user(&ctx.state, "2023030", "student").await;
+    create_student(&ctx.state, "2023030").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "全国大学生数学建模竞赛",
+            "contest_level": "国家级",
+            "contest_role": "负责人",
+            "award_level": "省赛一等奖",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record: serde_json::Value = response_json(response).await;
+    assert!(record["semester_id"].is_null());
+
+    let today = chrono::Utc::now().date_naive();
+    let request = json_request(
+        "POST",
+        "/admin/semesters",
+        json!({
+            "name": "本学期",
+            "academic_year": "2025-2026",
+            "start_date": today - chrono::Duration::days(30),
+            "end_date": today + chrono::Duration::days(30)
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let saved: serde_json::Value = response_json(response).await;
+    assert_eq!(saved["assigned_records"], 1);
+    let semester_id = saved["semester"]["id"].as_str().unwrap().to_string();
+
+    let request = json_request(
+        "POST",
+        "/admin/semesters",
+        json!({
+            "name": "重叠学期",
+            "academic_year": "2025-2026",
+            "start_date": today,
+            "end_date": today + chrono::Duration::days(60)
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = json_request("POST", "/records/contest/query", json!({ "semester_id": semester_id }))
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let records: Vec<serde_json::Value> = response_json(response).await;
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["semester_id"], semester_id.as_str());
+
+    let request = json_request("POST", "/records/contest/query", json!({ "academic_year": "2024-2025" }))
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let records: Vec<serde_json::Value> = response_json(response).await;
+    assert!(records.is_empty());
+
+    let request = json_request(
+        "POST",
+        "/records/contest",
+        json!({
+            "contest_name": "蓝桥杯全国软件和信息技术专业人才大赛",
+            "contest_level": "省级",
+            "contest_role": "队员",
+            "award_level": "二等奖",
+            "award_date": "2020-05-01",
+            "self_hours": 2,
+            "custom_fields": {}
+        }),
+    )
+    .with_cookie(&student_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let record: serde_json::Value = response_json(response).await;
+    assert!(record["semester_id"].is_null());
+
+    let in_semester = today.to_string();
+    let contest_xlsx = build_xlsx(
+        &["学号", "竞赛名称", "竞赛级别", "角色", "获奖等级", "自评学时", "获奖时间"],
+        &[
+            vec!["2023030", "全国大学生数学建模竞赛", "国家级", "负责人", "省赛一等奖", "8", in_semester.as_str()],
+            vec!["2023030", "全国大学生电子设计竞赛", "国家级", "队员", "省赛二等奖", "4", "2020-05-01"],
+        ],
+    );
+    let request = multipart_request("/admin/records/contest/import", "contest.xlsx", contest_xlsx)
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response_json(response).await;
+    assert_eq!(report["inserted"], 2);
+    let notes = report["notes"].as_array().unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0]["row"], 3);
+    let imported = ucaplatform::entities::ContestRecord::find()
+        .filter(ucaplatform::entities::contest_records::Column::ImportBatchId.is_not_null())
+        .all(&ctx.state.db)
+        .await
+        .unwrap();
+    let semester_uuid: Uuid = semester_id.parse().unwrap();
+    for record in imported {
+        let expected = (record.contest_name == "全国大学生数学建模竞赛").then_some(semester_uuid);
+        assert_eq!(record.semester_id, expected);
+    }
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/admin/semesters/{semester_id}"))
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .uri("/semesters")
+        .header(header::COOKIE, student_cookie)
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let semesters: Vec<serde_json::Value> = response_json(response).await;
+    assert!(semesters.is_empty());
+}
+
+#[tokio::test]
+async fn backup_export_and_restore() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_backup", "admin").await;
+    create_user(&ctx.state, "2023020", "student").await;
+    let student = create_student(&ctx.state, "2023020").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/admin/backup/export")
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/zip");
+    let archive = to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec();
 
-    let request = json_request(
-        "POST",
-        "/export/summary/excel",
-        json!({ "department": "信息学院" }),
-    )
-    .with_cookie(&cookie);
+    let request = multipart_request_with_type(
+        "/admin/backup/import",
+        "backup.zip",
+        archive.clone(),
+        "application/zip",
+    );
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    reset_database(&ctx.state).await;
+    let request =
+        multipart_request_with_type("/admin/backup/import", "backup.zip", archive, "application/zip");
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let manifest: serde_json::Value = response_json(response).await;
+    assert_eq!(manifest["tables"]["users"], 2);
+    assert_eq!(manifest["tables"]["students"], 1);
+
+    let restored = students::Entity::find_by_id(student.id)
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .expect("student restored");
+    assert_eq!(restored.student_no, "2023020");
+    let restored_admin = users::Entity::find_by_id(admin.id)
+        .one(&ctx.state.db)
+        .await
+        .unwrap()
+        .expect("admin restored");
+    assert_eq!(restored_admin.role, "admin");
+}
+
+#[tokio::test]
+async fn dev_seed_populates_demo_data() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_seed", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
 
     let request = Request::builder()
         .method("POST")
-        .uri("/export/student/2023003/excel")
-        .header(header::COOKIE, cookie.clone())
+        .uri("/admin/dev/seed")
         .body(Body::empty())
-        .unwrap();
+        .unwrap()
+        .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let summary: serde_json::Value = response_json(response).await;
+    assert_eq!(summary["students"], 6);
+    assert_eq!(summary["users"], 10);
+    assert_eq!(summary["form_fields"], 4);
+    assert_eq!(summary["competitions"], 3);
+    assert_eq!(summary["records"], 12);
 
-    let record = ucaplatform::entities::ContestRecord::find()
+    let teacher = users::Entity::find()
+        .filter(users::Column::Username.eq("demo_teacher"))
         .one(&ctx.state.db)
         .await
         .unwrap()
-        .unwrap();
+        .expect("demo teacher");
+    assert_eq!(teacher.role, "teacher");
+    assert!(teacher.department.is_some());
+
     let request = Request::builder()
         .method("POST")
-        .uri(format!("/export/record/contest/{}/pdf", record.id))
-        .header(header::COOKIE, cookie.clone())
+        .uri("/admin/dev/seed")
         .body(Body::empty())
-        .unwrap();
+        .unwrap()
+        .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let summary: serde_json::Value = response_json(response).await;
+    assert_eq!(summary["students"], 0);
+    assert_eq!(summary["records"], 0);
 
+    let mut config = (*ctx.state.config).clone();
+    config.developer_mode = false;
+    let mut state = ctx.state.clone();
+    state.config = Arc::new(config);
     let request = Request::builder()
         .method("POST")
-        .uri("/export/labor-hours/2023003/pdf")
-        .header(header::COOKIE, cookie.clone())
+        .uri("/admin/dev/seed")
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&admin_cookie);
+    let response = routes::router(state).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+trait WithCookie {
+    fn with_cookie(self, cookie: &str) -> Request<Body>;
+}
+
+impl WithCookie for Request<Body> {
+    fn with_cookie(self, cookie: &str) -> Request<Body> {
+        let (parts, body) = self.into_parts();
+        let mut builder = Request::builder()
+            .method(parts.method)
+            .uri(parts.uri)
+            .version(parts.version);
+        for (key, value) in parts.headers.iter() {
+            builder = builder.header(key, value);
+        }
+        builder.header(header::COOKIE, cookie).body(body).unwrap()
+    }
+}
+
+#[tokio::test]
+async fn hour_cap_warns_on_final_review() {
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_cap", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let student_user = create_user(&ctx.state, "2023040", "student").await;
+    create_student(&ctx.state, "2023040").await;
+    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+
+    let rules = json!({
+        "base_hours_a": 2,
+        "base_hours_b": 2,
+        "national_leader_hours": 4,
+        "national_member_hours": 2,
+        "provincial_leader_hours": 2,
+        "provincial_member_hours": 1,
+        "school_leader_hours": 1,
+        "school_member_hours": 1,
+        "annual_cap_hours": 0
+    });
+    let request = json_request("POST", "/admin/labor-hour-rules", rules.clone()).with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let mut rules = rules;
+    rules["annual_cap_hours"] = json!(5);
+    let request = json_request("POST", "/admin/labor-hour-rules", rules).with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let saved: serde_json::Value = response_json(response).await;
+    assert_eq!(saved["annual_cap_hours"], 5);
+
+    let mut record_ids = Vec::new();
+    for name in ["全国大学生数学建模竞赛", "蓝桥杯"] {
+        let request = json_request(
+            "POST",
+            "/records/contest",
+            json!({
+                "contest_name": name,
+                "award_level": "一等奖",
+                "self_hours": 4,
+                "custom_fields": {}
+            }),
+        )
+        .with_cookie(&student_cookie);
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let record: serde_json::Value = response_json(response).await;
+        record_ids.push(record["id"].as_str().unwrap().to_string());
+    }
+
+    let request = json_request(
+        "POST",
+        &format!("/records/contest/{}/review", record_ids[0]),
+        json!({ "stage": "final", "hours": 4, "status": "approved", "rejection_reason": null, "expected_version": 0 }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let reviewed: serde_json::Value = response_json(response).await;
+    assert_eq!(reviewed["hour_cap_warnings"], json!([]));
+
+    let request = json_request(
+        "POST",
+        "/records/contest/review/batch",
+        json!({
+            "record_ids": [record_ids[1]],
+            "stage": "final",
+            "hours": 4,
+            "status": "approved",
+            "rejection_reason": null
+        }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let batch: serde_json::Value = response_json(response).await;
+    let warnings = batch["hour_cap_warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0]["approved_hours"], 8);
+    assert_eq!(warnings[0]["cap_hours"], 5);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/students/2023040/hours")
+        .header(header::COOKIE, student_cookie.clone())
         .body(Body::empty())
         .unwrap();
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let ledger: serde_json::Value = response_json(response).await;
+    assert_eq!(ledger["records"].as_array().unwrap().len(), 2);
+    assert_eq!(ledger["records"][0]["final_review_hours"], 4);
+    assert_eq!(ledger["approved_hours"], 8);
+    assert_eq!(ledger["capped_hours"], 5);
+    assert_eq!(ledger["years"][0]["capped_hours"], 5);
+
+    let other_user = create_user(&ctx.state, "2023041", "student").await;
+    let other_cookie = create_session_cookie(&ctx.state, other_user.id).await;
+    let request = Request::builder()
+        .method("GET")
+        .uri("/students/2023040/hours")
+        .header(header::COOKIE, other_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 }
 
 #[tokio::test]
-async fn upload_attachments_and_signatures() {
+async fn webhooks_deliver_signed_payloads() {
+    use ucaplatform::events::{QueueEvent, EVENT_RECORD_REVIEWED};
+    use ucaplatform::webhooks::{deliver_due_webhooks, enqueue_event, sign_payload};
+
     let ctx = setup_context().await;
     reset_database(&ctx.state).await;
 
-    let student_user = create_user(&ctx.state, "2023010", "student").await;
-    create_student(&ctx.state, "2023010").await;
-    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+    // 本地接收端：记录请求头与请求体。
+    let received: Arc<tokio::sync::Mutex<Vec<(axum::http::HeaderMap, String)>>> = Default::default();
+    let sink = received.clone();
+    let receiver = axum::Router::new().route(
+        "/hook",
+        axum::routing::post(move |headers: axum::http::HeaderMap, body: String| {
+            let sink = sink.clone();
+            async move {
+                sink.lock().await.push((headers, body));
+                StatusCode::NO_CONTENT
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, receiver).await.unwrap();
+    });
+    // 已关闭的端口，用于验证失败重试。
+    let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed_address = closed.local_addr().unwrap();
+    drop(closed);
 
+    let admin = create_user(&ctx.state, "admin", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
     let request = json_request(
         "POST",
-        "/records/contest",
+        "/admin/webhooks",
         json!({
-            "contest_name": "全国大学生数学建模竞赛",
-            "contest_level": "国家级",
-            "contest_role": "负责人",
-            "award_level": "省赛一等奖",
-            "self_hours": 2,
-            "custom_fields": {}
+            "url": format!("http://{address}/hook"),
+            "events": ["record_approved", "record_rejected"],
+            "secret": "wechat-work-bot-secret"
         }),
     )
-    .with_cookie(&student_cookie);
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let hook: serde_json::Value = response_json(response).await;
+    assert_eq!(hook["secret"], "wechat-work-bot-secret");
+    assert_eq!(hook["events"], json!(["record_approved", "record_rejected"]));
+
+    let request = json_request(
+        "POST",
+        "/admin/webhooks",
+        json!({ "url": format!("http://{closed_address}/hook"), "events": ["record_approved"] }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let broken: serde_json::Value = response_json(response).await;
+
+    let request = json_request(
+        "POST",
+        "/admin/webhooks",
+        json!({ "url": "ftp://example.com", "events": ["record_approved"] }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = Request::builder()
+        .uri("/admin/webhooks")
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let listed: serde_json::Value = response_json(response).await;
+    assert_eq!(listed.as_array().unwrap().len(), 2);
+    assert!(listed[0].get("secret").is_none());
+
+    let student = create_student(&ctx.state, "2023001").await;
+    let record_id = Uuid::new_v4();
+    let record = ucaplatform::entities::contest_records::ActiveModel {
+        id: Set(record_id),
+        student_id: Set(student.id),
+        contest_name: Set("全国大学生数学建模竞赛".to_string()),
+        award_level: Set("省赛一等奖".to_string()),
+        self_hours: Set(2),
+        status: Set("final_reviewed".to_string()),
+        is_deleted: Set(false),
+        created_at: Set(chrono::Utc::now()),
+        updated_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    };
+    ucaplatform::entities::ContestRecord::insert(record)
+        .exec_without_returning(&ctx.state.db)
+        .await
+        .unwrap();
+
+    let event = QueueEvent::record(EVENT_RECORD_REVIEWED, "contest", record_id, "final_reviewed", None);
+    assert_eq!(enqueue_event(&ctx.state, &event).await.unwrap(), 2);
+    let client = reqwest::Client::new();
+    assert_eq!(deliver_due_webhooks(&ctx.state, &client).await.unwrap(), 1);
+
+    let received = received.lock().await;
+    assert_eq!(received.len(), 1);
+    let (headers, body) = &received[0];
+    assert_eq!(headers["x-uca-event"], "record_approved");
+    let timestamp: i64 = headers["x-uca-timestamp"].to_str().unwrap().parse().unwrap();
+    assert_eq!(
+        headers["x-uca-signature"].to_str().unwrap(),
+        sign_payload(b"wechat-work-bot-secret", timestamp, body)
+    );
+    let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(payload["event"], "record_approved");
+    assert_eq!(payload["data"]["student_no"], "2023001");
+    assert_eq!(payload["data"]["record_id"], record_id.to_string());
+
+    let request = Request::builder()
+        .uri(format!("/admin/webhooks/{}/deliveries", hook["id"].as_str().unwrap()))
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let deliveries: serde_json::Value = response_json(response).await;
+    assert_eq!(deliveries[0]["status"], "succeeded");
+    assert_eq!(deliveries[0]["response_status"], 204);
+
+    let request = Request::builder()
+        .uri(format!("/admin/webhooks/{}/deliveries", broken["id"].as_str().unwrap()))
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let deliveries: serde_json::Value = response_json(response).await;
+    assert_eq!(deliveries[0]["status"], "pending");
+    assert_eq!(deliveries[0]["attempts"], 1);
+    assert!(deliveries[0]["last_error"].is_string());
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/admin/webhooks/{}", broken["id"].as_str().unwrap()))
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let remaining = ucaplatform::entities::WebhookDelivery::find()
+        .all(&ctx.state.db)
+        .await
+        .unwrap();
+    assert_eq!(remaining.len(), 1);
+}
+
+#[tokio::test]
+async fn restore_student_cascades_to_records() {
+    use ucaplatform::entities::{contest_records, volunteer_records, ContestRecord, VolunteerRecord};
+
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_restore", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let student = create_student(&ctx.state, "2023050").await;
+    let now = chrono::Utc::now();
+    let contest_id = Uuid::new_v4();
+    ContestRecord::insert(contest_records::ActiveModel {
+        id: Set(contest_id),
+        student_id: Set(student.id),
+        contest_name: Set("全国大学生数学建模竞赛".to_string()),
+        award_level: Set("省赛一等奖".to_string()),
+        self_hours: Set(2),
+        status: Set("submitted".to_string()),
+        is_deleted: Set(false),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    })
+    .exec_without_returning(&ctx.state.db)
+    .await
+    .unwrap();
+    let volunteer_id = Uuid::new_v4();
+    VolunteerRecord::insert(volunteer_records::ActiveModel {
+        id: Set(volunteer_id),
+        student_id: Set(student.id),
+        title: Set("社区服务".to_string()),
+        description: Set("社区清扫".to_string()),
+        self_hours: Set(3),
+        status: Set("submitted".to_string()),
+        is_deleted: Set(false),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    })
+    .exec_without_returning(&ctx.state.db)
+    .await
+    .unwrap();
+
+    for uri in [
+        format!("/admin/records/contest/{contest_id}"),
+        format!("/admin/records/volunteer/{volunteer_id}"),
+        "/admin/students/2023050".to_string(),
+    ] {
+        let request = Request::builder()
+            .method("DELETE")
+            .uri(uri)
+            .header(header::COOKIE, admin_cookie.clone())
+            .body(Body::empty())
+            .unwrap();
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/deleted/records/volunteer")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let deleted: serde_json::Value = response_json(response).await;
+    assert_eq!(deleted[0]["id"], volunteer_id.to_string());
+    assert!(deleted[0].get("student").is_none());
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/deleted/records/volunteer?include_student=true")
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let deleted: serde_json::Value = response_json(response).await;
+    assert_eq!(deleted[0]["student"]["student_no"], "2023050");
+    assert_eq!(deleted[0]["student"]["class_name"], "软工1班");
+    assert_eq!(deleted[0]["student"]["is_deleted"], true);
+
+    // 不带 cascade 时只恢复学生，记录仍保持删除状态。
+    let request = json_request("POST", "/admin/restore/students/2023050", json!({})).with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let restored: serde_json::Value = response_json(response).await;
+    assert_eq!(restored["contest_records"], 0);
+    let contest = ContestRecord::find_by_id(contest_id).one(&ctx.state.db).await.unwrap().unwrap();
+    assert!(contest.is_deleted);
+
+    let request = json_request("POST", "/admin/restore/students/2023050?cascade=true", json!({}))
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let restored: serde_json::Value = response_json(response).await;
+    assert_eq!(restored["contest_records"], 1);
+    assert_eq!(restored["volunteer_records"], 1);
+    let student = students::Entity::find_by_id(student.id).one(&ctx.state.db).await.unwrap().unwrap();
+    assert!(!student.is_deleted);
+    let volunteer = VolunteerRecord::find_by_id(volunteer_id).one(&ctx.state.db).await.unwrap().unwrap();
+    assert!(!volunteer.is_deleted);
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/admin/records/volunteer/{volunteer_id}"))
+        .header(header::COOKIE, admin_cookie.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let request = json_request("POST", &format!("/admin/restore/records/volunteer/{volunteer_id}"), json!({}))
+        .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let volunteer = VolunteerRecord::find_by_id(volunteer_id).one(&ctx.state.db).await.unwrap().unwrap();
+    assert!(!volunteer.is_deleted);
+}
 
-    let record = ucaplatform::entities::ContestRecord::find()
-        .one(&ctx.state.db)
+#[tokio::test]
+async fn recompute_labor_hours_fills_unreviewed_records() {
+    use ucaplatform::entities::{contest_records, ContestRecord};
+
+    let ctx = setup_context().await;
+    reset_database(&ctx.state).await;
+
+    let admin = create_user(&ctx.state, "admin_recompute", "admin").await;
+    let cookie = create_session_cookie(&ctx.state, admin.id).await;
+    let student = create_student(&ctx.state, "2023060").await;
+    let now = chrono::Utc::now();
+    let mut ids = Vec::new();
+    for status in ["submitted", "final_reviewed"] {
+        let id = Uuid::new_v4();
+        ContestRecord::insert(contest_records::ActiveModel {
+            id: Set(id),
+            student_id: Set(student.id),
+            contest_category: Set(Some("A".to_string())),
+            contest_name: Set("全国大学生数学建模竞赛".to_string()),
+            contest_level: Set(Some("国家级".to_string())),
+            contest_role: Set(Some("负责人".to_string())),
+            award_level: Set("一等奖".to_string()),
+            self_hours: Set(2),
+            first_review_hours: Set(Some(2)),
+            status: Set(status.to_string()),
+            is_deleted: Set(false),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        })
+        .exec_without_returning(&ctx.state.db)
         .await
-        .unwrap()
         .unwrap();
+        ids.push(id);
+    }
 
-    let attachment = multipart_request_with_type(
-        &format!("/attachments/contest/{}", record.id),
-        "proof.pdf",
-        b"test".to_vec(),
-        "application/pdf",
-    )
-    .with_cookie(&student_cookie);
-    let response = ctx.app.clone().oneshot(attachment).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
+    let request = json_request("POST", "/admin/labor-hours/recompute", json!({ "statuses": ["reviewed"] }))
+        .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
-    let reviewer = create_user(&ctx.state, "reviewer2", "reviewer").await;
-    let reviewer_cookie = create_session_cookie(&ctx.state, reviewer.id).await;
-    let signature = multipart_request(
-        &format!("/signatures/contest/{}/first", record.id),
-        "sig.png",
-        b"sig".to_vec(),
-    )
-    .with_cookie(&reviewer_cookie);
-    let response = ctx.app.clone().oneshot(signature).await.unwrap();
+    let request = json_request("POST", "/admin/labor-hours/recompute", json!({ "fill_first_review": true }))
+        .with_cookie(&cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response_json(response).await;
+    assert_eq!(report["scanned"], 2);
+    assert_eq!(report["changed"], 2);
+    assert_eq!(report["filled"], 1);
+
+    let submitted = ContestRecord::find_by_id(ids[0]).one(&ctx.state.db).await.unwrap().unwrap();
+    assert_eq!(submitted.first_review_hours, Some(6));
+    assert_eq!(submitted.version, 1);
+    let reviewed = ContestRecord::find_by_id(ids[1]).one(&ctx.state.db).await.unwrap().unwrap();
+    assert_eq!(reviewed.first_review_hours, Some(2));
 }
 
 #[tokio::test]
-async fn delete_student_and_records() {
+async fn mail_templates_override_and_reset() {
     let ctx = setup_context().await;
     reset_database(&ctx.state).await;
 
-    let admin = create_user(&ctx.state, "admin4", "admin").await;
+    let admin = create_user(&ctx.state, "admin_mail", "admin").await;
     let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
 
-    let student_user = create_user(&ctx.state, "2023011", "student").await;
-    create_student(&ctx.state, "2023011").await;
-    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
-
     let request = json_request(
-        "POST",
-        "/records/contest",
-        json!({
-            "contest_name": "全国大学生数学建模竞赛",
-            "contest_level": "国家级",
-            "contest_role": "负责人",
-            "award_level": "省赛一等奖",
-            "self_hours": 2,
-            "custom_fields": {}
-        }),
+        "PUT",
+        "/admin/mail-templates/invite/zh-CN",
+        json!({ "subject": "【劳动教育】账号邀请", "body": "{{name}}：请打开 {{link}}" }),
     )
-    .with_cookie(&student_cookie);
+    .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let updated: serde_json::Value = response_json(response).await;
+    assert_eq!(updated["customized"], json!(true));
+    assert_eq!(updated["default_subject"], json!("账号邀请"));
 
-    let record = ucaplatform::entities::ContestRecord::find()
-        .one(&ctx.state.db)
-        .await
-        .unwrap()
-        .unwrap();
+    let (subject, body) = ucaplatform::mailer::render_mail(
+        &ctx.state.db,
+        &ucaplatform::mailer::INVITE_MAIL,
+        ucaplatform::i18n::Locale::ZhCn,
+        &[("name", "李老师"), ("link", "https://example.edu/invite"), ("hours", "72")],
+    )
+    .await
+    .unwrap();
+    assert_eq!(subject, "【劳动教育】账号邀请");
+    assert_eq!(body, "李老师：请打开 https://example.edu/invite");
+
+    let request = json_request(
+        "PUT",
+        "/admin/mail-templates/invite/en-US",
+        json!({ "subject": "Invite", "body": "{{student_no}}" }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
     let request = Request::builder()
         .method("DELETE")
-        .uri(format!("/admin/records/contest/{}", record.id))
-        .header(header::COOKIE, admin_cookie.clone())
+        .uri("/admin/mail-templates/invite/zh-CN")
         .body(Body::empty())
-        .unwrap();
-    let response = ctx.app.clone().oneshot(request).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let request = json_request("POST", "/records/contest/query", json!({}))
+        .unwrap()
         .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
-    let records: Vec<serde_json::Value> = response_json(response).await;
-    assert!(records.is_empty());
 
     let request = Request::builder()
-        .method("DELETE")
-        .uri("/admin/students/2023011")
-        .header(header::COOKIE, admin_cookie.clone())
+        .method("GET")
+        .uri("/admin/mail-templates")
         .body(Body::empty())
-        .unwrap();
-    let response = ctx.app.clone().oneshot(request).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let request = json_request("POST", "/students/query", json!({}))
+        .unwrap()
         .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
-    let students: Vec<serde_json::Value> = response_json(response).await;
-    assert!(students.is_empty());
+    let items: serde_json::Value = response_json(response).await;
+    assert_eq!(items.as_array().unwrap().len(), 14);
+    assert!(items
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|item| item["customized"] == json!(false)));
 }
 
 #[tokio::test]
-async fn purge_deleted_student_and_record() {
+async fn export_profiles_configure_summary_columns() {
+    use calamine::{Data, Reader};
+
     let ctx = setup_context().await;
     reset_database(&ctx.state).await;
 
-    let admin = create_user(&ctx.state, "admin5", "admin").await;
+    let admin = create_user(&ctx.state, "admin_export_profile", "admin").await;
     let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+    create_student(&ctx.state, "2023001").await;
 
-    let student_user = create_user(&ctx.state, "2023012", "student").await;
-    create_student(&ctx.state, "2023012").await;
-    let student_cookie = create_session_cookie(&ctx.state, student_user.id).await;
+    let request = json_request(
+        "PUT",
+        "/admin/export-profiles/summary",
+        json!({ "columns": [{ "field_key": "name", "number_format": "0" }] }),
+    )
+    .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = json_request("PUT", "/admin/export-profiles/contest", json!({ "columns": [] }))
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
     let request = json_request(
-        "POST",
-        "/records/contest",
+        "PUT",
+        "/admin/export-profiles/summary",
         json!({
-            "contest_name": "全国大学生数学建模竞赛",
-            "contest_level": "国家级",
-            "contest_role": "负责人",
-            "award_level": "省赛一等奖",
-            "self_hours": 2,
-            "custom_fields": {}
+            "columns": [
+                { "field_key": "approved_hours", "label": "认定学时", "number_format": "0.0" },
+                { "field_key": "student_no" }
+            ]
         }),
     )
-    .with_cookie(&student_cookie);
+    .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let profile: serde_json::Value = response_json(response).await;
+    assert_eq!(profile["customized"], json!(true));
+    assert_eq!(profile["columns"][1], json!({ "field_key": "student_no" }));
 
-    let record = ucaplatform::entities::ContestRecord::find()
-        .one(&ctx.state.db)
-        .await
-        .unwrap()
-        .unwrap();
+    let request = json_request("POST", "/export/summary/excel", json!({})).with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let mut workbook = calamine::Xlsx::new(std::io::Cursor::new(bytes.to_vec())).unwrap();
+    let sheet = workbook.worksheet_range_at(0).unwrap().unwrap();
+    assert_eq!(sheet.get((0, 0)), Some(&Data::String("认定学时".to_string())));
+    assert_eq!(sheet.get((0, 1)), Some(&Data::String("学号".to_string())));
+    assert_eq!(sheet.get((1, 1)), Some(&Data::String("2023001".to_string())));
+    assert_eq!(sheet.get((2, 1)), Some(&Data::String("合计".to_string())));
 
     let request = Request::builder()
-        .method("DELETE")
-        .uri(format!("/admin/records/contest/{}", record.id))
-        .header(header::COOKIE, admin_cookie.clone())
+        .method("GET")
+        .uri("/admin/form-schema/export")
         .body(Body::empty())
-        .unwrap();
+        .unwrap()
+        .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let schema: serde_json::Value = response_json(response).await;
+    assert_eq!(schema["version"], 2);
+    assert_eq!(schema["export_profiles"][0]["profile_key"], "summary");
 
     let request = Request::builder()
         .method("DELETE")
-        .uri(format!("/admin/purge/records/contest/{}", record.id))
-        .header(header::COOKIE, admin_cookie.clone())
+        .uri("/admin/export-profiles/summary")
         .body(Body::empty())
-        .unwrap();
+        .unwrap()
+        .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
 
     let request = Request::builder()
         .method("GET")
-        .uri("/admin/deleted/records/contest")
-        .header(header::COOKIE, admin_cookie.clone())
+        .uri("/admin/export-profiles")
         .body(Body::empty())
-        .unwrap();
+        .unwrap()
+        .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
-    let deleted_records: Vec<serde_json::Value> = response_json(response).await;
-    assert!(deleted_records.is_empty());
+    let items: serde_json::Value = response_json(response).await;
+    let keys: Vec<&str> = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["profile_key"].as_str().unwrap())
+        .collect();
+    assert_eq!(keys, vec!["summary", "student_export", "labor_hours_excel"]);
+    assert!(items
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|item| item["customized"] == json!(false)));
 
-    let request = Request::builder()
-        .method("DELETE")
-        .uri("/admin/students/2023012")
-        .header(header::COOKIE, admin_cookie.clone())
-        .body(Body::empty())
-        .unwrap();
+    // 表单配置导入恢复导出列配置。
+    let request = json_request("POST", "/admin/form-schema/import", json!({ "schema": schema }))
+        .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
-
+    let report: serde_json::Value = response_json(response).await;
+    assert_eq!(report["export_profiles"]["saved"], 1);
     let request = Request::builder()
-        .method("DELETE")
-        .uri("/admin/purge/students/2023012")
-        .header(header::COOKIE, admin_cookie.clone())
+        .method("GET")
+        .uri("/admin/export-profiles/summary")
         .body(Body::empty())
-        .unwrap();
+        .unwrap()
+        .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
+    let profile: serde_json::Value = response_json(response).await;
+    assert_eq!(profile["customized"], json!(true));
+    assert_eq!(profile["columns"], schema["export_profiles"][0]["columns"]);
 
-    let request = Request::builder()
-        .method("GET")
-        .uri("/admin/deleted/students")
-        .header(header::COOKIE, admin_cookie.clone())
-        .body(Body::empty())
-        .unwrap();
+    // 当前版本的文档不再接受以导出 key 作为 form_type 的字段。
+    let legacy_field = json!({
+        "form_type": "summary",
+        "field_key": "name",
+        "label": "姓名",
+        "field_type": "text",
+        "required": false,
+        "order_index": 1
+    });
+    let request = json_request(
+        "POST",
+        "/admin/form-schema/import",
+        json!({ "schema": { "version": 2, "fields": [legacy_field.clone()] } }),
+    )
+    .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
-    let deleted_students: Vec<serde_json::Value> = response_json(response).await;
-    assert!(deleted_students.is_empty());
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let request = json_request("POST", "/admin/form-fields", legacy_field).with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(ucaplatform::entities::form_fields::Entity::find()
+        .filter(ucaplatform::entities::form_fields::Column::FormType.eq("summary"))
+        .all(&ctx.state.db)
+        .await
+        .unwrap()
+        .is_empty());
 }
 
 #[tokio::test]
-async fn auth_totp_and_recovery() {
+async fn mail_outbox_lists_and_retries_failed_mail() {
     let ctx = setup_context().await;
     reset_database(&ctx.state).await;
-    let user = create_user(&ctx.state, "2023999", "student").await;
 
-    let (secret, _) = generate_totp("Labor Hours Platform", &user.username).unwrap();
-    let encrypted = encrypt_secret(&secret, &ctx.state.config.auth_secret_key).unwrap();
-    let totp_id = Uuid::new_v4();
-    let totp_model = totp_secrets::ActiveModel {
-        id: Set(totp_id),
-        user_id: Set(user.id),
-        secret_enc: Set(encrypted),
-        enabled: Set(true),
-        verified_at: Set(Some(chrono::Utc::now())),
-        created_at: Set(chrono::Utc::now()),
-    };
-    totp_secrets::Entity::insert(totp_model)
-        .exec_without_returning(&ctx.state.db)
+    let admin = create_user(&ctx.state, "admin_outbox", "admin").await;
+    let admin_cookie = create_session_cookie(&ctx.state, admin.id).await;
+
+    assert!(ucaplatform::mail_outbox::enqueue_mail(&ctx.state, "not an email", "s", "b")
+        .await
+        .is_err());
+    let mail_id = ucaplatform::mail_outbox::enqueue_mail(&ctx.state, "t001@example.edu", "账号邀请", "正文")
         .await
         .unwrap();
 
-    let code = totp_rs::TOTP::new_unchecked(
-        totp_rs::Algorithm::SHA1,
-        6,
-        1,
-        30,
-        secret.clone(),
-        Some(user.username.clone()),
-        "Labor Hours Platform".to_string(),
-    )
-    .generate_current()
-    .unwrap();
-
-    let request = json_request(
-        "POST",
-        "/auth/totp/verify",
-        json!({ "username": user.username, "code": code }),
-    );
+    let request = json_request("POST", &format!("/admin/mail/outbox/{mail_id}/retry"), json!({}))
+        .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
-    let codes = generate_recovery_codes(1).unwrap();
-    let recovery = &codes[0];
-    let recovery_id = Uuid::new_v4();
-    let recovery_model = recovery_codes::ActiveModel {
-        id: Set(recovery_id),
-        user_id: Set(user.id),
-        code_hash: Set(recovery.hash.clone()),
-        used_at: Set(None),
-        created_at: Set(chrono::Utc::now()),
-    };
-    recovery_codes::Entity::insert(recovery_model)
-        .exec_without_returning(&ctx.state.db)
+    ucaplatform::entities::MailOutbox::update_many()
+        .col_expr(
+            ucaplatform::entities::mail_outbox::Column::Status,
+            sea_orm::sea_query::Expr::value("failed"),
+        )
+        .col_expr(
+            ucaplatform::entities::mail_outbox::Column::Attempts,
+            sea_orm::sea_query::Expr::value(6),
+        )
+        .exec(&ctx.state.db)
         .await
         .unwrap();
 
-    let request = json_request(
-        "POST",
-        "/auth/recovery/verify",
-        json!({ "username": user.username, "code": recovery.plain }),
-    );
+    let request = Request::builder()
+        .method("GET")
+        .uri("/admin/mail/outbox?status=failed")
+        .body(Body::empty())
+        .unwrap()
+        .with_cookie(&admin_cookie);
     let response = ctx.app.clone().oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
-}
-
-trait WithCookie {
-    fn with_cookie(self, cookie: &str) -> Request<Body>;
-}
+    let items: serde_json::Value = response_json(response).await;
+    assert_eq!(items.as_array().unwrap().len(), 1);
+    assert_eq!(items[0]["recipient"], json!("t001@example.edu"));
+    assert!(items[0].get("body").is_none());
 
-impl WithCookie for Request<Body> {
-    fn with_cookie(self, cookie: &str) -> Request<Body> {
-        let (parts, body) = self.into_parts();
-        let mut builder = Request::builder()
-            .method(parts.method)
-            .uri(parts.uri)
-            .version(parts.version);
-        for (key, value) in parts.headers.iter() {
-            builder = builder.header(key, value);
-        }
-        builder.header(header::COOKIE, cookie).body(body).unwrap()
-    }
+    let request = json_request("POST", &format!("/admin/mail/outbox/{mail_id}/retry"), json!({}))
+        .with_cookie(&admin_cookie);
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let item: serde_json::Value = response_json(response).await;
+    assert_eq!(item["status"], json!("pending"));
+    assert_eq!(item["attempts"], json!(0));
 }
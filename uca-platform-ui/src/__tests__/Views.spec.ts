@@ -31,6 +31,12 @@ vi.mock('../api/forms', () => ({
       order_index: 1,
     },
   ]),
+  getSubmissionWindow: vi.fn().mockResolvedValue({
+    record_type: 'contest',
+    configured: false,
+    phase: 'open',
+    now: '2026-10-16T00:00:00Z',
+  }),
 }))
 
 vi.mock('../api/catalog', () => ({
@@ -46,6 +52,8 @@ vi.mock('../api/records', () => ({
 }))
 
 vi.mock('../api/admin', () => ({
+  assignRecords: vi.fn().mockResolvedValue({ assigned: 0, skipped: [], workload: [] }),
+  listReviewerWorkloads: vi.fn().mockResolvedValue([]),
   createCompetition: vi.fn().mockResolvedValue({}),
   createFormField: vi.fn().mockResolvedValue({}),
   importCompetitions: vi.fn().mockResolvedValue({}),
@@ -87,7 +95,9 @@ vi.mock('../api/exports', () => ({
   exportStudent: vi.fn().mockResolvedValue({}),
   exportRecordPdf: vi.fn().mockResolvedValue({}),
   exportLaborHoursPdf: vi.fn().mockResolvedValue({}),
+  exportLaborHoursExcel: vi.fn().mockResolvedValue({}),
   exportLaborHoursSummaryExcel: vi.fn().mockResolvedValue({}),
+  listLaborHoursTemplates: vi.fn().mockResolvedValue([]),
 }))
 
 vi.mock('../api/attachments', () => ({
@@ -109,7 +119,9 @@ vi.mock('../api/students', () => ({
 
 vi.mock('../api/auth', () => ({
   totpVerify: vi.fn().mockResolvedValue({}),
-  listDevices: vi.fn().mockResolvedValue([]),
+  listDevices: vi.fn().mockResolvedValue({ devices: [], recovery_codes: { total: 0, unused: 0 } }),
+  renameDevice: vi.fn().mockResolvedValue({ id: 'd1', label: '办公室电脑' }),
+  generateRecoveryCodes: vi.fn().mockResolvedValue({ codes: [] }),
   getCurrentUser: vi.fn().mockResolvedValue({ id: 'u1', username: 'u1', display_name: 'u1', role: 'reviewer' }),
   bootstrapStatus: vi.fn().mockResolvedValue({ ready: true, needs_totp: false }),
   totpEnrollStart: vi.fn().mockResolvedValue({ enrollment_id: 'e1', otpauth_url: 'otpauth://totp/demo' }),
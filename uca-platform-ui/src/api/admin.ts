@@ -1,4 +1,7 @@
 import { requestJson, requestMultipart } from './client'
+import type { Announcement } from './auth'
+import type { FormField } from './forms'
+import type { StudentPayload } from './students'
 
 export type CompetitionItem = {
   id: string
@@ -64,7 +67,7 @@ export async function importCompetitions(
   return requestMultipart('/admin/competitions/import', form)
 }
 
-export async function listFormFields(): Promise<unknown[]> {
+export async function listFormFields(): Promise<FormField[]> {
   return requestJson('/admin/form-fields', { method: 'GET' })
 }
 
@@ -75,6 +78,17 @@ export async function createFormField(payload: Record<string, unknown>): Promise
   })
 }
 
+export async function updateFormField(fieldId: string, payload: Record<string, unknown>): Promise<unknown> {
+  return requestJson(`/admin/form-fields/${fieldId}`, {
+    method: 'PUT',
+    body: JSON.stringify(payload),
+  })
+}
+
+export async function deleteFormField(fieldId: string): Promise<{ status: string }> {
+  return requestJson(`/admin/form-fields/${fieldId}`, { method: 'DELETE' })
+}
+
 export async function importContestRecords(
   file: File,
   fieldMap?: Record<string, string>,
@@ -91,8 +105,15 @@ export async function deleteStudent(studentNo: string): Promise<unknown> {
   return requestJson(`/admin/students/${encodeURIComponent(studentNo)}`, { method: 'DELETE' })
 }
 
-export async function restoreStudent(studentNo: string): Promise<unknown> {
-  return requestJson(`/admin/students/${encodeURIComponent(studentNo)}/restore`, { method: 'POST' })
+export type RestoreStudentResponse = {
+  restored: boolean
+  contest_records: number
+  volunteer_records: number
+}
+
+export async function restoreStudent(studentNo: string, cascade = false): Promise<RestoreStudentResponse> {
+  const query = cascade ? '?cascade=true' : ''
+  return requestJson(`/admin/restore/students/${encodeURIComponent(studentNo)}${query}`, { method: 'POST' })
 }
 
 export async function updateStudentLogin(studentNo: string, allowLogin: boolean): Promise<unknown> {
@@ -109,8 +130,8 @@ export async function resetStudentPassword(studentNo: string): Promise<unknown>
 }
 
 export type StudentPasswordRule = {
-  prefix?: string
-  suffix?: string
+  prefix?: string | null
+  suffix?: string | null
   include_student_no: boolean
   include_phone: boolean
 }
@@ -136,7 +157,15 @@ export async function deleteContestRecord(recordId: string): Promise<unknown> {
 }
 
 export async function restoreContestRecord(recordId: string): Promise<unknown> {
-  return requestJson(`/admin/records/contest/${recordId}/restore`, { method: 'POST' })
+  return requestJson(`/admin/restore/records/contest/${recordId}`, { method: 'POST' })
+}
+
+export async function deleteVolunteerRecord(recordId: string): Promise<unknown> {
+  return requestJson(`/admin/records/volunteer/${recordId}`, { method: 'DELETE' })
+}
+
+export async function restoreVolunteerRecord(recordId: string): Promise<unknown> {
+  return requestJson(`/admin/restore/records/volunteer/${recordId}`, { method: 'POST' })
 }
 
 export async function listDeletedStudents(): Promise<unknown[]> {
@@ -147,6 +176,10 @@ export async function listDeletedContestRecords(): Promise<unknown[]> {
   return requestJson('/admin/deleted/records/contest', { method: 'GET' })
 }
 
+export async function listDeletedVolunteerRecords(): Promise<unknown[]> {
+  return requestJson('/admin/deleted/records/volunteer', { method: 'GET' })
+}
+
 export async function purgeStudent(studentNo: string): Promise<unknown> {
   return requestJson(`/admin/purge/students/${encodeURIComponent(studentNo)}`, { method: 'DELETE' })
 }
@@ -158,7 +191,7 @@ export async function purgeContestRecord(recordId: string): Promise<unknown> {
 export async function createUser(payload: {
   username: string
   display_name: string
-  role: 'student' | 'teacher' | 'reviewer' | 'admin'
+  role: 'student' | 'teacher' | 'reviewer' | 'admin' | 'class_advisor'
   email?: string
   reset_purpose?: 'totp' | 'passkey'
 }): Promise<{ user_id?: string; invite_sent: boolean; reset_code?: string; reset_purpose?: string }> {
@@ -168,6 +201,22 @@ export async function createUser(payload: {
   })
 }
 
+export type AdvisorClasses = {
+  user_id: string
+  classes: string[]
+}
+
+export async function getAdvisorClasses(userId: string): Promise<AdvisorClasses> {
+  return requestJson(`/admin/users/${userId}/advisor-classes`, { method: 'GET' })
+}
+
+export async function updateAdvisorClasses(userId: string, classes: string[]): Promise<AdvisorClasses> {
+  return requestJson(`/admin/users/${userId}/advisor-classes`, {
+    method: 'PUT',
+    body: JSON.stringify({ classes }),
+  })
+}
+
 export async function getPasswordPolicy(): Promise<{
   min_length: number
   require_uppercase: boolean
@@ -197,6 +246,19 @@ export async function updatePasswordPolicy(payload: {
   })
 }
 
+export async function getStudentPasswordRule(): Promise<StudentPasswordRule> {
+  return requestJson('/admin/student-password-rule', { method: 'GET' })
+}
+
+export async function updateStudentPasswordRule(
+  payload: StudentPasswordRule,
+): Promise<StudentPasswordRule> {
+  return requestJson('/admin/student-password-rule', {
+    method: 'POST',
+    body: JSON.stringify(payload),
+  })
+}
+
 export type LaborHourRule = {
   base_hours_a: number
   base_hours_b: number
@@ -206,6 +268,7 @@ export type LaborHourRule = {
   provincial_member_hours: number
   school_leader_hours: number
   school_member_hours: number
+  annual_cap_hours: number | null
 }
 
 export async function getLaborHourRules(): Promise<LaborHourRule> {
@@ -219,11 +282,161 @@ export async function updateLaborHourRules(payload: LaborHourRule): Promise<Labo
   })
 }
 
+export type HourRecomputeRequest = {
+  statuses?: string[]
+  academic_years?: string[]
+  fill_first_review?: boolean
+}
+
+export type HourRecomputeReport = {
+  scanned: number
+  changed: number
+  filled: number
+  conflicts: number
+  by_status: Record<string, number>
+  records: Array<{
+    record_id: string
+    student_id: string
+    status: string
+    academic_year: string
+    first_review_hours: number | null
+    recommended_hours: number
+    filled: boolean
+  }>
+}
+
+export async function recomputeLaborHours(payload: HourRecomputeRequest): Promise<HourRecomputeReport> {
+  return requestJson('/admin/labor-hours/recompute', {
+    method: 'POST',
+    body: JSON.stringify(payload),
+  })
+}
+
+export type SubmissionWindowItem = {
+  id: string
+  record_type: 'contest' | 'volunteer'
+  department?: string | null
+  opens_at: string
+  closes_at: string
+}
+
+export type SubmissionWindowPayload = Omit<SubmissionWindowItem, 'id'>
+
+export async function listSubmissionWindows(): Promise<SubmissionWindowItem[]> {
+  return requestJson('/admin/submission-windows', { method: 'GET' })
+}
+
+export async function createSubmissionWindow(payload: SubmissionWindowPayload): Promise<SubmissionWindowItem> {
+  return requestJson('/admin/submission-windows', {
+    method: 'POST',
+    body: JSON.stringify(payload),
+  })
+}
+
+export async function updateSubmissionWindow(
+  id: string,
+  payload: SubmissionWindowPayload,
+): Promise<SubmissionWindowItem> {
+  return requestJson(`/admin/submission-windows/${id}`, {
+    method: 'PUT',
+    body: JSON.stringify(payload),
+  })
+}
+
+export async function deleteSubmissionWindow(id: string): Promise<{ status: string }> {
+  return requestJson(`/admin/submission-windows/${id}`, { method: 'DELETE' })
+}
+
+export type ExportColumn = {
+  field_key: string
+  label?: string | null
+  number_format?: string | null
+}
+
+export type ExportProfile = {
+  profile_key: 'summary' | 'student_export' | 'labor_hours_excel'
+  columns: ExportColumn[]
+  customized: boolean
+  available_columns: Array<{ field_key: string; label: string; numeric: boolean }>
+  default_columns: ExportColumn[]
+  updated_at?: string | null
+}
+
+export async function listExportProfiles(): Promise<ExportProfile[]> {
+  return requestJson('/admin/export-profiles', { method: 'GET' })
+}
+
+export async function updateExportProfile(profileKey: string, columns: ExportColumn[]): Promise<ExportProfile> {
+  return requestJson(`/admin/export-profiles/${profileKey}`, {
+    method: 'PUT',
+    body: JSON.stringify({ columns }),
+  })
+}
+
+export async function resetExportProfile(profileKey: string): Promise<ExportProfile> {
+  return requestJson(`/admin/export-profiles/${profileKey}`, { method: 'DELETE' })
+}
+
+export type AnnouncementPayload = {
+  title: string
+  content: string
+  severity: 'info' | 'warning' | 'critical'
+  target_roles: string[]
+  target_department?: string | null
+  starts_at?: string | null
+  ends_at?: string | null
+}
+
+export async function listAnnouncements(): Promise<Announcement[]> {
+  return requestJson('/admin/announcements', { method: 'GET' })
+}
+
+export async function createAnnouncement(payload: AnnouncementPayload): Promise<Announcement> {
+  return requestJson('/admin/announcements', {
+    method: 'POST',
+    body: JSON.stringify(payload),
+  })
+}
+
+export async function updateAnnouncement(id: string, payload: AnnouncementPayload): Promise<Announcement> {
+  return requestJson(`/admin/announcements/${id}`, {
+    method: 'PUT',
+    body: JSON.stringify(payload),
+  })
+}
+
+export async function deleteAnnouncement(id: string): Promise<{ deleted: boolean }> {
+  return requestJson(`/admin/announcements/${id}`, { method: 'DELETE' })
+}
+
 export type ExportTemplateFile = {
   template_key: string
   name: string
   issues: string[]
   orientation: 'portrait' | 'landscape'
+  display_name: string
+  description?: string | null
+  export_type: string
+}
+
+export async function listExportTemplates(): Promise<ExportTemplateFile[]> {
+  return requestJson('/admin/export-templates', { method: 'GET' })
+}
+
+export async function createExportTemplate(payload: {
+  template_key: string
+  display_name: string
+  description?: string | null
+  export_type?: string
+}): Promise<ExportTemplateFile> {
+  return requestJson('/admin/export-templates', {
+    method: 'POST',
+    body: JSON.stringify(payload),
+  })
+}
+
+export async function deleteExportTemplate(templateKey: string): Promise<{ status: string }> {
+  return requestJson(`/admin/export-templates/${encodeURIComponent(templateKey)}`, { method: 'DELETE' })
 }
 
 export async function getExportTemplateFile(templateKey: string): Promise<ExportTemplateFile> {
@@ -241,6 +454,51 @@ export async function uploadExportTemplateFile(
   return requestMultipart(`/admin/export-templates/${encodeURIComponent(templateKey)}/upload`, form)
 }
 
+export type ExportTemplatePreview = {
+  file_name: string
+  content_type: string
+  content_base64: string
+  unresolved_placeholders: string[]
+}
+
+export async function previewExportTemplate(
+  templateKey: string,
+  format: 'xlsx' | 'pdf',
+): Promise<ExportTemplatePreview> {
+  return requestJson(
+    `/admin/export-templates/${encodeURIComponent(templateKey)}/preview?format=${format}`,
+    { method: 'POST' },
+  )
+}
+
+export type ReviewerWorkload = {
+  reviewer_id: string
+  username: string
+  display_name: string
+  role: string
+  department?: string | null
+  pending: number
+}
+
+export async function listReviewerWorkloads(): Promise<ReviewerWorkload[]> {
+  return requestJson('/admin/assignments/workload', { method: 'GET' })
+}
+
+export async function assignRecords(payload: {
+  record_type: 'contest' | 'volunteer'
+  mode: 'manual' | 'round_robin'
+  record_ids?: string[]
+  reviewer_id?: string | null
+  reviewer_ids?: string[]
+  group_by?: 'record' | 'class' | 'department'
+  include_assigned?: boolean
+}): Promise<{ assigned: number; skipped: string[]; workload: ReviewerWorkload[] }> {
+  return requestJson('/admin/assignments', {
+    method: 'POST',
+    body: JSON.stringify(payload),
+  })
+}
+
 export async function resetUserTotp(username: string): Promise<{ status: string }> {
   return requestJson('/admin/users/reset/totp', {
     method: 'POST',
@@ -264,3 +522,133 @@ export async function generateResetCode(payload: {
     body: JSON.stringify(payload),
   })
 }
+
+export type WebhookEvent =
+  | 'record_submitted'
+  | 'record_approved'
+  | 'record_rejected'
+  | 'students_imported'
+
+export type Webhook = {
+  id: string
+  url: string
+  events: WebhookEvent[]
+  is_active: boolean
+  secret?: string
+  created_at: string
+  updated_at: string
+}
+
+export type WebhookDelivery = {
+  id: string
+  webhook_id: string
+  event: WebhookEvent
+  payload: string
+  status: 'pending' | 'succeeded' | 'failed'
+  attempts: number
+  response_status: number | null
+  last_error: string | null
+  next_attempt_at: string
+  created_at: string
+  delivered_at: string | null
+}
+
+export async function listWebhooks(): Promise<Webhook[]> {
+  return requestJson('/admin/webhooks', { method: 'GET' })
+}
+
+export async function createWebhook(payload: {
+  url: string
+  events: WebhookEvent[]
+  is_active?: boolean
+  secret?: string
+}): Promise<Webhook> {
+  return requestJson('/admin/webhooks', {
+    method: 'POST',
+    body: JSON.stringify(payload),
+  })
+}
+
+export async function updateWebhook(
+  webhookId: string,
+  payload: {
+    url?: string
+    events?: WebhookEvent[]
+    is_active?: boolean
+    rotate_secret?: boolean
+  },
+): Promise<Webhook> {
+  return requestJson(`/admin/webhooks/${webhookId}`, {
+    method: 'PUT',
+    body: JSON.stringify(payload),
+  })
+}
+
+export async function deleteWebhook(webhookId: string): Promise<{ deleted: boolean }> {
+  return requestJson(`/admin/webhooks/${webhookId}`, { method: 'DELETE' })
+}
+
+export async function listWebhookDeliveries(
+  webhookId: string,
+  params: { status?: string; limit?: number } = {},
+): Promise<WebhookDelivery[]> {
+  const query = new URLSearchParams()
+  if (params.status) query.set('status', params.status)
+  if (params.limit) query.set('limit', String(params.limit))
+  const suffix = query.toString() ? `?${query}` : ''
+  return requestJson(`/admin/webhooks/${webhookId}/deliveries${suffix}`, { method: 'GET' })
+}
+
+export type ArchivalPolicy = {
+  enabled: boolean
+  retention_years: number
+  program_years: number
+}
+
+export type ArchiveBatch = {
+  id: string
+  cohort_year: number
+  student_count: number
+  record_count: number
+  purged_attachments: number
+  created_by: string | null
+  created_at: string
+  restored_at: string | null
+  restored_by: string | null
+}
+
+export type ArchivedStudent = StudentPayload & {
+  id: string
+  is_deleted: boolean
+  created_at: string
+  updated_at: string
+}
+
+export async function getArchivalPolicy(): Promise<ArchivalPolicy> {
+  return requestJson('/admin/settings/archival', { method: 'GET' })
+}
+
+export async function updateArchivalPolicy(payload: ArchivalPolicy): Promise<ArchivalPolicy> {
+  return requestJson('/admin/settings/archival', {
+    method: 'PUT',
+    body: JSON.stringify(payload),
+  })
+}
+
+export async function listArchives(): Promise<ArchiveBatch[]> {
+  return requestJson('/admin/archives', { method: 'GET' })
+}
+
+export async function getArchive(
+  archiveId: string,
+): Promise<{ archive: ArchiveBatch; students: ArchivedStudent[] }> {
+  return requestJson(`/admin/archives/${archiveId}`, { method: 'GET' })
+}
+
+export async function runArchival(): Promise<{ cutoff_year: number; archives: ArchiveBatch[] }> {
+  return requestJson('/admin/archives/run', { method: 'POST' })
+}
+
+export async function restoreArchive(archiveId: string): Promise<ArchiveBatch> {
+  return requestJson(`/admin/archives/${archiveId}/restore`, { method: 'POST' })
+}
@@ -1,4 +1,11 @@
-import { requestMultipart } from './client'
+import { requestJson, requestMultipart } from './client'
+
+export type SignedFileKind = 'attachment' | 'thumbnail' | 'review_signature' | 'user_signature' | 'export'
+
+export type SignedUrl = {
+  url: string
+  expires_at: string
+}
 
 export async function uploadContestAttachment(recordId: string, file: File): Promise<unknown> {
   const form = new FormData()
@@ -16,3 +23,10 @@ export async function uploadSignature(
   form.append('file', file)
   return requestMultipart(`/signatures/${recordType}/${recordId}/${stage}`, form)
 }
+
+export async function signFileUrl(kind: SignedFileKind, id: string): Promise<SignedUrl> {
+  return requestJson<SignedUrl>('/files/sign', {
+    method: 'POST',
+    body: JSON.stringify({ kind, id }),
+  })
+}
@@ -9,8 +9,26 @@ export type CurrentUser = {
   id: string
   username: string
   display_name: string
-  role: 'student' | 'reviewer' | 'teacher' | 'admin'
+  role: 'student' | 'reviewer' | 'teacher' | 'admin' | 'class_advisor'
   must_change_password: boolean
+  department: string | null
+  totp_enrolled: boolean
+  permissions: string[]
+  scope: {
+    department: string | null
+    classes: string[] | null
+  }
+  student: {
+    student_no: string
+    name: string
+    department: string
+    major: string
+    class_name: string
+  } | null
+  pending_actions: {
+    must_change_password: boolean
+    unread_notifications: number
+  }
 }
 
 export type PasswordPolicy = {
@@ -23,6 +41,8 @@ export type PasswordPolicy = {
 
 export type AuthConfig = {
   reset_delivery: 'email' | 'code'
+  sso_provider?: 'cas' | 'oauth2' | null
+  ldap_enabled?: boolean
 }
 
 export type ReauthTokenResponse = {
@@ -83,15 +103,34 @@ export async function passwordLogin(username: string, password: string): Promise
   })
 }
 
+export async function ldapLogin(username: string, password: string): Promise<{ user_id: string }> {
+  return requestJson('/auth/ldap/login', {
+    method: 'POST',
+    body: JSON.stringify({ username, password }),
+  })
+}
+
 export async function loginOptions(username: string): Promise<{ methods: string[] }> {
   const query = new URLSearchParams({ username }).toString()
   return requestJson(`/auth/login/options?${query}`, { method: 'GET' })
 }
 
-export async function listDevices(): Promise<unknown[]> {
+export type DeviceListResponse = {
+  devices: unknown[]
+  recovery_codes: { total: number; unused: number }
+}
+
+export async function listDevices(): Promise<DeviceListResponse> {
   return requestJson('/auth/devices', { method: 'GET' })
 }
 
+export async function generateRecoveryCodes(reauthToken?: string): Promise<{ codes: string[] }> {
+  return requestJson('/auth/recovery/generate', {
+    method: 'POST',
+    headers: reauthHeaders(reauthToken),
+  })
+}
+
 export async function deleteDevice(device_id: string, reauthToken?: string): Promise<{ status: string }> {
   return requestJson(`/auth/devices/${device_id}`, {
     method: 'DELETE',
@@ -99,6 +138,16 @@ export async function deleteDevice(device_id: string, reauthToken?: string): Pro
   })
 }
 
+export async function renameDevice(
+  device_id: string,
+  label: string,
+): Promise<{ id: string; label: string }> {
+  return requestJson(`/auth/devices/${device_id}`, {
+    method: 'PUT',
+    body: JSON.stringify({ label }),
+  })
+}
+
 export async function getCurrentUser(): Promise<CurrentUser> {
   return requestJson('/auth/me', { method: 'GET' })
 }
@@ -119,6 +168,34 @@ export async function getAuthConfig(): Promise<AuthConfig> {
   return requestJson('/auth/config', { method: 'GET' })
 }
 
+export type RuntimeSettings = {
+  maintenance_mode: boolean
+  maintenance_message?: string | null
+  announcement?: string | null
+}
+
+export async function getRuntimeSettings(): Promise<RuntimeSettings> {
+  return requestJson('/settings/runtime', { method: 'GET' })
+}
+
+export type Announcement = {
+  id: string
+  title: string
+  content: string
+  severity: 'info' | 'warning' | 'critical'
+  target_roles: string[]
+  target_department?: string | null
+  starts_at: string
+  ends_at?: string | null
+  active: boolean
+  created_at: string
+  updated_at: string
+}
+
+export async function getActiveAnnouncements(): Promise<Announcement[]> {
+  return requestJson('/announcements/active', { method: 'GET' })
+}
+
 export async function bootstrapAdmin(payload: {
   username: string
   display_name: string
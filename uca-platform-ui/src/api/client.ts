@@ -29,20 +29,21 @@ async function parseJson<T>(response: Response): Promise<T> {
 
 export async function requestJson<T>(path: string, init?: RequestInit): Promise<T> {
   const response = await fetch(`${API_BASE}${path}`, {
+    ...init,
     credentials: 'include',
     headers: {
       'Content-Type': 'application/json',
       ...(init?.headers ?? {}),
     },
-    ...init,
   })
   return parseJson<T>(response)
 }
 
-export async function requestMultipart<T>(path: string, form: FormData): Promise<T> {
+export async function requestMultipart<T>(path: string, form: FormData, headers?: HeadersInit): Promise<T> {
   const response = await fetch(`${API_BASE}${path}`, {
     method: 'POST',
     credentials: 'include',
+    headers,
     body: form,
   })
   return parseJson<T>(response)
@@ -0,0 +1,27 @@
+import { requestJson } from './client'
+
+export type RecordComment = {
+  id: string
+  record_type: string
+  record_id: string
+  author_id: string
+  author_name?: string | null
+  author_role?: string | null
+  body: string
+  created_at: string
+}
+
+export async function listRecordComments(recordType: string, recordId: string): Promise<RecordComment[]> {
+  return requestJson(`/comments/${recordType}/${recordId}`, { method: 'GET' })
+}
+
+export async function createRecordComment(
+  recordType: string,
+  recordId: string,
+  body: string,
+): Promise<RecordComment> {
+  return requestJson(`/comments/${recordType}/${recordId}`, {
+    method: 'POST',
+    body: JSON.stringify({ body }),
+  })
+}
@@ -1,4 +1,10 @@
-import { downloadFile } from './client'
+import { downloadFile, requestJson } from './client'
+
+export type ExportTemplateOption = {
+  template_key: string
+  display_name: string
+  description?: string | null
+}
 
 export async function exportSummary(filters: Record<string, unknown>): Promise<void> {
   return downloadFile('/export/summary/excel', filters)
@@ -12,8 +18,18 @@ export async function exportRecordPdf(recordType: string, recordId: string): Pro
   return downloadFile(`/export/record/${recordType}/${recordId}/pdf`)
 }
 
-export async function exportLaborHoursPdf(studentNo: string): Promise<void> {
-  return downloadFile(`/export/labor-hours/${encodeURIComponent(studentNo)}/pdf`)
+export async function exportLaborHoursPdf(studentNo: string, templateKey?: string): Promise<void> {
+  const query = templateKey ? `?template_key=${encodeURIComponent(templateKey)}` : ''
+  return downloadFile(`/export/labor-hours/${encodeURIComponent(studentNo)}/pdf${query}`)
+}
+
+export async function exportLaborHoursExcel(studentNo: string, templateKey?: string): Promise<void> {
+  const query = templateKey ? `?template_key=${encodeURIComponent(templateKey)}` : ''
+  return downloadFile(`/export/labor-hours/${encodeURIComponent(studentNo)}/excel${query}`)
+}
+
+export async function listLaborHoursTemplates(): Promise<ExportTemplateOption[]> {
+  return requestJson('/export/labor-hours/templates', { method: 'GET' })
 }
 
 export async function exportLaborHoursSummaryExcel(filters: Record<string, unknown>): Promise<void> {
@@ -8,8 +8,26 @@ export type FormField = {
   field_type: string
   required: boolean
   order_index: number
+  options?: string[] | null
+  min_value?: number | null
+  max_value?: number | null
+  max_length?: number | null
 }
 
 export async function listFormFieldsByType(formType: string): Promise<FormField[]> {
   return requestJson(`/forms/${formType}/fields`, { method: 'GET' })
 }
+
+export type SubmissionWindowStatus = {
+  record_type: string
+  configured: boolean
+  department?: string | null
+  opens_at?: string | null
+  closes_at?: string | null
+  phase: 'upcoming' | 'open' | 'closed'
+  now: string
+}
+
+export async function getSubmissionWindow(formType: string): Promise<SubmissionWindowStatus> {
+  return requestJson(`/forms/${formType}/window`, { method: 'GET' })
+}
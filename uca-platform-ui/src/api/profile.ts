@@ -9,8 +9,26 @@ export async function getSignatureProfile(): Promise<SignatureProfile> {
   return requestJson('/profile/signature', { method: 'GET' })
 }
 
-export async function uploadSignatureImage(file: File): Promise<SignatureProfile> {
+export async function uploadSignatureImage(file: File, reauthToken?: string): Promise<SignatureProfile> {
   const form = new FormData()
   form.append('file', file)
-  return requestMultipart('/profile/signature', form)
+  return requestMultipart('/profile/signature', form, reauthToken ? { 'X-Reauth-Token': reauthToken } : undefined)
+}
+
+export type ServerLocale = 'zh-CN' | 'en-US'
+
+export type LocalePreference = {
+  locale: ServerLocale | null
+  effective_locale: ServerLocale
+}
+
+export async function getLocalePreference(): Promise<LocalePreference> {
+  return requestJson('/profile/locale', { method: 'GET' })
+}
+
+export async function updateLocalePreference(locale: ServerLocale | null): Promise<LocalePreference> {
+  return requestJson('/profile/locale', {
+    method: 'PUT',
+    body: JSON.stringify({ locale }),
+  })
 }
@@ -10,9 +10,10 @@ export async function createContest(payload: {
   award_date?: string | null
   self_hours: number
   custom_fields?: Record<string, string>
-}): Promise<unknown> {
+}, idempotencyKey?: string): Promise<unknown> {
   return requestJson('/records/contest', {
     method: 'POST',
+    headers: idempotencyKey ? { 'Idempotency-Key': idempotencyKey } : undefined,
     body: JSON.stringify(payload),
   })
 }
@@ -43,27 +44,80 @@ export type ContestRecord = {
   final_review_hours?: number | null
   status: string
   rejection_reason?: string | null
+  assigned_reviewer_id?: string | null
+  version: number
   match_status: string
+  matched_competition_id?: string | null
+  match_confidence?: string | null
   recommended_hours: number
+  hours_deviation?: number | null
   custom_fields: CustomFieldValue[]
   attachments?: {
     id: string
     original_name: string
     mime_type: string
     download_url: string
+    thumbnail_url?: string | null
   }[]
 }
 
-export async function queryContest(status?: string): Promise<ContestRecord[]> {
+export async function queryContest(
+  status?: string,
+  options: { assigned_to_me?: boolean } = {},
+): Promise<ContestRecord[]> {
   return requestJson('/records/contest/query', {
     method: 'POST',
-    body: JSON.stringify({ status }),
+    body: JSON.stringify({ status, ...options }),
   })
 }
 
-export async function reviewContest(recordId: string, payload: unknown): Promise<unknown> {
+export type ReviewPayload = {
+  stage: string
+  hours: number
+  status: string
+  rejection_reason?: string | null
+  /** 审核时看到的记录版本；记录已被他人修改时服务端返回 409。 */
+  expected_version: number
+}
+
+export async function reviewContest(recordId: string, payload: ReviewPayload): Promise<ContestRecord> {
   return requestJson(`/records/contest/${recordId}/review`, {
     method: 'POST',
     body: JSON.stringify(payload),
   })
 }
+
+export type ReviewDelegation = {
+  id: string
+  delegator_id: string
+  delegator_name?: string | null
+  delegate_id: string
+  delegate_name?: string | null
+  stage: string
+  starts_at: string
+  ends_at: string
+  revoked_at?: string | null
+  active: boolean
+  created_at: string
+}
+
+export async function listReviewDelegations(): Promise<ReviewDelegation[]> {
+  return requestJson('/reviews/delegations')
+}
+
+export async function createReviewDelegation(payload: {
+  delegate_id: string
+  stage: string
+  starts_at?: string | null
+  ends_at: string
+  delegator_id?: string | null
+}): Promise<ReviewDelegation> {
+  return requestJson('/reviews/delegations', {
+    method: 'POST',
+    body: JSON.stringify(payload),
+  })
+}
+
+export async function revokeReviewDelegation(delegationId: string): Promise<ReviewDelegation> {
+  return requestJson(`/reviews/delegations/${delegationId}`, { method: 'DELETE' })
+}
@@ -75,3 +75,34 @@ export async function updateStudent(
 export async function getCurrentStudent(): Promise<StudentProfile> {
   return requestJson('/students/me', { method: 'GET' })
 }
+
+export type HourLedgerEntry = {
+  record_id: string
+  contest_name: string
+  award_level: string
+  status: string
+  academic_year: string
+  self_hours: number
+  first_review_hours?: number | null
+  final_review_hours?: number | null
+  recommended_hours: number
+  approved_hours: number
+  rejection_reason?: string | null
+  created_at: string
+}
+
+export type StudentHours = {
+  student_id: string
+  student_no: string
+  name: string
+  records: HourLedgerEntry[]
+  years: { academic_year: string; approved_hours: number; capped_hours: number }[]
+  self_hours: number
+  approved_hours: number
+  capped_hours: number
+  annual_cap_hours?: number | null
+}
+
+export async function getStudentHours(studentNo: string): Promise<StudentHours> {
+  return requestJson(`/students/${encodeURIComponent(studentNo)}/hours`)
+}
@@ -91,7 +91,9 @@ router.beforeEach(async (to) => {
   if ((to.path === '/purge' || to.path.startsWith('/admin')) && auth.role !== 'admin') {
     return auth.homePath()
   }
-  if (to.path === '/review' && auth.role === 'student') return auth.homePath()
+  if (to.path === '/review' && (auth.role === 'student' || auth.role === 'class_advisor')) {
+    return auth.homePath()
+  }
   return true
 })
 
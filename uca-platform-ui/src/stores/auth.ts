@@ -5,7 +5,7 @@ import { bootstrapStatus, getAuthConfig, getCurrentUser } from '../api/auth'
 
 export const useAuthStore = defineStore('auth', () => {
   const loggedIn = ref(false)
-  const role = ref<'student' | 'reviewer' | 'teacher' | 'admin'>('student')
+  const role = ref<'student' | 'reviewer' | 'teacher' | 'admin' | 'class_advisor'>('student')
   const user = ref<CurrentUser | null>(null)
   const mustChangePassword = ref(false)
   const sessionChecked = ref(false)
@@ -14,6 +14,8 @@ export const useAuthStore = defineStore('auth', () => {
   const bootstrapNeedsTotp = ref<boolean | null>(null)
   const configChecked = ref(false)
   const resetDelivery = ref<'email' | 'code'>('email')
+  const ssoProvider = ref<'cas' | 'oauth2' | null>(null)
+  const ldapEnabled = ref(false)
 
   const login = (nextRole?: typeof role.value) => {
     loggedIn.value = true
@@ -84,6 +86,8 @@ export const useAuthStore = defineStore('auth', () => {
     try {
       const config = await getAuthConfig()
       resetDelivery.value = config.reset_delivery
+      ssoProvider.value = config.sso_provider ?? null
+      ldapEnabled.value = config.ldap_enabled ?? false
       configChecked.value = true
       return config
     } catch {
@@ -95,7 +99,11 @@ export const useAuthStore = defineStore('auth', () => {
 
   const ensureConfig = async () => {
     if (configChecked.value) {
-      return { reset_delivery: resetDelivery.value }
+      return {
+        reset_delivery: resetDelivery.value,
+        sso_provider: ssoProvider.value,
+        ldap_enabled: ldapEnabled.value,
+      }
     }
     return refreshConfig()
   }
@@ -103,6 +111,7 @@ export const useAuthStore = defineStore('auth', () => {
   const homePath = () => {
     if (role.value === 'admin') return '/admin'
     if (role.value === 'reviewer' || role.value === 'teacher') return '/review'
+    if (role.value === 'class_advisor') return '/exports'
     return '/student'
   }
 
@@ -116,6 +125,8 @@ export const useAuthStore = defineStore('auth', () => {
     bootstrapReady,
     bootstrapNeedsTotp,
     resetDelivery,
+    ssoProvider,
+    ldapEnabled,
     configChecked,
     login,
     logout,
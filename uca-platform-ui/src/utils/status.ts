@@ -7,6 +7,7 @@ export const statusLabels: Record<string, string> = {
 
 export const matchLabels: Record<string, string> = {
   matched: '已匹配',
+  fuzzy: '待确认',
   unmatched: '未匹配',
 }
 